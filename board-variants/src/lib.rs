@@ -0,0 +1,367 @@
+//! Pin tables for the Lilygo T-Embed family: the plain T-Embed and the
+//! T-Embed CC1101 (sub-1GHz radio daughterboard). Both are ESP32-S3 boards
+//! with the same 1.9" 170x320 ST7789 panel and EC11 rotary encoder, wired
+//! to **different, incompatible** GPIOs — driving one board with the
+//! other's pin map is how bead ai-bitwarden-hw-key-c6e ended up with a
+//! fully dark panel and an "init succeeded" log (the panel bus is
+//! write-only, so wrong wiring never surfaces as an error).
+//!
+//! Each board is one [`BoardVariant`] `const`; the firmware selects one at
+//! build time by cargo feature (`board-t-embed-cc1101`, the default, or
+//! `board-t-embed-plain` — see `firmware/Cargo.toml`) and
+//! `firmware::board::board_config` turns its numbers into typed
+//! `esp_idf_hal` pins. This crate holds nothing but the data and
+//! [`BoardVariant::check`], so the tables are tested on host
+//! (`tests/pin_tables.rs` runs `check` over [`ALL`]) instead of only being
+//! discovered wrong on a flashed board.
+//!
+//! Required pins are non-`Option` fields, so a variant missing one doesn't
+//! compile; the only optional pin is [`LcdPins::reset`], which the CC1101
+//! doesn't wire.
+
+/// An ESP32-S3 GPIO number.
+pub type Gpio = u8;
+
+/// Which level switches a control line on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    ActiveHigh,
+    ActiveLow,
+}
+
+impl Polarity {
+    /// The level (`true` = high) that turns the line on.
+    #[must_use]
+    pub const fn on_level(self) -> bool {
+        matches!(self, Self::ActiveHigh)
+    }
+}
+
+/// Panel rotation, in the same sense as `mipidsi::options::Rotation`
+/// (which the firmware maps this onto): clockwise from the panel's native
+/// portrait orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    /// Whether this rotation swaps the panel's width and height.
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Self::Deg90 | Self::Deg270)
+    }
+}
+
+/// The ST7789's SPI bus and control pins. The panel is write-only, so
+/// there is no MISO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LcdPins {
+    pub sclk: Gpio,
+    pub mosi: Gpio,
+    pub cs: Gpio,
+    pub dc: Gpio,
+    /// Driven as a plain on/off GPIO, active high on both variants.
+    pub backlight: Gpio,
+    /// Hardware reset line, if the board wires one. Without it the
+    /// firmware relies on `mipidsi`'s software reset.
+    pub reset: Option<Gpio>,
+}
+
+/// The rotary encoder's quadrature pins and its push button. All three
+/// are read with the internal pull-up, active low (see
+/// `firmware::board::rotary_input`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderPins {
+    pub a: Gpio,
+    pub b: Gpio,
+    pub button: Gpio,
+}
+
+/// The board's peripheral power rail switch. It gates more than the
+/// display, but the display is the only consumer the firmware drives, so
+/// `St7789Surface` switches it on before touching the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerEnable {
+    pub pin: Gpio,
+    pub polarity: Polarity,
+}
+
+/// How the ST7789's memory maps onto the glass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelGeometry {
+    /// The panel's native (unrotated, portrait) size — what
+    /// `mipidsi::Builder::display_size` wants, not the landscape size the
+    /// core renders into.
+    pub native_width: u16,
+    pub native_height: u16,
+    /// Column/row start of the glass inside the controller's 240x320
+    /// memory, in the native frame (`mipidsi::Builder::display_offset`).
+    pub offset: (u16, u16),
+    pub rotation: Rotation,
+    /// Whether the panel needs color inversion on (`TFT_INVERSION_ON` in
+    /// LilyGo's TFT_eSPI setups).
+    pub inverted: bool,
+}
+
+/// The ST7789's own memory size. The native size plus offset has to fit
+/// inside it; `mipidsi` rejects a bigger `display_size` at init.
+pub const CONTROLLER_SIZE: (u16, u16) = (240, 320);
+
+impl PanelGeometry {
+    /// The rotated size the core renders into and `flush` blits.
+    #[must_use]
+    pub const fn logical_size(&self) -> (u16, u16) {
+        if self.rotation.is_vertical() {
+            (self.native_height, self.native_width)
+        } else {
+            (self.native_width, self.native_height)
+        }
+    }
+}
+
+/// One T-Embed board's pin map and panel setup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardVariant {
+    /// Human-readable board name, for the boot log.
+    pub name: &'static str,
+    /// The `firmware` cargo feature that selects this variant.
+    pub feature: &'static str,
+    pub lcd: LcdPins,
+    pub encoder: EncoderPins,
+    pub power_enable: PowerEnable,
+    pub panel: PanelGeometry,
+}
+
+/// Why a [`BoardVariant`]'s table is inconsistent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinTableError {
+    /// Two roles name the same GPIO.
+    DuplicateGpio {
+        gpio: Gpio,
+        first: &'static str,
+        second: &'static str,
+    },
+    /// The number isn't a GPIO the ESP32-S3 has (it has 0-21 and 26-48).
+    NoSuchGpio { role: &'static str, gpio: Gpio },
+    /// The GPIO exists but is taken by the module's flash and octal PSRAM
+    /// (26-37 on the N16R8 both boards carry).
+    ReservedGpio { role: &'static str, gpio: Gpio },
+    /// The panel's native size plus offset doesn't fit the controller's
+    /// memory ([`CONTROLLER_SIZE`]).
+    PanelOutOfRange,
+}
+
+impl BoardVariant {
+    /// Every GPIO this variant uses, with the role it's used for.
+    #[must_use]
+    pub fn pins(&self) -> Vec<(&'static str, Gpio)> {
+        let mut pins = vec![
+            ("lcd.sclk", self.lcd.sclk),
+            ("lcd.mosi", self.lcd.mosi),
+            ("lcd.cs", self.lcd.cs),
+            ("lcd.dc", self.lcd.dc),
+            ("lcd.backlight", self.lcd.backlight),
+            ("encoder.a", self.encoder.a),
+            ("encoder.b", self.encoder.b),
+            ("encoder.button", self.encoder.button),
+            ("power_enable", self.power_enable.pin),
+        ];
+        if let Some(reset) = self.lcd.reset {
+            pins.push(("lcd.reset", reset));
+        }
+        pins
+    }
+
+    /// Checks the table is one the firmware can actually drive: every pin
+    /// is a real, free ESP32-S3 GPIO, no GPIO serves two roles, and the
+    /// panel fits its controller.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`PinTableError`] found, in [`BoardVariant::pins`]
+    /// order.
+    pub fn check(&self) -> Result<(), PinTableError> {
+        let pins = self.pins();
+        for (index, &(role, gpio)) in pins.iter().enumerate() {
+            if gpio > 48 || (22..=25).contains(&gpio) {
+                return Err(PinTableError::NoSuchGpio { role, gpio });
+            }
+            if (26..=37).contains(&gpio) {
+                return Err(PinTableError::ReservedGpio { role, gpio });
+            }
+            if let Some(&(first, _)) = pins[..index].iter().find(|&&(_, other)| other == gpio) {
+                return Err(PinTableError::DuplicateGpio {
+                    gpio,
+                    first,
+                    second: role,
+                });
+            }
+        }
+
+        let panel = &self.panel;
+        if panel.native_width + panel.offset.0 > CONTROLLER_SIZE.0
+            || panel.native_height + panel.offset.1 > CONTROLLER_SIZE.1
+        {
+            return Err(PinTableError::PanelOutOfRange);
+        }
+        Ok(())
+    }
+}
+
+/// The 1.9" ST7789V both variants ship: 170x320 native, shown landscape
+/// at 320x170.
+///
+/// Offset, rotation and inversion are HARDWARE-CONFIRMED on the CC1101
+/// (right-side up, fills the panel, correct colors — see
+/// `firmware::board::st7789_surface`): `offset` is TFT_eSPI's rotation-0
+/// `colstart = 35, rowstart = 0` for a 170-wide panel (mipidsi wants the
+/// native-frame offset and remaps it itself); `Deg270` matches the
+/// factory firmware's `setRotation(3)` (`Deg90` came out upside down);
+/// `TFT_INVERSION_ON` is set in LilyGo's setup for this panel.
+const T_EMBED_PANEL: PanelGeometry = PanelGeometry {
+    native_width: 170,
+    native_height: 320,
+    offset: (35, 0),
+    rotation: Rotation::Deg270,
+    inverted: true,
+};
+
+/// Lilygo T-Embed CC1101 — the board this project runs on.
+///
+/// Source: `github.com/Xinyuan-LilyGO/T-Embed-CC1101`:
+/// - `examples/utilities.h`: `DISPLAY_SCLK/MOSI/CS/DC/BL` = 11/9/41/16/21,
+///   `BOARD_PWR_EN` = 15, `ENCODER_INA/INB/KEY` = 4/5/0.
+/// - `lib/TFT_eSPI/User_Setups/Setup214_LilyGo_T_Embed_PN532.h`: the same
+///   LCD pins, and `TFT_RST = -1` — no hardware reset line.
+/// - `examples/factory/factory.cpp`: `setup()` drives `BOARD_PWR_EN` HIGH
+///   before any SPI activity — active high.
+///
+/// LCD and power pins HARDWARE-CONFIRMED (bead ai-bitwarden-hw-key-c6e);
+/// encoder pins corrected from the plain board's 2/1 in bead
+/// ai-bitwarden-hw-key-ekd and believed right, not yet exercised. The
+/// independent user button on GPIO6 (`BOARD_USER_KEY`) is intentionally
+/// not part of the table — nothing drives it (see `rotary_input`).
+pub const T_EMBED_CC1101: BoardVariant = BoardVariant {
+    name: "T-Embed CC1101",
+    feature: "board-t-embed-cc1101",
+    lcd: LcdPins {
+        sclk: 11,
+        mosi: 9,
+        cs: 41,
+        dc: 16,
+        backlight: 21,
+        reset: None,
+    },
+    encoder: EncoderPins {
+        a: 4,
+        b: 5,
+        button: 0,
+    },
+    power_enable: PowerEnable {
+        pin: 15,
+        polarity: Polarity::ActiveHigh,
+    },
+    panel: T_EMBED_PANEL,
+};
+
+/// Lilygo T-Embed (no radio).
+///
+/// Source: `github.com/Xinyuan-LilyGO/T-Embed`'s `example/factory/pin_config.h`:
+/// `PIN_LCD_CLK/MOSI/CS/DC/BL/RES` = 12/11/10/13/15/9, `PIN_POWER_ON` = 46
+/// (driven HIGH in `setup()`), `PIN_ENCODE_A/B/BTN` = 2/1/0. These are the
+/// numbers the firmware originally shipped with, before the move to the
+/// CC1101 (see git history of `board_config.rs`).
+///
+/// **Not hardware-verified**: no plain T-Embed has been on the bench since.
+/// The panel setup is assumed identical to the CC1101's (same part, same
+/// `setRotation(3)` landscape in the factory example).
+pub const T_EMBED_PLAIN: BoardVariant = BoardVariant {
+    name: "T-Embed",
+    feature: "board-t-embed-plain",
+    lcd: LcdPins {
+        sclk: 12,
+        mosi: 11,
+        cs: 10,
+        dc: 13,
+        backlight: 15,
+        reset: Some(9),
+    },
+    encoder: EncoderPins {
+        a: 2,
+        b: 1,
+        button: 0,
+    },
+    power_enable: PowerEnable {
+        pin: 46,
+        polarity: Polarity::ActiveHigh,
+    },
+    panel: T_EMBED_PANEL,
+};
+
+/// Every variant, for checks that have to hold across all of them.
+pub const ALL: [&BoardVariant; 2] = [&T_EMBED_CC1101, &T_EMBED_PLAIN];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_gpio_used_twice_is_reported_with_both_roles() {
+        let mut variant = T_EMBED_CC1101;
+        variant.lcd.backlight = variant.power_enable.pin;
+        assert_eq!(
+            variant.check(),
+            Err(PinTableError::DuplicateGpio {
+                gpio: 15,
+                first: "lcd.backlight",
+                second: "power_enable",
+            })
+        );
+    }
+
+    #[test]
+    fn the_optional_reset_pin_is_checked_when_present() {
+        let mut variant = T_EMBED_CC1101;
+        variant.lcd.reset = Some(variant.lcd.dc);
+        assert_eq!(
+            variant.check(),
+            Err(PinTableError::DuplicateGpio {
+                gpio: 16,
+                first: "lcd.dc",
+                second: "lcd.reset",
+            })
+        );
+    }
+
+    #[test]
+    fn missing_and_reserved_gpios_are_rejected() {
+        let mut variant = T_EMBED_PLAIN;
+        variant.encoder.a = 23;
+        assert_eq!(
+            variant.check(),
+            Err(PinTableError::NoSuchGpio {
+                role: "encoder.a",
+                gpio: 23,
+            })
+        );
+        variant.encoder.a = 33;
+        assert_eq!(
+            variant.check(),
+            Err(PinTableError::ReservedGpio {
+                role: "encoder.a",
+                gpio: 33,
+            })
+        );
+    }
+
+    #[test]
+    fn a_panel_past_the_controller_memory_is_rejected() {
+        let mut variant = T_EMBED_CC1101;
+        variant.panel.offset = (80, 0);
+        assert_eq!(variant.check(), Err(PinTableError::PanelOutOfRange));
+    }
+}
@@ -0,0 +1,64 @@
+//! Consistency checks over every T-Embed variant's pin table. These are
+//! the host-side half of the build-time board selection: the firmware
+//! can't be built or flashed here, but a table that reuses a GPIO, names a
+//! pin the ESP32-S3 doesn't have, or isn't wired to a `firmware` feature
+//! fails `cargo test` instead of a board bring-up.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use board_variants::{BoardVariant, ALL};
+
+#[test]
+fn every_variant_passes_its_pin_table_check() {
+    for variant in ALL {
+        assert_eq!(variant.check(), Ok(()), "{}", variant.name);
+    }
+}
+
+#[test]
+fn every_variant_drives_the_same_landscape_frame() {
+    // `firmware::board::board_config` derives DISPLAY_WIDTH/HEIGHT from the
+    // selected variant, and the render core's landscape profile is tuned
+    // for 320x170; a variant with another size needs its own profile first.
+    for variant in ALL {
+        assert_eq!(variant.panel.logical_size(), (320, 170), "{}", variant.name);
+    }
+}
+
+#[test]
+fn variant_names_and_features_are_unique() {
+    let names: HashSet<_> = ALL.iter().map(|variant| variant.name).collect();
+    let features: HashSet<_> = ALL.iter().map(|variant| variant.feature).collect();
+    assert_eq!(names.len(), ALL.len());
+    assert_eq!(features.len(), ALL.len());
+}
+
+#[test]
+fn every_variant_feature_is_declared_by_the_firmware() {
+    let manifest: PathBuf = [env!("CARGO_MANIFEST_DIR"), "..", "firmware", "Cargo.toml"]
+        .iter()
+        .collect();
+    let manifest = std::fs::read_to_string(&manifest).unwrap();
+    for variant in ALL {
+        let declaration = format!("{} = [\"board-t-embed\"]", variant.feature);
+        assert!(
+            manifest.lines().any(|line| line.trim() == declaration),
+            "firmware/Cargo.toml has no `{declaration}` for {}",
+            variant.name
+        );
+    }
+}
+
+#[test]
+fn the_variants_differ_where_the_boards_do() {
+    // The pin maps that were mixed up once (bead ai-bitwarden-hw-key-c6e)
+    // must stay apart: every LCD signal and the power rail are on
+    // different GPIOs between the two boards.
+    let [cc1101, plain]: [&BoardVariant; 2] = ALL;
+    for ((role, a), (_, b)) in cc1101.pins().into_iter().zip(plain.pins()) {
+        if role.starts_with("lcd.") || role == "power_enable" {
+            assert_ne!(a, b, "{role} is the same GPIO on both boards");
+        }
+    }
+}
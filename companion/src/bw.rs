@@ -0,0 +1,181 @@
+//! Where `push` gets vault items from: the official Bitwarden CLI (`bw list
+//! items`, with its own server-side filters, mapped by
+//! `crate::map_bw_items_to_credentials`) or a file (`--from-file`: saved
+//! `bw list items` output or a Bitwarden export, see `crate::export`).
+//!
+//! See `.planning/decisions/2026-08-12-m1-companion-bw-cli-bridge.md`.
+
+use std::io::Read;
+use std::process::Command;
+
+use push_protocol::Credential;
+use uuid::Uuid;
+
+use crate::error::CliError;
+
+/// `push` filters that `bw list items` applies itself. All optional; none
+/// set means the whole vault.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemFilter {
+    pub search: Option<String>,
+    pub folder_id: Option<String>,
+    pub collection_id: Option<String>,
+    pub organization_id: Option<String>,
+}
+
+impl ItemFilter {
+    /// Arguments for `bw`, e.g. `["list", "items", "--search", "github"]`.
+    #[must_use]
+    pub fn bw_args(&self) -> Vec<String> {
+        let mut args = vec!["list".to_string(), "items".to_string()];
+        let flags = [
+            ("--search", &self.search),
+            ("--folderid", &self.folder_id),
+            ("--collectionid", &self.collection_id),
+            ("--organizationid", &self.organization_id),
+        ];
+        for (flag, value) in flags {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value.clone());
+            }
+        }
+        args
+    }
+}
+
+/// Runs `bw` with `filter`'s arguments and returns its stdout. Distinguishes
+/// the three failure modes an operator actually hits: `bw` missing from
+/// PATH, `bw` present but not logged in/unlocked, and any other non-zero
+/// exit.
+///
+/// # Errors
+///
+/// [`CliError::Source`] for each of the above.
+pub fn fetch_bw_list_items(filter: &ItemFilter) -> Result<String, CliError> {
+    let output = Command::new("bw")
+        .args(filter.bw_args())
+        .output()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                CliError::Source(
+                    "The Bitwarden CLI (`bw`) was not found on PATH.\n\
+                 Install it from https://bitwarden.com/help/cli/ and ensure `bw` is on your PATH."
+                        .to_string(),
+                )
+            } else {
+                CliError::Source(format!("Failed to run `bw list items`: {e}"))
+            }
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr_lower = stderr.to_lowercase();
+        if stderr_lower.contains("not logged in") {
+            return Err(CliError::Source(format!(
+                "The Bitwarden CLI is not logged in.\n\
+                 Run `bw login` and then `bw unlock` (export the resulting BW_SESSION) before retrying.\n\
+                 bw said: {}",
+                stderr.trim()
+            )));
+        }
+        if stderr_lower.contains("vault is locked") || stderr_lower.contains("session") {
+            return Err(CliError::Source(format!(
+                "The Bitwarden vault appears to be locked.\n\
+                 Run `bw unlock` and export the printed BW_SESSION before retrying.\n\
+                 bw said: {}",
+                stderr.trim()
+            )));
+        }
+        return Err(CliError::Source(format!(
+            "`bw list items` failed (exit status {:?}): {}",
+            output.status.code(),
+            stderr.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Reads an items file (any format `crate::export` accepts) from `path`,
+/// or stdin for `-`.
+///
+/// # Errors
+///
+/// [`CliError::Source`] if it can't be read.
+pub fn read_items_file(path: &str) -> Result<String, CliError> {
+    if path == "-" {
+        let mut json = String::new();
+        std::io::stdin()
+            .read_to_string(&mut json)
+            .map_err(|e| CliError::Source(format!("failed to read items from stdin: {e}")))?;
+        return Ok(json);
+    }
+    std::fs::read_to_string(path)
+        .map_err(|e| CliError::Source(format!("failed to read {path}: {e}")))
+}
+
+/// Keeps only credentials whose id is in `ids`; an empty `ids` keeps
+/// everything. `bw list items` has no "these ids" filter, so `--id` is
+/// applied here, after mapping.
+#[must_use]
+pub fn retain_ids(credentials: Vec<Credential>, ids: &[Uuid]) -> Vec<Credential> {
+    if ids.is_empty() {
+        return credentials;
+    }
+    credentials
+        .into_iter()
+        .filter(|c| ids.contains(&c.id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_filter_lists_everything() {
+        assert_eq!(ItemFilter::default().bw_args(), vec!["list", "items"]);
+    }
+
+    #[test]
+    fn filters_map_to_bw_flags() {
+        let filter = ItemFilter {
+            search: Some("github".into()),
+            folder_id: Some("f1".into()),
+            collection_id: None,
+            organization_id: Some("o1".into()),
+        };
+        assert_eq!(
+            filter.bw_args(),
+            vec![
+                "list",
+                "items",
+                "--search",
+                "github",
+                "--folderid",
+                "f1",
+                "--organizationid",
+                "o1"
+            ]
+        );
+    }
+
+    #[test]
+    fn retain_ids_filters_only_when_ids_given() {
+        let credential = |id| Credential {
+            id,
+            name: String::new(),
+            username: String::new(),
+            password: String::new(),
+            uri: None,
+            notes: None,
+        };
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(retain_ids(vec![credential(a), credential(b)], &[]).len(), 2);
+        let kept = retain_ids(vec![credential(a), credential(b)], &[b]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].id, b);
+    }
+}
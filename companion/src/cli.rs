@@ -0,0 +1,412 @@
+//! Hand-rolled argument parsing (still no clap: the grammar is small and
+//! the crate's dependency list is deliberately short). Global flags may
+//! appear anywhere on the line; the first non-flag word is the subcommand.
+
+use std::path::PathBuf;
+
+use device_link::WireIntent;
+use uuid::Uuid;
+
+use crate::bw::ItemFilter;
+use crate::error::CliError;
+use crate::output::OutputFormat;
+use crate::target::{TargetFlags, DEFAULT_DEVICE_BASE_URL};
+
+pub const USAGE: &str = "\
+Usage: companion [GLOBAL OPTIONS] <COMMAND> [OPTIONS]
+
+Commands:
+  devices                     List reachable devices (emulator, USB serial, paired)
+  push                        Push vault items from `bw` to the device (alias: sync)
+      --search <text>         Only items matching <text> (bw --search)
+      --folder-id <id>        Only items in this folder (bw --folderid)
+      --collection-id <id>    Only items in this collection (bw --collectionid)
+      --org-id <id>           Only items in this organization (bw --organizationid)
+      --id <uuid>             Only this item; repeatable
+      --from-file <path|->    Read items from a file/stdin instead of running bw: `bw list items`
+                              output, or a Bitwarden JSON export (unencrypted or password-protected)
+      --password-stdin        Read a password-protected export's password from stdin
+                              (default: the BW_EXPORT_PASSWORD env var)
+      --dry-run               Map and count items, but don't send anything
+  status                      Show the device's status
+  screenshot [<path>]         Save the current screen as PNG (default: screenshot.png)
+      -o, --output <path>     Same as the positional <path>
+  input <intent>...           Send navigation intents: next, prev, home, end, activate, back,
+                              next:<n>, prev:<n>, char:<c>
+  pair                        Remember the selected device as the default target
+      --forget                Forget the paired device
+
+Global options:
+  --device-url <url>          Target an emulator/device HTTP API (env: DEVICE_URL)
+  --port <path>               Target a device-link serial port (env: DEVICE_PORT)
+  --json                      Machine-readable JSON output (same as --format json)
+  --format <human|json>       Output format (default: human)
+  -h, --help                  Show this help
+
+Without a target option, companion uses the paired device, or else ";
+
+/// Fully parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cli {
+    pub target: TargetFlags,
+    pub format: OutputFormat,
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Devices,
+    Push(PushArgs),
+    Status,
+    Screenshot { output: PathBuf },
+    Input { intents: Vec<WireIntent> },
+    Pair { forget: bool },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushArgs {
+    pub filter: ItemFilter,
+    pub ids: Vec<Uuid>,
+    pub from_file: Option<String>,
+    pub password_stdin: bool,
+    pub dry_run: bool,
+}
+
+/// The full help text.
+#[must_use]
+pub fn usage() -> String {
+    format!("{USAGE}{DEFAULT_DEVICE_BASE_URL}.")
+}
+
+/// Best-effort "was JSON output asked for", for reporting a parse error in
+/// the format the caller wanted.
+#[must_use]
+pub fn wants_json(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--json")
+        || args
+            .windows(2)
+            .any(|pair| pair[0] == "--format" && pair[1] == "json")
+}
+
+fn usage_error(message: impl Into<String>) -> CliError {
+    CliError::Usage(format!(
+        "{}\nRun `companion --help` for usage.",
+        message.into()
+    ))
+}
+
+/// Parses one `input` intent word: `next`, `prev`, `home`, `end`,
+/// `activate`, `back`, `next:<n>`, `prev:<n>`, or `char:<c>`. Only the
+/// keyword is case-insensitive: `char:G` sends an uppercase `G`.
+///
+/// # Errors
+///
+/// [`CliError::Usage`] for anything else.
+pub fn parse_intent(word: &str) -> Result<WireIntent, CliError> {
+    match word.to_ascii_lowercase().as_str() {
+        "next" => Ok(WireIntent::Next),
+        "prev" => Ok(WireIntent::Prev),
+        "activate" => Ok(WireIntent::Activate),
+        "back" => Ok(WireIntent::Back),
+        "home" => Ok(WireIntent::Home),
+        "end" => Ok(WireIntent::End),
+        other => jump(other, "next:", WireIntent::NextN)
+            .or_else(|| jump(other, "prev:", WireIntent::PrevN))
+            .or_else(|| typed_char(word))
+            .ok_or_else(|| {
+                usage_error(format!(
+                    "unknown intent {word:?}; expected next, prev, home, end, activate, back, \
+                     next:<n>, prev:<n>, or char:<c>"
+                ))
+            }),
+    }
+}
+
+/// `next:<n>`/`prev:<n>`: `prefix` followed by a `u16`.
+fn jump(word: &str, prefix: &str, intent: fn(u16) -> WireIntent) -> Option<WireIntent> {
+    word.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .map(intent)
+}
+
+/// `char:<c>`: exactly one character, case preserved.
+fn typed_char(word: &str) -> Option<WireIntent> {
+    const KEYWORD: &str = "char:";
+    if !word.get(..KEYWORD.len())?.eq_ignore_ascii_case(KEYWORD) {
+        return None;
+    }
+    let mut chars = word[KEYWORD.len()..].chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(WireIntent::Char(c)),
+        _ => None,
+    }
+}
+
+/// Parses `args` (without the program name).
+///
+/// # Errors
+///
+/// [`CliError::Usage`] for anything that isn't a valid command line.
+pub fn parse(args: &[String]) -> Result<Cli, CliError> {
+    let mut target = TargetFlags::default();
+    let mut format = OutputFormat::Human;
+    let mut words: Vec<&str> = Vec::new();
+    let mut push = PushArgs::default();
+    let mut output = None;
+    let mut forget = false;
+    let mut help = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| usage_error(format!("{arg} requires a value")))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => help = true,
+            "--json" => format = OutputFormat::Json,
+            "--format" => {
+                format = match value()?.as_str() {
+                    "human" => OutputFormat::Human,
+                    "json" => OutputFormat::Json,
+                    other => {
+                        return Err(usage_error(format!(
+                            "unknown --format {other:?}; expected human or json"
+                        )))
+                    }
+                }
+            }
+            "--device-url" => target.device_url = Some(value()?),
+            "--port" => target.port = Some(value()?),
+            "--search" => push.filter.search = Some(value()?),
+            "--folder-id" => push.filter.folder_id = Some(value()?),
+            "--collection-id" => push.filter.collection_id = Some(value()?),
+            "--org-id" => push.filter.organization_id = Some(value()?),
+            "--id" => {
+                let raw = value()?;
+                let id = Uuid::parse_str(&raw)
+                    .map_err(|e| usage_error(format!("--id {raw:?} is not a UUID: {e}")))?;
+                push.ids.push(id);
+            }
+            "--from-file" => push.from_file = Some(value()?),
+            "--password-stdin" => push.password_stdin = true,
+            "--dry-run" => push.dry_run = true,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "--forget" => forget = true,
+            other if other.starts_with('-') && other != "-" => {
+                return Err(usage_error(format!("unrecognized option {other}")));
+            }
+            word => words.push(word),
+        }
+    }
+
+    if help {
+        return Ok(Cli {
+            target,
+            format,
+            command: Command::Help,
+        });
+    }
+
+    let Some((&name, rest)) = words.split_first() else {
+        return Err(usage_error("no command given"));
+    };
+
+    // Subcommand-specific flags are only valid on their own subcommand, so
+    // a typo like `status --dry-run` fails loudly instead of being ignored.
+    let reject = |flag: &str, given: bool| {
+        if given {
+            Err(usage_error(format!("{flag} is not valid for `{name}`")))
+        } else {
+            Ok(())
+        }
+    };
+    if name != "push" && name != "sync" {
+        reject("push filters", push != PushArgs::default())?;
+    }
+    if name != "screenshot" {
+        reject("--output", output.is_some())?;
+    }
+    if name != "pair" {
+        reject("--forget", forget)?;
+    }
+    let no_positionals = || {
+        if rest.is_empty() {
+            Ok(())
+        } else {
+            Err(usage_error(format!(
+                "unexpected argument {:?} for `{name}`",
+                rest[0]
+            )))
+        }
+    };
+
+    let command = match name {
+        "devices" => no_positionals().map(|()| Command::Devices)?,
+        "push" | "sync" => {
+            if push.password_stdin && push.from_file.as_deref() == Some("-") {
+                return Err(usage_error(
+                    "--password-stdin can't be combined with --from-file -",
+                ));
+            }
+            if push.from_file.is_some() && push.filter != ItemFilter::default() {
+                return Err(usage_error("--search/--folder-id/--collection-id/--org-id filter `bw`, not --from-file; use --id"));
+            }
+            no_positionals().map(|()| Command::Push(push))?
+        }
+        "status" => no_positionals().map(|()| Command::Status)?,
+        "pair" => no_positionals().map(|()| Command::Pair { forget })?,
+        "screenshot" => {
+            let output = match (output, rest) {
+                (Some(_), [_, ..]) => {
+                    return Err(usage_error(
+                        "give the screenshot path once, positionally or with -o",
+                    ))
+                }
+                (Some(path), []) => path,
+                (None, [path]) => PathBuf::from(path),
+                (None, []) => PathBuf::from("screenshot.png"),
+                (None, [_, extra, ..]) => {
+                    return Err(usage_error(format!(
+                        "unexpected argument {extra:?} for `screenshot`"
+                    )))
+                }
+            };
+            Command::Screenshot { output }
+        }
+        "input" => {
+            if rest.is_empty() {
+                return Err(usage_error(
+                    "`input` needs at least one intent, e.g. `companion input next`",
+                ));
+            }
+            let intents = rest
+                .iter()
+                .map(|word| parse_intent(word))
+                .collect::<Result<_, _>>()?;
+            Command::Input { intents }
+        }
+        other => return Err(usage_error(format!("unknown command {other:?}"))),
+    };
+
+    Ok(Cli {
+        target,
+        format,
+        command,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(line: &str) -> Result<Cli, CliError> {
+        let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        parse(&args)
+    }
+
+    #[test]
+    fn global_flags_may_come_after_the_subcommand() {
+        let cli = parse_str("status --json --port /dev/ttyACM0").unwrap();
+        assert_eq!(cli.command, Command::Status);
+        assert_eq!(cli.format, OutputFormat::Json);
+        assert_eq!(cli.target.port.as_deref(), Some("/dev/ttyACM0"));
+    }
+
+    #[test]
+    fn push_collects_filters_and_ids() {
+        let id = Uuid::new_v4();
+        let cli = parse_str(&format!(
+            "sync --search git --org-id o1 --id {id} --dry-run"
+        ))
+        .unwrap();
+        let Command::Push(push) = cli.command else {
+            panic!("expected push")
+        };
+        assert_eq!(push.filter.search.as_deref(), Some("git"));
+        assert_eq!(push.filter.organization_id.as_deref(), Some("o1"));
+        assert_eq!(push.ids, vec![id]);
+        assert!(push.dry_run);
+    }
+
+    #[test]
+    fn screenshot_path_defaults_and_accepts_either_form() {
+        let path = |line| match parse_str(line).unwrap().command {
+            Command::Screenshot { output } => output,
+            other => panic!("expected screenshot, got {other:?}"),
+        };
+        assert_eq!(path("screenshot"), PathBuf::from("screenshot.png"));
+        assert_eq!(path("screenshot shot.png"), PathBuf::from("shot.png"));
+        assert_eq!(path("screenshot -o shot.png"), PathBuf::from("shot.png"));
+        assert!(parse_str("screenshot a.png -o b.png").is_err());
+    }
+
+    #[test]
+    fn input_parses_every_intent() {
+        let cli = parse_str("input next prev next:5 PREV:3 home End char:G Char:é Activate back")
+            .unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Input {
+                intents: vec![
+                    WireIntent::Next,
+                    WireIntent::Prev,
+                    WireIntent::NextN(5),
+                    WireIntent::PrevN(3),
+                    WireIntent::Home,
+                    WireIntent::End,
+                    WireIntent::Char('G'),
+                    WireIntent::Char('é'),
+                    WireIntent::Activate,
+                    WireIntent::Back,
+                ]
+            }
+        );
+        for bad in ["sideways", "char:", "char:ab", "prev:-1"] {
+            assert!(
+                matches!(parse_str(&format!("input {bad}")), Err(CliError::Usage(_))),
+                "{bad}"
+            );
+        }
+        assert!(matches!(parse_str("input"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn usage_errors() {
+        for line in [
+            "",
+            "frobnicate",
+            "status --dry-run",
+            "status extra",
+            "push --id not-a-uuid",
+            "push --device-url",
+            "--format yaml status",
+            "devices --forget",
+        ] {
+            assert!(
+                matches!(parse_str(line), Err(CliError::Usage(_))),
+                "{line:?} should be a usage error"
+            );
+        }
+    }
+
+    #[test]
+    fn help_wins_over_everything_else() {
+        assert_eq!(parse_str("push --help").unwrap().command, Command::Help);
+        assert_eq!(parse_str("-h").unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn detects_json_for_error_reporting() {
+        let args = |line: &str| {
+            line.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert!(wants_json(&args("frobnicate --json")));
+        assert!(wants_json(&args("--format json frobnicate")));
+        assert!(!wants_json(&args("status")));
+    }
+}
@@ -0,0 +1,437 @@
+//! One function per subcommand, each returning a [`Report`] (see
+//! `crate::output`). `run` is the only entry point `main.rs` calls.
+//!
+//! The environment is injected as `env` rather than read from `std::env`
+//! directly, matching `crate::target::resolve_target` and
+//! `crate::pairing::pairing_path`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use device_link::WireIntent;
+use push_protocol::SyncRequest;
+use serde::Serialize;
+
+use crate::bw::{fetch_bw_list_items, read_items_file, retain_ids};
+use crate::cli::{usage, Cli, Command, PushArgs};
+use crate::device::{self, DeviceStatus, DEFAULT_TIMEOUT};
+use crate::error::CliError;
+use crate::export;
+use crate::map_bw_items_to_credentials;
+use crate::output::{render, OutputFormat, Report};
+use crate::pairing::{self, Pairing};
+use crate::target::{resolve_target, Target, TargetSource, DEFAULT_DEVICE_BASE_URL};
+
+/// Espressif's USB vendor id: the ESP32-S3's built-in USB-Serial-JTAG
+/// enumerates with it. `devices` only pings ports with this VID; pinging
+/// arbitrary serial ports could upset whatever else is plugged in.
+const ESPRESSIF_USB_VID: u16 = 0x303A;
+
+/// `devices` probes several candidates; a dead one shouldn't stall the list.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Runs `cli` and prints its result to stdout.
+///
+/// # Errors
+///
+/// Whatever the subcommand fails with; `main.rs` prints it and exits with
+/// [`CliError::exit_code`].
+pub fn run(cli: &Cli, env: &dyn Fn(&str) -> Option<String>) -> Result<(), CliError> {
+    if cli.command == Command::Help {
+        println!("{}", usage());
+        return Ok(());
+    }
+
+    let pairing_path = pairing::pairing_path(env);
+    let format = cli.format;
+
+    // `pair` is how a broken pairing file gets replaced or reset (its error
+    // says to run `pair --forget`), so it reads the file tolerantly and
+    // `--forget` doesn't resolve a target at all. Every other command
+    // reports an unreadable pairing file rather than silently ignoring the
+    // device it names.
+    if let Command::Pair { forget } = &cli.command {
+        let path = pairing_path.ok_or_else(|| {
+            CliError::Local(
+                "can't locate a config directory; set COMPANION_CONFIG or HOME".to_string(),
+            )
+        })?;
+        if *forget {
+            print(format, &forget_pairing(&path)?);
+        } else {
+            let paired = pairing::load(&path).ok().flatten();
+            let (target, _) = resolve_target(&cli.target, env, paired.as_ref().map(|p| &p.target))?;
+            print(format, &pair(target, &path)?);
+        }
+        return Ok(());
+    }
+
+    let paired = match &pairing_path {
+        Some(path) => pairing::load(path)?,
+        None => None,
+    };
+    let (target, source) = resolve_target(&cli.target, env, paired.as_ref().map(|p| &p.target))?;
+
+    match &cli.command {
+        Command::Help | Command::Pair { .. } => unreachable!("handled above"),
+        Command::Devices => print(format, &devices(&target, paired.as_ref())),
+        Command::Push(args) => print(format, &push(&target, args, env)?),
+        Command::Status => print(format, &status(target, source)?),
+        Command::Screenshot { output } => print(format, &screenshot(target, output)?),
+        Command::Input { intents } => print(format, &input(target, intents)?),
+    }
+    Ok(())
+}
+
+fn print(format: OutputFormat, report: &impl Report) {
+    println!("{}", render(format, report));
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub target: Target,
+    pub source: TargetSource,
+    #[serde(flatten)]
+    pub status: DeviceStatus,
+}
+
+impl Report for StatusReport {
+    fn human(&self) -> String {
+        let mut lines = vec![format!(
+            "Device:      {} ({:?})",
+            self.target.address(),
+            self.source
+        )];
+        if let Some(name) = &self.status.name {
+            lines.push(format!("Name:        {name}"));
+        }
+        if let Some(profile) = &self.status.profile {
+            lines.push(format!("Profile:     {profile}"));
+        }
+        if let Some(fw) = &self.status.fw_version {
+            lines.push(format!("Firmware:    {fw}"));
+        }
+        if let Some((w, h)) = self.status.panel {
+            lines.push(format!("Panel:       {w}x{h}"));
+        }
+        if let Some(count) = self.status.credential_count {
+            lines.push(format!("Credentials: {count}"));
+        }
+        lines.join("\n")
+    }
+}
+
+fn status(target: Target, source: TargetSource) -> Result<StatusReport, CliError> {
+    let status = device::open(&target, DEFAULT_TIMEOUT)?.status()?;
+    Ok(StatusReport {
+        target,
+        source,
+        status,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct PushReport {
+    pub target: Target,
+    pub pushed: usize,
+    pub dry_run: bool,
+    /// The device's count of stored credentials after the sync; `None` on
+    /// a dry run.
+    pub synced: Option<usize>,
+}
+
+impl Report for PushReport {
+    fn human(&self) -> String {
+        if self.dry_run {
+            format!(
+                "Would push {} credential(s) to {} (dry run)",
+                self.pushed,
+                self.target.address()
+            )
+        } else {
+            format!(
+                "Pushed {} credential(s) to {}",
+                self.pushed,
+                self.target.address()
+            )
+        }
+    }
+}
+
+/// A password-protected export's password: stdin if `--password-stdin`,
+/// else `BW_EXPORT_PASSWORD`. Never a command-line argument, where it would
+/// end up in shell history and `ps`.
+fn export_password(
+    args: &PushArgs,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, CliError> {
+    if args.password_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).map_err(|e| {
+            CliError::Local(format!(
+                "failed to read the export password from stdin: {e}"
+            ))
+        })?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+    env("BW_EXPORT_PASSWORD").ok_or_else(|| {
+        CliError::Usage(
+            "this export is password-protected; set BW_EXPORT_PASSWORD or pass --password-stdin"
+                .to_string(),
+        )
+    })
+}
+
+fn push(
+    target: &Target,
+    args: &PushArgs,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<PushReport, CliError> {
+    let credentials = match &args.from_file {
+        Some(path) => {
+            export::load_credentials(&read_items_file(path)?, || export_password(args, env))?
+        }
+        None => map_bw_items_to_credentials(&fetch_bw_list_items(&args.filter)?),
+    };
+    let credentials = retain_ids(credentials, &args.ids);
+    let pushed = credentials.len();
+
+    let synced = if args.dry_run {
+        None
+    } else {
+        let response = device::open(target, DEFAULT_TIMEOUT)?.push(&SyncRequest { credentials })?;
+        Some(response.synced)
+    };
+    Ok(PushReport {
+        target: target.clone(),
+        pushed,
+        dry_run: args.dry_run,
+        synced,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreenshotReport {
+    pub target: Target,
+    pub path: PathBuf,
+    pub bytes: usize,
+}
+
+impl Report for ScreenshotReport {
+    fn human(&self) -> String {
+        format!(
+            "Saved screenshot from {} to {}",
+            self.target.address(),
+            self.path.display()
+        )
+    }
+}
+
+fn screenshot(target: Target, output: &Path) -> Result<ScreenshotReport, CliError> {
+    let png = device::open(&target, DEFAULT_TIMEOUT)?.screenshot()?;
+    std::fs::write(output, &png)
+        .map_err(|e| CliError::Local(format!("failed to write {}: {e}", output.display())))?;
+    Ok(ScreenshotReport {
+        target,
+        path: output.to_path_buf(),
+        bytes: png.len(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct InputReport {
+    pub target: Target,
+    pub sent: Vec<WireIntent>,
+}
+
+impl Report for InputReport {
+    fn human(&self) -> String {
+        format!(
+            "Sent {} intent(s) to {}",
+            self.sent.len(),
+            self.target.address()
+        )
+    }
+}
+
+fn input(target: Target, intents: &[WireIntent]) -> Result<InputReport, CliError> {
+    let mut device = device::open(&target, DEFAULT_TIMEOUT)?;
+    for intent in intents {
+        device.input(*intent)?;
+    }
+    Ok(InputReport {
+        target,
+        sent: intents.to_vec(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct PairReport {
+    /// `None` after `--forget` when nothing was paired.
+    pub target: Option<Target>,
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub forgotten: bool,
+}
+
+impl Report for PairReport {
+    fn human(&self) -> String {
+        match (&self.target, self.forgotten) {
+            (Some(target), true) => format!("Forgot paired device {}", target.address()),
+            (None, _) => "No device was paired".to_string(),
+            (Some(target), false) => {
+                let name = self
+                    .name
+                    .as_deref()
+                    .map(|n| format!(" ({n})"))
+                    .unwrap_or_default();
+                format!(
+                    "Paired with {}{name}; saved to {}",
+                    target.address(),
+                    self.path.display()
+                )
+            }
+        }
+    }
+}
+
+/// `pair --forget`: deletes the pairing file, even one that doesn't parse.
+fn forget_pairing(path: &Path) -> Result<PairReport, CliError> {
+    let previous = pairing::load(path).ok().flatten().map(|p| p.target);
+    let forgotten = pairing::forget(path)?;
+    Ok(PairReport {
+        target: previous.filter(|_| forgotten),
+        name: None,
+        path: path.to_path_buf(),
+        forgotten,
+    })
+}
+
+fn pair(target: Target, path: &Path) -> Result<PairReport, CliError> {
+    let status = device::open(&target, DEFAULT_TIMEOUT)?.status()?;
+    let paired_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let pairing = Pairing {
+        target: target.clone(),
+        name: status.name.clone(),
+        fw_version: status.fw_version,
+        paired_at,
+    };
+    pairing::save(path, &pairing)?;
+    Ok(PairReport {
+        target: Some(target),
+        name: status.name,
+        path: path.to_path_buf(),
+        forgotten: false,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceEntry {
+    pub target: Target,
+    /// e.g. "emulator HTTP API" or the USB product string.
+    pub description: String,
+    pub reachable: bool,
+    pub paired: bool,
+    /// Whether this is the target other subcommands would use right now.
+    pub selected: bool,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DevicesReport {
+    pub devices: Vec<DeviceEntry>,
+}
+
+impl Report for DevicesReport {
+    fn human(&self) -> String {
+        if self.devices.is_empty() {
+            return "No devices found".to_string();
+        }
+        self.devices
+            .iter()
+            .map(|d| {
+                let mut flags = vec![if d.reachable {
+                    "reachable"
+                } else {
+                    "unreachable"
+                }];
+                if d.paired {
+                    flags.push("paired");
+                }
+                let marker = if d.selected { '*' } else { ' ' };
+                let name = d
+                    .name
+                    .as_deref()
+                    .map(|n| format!(" {n}"))
+                    .unwrap_or_default();
+                format!(
+                    "{marker} {:<28} {}{name} [{}]",
+                    d.target.address(),
+                    d.description,
+                    flags.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn probe(target: &Target) -> Option<DeviceStatus> {
+    device::open(target, PROBE_TIMEOUT)
+        .and_then(|mut d| d.status())
+        .ok()
+}
+
+fn devices(selected: &Target, paired: Option<&Pairing>) -> DevicesReport {
+    let http = match selected {
+        Target::Http { .. } => selected.clone(),
+        Target::Serial { .. } => Target::Http {
+            base_url: DEFAULT_DEVICE_BASE_URL.to_string(),
+        },
+    };
+    let mut candidates = vec![(http, "emulator HTTP API".to_string(), true)];
+
+    // Enumeration failing (e.g. no sysfs in a container) just means no
+    // serial candidates, not an error.
+    for port in serialport::available_ports().unwrap_or_default() {
+        let serialport::SerialPortType::UsbPort(usb) = port.port_type else {
+            continue;
+        };
+        let description = usb
+            .product
+            .unwrap_or_else(|| format!("USB {:04x}:{:04x}", usb.vid, usb.pid));
+        candidates.push((
+            Target::Serial {
+                port: port.port_name,
+            },
+            description,
+            usb.vid == ESPRESSIF_USB_VID,
+        ));
+    }
+    if let Some(pairing) = paired {
+        if !candidates.iter().any(|(t, _, _)| *t == pairing.target) {
+            candidates.push((pairing.target.clone(), "paired device".to_string(), true));
+        }
+    }
+    if !candidates.iter().any(|(t, _, _)| t == selected) {
+        candidates.push((selected.clone(), "selected device".to_string(), true));
+    }
+
+    let devices = candidates
+        .into_iter()
+        .map(|(target, description, probe_it)| {
+            let status = if probe_it { probe(&target) } else { None };
+            DeviceEntry {
+                paired: paired.is_some_and(|p| p.target == target),
+                selected: target == *selected,
+                reachable: status.is_some(),
+                name: status.and_then(|s| s.name),
+                description,
+                target,
+            }
+        })
+        .collect();
+    DevicesReport { devices }
+}
@@ -0,0 +1,85 @@
+//! The transport-neutral view of "a device" every subcommand works against.
+//!
+//! [`Device`] has one implementation per [`Target`] transport:
+//! `crate::http::HttpDevice` (emulator HTTP API) and
+//! `crate::serial::SerialDevice` (device-link over a USB serial port).
+//! Subcommands in `crate::commands` only ever see `Box<dyn Device>`, so
+//! "works over HTTP but not serial" can only happen inside a transport
+//! module, never in command logic.
+
+use std::time::Duration;
+
+use device_link::WireIntent;
+use push_protocol::{SyncRequest, SyncResponse};
+use serde::Serialize;
+
+use crate::error::CliError;
+use crate::http::HttpDevice;
+use crate::serial::SerialDevice;
+use crate::target::Target;
+
+/// How long any single request/reply exchange may take before the device
+/// counts as unreachable. Generous for a local link; a screenshot over
+/// serial is the slowest exchange (~110 KB at 320x170 Rgb565).
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a device reports about itself. Each transport fills in what it
+/// knows: the emulator's `/api/status` has a credential count, its
+/// instance name, panel profile and size but no firmware version; a
+/// device-link `Pong` has identity and panel size but no count.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeviceStatus {
+    pub name: Option<String>,
+    pub fw_version: Option<String>,
+    /// The emulator's panel profile (e.g. `ssd1306`, or `custom`). Real
+    /// devices don't report one.
+    pub profile: Option<String>,
+    pub credential_count: Option<usize>,
+    /// `(width, height)` in pixels.
+    pub panel: Option<(u16, u16)>,
+}
+
+pub trait Device {
+    /// Probes the device; also serves as the reachability check for
+    /// `devices` and `pair`.
+    ///
+    /// # Errors
+    ///
+    /// [`CliError::Unreachable`] if it doesn't answer, [`CliError::Device`]
+    /// if it answers with an error.
+    fn status(&mut self) -> Result<DeviceStatus, CliError>;
+
+    /// Replaces the device's credential store with `request`.
+    ///
+    /// # Errors
+    ///
+    /// As [`Device::status`]; a rejected sync is [`CliError::Device`].
+    fn push(&mut self, request: &SyncRequest) -> Result<SyncResponse, CliError>;
+
+    /// The current screen, PNG-encoded.
+    ///
+    /// # Errors
+    ///
+    /// As [`Device::status`].
+    fn screenshot(&mut self) -> Result<Vec<u8>, CliError>;
+
+    /// Injects one navigation intent.
+    ///
+    /// # Errors
+    ///
+    /// As [`Device::status`].
+    fn input(&mut self, intent: WireIntent) -> Result<(), CliError>;
+}
+
+/// Opens a connection to `target`. For HTTP this can't fail (ureq connects
+/// per request); for serial it opens the port.
+///
+/// # Errors
+///
+/// [`CliError::Unreachable`] if the serial port can't be opened.
+pub fn open(target: &Target, timeout: Duration) -> Result<Box<dyn Device>, CliError> {
+    match target {
+        Target::Http { base_url } => Ok(Box::new(HttpDevice::new(base_url, timeout))),
+        Target::Serial { port } => Ok(Box::new(SerialDevice::open(port, timeout)?)),
+    }
+}
@@ -0,0 +1,92 @@
+//! The one error type every subcommand returns, and the process exit code
+//! each kind maps to.
+//!
+//! Exit codes are part of the CLI's contract (scripts branch on them), so
+//! they're grouped by *what the caller can do about it*, not by which
+//! layer failed:
+//!
+//! | code | kind          | meaning                                                        |
+//! |------|---------------|----------------------------------------------------------------|
+//! | 0    | --            | success                                                        |
+//! | 1    | `local`       | local I/O: writing the screenshot, the pairing file, ...       |
+//! | 2    | `usage`       | bad arguments; nothing was sent anywhere                       |
+//! | 3    | `unreachable` | couldn't reach the device (connection refused, no reply, ...)  |
+//! | 4    | `device`      | the device answered, but with an error (HTTP error, `SyncNack`)|
+//! | 5    | `source`      | couldn't get vault items (`bw` missing/locked, bad JSON file)  |
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    Local(String),
+    Usage(String),
+    Unreachable(String),
+    Device(String),
+    Source(String),
+}
+
+impl CliError {
+    /// Process exit code -- see module docs.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Local(_) => 1,
+            CliError::Usage(_) => 2,
+            CliError::Unreachable(_) => 3,
+            CliError::Device(_) => 4,
+            CliError::Source(_) => 5,
+        }
+    }
+
+    /// Stable machine-readable name, used as `error.kind` in `--json`
+    /// output.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Local(_) => "local",
+            CliError::Usage(_) => "usage",
+            CliError::Unreachable(_) => "unreachable",
+            CliError::Device(_) => "device",
+            CliError::Source(_) => "source",
+        }
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        match self {
+            CliError::Local(message)
+            | CliError::Usage(message)
+            | CliError::Unreachable(message)
+            | CliError::Device(message)
+            | CliError::Source(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for CliError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_distinct_and_nonzero() {
+        let all = [
+            CliError::Local(String::new()),
+            CliError::Usage(String::new()),
+            CliError::Unreachable(String::new()),
+            CliError::Device(String::new()),
+            CliError::Source(String::new()),
+        ];
+        let mut codes: Vec<u8> = all.iter().map(CliError::exit_code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes, vec![1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,353 @@
+//! Reading vault items from a file, without `bw`: `push --from-file` accepts
+//! any of
+//!
+//! - `bw list items` output (a bare JSON array) -- the M1 format, mapped by
+//!   [`crate::map_bw_items_to_credentials`], logins only;
+//! - a Bitwarden **unencrypted JSON export** (`{"encrypted": false,
+//!   "items": [...]}`);
+//! - a Bitwarden **password-protected export** (`{"encrypted": true,
+//!   "passwordProtected": true, ...}`), decrypted with
+//!   [`crate::export_crypto`].
+//!
+//! "Account restricted" encrypted exports are encrypted with the account's
+//! own key, which only a logged-in client has; those are rejected with a
+//! hint to re-export as password-protected.
+//!
+//! Export items share `bw list items`' schema, but an export is a
+//! deliberate "take my vault with me" action, so every item type the wire
+//! `Credential` can carry is mapped, not just logins:
+//!
+//! | type | item        | `username`                   | `password`    | `notes`                               |
+//! |------|-------------|------------------------------|---------------|---------------------------------------|
+//! | 1    | login       | as `map_bw_items_to_credentials` | same      | same                                  |
+//! | 2    | secure note | `""`                         | `""`          | the note                              |
+//! | 3    | card        | cardholder name              | card number   | brand, expiry, notes                  |
+//! | 4    | identity    | username, else email         | `""`          | name, email, phone, address, notes    |
+//!
+//! A card's security code is not carried at all: `password` is the only
+//! field the device masks, and it already holds the card number, so the
+//! code would end up in `notes` -- in plaintext on the detail screen and
+//! in the emulator's UI tree.
+//!
+//! SSH keys (type 5) are skipped with a warning: a private key is not
+//! something to page through on a 320x170 screen.
+
+use push_protocol::Credential;
+use serde_json::Value;
+
+use crate::error::CliError;
+use crate::export_crypto::{decrypt_enc_string, derive_key, stretch_key, CryptoError, Kdf};
+use crate::{
+    item_id, item_name, item_type, map_bw_items_to_credentials, map_one_item, BW_ITEM_TYPE_LOGIN,
+};
+
+const ITEM_TYPE_SECURE_NOTE: u64 = 2;
+const ITEM_TYPE_CARD: u64 = 3;
+const ITEM_TYPE_IDENTITY: u64 = 4;
+const ITEM_TYPE_SSH_KEY: u64 = 5;
+
+const KDF_TYPE_PBKDF2: u64 = 0;
+const KDF_TYPE_ARGON2ID: u64 = 1;
+
+/// Which of the formats in the module docs a file is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    BwListItems,
+    Unencrypted,
+    PasswordProtected,
+    AccountRestricted,
+}
+
+/// Identifies `document`'s format.
+///
+/// # Errors
+///
+/// [`CliError::Source`] if it's none of them.
+pub fn detect(document: &Value) -> Result<FileFormat, CliError> {
+    if document.is_array() {
+        return Ok(FileFormat::BwListItems);
+    }
+    let flag = |key: &str| document.get(key).and_then(Value::as_bool);
+    match (flag("encrypted"), flag("passwordProtected")) {
+        (Some(false), _) => Ok(FileFormat::Unencrypted),
+        (Some(true), Some(true)) => Ok(FileFormat::PasswordProtected),
+        (Some(true), _) => Ok(FileFormat::AccountRestricted),
+        (None, _) => Err(CliError::Source(
+            "not a Bitwarden export or `bw list items` output (no top-level array or \"encrypted\" field)"
+                .to_string(),
+        )),
+    }
+}
+
+/// Parses any supported file into credentials. `password` is only called
+/// for a password-protected export.
+///
+/// # Errors
+///
+/// [`CliError::Source`] for an unreadable/unsupported file or a wrong
+/// password; whatever `password` returns if it fails.
+pub fn load_credentials(
+    json: &str,
+    password: impl FnOnce() -> Result<String, CliError>,
+) -> Result<Vec<Credential>, CliError> {
+    let document: Value = serde_json::from_str(json)
+        .map_err(|e| CliError::Source(format!("file is not valid JSON: {e}")))?;
+    match detect(&document)? {
+        FileFormat::BwListItems => Ok(map_bw_items_to_credentials(json)),
+        FileFormat::Unencrypted => Ok(map_export_items(&document)),
+        FileFormat::PasswordProtected => {
+            let decrypted = decrypt_export(&document, &password()?)?;
+            let inner: Value = serde_json::from_str(&decrypted).map_err(|e| {
+                CliError::Source(format!("decrypted export is not valid JSON: {e}"))
+            })?;
+            Ok(map_export_items(&inner))
+        }
+        FileFormat::AccountRestricted => Err(CliError::Source(
+            "this export is encrypted with your account key, which only a logged-in client can \
+             decrypt; re-export with the \"Password protected\" file type"
+                .to_string(),
+        )),
+    }
+}
+
+fn kdf_of(document: &Value) -> Result<Kdf, CliError> {
+    let number = |key: &str| {
+        document
+            .get(key)
+            .and_then(Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| CliError::Source(format!("export is missing a valid \"{key}\"")))
+    };
+    match document.get("kdfType").and_then(Value::as_u64) {
+        Some(KDF_TYPE_PBKDF2) => Ok(Kdf::Pbkdf2 {
+            iterations: number("kdfIterations")?,
+        }),
+        Some(KDF_TYPE_ARGON2ID) => Ok(Kdf::Argon2id {
+            iterations: number("kdfIterations")?,
+            memory_mib: number("kdfMemory")?,
+            parallelism: number("kdfParallelism")?,
+        }),
+        other => Err(CliError::Source(format!(
+            "export uses an unsupported kdfType {other:?}"
+        ))),
+    }
+}
+
+/// Decrypts a password-protected export's `data` to the JSON text of the
+/// unencrypted export inside it. The validation string is decrypted first
+/// so a wrong password is reported as such, not as corrupt data.
+///
+/// # Errors
+///
+/// [`CliError::Source`] on a wrong password or a malformed file.
+pub fn decrypt_export(document: &Value, password: &str) -> Result<String, CliError> {
+    let field = |key: &str| {
+        document
+            .get(key)
+            .and_then(Value::as_str)
+            .ok_or_else(|| CliError::Source(format!("export is missing \"{key}\"")))
+    };
+    let source = |e: CryptoError| CliError::Source(format!("failed to decrypt the export: {e}"));
+
+    let key =
+        stretch_key(&derive_key(password, field("salt")?, kdf_of(document)?).map_err(source)?);
+    decrypt_enc_string(field("encKeyValidation_DO_NOT_EDIT")?, &key).map_err(source)?;
+    let data = decrypt_enc_string(field("data")?, &key).map_err(source)?;
+    String::from_utf8(data)
+        .map_err(|_| CliError::Source("decrypted export is not UTF-8".to_string()))
+}
+
+/// Maps an unencrypted export document's `items` per the table in the
+/// module docs.
+#[must_use]
+pub fn map_export_items(document: &Value) -> Vec<Credential> {
+    let Some(items) = document.get("items").and_then(Value::as_array) else {
+        eprintln!("warning: export has no \"items\" array; no credentials mapped");
+        return Vec::new();
+    };
+    items.iter().filter_map(map_export_item).collect()
+}
+
+fn map_export_item(item: &Value) -> Option<Credential> {
+    let kind = item_type(item);
+    if kind == Some(BW_ITEM_TYPE_LOGIN) {
+        return map_one_item(item);
+    }
+
+    let str_field = |object: Option<&Value>, key: &str| {
+        object
+            .and_then(|o| o.get(key))
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let item_notes = str_field(Some(item), "notes");
+    let (username, password, details) = match kind {
+        Some(ITEM_TYPE_SECURE_NOTE) => (None, None, Vec::new()),
+        Some(ITEM_TYPE_CARD) => {
+            let card = item.get("card");
+            let expiry = match (str_field(card, "expMonth"), str_field(card, "expYear")) {
+                (Some(month), Some(year)) => Some(format!("{month:0>2}/{year}")),
+                (month, year) => month.or(year),
+            };
+            let details = [
+                ("Brand", str_field(card, "brand")),
+                ("Expires", expiry),
+            ];
+            (
+                str_field(card, "cardholderName"),
+                str_field(card, "number"),
+                labelled(&details),
+            )
+        }
+        Some(ITEM_TYPE_IDENTITY) => {
+            let identity = item.get("identity");
+            let joined = |keys: &[&str], separator: &str| {
+                let parts: Vec<String> = keys
+                    .iter()
+                    .filter_map(|key| str_field(identity, key))
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join(separator))
+            };
+            let details = [
+                (
+                    "Name",
+                    joined(&["title", "firstName", "middleName", "lastName"], " "),
+                ),
+                ("Email", str_field(identity, "email")),
+                ("Phone", str_field(identity, "phone")),
+                (
+                    "Address",
+                    joined(
+                        &[
+                            "address1",
+                            "address2",
+                            "address3",
+                            "city",
+                            "state",
+                            "postalCode",
+                            "country",
+                        ],
+                        ", ",
+                    ),
+                ),
+            ];
+            let username = str_field(identity, "username").or_else(|| str_field(identity, "email"));
+            (username, None, labelled(&details))
+        }
+        Some(ITEM_TYPE_SSH_KEY) => {
+            eprintln!(
+                "warning: skipping SSH key item {:?}: not supported on the device",
+                item_name(item)
+            );
+            return None;
+        }
+        other => {
+            eprintln!(
+                "warning: skipping item {:?} of unknown type {other:?}",
+                item_name(item)
+            );
+            return None;
+        }
+    };
+
+    let notes: Vec<String> = details.into_iter().chain(item_notes).collect();
+    Some(Credential {
+        id: item_id(item)?,
+        name: item_name(item),
+        username: username.unwrap_or_default(),
+        password: password.unwrap_or_default(),
+        uri: None,
+        notes: (!notes.is_empty()).then(|| notes.join("\n")),
+    })
+}
+
+fn labelled(fields: &[(&str, Option<String>)]) -> Vec<String> {
+    fields
+        .iter()
+        .filter_map(|(label, value)| value.as_ref().map(|value| format!("{label}: {value}")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_password() -> Result<String, CliError> {
+        panic!("password must not be asked for");
+    }
+
+    #[test]
+    fn detects_every_format() {
+        let detect_str = |json: &str| detect(&serde_json::from_str(json).unwrap());
+        assert_eq!(detect_str("[]").unwrap(), FileFormat::BwListItems);
+        assert_eq!(
+            detect_str(r#"{"encrypted": false, "items": []}"#).unwrap(),
+            FileFormat::Unencrypted
+        );
+        assert_eq!(
+            detect_str(r#"{"encrypted": true, "passwordProtected": true}"#).unwrap(),
+            FileFormat::PasswordProtected
+        );
+        assert_eq!(
+            detect_str(r#"{"encrypted": true}"#).unwrap(),
+            FileFormat::AccountRestricted
+        );
+        assert!(detect_str(r#"{"hello": 1}"#).is_err());
+    }
+
+    #[test]
+    fn bw_list_items_keeps_the_m1_login_only_mapping() {
+        let json = r#"[
+            {"id": "6b1f0c2e-6b4a-4b8b-9b1a-000000000001", "type": 1, "name": "L", "login": {}},
+            {"id": "6b1f0c2e-6b4a-4b8b-9b1a-000000000002", "type": 2, "name": "N", "notes": "x"}
+        ]"#;
+        let credentials = load_credentials(json, no_password).unwrap();
+        assert_eq!(credentials.len(), 1);
+    }
+
+    #[test]
+    fn maps_cards_and_identities_into_credential_fields() {
+        let document = serde_json::json!({"encrypted": false, "items": [
+            {"id": "6b1f0c2e-6b4a-4b8b-9b1a-000000000003", "type": 3, "name": "Visa", "notes": "travel card",
+             "card": {"cardholderName": "Jane", "number": "4111", "brand": "Visa", "expMonth": "4", "expYear": "2030", "code": "123"}},
+            {"id": "6b1f0c2e-6b4a-4b8b-9b1a-000000000004", "type": 4, "name": "Me",
+             "identity": {"firstName": "Jane", "lastName": "Doe", "email": "jane@example.com", "city": "Springfield", "country": "US"}}
+        ]});
+        let credentials = map_export_items(&document);
+
+        assert_eq!(credentials[0].username, "Jane");
+        assert_eq!(credentials[0].password, "4111");
+        assert_eq!(
+            credentials[0].notes.as_deref(),
+            Some("Brand: Visa\nExpires: 04/2030\ntravel card")
+        );
+        assert!(!credentials.iter().any(|c| format!("{c:?}").contains("123")));
+
+        assert_eq!(credentials[1].username, "jane@example.com");
+        assert_eq!(credentials[1].password, "");
+        assert_eq!(
+            credentials[1].notes.as_deref(),
+            Some("Name: Jane Doe\nEmail: jane@example.com\nAddress: Springfield, US")
+        );
+    }
+
+    #[test]
+    fn account_restricted_exports_are_rejected_with_a_hint() {
+        let error =
+            load_credentials(r#"{"encrypted": true, "data": "2.x|y|z"}"#, no_password).unwrap_err();
+        assert!(
+            matches!(&error, CliError::Source(message) if message.contains("Password protected"))
+        );
+    }
+
+    #[test]
+    fn unsupported_kdf_is_a_source_error() {
+        let document = serde_json::json!({"encrypted": true, "passwordProtected": true, "kdfType": 7,
+            "salt": "s", "encKeyValidation_DO_NOT_EDIT": "2.a|b|c", "data": "2.a|b|c"});
+        assert!(matches!(
+            decrypt_export(&document, "pw"),
+            Err(CliError::Source(_))
+        ));
+    }
+}
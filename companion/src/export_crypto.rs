@@ -0,0 +1,352 @@
+//! The crypto of a Bitwarden *password-protected* export, and nothing else:
+//! password -> key (the file's KDF), key -> enc/mac keys (HKDF stretch), and
+//! decrypting type-2 EncStrings (AES-256-CBC, then HMAC-SHA256 over
+//! `iv || ciphertext`, verified before decrypting).
+//!
+//! This mirrors the official clients' export format, not the SDK's API:
+//!
+//! - PBKDF2 (`kdfType` 0): PBKDF2-HMAC-SHA256 over the UTF-8 password, with
+//!   the file's `salt` string's UTF-8 bytes as the salt.
+//! - Argon2id (`kdfType` 1): over the UTF-8 password with `SHA-256(salt)`
+//!   as the salt, `kdfMemory` in MiB, `kdfIterations`, `kdfParallelism`.
+//! - Both produce 32 bytes, stretched with HKDF-Expand-SHA256 (no extract
+//!   step) into a 32-byte `"enc"` key and a 32-byte `"mac"` key.
+//!
+//! Known-answer tests pin each step to values computed independently by
+//! `tests/fixtures/exports/generate.py`.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+/// A password-protected export's KDF settings, as stored in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Pbkdf2 {
+        iterations: u32,
+    },
+    Argon2id {
+        iterations: u32,
+        memory_mib: u32,
+        parallelism: u32,
+    },
+}
+
+// The KDF parameter ranges the official Bitwarden clients accept. The
+// parameters come from the file being imported, so `derive_key` checks
+// them before running the KDF: a corrupt or hostile export could otherwise
+// ask for a multi-GB Argon2 allocation (which aborts the process) or hours
+// of PBKDF2.
+
+/// PBKDF2-SHA256 iterations.
+pub const PBKDF2_ITERATIONS: RangeInclusive<u32> = 5_000..=2_000_000;
+/// Argon2id memory, in MiB (`kdfMemory`).
+pub const ARGON2_MEMORY_MIB: RangeInclusive<u32> = 16..=1024;
+/// Argon2id iterations.
+pub const ARGON2_ITERATIONS: RangeInclusive<u32> = 2..=10;
+/// Argon2id parallelism (lanes).
+pub const ARGON2_PARALLELISM: RangeInclusive<u32> = 1..=16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    /// KDF parameters outside the ranges the official clients accept
+    /// (`PBKDF2_ITERATIONS`, `ARGON2_*`), or that the KDF itself rejects.
+    InvalidKdf(String),
+    /// Not a `2.<iv>|<data>|<mac>` EncString, or bad base64 inside one.
+    MalformedEncString(String),
+    /// An EncString type other than 2 (AES-CBC-256 + HMAC-SHA256).
+    UnsupportedEncStringType(String),
+    /// MAC mismatch: wrong password, or a tampered/corrupt file.
+    MacMismatch,
+    /// MAC verified but the plaintext didn't unpad. Can't happen for a file
+    /// the clients wrote; reported rather than panicked on.
+    BadPadding,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::InvalidKdf(reason) => write!(f, "invalid KDF parameters: {reason}"),
+            CryptoError::MalformedEncString(reason) => {
+                write!(f, "malformed encrypted value: {reason}")
+            }
+            CryptoError::UnsupportedEncStringType(kind) => {
+                write!(f, "unsupported encryption type {kind:?} (only type 2, AES-CBC-256 + HMAC, is supported)")
+            }
+            CryptoError::MacMismatch => f.write_str("wrong password, or the file is corrupt"),
+            CryptoError::BadPadding => f.write_str("decrypted data has invalid padding"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// The stretched key pair an export is encrypted with.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExportKey {
+    pub enc: [u8; 32],
+    pub mac: [u8; 32],
+}
+
+// Never print key material, even in debug output.
+impl fmt::Debug for ExportKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExportKey { .. }")
+    }
+}
+
+/// Runs `kdf` over `password`/`salt`: the 32-byte key before stretching.
+///
+/// # Errors
+///
+/// [`CryptoError::InvalidKdf`] if the parameters are outside the ranges
+/// the official clients accept ([`PBKDF2_ITERATIONS`], [`ARGON2_MEMORY_MIB`],
+/// [`ARGON2_ITERATIONS`], [`ARGON2_PARALLELISM`]) or the KDF rejects them.
+pub fn derive_key(password: &str, salt: &str, kdf: Kdf) -> Result<[u8; 32], CryptoError> {
+    let mut key = [0u8; 32];
+    match kdf {
+        Kdf::Pbkdf2 { iterations } => {
+            check_kdf_param("PBKDF2 iterations", iterations, &PBKDF2_ITERATIONS)?;
+            pbkdf2::pbkdf2_hmac::<Sha256>(
+                password.as_bytes(),
+                salt.as_bytes(),
+                iterations,
+                &mut key,
+            );
+        }
+        Kdf::Argon2id {
+            iterations,
+            memory_mib,
+            parallelism,
+        } => {
+            check_kdf_param("Argon2 memory (MiB)", memory_mib, &ARGON2_MEMORY_MIB)?;
+            check_kdf_param("Argon2 iterations", iterations, &ARGON2_ITERATIONS)?;
+            check_kdf_param("Argon2 parallelism", parallelism, &ARGON2_PARALLELISM)?;
+            let memory_kib = memory_mib * 1024;
+            let params = argon2::Params::new(memory_kib, iterations, parallelism, Some(32))
+                .map_err(|e| CryptoError::InvalidKdf(e.to_string()))?;
+            let salt_hash = Sha256::digest(salt.as_bytes());
+            argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                .hash_password_into(password.as_bytes(), &salt_hash, &mut key)
+                .map_err(|e| CryptoError::InvalidKdf(e.to_string()))?;
+        }
+    }
+    Ok(key)
+}
+
+fn check_kdf_param(name: &str, value: u32, range: &RangeInclusive<u32>) -> Result<(), CryptoError> {
+    if range.contains(&value) {
+        return Ok(());
+    }
+    Err(CryptoError::InvalidKdf(format!(
+        "{name} is {value}, outside {}..={}",
+        range.start(),
+        range.end()
+    )))
+}
+
+/// HKDF-Expand-SHA256 `key` into the `"enc"`/`"mac"` pair.
+#[must_use]
+pub fn stretch_key(key: &[u8; 32]) -> ExportKey {
+    let hkdf = hkdf::Hkdf::<Sha256>::from_prk(key).expect("a 32-byte PRK is always long enough");
+    let mut stretched = ExportKey {
+        enc: [0; 32],
+        mac: [0; 32],
+    };
+    hkdf.expand(b"enc", &mut stretched.enc)
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    hkdf.expand(b"mac", &mut stretched.mac)
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    stretched
+}
+
+/// Decrypts a type-2 EncString (`2.<b64 iv>|<b64 data>|<b64 mac>`).
+///
+/// # Errors
+///
+/// See [`CryptoError`]; a wrong key is [`CryptoError::MacMismatch`].
+pub fn decrypt_enc_string(enc_string: &str, key: &ExportKey) -> Result<Vec<u8>, CryptoError> {
+    let (kind, rest) = enc_string
+        .split_once('.')
+        .ok_or_else(|| CryptoError::MalformedEncString("missing type prefix".to_string()))?;
+    if kind != "2" {
+        return Err(CryptoError::UnsupportedEncStringType(kind.to_string()));
+    }
+    let parts: Vec<&str> = rest.split('|').collect();
+    let [iv, data, mac] = parts[..] else {
+        return Err(CryptoError::MalformedEncString(format!(
+            "expected 3 parts, found {}",
+            parts.len()
+        )));
+    };
+    let decode = |part: &str, what: &str| {
+        STANDARD
+            .decode(part)
+            .map_err(|e| CryptoError::MalformedEncString(format!("{what}: {e}")))
+    };
+    let (iv, data, mac) = (
+        decode(iv, "iv")?,
+        decode(data, "data")?,
+        decode(mac, "mac")?,
+    );
+    let iv: [u8; 16] = iv
+        .try_into()
+        .map_err(|_| CryptoError::MalformedEncString("iv is not 16 bytes".to_string()))?;
+
+    let mut hmac = HmacSha256::new_from_slice(&key.mac).expect("HMAC accepts any key length");
+    hmac.update(&iv);
+    hmac.update(&data);
+    hmac.verify_slice(&mac)
+        .map_err(|_| CryptoError::MacMismatch)?;
+
+    Aes256CbcDec::new(&key.enc.into(), &iv.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(&data)
+        .map_err(|_| CryptoError::BadPadding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected values from `tests/fixtures/exports/generate.py`.
+    const PASSWORD: &str = "correct horse battery staple";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn pbkdf2_known_answer() {
+        let key = derive_key(
+            PASSWORD,
+            "fixture-salt-pbkdf2",
+            Kdf::Pbkdf2 { iterations: 5000 },
+        )
+        .unwrap();
+        assert_eq!(
+            hex(&key),
+            "679fee0b9fe5f07250115affa003329984d0cb7f380a008c3d390169a2a964f0"
+        );
+    }
+
+    #[test]
+    fn argon2id_known_answer() {
+        let kdf = Kdf::Argon2id {
+            iterations: 3,
+            memory_mib: 16,
+            parallelism: 4,
+        };
+        let key = derive_key(PASSWORD, "fixture-salt-argon2id", kdf).unwrap();
+        assert_eq!(
+            hex(&key),
+            "f2e23f8604f6d880ecce966ec7d89629cdb0172c69a7b2470bb212df7dfe1f17"
+        );
+    }
+
+    #[test]
+    fn stretch_known_answer() {
+        let key = derive_key(
+            PASSWORD,
+            "fixture-salt-pbkdf2",
+            Kdf::Pbkdf2 { iterations: 5000 },
+        )
+        .unwrap();
+        let stretched = stretch_key(&key);
+        assert_eq!(
+            hex(&stretched.enc),
+            "194a2d48a3042082e3aead57f0c45b389e79debf4fa8672013cbb525606f2246"
+        );
+        assert_eq!(
+            hex(&stretched.mac),
+            "527cdcb0b0a984df2185b59b2191a4b1dc02d7328c5e22e9af8c8b11ffde71b6"
+        );
+    }
+
+    #[test]
+    fn decrypts_the_fixture_validation_string() {
+        let key = stretch_key(
+            &derive_key(
+                PASSWORD,
+                "fixture-salt-pbkdf2",
+                Kdf::Pbkdf2 { iterations: 5000 },
+            )
+            .unwrap(),
+        );
+        let validation = "2.AAECAwQFBgcICQoLDA0ODw==|WzQxMOy0km9k3EADeIFiM0ZJk67mYGekGtYABlD5i9iT3wlnix3M+rG99wBbJwJN|Uiqr9euuKLokYCKgg9uSrR9TnX3KhaSISGjG5apnsHU=";
+        assert_eq!(
+            decrypt_enc_string(validation, &key).unwrap(),
+            b"c0ffee00-0000-4000-8000-000000000000"
+        );
+
+        let wrong = stretch_key(&[0; 32]);
+        assert_eq!(
+            decrypt_enc_string(validation, &wrong),
+            Err(CryptoError::MacMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_enc_strings() {
+        let key = stretch_key(&[0; 32]);
+        assert!(matches!(
+            decrypt_enc_string("no-type", &key),
+            Err(CryptoError::MalformedEncString(_))
+        ));
+        assert!(matches!(
+            decrypt_enc_string("2.a|b", &key),
+            Err(CryptoError::MalformedEncString(_))
+        ));
+        assert!(matches!(
+            decrypt_enc_string("2.!!|!!|!!", &key),
+            Err(CryptoError::MalformedEncString(_))
+        ));
+        assert!(matches!(
+            decrypt_enc_string("0.aaaa|bbbb", &key),
+            Err(CryptoError::UnsupportedEncStringType(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_kdf_parameters_are_errors_not_panics() {
+        // The out-of-range maxima would otherwise run for hours or try to
+        // allocate gigabytes: reaching the KDF at all would hang or abort.
+        for iterations in [0, 4_999, 2_000_001, u32::MAX] {
+            assert!(
+                matches!(
+                    derive_key("p", "s", Kdf::Pbkdf2 { iterations }),
+                    Err(CryptoError::InvalidKdf(_))
+                ),
+                "{iterations}"
+            );
+        }
+        let argon2 = |iterations, memory_mib, parallelism| Kdf::Argon2id {
+            iterations,
+            memory_mib,
+            parallelism,
+        };
+        for kdf in [
+            argon2(0, 16, 4),
+            argon2(1, 16, 4),
+            argon2(11, 16, 4),
+            argon2(u32::MAX, 16, 4),
+            argon2(3, 15, 4),
+            argon2(3, 1025, 4),
+            argon2(3, u32::MAX, 4),
+            argon2(3, 16, 0),
+            argon2(3, 16, 17),
+            argon2(3, 16, u32::MAX),
+        ] {
+            assert!(
+                matches!(derive_key("p", "s", kdf), Err(CryptoError::InvalidKdf(_))),
+                "{kdf:?}"
+            );
+        }
+    }
+}
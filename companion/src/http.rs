@@ -0,0 +1,159 @@
+//! [`Device`] over the desktop emulator's HTTP API
+//! (`emulator/src/desktop/http_server.rs`): CBOR `POST /api/sync`, JSON
+//! `GET /api/status`, JSON `POST /api/input`, PNG `GET /api/screenshot`.
+//!
+//! Error mapping: a transport-level failure (connection refused, DNS,
+//! timeout) is [`CliError::Unreachable`]; an HTTP error status means the
+//! emulator answered, so it's [`CliError::Device`].
+
+use std::io::Read;
+use std::time::Duration;
+
+use device_link::WireIntent;
+use push_protocol::{SyncRequest, SyncResponse};
+use serde_json::Value;
+
+use crate::device::{Device, DeviceStatus};
+use crate::error::CliError;
+
+pub struct HttpDevice {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+impl HttpDevice {
+    #[must_use]
+    pub fn new(base_url: &str, timeout: Duration) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(timeout.min(Duration::from_secs(3)))
+            .timeout(timeout)
+            .build();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    fn map_error(&self, path: &str, error: ureq::Error) -> CliError {
+        let url = self.url(path);
+        match error {
+            ureq::Error::Status(code, response) => {
+                let body = response.into_string().unwrap_or_default();
+                CliError::Device(format!("{url} answered HTTP {code}: {}", body.trim()))
+            }
+            ureq::Error::Transport(transport) => CliError::Unreachable(format!(
+                "failed to reach the device at {url}: {transport}\n\
+                 Is the emulator/device running and reachable?"
+            )),
+        }
+    }
+
+    fn read_json(&self, path: &str, response: ureq::Response) -> Result<Value, CliError> {
+        response.into_json().map_err(|e| {
+            CliError::Device(format!(
+                "{} returned an unreadable JSON body: {e}",
+                self.url(path)
+            ))
+        })
+    }
+}
+
+/// The descriptor's `[width, height]`, if it is one that fits a
+/// [`DeviceStatus::panel`].
+fn panel_size(panel: &Value) -> Option<(u16, u16)> {
+    let [width, height] = panel.as_array()?.as_slice() else {
+        return None;
+    };
+    let dimension = |value: &Value| u16::try_from(value.as_u64()?).ok();
+    Some((dimension(width)?, dimension(height)?))
+}
+
+impl Device for HttpDevice {
+    fn status(&mut self) -> Result<DeviceStatus, CliError> {
+        const PATH: &str = "/api/status";
+        let response = self
+            .agent
+            .get(&self.url(PATH))
+            .call()
+            .map_err(|e| self.map_error(PATH, e))?;
+        let body = self.read_json(PATH, response)?;
+        let credential_count = body
+            .get("credential_count")
+            .and_then(Value::as_u64)
+            .and_then(|count| usize::try_from(count).ok());
+        // Emulators older than the `device` descriptor just leave these
+        // unset.
+        let device = body.get("device");
+        let text = |key: &str| {
+            device
+                .and_then(|device| device.get(key))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        Ok(DeviceStatus {
+            name: text("name"),
+            profile: text("profile"),
+            panel: device
+                .and_then(|device| device.get("panel"))
+                .and_then(panel_size),
+            credential_count,
+            ..DeviceStatus::default()
+        })
+    }
+
+    fn push(&mut self, request: &SyncRequest) -> Result<SyncResponse, CliError> {
+        const PATH: &str = "/api/sync";
+        let mut cbor = Vec::new();
+        ciborium::into_writer(request, &mut cbor)
+            .map_err(|e| CliError::Local(format!("failed to CBOR-encode the sync request: {e}")))?;
+        let response = self
+            .agent
+            .post(&self.url(PATH))
+            .set("Content-Type", "application/cbor")
+            .send_bytes(&cbor)
+            .map_err(|e| self.map_error(PATH, e))?;
+        serde_json::from_value(self.read_json(PATH, response)?).map_err(|e| {
+            CliError::Device(format!(
+                "{} returned an unexpected sync response: {e}",
+                self.url(PATH)
+            ))
+        })
+    }
+
+    fn screenshot(&mut self) -> Result<Vec<u8>, CliError> {
+        const PATH: &str = "/api/screenshot";
+        let response = self
+            .agent
+            .get(&self.url(PATH))
+            .call()
+            .map_err(|e| match e {
+                // 404 is the emulator's "not running --headless" answer, which
+                // deserves a hint rather than the bare body.
+                ureq::Error::Status(404, _) => CliError::Device(format!(
+                    "{} has no screenshot surface; start the emulator with --headless",
+                    self.url(PATH)
+                )),
+                other => self.map_error(PATH, other),
+            })?;
+        let mut png = Vec::new();
+        response.into_reader().read_to_end(&mut png).map_err(|e| {
+            CliError::Unreachable(format!("reading {} failed: {e}", self.url(PATH)))
+        })?;
+        Ok(png)
+    }
+
+    fn input(&mut self, intent: WireIntent) -> Result<(), CliError> {
+        // `WireIntent`'s derived JSON is variant-for-variant the same as
+        // `bhk_core::NavIntent`'s, which is what `/api/input` decodes.
+        const PATH: &str = "/api/input";
+        self.agent
+            .post(&self.url(PATH))
+            .send_json(intent)
+            .map_err(|e| self.map_error(PATH, e))?;
+        Ok(())
+    }
+}
@@ -1,10 +1,34 @@
-//! Pure bw-CLI JSON -> `push_protocol::Credential` mapping (M1 companion).
+//! Companion CLI library: everything `main.rs` runs, split so it can be
+//! unit-tested without a real `bw`, vault, or device.
 //!
-//! Deliberately separated from `main.rs`'s I/O (subprocess + HTTP) so the
-//! mapping logic can be unit-tested against inline fixture JSON without a
-//! real `bw` binary, a real vault, or a running device. See
+//! - [`cli`]: argument parsing into a [`cli::Command`]
+//! - [`commands`]: one function per subcommand, each producing an
+//!   [`output::Report`]
+//! - [`target`] / [`pairing`]: which device a command talks to
+//! - [`device`]: the transport-neutral [`device::Device`] trait, with
+//!   [`http`] (emulator HTTP API) and [`serial`] (device-link) behind it
+//! - [`bw`]: getting vault items out of the official Bitwarden CLI
+//! - [`export`] / [`export_crypto`]: reading Bitwarden export files,
+//!   including password-protected ones, without `bw`
+//! - [`error`]: [`error::CliError`] and its exit codes
+//!
+//! The bw-CLI JSON -> `push_protocol::Credential` mapping (M1) stays here at
+//! the crate root: pure, unit-tested against inline fixture JSON. See
 //! `.planning/decisions/2026-08-12-m1-companion-bw-cli-bridge.md`.
 
+pub mod bw;
+pub mod cli;
+pub mod commands;
+pub mod device;
+pub mod error;
+pub mod export;
+pub mod export_crypto;
+pub mod http;
+pub mod output;
+pub mod pairing;
+pub mod serial;
+pub mod target;
+
 use push_protocol::Credential;
 use serde_json::Value;
 use uuid::Uuid;
@@ -12,13 +36,13 @@ use uuid::Uuid;
 /// bw's `list items` schema marks logins as `type == 1` (2 = secure note,
 /// 3 = card, 4 = identity). Only logins carry the `login.*` fields this
 /// bridge maps.
-const BW_ITEM_TYPE_LOGIN: u64 = 1;
+pub(crate) const BW_ITEM_TYPE_LOGIN: u64 = 1;
 
 /// Maps the raw JSON text of `bw list items` output to the wire
 /// `Credential` type the device's `/api/sync` endpoint expects.
 ///
 /// Pure function: no subprocess, no network, no filesystem. Takes the JSON
-/// **text** (not a pre-parsed value) so callers — production `main.rs` and
+/// **text** (not a pre-parsed value) so callers — production `commands::push` and
 /// tests alike — have exactly one entry point.
 ///
 /// Non-login items (`type != 1`) are silently skipped (not an error: most
@@ -67,25 +91,36 @@ pub fn map_bw_items_to_credentials(bw_list_items_json: &str) -> Vec<Credential>
         .collect()
 }
 
-fn item_type(item: &Value) -> Option<u64> {
+pub(crate) fn item_type(item: &Value) -> Option<u64> {
     item.get("type").and_then(Value::as_u64)
 }
 
-fn map_one_item(item: &Value) -> Option<Credential> {
+/// An item's `id`, or `None` (with a stderr warning) if it isn't a UUID.
+/// Shared with `export`, whose items use the same schema.
+pub(crate) fn item_id(item: &Value) -> Option<Uuid> {
     let raw_id = item.get("id").and_then(Value::as_str).unwrap_or("");
-    let id = match Uuid::parse_str(raw_id) {
-        Ok(id) => id,
+    match Uuid::parse_str(raw_id) {
+        Ok(id) => Some(id),
         Err(e) => {
             eprintln!("warning: skipping bw item with unparsable id {raw_id:?}: {e}");
-            return None;
+            None
         }
-    };
+    }
+}
 
-    let name = item
-        .get("name")
+/// An item's `name`, `""` if missing.
+pub(crate) fn item_name(item: &Value) -> String {
+    item.get("name")
         .and_then(Value::as_str)
         .unwrap_or_default()
-        .to_string();
+        .to_string()
+}
+
+/// Maps one login item (`type == 1`); see [`map_bw_items_to_credentials`]
+/// for the field mapping. Also used by `export` for export-file logins.
+pub(crate) fn map_one_item(item: &Value) -> Option<Credential> {
+    let id = item_id(item)?;
+    let name = item_name(item);
 
     let login = item.get("login");
 
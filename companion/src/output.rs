@@ -0,0 +1,87 @@
+//! Shared output formatting: every subcommand's result is a [`Report`],
+//! printed either as human text or as one JSON object (`--json`), so
+//! scripts get the same shape regardless of transport.
+//!
+//! Results go to stdout; errors go to stderr (as `{"error": {"kind",
+//! "message"}}` in JSON mode), and the exit code carries the error class
+//! (see `crate::error`).
+
+use serde::Serialize;
+
+use crate::error::CliError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+/// A subcommand's result. The JSON form is the derived `Serialize`; the
+/// human form is whatever reads best in a terminal.
+pub trait Report: Serialize {
+    fn human(&self) -> String;
+}
+
+/// Renders `report` in `format`, without a trailing newline.
+#[must_use]
+pub fn render(format: OutputFormat, report: &impl Report) -> String {
+    match format {
+        OutputFormat::Human => report.human(),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(report).expect("reports always serialize")
+        }
+    }
+}
+
+/// Renders `error` in `format`, without a trailing newline.
+#[must_use]
+pub fn render_error(format: OutputFormat, error: &CliError) -> String {
+    match format {
+        OutputFormat::Human => format!("error: {error}"),
+        OutputFormat::Json => serde_json::json!({
+            "error": { "kind": error.kind(), "message": error.message() }
+        })
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Pushed {
+        pushed: usize,
+    }
+
+    impl Report for Pushed {
+        fn human(&self) -> String {
+            format!("Pushed {} credential(s)", self.pushed)
+        }
+    }
+
+    #[test]
+    fn renders_both_formats() {
+        let report = Pushed { pushed: 3 };
+        assert_eq!(
+            render(OutputFormat::Human, &report),
+            "Pushed 3 credential(s)"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render(OutputFormat::Json, &report)).unwrap();
+        assert_eq!(json, serde_json::json!({"pushed": 3}));
+    }
+
+    #[test]
+    fn json_errors_carry_kind_and_message() {
+        let error = CliError::Unreachable("no reply".into());
+        let json: serde_json::Value =
+            serde_json::from_str(&render_error(OutputFormat::Json, &error)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"error": {"kind": "unreachable", "message": "no reply"}})
+        );
+        assert_eq!(render_error(OutputFormat::Human, &error), "error: no reply");
+    }
+}
@@ -0,0 +1,178 @@
+//! `companion pair`: remembers one device as the default target, so later
+//! subcommands don't need `--device-url`/`--port` every time (see
+//! `crate::target` for where a pairing sits in the precedence order).
+//!
+//! This is host-side bookkeeping only. The device has no pairing concept
+//! on its side today (no bonding, no shared secret -- the serial and HTTP
+//! links are both local and unauthenticated), so "pairing" means: probe the
+//! device, and if it answers, write its target and identity to a small JSON
+//! file. BLE bonding is a separate, later piece of work (roadmap M4).
+//!
+//! The file lives at, first match wins:
+//!
+//! 1. `$COMPANION_CONFIG` (a file path -- tests and CI point this at a
+//!    temp dir)
+//! 2. `$XDG_CONFIG_HOME/bhk-companion/pairing.json`
+//! 3. `$HOME/.config/bhk-companion/pairing.json`
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CliError;
+use crate::target::Target;
+
+/// Contents of the pairing file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pairing {
+    pub target: Target,
+    /// The device's self-reported name at pairing time, if it reports one
+    /// (a device-link `Pong` does; the emulator's HTTP status doesn't).
+    pub name: Option<String>,
+    /// Firmware version at pairing time, same caveat as `name`.
+    pub fw_version: Option<String>,
+    /// Unix seconds.
+    pub paired_at: u64,
+}
+
+/// Resolves the pairing file path (see module docs). `None` if none of the
+/// variables are set.
+#[must_use]
+pub fn pairing_path(env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    if let Some(path) = env("COMPANION_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("bhk-companion").join("pairing.json"))
+}
+
+/// Reads the pairing file. A missing file is `Ok(None)` (never paired), not
+/// an error.
+///
+/// # Errors
+///
+/// [`CliError::Local`] if the file exists but can't be read or parsed.
+pub fn load(path: &Path) -> Result<Option<Pairing>, CliError> {
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map(Some).map_err(|e| {
+            CliError::Local(format!(
+                "pairing file {} is not valid ({e}); run `companion pair --forget` to reset it",
+                path.display()
+            ))
+        }),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(CliError::Local(format!(
+            "failed to read {}: {e}",
+            path.display()
+        ))),
+    }
+}
+
+/// Writes the pairing file, creating its directory if needed.
+///
+/// # Errors
+///
+/// [`CliError::Local`] on any filesystem error.
+pub fn save(path: &Path, pairing: &Pairing) -> Result<(), CliError> {
+    let local =
+        |e: std::io::Error| CliError::Local(format!("failed to write {}: {e}", path.display()));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(local)?;
+    }
+    let json = serde_json::to_string_pretty(pairing).expect("Pairing always serializes");
+    fs::write(path, json).map_err(local)
+}
+
+/// Deletes the pairing file. Returns whether there was one.
+///
+/// # Errors
+///
+/// [`CliError::Local`] if it exists but can't be removed.
+pub fn forget(path: &Path) -> Result<bool, CliError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(CliError::Local(format!(
+            "failed to remove {}: {e}",
+            path.display()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "companion-pairing-test-{}-{name}",
+                std::process::id()
+            ))
+            .join("pairing.json")
+    }
+
+    #[test]
+    fn explicit_config_path_wins() {
+        let env = |key: &str| match key {
+            "COMPANION_CONFIG" => Some("/tmp/x.json".to_string()),
+            "HOME" => Some("/home/me".to_string()),
+            _ => None,
+        };
+        assert_eq!(pairing_path(env), Some(PathBuf::from("/tmp/x.json")));
+    }
+
+    #[test]
+    fn falls_back_to_xdg_then_home() {
+        let xdg = |key: &str| (key == "XDG_CONFIG_HOME").then(|| "/cfg".to_string());
+        assert_eq!(
+            pairing_path(xdg),
+            Some(PathBuf::from("/cfg/bhk-companion/pairing.json"))
+        );
+
+        let home = |key: &str| (key == "HOME").then(|| "/home/me".to_string());
+        assert_eq!(
+            pairing_path(home),
+            Some(PathBuf::from("/home/me/.config/bhk-companion/pairing.json"))
+        );
+
+        assert_eq!(pairing_path(|_| None), None);
+    }
+
+    #[test]
+    fn save_load_forget_round_trip() {
+        let path = temp_path("round-trip");
+        let pairing = Pairing {
+            target: Target::Serial {
+                port: "/dev/ttyACM0".into(),
+            },
+            name: Some("T-Embed".into()),
+            fw_version: Some("0.1.0".into()),
+            paired_at: 1_700_000_000,
+        };
+
+        assert_eq!(load(&path).unwrap(), None);
+        save(&path, &pairing).unwrap();
+        assert_eq!(load(&path).unwrap(), Some(pairing));
+        assert!(forget(&path).unwrap());
+        assert!(!forget(&path).unwrap());
+        assert_eq!(load(&path).unwrap(), None);
+
+        let _ = fs::remove_dir(path.parent().unwrap());
+    }
+
+    #[test]
+    fn corrupt_file_is_a_local_error() {
+        let path = temp_path("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(load(&path), Err(CliError::Local(_))));
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,526 @@
+//! [`Device`] over device-link on a USB serial port (see the `device-link`
+//! crate docs for framing, message types and chunking).
+//!
+//! [`SerialLink`] is the request/reply engine and is generic over
+//! `Read + Write`, so the tests below drive it against an in-memory fake
+//! device instead of a real port. [`SerialDevice`] is the same engine over
+//! an opened `serialport` port.
+//!
+//! Exchanges, host side:
+//!
+//! - status: `Ping` -> `Pong` (CBOR `DeviceDescriptor`)
+//! - push: `SyncBegin`, `SyncChunk`..., `SyncEnd` -> `SyncAck` (CBOR
+//!   `SyncResponse`) or `SyncNack`
+//! - screenshot: `FramebufferRequest` -> `FramebufferData`... (header +
+//!   big-endian Rgb565, `MORE`-linked)
+//! - input: `InputInject` (CBOR `WireIntent`), no reply
+//!
+//! While waiting for a reply, `Log` frames are echoed to stderr and any
+//! other frame (or undecodable bytes, e.g. ROM boot chatter on the same
+//! CDC port) is skipped. No reply within the timeout is
+//! [`CliError::Unreachable`].
+
+use std::io::{ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+use device_link::{
+    encode_chunks, encode_frame, from_cbor, to_cbor, Decoder, DeviceDescriptor, Frame,
+    FramebufferHeader, MessageType, PixelFormat, Reassembler, SyncBegin, SyncEnd, SyncNack,
+    WireIntent,
+};
+use push_protocol::{SyncRequest, SyncResponse};
+
+use crate::device::{Device, DeviceStatus};
+use crate::error::CliError;
+
+/// Payload bytes per `SyncChunk` frame. Well under device-link's
+/// `MAX_PAYLOAD_LEN`, so the device never has to buffer more than ~1 KB of
+/// one frame before it can CRC-check it.
+pub const SYNC_CHUNK_LEN: usize = 1024;
+
+/// device-link over USB-Serial-JTAG ignores the baud rate, but a real
+/// USB-UART bridge doesn't; 115200 matches the firmware's console.
+const BAUD_RATE: u32 = 115_200;
+
+/// Per-`read` timeout on the real port: short, so the overall deadline in
+/// [`SerialLink`] is honoured closely.
+const READ_POLL: Duration = Duration::from_millis(50);
+
+const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+pub struct SerialLink<P> {
+    port: P,
+    decoder: Decoder,
+    timeout: Duration,
+    name: String,
+}
+
+impl<P: Read + Write> SerialLink<P> {
+    /// `name` is only used in error messages.
+    pub fn new(port: P, name: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            port,
+            decoder: Decoder::new(),
+            timeout,
+            name: name.into(),
+        }
+    }
+
+    fn send(&mut self, msg_type: MessageType, payload: &[u8]) -> Result<(), CliError> {
+        let frame = encode_frame(msg_type, 0, payload).map_err(|e| {
+            CliError::Local(format!("failed to encode a {msg_type:?} frame: {e:?}"))
+        })?;
+        self.write_all(&frame)
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), CliError> {
+        self.port
+            .write_all(bytes)
+            .and_then(|()| self.port.flush())
+            .map_err(|e| CliError::Unreachable(format!("writing to {} failed: {e}", self.name)))
+    }
+
+    /// Next frame of one of `wanted` types, skipping everything else.
+    fn expect(&mut self, wanted: &[MessageType]) -> Result<Frame, CliError> {
+        let deadline = Instant::now() + self.timeout;
+        let mut buf = [0u8; 512];
+        loop {
+            while let Some(decoded) = self.decoder.poll() {
+                let Ok(frame) = decoded else { continue };
+                if wanted.contains(&frame.msg_type) {
+                    return Ok(frame);
+                }
+                if frame.msg_type == MessageType::Log {
+                    eprintln!(
+                        "device: {}",
+                        String::from_utf8_lossy(&frame.payload).trim_end()
+                    );
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(CliError::Unreachable(format!(
+                    "no {wanted:?} reply from {} within {:?}; is the device running device-link firmware?",
+                    self.name, self.timeout
+                )));
+            }
+            match self.port.read(&mut buf) {
+                Ok(n) => self.decoder.feed(&buf[..n]),
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        ErrorKind::TimedOut | ErrorKind::WouldBlock | ErrorKind::Interrupted
+                    ) => {}
+                Err(e) => {
+                    return Err(CliError::Unreachable(format!(
+                        "reading from {} failed: {e}",
+                        self.name
+                    )))
+                }
+            }
+        }
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, frame: &Frame) -> Result<T, CliError> {
+        from_cbor(&frame.payload).map_err(|e| {
+            CliError::Device(format!(
+                "{} sent a malformed {:?}: {e:?}",
+                self.name, frame.msg_type
+            ))
+        })
+    }
+
+    /// `Ping` -> `Pong`.
+    ///
+    /// # Errors
+    ///
+    /// [`CliError::Unreachable`] on I/O failure or timeout,
+    /// [`CliError::Device`] on a malformed reply.
+    pub fn ping(&mut self) -> Result<DeviceDescriptor, CliError> {
+        self.send(MessageType::Ping, &[])?;
+        let pong = self.expect(&[MessageType::Pong])?;
+        self.decode(&pong)
+    }
+
+    /// Full sync exchange; see module docs.
+    ///
+    /// # Errors
+    ///
+    /// As [`SerialLink::ping`]; a `SyncNack` is [`CliError::Device`].
+    pub fn sync(&mut self, request: &SyncRequest) -> Result<SyncResponse, CliError> {
+        let blob = to_cbor(request).map_err(|e| {
+            CliError::Local(format!("failed to CBOR-encode the sync request: {e:?}"))
+        })?;
+        let begin = SyncBegin {
+            total_bytes: u32::try_from(blob.len())
+                .map_err(|_| CliError::Local("sync request is larger than 4 GiB".to_string()))?,
+            item_count: u32::try_from(request.credentials.len()).unwrap_or(u32::MAX),
+        };
+        let end = SyncEnd {
+            crc32_of_whole_blob: CRC.checksum(&blob),
+        };
+
+        self.send(
+            MessageType::SyncBegin,
+            &to_cbor(&begin).expect("SyncBegin always encodes"),
+        )?;
+        for chunk in encode_chunks(MessageType::SyncChunk, &blob, SYNC_CHUNK_LEN)
+            .map_err(|e| CliError::Local(format!("failed to chunk the sync request: {e:?}")))?
+        {
+            self.write_all(&chunk)?;
+        }
+        self.send(
+            MessageType::SyncEnd,
+            &to_cbor(&end).expect("SyncEnd always encodes"),
+        )?;
+
+        let reply = self.expect(&[MessageType::SyncAck, MessageType::SyncNack])?;
+        if reply.msg_type == MessageType::SyncNack {
+            let nack: SyncNack = self.decode(&reply)?;
+            return Err(CliError::Device(format!(
+                "{} rejected the sync (code {}): {}",
+                self.name, nack.code, nack.message
+            )));
+        }
+        self.decode(&reply)
+    }
+
+    /// `FramebufferRequest` -> reassembled `FramebufferData`, PNG-encoded.
+    ///
+    /// # Errors
+    ///
+    /// As [`SerialLink::ping`]; a malformed header or short pixel stream
+    /// is [`CliError::Device`].
+    pub fn screenshot(&mut self) -> Result<Vec<u8>, CliError> {
+        self.send(MessageType::FramebufferRequest, &[])?;
+        let mut reassembler = Reassembler::new();
+        while !reassembler.is_done() {
+            let frame = self.expect(&[MessageType::FramebufferData])?;
+            reassembler.push(&frame.payload, frame.more());
+        }
+        let data = reassembler.finish().unwrap_or_default();
+        let (header, pixels) = FramebufferHeader::decode(&data).map_err(|e| {
+            CliError::Device(format!(
+                "{} sent a malformed framebuffer header: {e:?}",
+                self.name
+            ))
+        })?;
+        rgb565_be_to_png(header, pixels)
+            .ok_or_else(|| CliError::Device(format!("{} sent a truncated framebuffer", self.name)))
+    }
+
+    /// `InputInject`; fire-and-forget.
+    ///
+    /// # Errors
+    ///
+    /// [`CliError::Unreachable`] if the write fails.
+    pub fn input(&mut self, intent: WireIntent) -> Result<(), CliError> {
+        self.send(
+            MessageType::InputInject,
+            &to_cbor(&intent).expect("WireIntent always encodes"),
+        )
+    }
+}
+
+/// PNG-encodes a big-endian Rgb565 pixel stream with the same channel
+/// expansion as the emulator's `HeadlessSurface::encode_png` (left-shift,
+/// not bit-replicate), so a serial screenshot and an HTTP one of the same
+/// screen are byte-comparable. `None` if `pixels` is shorter than the
+/// header says.
+#[must_use]
+pub fn rgb565_be_to_png(header: FramebufferHeader, pixels: &[u8]) -> Option<Vec<u8>> {
+    let PixelFormat::Rgb565 = header.format;
+    let (width, height) = (u32::from(header.width), u32::from(header.height));
+    let needed = width as usize * height as usize * 2;
+    let pixels = pixels.get(..needed)?;
+
+    let mut image = image::RgbImage::new(width, height);
+    for (index, pair) in pixels.chunks_exact(2).enumerate() {
+        let raw = u16::from_be_bytes([pair[0], pair[1]]);
+        let (r, g, b) = (
+            (raw >> 11) as u8,
+            ((raw >> 5) & 0x3f) as u8,
+            (raw & 0x1f) as u8,
+        );
+        let index = index as u32;
+        image.put_pixel(
+            index % width,
+            index / width,
+            image::Rgb([r << 3, g << 2, b << 3]),
+        );
+    }
+
+    let mut buffer = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut buffer),
+            image::ImageFormat::Png,
+        )
+        .expect("in-memory PNG encode should never fail");
+    Some(buffer)
+}
+
+/// [`SerialLink`] over a real port, as a [`Device`].
+pub struct SerialDevice(SerialLink<Box<dyn serialport::SerialPort>>);
+
+impl SerialDevice {
+    /// Opens `path`.
+    ///
+    /// # Errors
+    ///
+    /// [`CliError::Unreachable`] if the port can't be opened (missing,
+    /// busy, permissions).
+    pub fn open(path: &str, timeout: Duration) -> Result<Self, CliError> {
+        let mut port = serialport::new(path, BAUD_RATE)
+            .timeout(READ_POLL)
+            .open()
+            .map_err(|e| {
+                CliError::Unreachable(format!("failed to open serial port {path}: {e}"))
+            })?;
+        // The ESP32-S3's USB-Serial-JTAG wires DTR/RTS to EN/IO0: leaving
+        // them asserted on open resets (or bootloader-strands) the board.
+        let _ = port.write_data_terminal_ready(false);
+        let _ = port.write_request_to_send(false);
+        Ok(Self(SerialLink::new(port, path, timeout)))
+    }
+}
+
+impl Device for SerialDevice {
+    fn status(&mut self) -> Result<DeviceStatus, CliError> {
+        let descriptor = self.0.ping()?;
+        Ok(DeviceStatus {
+            name: Some(descriptor.name),
+            fw_version: Some(descriptor.fw_version),
+            profile: None,
+            credential_count: None,
+            panel: Some((descriptor.panel_w, descriptor.panel_h)),
+        })
+    }
+
+    fn push(&mut self, request: &SyncRequest) -> Result<SyncResponse, CliError> {
+        self.0.sync(request)
+    }
+
+    fn screenshot(&mut self) -> Result<Vec<u8>, CliError> {
+        self.0.screenshot()
+    }
+
+    fn input(&mut self, intent: WireIntent) -> Result<(), CliError> {
+        self.0.input(intent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use push_protocol::Credential;
+    use std::collections::VecDeque;
+    use uuid::Uuid;
+
+    /// Enough of the firmware side of device-link to answer the host:
+    /// decodes what the link writes, and queues canned replies to read.
+    struct FakeDevice {
+        decoder: Decoder,
+        outbox: VecDeque<u8>,
+        sync_blob: Reassembler,
+        nack: bool,
+        received: Vec<MessageType>,
+    }
+
+    impl FakeDevice {
+        fn new() -> Self {
+            Self {
+                decoder: Decoder::new(),
+                outbox: VecDeque::new(),
+                sync_blob: Reassembler::new(),
+                nack: false,
+                received: Vec::new(),
+            }
+        }
+
+        fn reply(&mut self, msg_type: MessageType, payload: &[u8]) {
+            self.outbox
+                .extend(encode_frame(msg_type, 0, payload).unwrap());
+        }
+
+        fn handle(&mut self, frame: Frame) {
+            self.received.push(frame.msg_type);
+            match frame.msg_type {
+                MessageType::Ping => {
+                    self.reply(MessageType::Log, b"pinged");
+                    let pong = DeviceDescriptor {
+                        name: "T-Embed".into(),
+                        fw_version: "0.1.0".into(),
+                        panel_w: 320,
+                        panel_h: 170,
+                    };
+                    self.reply(MessageType::Pong, &to_cbor(&pong).unwrap());
+                }
+                MessageType::SyncChunk => self.sync_blob.push(&frame.payload, frame.more()),
+                MessageType::SyncEnd => {
+                    let end: SyncEnd = from_cbor(&frame.payload).unwrap();
+                    let blob = std::mem::take(&mut self.sync_blob).finish().unwrap();
+                    assert_eq!(end.crc32_of_whole_blob, CRC.checksum(&blob));
+                    if self.nack {
+                        let nack = SyncNack {
+                            code: 7,
+                            message: "storage full".into(),
+                        };
+                        self.reply(MessageType::SyncNack, &to_cbor(&nack).unwrap());
+                    } else {
+                        let request: SyncRequest = from_cbor(&blob).unwrap();
+                        let ack = SyncResponse {
+                            status: "success".into(),
+                            synced: request.credentials.len(),
+                            total_bytes: blob.len(),
+                        };
+                        self.reply(MessageType::SyncAck, &to_cbor(&ack).unwrap());
+                    }
+                }
+                MessageType::FramebufferRequest => {
+                    let header = FramebufferHeader {
+                        width: 2,
+                        height: 1,
+                        format: PixelFormat::Rgb565,
+                    };
+                    let mut data = header.encode().to_vec();
+                    data.extend_from_slice(&[0xf8, 0x00, 0x00, 0x1f]); // red, blue
+                    for frame in encode_chunks(MessageType::FramebufferData, &data, 3).unwrap() {
+                        self.outbox.extend(frame);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    impl Read for FakeDevice {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.outbox.is_empty() {
+                return Err(ErrorKind::TimedOut.into());
+            }
+            let n = buf.len().min(self.outbox.len());
+            for (slot, byte) in buf.iter_mut().zip(self.outbox.drain(..n)) {
+                *slot = byte;
+            }
+            Ok(n)
+        }
+    }
+
+    impl Write for FakeDevice {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.decoder.feed(buf);
+            while let Some(frame) = self.decoder.poll() {
+                self.handle(frame.unwrap());
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn link(device: FakeDevice) -> SerialLink<FakeDevice> {
+        SerialLink::new(device, "fake", Duration::from_millis(200))
+    }
+
+    fn request(count: usize) -> SyncRequest {
+        SyncRequest {
+            credentials: (0..count)
+                .map(|i| Credential {
+                    id: Uuid::new_v4(),
+                    name: format!("item {i}"),
+                    username: "user".into(),
+                    password: "x".repeat(100),
+                    uri: None,
+                    notes: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn ping_skips_boot_garbage_and_log_frames() {
+        let mut device = FakeDevice::new();
+        device.outbox.extend(b"ESP-ROM:esp32s3-20210327\r\n");
+        let mut link = link(device);
+
+        let descriptor = link.ping().unwrap();
+        assert_eq!(descriptor.name, "T-Embed");
+        assert_eq!((descriptor.panel_w, descriptor.panel_h), (320, 170));
+    }
+
+    #[test]
+    fn push_chunks_the_blob_and_returns_the_ack() {
+        let mut link = link(FakeDevice::new());
+
+        let response = link.sync(&request(40)).unwrap();
+        assert_eq!(response.synced, 40);
+        assert!(
+            response.total_bytes > SYNC_CHUNK_LEN,
+            "test should exercise chunking"
+        );
+
+        let received = &link.port.received;
+        assert_eq!(received.first(), Some(&MessageType::SyncBegin));
+        assert_eq!(received.last(), Some(&MessageType::SyncEnd));
+        assert!(
+            received
+                .iter()
+                .filter(|t| **t == MessageType::SyncChunk)
+                .count()
+                > 1
+        );
+    }
+
+    #[test]
+    fn nack_is_a_device_error() {
+        let mut device = FakeDevice::new();
+        device.nack = true;
+        let mut link = link(device);
+
+        let error = link.sync(&request(1)).unwrap_err();
+        assert!(matches!(&error, CliError::Device(message) if message.contains("storage full")));
+    }
+
+    #[test]
+    fn screenshot_reassembles_and_encodes_png() {
+        let mut link = link(FakeDevice::new());
+
+        let png = link.screenshot().unwrap();
+        let image = image::load_from_memory(&png).unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0).0, [0xf8, 0, 0]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0xf8]);
+    }
+
+    #[test]
+    fn input_sends_one_frame_and_expects_no_reply() {
+        let mut link = link(FakeDevice::new());
+
+        link.input(WireIntent::NextN(5)).unwrap();
+        assert_eq!(link.port.received, vec![MessageType::InputInject]);
+    }
+
+    #[test]
+    fn silence_is_unreachable() {
+        struct Silent;
+        impl Read for Silent {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(ErrorKind::TimedOut.into())
+            }
+        }
+        impl Write for Silent {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut link = SerialLink::new(Silent, "silent", Duration::from_millis(20));
+        assert!(matches!(link.ping(), Err(CliError::Unreachable(_))));
+    }
+}
@@ -0,0 +1,195 @@
+//! Which device a subcommand talks to, and how that's decided.
+//!
+//! Every subcommand targets exactly one device over one of two transports:
+//!
+//! - [`Target::Http`]: the desktop emulator's HTTP API
+//!   (`emulator/src/desktop/http_server.rs`), CBOR push + JSON status.
+//! - [`Target::Serial`]: a device-link USB serial port (the T-Embed's
+//!   USB-Serial-JTAG CDC), see `crate::serial`.
+//!
+//! Precedence, highest first (see [`resolve_target`]):
+//!
+//! 1. `--device-url <url>` / `--port <path>` on the command line
+//! 2. `DEVICE_URL` / `DEVICE_PORT` environment variables
+//! 3. the paired device, if `companion pair` was run (see `crate::pairing`)
+//! 4. the emulator's default address, [`DEFAULT_DEVICE_BASE_URL`]
+//!
+//! Within one level, naming both an HTTP and a serial target is a usage
+//! error rather than one silently winning.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CliError;
+
+/// The desktop emulator's fixed HTTP address (`emulator/src/main.rs`).
+pub const DEFAULT_DEVICE_BASE_URL: &str = "http://127.0.0.1:8080";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "transport", rename_all = "snake_case")]
+pub enum Target {
+    Http { base_url: String },
+    Serial { port: String },
+}
+
+impl Target {
+    /// Human-readable address, e.g. `http://127.0.0.1:8080` or
+    /// `serial:/dev/ttyACM0`.
+    #[must_use]
+    pub fn address(&self) -> String {
+        match self {
+            Target::Http { base_url } => base_url.trim_end_matches('/').to_string(),
+            Target::Serial { port } => format!("serial:{port}"),
+        }
+    }
+}
+
+/// Where the resolved target came from -- reported by `status`/`devices`
+/// so "why is it talking to *that*?" has an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetSource {
+    Flag,
+    Environment,
+    Paired,
+    Default,
+}
+
+/// The `--device-url`/`--port` flags, exactly as given.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TargetFlags {
+    pub device_url: Option<String>,
+    pub port: Option<String>,
+}
+
+fn from_pair(
+    url: Option<String>,
+    port: Option<String>,
+    what: &str,
+) -> Result<Option<Target>, CliError> {
+    match (url, port) {
+        (Some(_), Some(_)) => Err(CliError::Usage(format!(
+            "{what} name both an HTTP device and a serial port; pick one"
+        ))),
+        (Some(base_url), None) => Ok(Some(Target::Http { base_url })),
+        (None, Some(port)) => Ok(Some(Target::Serial { port })),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Applies the precedence in the module docs. `env` is injected (rather
+/// than read from `std::env` here) so this stays a pure function under
+/// test.
+///
+/// # Errors
+///
+/// [`CliError::Usage`] if one level names both an HTTP and a serial
+/// target.
+pub fn resolve_target(
+    flags: &TargetFlags,
+    env: impl Fn(&str) -> Option<String>,
+    paired: Option<&Target>,
+) -> Result<(Target, TargetSource), CliError> {
+    if let Some(target) = from_pair(
+        flags.device_url.clone(),
+        flags.port.clone(),
+        "--device-url and --port",
+    )? {
+        return Ok((target, TargetSource::Flag));
+    }
+    if let Some(target) = from_pair(
+        env("DEVICE_URL"),
+        env("DEVICE_PORT"),
+        "DEVICE_URL and DEVICE_PORT",
+    )? {
+        return Ok((target, TargetSource::Environment));
+    }
+    if let Some(target) = paired {
+        return Ok((target.clone(), TargetSource::Paired));
+    }
+    Ok((
+        Target::Http {
+            base_url: DEFAULT_DEVICE_BASE_URL.to_string(),
+        },
+        TargetSource::Default,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn defaults_to_the_emulator() {
+        let (target, source) = resolve_target(&TargetFlags::default(), no_env, None).unwrap();
+        assert_eq!(
+            target,
+            Target::Http {
+                base_url: DEFAULT_DEVICE_BASE_URL.to_string()
+            }
+        );
+        assert_eq!(source, TargetSource::Default);
+    }
+
+    #[test]
+    fn flag_beats_environment_beats_pairing() {
+        let paired = Target::Serial {
+            port: "/dev/paired".into(),
+        };
+        let env = |key: &str| (key == "DEVICE_PORT").then(|| "/dev/from-env".to_string());
+
+        let (target, source) = resolve_target(&TargetFlags::default(), env, Some(&paired)).unwrap();
+        assert_eq!(
+            target,
+            Target::Serial {
+                port: "/dev/from-env".into()
+            }
+        );
+        assert_eq!(source, TargetSource::Environment);
+
+        let flags = TargetFlags {
+            device_url: Some("http://10.0.0.2:8080".into()),
+            port: None,
+        };
+        let (target, source) = resolve_target(&flags, env, Some(&paired)).unwrap();
+        assert_eq!(
+            target,
+            Target::Http {
+                base_url: "http://10.0.0.2:8080".into()
+            }
+        );
+        assert_eq!(source, TargetSource::Flag);
+
+        let (target, source) =
+            resolve_target(&TargetFlags::default(), no_env, Some(&paired)).unwrap();
+        assert_eq!(target, paired);
+        assert_eq!(source, TargetSource::Paired);
+    }
+
+    #[test]
+    fn both_transports_at_one_level_is_a_usage_error() {
+        let flags = TargetFlags {
+            device_url: Some("http://x".into()),
+            port: Some("/dev/y".into()),
+        };
+        assert!(matches!(
+            resolve_target(&flags, no_env, None),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn target_serializes_with_a_transport_tag() {
+        let json = serde_json::to_value(Target::Serial {
+            port: "/dev/ttyACM0".into(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"transport": "serial", "port": "/dev/ttyACM0"})
+        );
+    }
+}
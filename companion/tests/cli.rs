@@ -0,0 +1,351 @@
+//! End-to-end tests of the `companion` binary against a stub HTTP device: a
+//! std `TcpListener` that answers the emulator's `/api/*` routes with canned
+//! responses and records what it was sent. Covers the wiring `main.rs` and
+//! `commands.rs` add on top of the unit-tested modules: exit codes, `--json`
+//! output on stdout/stderr, and the CBOR body `push` actually sends.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use push_protocol::SyncRequest;
+use serde_json::Value;
+
+type Requests = Arc<Mutex<Vec<(String, String, Vec<u8>)>>>;
+
+/// Starts the stub; returns its base URL and the `(method, path, body)` of
+/// every request it has served.
+fn stub_device() -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests: Requests = Arc::default();
+    let recorded = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut parts = request_line.split_whitespace();
+            let (method, path) = (
+                parts.next().unwrap_or("").to_string(),
+                parts.next().unwrap_or("").to_string(),
+            );
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let (status, response) = match (method.as_str(), path.as_str()) {
+                ("GET", "/api/status") => (
+                    "200 OK",
+                    r#"{"status":"running","credential_count":7,"device":{"name":"Strip key","profile":"ssd1306","panel":[128,32],"port":0,"data_dir":"data/strip"}}"#.to_string(),
+                ),
+                ("POST", "/api/sync") => {
+                    let request: SyncRequest = ciborium::from_reader(body.as_slice()).unwrap();
+                    let synced = request.credentials.len();
+                    (
+                        "200 OK",
+                        format!(
+                            r#"{{"status":"success","synced":{synced},"total_bytes":{content_length}}}"#
+                        ),
+                    )
+                }
+                ("POST", "/api/input") => ("200 OK", r#"{"status":"success"}"#.to_string()),
+                _ => ("404 Not Found", "Not Found".to_string()),
+            };
+            recorded.lock().unwrap().push((method, path, body));
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            );
+        }
+    });
+
+    (base_url, requests)
+}
+
+/// Runs the binary with a clean environment: no inherited `DEVICE_URL`,
+/// and a pairing file path that doesn't exist.
+fn companion(args: &[&str]) -> Output {
+    let config = std::env::temp_dir().join(format!(
+        "companion-cli-test-{}-none.json",
+        std::process::id()
+    ));
+    companion_with_config(args, &config)
+}
+
+/// `companion`, with `config` as the pairing file.
+fn companion_with_config(args: &[&str], config: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_companion"))
+        .args(args)
+        .env_remove("DEVICE_URL")
+        .env_remove("DEVICE_PORT")
+        .env("COMPANION_CONFIG", config)
+        .output()
+        .unwrap()
+}
+
+fn stdout_json(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|e| {
+        panic!(
+            "stdout is not JSON ({e}): {}",
+            String::from_utf8_lossy(&output.stdout)
+        )
+    })
+}
+
+#[test]
+fn status_json_reports_the_credential_count() {
+    let (base_url, _) = stub_device();
+
+    let output = companion(&["status", "--json", "--device-url", &base_url]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json = stdout_json(&output);
+    assert_eq!(json["credential_count"], 7);
+    assert_eq!(json["source"], "flag");
+    assert_eq!(json["target"]["transport"], "http");
+}
+
+#[test]
+fn status_names_the_emulator_instance_and_its_profile() {
+    let (base_url, _) = stub_device();
+
+    let output = companion(&["status", "--device-url", &base_url]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Name:        Strip key"), "{stdout}");
+    assert!(stdout.contains("Profile:     ssd1306"), "{stdout}");
+    assert!(stdout.contains("Panel:       128x32"), "{stdout}");
+}
+
+#[test]
+fn unreachable_device_exits_3_with_a_json_error() {
+    // Bind then drop, so nothing is listening on the port.
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let output = companion(&[
+        "--json",
+        "status",
+        "--device-url",
+        &format!("http://127.0.0.1:{port}"),
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let error: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "unreachable");
+}
+
+#[test]
+fn usage_errors_exit_2_without_contacting_anything() {
+    assert_eq!(companion(&[]).status.code(), Some(2));
+    assert_eq!(companion(&["frobnicate"]).status.code(), Some(2));
+    assert_eq!(companion(&["input", "sideways"]).status.code(), Some(2));
+    assert_eq!(
+        companion(&["status", "--device-url", "http://x", "--port", "/dev/y"])
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn help_exits_0() {
+    let output = companion(&["--help"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Commands:"));
+}
+
+#[test]
+fn push_from_file_sends_cbor_and_honours_id_filter() {
+    let (base_url, requests) = stub_device();
+    let items = r#"[
+        {"id": "11111111-1111-4111-8111-111111111111", "type": 1, "name": "A", "login": {"username": "a", "password": "pa"}},
+        {"id": "22222222-2222-4222-8222-222222222222", "type": 1, "name": "B", "login": {"username": "b", "password": "pb"}}
+    ]"#;
+    let file: PathBuf = std::env::temp_dir().join(format!(
+        "companion-cli-test-{}-items.json",
+        std::process::id()
+    ));
+    std::fs::write(&file, items).unwrap();
+
+    let output = companion(&[
+        "push",
+        "--json",
+        "--device-url",
+        &base_url,
+        "--from-file",
+        file.to_str().unwrap(),
+        "--id",
+        "22222222-2222-4222-8222-222222222222",
+    ]);
+    let _ = std::fs::remove_file(&file);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(stdout_json(&output)["pushed"], 1);
+
+    let requests = requests.lock().unwrap();
+    let (_, path, body) = requests
+        .iter()
+        .find(|(method, _, _)| method == "POST")
+        .unwrap();
+    assert_eq!(path, "/api/sync");
+    let sent: SyncRequest = ciborium::from_reader(body.as_slice()).unwrap();
+    assert_eq!(sent.credentials.len(), 1);
+    assert_eq!(sent.credentials[0].name, "B");
+}
+
+#[test]
+fn dry_run_sends_nothing() {
+    let (base_url, requests) = stub_device();
+    let file = std::env::temp_dir().join(format!(
+        "companion-cli-test-{}-dry.json",
+        std::process::id()
+    ));
+    std::fs::write(&file, "[]").unwrap();
+
+    let output = companion(&[
+        "push",
+        "--dry-run",
+        "--device-url",
+        &base_url,
+        "--from-file",
+        file.to_str().unwrap(),
+    ]);
+    let _ = std::fs::remove_file(&file);
+    assert!(output.status.success());
+    assert!(requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn input_posts_each_intent_as_nav_intent_json() {
+    let (base_url, requests) = stub_device();
+
+    let output = companion(&[
+        "input",
+        "next",
+        "next:3",
+        "prev:2",
+        "end",
+        "char:G",
+        "activate",
+        "--device-url",
+        &base_url,
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let bodies: Vec<Value> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, _, body)| serde_json::from_slice(body).unwrap())
+        .collect();
+    assert_eq!(
+        bodies,
+        vec![
+            Value::from("Next"),
+            serde_json::json!({"NextN": 3}),
+            serde_json::json!({"PrevN": 2}),
+            Value::from("End"),
+            serde_json::json!({"Char": "G"}),
+            Value::from("Activate")
+        ]
+    );
+}
+
+#[test]
+fn missing_screenshot_surface_is_a_device_error() {
+    let (base_url, _) = stub_device();
+    let output = companion(&[
+        "screenshot",
+        "--device-url",
+        &base_url,
+        "-o",
+        "/nonexistent/never-written.png",
+    ]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[cfg(unix)]
+#[test]
+fn push_runs_bw_with_filters() {
+    let (base_url, requests) = stub_device();
+    let fake_bw = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/fake-bw-bin");
+    let path = format!("{fake_bw}:{}", std::env::var("PATH").unwrap_or_default());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_companion"))
+        .args(["push", "--search", "git", "--device-url", &base_url])
+        .env("PATH", path)
+        .env(
+            "COMPANION_CONFIG",
+            std::env::temp_dir().join("companion-cli-test-unused.json"),
+        )
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Pushed "));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn pair_forget_resets_a_corrupt_pairing_file_other_commands_reject() {
+    let config = std::env::temp_dir().join(format!(
+        "companion-cli-test-{}-corrupt.json",
+        std::process::id()
+    ));
+    std::fs::write(&config, "not json {").unwrap();
+
+    let status = companion_with_config(&["status"], &config);
+    assert_eq!(status.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&status.stderr).contains("pair --forget"));
+
+    let forget = companion_with_config(&["pair", "--forget"], &config);
+    assert!(
+        forget.status.success(),
+        "{}",
+        String::from_utf8_lossy(&forget.stderr)
+    );
+    assert!(!config.exists());
+}
@@ -0,0 +1,114 @@
+//! Known-answer tests against the export fixtures in
+//! `tests/fixtures/exports/` (generated independently of this crate by
+//! `generate.py` there): all three files hold the same vault, so they must
+//! all map to the same credentials.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use companion::error::CliError;
+use companion::export::load_credentials;
+use push_protocol::Credential;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/exports")
+        .join(name)
+}
+
+fn load(name: &str, password: &str) -> Result<Vec<Credential>, CliError> {
+    let json = std::fs::read_to_string(fixture(name)).unwrap();
+    load_credentials(&json, || Ok(password.to_string()))
+}
+
+#[test]
+fn unencrypted_export_maps_every_supported_item_type() {
+    let credentials = load("unencrypted.json", "").unwrap();
+    let names: Vec<&str> = credentials.iter().map(|c| c.name.as_str()).collect();
+    // The SSH key item is skipped.
+    assert_eq!(names, ["GitHub", "Wi-Fi", "Visa", "Me"]);
+
+    let github = &credentials[0];
+    assert_eq!(github.username, "octocat@example.com");
+    assert_eq!(github.password, "hunter2");
+    assert_eq!(github.uri.as_deref(), Some("https://github.com"));
+
+    assert_eq!(
+        credentials[1].notes.as_deref(),
+        Some("SSID: home\nPSK: swordfish")
+    );
+    assert_eq!(credentials[2].password, "4111111111111111");
+    assert_eq!(
+        credentials[2].notes.as_deref(),
+        Some("Brand: Visa\nExpires: 04/2030")
+    );
+    assert_eq!(credentials[3].username, "jdoe");
+}
+
+#[test]
+fn pbkdf2_export_decrypts_to_the_same_vault() {
+    assert_eq!(
+        load("encrypted-pbkdf2.json", PASSWORD).unwrap(),
+        load("unencrypted.json", "").unwrap()
+    );
+}
+
+#[test]
+fn argon2id_export_decrypts_to_the_same_vault() {
+    assert_eq!(
+        load("encrypted-argon2id.json", PASSWORD).unwrap(),
+        load("unencrypted.json", "").unwrap()
+    );
+}
+
+#[test]
+fn wrong_password_is_reported_as_such() {
+    let error = load("encrypted-pbkdf2.json", "not the password").unwrap_err();
+    assert!(matches!(&error, CliError::Source(message) if message.contains("wrong password")));
+}
+
+fn companion_dry_run(name: &str, password: Option<&str>) -> std::process::Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_companion"));
+    command
+        .args(["push", "--dry-run", "--json", "--from-file"])
+        .arg(fixture(name))
+        .env_remove("BW_EXPORT_PASSWORD")
+        .env(
+            "COMPANION_CONFIG",
+            std::env::temp_dir().join("companion-export-test-unused.json"),
+        );
+    if let Some(password) = password {
+        command.env("BW_EXPORT_PASSWORD", password);
+    }
+    command.output().unwrap()
+}
+
+#[test]
+fn cli_reads_the_password_from_the_environment() {
+    let output = companion_dry_run("encrypted-argon2id.json", Some(PASSWORD));
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["pushed"], 4);
+}
+
+#[test]
+fn cli_exit_codes_for_missing_and_wrong_passwords() {
+    assert_eq!(
+        companion_dry_run("encrypted-pbkdf2.json", None)
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        companion_dry_run("encrypted-pbkdf2.json", Some("nope"))
+            .status
+            .code(),
+        Some(5)
+    );
+}
@@ -7,7 +7,7 @@
 //! Run with: `cargo run -p bhk-core --example render_scene`
 //! Writes `render_scene.png` to the current directory.
 
-use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Screen, VerticalList};
+use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Screen, Theme, VerticalList};
 
 fn main() {
     let items = vec![
@@ -26,7 +26,7 @@ fn main() {
     navigator.dispatch(bhk_core::NavIntent::Next);
 
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator.render(&Theme::DARK, &mut framebuffer).expect("core DrawTarget is Infallible");
 
     let path = "render_scene.png";
     dump_png(&framebuffer, path);
@@ -9,16 +9,32 @@
 //! `Navigator` — so a landing sync can never destroy a pushed screen (once
 //! one exists; see the ADR referenced below) or pop the user out mid-read.
 //!
+//! [`Settings`] follow the same pattern: App-owned, shared as
+//! `Rc<RefCell<_>>` with the settings screen (which writes) and the root
+//! list (which reads), and applied live — `App` itself reads brightness
+//! and the idle timeout from it every frame. Loading and saving go through
+//! the platform's `Storage`, driven by the run loop
+//! ([`App::restore_settings`]/[`App::persist_settings`]).
+//!
 //! See:
 //! `.planning/decisions/2026-08-12-m1-vault-store-data-ownership.md`.
 
 use std::cell::RefCell;
+use std::fmt::Debug;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use embedded_graphics::primitives::Rectangle;
 
 use crate::credential_detail_view::CredentialDetailView;
 use crate::credential_list_view::CredentialListView;
 use crate::input::NavIntent;
-use crate::render::{Action, FrameBuffer565, Navigator, Screen};
+use crate::platform::Storage;
+use crate::render::{
+    Action, ActionRow, ChromeContribution, Dialog, EnumPicker, Form, FormRow, FrameBuffer565, Navigator, Screen,
+    SemanticNode, Stepper, Toast, Toggle,
+};
+use crate::settings::{self, Settings, SortOrder, Sublabel, ThemeKind, SETTINGS_KEY};
 use crate::sync_source::SyncSource;
 use crate::vault_item::VaultItem;
 use crate::vault_store::{SyncStatus, VaultStore};
@@ -35,19 +51,30 @@ use crate::vault_store::{SyncStatus, VaultStore};
 /// a row, using whatever `id` `CredentialListView` resolved as selected at
 /// that moment.
 ///
+/// A long press (`Back`, which the root screen can't pop) opens the
+/// settings screen via `.on_back(...)`, and `.with_settings(...)` makes the
+/// list follow the sort order and sublabel chosen there.
+///
 /// The `.with_hint(...)` here is only a fallback: `CredentialListView` is
 /// always the screen's sole (and thus always-focused) widget, so its own
 /// [`crate::render::ChromeContribution::hint`] always overrides this in
 /// practice (see `Screen::render`) — kept non-empty anyway so a screen
 /// somehow rendered before its widget is focused still shows sane control
 /// legend text instead of a blank hint bar.
-fn credential_list_screen(store: Rc<RefCell<VaultStore>>) -> Screen {
+fn credential_list_screen(store: Rc<RefCell<VaultStore>>, settings: Rc<RefCell<Settings>>) -> Screen {
     let store_for_activate = Rc::clone(&store);
-    let list = CredentialListView::new(store).on_activate(move |id| {
-        let store = Rc::clone(&store_for_activate);
-        Action::PushView(Box::new(move || credential_detail_screen(store, id)))
-    });
-    Screen::new("Vault", vec![Box::new(list)]).with_hint("Rotate to browse - Press to open")
+    let settings_for_back = Rc::clone(&settings);
+    let list = CredentialListView::new(store)
+        .with_settings(settings)
+        .on_activate(move |id| {
+            let store = Rc::clone(&store_for_activate);
+            Action::PushView(Box::new(move || credential_detail_screen(store, id)))
+        })
+        .on_back(move || {
+            let settings = Rc::clone(&settings_for_back);
+            Action::PushView(Box::new(move || settings_screen(settings)))
+        });
+    Screen::new("Vault", vec![Box::new(list)]).with_hint("Rotate to browse - Press to open - Hold for settings")
 }
 
 /// Builds a credential detail screen for `id`, backed live by `store` — see
@@ -60,6 +87,105 @@ fn credential_detail_screen(store: Rc<RefCell<VaultStore>>, id: uuid::Uuid) -> S
     Screen::new("Credential", vec![Box::new(detail)]).with_hint("Hold to go back")
 }
 
+/// Builds the settings screen: a [`Form`] whose rows read and write
+/// `settings` directly, so every change (brightness included, mid-edit)
+/// takes effect on the next frame. Persisting is `App`'s job, not the
+/// screen's — see [`App::persist_settings`].
+fn settings_screen(settings: Rc<RefCell<Settings>>) -> Screen {
+    let rows: Vec<Box<dyn FormRow>> = vec![
+        Box::new(
+            Stepper::new(
+                "Brightness",
+                (i32::from(settings::BRIGHTNESS_MIN), i32::from(settings::BRIGHTNESS_MAX)),
+                i32::from(settings::BRIGHTNESS_STEP),
+                getter(&settings, |s| i32::from(s.brightness)),
+                setter(&settings, |s, v| s.brightness = u8::try_from(v).unwrap_or(settings::BRIGHTNESS_MAX)),
+            )
+            .with_format(|v| format!("{v}%")),
+        ),
+        Box::new(Toggle::new(
+            "Screen off when idle",
+            getter(&settings, |s| s.screen_off_when_idle),
+            setter(&settings, |s, v| s.screen_off_when_idle = v),
+        )),
+        Box::new(
+            Stepper::new(
+                "Idle timeout",
+                (i32::from(settings::IDLE_TIMEOUT_MIN_SECS), i32::from(settings::IDLE_TIMEOUT_MAX_SECS)),
+                i32::from(settings::IDLE_TIMEOUT_STEP_SECS),
+                getter(&settings, |s| i32::from(s.idle_timeout_secs)),
+                setter(&settings, |s, v| s.idle_timeout_secs = u16::try_from(v).unwrap_or(settings::IDLE_TIMEOUT_MAX_SECS)),
+            )
+            .with_format(format_duration_secs),
+        ),
+        Box::new(EnumPicker::new(
+            "Sort by",
+            vec![(SortOrder::AsSynced, "Sync order".into()), (SortOrder::Name, "Name".into())],
+            getter(&settings, |s| s.sort_order),
+            setter(&settings, |s, v| s.sort_order = v),
+        )),
+        Box::new(EnumPicker::new(
+            "Show under name",
+            vec![
+                (Sublabel::Username, "Username".into()),
+                (Sublabel::Website, "Website".into()),
+                (Sublabel::Nothing, "Nothing".into()),
+            ],
+            getter(&settings, |s| s.sublabel),
+            setter(&settings, |s, v| s.sublabel = v),
+        )),
+        Box::new(EnumPicker::new(
+            "Theme",
+            vec![
+                (ThemeKind::Dark, "Dark".into()),
+                (ThemeKind::Light, "Light".into()),
+                (ThemeKind::HighContrast, "High contrast".into()),
+                (ThemeKind::LargeText, "Large text".into()),
+            ],
+            getter(&settings, |s| s.theme),
+            setter(&settings, |s, v| s.theme = v),
+        )),
+        Box::new(ActionRow::new("Reset to defaults", move || {
+            let settings = Rc::clone(&settings);
+            Action::ShowModal(
+                Dialog::new("Reset settings?", "Every setting on this screen goes back to its default.")
+                    .with_choice("Cancel", || Action::None)
+                    .with_choice("Reset", move || {
+                        *settings.borrow_mut() = Settings::default();
+                        Action::ShowToast(Toast::new("Settings reset"))
+                    }),
+            )
+        })),
+    ];
+    Screen::new("Settings", vec![Box::new(Form::new(rows))]).with_hint("Hold to go back")
+}
+
+/// A [`FormRow`] getter over one field of the shared settings.
+fn getter<T>(settings: &Rc<RefCell<Settings>>, read: impl Fn(&Settings) -> T) -> impl Fn() -> T {
+    let settings = Rc::clone(settings);
+    move || read(&settings.borrow())
+}
+
+/// A [`FormRow`] setter over one field of the shared settings.
+fn setter<T>(settings: &Rc<RefCell<Settings>>, write: impl Fn(&mut Settings, T)) -> impl Fn(T) {
+    let settings = Rc::clone(settings);
+    move |value| write(&mut settings.borrow_mut(), value)
+}
+
+/// `45` -> `"45 s"`, `90` -> `"1 min 30 s"`, `120` -> `"2 min"`.
+fn format_duration_secs(secs: i32) -> String {
+    match (secs / 60, secs % 60) {
+        (0, s) => format!("{s} s"),
+        (m, 0) => format!("{m} min"),
+        (m, s) => format!("{m} min {s} s"),
+    }
+}
+
+/// How long settings must sit unchanged before [`App::persist_settings`]
+/// writes them. Turning the brightness knob through ten steps should cost
+/// one flash write, not ten.
+pub const SETTINGS_SAVE_DELAY: Duration = Duration::from_secs(1);
+
 /// The application core: a [`VaultStore`] holding the authoritative
 /// credential state, a [`Navigator`] built once over a store-backed root
 /// screen, and the single [`FrameBuffer565`] it renders into.
@@ -71,6 +197,25 @@ pub struct App {
     /// [`App::render`] call. The run loop uses this to skip
     /// `DisplaySurface::flush` on frames where nothing changed.
     dirty: bool,
+    settings: Rc<RefCell<Settings>>,
+    /// What's in `Storage` as far as we know — the last value restored or
+    /// written. [`App::persist_settings`] writes only when the live
+    /// settings differ from this.
+    persisted: Settings,
+    /// The live settings as of the last `persist_settings` call, and when
+    /// they last changed — the save debounce (see
+    /// [`SETTINGS_SAVE_DELAY`]).
+    last_seen: Settings,
+    changed_at: Option<Instant>,
+    /// When input last arrived, for the idle timeout. `None` until the
+    /// first [`App::tick`], which starts the clock.
+    last_activity: Option<Instant>,
+    /// Input arrived since the last tick; `handle_input` has no clock, so
+    /// it flags this and `tick` stamps it.
+    activity_pending: bool,
+    /// The idle timeout fired and the screen is off; the next input only
+    /// wakes it.
+    asleep: bool,
 }
 
 impl App {
@@ -89,13 +234,21 @@ impl App {
     pub fn new(width: u32, height: u32, items: Vec<VaultItem>) -> Self {
         let store = Rc::new(RefCell::new(VaultStore::new()));
         store.borrow_mut().apply_sync_ok(items);
+        let settings = Rc::new(RefCell::new(Settings::default()));
 
-        let navigator = Navigator::new(credential_list_screen(Rc::clone(&store)));
+        let navigator = Navigator::new(credential_list_screen(Rc::clone(&store), Rc::clone(&settings)));
         Self {
             store,
             navigator,
             framebuffer: FrameBuffer565::new(width, height),
             dirty: true,
+            settings,
+            persisted: Settings::default(),
+            last_seen: Settings::default(),
+            changed_at: None,
+            last_activity: None,
+            activity_pending: false,
+            asleep: false,
         }
     }
 
@@ -117,18 +270,124 @@ impl App {
         self.navigator.depth()
     }
 
+    /// The title, readout, hint and status the current screen's chrome
+    /// shows (see [`Screen::chrome`]) — what's on screen, as text, for
+    /// tests and tooling like the emulator's scenario runner.
+    #[must_use]
+    pub fn chrome(&self) -> ChromeContribution {
+        self.navigator.current().chrome()
+    }
+
+    /// Everything on screen as a semantic tree (see
+    /// [`Navigator::semantics`]): the current screen's widgets, rows and
+    /// fields with their roles, values and focus, then any dialog and
+    /// toast. What the emulator serves from `GET /api/ui-tree`.
+    #[must_use]
+    pub fn ui_tree(&self) -> SemanticNode {
+        self.navigator.semantics()
+    }
+
+    /// The dialog open over the current screen, if any.
+    #[must_use]
+    pub fn modal(&self) -> Option<&Dialog> {
+        self.navigator.modal()
+    }
+
+    /// The toast currently showing, if any.
+    #[must_use]
+    pub fn toast(&self) -> Option<&Toast> {
+        self.navigator.toast()
+    }
+
+    /// A copy of the current (live) settings.
+    #[must_use]
+    pub fn settings(&self) -> Settings {
+        self.settings.borrow().clone()
+    }
+
+    /// Whether the idle timeout has turned the screen off.
+    #[must_use]
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// The backlight level the display should be at right now: the
+    /// brightness setting, or 0 while asleep. The run loop hands this to
+    /// `DisplaySurface::set_brightness` whenever it changes.
+    #[must_use]
+    pub fn display_brightness(&self) -> u8 {
+        if self.asleep {
+            0
+        } else {
+            self.settings.borrow().brightness
+        }
+    }
+
     /// Dispatches every polled `NavIntent` to the navigator, in order.
     /// A no-op (including leaving `dirty` untouched) if `intents` is empty.
+    ///
+    /// While asleep, the batch only wakes the screen and is otherwise
+    /// dropped — a press that lights up a dark screen shouldn't also open
+    /// whatever happened to be selected.
     pub fn handle_input(&mut self, intents: Vec<NavIntent>) {
         if intents.is_empty() {
             return;
         }
+        self.activity_pending = true;
+        self.dirty = true;
+        if self.asleep {
+            self.asleep = false;
+            return;
+        }
         for intent in intents {
             self.navigator.dispatch(intent);
         }
+    }
+
+    /// Loads settings from `storage` (see [`Settings::decode`] for how
+    /// missing, old and unreadable data are handled) and applies them.
+    /// Called once by the run loop before the first frame.
+    pub fn restore_settings(&mut self, storage: &impl Storage) {
+        let restored = Settings::decode(storage.get(SETTINGS_KEY).as_deref());
+        *self.settings.borrow_mut() = restored.clone();
+        self.persisted = restored.clone();
+        self.last_seen = restored;
+        self.changed_at = None;
         self.dirty = true;
     }
 
+    /// Writes the live settings to `storage` once they've differed from
+    /// what's stored and sat unchanged for [`SETTINGS_SAVE_DELAY`]. Called
+    /// by the run loop every frame with the platform clock's `now`.
+    ///
+    /// A failed write is logged and not retried until the settings change
+    /// again — retrying a broken flash every frame would only flood the
+    /// log; the live settings still apply for this session either way.
+    pub fn persist_settings<S: Storage>(&mut self, storage: &mut S, now: Instant)
+    where
+        S::Error: Debug,
+    {
+        let current = self.settings.borrow().clone();
+        if current != self.last_seen {
+            self.last_seen = current.clone();
+            self.changed_at = Some(now);
+        }
+        let Some(changed_at) = self.changed_at else {
+            return;
+        };
+        if now.saturating_duration_since(changed_at) < SETTINGS_SAVE_DELAY {
+            return;
+        }
+        self.changed_at = None;
+        if current == self.persisted {
+            return;
+        }
+        if let Err(error) = storage.set(SETTINGS_KEY, current.encode()) {
+            log::warn!("failed to save settings: {error:?}");
+        }
+        self.persisted = current;
+    }
+
     /// Pulls the latest vault snapshot from `sync` and writes it into the
     /// [`VaultStore`] in place — the [`Navigator`] is never rebuilt (see
     /// the module doc and the M1 ADR). `dirty` is set only when the store
@@ -151,6 +410,39 @@ impl App {
         }
     }
 
+    /// Advances time-driven UI state (toast expiry, the idle timeout) to
+    /// `now`, read by the run loop from the platform's `Clock`. Marks the
+    /// app dirty only if something visible changed; falling asleep isn't
+    /// one (the screen goes dark via [`App::display_brightness`]).
+    pub fn tick(&mut self, now: Instant) {
+        if self.navigator.tick(now) {
+            self.dirty = true;
+        }
+
+        if self.activity_pending || self.last_activity.is_none() {
+            self.last_activity = Some(now);
+            self.activity_pending = false;
+        }
+        let timeout = self.settings.borrow().idle_timeout();
+        if let (Some(timeout), Some(last_activity), false) = (timeout, self.last_activity, self.asleep) {
+            if now.saturating_duration_since(last_activity) >= timeout {
+                self.asleep = true;
+            }
+        }
+    }
+
+    /// Opens `dialog` over the current screen (see [`Navigator::show_modal`]).
+    pub fn show_modal(&mut self, dialog: Dialog) {
+        self.navigator.show_modal(dialog);
+        self.dirty = true;
+    }
+
+    /// Queues `toast` (see [`Navigator::show_toast`]).
+    pub fn show_toast(&mut self, toast: Toast) {
+        self.navigator.show_toast(toast);
+        self.dirty = true;
+    }
+
     /// Whether [`App::render`] would draw something different from the
     /// last time it was called.
     #[must_use]
@@ -158,9 +450,11 @@ impl App {
         self.dirty
     }
 
-    /// Renders the current screen into the app's framebuffer and clears
-    /// the dirty flag, returning the freshly rendered framebuffer for the
-    /// caller to hand to a `DisplaySurface::flush`.
+    /// Renders the current screen into the app's framebuffer, in the
+    /// theme the live settings select, and clears the dirty flag,
+    /// returning the freshly rendered framebuffer for the caller to hand
+    /// to a `DisplaySurface::flush`. The theme is read per frame, so
+    /// picking one in settings restyles everything on the next render.
     ///
     /// # Panics
     ///
@@ -169,12 +463,24 @@ impl App {
     /// never fail to draw). The `expect` exists only because
     /// `Result::expect` is how that's asserted at the call site.
     pub fn render(&mut self) -> &FrameBuffer565 {
+        let theme = self.settings.borrow().theme.theme();
         self.navigator
-            .render(&mut self.framebuffer)
+            .render(&theme, &mut self.framebuffer)
             .expect("core DrawTarget is Infallible");
         self.dirty = false;
         &self.framebuffer
     }
+
+    /// [`App::render`], plus the regions that changed since the last call
+    /// ([`FrameBuffer565::take_damage`]) — what `run` hands to
+    /// `DisplaySurface::flush`. Every rendered frame is a full repaint, so
+    /// the damage is what differs in the *result*: moving the selection
+    /// damages the two rows involved, not the screen.
+    pub fn render_with_damage(&mut self) -> (&FrameBuffer565, Vec<Rectangle>) {
+        self.render();
+        let damage = self.framebuffer.take_damage();
+        (&self.framebuffer, damage)
+    }
 }
 
 #[cfg(test)]
@@ -183,8 +489,8 @@ mod tests {
     use std::convert::Infallible;
 
     use crate::render::chrome::TITLE_BAR_HEIGHT;
-    use crate::render::theme::palette;
-    use crate::render::ROW_HEIGHT;
+    use crate::render::{ChromeStatus, Palette, Role, DAMAGE_TILE, ROW_HEIGHT};
+    use embedded_graphics::geometry::Dimensions;
     use embedded_graphics::pixelcolor::Rgb565;
     use embedded_graphics::prelude::Point;
     use uuid::Uuid;
@@ -243,13 +549,31 @@ mod tests {
         // these short labels' text, so it samples the row's plain
         // elevated fill rather than the accent stripe or a glyph pixel.
         let frame_0 = app.render().pixel(Point::new(250, 18));
-        assert_eq!(frame_0, palette::SURFACE_ELEVATED, "row 0 should start selected");
+        assert_eq!(frame_0, Palette::DARK.surface_elevated, "row 0 should start selected");
 
         app.handle_input(vec![NavIntent::Next]);
         assert!(app.dirty(), "moving selection should mark the app dirty");
 
         let frame_1_row_0 = app.render().pixel(Point::new(250, 18));
-        assert_ne!(frame_1_row_0, palette::SURFACE_ELEVATED, "row 0 should no longer be selected");
+        assert_ne!(frame_1_row_0, Palette::DARK.surface_elevated, "row 0 should no longer be selected");
+    }
+
+    #[test]
+    fn moving_the_selection_damages_only_the_rows_and_readout_involved() {
+        let mut app = App::new(320, 170, vec![item("GitHub"), item("AWS"), item("Postgres")]);
+        let (framebuffer, damage) = app.render_with_damage();
+        assert_eq!(damage, vec![framebuffer.bounding_box()], "the first frame is all new");
+        assert_eq!(app.render_with_damage().1, vec![], "an identical repaint damages nothing");
+
+        app.handle_input(vec![NavIntent::Next]);
+        let damage = app.render_with_damage().1;
+        let area: u32 = damage.iter().map(|rect| rect.size.width * rect.size.height).sum();
+        assert!(!damage.is_empty());
+        assert!(area < 320 * 170 / 2, "{damage:?}");
+        assert!(
+            damage.iter().all(|rect| rect.top_left.y + rect.size.height as i32 <= TITLE_BAR_HEIGHT as i32 + 2 * ROW_HEIGHT as i32 + DAMAGE_TILE as i32),
+            "nothing below the second row changed: {damage:?}"
+        );
     }
 
     #[test]
@@ -365,7 +689,7 @@ mod tests {
         let row1_y = (TITLE_BAR_HEIGHT + ROW_HEIGHT + 2) as i32;
         let sample_x = 250;
         let row1_selected_before_activate = app.render().pixel(Point::new(sample_x, row1_y));
-        assert_eq!(row1_selected_before_activate, palette::SURFACE_ELEVATED, "AWS (row 1) is selected before activating");
+        assert_eq!(row1_selected_before_activate, Palette::DARK.surface_elevated, "AWS (row 1) is selected before activating");
 
         app.handle_input(vec![NavIntent::Activate]);
         assert_eq!(app.navigator_depth(), 2, "activating a credential should push a detail screen");
@@ -375,8 +699,201 @@ mod tests {
 
         let row1_selected_after_back = app.render().pixel(Point::new(sample_x, row1_y));
         assert_eq!(
-            row1_selected_after_back, palette::SURFACE_ELEVATED,
+            row1_selected_after_back, Palette::DARK.surface_elevated,
             "the list's selection (AWS, row 1) must survive the push/pop round trip"
         );
     }
+
+    #[test]
+    fn chrome_reports_the_text_the_current_screen_shows() {
+        let mut app = App::new(320, 170, vec![item("GitHub"), item("AWS"), item("Postgres")]);
+        app.handle_input(vec![NavIntent::Next]);
+
+        let chrome = app.chrome();
+        assert_eq!(chrome.title.as_deref(), Some("Vault"), "the list has no title override: the screen's own applies");
+        assert_eq!(chrome.readout.as_deref(), Some("2 / 3"));
+        assert!(chrome.hint.is_some());
+
+        app.handle_input(vec![NavIntent::Activate]);
+        assert_eq!(app.chrome().title.as_deref(), Some("AWS"), "the detail view titles itself with the item's name");
+        assert!(app.modal().is_none() && app.toast().is_none());
+    }
+
+    #[test]
+    fn the_ui_tree_names_the_focused_row_and_follows_it_into_the_detail_view() {
+        let mut app = App::new(320, 170, vec![item("GitHub"), item("AWS")]);
+        app.handle_input(vec![NavIntent::Next]);
+
+        let tree = app.ui_tree();
+        let screen = &tree.children[0];
+        assert_eq!((screen.role, screen.label.as_str(), screen.value.as_deref()), (Role::Screen, "Vault", Some("2 / 2")));
+        let focus = tree.focus().expect("the list row has focus");
+        assert_eq!((focus.role, focus.label.as_str(), focus.value.as_deref()), (Role::ListItem, "AWS", Some("AWS-user")));
+        assert_eq!(screen.children.last().map(|hint| hint.role), Some(Role::Hint));
+
+        app.handle_input(vec![NavIntent::Activate, NavIntent::Next]);
+        let tree = app.ui_tree();
+        let focus = tree.focus().unwrap();
+        assert_eq!((focus.role, focus.label.as_str()), (Role::SecretField, "PASSWORD"));
+        assert_eq!(focus.value.as_deref(), Some("**********"), "masked: the tree shows what the screen shows");
+        assert!(tree.find(|node| node.value.as_deref() == Some("hunter2")).is_none());
+
+        app.handle_input(vec![NavIntent::Activate]);
+        assert_eq!(app.ui_tree().focus().unwrap().value.as_deref(), Some("hunter2"), "revealed on screen, revealed in the tree");
+    }
+
+    #[test]
+    fn an_open_dialog_takes_focus_in_the_ui_tree_and_a_toast_is_listed_last() {
+        let mut app = App::new(320, 170, vec![item("GitHub")]);
+        app.show_modal(Dialog::new("Wipe vault?", "This can't be undone.").with_choice("Cancel", || Action::None).with_choice("Wipe", || Action::None));
+        app.show_toast(Toast::new("Sync failed").with_status(ChromeStatus::Error));
+
+        let tree = app.ui_tree();
+        let roles: Vec<Role> = tree.children.iter().map(|layer| layer.role).collect();
+        assert_eq!(roles, [Role::Screen, Role::Dialog, Role::Toast]);
+        assert_eq!(tree.focus().map(|node| node.label.as_str()), Some("Cancel"));
+        let row = tree.find(|node| node.label == "GitHub").unwrap();
+        assert!(row.selected && !row.focused, "the list keeps its selection but not the focus under a dialog");
+        assert_eq!(tree.children[2].value.as_deref(), Some("error"));
+    }
+
+    #[test]
+    fn the_settings_form_describes_each_row_by_kind_and_value() {
+        let mut app = App::new(320, 170, vec![item("GitHub")]);
+        app.handle_input(vec![NavIntent::Back, NavIntent::Next]);
+
+        let tree = app.ui_tree();
+        let focus = tree.focus().unwrap();
+        assert_eq!((focus.role, focus.label.as_str(), focus.value.as_deref()), (Role::Toggle, "Screen off when idle", Some("Off")));
+        let form = tree.find(|node| node.role == Role::Form).unwrap();
+        assert_eq!(form.children.first().map(|row| row.role), Some(Role::Stepper));
+        assert_eq!(form.children.last().map(|row| (row.role, row.value.is_none())), Some((Role::Button, true)));
+    }
+
+    #[test]
+    fn an_expiring_toast_marks_the_app_dirty_on_tick() {
+        use std::time::Duration;
+
+        let mut app = App::new(320, 170, vec![item("GitHub")]);
+        let start = Instant::now();
+        app.show_toast(Toast::new("Copied").with_duration(Duration::from_secs(1)));
+        app.tick(start);
+        app.render();
+
+        app.tick(start + Duration::from_millis(500));
+        assert!(!app.dirty(), "nothing visible changed yet");
+        app.tick(start + Duration::from_secs(1));
+        assert!(app.dirty(), "the toast expiring is a visible change");
+    }
+
+    #[test]
+    fn input_goes_to_an_open_modal_instead_of_the_list() {
+        let mut app = App::new(320, 170, vec![item("GitHub"), item("AWS")]);
+        app.show_modal(Dialog::new("Wipe vault?", "").with_choice("Cancel", || Action::None));
+
+        app.handle_input(vec![NavIntent::Activate]);
+        assert_eq!(app.navigator_depth(), 1, "Activate closed the dialog instead of opening a credential");
+        app.handle_input(vec![NavIntent::Activate]);
+        assert_eq!(app.navigator_depth(), 2);
+    }
+
+    #[derive(Default)]
+    struct MemoryStorage {
+        values: std::collections::HashMap<String, Vec<u8>>,
+        writes: u32,
+    }
+    impl Storage for MemoryStorage {
+        type Error = Infallible;
+        fn get(&self, key: &str) -> Option<Vec<u8>> {
+            self.values.get(key).cloned()
+        }
+        fn set(&mut self, key: &str, value: Vec<u8>) -> Result<(), Self::Error> {
+            self.writes += 1;
+            self.values.insert(key.to_string(), value);
+            Ok(())
+        }
+        fn remove(&mut self, key: &str) -> Result<(), Self::Error> {
+            self.writes += 1;
+            self.values.remove(key);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn holding_on_the_root_list_opens_settings_and_changes_apply_live() {
+        let mut app = App::new(320, 170, vec![item("GitHub")]);
+        app.handle_input(vec![NavIntent::Back]);
+        assert_eq!(app.navigator_depth(), 2, "Back on the root opens the settings screen");
+
+        // Brightness is the first row: press to edit, rotate down twice.
+        app.handle_input(vec![NavIntent::Activate, NavIntent::Prev, NavIntent::Prev]);
+        assert_eq!(app.settings().brightness, 80);
+        assert_eq!(app.display_brightness(), 80, "applied before the edit is even confirmed");
+
+        app.handle_input(vec![NavIntent::Activate, NavIntent::Back]);
+        assert_eq!(app.navigator_depth(), 1);
+    }
+
+    #[test]
+    fn reset_to_defaults_asks_first_then_resets_and_toasts() {
+        let mut app = App::new(320, 170, vec![item("GitHub")]);
+        app.handle_input(vec![NavIntent::Back, NavIntent::Activate, NavIntent::Prev, NavIntent::Activate]);
+        assert_eq!(app.settings().brightness, 90);
+
+        // "Reset to defaults" is the last row; the dialog opens on Cancel.
+        app.handle_input(vec![NavIntent::NextN(10), NavIntent::Activate]);
+        app.handle_input(vec![NavIntent::Next, NavIntent::Activate]);
+        assert_eq!(app.settings(), Settings::default());
+        assert_eq!(app.navigator.toast().map(|t| t.message.as_str()), Some("Settings reset"));
+        assert_eq!(app.navigator_depth(), 2, "still on the settings screen");
+    }
+
+    #[test]
+    fn the_screen_sleeps_after_the_idle_timeout_and_the_waking_input_is_swallowed() {
+        let mut app = App::new(320, 170, vec![item("GitHub"), item("AWS")]);
+        app.settings.borrow_mut().screen_off_when_idle = true;
+        let start = Instant::now();
+        app.tick(start);
+        app.tick(start + Duration::from_secs(59));
+        assert!(!app.is_asleep());
+        app.tick(start + Duration::from_secs(60));
+        assert!(app.is_asleep());
+        assert_eq!(app.display_brightness(), 0);
+
+        app.handle_input(vec![NavIntent::Activate]);
+        assert!(!app.is_asleep());
+        assert_eq!(app.navigator_depth(), 1, "the waking press didn't open a credential");
+        app.tick(start + Duration::from_secs(61));
+        app.tick(start + Duration::from_secs(120));
+        assert!(!app.is_asleep(), "the idle clock restarted at the waking input");
+    }
+
+    #[test]
+    fn settings_are_saved_once_they_settle_and_restored_on_the_next_boot() {
+        let mut storage = MemoryStorage::default();
+        let mut app = App::new(320, 170, vec![]);
+        app.restore_settings(&storage);
+        let start = Instant::now();
+
+        app.settings.borrow_mut().brightness = 70;
+        app.persist_settings(&mut storage, start);
+        app.settings.borrow_mut().brightness = 60;
+        app.persist_settings(&mut storage, start + Duration::from_millis(500));
+        app.persist_settings(&mut storage, start + Duration::from_millis(1400));
+        assert_eq!(storage.writes, 0, "still within the save delay of the last change");
+        app.persist_settings(&mut storage, start + Duration::from_millis(1500));
+        app.persist_settings(&mut storage, start + Duration::from_secs(5));
+        assert_eq!(storage.writes, 1);
+
+        let mut rebooted = App::new(320, 170, vec![]);
+        rebooted.restore_settings(&storage);
+        assert_eq!(rebooted.settings().brightness, 60);
+    }
+
+    #[test]
+    fn format_duration_secs_reads_naturally() {
+        assert_eq!(format_duration_secs(45), "45 s");
+        assert_eq!(format_duration_secs(120), "2 min");
+        assert_eq!(format_duration_secs(90), "1 min 30 s");
+    }
 }
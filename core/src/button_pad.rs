@@ -0,0 +1,303 @@
+//! Three-button input: the debounce and press/hold logic that turns raw
+//! button levels into [`NavIntent`]s for boards with no rotary encoder —
+//! the HUZZAH32 prototype's OLED FeatherWing, whose A/B/C buttons are the
+//! only input it has.
+//!
+//! Lives here rather than next to the GPIO driver for the same reason the
+//! render core does: it's pure logic over `(levels, now)` samples, so it
+//! can be unit-tested on host, while the firmware's `InputSource` is left
+//! with nothing to do but read three pins and hand them to
+//! [`ButtonPad::update`]. Time comes in as an argument (the caller's
+//! `Clock::now`), never read here — this crate doesn't touch platform
+//! time APIs.
+//!
+//! The mapping follows the encoder's (see
+//! `.planning/decisions/2026-08-11-rotary-encoder-input-model.md`): the
+//! outer buttons step like a detent, the middle one is the encoder's push
+//! button — click to `Activate`, hold to go `Back`.
+
+use std::time::{Duration, Instant};
+
+use crate::input::NavIntent;
+
+/// How long a raw level has to hold still before it's believed. Tactile
+/// switches bounce for a few milliseconds; this is comfortably longer
+/// without being a noticeable delay.
+pub const DEBOUNCE: Duration = Duration::from_millis(20);
+/// How long the middle button has to be held for `Back` instead of
+/// `Activate` — also the T-Embed encoder button's long press
+/// ([`crate::input::encoder`]), so the gesture feels the same on both
+/// boards.
+pub const LONG_PRESS: Duration = Duration::from_millis(500);
+/// How long an outer button has to be held before it starts repeating.
+pub const REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// Interval between repeats while an outer button stays held.
+pub const REPEAT_INTERVAL: Duration = Duration::from_millis(120);
+
+/// One debounced button. Reports an edge only once a new raw level has
+/// been stable for [`DEBOUNCE`], so a bouncing contact produces a single
+/// press and a single release.
+#[derive(Debug, Clone)]
+pub struct Debouncer {
+    stable: bool,
+    candidate: bool,
+    candidate_since: Option<Instant>,
+}
+
+/// A debounced level change reported by [`Debouncer::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Pressed,
+    Released,
+}
+
+impl Debouncer {
+    /// A debouncer for a button that starts out released.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stable: false,
+            candidate: false,
+            candidate_since: None,
+        }
+    }
+
+    /// The debounced level: `true` while pressed.
+    #[must_use]
+    pub fn is_pressed(&self) -> bool {
+        self.stable
+    }
+
+    /// Feeds one raw sample (`pressed` is the level after the board's
+    /// active-low inversion) taken at `now`. Returns the edge, if this
+    /// sample completes one.
+    pub fn update(&mut self, pressed: bool, now: Instant) -> Option<Edge> {
+        if pressed != self.candidate {
+            self.candidate = pressed;
+            self.candidate_since = Some(now);
+        }
+        if self.candidate == self.stable {
+            return None;
+        }
+        let since = self.candidate_since?;
+        if now.saturating_duration_since(since) < DEBOUNCE {
+            return None;
+        }
+        self.stable = self.candidate;
+        Some(if self.stable {
+            Edge::Pressed
+        } else {
+            Edge::Released
+        })
+    }
+}
+
+impl Default for Debouncer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The three buttons, top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadButton {
+    /// `Prev`, repeating while held.
+    Up,
+    /// `Activate` on a click, `Back` once held for [`LONG_PRESS`].
+    Select,
+    /// `Next`, repeating while held.
+    Down,
+}
+
+impl PadButton {
+    const ALL: [Self; 3] = [Self::Up, Self::Select, Self::Down];
+}
+
+/// Three debounced buttons mapped to [`NavIntent`]s.
+#[derive(Debug, Clone, Default)]
+pub struct ButtonPad {
+    buttons: [Debouncer; 3],
+    /// When the current press of each button was debounced.
+    pressed_at: [Option<Instant>; 3],
+    /// When each held outer button next repeats.
+    next_repeat: [Option<Instant>; 3],
+    /// Set once the current `Select` press has turned into `Back`, so its
+    /// release doesn't also `Activate`.
+    select_held: bool,
+}
+
+impl ButtonPad {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds one sample of all three buttons (`pressed[i]` for
+    /// [`PadButton`] `i`, top to bottom) taken at `now`, returning the
+    /// intents it produces in button order. Call it every frame: holds and
+    /// repeats are only noticed when sampled.
+    pub fn update(&mut self, pressed: [bool; 3], now: Instant) -> Vec<NavIntent> {
+        let mut intents = Vec::new();
+        for (index, button) in PadButton::ALL.into_iter().enumerate() {
+            let edge = self.buttons[index].update(pressed[index], now);
+            match edge {
+                Some(Edge::Pressed) => self.pressed_at[index] = Some(now),
+                Some(Edge::Released) => self.pressed_at[index] = None,
+                None => {}
+            }
+
+            match (button, edge) {
+                (PadButton::Select, Some(Edge::Released)) => {
+                    if !std::mem::take(&mut self.select_held) {
+                        intents.push(NavIntent::Activate);
+                    }
+                }
+                (PadButton::Select, _) => {
+                    let held = self.pressed_at[index]
+                        .is_some_and(|at| now.saturating_duration_since(at) >= LONG_PRESS);
+                    if held && !self.select_held {
+                        self.select_held = true;
+                        intents.push(NavIntent::Back);
+                    }
+                }
+                (PadButton::Up | PadButton::Down, _) => {
+                    if self.step_due(index, edge, now) {
+                        intents.push(if button == PadButton::Up {
+                            NavIntent::Prev
+                        } else {
+                            NavIntent::Next
+                        });
+                    }
+                }
+            }
+        }
+        intents
+    }
+
+    /// Whether outer button `index` steps at `now`: on its press, then
+    /// every [`REPEAT_INTERVAL`] once it's been held for [`REPEAT_DELAY`].
+    fn step_due(&mut self, index: usize, edge: Option<Edge>, now: Instant) -> bool {
+        match (edge, self.next_repeat[index]) {
+            (Some(Edge::Pressed), _) => {
+                self.next_repeat[index] = Some(now + REPEAT_DELAY);
+                true
+            }
+            (Some(Edge::Released), _) => {
+                self.next_repeat[index] = None;
+                false
+            }
+            (None, Some(next)) if now >= next => {
+                self.next_repeat[index] = Some(next + REPEAT_INTERVAL);
+                true
+            }
+            (None, _) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: [bool; 3] = [true, false, false];
+    const SELECT: [bool; 3] = [false, true, false];
+    const DOWN: [bool; 3] = [false, false, true];
+    const NONE: [bool; 3] = [false; 3];
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn a_bouncing_contact_is_one_press_and_one_release() {
+        let t0 = Instant::now();
+        let mut button = Debouncer::new();
+        let mut edges = Vec::new();
+        for (millis, level) in [
+            (0, true),
+            (2, false),
+            (4, true),
+            (6, false),
+            (8, true),
+            (30, true),
+            (40, false),
+            (42, true),
+            (44, false),
+            (70, false),
+        ] {
+            edges.extend(button.update(level, ms(t0, millis)));
+        }
+        assert_eq!(edges, vec![Edge::Pressed, Edge::Released]);
+        assert!(!button.is_pressed());
+    }
+
+    #[test]
+    fn a_glitch_shorter_than_the_debounce_window_is_ignored() {
+        let t0 = Instant::now();
+        let mut button = Debouncer::new();
+        assert_eq!(button.update(true, t0), None);
+        assert_eq!(button.update(false, ms(t0, 5)), None);
+        assert_eq!(button.update(false, ms(t0, 50)), None);
+        assert!(!button.is_pressed());
+    }
+
+    #[test]
+    fn outer_buttons_step_once_per_press() {
+        let t0 = Instant::now();
+        let mut pad = ButtonPad::new();
+        let mut intents = Vec::new();
+        for (millis, levels) in [
+            (0, DOWN),
+            (25, DOWN),
+            (100, NONE),
+            (130, NONE),
+            (200, UP),
+            (230, UP),
+            (300, NONE),
+            (330, NONE),
+        ] {
+            intents.extend(pad.update(levels, ms(t0, millis)));
+        }
+        assert_eq!(intents, vec![NavIntent::Next, NavIntent::Prev]);
+    }
+
+    #[test]
+    fn a_held_outer_button_repeats_after_the_delay() {
+        let t0 = Instant::now();
+        let mut pad = ButtonPad::new();
+        let mut steps = 0;
+        // Debounced at 20ms; repeats from 420ms, every 120ms: 420, 540, 660.
+        for millis in (0..=700).step_by(10) {
+            steps += pad.update(DOWN, ms(t0, millis)).len();
+        }
+        assert_eq!(steps, 4);
+    }
+
+    #[test]
+    fn select_clicks_to_activate_and_holds_to_go_back() {
+        let t0 = Instant::now();
+        let mut pad = ButtonPad::new();
+        let mut click = Vec::new();
+        for (millis, levels) in [(0, SELECT), (25, SELECT), (150, NONE), (180, NONE)] {
+            click.extend(pad.update(levels, ms(t0, millis)));
+        }
+        assert_eq!(click, vec![NavIntent::Activate]);
+
+        let mut hold = Vec::new();
+        for (millis, levels) in [
+            (1000, SELECT),
+            (1025, SELECT),
+            (1600, SELECT),
+            (1700, SELECT),
+            (1800, NONE),
+            (1830, NONE),
+        ] {
+            hold.extend(pad.update(levels, ms(t0, millis)));
+        }
+        assert_eq!(
+            hold,
+            vec![NavIntent::Back],
+            "a hold is Back once, and its release doesn't also Activate"
+        );
+    }
+}
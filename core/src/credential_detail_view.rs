@@ -28,6 +28,14 @@
 //! - **Gone state**: when `store.get(id)` is `None` (deleted upstream
 //!   while viewing), the field stack is replaced with a centered "this
 //!   item was removed" message — never a panic or a blank screen.
+//! - **Notes**: the NOTES field only shows what fits its row — the first
+//!   line, clipped — and the semantic tree exposes no more. Activating
+//!   it pushes a full-screen [`TextView`] with the whole note, word-wrapped
+//!   and scrollable — a snapshot of the note at the moment it was opened.
+//! - **Marquee**: a focused USERNAME/WEBSITE value too wide for its row
+//!   scrolls horizontally (a [`Marquee`] keyed by the focused field). The
+//!   PASSWORD field is never keyed, so a revealed secret never moves;
+//!   NOTES isn't either — activating it is how you read the rest.
 
 // Identical allow (and rationale) as `bhk_core::render`/`credential_list_view`:
 // this module does the same `embedded-graphics` `Point`(i32)/`Size`(u32)
@@ -44,6 +52,7 @@
 use std::cell::{Cell, RefCell};
 use std::convert::Infallible;
 use std::rc::Rc;
+use std::time::Instant;
 
 use embedded_graphics::{
     draw_target::DrawTargetExt,
@@ -59,47 +68,49 @@ use uuid::Uuid;
 
 use crate::credential_list_view::render_message;
 use crate::input::NavIntent;
-use crate::render::theme::{font, icon, palette};
-use crate::render::{Action, ChromeContribution, ChromeStatus, FocusEvent, FrameBuffer565, SecretField, Widget};
+use crate::render::marquee::Marquee;
+use crate::render::screen::text_width;
+use crate::render::theme::{icon, line_height, FontSet, Theme};
+use crate::render::{
+    Action, ChromeContribution, ChromeStatus, FocusEvent, FrameBuffer565, Role, Screen, SecretField, SemanticNode, TextView, Widget,
+};
 use crate::vault_item::VaultItem;
 use crate::vault_store::{SyncStatus, VaultStore};
 
+/// The gone state's headline and subline (see the module doc).
+const GONE_HEADLINE: &str = "This item was removed";
+const GONE_SUBLINE: &str = "Hold to go back";
+
 /// Horizontal margin (px) from a field row's left/right edges to its
 /// label/value text.
 const FIELD_SIDE_MARGIN: i32 = 8;
 /// Vertical padding (px) above a field row's label and below its value.
 const FIELD_PADDING: i32 = 6;
+/// [`FIELD_PADDING`] for [`FontSet::Compact`]: a strip panel's 24px
+/// content area has to hold one whole field, label and value.
+const COMPACT_FIELD_PADDING: i32 = 1;
 /// Gap (px) between a field's label line and its value line.
 const LABEL_VALUE_GAP: i32 = 3;
+/// [`LABEL_VALUE_GAP`] for [`FontSet::Compact`].
+const COMPACT_LABEL_VALUE_GAP: i32 = 0;
+
+const fn field_padding(fonts: FontSet) -> i32 {
+    match fonts {
+        FontSet::Compact => COMPACT_FIELD_PADDING,
+        FontSet::Standard | FontSet::Large => FIELD_PADDING,
+    }
+}
 
-/// Fallback line height (px) used only if a font's metrics are somehow
-/// unavailable (`get_rendered_dimensions_aligned` returning `None` — see
-/// `font`'s module doc: this happens for a glyph with no coverage, not for
-/// the fixed ASCII probe string used here, but the render core must never
-/// panic on a font-metrics hiccup).
-const FALLBACK_LINE_HEIGHT: i32 = 16;
-
-/// Measures a font's worst-case single-line pixel footprint from a
-/// `VerticalPosition::Top`-anchored position, using the same "all five
-/// ASCII descenders (+ a capital, for ascent)" probe string convention
-/// `core/src/render/list.rs`'s `NAME_LINE_FOOTPRINT`/`USERNAME_LINE_FOOTPRINT`
-/// doc comment describes — except computed here at runtime via
-/// `get_rendered_dimensions_aligned` (the same mechanism `screen.rs`'s
-/// `text_width` already uses for width) rather than hardcoded from an
-/// offline probe. A field's value font varies per field kind (`profont17`
-/// for the password field, `helvR12` for everything else), so a single
-/// hardcoded constant can't cover every combination the way `list.rs`'s
-/// two fixed fonts could — measuring at runtime is what keeps this correct
-/// without a probe-per-font-combination.
-fn line_height(font: &FontRenderer) -> i32 {
-    font.get_rendered_dimensions_aligned("Agjpqy", Point::zero(), VerticalPosition::Top, HorizontalAlignment::Left)
-        .unwrap_or(None)
-        .map_or(FALLBACK_LINE_HEIGHT, |bbox| bbox.size.height as i32)
+const fn label_value_gap(fonts: FontSet) -> i32 {
+    match fonts {
+        FontSet::Compact => COMPACT_LABEL_VALUE_GAP,
+        FontSet::Standard | FontSet::Large => LABEL_VALUE_GAP,
+    }
 }
 
-/// Row-relative Y offset for a field's value line, given its label's font.
-fn value_top_offset(label_font: &FontRenderer) -> i32 {
-    FIELD_PADDING + line_height(label_font) + LABEL_VALUE_GAP
+/// Row-relative Y offset for a field's value line in `fonts`.
+fn value_top_offset(fonts: FontSet) -> i32 {
+    field_padding(fonts) + line_height(&fonts.label()) + label_value_gap(fonts)
 }
 
 /// Total pixel height of a field row: top padding, label line, the gap,
@@ -107,8 +118,8 @@ fn value_top_offset(label_font: &FontRenderer) -> i32 {
 /// password field's `profont17` value line is taller than the others'
 /// `helvR12`), so this is computed per-field rather than being a single
 /// shared constant like list rows' `ROW_HEIGHT`.
-fn field_height(label_font: &FontRenderer, value_font: &FontRenderer) -> u32 {
-    (FIELD_PADDING + line_height(label_font) + LABEL_VALUE_GAP + line_height(value_font) + FIELD_PADDING) as u32
+fn field_height(fonts: FontSet, value_font: &FontRenderer) -> u32 {
+    (value_top_offset(fonts) + line_height(value_font) + field_padding(fonts)) as u32
 }
 
 /// Scroll offset (in pixels) that keeps the `focused`-th row — given each
@@ -160,12 +171,20 @@ impl Field {
     /// The font a field's *value* line renders in. The password field
     /// uses the monospaced secret font (see `SecretField`'s doc comment on
     /// why); every other field uses the plain detail-value font.
-    fn value_font(self) -> FontRenderer {
+    fn value_font(self, fonts: FontSet) -> FontRenderer {
         match self {
-            Field::Password => font::secret(),
-            Field::Username | Field::Website | Field::Notes => font::value(),
+            Field::Password => fonts.secret(),
+            Field::Username | Field::Website | Field::Notes => fonts.value(),
         }
     }
+
+    /// Whether a focused, over-long value of this field marquees. Never
+    /// PASSWORD: a revealed secret stays put, masked or not, so it can't
+    /// drift into a position an onlooker catches mid-scroll. Not NOTES
+    /// either, which opens a full reader instead.
+    fn scrolls(self) -> bool {
+        matches!(self, Field::Username | Field::Website)
+    }
 }
 
 /// The fields to show for `item`, in display order: USERNAME and PASSWORD
@@ -204,6 +223,14 @@ pub struct CredentialDetailView {
     /// assumed, so a hypothetical future multi-widget detail screen
     /// wouldn't silently paint focus highlighting while unfocused.
     focused: bool,
+    /// Horizontal scroll of the focused field's value, keyed by that field
+    /// — only ever USERNAME or WEBSITE (see [`Field::scrolls`]).
+    marquee: Marquee<Field>,
+    /// What the last render drew of NOTES: the note's first line, cut to
+    /// the characters that fit its row. Empty before the first render and
+    /// while the row is scrolled out of view. What [`Widget::semantics`]
+    /// exposes in place of the note itself.
+    shown_notes: RefCell<String>,
 }
 
 impl CredentialDetailView {
@@ -215,6 +242,8 @@ impl CredentialDetailView {
             focused_field: Cell::new(0),
             secret: SecretField::new(),
             focused: false,
+            marquee: Marquee::default(),
+            shown_notes: RefCell::new(String::new()),
         }
     }
 
@@ -280,11 +309,10 @@ impl CredentialDetailView {
         }
     }
 
-    fn render_fields(&self, area: Rectangle, item: &VaultItem, target: &mut FrameBuffer565) -> Result<(), Infallible> {
+    fn render_fields(&self, area: Rectangle, item: &VaultItem, theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible> {
         let fields = available_fields(item);
         let focused_index = self.resolve_focus(fields.len());
-        let label_font = font::label();
-        let heights: Vec<u32> = fields.iter().map(|field| field_height(&label_font, &field.value_font())).collect();
+        let heights: Vec<u32> = fields.iter().map(|field| field_height(theme.fonts, &field.value_font(theme.fonts))).collect();
 
         // Auto-scroll so the focused field's row is fully visible — the
         // same requirement `list.rs`'s `scroll_offset_for_selection` meets
@@ -301,6 +329,7 @@ impl CredentialDetailView {
         // `CredentialListView::render_list` clips to the whole `rows_area`
         // up front rather than per-row.
         let mut clipped = target.clipped(&area);
+        self.shown_notes.borrow_mut().clear();
 
         let mut y = area.top_left.y - scroll as i32;
         let bottom = area.top_left.y + area.size.height as i32;
@@ -316,7 +345,7 @@ impl CredentialDetailView {
             if row_bottom > area.top_left.y && y < bottom {
                 let row_rect = Rectangle::new(Point::new(area.top_left.x, y), Size::new(area.size.width, height));
                 let is_focused = self.focused && focused_index == Some(index);
-                self.render_field(row_rect, *field, item, is_focused, &mut clipped)?;
+                self.render_field(row_rect, *field, item, is_focused, theme, &mut clipped)?;
             }
 
             y += height as i32;
@@ -331,6 +360,7 @@ impl CredentialDetailView {
         field: Field,
         item: &VaultItem,
         is_focused: bool,
+        theme: &Theme,
         target: &mut D,
     ) -> Result<(), Infallible>
     where
@@ -339,45 +369,56 @@ impl CredentialDetailView {
         let mut clipped = target.clipped(&row_rect);
 
         if is_focused {
-            crate::render::theme::draw_selection(row_rect, &mut clipped)?;
+            crate::render::theme::draw_selection(row_rect, theme, &mut clipped)?;
         } else {
             let divider = Rectangle::new(
                 Point::new(row_rect.top_left.x, row_rect.top_left.y + row_rect.size.height as i32 - 1),
                 Size::new(row_rect.size.width, 1),
             );
-            divider.into_styled(PrimitiveStyle::with_fill(palette::DIVIDER)).draw(&mut clipped)?;
+            divider.into_styled(PrimitiveStyle::with_fill(theme.palette.divider)).draw(&mut clipped)?;
         }
 
-        let label_font = font::label();
+        let label_font = theme.fonts.label();
         let label_x = row_rect.top_left.x + FIELD_SIDE_MARGIN;
-        let label_y = row_rect.top_left.y + FIELD_PADDING;
+        let label_y = row_rect.top_left.y + field_padding(theme.fonts);
         let _ = label_font.render_aligned(
             field.label(),
             Point::new(label_x, label_y),
             VerticalPosition::Top,
             HorizontalAlignment::Left,
-            FontColor::Transparent(palette::TEXT_SECONDARY),
+            FontColor::Transparent(theme.palette.text_secondary),
             &mut clipped,
         );
 
-        let value_y = row_rect.top_left.y + value_top_offset(&label_font);
+        let value_y = row_rect.top_left.y + value_top_offset(theme.fonts);
         let value_width = row_rect.size.width.saturating_sub((FIELD_SIDE_MARGIN as u32) * 2);
         let value_area = Rectangle::new(
             Point::new(row_rect.top_left.x + FIELD_SIDE_MARGIN, value_y),
-            Size::new(value_width, line_height(&field.value_font()) as u32),
+            Size::new(value_width, line_height(&field.value_font(theme.fonts)) as u32),
         );
 
         if field == Field::Password {
-            self.secret.render(value_area, item.password.as_str(), &mut clipped)?;
+            self.secret.render(value_area, item.password.as_str(), theme, &mut clipped)?;
         } else {
-            let value_font = field.value_font();
+            let value_font = field.value_font(theme.fonts);
+            let value = field.value(item);
+            let offset = if is_focused && field.scrolls() {
+                let overflow = text_width(&value_font, value).saturating_sub(value_width);
+                self.marquee.set_overflow(overflow);
+                self.marquee.offset(overflow)
+            } else {
+                0
+            };
+            if field == Field::Notes {
+                *self.shown_notes.borrow_mut() = visible_prefix(&value_font, value, value_width);
+            }
             let _ = value_font.render_aligned(
-                field.value(item),
-                value_area.top_left,
+                value,
+                value_area.top_left - Point::new(offset as i32, 0),
                 VerticalPosition::Top,
                 HorizontalAlignment::Left,
-                FontColor::Transparent(palette::TEXT_PRIMARY),
-                &mut clipped,
+                FontColor::Transparent(theme.palette.text_primary),
+                &mut clipped.clipped(&value_area),
             );
         }
 
@@ -385,8 +426,29 @@ impl CredentialDetailView {
     }
 }
 
+/// The part of `text` a one-line row `width` pixels wide shows: its first
+/// line, up to the last character that still fits whole.
+fn visible_prefix(font: &FontRenderer, text: &str, width: u32) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    let mut end = 0;
+    for (index, c) in line.char_indices() {
+        let next = index + c.len_utf8();
+        if text_width(font, &line[..next]) > width {
+            break;
+        }
+        end = next;
+    }
+    line[..end].to_string()
+}
+
+/// The full-screen note reader pushed by activating NOTES, titled with the
+/// credential's name so it's clear whose note it is.
+fn notes_screen(title: String, notes: String) -> Screen {
+    Screen::new(title, vec![Box::new(TextView::new(notes))]).with_hint("Rotate to scroll - Hold to go back")
+}
+
 impl Widget for CredentialDetailView {
-    fn measure(&self, constraints: Size) -> Size {
+    fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
         constraints
     }
 
@@ -418,15 +480,22 @@ impl Widget for CredentialDetailView {
             }
             FocusEvent::Activated => {
                 let store = self.store.borrow();
-                if let Some(item) = store.get(self.id) {
-                    let fields = available_fields(item);
-                    if let Some(index) = self.resolve_focus(fields.len()) {
-                        if fields[index] == Field::Password {
-                            self.secret.on_focus(FocusEvent::Activated);
-                        }
+                let Some(item) = store.get(self.id) else {
+                    return Action::None;
+                };
+                let fields = available_fields(item);
+                match self.resolve_focus(fields.len()).map(|index| fields[index]) {
+                    Some(Field::Password) => {
+                        self.secret.on_focus(FocusEvent::Activated);
+                        Action::None
                     }
+                    Some(Field::Notes) => {
+                        let title = item.name.clone();
+                        let notes = item.notes.clone().unwrap_or_default();
+                        Action::PushView(Box::new(move || notes_screen(title, notes)))
+                    }
+                    Some(Field::Username | Field::Website) | None => Action::None,
                 }
-                Action::None
             }
         }
     }
@@ -439,25 +508,43 @@ impl Widget for CredentialDetailView {
                 NavIntent::Next => self.move_focus(&fields, 1),
                 NavIntent::Prev => self.move_focus(&fields, -1),
                 NavIntent::NextN(n) => self.move_focus(&fields, i32::from(n)),
-                NavIntent::Activate | NavIntent::Back => {}
+                NavIntent::PrevN(n) => self.move_focus(&fields, -i32::from(n)),
+                NavIntent::Home => self.move_focus(&fields, -(fields.len() as i32)),
+                NavIntent::End => self.move_focus(&fields, fields.len() as i32),
+                NavIntent::Char(_) | NavIntent::Activate | NavIntent::Back => {}
             }
         }
         Action::None
     }
 
-    fn render(&self, area: Rectangle, target: &mut FrameBuffer565) -> Result<(), Infallible> {
+    /// Advances the focused value's marquee. Keyed by the focused field
+    /// only while it's one that [`Field::scrolls`] — focusing PASSWORD
+    /// keys it `None`, which also snaps any scrolled value back.
+    fn tick(&mut self, now: Instant) -> bool {
+        let key = {
+            let store = self.store.borrow();
+            store.get(self.id).filter(|_| self.focused).and_then(|item| {
+                let fields = available_fields(item);
+                self.resolve_focus(fields.len()).map(|index| fields[index]).filter(|field| field.scrolls())
+            })
+        };
+        self.marquee.tick(key, now)
+    }
+
+    fn render(&self, area: Rectangle, theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible> {
         let item = self.store.borrow().get(self.id).cloned();
         if let Some(item) = item {
-            return self.render_fields(area, &item, target);
+            return self.render_fields(area, &item, theme, target);
         }
 
         render_message(
             area,
             Some(icon::SHIELD),
-            palette::STATUS_ERROR,
-            "This item was removed",
-            palette::TEXT_PRIMARY,
-            Some("Hold to go back"),
+            theme.palette.status_error,
+            GONE_HEADLINE,
+            theme.palette.text_primary,
+            Some(GONE_SUBLINE),
+            theme,
             target,
         );
         Ok(())
@@ -478,7 +565,8 @@ impl Widget for CredentialDetailView {
                 let fields = available_fields(item);
                 match self.resolve_focus(fields.len()).and_then(|index| fields.get(index).copied()) {
                     Some(Field::Password) => self.secret.hint().to_string(),
-                    Some(Field::Username | Field::Website | Field::Notes) | None => {
+                    Some(Field::Notes) => "Press to read - Hold to go back".to_string(),
+                    Some(Field::Username | Field::Website) | None => {
                         "Rotate to switch fields - Hold to go back".to_string()
                     }
                 }
@@ -492,13 +580,42 @@ impl Widget for CredentialDetailView {
             status: Some(self.chrome_status()),
         })
     }
+
+    /// The credential's name over one node per present field, the focused
+    /// one selected. PASSWORD is a [`Role::SecretField`] whose value is the
+    /// mask until it's revealed (see [`SecretField::semantic_value`]);
+    /// NOTES carries only what its row showed in the last render, never
+    /// the rest of the note. The gone state is its message instead.
+    fn semantics(&self) -> Option<SemanticNode> {
+        let store = self.store.borrow();
+        let Some(item) = store.get(self.id) else {
+            return Some(SemanticNode::new(Role::Message, GONE_HEADLINE).with_value(GONE_SUBLINE));
+        };
+        let fields = available_fields(item);
+        let focused = self.resolve_focus(fields.len());
+        let children = fields
+            .iter()
+            .enumerate()
+            .map(|(index, &field)| {
+                let (role, value) = match field {
+                    Field::Password => (Role::SecretField, self.secret.semantic_value(field.value(item))),
+                    Field::Username | Field::Website => (Role::Field, field.value(item).to_string()),
+                    Field::Notes => (Role::Field, self.shown_notes.borrow().clone()),
+                };
+                SemanticNode::new(role, field.label()).with_value(value).with_selected(focused == Some(index))
+            })
+            .collect();
+        Some(SemanticNode::new(Role::Group, item.name.as_str()).with_children(children))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::render::Screen;
+    use crate::render::theme::Palette;
+    use crate::render::{Screen, MARQUEE_DWELL};
     use embedded_graphics::prelude::OriginDimensions;
+    use std::time::Duration;
 
     fn item(name: &str) -> VaultItem {
         VaultItem {
@@ -641,11 +758,11 @@ mod tests {
         let view = CredentialDetailView::new(store, id);
 
         let mut fb = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
 
-        let any_label_ink = fb.pixels().any(|p| p.1 == palette::TEXT_SECONDARY);
+        let any_label_ink = fb.pixels().any(|p| p.1 == Palette::DARK.text_secondary);
         assert!(any_label_ink, "field labels should render in the muted label color");
-        let any_value_ink = fb.pixels().any(|p| p.1 == palette::TEXT_PRIMARY);
+        let any_value_ink = fb.pixels().any(|p| p.1 == Palette::DARK.text_primary);
         assert!(any_value_ink, "field values should render in the primary text color");
     }
 
@@ -670,9 +787,9 @@ mod tests {
         assert_eq!(view.resolve_focus(4), Some(3));
 
         let mut fb = FrameBuffer565::new(320, 170);
-        view.render(short_area, &mut fb).unwrap();
+        view.render(short_area, &Theme::DARK, &mut fb).unwrap();
 
-        let any_focus_highlight = fb.pixels().any(|p| p.1 == palette::SURFACE_ELEVATED);
+        let any_focus_highlight = fb.pixels().any(|p| p.1 == Palette::DARK.surface_elevated);
         assert!(any_focus_highlight, "the focused (Notes) field must be scrolled into view, not left off-screen");
     }
 
@@ -688,9 +805,9 @@ mod tests {
         let full_view = CredentialDetailView::new(full_store, id);
 
         let mut fb_sparse = FrameBuffer565::new(320, 170);
-        sparse_view.render(AREA, &mut fb_sparse).unwrap();
+        sparse_view.render(AREA, &Theme::DARK, &mut fb_sparse).unwrap();
         let mut fb_full = FrameBuffer565::new(320, 170);
-        full_view.render(AREA, &mut fb_full).unwrap();
+        full_view.render(AREA, &Theme::DARK, &mut fb_full).unwrap();
 
         let sparse_pixels: Vec<_> = fb_sparse.pixels().map(|p| p.1).collect();
         let full_pixels: Vec<_> = fb_full.pixels().map(|p| p.1).collect();
@@ -704,9 +821,9 @@ mod tests {
         let view = CredentialDetailView::new(store, missing_id);
 
         let mut fb = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
 
-        let any_error_icon_ink = fb.pixels().any(|p| p.1 == palette::STATUS_ERROR);
+        let any_error_icon_ink = fb.pixels().any(|p| p.1 == Palette::DARK.status_error);
         assert!(any_error_icon_ink, "the gone-state icon should render in the error color");
     }
 
@@ -718,12 +835,12 @@ mod tests {
         let view = CredentialDetailView::new(Rc::clone(&store), id);
 
         let mut fb_present = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb_present).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_present).unwrap();
 
         store.borrow_mut().apply_sync_ok(vec![]); // deleted upstream
 
         let mut fb_gone = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb_gone).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_gone).unwrap();
 
         let present_pixels: Vec<_> = fb_present.pixels().map(|p| p.1).collect();
         let gone_pixels: Vec<_> = fb_gone.pixels().map(|p| p.1).collect();
@@ -787,10 +904,92 @@ mod tests {
         let navigator = crate::render::Navigator::new(screen);
 
         let mut fb = FrameBuffer565::new(320, 170);
-        navigator.render(&mut fb).unwrap();
+        navigator.render(&Theme::DARK, &mut fb).unwrap();
         assert_eq!(fb.size(), Size::new(320, 170));
 
-        let any_value_ink = fb.pixels().any(|p| p.1 == palette::TEXT_PRIMARY);
+        let any_value_ink = fb.pixels().any(|p| p.1 == Palette::DARK.text_primary);
         assert!(any_value_ink);
     }
+
+    #[test]
+    fn activating_notes_pushes_a_full_screen_reader_with_the_whole_note() {
+        let mut full = full_item("GitHub");
+        full.notes = Some("line\n".repeat(40));
+        let id = full.id;
+        let store = store_with(vec![full]);
+        let mut view = CredentialDetailView::new(store, id);
+        view.on_focus(FocusEvent::Gained);
+        view.on_intent(NavIntent::NextN(3)); // Notes, the last field
+
+        let hint = view.chrome_contribution().and_then(|c| c.hint);
+        assert_eq!(hint.as_deref(), Some("Press to read - Hold to go back"));
+        let Action::PushView(build) = view.on_focus(FocusEvent::Activated) else {
+            panic!("activating NOTES should push the reader");
+        };
+        let reader = build();
+        assert_eq!(reader.title, "GitHub");
+        assert_eq!(reader.widgets().len(), 1);
+    }
+
+    #[test]
+    fn the_notes_node_exposes_only_what_its_row_shows() {
+        let mut full = full_item("GitHub");
+        let first_line = "recovery codes are in the safe, ask the office manager for the key";
+        full.notes = Some(format!("{first_line}\nsecond line with the combination 12-34-56"));
+        let id = full.id;
+        let view = CredentialDetailView::new(store_with(vec![full]), id);
+        let notes_value = |view: &CredentialDetailView| {
+            let tree = view.semantics().unwrap();
+            tree.children.into_iter().find(|node| node.label == "NOTES").and_then(|node| node.value).unwrap()
+        };
+        assert_eq!(notes_value(&view), "");
+
+        let mut fb = FrameBuffer565::new(320, 170);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        let shown = notes_value(&view);
+        assert!(!shown.is_empty());
+        assert!(first_line.starts_with(&shown) && shown.len() < first_line.len(), "{shown:?}");
+        assert!(!shown.contains("combination"));
+    }
+
+    #[test]
+    fn a_long_focused_username_scrolls_after_the_dwell() {
+        let start = Instant::now();
+        let long = VaultItem {
+            username: "firstname.lastname+newsletters@a-rather-long-domain.example.com".to_string(),
+            ..item("GitHub")
+        };
+        let id = long.id;
+        let mut view = CredentialDetailView::new(store_with(vec![long]), id);
+        view.on_focus(FocusEvent::Gained);
+        let mut fb = FrameBuffer565::new(320, 170);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+
+        assert!(!view.tick(start));
+        assert!(view.tick(start + MARQUEE_DWELL + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn a_revealed_password_never_scrolls_however_long_it_is() {
+        let start = Instant::now();
+        let long = VaultItem {
+            password: "correct-horse-battery-staple-".repeat(4),
+            ..item("GitHub")
+        };
+        let id = long.id;
+        let mut view = CredentialDetailView::new(store_with(vec![long]), id);
+        view.on_focus(FocusEvent::Gained);
+        view.on_intent(NavIntent::Next); // Username -> Password
+        view.on_focus(FocusEvent::Activated);
+        assert!(view.secret.is_revealed());
+
+        let mut fb = FrameBuffer565::new(320, 170);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        let revealed = fb.pixels().collect::<Vec<_>>();
+        for secs in 0..10 {
+            assert!(!view.tick(start + Duration::from_secs(secs)));
+        }
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixels().collect::<Vec<_>>(), revealed);
+    }
 }
@@ -17,6 +17,14 @@
 //!   the ADR: an error must not blank a previously-populated list).
 //! - Draws a right-edge scrollbar and a full-row focus block (shared
 //!   [`crate::render::draw_focus_block`] helper) on the selected row.
+//! - Optionally follows the user's [`Settings`] (`with_settings`), also
+//!   read live: the sort order applies to the visible list only (the
+//!   store keeps sync order) and the sublabel picks what goes under each
+//!   name. Selection is by id, so re-sorting never moves the cursor off
+//!   the selected credential.
+//! - Scrolls the selected row's name and sublabel horizontally when they
+//!   don't fit (a [`Marquee`] keyed by the selected id), driven by
+//!   [`Widget::tick`] so the cycle restarts whenever the selection moves.
 //!
 //! ## The "focus-init runs once" gotcha
 //!
@@ -56,34 +64,27 @@
 use std::cell::{Cell, RefCell};
 use std::convert::Infallible;
 use std::rc::Rc;
+use std::time::Instant;
 
 use embedded_graphics::{
     draw_target::DrawTargetExt,
     pixelcolor::Rgb565,
-    prelude::{Point, Primitive, Size},
-    primitives::{PrimitiveStyle, Rectangle},
-    Drawable,
+    prelude::{Point, Size},
+    primitives::Rectangle,
 };
 use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
 use uuid::Uuid;
 
+use crate::input::type_ahead::TypeAhead;
 use crate::input::NavIntent;
-use crate::render::list::{draw_row, name_top_offset, reconcile_top_index, username_top_offset};
-use crate::render::theme::{font, icon, palette};
-use crate::render::{Action, ChromeContribution, ChromeStatus, FocusEvent, FrameBuffer565, Widget, ROW_HEIGHT};
+use crate::render::list::{draw_row, label_overflow, name_top_offset, reconcile_top_index, row_height, username_top_offset, LabelOffsets};
+use crate::render::marquee::Marquee;
+use crate::render::theme::{draw_scrollbar, font, icon, Theme, SCROLLBAR_WIDTH};
+use crate::render::{Action, ChromeContribution, ChromeStatus, FocusEvent, FrameBuffer565, Role, SemanticNode, Widget};
+use crate::settings::{Settings, SortOrder, Sublabel};
 use crate::vault_item::VaultItem;
 use crate::vault_store::{SyncStatus, VaultStore};
 
-/// Width, in pixels, reserved at the content area's right edge for the
-/// scrollbar track/thumb. Reserved unconditionally (not just when the list
-/// overflows) so the row text's right boundary doesn't shift depending on
-/// item count.
-const SCROLLBAR_WIDTH: u32 = 3;
-
-/// Minimum thumb height, so a very long list doesn't shrink the thumb to
-/// an unclickable/invisible sliver.
-const MIN_THUMB_HEIGHT: u32 = 4;
-
 /// Extra top padding (beyond the label's own baseline offset) before the
 /// first line of an empty/waiting/error content message, so it isn't
 /// glued to the chrome's title-bar boundary.
@@ -108,6 +109,11 @@ const MESSAGE_ICON_GAP: i32 = 12;
 /// display-only row.
 type OnActivate = Box<dyn Fn(Uuid) -> Action>;
 
+/// Callback invoked on `NavIntent::Back` (encoder long press). The root
+/// list is the one screen `Back` can't pop, so the `Navigator` forwards it
+/// here instead; `App` uses it to open the settings screen.
+type OnBack = Box<dyn Fn() -> Action>;
+
 /// The derived "what should the content region show" state, computed from
 /// live item count + [`SyncStatus`] on every render — never cached.
 #[derive(Debug, PartialEq, Eq)]
@@ -126,6 +132,29 @@ enum ContentState<'a> {
     Error(&'a str),
 }
 
+impl<'a> ContentState<'a> {
+    /// The headline and subline [`render_message`] shows for a non-list
+    /// state — `None` for [`ContentState::List`], which shows rows instead.
+    /// Shared by `render` and `semantics`, so the tree reads what's drawn.
+    fn message(&self) -> Option<(&'static str, Option<&'a str>)> {
+        match *self {
+            ContentState::List => None,
+            ContentState::Waiting => Some(("Waiting for sync...", None)),
+            ContentState::Empty => Some(("No credentials yet", Some("Sync from your companion app"))),
+            ContentState::Error(message) => Some(("Sync error", Some(message))),
+        }
+    }
+}
+
+/// The second line under `item`'s name for `sublabel`, if any.
+fn secondary(item: &VaultItem, sublabel: Sublabel) -> Option<&str> {
+    match sublabel {
+        Sublabel::Username => Some(item.username.as_str()),
+        Sublabel::Website => item.uri.as_deref(),
+        Sublabel::Nothing => None,
+    }
+}
+
 fn content_state(item_count: usize, status: Option<&SyncStatus>) -> ContentState<'_> {
     if item_count > 0 {
         return ContentState::List;
@@ -167,6 +196,16 @@ pub struct CredentialListView {
     top_index: Cell<usize>,
     focused: bool,
     on_activate: Option<OnActivate>,
+    on_back: Option<OnBack>,
+    /// The preferences driving sort order and sublabel; `None` means sync
+    /// order and usernames, as before settings existed.
+    settings: Option<Rc<RefCell<Settings>>>,
+    /// Horizontal scroll of the selected row's labels, keyed by the
+    /// selected id (see `Widget::tick` below).
+    marquee: Marquee<Uuid>,
+    /// The prefix typed via `NavIntent::Char` since the last other intent,
+    /// matched against credential names in display order.
+    type_ahead: TypeAhead,
 }
 
 impl CredentialListView {
@@ -179,6 +218,10 @@ impl CredentialListView {
             top_index: Cell::new(0),
             focused: false,
             on_activate: None,
+            on_back: None,
+            settings: None,
+            marquee: Marquee::default(),
+            type_ahead: TypeAhead::new(),
         }
     }
 
@@ -196,6 +239,40 @@ impl CredentialListView {
         self
     }
 
+    /// Registers a callback invoked on `NavIntent::Back`. See [`OnBack`].
+    #[must_use]
+    pub fn on_back(mut self, callback: impl Fn() -> Action + 'static) -> Self {
+        self.on_back = Some(Box::new(callback));
+        self
+    }
+
+    /// Follows `settings`' sort order and sublabel, read live on every
+    /// render and input.
+    #[must_use]
+    pub fn with_settings(mut self, settings: Rc<RefCell<Settings>>) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// The store's items in display order: as synced, or sorted per
+    /// [`Settings::sort_order`]. A stable sort, so equal names keep their
+    /// sync order.
+    fn visible_items(&self) -> Vec<VaultItem> {
+        let mut items = self.store.borrow().items().to_vec();
+        if self.sort_order() == SortOrder::Name {
+            items.sort_by_cached_key(|item| item.name.to_lowercase());
+        }
+        items
+    }
+
+    fn sort_order(&self) -> SortOrder {
+        self.settings.as_ref().map_or(SortOrder::AsSynced, |s| s.borrow().sort_order)
+    }
+
+    fn sublabel(&self) -> Sublabel {
+        self.settings.as_ref().map_or(Sublabel::Username, |s| s.borrow().sublabel)
+    }
+
     /// The number of credentials currently in the store, read live.
     /// Exposed for chrome/readout consumers (e.g. bead
     /// `ai-bitwarden-hw-key-0v8.5`'s "N of M" indicator).
@@ -208,8 +285,7 @@ impl CredentialListView {
     /// against the store. `None` if the store has no items.
     #[must_use]
     pub fn selected_index(&self) -> Option<usize> {
-        let store = self.store.borrow();
-        self.resolve_selection(store.items())
+        self.resolve_selection(&self.visible_items())
     }
 
     /// Re-resolves `selected_id` against `items` (the *live* store
@@ -268,7 +344,20 @@ impl CredentialListView {
         self.last_index.set(next);
     }
 
-    fn render_list(&self, area: Rectangle, items: &[VaultItem], target: &mut FrameBuffer565) -> Result<(), Infallible> {
+    /// Jumps to the first credential whose name starts with the typed
+    /// prefix (see [`TypeAhead::push`]); leaves the selection alone when
+    /// nothing matches.
+    fn type_ahead(&mut self, items: &[VaultItem], c: char) {
+        let current = self.resolve_selection(items).unwrap_or(0);
+        let names = items.iter().map(|item| item.name.as_str());
+        if let Some(index) = self.type_ahead.push(c, names, current) {
+            self.selected_id.set(Some(items[index].id));
+            self.last_index.set(index);
+        }
+    }
+
+    fn render_list(&self, area: Rectangle, items: &[VaultItem], theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible> {
+        let row_height = row_height(theme.fonts);
         let selected = self.resolve_selection(items);
 
         let rows_width = area.size.width.saturating_sub(SCROLLBAR_WIDTH);
@@ -278,18 +367,19 @@ impl CredentialListView {
         // `reconcile_top_index`'s doc comment for the full rule and why
         // this reconciliation happens here, in `render`, rather than in
         // `on_intent` (which has no viewport dimensions to work with).
-        let visible_rows = (area.size.height / ROW_HEIGHT) as usize;
+        let visible_rows = (area.size.height / row_height) as usize;
         let top = reconcile_top_index(self.top_index.get(), selected.unwrap_or(0), visible_rows, items.len());
         self.top_index.set(top);
-        let scroll = top as u32 * ROW_HEIGHT;
+        let scroll = top as u32 * row_height;
+        let sublabel = self.sublabel();
 
         {
             let mut clipped = target.clipped(&rows_area);
 
             for (index, item) in items.iter().enumerate() {
-                let row_top = rows_area.top_left.y + (index as u32 * ROW_HEIGHT) as i32 - scroll as i32;
+                let row_top = rows_area.top_left.y + (index as u32 * row_height) as i32 - scroll as i32;
 
-                if row_top + ROW_HEIGHT as i32 <= rows_area.top_left.y
+                if row_top + row_height as i32 <= rows_area.top_left.y
                     || row_top >= rows_area.top_left.y + rows_area.size.height as i32
                 {
                     continue;
@@ -297,22 +387,35 @@ impl CredentialListView {
 
                 let row_rect = Rectangle::new(
                     Point::new(rows_area.top_left.x, row_top),
-                    Size::new(rows_area.size.width, ROW_HEIGHT),
+                    Size::new(rows_area.size.width, row_height),
                 );
 
                 let row_selected = self.focused && selected == Some(index);
+                let secondary = secondary(item, sublabel);
+                let offsets = if row_selected {
+                    let overflow = label_overflow(row_rect.size.width, item.name.as_str(), secondary, theme.fonts);
+                    self.marquee.set_overflow(overflow.max());
+                    LabelOffsets {
+                        name: self.marquee.offset(overflow.name),
+                        username: self.marquee.offset(overflow.username),
+                    }
+                } else {
+                    LabelOffsets::default()
+                };
                 draw_row(
                     &mut clipped,
                     row_rect,
                     item.name.as_str(),
-                    Some(item.username.as_str()),
+                    secondary,
+                    offsets,
                     row_selected,
                     !row_selected,
+                    theme,
                 )?;
             }
         }
 
-        render_scrollbar(area, items.len(), area.size.height, scroll, target)
+        draw_scrollbar(area, items.len() as u32 * row_height, scroll, theme, target)
     }
 
     /// The right-aligned title-bar readout (e.g. `"2 / 5"`), or `None` when
@@ -340,47 +443,6 @@ impl CredentialListView {
     }
 }
 
-/// Draws the right-edge scrollbar (thin track + proportional thumb) for a
-/// list of `item_count` rows scrolled by `scroll` pixels within a
-/// `viewport_height`-px-tall `area`. A no-op if the whole list already
-/// fits the viewport — there's nothing to scroll, so no thumb (or even an
-/// empty track) is drawn.
-fn render_scrollbar(
-    area: Rectangle,
-    item_count: usize,
-    viewport_height: u32,
-    scroll: u32,
-    target: &mut FrameBuffer565,
-) -> Result<(), Infallible> {
-    let total_height = item_count as u32 * ROW_HEIGHT;
-    if viewport_height == 0 || total_height <= viewport_height {
-        return Ok(());
-    }
-
-    let track_x = area.top_left.x + area.size.width as i32 - SCROLLBAR_WIDTH as i32;
-
-    let track = Rectangle::new(Point::new(track_x, area.top_left.y), Size::new(SCROLLBAR_WIDTH, viewport_height));
-    track.into_styled(PrimitiveStyle::with_fill(palette::DIVIDER)).draw(target)?;
-
-    let thumb_height = (u64::from(viewport_height) * u64::from(viewport_height) / u64::from(total_height))
-        .max(u64::from(MIN_THUMB_HEIGHT)) as u32;
-    let thumb_height = thumb_height.min(viewport_height);
-
-    let max_thumb_top = viewport_height - thumb_height;
-    let scrollable_range = total_height - viewport_height;
-    let thumb_top = if max_thumb_top == 0 || scrollable_range == 0 {
-        0
-    } else {
-        (u64::from(scroll) * u64::from(max_thumb_top) / u64::from(scrollable_range)) as u32
-    };
-
-    let thumb = Rectangle::new(
-        Point::new(track_x, area.top_left.y + thumb_top as i32),
-        Size::new(SCROLLBAR_WIDTH, thumb_height),
-    );
-    thumb.into_styled(PrimitiveStyle::with_fill(palette::TEXT_SECONDARY)).draw(target)
-}
-
 /// Draws a centered content message — an optional large icon, a headline,
 /// and an optional subline — for the non-list content states (waiting/
 /// empty/error). Not list-specific, but kept `pub(crate)` to this module
@@ -405,6 +467,7 @@ fn render_scrollbar(
 /// nothing left that can fail here to report. Callers in `Widget::render`
 /// wrap this call with an explicit `Ok(())` to match the trait's
 /// signature.
+#[allow(clippy::too_many_arguments)] // one message's worth of independent inputs; a struct would only rename them
 pub(crate) fn render_message(
     area: Rectangle,
     icon: Option<char>,
@@ -412,14 +475,22 @@ pub(crate) fn render_message(
     headline: &str,
     headline_color: Rgb565,
     subline: Option<&str>,
+    theme: &Theme,
     target: &mut FrameBuffer565,
 ) {
     let mut clipped = target.clipped(&area);
     let center_x = area.top_left.x + area.size.width as i32 / 2;
 
-    let text_top = area.top_left.y
-        + MESSAGE_TOP_PADDING
-        + if icon.is_some() { MESSAGE_ICON_SIZE + MESSAGE_ICON_GAP } else { 0 };
+    // On a content area too short for the full layout (a 128x32 strip
+    // panel's), the icon and then the top padding give way: the headline
+    // is the message.
+    let text_height = username_top_offset(theme.fonts) + theme.fonts.username_line_footprint();
+    let height = area.size.height as i32;
+    let icon = icon.filter(|_| height >= MESSAGE_TOP_PADDING + MESSAGE_ICON_SIZE + MESSAGE_ICON_GAP + text_height);
+    let top_padding = if height >= MESSAGE_TOP_PADDING + text_height { MESSAGE_TOP_PADDING } else { 0 };
+
+    let text_top =
+        area.top_left.y + top_padding + if icon.is_some() { MESSAGE_ICON_SIZE + MESSAGE_ICON_GAP } else { 0 };
 
     if let Some(icon_char) = icon {
         let mut buf = [0_u8; 4];
@@ -434,9 +505,9 @@ pub(crate) fn render_message(
         );
     }
 
-    let _ = font::name().render_aligned(
+    let _ = theme.fonts.name().render_aligned(
         headline,
-        Point::new(center_x, text_top + name_top_offset()),
+        Point::new(center_x, text_top + name_top_offset(theme.fonts)),
         VerticalPosition::Top,
         HorizontalAlignment::Center,
         FontColor::Transparent(headline_color),
@@ -444,19 +515,19 @@ pub(crate) fn render_message(
     );
 
     if let Some(subline) = subline {
-        let _ = font::username().render_aligned(
+        let _ = theme.fonts.username().render_aligned(
             subline,
-            Point::new(center_x, text_top + username_top_offset()),
+            Point::new(center_x, text_top + username_top_offset(theme.fonts)),
             VerticalPosition::Top,
             HorizontalAlignment::Center,
-            FontColor::Transparent(palette::TEXT_SECONDARY),
+            FontColor::Transparent(theme.palette.text_secondary),
             &mut clipped,
         );
     }
 }
 
 impl Widget for CredentialListView {
-    fn measure(&self, constraints: Size) -> Size {
+    fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
         constraints
     }
 
@@ -479,11 +550,9 @@ impl Widget for CredentialListView {
                 Action::None
             }
             FocusEvent::Activated => {
-                let store = self.store.borrow();
-                let items = store.items();
-                let index = self.resolve_selection(items);
+                let items = self.visible_items();
+                let index = self.resolve_selection(&items);
                 let id = index.map(|i| items[i].id);
-                drop(store);
 
                 match (id, &self.on_activate) {
                     (Some(id), Some(callback)) => callback(id),
@@ -494,61 +563,69 @@ impl Widget for CredentialListView {
     }
 
     fn on_intent(&mut self, intent: NavIntent) -> Action {
-        let store = self.store.borrow();
-        let items = store.items();
+        let items = self.visible_items();
+        if !matches!(intent, NavIntent::Char(_)) {
+            self.type_ahead.reset();
+        }
         match intent {
-            NavIntent::Next => self.move_selection(items, 1),
-            NavIntent::Prev => self.move_selection(items, -1),
-            NavIntent::NextN(n) => self.move_selection(items, i32::from(n)),
-            NavIntent::Activate | NavIntent::Back => {}
+            NavIntent::Next => self.move_selection(&items, 1),
+            NavIntent::Prev => self.move_selection(&items, -1),
+            NavIntent::NextN(n) => self.move_selection(&items, i32::from(n)),
+            NavIntent::PrevN(n) => self.move_selection(&items, -i32::from(n)),
+            NavIntent::Home => self.move_selection(&items, -(items.len() as i32)),
+            NavIntent::End => self.move_selection(&items, items.len() as i32),
+            NavIntent::Char(c) => self.type_ahead(&items, c),
+            NavIntent::Back => {
+                if let Some(callback) = &self.on_back {
+                    return callback();
+                }
+            }
+            NavIntent::Activate => {}
         }
         Action::None
     }
 
-    fn render(&self, area: Rectangle, target: &mut FrameBuffer565) -> Result<(), Infallible> {
-        let store = self.store.borrow();
-        let items = store.items().to_vec();
-        let status = store.status().cloned();
-        drop(store);
+    /// Advances the selected row's marquee. Keyed by `selected_id` as last
+    /// resolved by `render` (cheap — no store read per tick); a selection
+    /// move restarts the cycle, and losing focus stops it.
+    fn tick(&mut self, now: Instant) -> bool {
+        let key = if self.focused { self.selected_id.get() } else { None };
+        self.marquee.tick(key, now)
+    }
 
-        match content_state(items.len(), status.as_ref()) {
-            ContentState::List => self.render_list(area, &items, target),
-            ContentState::Waiting => {
-                render_message(area, None, palette::BRAND_BRIGHT, "Waiting for sync...", palette::TEXT_PRIMARY, None, target);
-                Ok(())
-            }
-            ContentState::Empty => {
-                render_message(
-                    area,
-                    Some(icon::SHIELD),
-                    palette::BRAND_BRIGHT,
-                    "No credentials yet",
-                    palette::TEXT_PRIMARY,
-                    Some("Sync from your companion app"),
-                    target,
-                );
-                Ok(())
-            }
-            ContentState::Error(message) => {
-                render_message(area, None, palette::BRAND_BRIGHT, "Sync error", palette::STATUS_ERROR, Some(message), target);
-                Ok(())
-            }
-        }
+    fn render(&self, area: Rectangle, theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible> {
+        let items = self.visible_items();
+        let status = self.store.borrow().status().cloned();
+
+        let state = content_state(items.len(), status.as_ref());
+        let Some((headline, subline)) = state.message() else {
+            return self.render_list(area, &items, theme, target);
+        };
+        let (message_icon, headline_color) = match state {
+            ContentState::Empty => (Some(icon::SHIELD), theme.palette.text_primary),
+            ContentState::Error(_) => (None, theme.palette.status_error),
+            ContentState::Waiting | ContentState::List => (None, theme.palette.text_primary),
+        };
+        render_message(area, message_icon, theme.palette.brand_bright, headline, headline_color, subline, theme, target);
+        Ok(())
     }
 
     /// Always contributes: a right-aligned "N / M" readout (when there are
     /// items to count), a title-bar status dot derived from the store's
     /// [`SyncStatus`], and contextual hint text that differs between "there
-    /// is a list to browse" and "there is nothing to browse, go back." This
-    /// widget is unconditionally focusable (see `is_focusable`'s doc
-    /// comment) and is the only widget on its screen, so its contribution
-    /// is shown on every frame regardless of item count/content state.
+    /// is a list to browse" and "there is nothing to browse, go back" —
+    /// with "go back" reading "Hold for settings" instead once an
+    /// [`Self::on_back`] callback is wired. This widget is unconditionally
+    /// focusable (see `is_focusable`'s doc comment) and is the only widget
+    /// on its screen, so its contribution is shown on every frame
+    /// regardless of item count/content state.
     fn chrome_contribution(&self) -> Option<ChromeContribution> {
         let has_items = self.item_count() > 0;
-        let hint = if has_items {
-            "Rotate to browse - Press to open"
-        } else {
-            "Hold to go back"
+        let hint = match (has_items, self.on_back.is_some()) {
+            (true, true) => "Rotate to browse - Press to open - Hold for settings",
+            (true, false) => "Rotate to browse - Press to open",
+            (false, true) => "Hold for settings",
+            (false, false) => "Hold to go back",
         };
 
         Some(ChromeContribution {
@@ -558,14 +635,40 @@ impl Widget for CredentialListView {
             status: Some(self.chrome_status()),
         })
     }
+
+    /// The rows in display order, named and sublabelled as drawn, with the
+    /// live selection marked — or, with no rows to show, the content-state
+    /// message in their place.
+    fn semantics(&self) -> Option<SemanticNode> {
+        let items = self.visible_items();
+        let status = self.store.borrow().status().cloned();
+        if let Some((headline, subline)) = content_state(items.len(), status.as_ref()).message() {
+            return Some(SemanticNode::new(Role::Message, headline).with_optional_value(subline));
+        }
+
+        let selected = self.resolve_selection(&items);
+        let sublabel = self.sublabel();
+        let rows = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                SemanticNode::new(Role::ListItem, item.name.as_str())
+                    .with_optional_value(secondary(item, sublabel))
+                    .with_selected(selected == Some(index))
+            })
+            .collect();
+        Some(SemanticNode::new(Role::List, "").with_children(rows))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::render::chrome::compute_chrome;
-    use crate::render::Navigator;
+    use crate::render::theme::Palette;
+    use crate::render::{LayoutProfile, Navigator, MARQUEE_DWELL, ROW_HEIGHT};
     use embedded_graphics::prelude::OriginDimensions;
+    use std::time::Duration;
 
     fn item(name: &str) -> VaultItem {
         VaultItem {
@@ -693,15 +796,15 @@ mod tests {
         store.borrow_mut().apply_sync_ok(vec![item("GitHub"), item("AWS")]);
 
         let mut fb = FrameBuffer565::new(320, 170);
-        navigator.render(&mut fb).unwrap();
+        navigator.render(&Theme::DARK, &mut fb).unwrap();
 
-        let chrome = compute_chrome(fb.size());
+        let chrome = compute_chrome(fb.size(), &LayoutProfile::for_panel(fb.size()));
         // x=250: past the 4px selection accent bar and past these short
         // labels' text, so it samples the row's plain elevated fill.
         let row0_highlight = fb.pixel(Point::new(250, chrome.content.top_left.y + 2));
         assert_eq!(
             row0_highlight,
-            palette::SURFACE_ELEVATED,
+            Palette::DARK.surface_elevated,
             "row 0 should be highlighted as selected immediately once items exist, \
              with no extra focus-cycling input needed"
         );
@@ -709,9 +812,9 @@ mod tests {
         // dispatch is exercised too, proving the widget still responds
         // normally to input after the empty->populated transition.
         navigator.dispatch(NavIntent::Next);
-        navigator.render(&mut fb).unwrap();
+        navigator.render(&Theme::DARK, &mut fb).unwrap();
         let row0_after_move = fb.pixel(Point::new(250, chrome.content.top_left.y + 2));
-        assert_ne!(row0_after_move, palette::SURFACE_ELEVATED);
+        assert_ne!(row0_after_move, Palette::DARK.surface_elevated);
     }
 
     #[test]
@@ -719,14 +822,14 @@ mod tests {
         let store = Rc::new(RefCell::new(VaultStore::new()));
         let view = CredentialListView::new(store);
         let mut fb = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
 
         // No list rows: no selection-fill pixel anywhere, but the message
         // did draw something (primary-text-colored ink) into the content
         // area.
-        let any_selected_fill = fb.pixels().any(|p| p.1 == palette::SURFACE_ELEVATED);
+        let any_selected_fill = fb.pixels().any(|p| p.1 == Palette::DARK.surface_elevated);
         assert!(!any_selected_fill);
-        let any_headline_ink = fb.pixels().any(|p| p.1 == palette::TEXT_PRIMARY);
+        let any_headline_ink = fb.pixels().any(|p| p.1 == Palette::DARK.text_primary);
         assert!(any_headline_ink, "the waiting message's headline text should have drawn something");
     }
 
@@ -735,11 +838,11 @@ mod tests {
         let never_synced = Rc::new(RefCell::new(VaultStore::new()));
         let view_waiting = CredentialListView::new(never_synced);
         let mut fb_waiting = FrameBuffer565::new(320, 170);
-        view_waiting.render(AREA, &mut fb_waiting).unwrap();
+        view_waiting.render(AREA, &Theme::DARK, &mut fb_waiting).unwrap();
 
         let view_empty = CredentialListView::new(store_with(vec![]));
         let mut fb_empty = FrameBuffer565::new(320, 170);
-        view_empty.render(AREA, &mut fb_empty).unwrap();
+        view_empty.render(AREA, &Theme::DARK, &mut fb_empty).unwrap();
 
         let waiting_pixels: Vec<Rgb565> = fb_waiting.pixels().map(|p| p.1).collect();
         let empty_pixels: Vec<Rgb565> = fb_empty.pixels().map(|p| p.1).collect();
@@ -752,9 +855,9 @@ mod tests {
         store.borrow_mut().apply_sync_err("network unreachable".to_string());
         let view = CredentialListView::new(store);
         let mut fb = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
 
-        let any_error_color = fb.pixels().any(|p| p.1 == palette::STATUS_ERROR);
+        let any_error_color = fb.pixels().any(|p| p.1 == Palette::DARK.status_error);
         assert!(any_error_color, "the error headline should render in its distinct color");
     }
 
@@ -769,11 +872,11 @@ mod tests {
         let view = CredentialListView::new(Rc::clone(&store));
 
         let mut fb_synced = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb_synced).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_synced).unwrap();
 
         store.borrow_mut().apply_sync_err("network unreachable".to_string());
         let mut fb_error = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb_error).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_error).unwrap();
 
         let synced_pixels: Vec<Rgb565> = fb_synced.pixels().map(|p| p.1).collect();
         let error_pixels: Vec<Rgb565> = fb_error.pixels().map(|p| p.1).collect();
@@ -786,12 +889,12 @@ mod tests {
         let view = CredentialListView::new(Rc::clone(&store));
 
         let mut fb_before = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb_before).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_before).unwrap();
 
         store.borrow_mut().apply_sync_ok(vec![item("GitHub")]);
 
         let mut fb_after = FrameBuffer565::new(320, 170);
-        view.render(AREA, &mut fb_after).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_after).unwrap();
 
         let before: Vec<Rgb565> = fb_before.pixels().map(|p| p.1).collect();
         let after: Vec<Rgb565> = fb_after.pixels().map(|p| p.1).collect();
@@ -818,6 +921,49 @@ mod tests {
         assert!(matches!(action, Action::PopView));
     }
 
+    #[test]
+    fn name_sort_reorders_the_visible_list_and_the_selection_follows_the_credential() {
+        let settings = Rc::new(RefCell::new(Settings::default()));
+        let store = store_with(vec![item("zeta"), item("Alpha"), item("mu")]);
+        let mut view = CredentialListView::new(store).with_settings(Rc::clone(&settings));
+        view.on_intent(NavIntent::Next); // "Alpha", in sync order
+        assert_eq!(view.selected_index(), Some(1));
+
+        settings.borrow_mut().sort_order = SortOrder::Name;
+        assert_eq!(view.selected_index(), Some(0), "Alpha sorts first, case-insensitively");
+        let names: Vec<String> = view.visible_items().into_iter().map(|i| i.name).collect();
+        assert_eq!(names, ["Alpha", "mu", "zeta"]);
+    }
+
+    #[test]
+    fn type_ahead_matches_names_in_display_order_and_home_end_reach_the_ends() {
+        let settings = Rc::new(RefCell::new(Settings { sort_order: SortOrder::Name, ..Settings::default() }));
+        let store = store_with(vec![item("zeta"), item("GitLab"), item("Alpha"), item("github")]);
+        let mut view = CredentialListView::new(store).with_settings(settings);
+
+        view.on_intent(NavIntent::Char('G'));
+        assert_eq!(view.selected_index(), Some(1), "github sorts before GitLab");
+        view.on_intent(NavIntent::Char('i'));
+        view.on_intent(NavIntent::Char('t'));
+        view.on_intent(NavIntent::Char('l'));
+        assert_eq!(view.selected_index(), Some(2));
+
+        view.on_intent(NavIntent::End);
+        assert_eq!(view.selected_index(), Some(3));
+        view.on_intent(NavIntent::Home);
+        assert_eq!(view.selected_index(), Some(0));
+    }
+
+    #[test]
+    fn back_runs_the_on_back_callback_and_switches_the_hint() {
+        let mut view = CredentialListView::new(store_with(vec![item("GitHub")]));
+        assert!(matches!(view.on_intent(NavIntent::Back), Action::None));
+        view = view.on_back(|| Action::PopView);
+        assert!(matches!(view.on_intent(NavIntent::Back), Action::PopView));
+        let hint = view.chrome_contribution().unwrap().hint.unwrap();
+        assert!(hint.ends_with("Hold for settings"), "{hint}");
+    }
+
     #[test]
     fn moving_selection_up_within_the_visible_window_does_not_scroll_the_list() {
         // The bead 47g repro, at the actual production widget (not just
@@ -838,7 +984,7 @@ mod tests {
         assert_eq!(view.selected_index(), Some(4));
 
         let mut fb_a = FrameBuffer565::new(320, 150);
-        view.render(AREA, &mut fb_a).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_a).unwrap();
         let top_after_scrolling_down = view.top_index.get();
         assert_eq!(top_after_scrolling_down, 2, "sanity check: the window should have scrolled to keep row 4 visible");
 
@@ -857,14 +1003,14 @@ mod tests {
         let row4_fill_sample = Point::new(250, row4_top + 2);
         let row3_fill_sample = Point::new(250, row3_top + 2);
 
-        assert_eq!(fb_a.pixel(row4_fill_sample), palette::SURFACE_ELEVATED, "row 4 should be selected in frame A");
-        assert_ne!(fb_a.pixel(row3_fill_sample), palette::SURFACE_ELEVATED, "row 3 should not be selected in frame A");
+        assert_eq!(fb_a.pixel(row4_fill_sample), Palette::DARK.surface_elevated, "row 4 should be selected in frame A");
+        assert_ne!(fb_a.pixel(row3_fill_sample), Palette::DARK.surface_elevated, "row 3 should not be selected in frame A");
 
         view.on_intent(NavIntent::Prev); // selected=3, still within [2, 5)
         assert_eq!(view.selected_index(), Some(3));
 
         let mut fb_b = FrameBuffer565::new(320, 150);
-        view.render(AREA, &mut fb_b).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb_b).unwrap();
 
         assert_eq!(
             view.top_index.get(),
@@ -876,8 +1022,8 @@ mod tests {
             fb_b.pixel(non_selected_row_sample),
             "a non-selected, still-visible row's pixels must not shift when the selection moves within the window"
         );
-        assert_ne!(fb_b.pixel(row4_fill_sample), palette::SURFACE_ELEVATED, "row 4 should no longer be selected in frame B");
-        assert_eq!(fb_b.pixel(row3_fill_sample), palette::SURFACE_ELEVATED, "row 3 should now be selected in frame B -- the highlight moved");
+        assert_ne!(fb_b.pixel(row4_fill_sample), Palette::DARK.surface_elevated, "row 4 should no longer be selected in frame B");
+        assert_eq!(fb_b.pixel(row3_fill_sample), Palette::DARK.surface_elevated, "row 3 should now be selected in frame B -- the highlight moved");
     }
 
     #[test]
@@ -888,21 +1034,56 @@ mod tests {
         // A tall-enough list that it overflows the viewport (30 rows, well
         // over what a ~150px content area fits) should paint a thumb
         // somewhere in the rightmost `SCROLLBAR_WIDTH` columns.
-        view.render(AREA, &mut fb).unwrap();
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
 
         let scrollbar_x = (AREA.size.width - 1) as i32;
         let any_scrollbar_pixel = (AREA.top_left.y..(AREA.top_left.y + AREA.size.height as i32))
             .map(|y| fb.pixel(Point::new(scrollbar_x, y)))
-            .any(|color| color == palette::DIVIDER || color == palette::TEXT_SECONDARY);
+            .any(|color| color == Palette::DARK.divider || color == Palette::DARK.text_secondary);
         assert!(any_scrollbar_pixel, "a long list should paint a scrollbar track/thumb at the right edge");
     }
 
+    fn render_to_fb(view: &CredentialListView) -> FrameBuffer565 {
+        let mut fb = FrameBuffer565::new(320, 170);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        fb
+    }
+
+    #[test]
+    fn a_long_selected_name_scrolls_only_once_the_dwell_is_up() {
+        let start = Instant::now();
+        let mut view = CredentialListView::new(store_with(vec![item("Consolidated Regional Credit Union Online")]));
+        view.on_focus(FocusEvent::Gained);
+        let at_rest = render_to_fb(&view);
+
+        assert!(!view.tick(start), "focusing a row doesn't move it");
+        assert!(!view.tick(start + Duration::from_secs(1)), "nor does the dwell");
+        assert!(view.tick(start + MARQUEE_DWELL + Duration::from_millis(500)));
+        assert_ne!(render_to_fb(&view).pixels().collect::<Vec<_>>(), at_rest.pixels().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn a_short_name_and_an_unfocused_list_never_tick() {
+        let start = Instant::now();
+        let mut short = CredentialListView::new(store_with(vec![item("GitHub")]));
+        short.on_focus(FocusEvent::Gained);
+        render_to_fb(&short);
+        let mut unfocused = CredentialListView::new(store_with(vec![item("Consolidated Regional Credit Union Online")]));
+        render_to_fb(&unfocused);
+
+        for secs in 0..10 {
+            let now = start + Duration::from_secs(secs);
+            assert!(!short.tick(now));
+            assert!(!unfocused.tick(now));
+        }
+    }
+
     #[test]
     fn render_does_not_panic_for_a_small_viewport_with_more_rows_than_fit() {
         let items: Vec<VaultItem> = (0..50).map(|i| item(&format!("item-{i}"))).collect();
         let view = CredentialListView::new(store_with(items));
         let mut fb = FrameBuffer565::new(64, 40);
         let area = Rectangle::new(Point::new(0, 0), Size::new(64, 40));
-        view.render(area, &mut fb).unwrap();
+        view.render(area, &Theme::DARK, &mut fb).unwrap();
     }
 }
@@ -0,0 +1,496 @@
+//! Rotary encoder input: velocity-based acceleration for the wheel and
+//! click / double-click / long-press recognition for its push button,
+//! turning raw samples into [`NavIntent`]s.
+//!
+//! Lives in the core rather than in `firmware::board::rotary_input` for the
+//! same reason [`crate::button_pad`] does: it's pure logic over `(sample,
+//! now)` pairs, so every timing edge case can be exercised on host with
+//! synthetic streams, while the firmware's `InputSource` is left with
+//! reading the ISR's detent counter and the button pin. Time always comes
+//! in as an argument (the caller's `Clock::now`), never read here.
+//!
+//! Mapping, per `.planning/decisions/2026-08-11-rotary-encoder-input-model.md`:
+//! - Slow rotation: one `Next`/`Prev` per detent.
+//! - Fast rotation (at least [`ACCEL_THRESHOLD`] detents within
+//!   [`ACCEL_WINDOW`], one direction): `NextN`/`PrevN` of twice the
+//!   detents just turned, capped at [`MAX_JUMP`]. Both directions
+//!   accelerate the same way — the dropped first attempt only ever
+//!   jumped forward (bead ai-bitwarden-hw-key-2ed), which is why `PrevN`
+//!   exists.
+//! - Click: `Activate`. Double-click: `Back`, the quick way out of a
+//!   detail screen. Long press: `Back`, reported as soon as the hold
+//!   crosses [`LONG_PRESS`] rather than on release, so the screen changes
+//!   while the finger is still down.
+//!
+//! A click is only reported once [`DOUBLE_CLICK_WINDOW`] has passed without
+//! a second press, so `Activate` lands that much later than the release.
+//!
+//! The encoder's button is GPIO0, the ESP32-S3's boot strapping pin (the
+//! ADR's 2026-08-12 amendment): a press held through power-on is ignored
+//! until the button has been seen released once.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::NavIntent;
+pub use crate::button_pad::LONG_PRESS;
+use crate::button_pad::{Debouncer, Edge};
+
+/// How far back [`Acceleration`] looks when deciding whether the wheel is
+/// spinning fast.
+pub const ACCEL_WINDOW: Duration = Duration::from_millis(100);
+/// Detents within [`ACCEL_WINDOW`] that count as a fast spin (the ADR's
+/// "≥4 ticks in 100ms").
+pub const ACCEL_THRESHOLD: u32 = 4;
+/// The largest single accelerated jump — about one screen of list rows,
+/// so a fast spin never skips past items the user hasn't had on screen.
+pub const MAX_JUMP: u16 = 16;
+/// How soon after a click's release a second press makes it a
+/// double-click.
+pub const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(250);
+
+/// Turns drained detent counts into `Next`/`Prev`, or `NextN`/`PrevN`
+/// while the wheel is spinning fast.
+#[derive(Debug, Clone, Default)]
+pub struct Acceleration {
+    /// Detents turned within the last [`ACCEL_WINDOW`], with when.
+    recent: VecDeque<(Instant, u32)>,
+    /// The direction `recent` was turned in (`1` or `-1`; `0` before the
+    /// first detent). A reversal starts the window over.
+    direction: i32,
+}
+
+impl Acceleration {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the signed detents (positive = clockwise) turned since the
+    /// last call, drained at `now`.
+    pub fn update(&mut self, detents: i32, now: Instant) -> Vec<NavIntent> {
+        if detents == 0 {
+            return Vec::new();
+        }
+        let direction = detents.signum();
+        if direction != self.direction {
+            self.recent.clear();
+            self.direction = direction;
+        }
+        while self
+            .recent
+            .front()
+            .is_some_and(|&(at, _)| now.saturating_duration_since(at) >= ACCEL_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+
+        let turned = detents.unsigned_abs();
+        self.recent.push_back((now, turned));
+        let in_window: u32 = self.recent.iter().map(|&(_, detents)| detents).sum();
+
+        if in_window >= ACCEL_THRESHOLD {
+            let jump = turned.saturating_mul(2).min(u32::from(MAX_JUMP)) as u16;
+            vec![if direction > 0 {
+                NavIntent::NextN(jump)
+            } else {
+                NavIntent::PrevN(jump)
+            }]
+        } else {
+            let step = if direction > 0 {
+                NavIntent::Next
+            } else {
+                NavIntent::Prev
+            };
+            vec![step; turned as usize]
+        }
+    }
+}
+
+/// A recognized button gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// One short press, with no second press inside
+    /// [`DOUBLE_CLICK_WINDOW`].
+    Click,
+    /// Two short presses, the second starting inside
+    /// [`DOUBLE_CLICK_WINDOW`] of the first's release.
+    DoubleClick,
+    /// A press held for [`LONG_PRESS`], reported while still held. Its
+    /// release reports nothing; a click still waiting for its double is
+    /// dropped (click-then-hold reads as a hold).
+    LongPress,
+}
+
+impl Gesture {
+    /// The intent this gesture maps to (see the module doc).
+    #[must_use]
+    pub const fn intent(self) -> NavIntent {
+        match self {
+            Self::Click => NavIntent::Activate,
+            Self::DoubleClick | Self::LongPress => NavIntent::Back,
+        }
+    }
+}
+
+/// Recognizes [`Gesture`]s from one debounced button.
+#[derive(Debug, Clone, Default)]
+pub struct ButtonGestures {
+    button: Debouncer,
+    /// Set once a released sample has been seen; presses before that
+    /// were held through boot (see the module doc) and are ignored.
+    armed: bool,
+    /// When the current press was debounced, if it counts.
+    pressed_at: Option<Instant>,
+    /// Set once the current press has been reported as a long press.
+    long_pressed: bool,
+    /// When the last click was released, while it waits to see whether a
+    /// second press makes it a double-click.
+    pending_click: Option<Instant>,
+}
+
+impl ButtonGestures {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds one raw sample (`pressed` after the board's active-low
+    /// inversion) taken at `now`. Call it every frame, pressed or not:
+    /// long presses and the end of the double-click window are only
+    /// noticed when sampled.
+    pub fn update(&mut self, pressed: bool, now: Instant) -> Option<Gesture> {
+        if !pressed {
+            self.armed = true;
+        }
+        match self.button.update(pressed, now) {
+            Some(Edge::Pressed) => {
+                if !self.armed {
+                    return None;
+                }
+                self.pressed_at = Some(now);
+                self.long_pressed = false;
+                // A click whose window ran out between two samples is
+                // still a click; this press starts a new gesture.
+                let expired = self.pending_click.is_some_and(|released| {
+                    now.saturating_duration_since(released) >= DOUBLE_CLICK_WINDOW
+                });
+                if expired {
+                    self.pending_click = None;
+                    return Some(Gesture::Click);
+                }
+                None
+            }
+            Some(Edge::Released) => {
+                self.pressed_at.take()?;
+                if std::mem::take(&mut self.long_pressed) {
+                    return None;
+                }
+                if self.pending_click.take().is_some() {
+                    return Some(Gesture::DoubleClick);
+                }
+                self.pending_click = Some(now);
+                None
+            }
+            None => self.poll_timers(now),
+        }
+    }
+
+    /// The gestures that complete with time rather than an edge: a hold
+    /// crossing [`LONG_PRESS`], or a lone click's double-click window
+    /// running out.
+    fn poll_timers(&mut self, now: Instant) -> Option<Gesture> {
+        if let Some(at) = self.pressed_at {
+            if !self.long_pressed && now.saturating_duration_since(at) >= LONG_PRESS {
+                self.long_pressed = true;
+                self.pending_click = None;
+                return Some(Gesture::LongPress);
+            }
+            return None;
+        }
+        let released = self.pending_click?;
+        if now.saturating_duration_since(released) >= DOUBLE_CLICK_WINDOW {
+            self.pending_click = None;
+            return Some(Gesture::Click);
+        }
+        None
+    }
+}
+
+/// The whole encoder: wheel [`Acceleration`] and [`ButtonGestures`],
+/// sampled together once per frame.
+#[derive(Debug, Clone, Default)]
+pub struct EncoderGestures {
+    wheel: Acceleration,
+    button: ButtonGestures,
+}
+
+impl EncoderGestures {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the detents turned since the last call and the button's raw
+    /// level, both sampled at `now`, returning the wheel's intents first.
+    pub fn update(&mut self, detents: i32, button_pressed: bool, now: Instant) -> Vec<NavIntent> {
+        let mut intents = self.wheel.update(detents, now);
+        intents.extend(self.button.update(button_pressed, now).map(Gesture::intent));
+        intents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    /// Runs `(millis, detents)` polls through a fresh [`Acceleration`].
+    fn spin(polls: &[(u64, i32)]) -> Vec<NavIntent> {
+        let t0 = Instant::now();
+        let mut wheel = Acceleration::new();
+        polls
+            .iter()
+            .flat_map(|&(millis, detents)| wheel.update(detents, ms(t0, millis)))
+            .collect()
+    }
+
+    /// Samples `levels` (one per 10ms) through a fresh [`ButtonGestures`],
+    /// after a released sample so it's armed, and collects what it reports.
+    fn press(levels: &[bool]) -> Vec<Gesture> {
+        let t0 = Instant::now();
+        let mut button = ButtonGestures::new();
+        button.update(false, t0);
+        levels
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &level)| button.update(level, ms(t0, 10 * (index as u64 + 1))))
+            .collect()
+    }
+
+    /// `millis` worth of 10ms samples at `level`.
+    fn held(level: bool, millis: usize) -> Vec<bool> {
+        vec![level; millis / 10]
+    }
+
+    fn sequence(parts: &[(bool, usize)]) -> Vec<bool> {
+        parts
+            .iter()
+            .flat_map(|&(level, millis)| held(level, millis))
+            .collect()
+    }
+
+    #[test]
+    fn slow_rotation_steps_one_item_per_detent() {
+        let polls: Vec<_> = (0..6).map(|i| (i * 50, 1)).collect();
+        assert_eq!(spin(&polls), vec![NavIntent::Next; 6]);
+        let polls: Vec<_> = (0..6).map(|i| (i * 50, -1)).collect();
+        assert_eq!(spin(&polls), vec![NavIntent::Prev; 6]);
+    }
+
+    #[test]
+    fn fast_rotation_accelerates_once_the_window_fills() {
+        let polls: Vec<_> = (0..6).map(|i| (i * 20, 1)).collect();
+        assert_eq!(
+            spin(&polls),
+            vec![
+                NavIntent::Next,
+                NavIntent::Next,
+                NavIntent::Next,
+                NavIntent::NextN(2),
+                NavIntent::NextN(2),
+                NavIntent::NextN(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn fast_reverse_rotation_accelerates_symmetrically() {
+        let forward: Vec<_> = (0..6).map(|i| (i * 20, 1)).collect();
+        let reverse: Vec<_> = (0..6).map(|i| (i * 20, -1)).collect();
+        let mirrored: Vec<_> = spin(&forward)
+            .into_iter()
+            .map(|intent| match intent {
+                NavIntent::Next => NavIntent::Prev,
+                NavIntent::NextN(n) => NavIntent::PrevN(n),
+                other => other,
+            })
+            .collect();
+        assert_eq!(spin(&reverse), mirrored);
+    }
+
+    #[test]
+    fn a_burst_in_one_poll_jumps_twice_its_detents_up_to_the_cap() {
+        assert_eq!(spin(&[(0, 5)]), vec![NavIntent::NextN(10)]);
+        assert_eq!(spin(&[(0, -3)]), vec![NavIntent::Prev; 3]);
+        assert_eq!(spin(&[(0, 40)]), vec![NavIntent::NextN(MAX_JUMP)]);
+        assert_eq!(spin(&[(0, -40)]), vec![NavIntent::PrevN(MAX_JUMP)]);
+    }
+
+    #[test]
+    fn a_reversal_or_a_pause_drops_back_to_single_steps() {
+        let reversed = spin(&[(0, 2), (20, 2), (40, -1)]);
+        assert_eq!(reversed.last(), Some(&NavIntent::Prev));
+        let paused = spin(&[(0, 2), (20, 2), (200, 1)]);
+        assert_eq!(paused.last(), Some(&NavIntent::Next));
+    }
+
+    #[test]
+    fn idle_polls_report_nothing() {
+        assert_eq!(spin(&[(0, 0), (10, 0)]), vec![]);
+    }
+
+    #[test]
+    fn a_short_press_is_a_click_once_the_double_click_window_passes() {
+        let gestures = press(&sequence(&[(true, 100), (false, 300)]));
+        assert_eq!(gestures, vec![Gesture::Click]);
+        let early = press(&sequence(&[(true, 100), (false, 200)]));
+        assert_eq!(early, vec![], "still waiting for a second press");
+    }
+
+    #[test]
+    fn two_quick_presses_are_one_double_click() {
+        let gestures = press(&sequence(&[
+            (true, 80),
+            (false, 120),
+            (true, 80),
+            (false, 400),
+        ]));
+        assert_eq!(gestures, vec![Gesture::DoubleClick]);
+    }
+
+    #[test]
+    fn presses_further_apart_than_the_window_are_two_clicks() {
+        let gestures = press(&sequence(&[
+            (true, 80),
+            (false, 400),
+            (true, 80),
+            (false, 400),
+        ]));
+        assert_eq!(gestures, vec![Gesture::Click, Gesture::Click]);
+    }
+
+    #[test]
+    fn a_hold_is_a_long_press_while_still_held_and_its_release_is_silent() {
+        let t0 = Instant::now();
+        let mut button = ButtonGestures::new();
+        button.update(false, t0);
+        let mut reported = Vec::new();
+        for millis in (10..=600).step_by(10) {
+            if let Some(gesture) = button.update(true, ms(t0, millis)) {
+                reported.push((millis, gesture));
+            }
+        }
+        // Debounced at 30ms, so the hold crosses LONG_PRESS at 530ms.
+        assert_eq!(reported, vec![(530, Gesture::LongPress)]);
+        for millis in (610..=1200).step_by(10) {
+            assert_eq!(button.update(false, ms(t0, millis)), None);
+        }
+    }
+
+    #[test]
+    fn click_then_hold_reads_as_a_hold() {
+        let gestures = press(&sequence(&[
+            (true, 80),
+            (false, 120),
+            (true, 700),
+            (false, 400),
+        ]));
+        assert_eq!(gestures, vec![Gesture::LongPress]);
+    }
+
+    #[test]
+    fn contact_bounce_is_not_a_double_click() {
+        let bouncy = [true, false, true, true, false, true, true, true, true, true];
+        let mut levels = bouncy.to_vec();
+        levels.extend(held(false, 400));
+        assert_eq!(press(&levels), vec![Gesture::Click]);
+    }
+
+    #[test]
+    fn a_press_held_through_boot_is_ignored_until_released() {
+        let t0 = Instant::now();
+        let mut button = ButtonGestures::new();
+        let mut reported = Vec::new();
+        // Held from the very first sample (GPIO0 as the boot strap pin).
+        for millis in (0..1000).step_by(10) {
+            reported.extend(button.update(true, ms(t0, millis)));
+        }
+        for millis in (1000..1500).step_by(10) {
+            reported.extend(button.update(false, ms(t0, millis)));
+        }
+        assert_eq!(reported, vec![], "neither a long press nor a click");
+
+        for millis in (1500..1600).step_by(10) {
+            reported.extend(button.update(true, ms(t0, millis)));
+        }
+        for millis in (1600..2000).step_by(10) {
+            reported.extend(button.update(false, ms(t0, millis)));
+        }
+        assert_eq!(reported, vec![Gesture::Click], "later presses count");
+    }
+
+    /// Every press/release pattern over eight 100ms slots: each press is
+    /// reported at most once, and exactly the holds long enough to cross
+    /// `LONG_PRESS` are long presses.
+    #[test]
+    fn every_eight_slot_pattern_accounts_for_each_press_at_most_once() {
+        for pattern in 0u32..(1 << 8) {
+            let slots: Vec<bool> = (0..8).map(|bit| pattern & (1 << bit) != 0).collect();
+            let mut levels: Vec<bool> = slots.iter().flat_map(|&level| held(level, 100)).collect();
+            levels.extend(held(false, 500));
+            let gestures = press(&levels);
+
+            let runs: Vec<usize> = slots
+                .split(|&level| !level)
+                .map(<[bool]>::len)
+                .filter(|&len| len > 0)
+                .collect();
+            let count = |wanted| gestures.iter().filter(|&&g| g == wanted).count();
+            let accounted =
+                count(Gesture::Click) + 2 * count(Gesture::DoubleClick) + count(Gesture::LongPress);
+            assert!(
+                accounted <= runs.len(),
+                "{pattern:08b}: {gestures:?} for {runs:?}"
+            );
+            // A run of n slots is debounced for n * 100ms less the final
+            // sample, so six slots is the shortest hold that crosses 500ms.
+            let long_runs = runs.iter().filter(|&&len| len >= 6).count();
+            assert_eq!(
+                count(Gesture::LongPress),
+                long_runs,
+                "{pattern:08b}: {gestures:?} for {runs:?}"
+            );
+            if let [len] = runs[..] {
+                let expected = if len >= 6 {
+                    Gesture::LongPress
+                } else {
+                    Gesture::Click
+                };
+                assert_eq!(gestures, vec![expected], "{pattern:08b}");
+            }
+        }
+    }
+
+    #[test]
+    fn the_encoder_reports_the_wheel_then_the_button() {
+        let t0 = Instant::now();
+        let mut encoder = EncoderGestures::new();
+        let mut intents = Vec::new();
+        intents.extend(encoder.update(1, false, t0));
+        for millis in (10..=100).step_by(10) {
+            intents.extend(encoder.update(0, true, ms(t0, millis)));
+        }
+        for millis in (110..=500).step_by(10) {
+            intents.extend(encoder.update(0, false, ms(t0, millis)));
+        }
+        intents.extend(encoder.update(-1, false, ms(t0, 510)));
+        assert_eq!(
+            intents,
+            vec![NavIntent::Next, NavIntent::Activate, NavIntent::Prev]
+        );
+    }
+}
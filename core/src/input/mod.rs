@@ -1,3 +1,12 @@
+//! The semantic input vocabulary ([`NavIntent`]) every platform's
+//! `InputSource` produces, plus [`encoder`]: the platform-free rotary
+//! encoder acceleration and button-gesture recognizer the T-Embed's
+//! `InputSource` feeds raw samples into, and [`type_ahead`]: the prefix
+//! matcher lists use to turn `NavIntent::Char` into a jump.
+
+pub mod encoder;
+pub mod type_ahead;
+
 use serde::{Deserialize, Serialize};
 
 /// Semantic navigation intent: the hardware-independent "Tier 2" input
@@ -6,11 +15,6 @@ use serde::{Deserialize, Serialize};
 /// enum by the platform/emulator layer; app code never mentions "encoder"
 /// or "keyboard."
 ///
-/// This is a placeholder module seam only: nothing in the core wires this
-/// up to the (still-old) GUI engines yet, and no `InputSource` implementation
-/// exists. It exists now so the boundary is visible in the workspace split,
-/// per the ADR below.
-///
 /// See: .planning/decisions/2026-08-11-rotary-encoder-input-model.md
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NavIntent {
@@ -20,6 +24,18 @@ pub enum NavIntent {
     Prev,
     /// Jump forward N items (fast encoder rotation, held button, Pg Dn).
     NextN(u16),
+    /// Jump backward N items (fast reverse rotation) — the symmetric
+    /// counterpart to `NextN`, per the ADR's 2026-08-12 amendment.
+    PrevN(u16),
+    /// Jump to the first item (Home). Only keyboard-driven sources produce
+    /// this; the encoder has no equivalent gesture.
+    Home,
+    /// Jump to the last item (End).
+    End,
+    /// A typed character, fed to the focused list's type-ahead jump to the
+    /// first matching name (see [`type_ahead`]). Widgets without a notion
+    /// of names ignore it.
+    Char(char),
     /// Select the focused item (encoder short press, space, enter).
     Activate,
     /// Return to parent / dismiss modal (encoder long press, esc, back).
@@ -0,0 +1,132 @@
+//! Type-ahead matching for lists: the state behind `NavIntent::Char`.
+//!
+//! Typed characters accumulate into a prefix, and the list jumps to the
+//! first row whose name starts with it (case-insensitively), the way a
+//! desktop file list does. Typing the same letter again when no name
+//! starts with the doubled prefix cycles through the rows starting with
+//! that letter instead, so "g g g" walks every "G..." entry.
+//!
+//! There is deliberately no timeout: widgets never read the clock in
+//! `on_intent`, so the prefix is cleared by the owning widget on any
+//! other intent (a rotation, `Activate`, `Back`, ...) rather than after
+//! a pause. A prefix that no longer matches anything also starts over
+//! from the character just typed.
+
+/// The accumulated type-ahead prefix for one list widget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeAhead {
+    prefix: String,
+}
+
+impl TypeAhead {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The prefix typed so far, lowercased. Empty after [`reset`](Self::reset).
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Forgets the typed prefix. Called by the owning list on every
+    /// non-character intent.
+    pub fn reset(&mut self) {
+        self.prefix.clear();
+    }
+
+    /// Feeds one typed character and returns the row to select, if any.
+    ///
+    /// `names` are the rows' names in display order and `current` the
+    /// selected row. Resolution, in order:
+    /// 1. the first row starting with the extended prefix;
+    /// 2. if `c` repeats a single-letter prefix, the next row after
+    ///    `current` starting with `c`, wrapping around;
+    /// 3. the first row starting with `c` alone, restarting the prefix.
+    ///
+    /// Returns `None` (and clears the prefix) when no row matches even
+    /// `c` alone; the selection should stay where it is.
+    pub fn push<'a>(
+        &mut self,
+        c: char,
+        names: impl IntoIterator<Item = &'a str>,
+        current: usize,
+    ) -> Option<usize> {
+        let names: Vec<String> = names.into_iter().map(str::to_lowercase).collect();
+        let typed: String = c.to_lowercase().collect();
+
+        let mut extended = self.prefix.clone();
+        extended.push_str(&typed);
+        if let Some(index) = names.iter().position(|name| name.starts_with(&extended)) {
+            self.prefix = extended;
+            return Some(index);
+        }
+
+        if self.prefix == typed {
+            let len = names.len();
+            let next = (1..=len)
+                .map(|offset| (current + offset) % len)
+                .find(|&index| names[index].starts_with(&typed));
+            if next.is_some() {
+                return next;
+            }
+        }
+
+        match names.iter().position(|name| name.starts_with(&typed)) {
+            Some(index) => {
+                self.prefix = typed;
+                Some(index)
+            }
+            None => {
+                self.reset();
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 5] = ["Amazon", "GitHub", "Gitlab", "gmail", "Google"];
+
+    #[test]
+    fn typing_a_prefix_narrows_to_the_first_match() {
+        let mut type_ahead = TypeAhead::new();
+        assert_eq!(type_ahead.push('g', NAMES, 0), Some(1));
+        assert_eq!(type_ahead.push('I', NAMES, 1), Some(1));
+        assert_eq!(type_ahead.push('t', NAMES, 1), Some(1));
+        assert_eq!(type_ahead.push('l', NAMES, 1), Some(2));
+        assert_eq!(type_ahead.prefix(), "gitl");
+    }
+
+    #[test]
+    fn repeating_a_letter_cycles_through_its_rows_and_wraps() {
+        let mut type_ahead = TypeAhead::new();
+        assert_eq!(type_ahead.push('g', NAMES, 0), Some(1));
+        assert_eq!(type_ahead.push('g', NAMES, 1), Some(2));
+        assert_eq!(type_ahead.push('g', NAMES, 2), Some(3));
+        assert_eq!(type_ahead.push('g', NAMES, 3), Some(4));
+        assert_eq!(type_ahead.push('g', NAMES, 4), Some(1));
+    }
+
+    #[test]
+    fn a_prefix_that_stops_matching_restarts_from_the_new_character() {
+        let mut type_ahead = TypeAhead::new();
+        type_ahead.push('g', NAMES, 0);
+        type_ahead.push('o', NAMES, 4);
+        assert_eq!(type_ahead.push('a', NAMES, 4), Some(0));
+        assert_eq!(type_ahead.prefix(), "a");
+    }
+
+    #[test]
+    fn no_match_leaves_the_selection_and_clears_the_prefix() {
+        let mut type_ahead = TypeAhead::new();
+        type_ahead.push('g', NAMES, 0);
+        assert_eq!(type_ahead.push('z', NAMES, 1), None);
+        assert_eq!(type_ahead.prefix(), "");
+        assert_eq!(type_ahead.push('x', [], 0), None);
+    }
+}
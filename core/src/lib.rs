@@ -8,7 +8,14 @@
 //! Current contents:
 //! - [`vault_item::VaultItem`]: the credential view-model, migrated out of
 //!   the old single-crate `credentials` module.
-//! - [`input`]: the frozen `NavIntent` semantic input vocabulary (W1).
+//! - [`input`]: the `NavIntent` semantic input vocabulary (W1), and
+//!   [`input::encoder`]'s rotary-encoder acceleration and click /
+//!   double-click / long-press recognition, fed raw samples by the
+//!   T-Embed's `InputSource`, plus [`input::type_ahead`]'s prefix
+//!   matching behind `NavIntent::Char`.
+//! - [`button_pad::ButtonPad`]: debounced three-button input mapped to
+//!   `NavIntent`s, for boards without an encoder (the HUZZAH32 + SSD1306
+//!   FeatherWing) — platform-free so it's tested on host.
 //! - [`platform`]: the `DisplaySurface`/`InputSource`/`Clock`/`Storage`/
 //!   `Platform` trait seams (W1), with no implementations yet.
 //! - [`sync_source::SyncSource`]: the `sync() -> Vec<VaultItem>` trait
@@ -38,6 +45,15 @@
 //!   NOTES) internally, reading the credential live by id from the
 //!   `VaultStore`, with a "gone" state if it's deleted upstream while
 //!   viewing.
+//! - [`settings::Settings`]: the device preferences (brightness, idle
+//!   screen-off, list sort order and sublabel), App-owned and shared like
+//!   `VaultStore`, persisted via `Storage` under a versioned CBOR schema
+//!   with a migration hook.
+//! - [`transactional_storage::TransactionalStorage`]: a `Storage` layer
+//!   that stores values of any size atomically over raw NVS — chunked
+//!   under alternating A/B slots, committed by one manifest write — and
+//!   [`nvs::SimulatedNvs`], the in-memory NVS with its limits and power
+//!   cuts that it's tested against on host.
 //! - [`app::App`]: the platform-free application state — a `Navigator`,
 //!   built once, over a `VaultStore`-backed root credential list that
 //!   `App::step` keeps live-updated in place, wired to push a
@@ -54,13 +70,17 @@
 //! See: .planning/decisions/2026-08-11-portability-boundary-and-workspace-split.md
 
 pub mod app;
+pub mod button_pad;
 pub mod credential_detail_view;
 pub mod credential_list_view;
 pub mod input;
+pub mod nvs;
 pub mod platform;
 pub mod render;
 pub mod run;
+pub mod settings;
 pub mod sync_source;
+pub mod transactional_storage;
 pub mod vault_item;
 pub mod vault_store;
 
@@ -68,7 +88,8 @@ pub use app::App;
 pub use credential_detail_view::CredentialDetailView;
 pub use credential_list_view::CredentialListView;
 pub use input::NavIntent;
-pub use run::run;
+pub use run::{run, FrameStepper};
+pub use settings::Settings;
 pub use sync_source::SyncSource;
 pub use vault_item::VaultItem;
 pub use vault_store::{SyncStatus, VaultStore};
@@ -0,0 +1,174 @@
+//! ESP-IDF NVS's limits, and [`SimulatedNvs`]: an in-memory [`Storage`]
+//! that enforces them and can lose power on cue, so code meant for the
+//! firmware's `NvsStorage` can be checked on host — above all
+//! [`crate::transactional_storage::TransactionalStorage`], whose whole job
+//! is surviving a power cut between two NVS writes.
+//!
+//! What's simulated is what callers can observe: keys and values over the
+//! limits are refused, every write or removal is atomic (NVS checksums
+//! each entry, so after a power cut an entry is either the old value or
+//! the new one), and once the power is "cut" every further write fails
+//! without effect until it's restored. Per-key write counts stand in for
+//! flash wear. Page layout, erase cycles and the real free-space
+//! accounting are not modelled.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::platform::Storage;
+
+/// The longest NVS key: 15 bytes (16 with the NUL terminator ESP-IDF
+/// adds).
+pub const MAX_KEY_LEN: usize = 15;
+
+/// The largest blob this project writes to a single NVS entry. NVS format
+/// version 1 (ESP-IDF before 4.2) can't store a blob over 1984 bytes at
+/// all; version 2 can, by spanning pages internally, but staying under
+/// the old limit works on both and keeps each write small.
+pub const MAX_BLOB_LEN: usize = 1984;
+
+/// Why a [`SimulatedNvs`] refused a write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulatedNvsError {
+    /// The key is longer than [`MAX_KEY_LEN`].
+    KeyTooLong,
+    /// The value is longer than [`MAX_BLOB_LEN`].
+    BlobTooLarge,
+    /// The power is cut (see [`SimulatedNvs::cut_power_after`]); nothing
+    /// was written.
+    PowerLost,
+}
+
+impl fmt::Display for SimulatedNvsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyTooLong => write!(f, "NVS keys are at most {MAX_KEY_LEN} bytes"),
+            Self::BlobTooLarge => write!(f, "NVS blobs are at most {MAX_BLOB_LEN} bytes"),
+            Self::PowerLost => f.write_str("power lost"),
+        }
+    }
+}
+
+impl std::error::Error for SimulatedNvsError {}
+
+/// An in-memory NVS namespace. Cloning it is taking a snapshot of the
+/// flash, which is how a test "reboots": clone, then read the clone
+/// through a fresh layer.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedNvs {
+    entries: BTreeMap<String, Vec<u8>>,
+    writes: HashMap<String, u32>,
+    /// Writes (and removals) left before the power goes; `None` while
+    /// it's on for good.
+    power_left: Option<usize>,
+}
+
+impl SimulatedNvs {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lets the next `writes` writes or removals through, then fails every
+    /// one after them with [`SimulatedNvsError::PowerLost`], leaving the
+    /// store exactly as the last successful one left it.
+    pub fn cut_power_after(&mut self, writes: usize) {
+        self.power_left = Some(writes);
+    }
+
+    /// Ends a [`SimulatedNvs::cut_power_after`]: writes succeed again.
+    pub fn restore_power(&mut self) {
+        self.power_left = None;
+    }
+
+    /// Every key currently stored, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// How many times `key` has been written or removed: its share of the
+    /// flash wear.
+    #[must_use]
+    pub fn writes(&self, key: &str) -> u32 {
+        self.writes.get(key).copied().unwrap_or(0)
+    }
+
+    /// Writes and removals across every key.
+    #[must_use]
+    pub fn total_writes(&self) -> u32 {
+        self.writes.values().sum()
+    }
+
+    /// Checks `key` and spends one write of power, or fails.
+    fn admit(&mut self, key: &str) -> Result<(), SimulatedNvsError> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(SimulatedNvsError::KeyTooLong);
+        }
+        match &mut self.power_left {
+            Some(0) => return Err(SimulatedNvsError::PowerLost),
+            Some(left) => *left -= 1,
+            None => {}
+        }
+        *self.writes.entry(key.to_string()).or_default() += 1;
+        Ok(())
+    }
+}
+
+impl Storage for SimulatedNvs {
+    type Error = SimulatedNvsError;
+
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn set(&mut self, key: &str, value: Vec<u8>) -> Result<(), Self::Error> {
+        if value.len() > MAX_BLOB_LEN {
+            return Err(SimulatedNvsError::BlobTooLarge);
+        }
+        self.admit(key)?;
+        self.entries.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), Self::Error> {
+        self.admit(key)?;
+        self.entries.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_are_enforced_and_a_refused_write_changes_nothing() {
+        let mut nvs = SimulatedNvs::new();
+        assert_eq!(
+            nvs.set("sixteen-bytes-xx", vec![1]),
+            Err(SimulatedNvsError::KeyTooLong)
+        );
+        assert_eq!(
+            nvs.set("big", vec![0; MAX_BLOB_LEN + 1]),
+            Err(SimulatedNvsError::BlobTooLarge)
+        );
+        nvs.set("fits", vec![0; MAX_BLOB_LEN]).unwrap();
+        assert_eq!(nvs.keys().collect::<Vec<_>>(), ["fits"]);
+        assert_eq!(nvs.total_writes(), 1);
+    }
+
+    #[test]
+    fn after_a_power_cut_writes_fail_until_it_is_restored() {
+        let mut nvs = SimulatedNvs::new();
+        nvs.cut_power_after(1);
+        nvs.set("a", vec![1]).unwrap();
+        assert_eq!(nvs.set("a", vec![2]), Err(SimulatedNvsError::PowerLost));
+        assert_eq!(nvs.remove("a"), Err(SimulatedNvsError::PowerLost));
+        assert_eq!(nvs.get("a"), Some(vec![1]));
+
+        nvs.restore_power();
+        nvs.remove("a").unwrap();
+        assert_eq!(nvs.get("a"), None);
+        assert_eq!(nvs.writes("a"), 2);
+    }
+}
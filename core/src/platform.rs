@@ -14,6 +14,7 @@
 //! See: .planning/decisions/2026-08-11-presentation-surface-run-mode-seam.md
 
 use crate::input::NavIntent;
+use embedded_graphics::primitives::Rectangle;
 use std::time::Instant;
 
 pub use crate::render::FrameBuffer565;
@@ -24,11 +25,37 @@ pub use crate::render::FrameBuffer565;
 pub trait DisplaySurface {
     type Error;
 
+    /// Presents `framebuffer`. `damage` lists the only regions whose
+    /// pixels changed since the previous `flush` (from
+    /// [`FrameBuffer565::take_damage`]: disjoint, inside the frame), so a
+    /// surface may send just those — the ST7789 sets an address window per
+    /// region — and must treat an empty list as "nothing changed". Sending
+    /// more than `damage` is always correct; a caller with no damage
+    /// information passes the whole frame's bounding box.
+    ///
+    /// Pixels outside `damage` are only guaranteed to match what *this*
+    /// surface was last given, so one that fails midway, or has never been
+    /// flushed, must fall back to the whole frame on its next call.
+    ///
     /// # Errors
     ///
     /// Returns `Self::Error` if the framebuffer could not be transferred to
     /// the underlying display (e.g. an SPI write failure on real hardware).
-    fn flush(&mut self, framebuffer: &FrameBuffer565) -> Result<(), Self::Error>;
+    fn flush(
+        &mut self,
+        framebuffer: &FrameBuffer565,
+        damage: &[Rectangle],
+    ) -> Result<(), Self::Error>;
+
+    /// Sets the backlight to `percent` (0 = off, 100 = full), as chosen in
+    /// the settings screen or by the idle timeout. Best-effort: a surface
+    /// with coarser control rounds (the T-Embed's backlight is a plain
+    /// GPIO, so anything above 0 is simply "on"), and one with no
+    /// backlight at all — the headless PNG surface — keeps this default
+    /// no-op. Infallible for the same reason the run loop absorbs flush
+    /// errors: a failed brightness change is not something the core can
+    /// act on.
+    fn set_brightness(&mut self, _percent: u8) {}
 }
 
 /// Polls for input, already resolved to the semantic `NavIntent` level
@@ -43,12 +70,33 @@ pub trait InputSource {
 /// APIs directly. `std::time::Instant` is available on both targets today
 /// (esp-idf-svc's `std` feature provides it), so no custom time type is
 /// needed yet.
+///
+/// Also the run loop's scheduler: [`Clock::sleep_until`] is how
+/// `crate::run` waits out the rest of a frame, so a clock that isn't the
+/// wall clock (the emulator's virtual time) paces the loop too, not just
+/// what the app sees.
 pub trait Clock {
     fn now(&self) -> Instant;
+
+    /// Blocks until [`now`](Clock::now) reaches `deadline`; returns at once
+    /// if it already has. The default sleeps the thread, which is right
+    /// for any clock that follows real time.
+    ///
+    /// An implementation may also return early, before `deadline`: the
+    /// run loop only uses this for pacing, so waking early just runs one
+    /// more frame at the same `now`. A virtual clock relies on that to
+    /// keep serving input and shutdown while its time stands still.
+    fn sleep_until(&self, deadline: Instant) {
+        if let Some(remaining) = deadline.checked_duration_since(self.now()) {
+            std::thread::sleep(remaining);
+        }
+    }
 }
 
 /// Persistent key/value storage. Implementations: native filesystem
-/// (emulator), NVS (firmware).
+/// (emulator), NVS (firmware), and [`crate::transactional_storage::
+/// TransactionalStorage`] layered over either for values too big or too
+/// important for one raw entry.
 pub trait Storage {
     type Error;
 
@@ -59,6 +107,14 @@ pub trait Storage {
     /// Returns `Self::Error` if the value could not be persisted (e.g. an
     /// NVS write failure on real hardware, or a filesystem error on host).
     fn set(&mut self, key: &str, value: Vec<u8>) -> Result<(), Self::Error>;
+
+    /// Deletes `key`, so `get` returns `None`. Removing a key that isn't
+    /// there is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Self::Error` if the deletion could not be persisted.
+    fn remove(&mut self, key: &str) -> Result<(), Self::Error>;
 }
 
 /// Capability bundle: groups the four injected platform traits behind a
@@ -5,18 +5,24 @@
 //! rejects both the old flexbox attempt and a new general-purpose one in
 //! favor of "fixed chrome regions + linear stacks").
 //!
-//! The bar heights are fixed pixel constants, not resolution-derived
-//! fractions — but the *regions* are computed from whatever screen size is
-//! passed in, and nothing here (or in any widget) hardcodes a screen
-//! dimension. The same [`compute_chrome`] call works for a 320x170 T-Embed
-//! panel, a 128x32 HUZZAH32 OLED, or an arbitrary test framebuffer.
+//! The bar heights are fixed pixel values, not resolution-derived
+//! fractions — but which values, and the part of the panel they stack in,
+//! come from the panel's [`LayoutProfile`] (see `layout.rs`), and the
+//! *regions* are computed from whatever screen size is passed in. Nothing
+//! here (or in any widget) hardcodes a screen dimension: the same
+//! [`compute_chrome`] call works for a 320x170 T-Embed panel, a 128x32
+//! HUZZAH32 OLED, a round 240x240, or an arbitrary test framebuffer.
 
 use embedded_graphics::prelude::{Point, Size};
 use embedded_graphics::primitives::Rectangle;
 
-/// Height of the title bar, in pixels.
+use super::layout::LayoutProfile;
+
+/// Height of the title bar, in pixels, in the landscape and round
+/// profiles.
 pub const TITLE_BAR_HEIGHT: u32 = 16;
-/// Height of the hint/status bar, in pixels.
+/// Height of the hint/status bar, in pixels, in the landscape and round
+/// profiles.
 ///
 /// Bumped from `12` (bead `ai-bitwarden-hw-key-0v8.5`, post-review polish
 /// pass): at `12`, vertically centering the hint text in the bar left the
@@ -28,42 +34,62 @@ pub const TITLE_BAR_HEIGHT: u32 = 16;
 /// above-the-divider layout special-case.
 pub const HINT_BAR_HEIGHT: u32 = 18;
 
-/// The three fixed regions a screen renders into.
+/// The three fixed regions a screen renders into, plus where a toast
+/// goes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ChromeLayout {
     pub title: Rectangle,
     pub content: Rectangle,
     pub hint: Rectangle,
+    /// Over the hint bar, or — in a profile without one — over the
+    /// bottom of the content area, a title bar's height tall.
+    pub toast: Rectangle,
 }
 
-/// Computes the three chrome regions for a screen of the given size.
-/// Saturates rather than panicking on very small screens: a screen too
-/// short for a full title/hint bar just gets a squeezed (possibly
-/// zero-height) region instead of an arithmetic overflow.
+/// Computes the chrome regions for a screen of the given size, stacked
+/// inside `profile`'s safe area with its bar heights. Saturates rather
+/// than panicking on very small screens: a screen too short for a full
+/// title/hint bar just gets a squeezed (possibly zero-height) region
+/// instead of an arithmetic overflow.
 #[must_use]
-pub fn compute_chrome(screen_size: Size) -> ChromeLayout {
-    let width = screen_size.width;
-    let height = screen_size.height;
+pub fn compute_chrome(screen_size: Size, profile: &LayoutProfile) -> ChromeLayout {
+    let area = profile.safe_area(screen_size);
+    let Point { x: left, y: top } = area.top_left;
+    let width = area.size.width;
+    let height = area.size.height;
 
-    let title_height = TITLE_BAR_HEIGHT.min(height);
+    let title_height = profile.title_bar_height.min(height);
     let remaining = height.saturating_sub(title_height);
-    let hint_height = HINT_BAR_HEIGHT.min(remaining);
+    let hint_height = profile.hint_bar_height.min(remaining);
     let content_height = remaining.saturating_sub(hint_height);
 
-    let title = Rectangle::new(Point::new(0, 0), Size::new(width, title_height));
+    let title = Rectangle::new(Point::new(left, top), Size::new(width, title_height));
     let content = Rectangle::new(
-        Point::new(0, title_height as i32),
+        Point::new(left, top + title_height as i32),
         Size::new(width, content_height),
     );
     let hint = Rectangle::new(
-        Point::new(0, (title_height + content_height) as i32),
+        Point::new(left, top + (title_height + content_height) as i32),
         Size::new(width, hint_height),
     );
+    let toast = if hint_height > 0 {
+        hint
+    } else {
+        let toast_height = profile.title_bar_height.min(content_height);
+        Rectangle::new(
+            Point::new(
+                left,
+                top + (title_height + content_height - toast_height) as i32,
+            ),
+            Size::new(width, toast_height),
+        )
+    };
 
     ChromeLayout {
         title,
         content,
         hint,
+        toast,
     }
 }
 
@@ -73,7 +99,7 @@ mod tests {
 
     #[test]
     fn regions_stack_vertically_and_fill_the_screen_exactly() {
-        let chrome = compute_chrome(Size::new(320, 170));
+        let chrome = compute_chrome(Size::new(320, 170), &LayoutProfile::LANDSCAPE);
         assert_eq!(chrome.title.top_left, Point::new(0, 0));
         assert_eq!(chrome.content.top_left.y, chrome.title.size.height as i32);
         assert_eq!(
@@ -87,7 +113,7 @@ mod tests {
 
     #[test]
     fn no_literal_resolution_is_baked_in_128x32_also_lays_out_cleanly() {
-        let chrome = compute_chrome(Size::new(128, 32));
+        let chrome = compute_chrome(Size::new(128, 32), &LayoutProfile::STRIP);
         let total_height =
             chrome.title.size.height + chrome.content.size.height + chrome.hint.size.height;
         assert_eq!(total_height, 32);
@@ -97,7 +123,7 @@ mod tests {
 
     #[test]
     fn tiny_screen_does_not_panic_or_underflow() {
-        let chrome = compute_chrome(Size::new(10, 5));
+        let chrome = compute_chrome(Size::new(10, 5), &LayoutProfile::LANDSCAPE);
         let total_height =
             chrome.title.size.height + chrome.content.size.height + chrome.hint.size.height;
         assert_eq!(total_height, 5);
@@ -105,9 +131,39 @@ mod tests {
 
     #[test]
     fn full_width_is_preserved_in_every_region() {
-        let chrome = compute_chrome(Size::new(201, 90));
+        let chrome = compute_chrome(Size::new(201, 90), &LayoutProfile::LANDSCAPE);
         assert_eq!(chrome.title.size.width, 201);
         assert_eq!(chrome.content.size.width, 201);
         assert_eq!(chrome.hint.size.width, 201);
     }
+
+    #[test]
+    fn a_strip_panel_has_no_hint_bar_and_toasts_over_the_content() {
+        let chrome = compute_chrome(Size::new(128, 32), &LayoutProfile::STRIP);
+        assert_eq!(chrome.hint.size.height, 0);
+        assert_eq!(chrome.content.size.height, 24);
+        assert_eq!(chrome.toast.size.height, 8);
+        assert_eq!(
+            chrome.toast.top_left.y, 24,
+            "the bottom of the content area"
+        );
+        let landscape = compute_chrome(Size::new(320, 170), &LayoutProfile::LANDSCAPE);
+        assert_eq!(landscape.toast, landscape.hint);
+    }
+
+    #[test]
+    fn a_round_panels_regions_stay_inside_its_safe_area() {
+        let size = Size::new(240, 240);
+        let profile = LayoutProfile::for_panel(size);
+        let safe = profile.safe_area(size);
+        let chrome = compute_chrome(size, &profile);
+        for region in [chrome.title, chrome.content, chrome.hint] {
+            assert_eq!(region.intersection(&safe), region);
+        }
+        assert_eq!(chrome.title.top_left, safe.top_left);
+        assert_eq!(
+            chrome.hint.top_left.y + chrome.hint.size.height as i32,
+            safe.top_left.y + safe.size.height as i32
+        );
+    }
 }
@@ -0,0 +1,562 @@
+//! `Dialog`: the modal confirmation widget the `Navigator` layers over the
+//! current screen (`Action::ShowModal`). A title, a short wrapped body, and
+//! up to [`MAX_CHOICES`] buttons laid out in a row — enough for "Wipe
+//! vault? Cancel / Wipe" or "Sync failed. Retry / Dismiss / Details",
+//! without growing into a general form layout (the chrome ADR's "fixed
+//! regions + linear stacks" rule still applies: a dialog is one card, not
+//! a nested screen).
+//!
+//! A dialog is not a `Screen` and never goes on the screen stack: while one
+//! is open the `Navigator` routes every intent to it alone (see
+//! `Navigator::dispatch`), dims whatever was rendered beneath it, and closes
+//! it on `Activate` (running the selected choice) or `Back` (cancel, no
+//! choice runs).
+
+use std::convert::Infallible;
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use embedded_graphics::prelude::{Point, Primitive, Size};
+use embedded_graphics::primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Rectangle};
+use embedded_graphics::Drawable;
+use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
+use u8g2_fonts::FontRenderer;
+
+use crate::input::NavIntent;
+
+use super::framebuffer::FrameBuffer565;
+use super::semantics::{Role, SemanticNode};
+use super::text_view::wrap_text;
+use super::theme::{FontSet, Theme};
+use super::widget::{Action, FocusEvent, Widget};
+
+/// The most buttons a dialog can have. Three is what fits legibly side by
+/// side on the T-Embed's 320px panel in [`FontSet::hint`]; a decision that
+/// needs more options than that wants a list screen, not a dialog.
+pub const MAX_CHOICES: usize = 3;
+
+/// Widest the card gets, however wide the screen is.
+const CARD_MAX_WIDTH: u32 = 260;
+/// Minimum gap (px) kept between the card and the screen edges.
+const CARD_MARGIN: u32 = 8;
+/// Inner padding (px) between the card's border and its content.
+const CARD_PADDING: u32 = 8;
+/// [`CARD_PADDING`] for [`FontSet::Compact`]: a 32px strip can't spare
+/// 16px of padding around a title, a body line and a button row.
+const COMPACT_CARD_PADDING: u32 = 2;
+/// Vertical footprint (px) of the title line in [`FontSet::title`].
+const TITLE_LINE_HEIGHT: u32 = 14;
+/// [`TITLE_LINE_HEIGHT`] for [`FontSet::Compact`]'s 5x7 title font.
+const COMPACT_TITLE_LINE_HEIGHT: u32 = 7;
+/// How much tighter (px) wrapped body lines are pitched than
+/// [`FontSet::username`]'s full line footprint: the footprint includes
+/// leading above the ascent that consecutive lines of one paragraph don't
+/// need.
+const BODY_LINE_TIGHTENING: i32 = 2;
+/// Body text beyond this many wrapped lines is dropped: a dialog asks one
+/// short question, it isn't a text viewer.
+const BODY_MAX_LINES: usize = 3;
+/// Gap (px) between the title, the body, and the button row.
+const SECTION_GAP: u32 = 6;
+/// [`SECTION_GAP`] for [`FontSet::Compact`].
+const COMPACT_SECTION_GAP: u32 = 1;
+/// Height (px) of a choice button.
+const BUTTON_HEIGHT: u32 = 18;
+/// [`BUTTON_HEIGHT`] for [`FontSet::Compact`]: one line of its 4x6 hint
+/// font plus a pixel above and below.
+const COMPACT_BUTTON_HEIGHT: u32 = 8;
+/// Horizontal gap (px) between adjacent choice buttons.
+const BUTTON_GAP: u32 = 6;
+
+/// One button on a [`Dialog`]: its label and what choosing it does. The
+/// action is built lazily (`FnOnce`, like `Action::PushView`'s builder) and
+/// at most once — a dialog closes as soon as a choice is activated.
+struct DialogChoice {
+    label: String,
+    action: Option<Box<dyn FnOnce() -> Action>>,
+}
+
+pub struct Dialog {
+    title: String,
+    body: String,
+    choices: Vec<DialogChoice>,
+    selected: usize,
+}
+
+impl Dialog {
+    /// A dialog with no choices yet. One with none at all is a plain
+    /// notice: `Activate` or `Back` just closes it.
+    #[must_use]
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            choices: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Appends a choice button, laid out left to right in the order added.
+    /// `action` runs when the choice is activated, after the dialog has
+    /// closed — so returning `Action::PopView` pops the screen *under* the
+    /// dialog, and returning another `Action::ShowModal` chains a follow-up
+    /// question.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dialog already has [`MAX_CHOICES`] choices — a
+    /// programming error in whoever builds the dialog, not something user
+    /// input can cause.
+    #[must_use]
+    pub fn with_choice(
+        mut self,
+        label: impl Into<String>,
+        action: impl FnOnce() -> Action + 'static,
+    ) -> Self {
+        assert!(
+            self.choices.len() < MAX_CHOICES,
+            "a Dialog has at most {MAX_CHOICES} choices"
+        );
+        self.choices.push(DialogChoice {
+            label: label.into(),
+            action: Some(Box::new(action)),
+        });
+        self
+    }
+
+    /// Selects choice `index` initially (clamped to the last choice) —
+    /// e.g. to default a destructive confirmation to "Cancel" when that
+    /// isn't the first button.
+    #[must_use]
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.choices.len().saturating_sub(1));
+        self
+    }
+
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    #[must_use]
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// The choice labels, in button order.
+    pub fn choice_labels(&self) -> impl Iterator<Item = &str> {
+        self.choices.iter().map(|choice| choice.label.as_str())
+    }
+
+    #[must_use]
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    fn move_selection(&mut self, delta: i32) {
+        if self.choices.is_empty() {
+            return;
+        }
+        let len = self.choices.len() as i32;
+        self.selected = (self.selected as i32 + delta).clamp(0, len - 1) as usize;
+    }
+
+    /// The card's width for a screen `constraints.width` wide.
+    fn card_width(constraints: Size) -> u32 {
+        constraints
+            .width
+            .saturating_sub(2 * CARD_MARGIN)
+            .min(CARD_MAX_WIDTH)
+    }
+
+    /// The body wrapped to a card `card_width` wide, capped at as many
+    /// lines as fit on a screen `max_height` tall alongside the title and
+    /// buttons (always at least one, so a short screen still shows the
+    /// start of the question).
+    fn body_lines(&self, card_width: u32, max_height: u32, fonts: FontSet) -> Vec<String> {
+        let spare = max_height.saturating_sub(self.card_height(0, fonts) + section_gap(fonts));
+        let fitting = (spare / body_line_height(fonts)).max(1) as usize;
+        wrap_lines(
+            &fonts.username(),
+            &self.body,
+            card_width.saturating_sub(2 * card_padding(fonts)),
+            BODY_MAX_LINES.min(fitting),
+        )
+    }
+
+    fn card_height(&self, line_count: usize, fonts: FontSet) -> u32 {
+        let mut height = 2 * card_padding(fonts) + title_line_height(fonts);
+        if line_count > 0 {
+            height += section_gap(fonts) + line_count as u32 * body_line_height(fonts);
+        }
+        if !self.choices.is_empty() {
+            height += section_gap(fonts) + button_height(fonts);
+        }
+        height
+    }
+}
+
+const fn card_padding(fonts: FontSet) -> u32 {
+    match fonts {
+        FontSet::Compact => COMPACT_CARD_PADDING,
+        FontSet::Standard | FontSet::Large => CARD_PADDING,
+    }
+}
+
+const fn title_line_height(fonts: FontSet) -> u32 {
+    match fonts {
+        FontSet::Compact => COMPACT_TITLE_LINE_HEIGHT,
+        FontSet::Standard | FontSet::Large => TITLE_LINE_HEIGHT,
+    }
+}
+
+const fn section_gap(fonts: FontSet) -> u32 {
+    match fonts {
+        FontSet::Compact => COMPACT_SECTION_GAP,
+        FontSet::Standard | FontSet::Large => SECTION_GAP,
+    }
+}
+
+const fn button_height(fonts: FontSet) -> u32 {
+    match fonts {
+        FontSet::Compact => COMPACT_BUTTON_HEIGHT,
+        FontSet::Standard | FontSet::Large => BUTTON_HEIGHT,
+    }
+}
+
+/// Vertical pitch (px) of one wrapped body line in `fonts`. Not tightened
+/// for [`FontSet::Compact`]: its 5x7 footprint has no spare leading.
+const fn body_line_height(fonts: FontSet) -> u32 {
+    match fonts {
+        FontSet::Compact => fonts.username_line_footprint() as u32,
+        FontSet::Standard | FontSet::Large => {
+            (fonts.username_line_footprint() - BODY_LINE_TIGHTENING) as u32
+        }
+    }
+}
+
+/// [`wrap_text`] capped at `max_lines` lines, without trailing blank
+/// lines (a body ending in `\n` shouldn't grow the card).
+fn wrap_lines(font: &FontRenderer, text: &str, max_width: u32, max_lines: usize) -> Vec<String> {
+    let mut lines = wrap_text(font, text, max_width);
+    lines.truncate(max_lines);
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+impl Widget for Dialog {
+    /// The card's size for a screen of `constraints` — the `Navigator`
+    /// centers it within the whole screen, not the content region, so a
+    /// dialog is never squeezed by the chrome bars it dims.
+    fn measure(&self, constraints: Size, theme: &Theme) -> Size {
+        let width = Self::card_width(constraints);
+        let lines = self.body_lines(width, constraints.height, theme.fonts);
+        let height = self.card_height(lines.len(), theme.fonts);
+        Size::new(width, height.min(constraints.height))
+    }
+
+    fn render(
+        &self,
+        area: Rectangle,
+        theme: &Theme,
+        target: &mut FrameBuffer565,
+    ) -> Result<(), Infallible> {
+        let width = Self::card_width(area.size);
+        let lines = self.body_lines(width, area.size.height, theme.fonts);
+        let height = self
+            .card_height(lines.len(), theme.fonts)
+            .min(area.size.height);
+        let card = Rectangle::new(
+            Point::new(
+                area.top_left.x + (area.size.width.saturating_sub(width) / 2) as i32,
+                area.top_left.y + (area.size.height.saturating_sub(height) / 2) as i32,
+            ),
+            Size::new(width, height),
+        );
+
+        let card_style = PrimitiveStyleBuilder::new()
+            .fill_color(theme.palette.surface)
+            .stroke_color(theme.palette.divider)
+            .stroke_width(1)
+            .build();
+        card.into_styled(card_style).draw(target)?;
+
+        let mut clipped = target.clipped(&card);
+        let padding = card_padding(theme.fonts);
+        let left = card.top_left.x + padding as i32;
+        let mut y = card.top_left.y + padding as i32;
+
+        let _ = theme.fonts.title().render_aligned(
+            self.title.as_str(),
+            Point::new(left, y),
+            VerticalPosition::Top,
+            HorizontalAlignment::Left,
+            FontColor::Transparent(theme.palette.text_primary),
+            &mut clipped,
+        );
+        y += title_line_height(theme.fonts) as i32;
+
+        if !lines.is_empty() {
+            y += section_gap(theme.fonts) as i32;
+            for line in &lines {
+                let _ = theme.fonts.username().render_aligned(
+                    line.as_str(),
+                    Point::new(left, y),
+                    VerticalPosition::Top,
+                    HorizontalAlignment::Left,
+                    FontColor::Transparent(theme.palette.text_secondary),
+                    &mut clipped,
+                );
+                y += body_line_height(theme.fonts) as i32;
+            }
+        }
+
+        if !self.choices.is_empty() {
+            // Pinned to the card's bottom edge rather than following `y`,
+            // so the buttons stay visible when a short screen clamps the
+            // card's height and the body is what gets cut off.
+            let button_height = button_height(theme.fonts);
+            let buttons_top =
+                card.top_left.y + height.saturating_sub(padding + button_height) as i32;
+            let count = self.choices.len() as u32;
+            let inner_width = width.saturating_sub(2 * padding);
+            let button_width = inner_width.saturating_sub(BUTTON_GAP * (count - 1)) / count;
+            for (index, choice) in self.choices.iter().enumerate() {
+                let button = Rectangle::new(
+                    Point::new(
+                        left + (index as u32 * (button_width + BUTTON_GAP)) as i32,
+                        buttons_top,
+                    ),
+                    Size::new(button_width, button_height),
+                );
+                let (fill, text) = if index == self.selected {
+                    (theme.palette.brand, theme.palette.on_brand)
+                } else {
+                    (theme.palette.surface_elevated, theme.palette.text_secondary)
+                };
+                button
+                    .into_styled(PrimitiveStyle::with_fill(fill))
+                    .draw(&mut clipped)?;
+                let _ = theme.fonts.hint().render_aligned(
+                    choice.label.as_str(),
+                    button.center(),
+                    VerticalPosition::Center,
+                    HorizontalAlignment::Center,
+                    FontColor::Transparent(text),
+                    &mut clipped.clipped(&button),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    /// `Activated` runs the selected choice's action (once — the
+    /// `Navigator` closes the dialog straight after). Focus changes don't
+    /// apply: an open dialog always has the user's attention.
+    fn on_focus(&mut self, event: FocusEvent) -> Action {
+        match event {
+            FocusEvent::Activated => self
+                .choices
+                .get_mut(self.selected)
+                .and_then(|choice| choice.action.take())
+                .map_or(Action::None, |action| action()),
+            FocusEvent::Gained | FocusEvent::Lost => Action::None,
+        }
+    }
+
+    fn on_intent(&mut self, intent: NavIntent) -> Action {
+        match intent {
+            NavIntent::Next => self.move_selection(1),
+            NavIntent::Prev => self.move_selection(-1),
+            NavIntent::NextN(n) => self.move_selection(i32::from(n)),
+            NavIntent::PrevN(n) => self.move_selection(-i32::from(n)),
+            NavIntent::Home => self.move_selection(-(self.choices.len() as i32)),
+            NavIntent::End => self.move_selection(self.choices.len() as i32),
+            NavIntent::Char(_) | NavIntent::Activate | NavIntent::Back => {}
+        }
+        Action::None
+    }
+
+    /// The title, valued with the body, over one [`Role::Button`] per
+    /// choice with the highlighted one selected.
+    fn semantics(&self) -> Option<SemanticNode> {
+        let buttons = self
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(index, choice)| {
+                SemanticNode::new(Role::Button, choice.label.as_str())
+                    .with_selected(index == self.selected)
+            })
+            .collect();
+        Some(
+            SemanticNode::new(Role::Dialog, self.title.as_str())
+                .with_value(self.body.as_str())
+                .with_children(buttons),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::theme::Palette;
+
+    fn wipe_dialog() -> Dialog {
+        Dialog::new("Wipe vault?", "Every credential on this device is deleted.")
+            .with_choice("Cancel", || Action::None)
+            .with_choice("Wipe", || Action::PopView)
+    }
+
+    #[test]
+    fn next_and_prev_move_between_choices_and_clamp_at_the_ends() {
+        let mut dialog = wipe_dialog();
+        assert_eq!(dialog.selected_index(), 0);
+        dialog.on_intent(NavIntent::Next);
+        assert_eq!(dialog.selected_index(), 1);
+        dialog.on_intent(NavIntent::Next);
+        assert_eq!(dialog.selected_index(), 1);
+        dialog.on_intent(NavIntent::Prev);
+        dialog.on_intent(NavIntent::Prev);
+        assert_eq!(dialog.selected_index(), 0);
+        dialog.on_intent(NavIntent::NextN(9));
+        assert_eq!(dialog.selected_index(), 1);
+        dialog.on_intent(NavIntent::PrevN(9));
+        assert_eq!(dialog.selected_index(), 0);
+    }
+
+    #[test]
+    fn activating_runs_only_the_selected_choices_action() {
+        let mut dialog = wipe_dialog().with_selected(1);
+        assert!(matches!(
+            dialog.on_focus(FocusEvent::Activated),
+            Action::PopView
+        ));
+        // Already taken: a second activation can't run it twice.
+        assert!(matches!(
+            dialog.on_focus(FocusEvent::Activated),
+            Action::None
+        ));
+    }
+
+    #[test]
+    fn a_dialog_without_choices_activates_to_nothing() {
+        let mut dialog = Dialog::new("Synced", "");
+        dialog.on_intent(NavIntent::Next);
+        assert!(matches!(
+            dialog.on_focus(FocusEvent::Activated),
+            Action::None
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 3 choices")]
+    fn a_fourth_choice_panics() {
+        let _ = wipe_dialog()
+            .with_choice("Later", || Action::None)
+            .with_choice("Never", || Action::None);
+    }
+
+    #[test]
+    fn with_selected_clamps_to_the_last_choice() {
+        assert_eq!(wipe_dialog().with_selected(7).selected_index(), 1);
+        assert_eq!(Dialog::new("t", "b").with_selected(7).selected_index(), 0);
+    }
+
+    #[test]
+    fn body_wraps_to_the_card_width_and_caps_the_line_count() {
+        let short = wrap_lines(
+            &FontSet::Standard.username(),
+            "one two",
+            1000,
+            BODY_MAX_LINES,
+        );
+        assert_eq!(short, ["one two"]);
+
+        let narrow = wrap_lines(
+            &FontSet::Standard.username(),
+            "one two three four five",
+            40,
+            BODY_MAX_LINES,
+        );
+        assert_eq!(narrow.len(), BODY_MAX_LINES);
+        assert_eq!(narrow[0], "one");
+
+        let explicit = wrap_lines(
+            &FontSet::Standard.username(),
+            "first\nsecond",
+            1000,
+            BODY_MAX_LINES,
+        );
+        assert_eq!(explicit, ["first", "second"]);
+    }
+
+    #[test]
+    fn measure_fits_within_the_screen_and_grows_with_buttons() {
+        let with_buttons = wipe_dialog().measure(Size::new(320, 170), &Theme::DARK);
+        assert!(with_buttons.width <= CARD_MAX_WIDTH);
+        let without = Dialog::new("Wipe vault?", "Every credential on this device is deleted.")
+            .measure(Size::new(320, 170), &Theme::DARK);
+        assert_eq!(
+            with_buttons.height,
+            without.height + SECTION_GAP + BUTTON_HEIGHT
+        );
+
+        let tiny = wipe_dialog().measure(Size::new(128, 32), &Theme::DARK);
+        assert!(tiny.width <= 128 && tiny.height <= 32);
+    }
+
+    #[test]
+    fn a_compact_dialog_keeps_one_body_line_clear_of_its_buttons_on_a_strip() {
+        let compact = Theme {
+            fonts: FontSet::Compact,
+            ..Theme::DARK
+        };
+        let dialog = wipe_dialog();
+        let size = dialog.measure(Size::new(128, 32), &compact);
+        // Not clamped: title, one body line and the buttons all fit.
+        assert_eq!(size.height, dialog.card_height(1, FontSet::Compact));
+        assert!(size.height <= 32);
+        assert_eq!(dialog.body_lines(size.width, 32, FontSet::Compact).len(), 1);
+    }
+
+    #[test]
+    fn render_draws_a_centered_card_with_the_selected_button_in_brand_color() {
+        let mut fb = FrameBuffer565::new(320, 170);
+        let dialog = wipe_dialog().with_selected(1);
+        dialog
+            .render(
+                Rectangle::new(Point::zero(), Size::new(320, 170)),
+                &Theme::DARK,
+                &mut fb,
+            )
+            .unwrap();
+
+        let size = dialog.measure(Size::new(320, 170), &Theme::DARK);
+        let card_left = (320 - size.width as i32) / 2;
+        let card_bottom = (170 - size.height as i32) / 2 + size.height as i32;
+        // Outside the card: untouched.
+        assert_eq!(fb.pixel(Point::new(2, 2)), Default::default());
+        // The card's border.
+        assert_eq!(fb.pixel(Point::new(card_left, 85)), Palette::DARK.divider);
+        // Just above the bottom padding, near each button's left edge: the
+        // unselected "Cancel" and the selected "Wipe".
+        let button_y = card_bottom - CARD_PADDING as i32 - 2;
+        let first_button_x = card_left + CARD_PADDING as i32 + 1;
+        assert_eq!(
+            fb.pixel(Point::new(first_button_x, button_y)),
+            Palette::DARK.surface_elevated
+        );
+        let second_button_x = 160 + BUTTON_GAP as i32;
+        assert_eq!(
+            fb.pixel(Point::new(second_button_x, button_y)),
+            Palette::DARK.brand
+        );
+    }
+}
@@ -0,0 +1,719 @@
+//! `Form`: a focusable list of labelled setting rows, plus the four row
+//! kinds it's built from — [`Toggle`], [`EnumPicker`], [`Stepper`] and
+//! [`ActionRow`]. Written for the settings screen, but nothing here knows
+//! about settings: every row reads and writes its value through the
+//! closures it's built with, so any screen of "label: value" preferences
+//! can reuse them.
+//!
+//! `Form` is a single-container widget managing row focus internally —
+//! the same shape as `CredentialDetailView`, and for the same reason: the
+//! `Navigator`'s top-level focus cycling can't tell "move to the next row"
+//! from "turn this stepper up" (see `Navigator::dispatch`'s known
+//! simplification), so the form owns that decision itself.
+//!
+//! Editing model, for a one-knob device: rotating moves between rows;
+//! pressing a toggle flips it and pressing an action row runs it; pressing
+//! a picker or stepper enters *edit mode*, where rotating changes the value
+//! (applied live, so e.g. brightness previews as you turn) and pressing
+//! again leaves edit mode.
+//!
+//! Rows read their value through their getter on every render rather than
+//! caching it, so something that changes the underlying value behind the
+//! form's back (a "Reset to defaults" action) is reflected immediately.
+
+use std::cell::Cell;
+use std::convert::Infallible;
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use embedded_graphics::prelude::{Point, Primitive, Size};
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::Drawable;
+use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
+
+use crate::input::NavIntent;
+
+use super::framebuffer::FrameBuffer565;
+use super::list::reconcile_top_index;
+use super::semantics::{Role, SemanticNode};
+use super::theme::{self, font, icon, Theme, SELECTION_ACCENT_WIDTH};
+use super::widget::{Action, ChromeContribution, FocusEvent, Widget};
+
+/// Height (px) of one form row: a single line of the username font with
+/// room to breathe — settings rows have no second line, unlike credential
+/// rows. Fixed across font sets: even [`FontSet::Large`]'s username font
+/// (17 px) fits.
+///
+/// [`FontSet::Large`]: super::theme::FontSet::Large
+pub const FORM_ROW_HEIGHT: u32 = 26;
+/// Horizontal padding (px) inside a row, left of the label (past the
+/// selection accent bar) and right of the value.
+const ROW_SIDE_PADDING: i32 = 8;
+
+/// One row of a [`Form`].
+pub trait FormRow {
+    /// The row's left-aligned label.
+    fn label(&self) -> &str;
+
+    /// The row's right-aligned value text, or `None` for a row that has no
+    /// value (an [`ActionRow`], which draws a caret instead).
+    fn value(&self) -> Option<String>;
+
+    /// What kind of row this is, for its [`SemanticNode`].
+    fn role(&self) -> Role;
+
+    /// The row was pressed while not editing. Returns whatever the
+    /// `Navigator` should do next (usually [`Action::None`]).
+    fn activate(&mut self) -> Action;
+
+    /// Whether the row is in edit mode, i.e. wants rotation for itself.
+    fn is_editing(&self) -> bool {
+        false
+    }
+
+    /// Rotation while editing: move the value by `steps` (negative for
+    /// `Prev`).
+    fn adjust(&mut self, _steps: i32) {}
+}
+
+type Getter<T> = Box<dyn Fn() -> T>;
+type Setter<T> = Box<dyn Fn(T)>;
+
+/// An on/off row. Pressing it flips the value.
+pub struct Toggle {
+    label: String,
+    get: Getter<bool>,
+    set: Setter<bool>,
+}
+
+impl Toggle {
+    #[must_use]
+    pub fn new(
+        label: impl Into<String>,
+        get: impl Fn() -> bool + 'static,
+        set: impl Fn(bool) + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            get: Box::new(get),
+            set: Box::new(set),
+        }
+    }
+}
+
+impl FormRow for Toggle {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn value(&self) -> Option<String> {
+        Some(if (self.get)() { "On" } else { "Off" }.to_string())
+    }
+
+    fn role(&self) -> Role {
+        Role::Toggle
+    }
+
+    fn activate(&mut self) -> Action {
+        (self.set)(!(self.get)());
+        Action::None
+    }
+}
+
+/// A row picking one of a fixed set of options, shown by name.
+pub struct EnumPicker<T> {
+    label: String,
+    options: Vec<(T, String)>,
+    get: Getter<T>,
+    set: Setter<T>,
+    editing: bool,
+}
+
+impl<T: Copy + PartialEq + 'static> EnumPicker<T> {
+    /// `options` are `(value, display name)` pairs in picker order.
+    #[must_use]
+    pub fn new(
+        label: impl Into<String>,
+        options: Vec<(T, String)>,
+        get: impl Fn() -> T + 'static,
+        set: impl Fn(T) + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            options,
+            get: Box::new(get),
+            set: Box::new(set),
+            editing: false,
+        }
+    }
+
+    fn current_index(&self) -> Option<usize> {
+        let current = (self.get)();
+        self.options.iter().position(|(value, _)| *value == current)
+    }
+}
+
+impl<T: Copy + PartialEq + 'static> FormRow for EnumPicker<T> {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn value(&self) -> Option<String> {
+        let name = self
+            .current_index()
+            .map_or("?", |index| self.options[index].1.as_str());
+        Some(name.to_string())
+    }
+
+    fn role(&self) -> Role {
+        Role::Picker
+    }
+
+    fn activate(&mut self) -> Action {
+        self.editing = !self.editing;
+        Action::None
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    fn adjust(&mut self, steps: i32) {
+        if self.options.is_empty() {
+            return;
+        }
+        let last = self.options.len() as i32 - 1;
+        let next = (self.current_index().unwrap_or(0) as i32 + steps).clamp(0, last);
+        (self.set)(self.options[next as usize].0);
+    }
+}
+
+/// A numeric row stepping between `min` and `max` in increments of `step`.
+pub struct Stepper {
+    label: String,
+    min: i32,
+    max: i32,
+    step: i32,
+    get: Getter<i32>,
+    set: Setter<i32>,
+    format: Box<dyn Fn(i32) -> String>,
+    editing: bool,
+}
+
+impl Stepper {
+    #[must_use]
+    pub fn new(
+        label: impl Into<String>,
+        range: (i32, i32),
+        step: i32,
+        get: impl Fn() -> i32 + 'static,
+        set: impl Fn(i32) + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            min: range.0,
+            max: range.1,
+            step,
+            get: Box::new(get),
+            set: Box::new(set),
+            format: Box::new(|value| value.to_string()),
+            editing: false,
+        }
+    }
+
+    /// How the value is displayed (e.g. `|v| format!("{v}%")`). Defaults
+    /// to the bare number.
+    #[must_use]
+    pub fn with_format(mut self, format: impl Fn(i32) -> String + 'static) -> Self {
+        self.format = Box::new(format);
+        self
+    }
+}
+
+impl FormRow for Stepper {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn value(&self) -> Option<String> {
+        Some((self.format)((self.get)()))
+    }
+
+    fn role(&self) -> Role {
+        Role::Stepper
+    }
+
+    fn activate(&mut self) -> Action {
+        self.editing = !self.editing;
+        Action::None
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    fn adjust(&mut self, steps: i32) {
+        let next = (self.get)().saturating_add(steps.saturating_mul(self.step));
+        (self.set)(next.clamp(self.min, self.max));
+    }
+}
+
+/// A row that does something when pressed (opens a screen, asks for
+/// confirmation, ...) rather than holding a value.
+pub struct ActionRow {
+    label: String,
+    on_activate: Box<dyn Fn() -> Action>,
+}
+
+impl ActionRow {
+    #[must_use]
+    pub fn new(label: impl Into<String>, on_activate: impl Fn() -> Action + 'static) -> Self {
+        Self {
+            label: label.into(),
+            on_activate: Box::new(on_activate),
+        }
+    }
+}
+
+impl FormRow for ActionRow {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn value(&self) -> Option<String> {
+        None
+    }
+
+    fn role(&self) -> Role {
+        Role::Button
+    }
+
+    fn activate(&mut self) -> Action {
+        (self.on_activate)()
+    }
+}
+
+/// A focusable, scrollable list of [`FormRow`]s. See the module doc for
+/// the editing model.
+pub struct Form {
+    rows: Vec<Box<dyn FormRow>>,
+    selected: usize,
+    /// The row scrolled to the top of the viewport; `Cell` because it's
+    /// reconciled in `render` (`&self`), as in `VerticalList`.
+    top_index: Cell<usize>,
+    focused: bool,
+}
+
+impl Form {
+    #[must_use]
+    pub fn new(rows: Vec<Box<dyn FormRow>>) -> Self {
+        Self {
+            rows,
+            selected: 0,
+            top_index: Cell::new(0),
+            focused: false,
+        }
+    }
+
+    #[must_use]
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Whether the selected row is in edit mode.
+    #[must_use]
+    pub fn is_editing(&self) -> bool {
+        self.rows
+            .get(self.selected)
+            .is_some_and(|row| row.is_editing())
+    }
+
+    fn step(&mut self, steps: i32) {
+        if self.is_editing() {
+            self.rows[self.selected].adjust(steps);
+        } else if !self.rows.is_empty() {
+            let last = self.rows.len() as i32 - 1;
+            self.selected = (self.selected as i32 + steps).clamp(0, last) as usize;
+        }
+    }
+
+    fn render_row(
+        &self,
+        row: &dyn FormRow,
+        selected: bool,
+        rect: Rectangle,
+        theme: &Theme,
+        target: &mut impl embedded_graphics::draw_target::DrawTarget<
+            Color = embedded_graphics::pixelcolor::Rgb565,
+            Error = Infallible,
+        >,
+    ) -> Result<(), Infallible> {
+        if selected {
+            theme::draw_selection(rect, theme, target)?;
+        } else {
+            let divider = Rectangle::new(
+                Point::new(
+                    rect.top_left.x,
+                    rect.top_left.y + rect.size.height as i32 - 1,
+                ),
+                Size::new(rect.size.width, 1),
+            );
+            divider
+                .into_styled(PrimitiveStyle::with_fill(theme.palette.divider))
+                .draw(target)?;
+        }
+
+        let mid_y = rect.top_left.y + rect.size.height as i32 / 2;
+        let right = rect.top_left.x + rect.size.width as i32 - ROW_SIDE_PADDING;
+        let _ = theme.fonts.username().render_aligned(
+            row.label(),
+            Point::new(
+                rect.top_left.x + SELECTION_ACCENT_WIDTH as i32 + ROW_SIDE_PADDING,
+                mid_y,
+            ),
+            VerticalPosition::Center,
+            HorizontalAlignment::Left,
+            FontColor::Transparent(theme.palette.text_primary),
+            target,
+        );
+
+        match row.value() {
+            Some(value) => {
+                let (text, color) = if row.is_editing() {
+                    (format!("< {value} >"), theme.palette.brand_bright)
+                } else {
+                    (value, theme.palette.text_secondary)
+                };
+                let _ = theme.fonts.username().render_aligned(
+                    text.as_str(),
+                    Point::new(right, mid_y),
+                    VerticalPosition::Center,
+                    HorizontalAlignment::Right,
+                    FontColor::Transparent(color),
+                    target,
+                );
+            }
+            None if selected => {
+                let mut buf = [0_u8; 4];
+                let caret: &str = icon::CARET_RIGHT.encode_utf8(&mut buf);
+                let _ = font::icon_1x().render_aligned(
+                    caret,
+                    Point::new(right, mid_y),
+                    VerticalPosition::Center,
+                    HorizontalAlignment::Right,
+                    FontColor::Transparent(theme.palette.text_primary),
+                    target,
+                );
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Form {
+    fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
+        constraints
+    }
+
+    fn render(
+        &self,
+        area: Rectangle,
+        theme: &Theme,
+        target: &mut FrameBuffer565,
+    ) -> Result<(), Infallible> {
+        let mut clipped = target.clipped(&area);
+        let visible_rows = (area.size.height / FORM_ROW_HEIGHT).max(1) as usize;
+        let top = reconcile_top_index(
+            self.top_index.get(),
+            self.selected,
+            visible_rows,
+            self.rows.len(),
+        );
+        self.top_index.set(top);
+
+        for (index, row) in self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(visible_rows + 1)
+        {
+            let rect = Rectangle::new(
+                Point::new(
+                    area.top_left.x,
+                    area.top_left.y + ((index - top) as u32 * FORM_ROW_HEIGHT) as i32,
+                ),
+                Size::new(area.size.width, FORM_ROW_HEIGHT),
+            );
+            self.render_row(
+                row.as_ref(),
+                self.focused && index == self.selected,
+                rect,
+                theme,
+                &mut clipped,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.rows.is_empty()
+    }
+
+    fn on_focus(&mut self, event: FocusEvent) -> Action {
+        match event {
+            FocusEvent::Gained => {
+                self.focused = true;
+                Action::None
+            }
+            FocusEvent::Lost => {
+                self.focused = false;
+                Action::None
+            }
+            FocusEvent::Activated => match self.rows.get_mut(self.selected) {
+                Some(row) => row.activate(),
+                None => Action::None,
+            },
+        }
+    }
+
+    fn on_intent(&mut self, intent: NavIntent) -> Action {
+        match intent {
+            NavIntent::Next => self.step(1),
+            NavIntent::Prev => self.step(-1),
+            NavIntent::NextN(n) => self.step(i32::from(n)),
+            NavIntent::PrevN(n) => self.step(-i32::from(n)),
+            // First/last row only: a row being edited has no "extreme" a
+            // jump could sensibly land on.
+            NavIntent::Home if !self.is_editing() => self.step(-(self.rows.len() as i32)),
+            NavIntent::End if !self.is_editing() => self.step(self.rows.len() as i32),
+            NavIntent::Home
+            | NavIntent::End
+            | NavIntent::Char(_)
+            | NavIntent::Activate
+            | NavIntent::Back => {}
+        }
+        Action::None
+    }
+
+    /// A "2 / 5" readout and a hint that switches to the edit-mode legend
+    /// while a row is being edited — the only on-screen cue that rotating
+    /// now changes a value instead of moving between rows.
+    fn chrome_contribution(&self) -> Option<ChromeContribution> {
+        let hint = if self.is_editing() {
+            "Rotate to change - Press to confirm"
+        } else {
+            "Rotate to browse - Press to change - Hold to go back"
+        };
+        Some(ChromeContribution {
+            title: None,
+            readout: (!self.rows.is_empty())
+                .then(|| format!("{} / {}", self.selected + 1, self.rows.len())),
+            hint: Some(hint.to_string()),
+            status: None,
+        })
+    }
+
+    /// One node per row, by [`FormRow::role`], valued with what the row
+    /// shows.
+    fn semantics(&self) -> Option<SemanticNode> {
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                SemanticNode::new(row.role(), row.label())
+                    .with_optional_value(row.value())
+                    .with_selected(index == self.selected)
+            })
+            .collect();
+        Some(SemanticNode::new(Role::Form, "").with_children(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::theme::Palette;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Fruit {
+        Apple,
+        Pear,
+        Plum,
+    }
+
+    struct Values {
+        flag: Rc<Cell<bool>>,
+        fruit: Rc<Cell<Fruit>>,
+        number: Rc<Cell<i32>>,
+        pressed: Rc<Cell<u32>>,
+    }
+
+    fn form() -> (Form, Values) {
+        let values = Values {
+            flag: Rc::new(Cell::new(false)),
+            fruit: Rc::new(Cell::new(Fruit::Apple)),
+            number: Rc::new(Cell::new(50)),
+            pressed: Rc::new(Cell::new(0)),
+        };
+        let (flag_get, flag_set) = (Rc::clone(&values.flag), Rc::clone(&values.flag));
+        let (fruit_get, fruit_set) = (Rc::clone(&values.fruit), Rc::clone(&values.fruit));
+        let (number_get, number_set) = (Rc::clone(&values.number), Rc::clone(&values.number));
+        let pressed = Rc::clone(&values.pressed);
+        let rows: Vec<Box<dyn FormRow>> = vec![
+            Box::new(Toggle::new(
+                "Flag",
+                move || flag_get.get(),
+                move |v| flag_set.set(v),
+            )),
+            Box::new(EnumPicker::new(
+                "Fruit",
+                vec![
+                    (Fruit::Apple, "Apple".into()),
+                    (Fruit::Pear, "Pear".into()),
+                    (Fruit::Plum, "Plum".into()),
+                ],
+                move || fruit_get.get(),
+                move |v| fruit_set.set(v),
+            )),
+            Box::new(
+                Stepper::new(
+                    "Number",
+                    (10, 100),
+                    10,
+                    move || number_get.get(),
+                    move |v| number_set.set(v),
+                )
+                .with_format(|v| format!("{v}%")),
+            ),
+            Box::new(ActionRow::new("Press me", move || {
+                pressed.set(pressed.get() + 1);
+                Action::PopView
+            })),
+        ];
+        let mut form = Form::new(rows);
+        form.on_focus(FocusEvent::Gained);
+        (form, values)
+    }
+
+    #[test]
+    fn rotating_moves_between_rows_and_clamps() {
+        let (mut form, _) = form();
+        form.on_intent(NavIntent::NextN(10));
+        assert_eq!(form.selected_index(), 3);
+        form.on_intent(NavIntent::Prev);
+        assert_eq!(form.selected_index(), 2);
+    }
+
+    #[test]
+    fn pressing_a_toggle_flips_it() {
+        let (mut form, values) = form();
+        form.on_focus(FocusEvent::Activated);
+        assert!(values.flag.get());
+        assert!(!form.is_editing(), "a toggle never enters edit mode");
+        form.on_focus(FocusEvent::Activated);
+        assert!(!values.flag.get());
+    }
+
+    #[test]
+    fn a_picker_in_edit_mode_takes_rotation_for_itself() {
+        let (mut form, values) = form();
+        form.on_intent(NavIntent::Next);
+        form.on_focus(FocusEvent::Activated);
+        assert!(form.is_editing());
+
+        form.on_intent(NavIntent::Next);
+        assert_eq!(
+            values.fruit.get(),
+            Fruit::Pear,
+            "applied live, before confirming"
+        );
+        form.on_intent(NavIntent::NextN(5));
+        assert_eq!(
+            values.fruit.get(),
+            Fruit::Plum,
+            "clamped at the last option"
+        );
+        assert_eq!(
+            form.selected_index(),
+            1,
+            "row selection didn't move while editing"
+        );
+
+        form.on_focus(FocusEvent::Activated);
+        assert!(!form.is_editing());
+        form.on_intent(NavIntent::Next);
+        assert_eq!(form.selected_index(), 2);
+    }
+
+    #[test]
+    fn a_stepper_moves_by_its_step_within_its_range() {
+        let (mut form, values) = form();
+        form.on_intent(NavIntent::NextN(2));
+        form.on_focus(FocusEvent::Activated);
+        form.on_intent(NavIntent::Next);
+        assert_eq!(values.number.get(), 60);
+        form.on_intent(NavIntent::NextN(20));
+        assert_eq!(values.number.get(), 100);
+        for _ in 0..20 {
+            form.on_intent(NavIntent::Prev);
+        }
+        assert_eq!(values.number.get(), 10);
+        assert_eq!(form.rows[2].value().as_deref(), Some("10%"));
+    }
+
+    #[test]
+    fn an_action_row_returns_its_action() {
+        let (mut form, values) = form();
+        form.on_intent(NavIntent::NextN(3));
+        assert!(matches!(
+            form.on_focus(FocusEvent::Activated),
+            Action::PopView
+        ));
+        assert_eq!(values.pressed.get(), 1);
+    }
+
+    #[test]
+    fn rows_read_their_value_live() {
+        let shared = Rc::new(RefCell::new(false));
+        let reader = Rc::clone(&shared);
+        let toggle = Toggle::new("Flag", move || *reader.borrow(), |_| {});
+        assert_eq!(toggle.value().as_deref(), Some("Off"));
+        *shared.borrow_mut() = true;
+        assert_eq!(toggle.value().as_deref(), Some("On"));
+    }
+
+    #[test]
+    fn the_hint_switches_to_the_edit_legend_while_editing() {
+        let (mut form, _) = form();
+        form.on_intent(NavIntent::Next);
+        let browsing = form.chrome_contribution().unwrap();
+        assert_eq!(browsing.readout.as_deref(), Some("2 / 4"));
+        form.on_focus(FocusEvent::Activated);
+        let editing = form.chrome_contribution().unwrap();
+        assert_ne!(browsing.hint, editing.hint);
+    }
+
+    #[test]
+    fn render_highlights_the_selected_row() {
+        let (mut form, _) = form();
+        form.on_intent(NavIntent::Next);
+        let mut fb = FrameBuffer565::new(320, 150);
+        form.render(
+            Rectangle::new(Point::zero(), Size::new(320, 150)),
+            &Theme::DARK,
+            &mut fb,
+        )
+        .unwrap();
+        let row_1_y = FORM_ROW_HEIGHT as i32 + 2;
+        assert_eq!(
+            fb.pixel(Point::new(0, row_1_y)),
+            Palette::DARK.brand_bright,
+            "selection accent bar"
+        );
+        assert_ne!(fb.pixel(Point::new(0, 2)), Palette::DARK.brand_bright);
+    }
+}
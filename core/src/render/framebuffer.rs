@@ -14,6 +14,19 @@
 //! `FrameBufferBackend` over a `Vec<Rgb565>` instead, so [`FrameBuffer565`]
 //! can be sized at runtime from whatever `DisplaySurface` reports.
 //!
+//! # Damage tracking
+//!
+//! Every dirty frame is a full repaint — `Navigator::render` clears the
+//! buffer before drawing — so "what did this frame draw" is always the
+//! whole screen and says nothing about what a panel actually needs
+//! resent. What the buffer tracks instead is two-level: draws mark the
+//! [`DAMAGE_TILE`]-sized tiles their pixels fell in as *touched*, and
+//! [`FrameBuffer565::take_damage`] hashes only the touched tiles and
+//! reports the ones whose contents differ from the last take, merged into
+//! rectangles. A focused row moving, or a countdown ticking, damages a
+//! handful of tiles; repainting identical pixels damages none. That list
+//! is what `DisplaySurface::flush` receives.
+//!
 //! See: .planning/decisions/2026-08-11-presentation-surface-run-mode-seam.md
 
 use std::convert::Infallible;
@@ -21,8 +34,9 @@ use std::convert::Infallible;
 use embedded_graphics::{
     draw_target::DrawTarget,
     geometry::OriginDimensions,
-    pixelcolor::{IntoStorage, Rgb565},
+    pixelcolor::{BinaryColor, IntoStorage, Rgb565, RgbColor},
     prelude::{Point, Size},
+    primitives::Rectangle,
     Pixel,
 };
 use embedded_graphics_framebuf::{backends::FrameBufferBackend, FrameBuf};
@@ -49,12 +63,72 @@ impl FrameBufferBackend for HeapBuffer {
     }
 }
 
+/// Side of the square tiles damage is tracked in, in pixels. Small enough
+/// that one changed list row damages a strip rather than half the panel,
+/// large enough that the per-tile bookkeeping for a 320x170 frame is a few
+/// hundred entries.
+pub const DAMAGE_TILE: u32 = 16;
+
+/// Per-tile damage state (see the module doc's "Damage tracking").
+struct DamageTracker {
+    /// Tile grid size: the frame's width/height in tiles, rounded up.
+    columns: u32,
+    rows: u32,
+    /// Tiles drawn into since the last [`FrameBuffer565::take_damage`].
+    touched: Vec<bool>,
+    /// Each tile's content hash as of the last `take_damage`; `None`
+    /// until it has been reported once, so the first take reports all.
+    reported: Vec<Option<u64>>,
+}
+
+impl DamageTracker {
+    fn new(width: u32, height: u32) -> Self {
+        let columns = width.div_ceil(DAMAGE_TILE);
+        let rows = height.div_ceil(DAMAGE_TILE);
+        let tiles = (columns * rows) as usize;
+        Self {
+            columns,
+            rows,
+            touched: vec![true; tiles],
+            reported: vec![None; tiles],
+        }
+    }
+
+    /// Marks every tile overlapping the pixel span `min..=max` touched.
+    fn touch(&mut self, min: Point, max: Point) {
+        let (x0, y0) = (min.x as u32 / DAMAGE_TILE, min.y as u32 / DAMAGE_TILE);
+        let (x1, y1) = (max.x as u32 / DAMAGE_TILE, max.y as u32 / DAMAGE_TILE);
+        for row in y0..=y1 {
+            let start = (row * self.columns) as usize;
+            self.touched[start + x0 as usize..=start + x1 as usize].fill(true);
+        }
+    }
+
+    fn touch_all(&mut self) {
+        self.touched.fill(true);
+    }
+}
+
+/// FNV-1a over a tile's pixels. 64 bits, so two different contents of the
+/// same tile colliding (and a change going unflushed) is not a practical
+/// concern for a few hundred tiles per frame.
+fn tile_hash(pixels: impl Iterator<Item = Rgb565>) -> u64 {
+    pixels.fold(0xcbf2_9ce4_8422_2325, |hash, color| {
+        color
+            .into_storage()
+            .to_le_bytes()
+            .into_iter()
+            .fold(hash, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3))
+    })
+}
+
 /// The shared Rgb565 framebuffer the render core draws into and a
 /// `DisplaySurface` implementation flushes out. Resolution is a runtime
 /// parameter (see [`FrameBuffer565::new`]) — nothing in this type, or in
 /// anything that draws into it, hardcodes a specific panel's dimensions.
 pub struct FrameBuffer565 {
     inner: FrameBuf<Rgb565, HeapBuffer>,
+    damage: DamageTracker,
 }
 
 impl FrameBuffer565 {
@@ -70,6 +144,7 @@ impl FrameBuffer565 {
         let data = vec![Rgb565::default(); pixel_count];
         Self {
             inner: FrameBuf::new(HeapBuffer(data), width as usize, height as usize),
+            damage: DamageTracker::new(width, height),
         }
     }
 
@@ -133,6 +208,123 @@ impl FrameBuffer565 {
             chunk.copy_from_slice(&color.into_storage().to_be_bytes());
         }
     }
+
+    /// Writes the pixels inside `area` in the same panel byte order as
+    /// [`Self::write_be_bytes`], row by row: the payload for a panel
+    /// address window set to exactly `area` — how `St7789Surface` sends
+    /// one damage rectangle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `area` isn't entirely inside the buffer, or if
+    /// `out.len() != area.width * area.height * 2`.
+    pub fn write_be_bytes_in(&self, area: &Rectangle, out: &mut [u8]) {
+        let colors: &[Rgb565] = &self.inner.data.0;
+        let width = self.width() as usize;
+        let (x, y) = (area.top_left.x as usize, area.top_left.y as usize);
+        let (area_width, area_height) = (area.size.width as usize, area.size.height as usize);
+        assert!(
+            area.top_left.x >= 0 && area.top_left.y >= 0 && x + area_width <= width && y + area_height <= self.height() as usize,
+            "write_be_bytes_in: `area` must lie inside the framebuffer"
+        );
+        assert_eq!(out.len(), area_width * area_height * 2, "write_be_bytes_in: `out` must be exactly area width*height*2 bytes");
+
+        for (row, out_row) in out.chunks_exact_mut(area_width * 2).enumerate() {
+            let start = (y + row) * width + x;
+            for (chunk, color) in out_row.chunks_exact_mut(2).zip(&colors[start..start + area_width]) {
+                chunk.copy_from_slice(&color.into_storage().to_be_bytes());
+            }
+        }
+    }
+
+    /// Returns the regions whose pixels changed since the previous call
+    /// (everything, on the first), and starts tracking afresh. Regions are
+    /// [`DAMAGE_TILE`]-aligned (clipped to the buffer's edge), disjoint,
+    /// and ordered top to bottom, then left to right; a tile row's
+    /// adjacent damaged tiles are merged into one span, and a span
+    /// repeated on the next tile row extends downwards. An empty list means
+    /// the frame is pixel-identical to the last one reported.
+    ///
+    /// Whoever calls this owns the result: the damage is consumed, so a
+    /// surface that fails to send part of it must fall back to a full
+    /// flush next time (see `St7789Surface`).
+    pub fn take_damage(&mut self) -> Vec<Rectangle> {
+        let (columns, rows) = (self.damage.columns, self.damage.rows);
+        let touched = std::mem::replace(&mut self.damage.touched, vec![false; (columns * rows) as usize]);
+        let mut damaged = vec![false; touched.len()];
+        for (index, _) in touched.iter().enumerate().filter(|(_, touched)| **touched) {
+            let tile = self.tile_rect(index as u32 % columns, index as u32 / columns);
+            let hash = tile_hash(self.colors_in(&tile));
+            if self.damage.reported[index] != Some(hash) {
+                self.damage.reported[index] = Some(hash);
+                damaged[index] = true;
+            }
+        }
+
+        let mut rects: Vec<Rectangle> = Vec::new();
+        // Index into `rects` of each span still open from the row above,
+        // keyed by its (first, last) tile column.
+        let mut open: Vec<((u32, u32), usize)> = Vec::new();
+        for row in 0..rows {
+            let mut still_open = Vec::new();
+            let mut column = 0;
+            while column < columns {
+                if !damaged[(row * columns + column) as usize] {
+                    column += 1;
+                    continue;
+                }
+                let first = column;
+                while column < columns && damaged[(row * columns + column) as usize] {
+                    column += 1;
+                }
+                let span = (first, column - 1);
+                let bottom = self.tile_rect(0, row).bottom_right().unwrap().y;
+                if let Some(&(_, index)) = open.iter().find(|(open_span, _)| *open_span == span) {
+                    let rect = &mut rects[index];
+                    rect.size.height = (bottom - rect.top_left.y + 1) as u32;
+                    still_open.push((span, index));
+                } else {
+                    let top_left = self.tile_rect(span.0, row).top_left;
+                    let bottom_right = self.tile_rect(span.1, row).bottom_right().unwrap();
+                    still_open.push((span, rects.len()));
+                    rects.push(Rectangle::with_corners(top_left, bottom_right));
+                }
+            }
+            open = still_open;
+        }
+        rects.sort_by_key(|rect| (rect.top_left.y, rect.top_left.x));
+        rects
+    }
+
+    /// Tile (`column`, `row`)'s pixel rectangle, clipped to the buffer.
+    fn tile_rect(&self, column: u32, row: u32) -> Rectangle {
+        let (x, y) = (column * DAMAGE_TILE, row * DAMAGE_TILE);
+        Rectangle::new(
+            Point::new(x as i32, y as i32),
+            Size::new(DAMAGE_TILE.min(self.width() - x), DAMAGE_TILE.min(self.height() - y)),
+        )
+    }
+
+    /// The colors inside `area` (already inside the buffer), row-major.
+    fn colors_in<'a>(&'a self, area: &Rectangle) -> impl Iterator<Item = Rgb565> + 'a {
+        let colors: &[Rgb565] = &self.inner.data.0;
+        let width = self.width() as usize;
+        let (x, y) = (area.top_left.x as usize, area.top_left.y as usize);
+        let area_width = area.size.width as usize;
+        (y..y + area.size.height as usize).flat_map(move |row| colors[row * width + x..row * width + x + area_width].iter().copied())
+    }
+
+    /// Darkens every pixel already in the buffer to half brightness
+    /// (each channel shifted right by one). This is how the `Navigator`
+    /// dims the screen beneath an open modal: a single pass over what was
+    /// just rendered, rather than every widget knowing how to draw a
+    /// "disabled" variant of itself.
+    pub fn dim(&mut self) {
+        self.damage.touch_all();
+        for color in &mut self.inner.data.0 {
+            *color = Rgb565::new(color.r() >> 1, color.g() >> 1, color.b() >> 1);
+        }
+    }
 }
 
 impl OriginDimensions for FrameBuffer565 {
@@ -148,24 +340,215 @@ impl DrawTarget for FrameBuffer565 {
     type Color = Rgb565;
     type Error = Infallible;
 
+    /// Also records the bounding box of the pixels that landed inside the
+    /// buffer as touched, for [`FrameBuffer565::take_damage`].
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        self.inner.draw_iter(pixels)
+        let (width, height) = (self.width() as i32, self.height() as i32);
+        let mut bounds: Option<(Point, Point)> = None;
+        let inside = pixels.into_iter().filter(|Pixel(p, _)| p.x >= 0 && p.y >= 0 && p.x < width && p.y < height);
+        let result = self.inner.draw_iter(inside.inspect(|Pixel(p, _)| {
+            bounds = Some(match bounds {
+                Some((min, max)) => (min.component_min(*p), max.component_max(*p)),
+                None => (*p, *p),
+            });
+        }));
+        if let Some((min, max)) = bounds {
+            self.damage.touch(min, max);
+        }
+        result
     }
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.damage.touch_all();
         self.inner.clear(color)
     }
 }
 
+/// How [`MonoFrameBuffer::from_rgb565`] turns a color into on or off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// On when the pixel's luminance is at least half. Crisp text and
+    /// edges; every surface darker than mid-grey goes black, so a theme's
+    /// fills (selection, chips, bars) vanish into the background.
+    Threshold,
+    /// A 4x4 Bayer matrix: each pixel is compared against a threshold
+    /// that varies with its position, so a flat fill comes out as a dot
+    /// pattern whose density tracks its luminance. Keeps a dark theme's
+    /// surfaces distinguishable from each other, at the cost of a
+    /// stippled look; text (near-white on near-black) is unaffected.
+    #[default]
+    Ordered,
+}
+
+/// The 4x4 Bayer index matrix, `0..16`, for [`Dither::Ordered`].
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+/// The lowest [`Dither::Ordered`] threshold. Above zero so every theme's
+/// near-black background stays solidly off instead of dusting the whole
+/// panel with its sparsest dot pattern.
+const ORDERED_FLOOR: u8 = 16;
+/// Spacing between the sixteen [`Dither::Ordered`] thresholds, which run
+/// from [`ORDERED_FLOOR`] to `241` so white is still solidly on.
+const ORDERED_STEP: u8 = 15;
+
+/// Relative luminance (Rec. 709 weights) of an `Rgb565` color, `0..=255`,
+/// from each channel widened to 8 bits.
+fn luma(color: Rgb565) -> u8 {
+    let widen = |value: u8, bits: u32| u32::from(value) * 255 / ((1 << bits) - 1);
+    let (r, g, b) = (widen(color.r(), 5), widen(color.g(), 6), widen(color.b(), 5));
+    ((54 * r + 183 * g + 19 * b) >> 8) as u8
+}
+
+/// A 1-bit framebuffer for monochrome panels (the SSD1306 OLED). The
+/// render core never draws into this directly: it renders into the usual
+/// [`FrameBuffer565`], and a monochrome panel's `DisplaySurface` converts
+/// that with [`Self::from_rgb565`] — so themes, widgets and tests stay
+/// color, and a 1-bit panel is purely a flush-side concern, the same seam
+/// the presentation-surface ADR draws for every other panel difference.
+///
+/// Stored row-major, eight pixels per byte, most significant bit
+/// leftmost, each row padded to a whole byte. Also a `DrawTarget` of
+/// `BinaryColor`, for drawing overlays after conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonoFrameBuffer {
+    width: u32,
+    height: u32,
+    bits: Vec<u8>,
+}
+
+impl MonoFrameBuffer {
+    /// Allocates a new framebuffer of the given size, all pixels off.
+    #[must_use]
+    pub fn new(width: u32, height: u32) -> Self {
+        let stride = width.div_ceil(8) as usize;
+        Self {
+            width,
+            height,
+            bits: vec![0; stride * height as usize],
+        }
+    }
+
+    /// Converts a rendered frame to one bit per pixel with `dither`.
+    #[must_use]
+    pub fn from_rgb565(source: &FrameBuffer565, dither: Dither) -> Self {
+        let mut mono = Self::new(source.width(), source.height());
+        for (index, color) in source.inner.data.0.iter().enumerate() {
+            let x = index as u32 % mono.width;
+            let y = index as u32 / mono.width;
+            let threshold = match dither {
+                Dither::Threshold => 128,
+                Dither::Ordered => ORDERED_FLOOR + BAYER_4X4[(y % 4) as usize][(x % 4) as usize] * ORDERED_STEP,
+            };
+            if luma(*color) >= threshold {
+                mono.set(x, y, true);
+            }
+        }
+        mono
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Reads back a single pixel; off for anything outside the buffer.
+    #[must_use]
+    pub fn pixel(&self, p: Point) -> BinaryColor {
+        let on = self
+            .index(p)
+            .is_some_and(|(byte, mask)| self.bits[byte] & mask != 0);
+        BinaryColor::from(on)
+    }
+
+    /// The packed pixels: row-major, MSB leftmost, rows padded to a byte.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Writes the frame in SSD1306 (and SH1106/SSD1309) GDDRAM order: one
+    /// 8-row "page" at a time, top page first, and within a page one byte
+    /// per column, left to right, least significant bit the page's top
+    /// row. That's what the controller expects after `SET_MEMORY_MODE
+    /// horizontal`, so a monochrome `DisplaySurface` can send `out` as a
+    /// single data write, the same way `St7789Surface` sends
+    /// [`FrameBuffer565::write_be_bytes`]'s output. Rows past the height
+    /// in the last page (a height that isn't a multiple of 8) are off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len() != width() * ceil(height() / 8)`.
+    pub fn write_pages(&self, out: &mut [u8]) {
+        let width = self.width as usize;
+        let pages = self.height.div_ceil(8) as usize;
+        assert_eq!(out.len(), width * pages, "write_pages: `out` must be exactly width*ceil(height/8) bytes");
+
+        for (page, columns) in out.chunks_exact_mut(width.max(1)).enumerate() {
+            for (x, byte) in columns.iter_mut().enumerate() {
+                *byte = (0..8).fold(0, |acc, bit| {
+                    let y = (page * 8 + bit) as i32;
+                    acc | (u8::from(self.pixel(Point::new(x as i32, y)).is_on()) << bit)
+                });
+            }
+        }
+    }
+
+    fn index(&self, p: Point) -> Option<(usize, u8)> {
+        let (x, y) = (u32::try_from(p.x).ok()?, u32::try_from(p.y).ok()?);
+        (x < self.width && y < self.height).then(|| {
+            let stride = self.width.div_ceil(8);
+            ((y * stride + x / 8) as usize, 0x80 >> (x % 8))
+        })
+    }
+
+    fn set(&mut self, x: u32, y: u32, on: bool) {
+        if let Some((byte, mask)) = self.index(Point::new(x as i32, y as i32)) {
+            if on {
+                self.bits[byte] |= mask;
+            } else {
+                self.bits[byte] &= !mask;
+            }
+        }
+    }
+}
+
+impl OriginDimensions for MonoFrameBuffer {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+impl DrawTarget for MonoFrameBuffer {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y)) {
+                self.set(x, y, color.is_on());
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use embedded_graphics::{
+        geometry::Dimensions,
         prelude::{Primitive, RgbColor},
-        primitives::{PrimitiveStyle, Rectangle},
+        primitives::{PointsIter, PrimitiveStyle},
         Drawable,
     };
 
@@ -248,4 +631,177 @@ mod tests {
         let mut too_small = [0u8; 4];
         fb.write_be_bytes(&mut too_small);
     }
+
+    #[test]
+    fn write_be_bytes_in_matches_the_full_frame_bytes_for_that_area() {
+        let mut fb = FrameBuffer565::new(5, 4);
+        for (index, point) in fb.bounding_box().points().enumerate() {
+            fb.inner.set_color_at(point, Rgb565::new(index as u8, index as u8, 0));
+        }
+        let mut full = [0u8; 5 * 4 * 2];
+        fb.write_be_bytes(&mut full);
+
+        let area = Rectangle::new(Point::new(1, 2), Size::new(3, 2));
+        let mut part = [0u8; 3 * 2 * 2];
+        fb.write_be_bytes_in(&area, &mut part);
+
+        let expected: Vec<u8> = [(1, 2), (2, 2), (3, 2), (1, 3), (2, 3), (3, 3)]
+            .into_iter()
+            .flat_map(|(x, y)| full[(y * 5 + x) * 2..(y * 5 + x) * 2 + 2].to_vec())
+            .collect();
+        assert_eq!(part.to_vec(), expected);
+    }
+
+    #[test]
+    fn the_first_take_damages_everything_and_an_untouched_frame_nothing() {
+        let mut fb = FrameBuffer565::new(40, 20);
+        assert_eq!(fb.take_damage(), vec![fb.bounding_box()]);
+        assert_eq!(fb.take_damage(), vec![]);
+    }
+
+    #[test]
+    fn repainting_identical_pixels_is_not_damage() {
+        let mut fb = FrameBuffer565::new(40, 20);
+        fb.clear(Rgb565::BLUE).unwrap();
+        fb.take_damage();
+
+        fb.clear(Rgb565::BLUE).unwrap();
+        Pixel(Point::new(3, 3), Rgb565::BLUE).draw(&mut fb).unwrap();
+        assert_eq!(fb.take_damage(), vec![]);
+    }
+
+    #[test]
+    fn a_changed_pixel_damages_only_its_tile_clipped_to_the_edge() {
+        let mut fb = FrameBuffer565::new(40, 20);
+        fb.take_damage();
+
+        Pixel(Point::new(20, 5), Rgb565::RED).draw(&mut fb).unwrap();
+        Pixel(Point::new(39, 19), Rgb565::RED).draw(&mut fb).unwrap();
+        Pixel(Point::new(-1, 50), Rgb565::RED).draw(&mut fb).unwrap(); // off-buffer: ignored
+        assert_eq!(
+            fb.take_damage(),
+            vec![
+                Rectangle::new(Point::new(16, 0), Size::new(16, 16)),
+                Rectangle::new(Point::new(32, 16), Size::new(8, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn damaged_tiles_merge_across_a_row_and_down_matching_rows() {
+        let mut fb = FrameBuffer565::new(64, 48);
+        fb.take_damage();
+
+        // Tile columns 0-1 on all three tile rows, plus column 3 on the
+        // middle row only.
+        Rectangle::new(Point::new(10, 0), Size::new(12, 48))
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+            .draw(&mut fb)
+            .unwrap();
+        Pixel(Point::new(50, 20), Rgb565::RED).draw(&mut fb).unwrap();
+
+        assert_eq!(
+            fb.take_damage(),
+            vec![
+                Rectangle::new(Point::new(0, 0), Size::new(32, 48)),
+                Rectangle::new(Point::new(48, 16), Size::new(16, 16)),
+            ]
+        );
+    }
+
+    #[test]
+    fn applying_every_damage_rect_reproduces_the_frame() {
+        // What a partial-flush surface does: copy only the damaged areas
+        // into its own copy of the last frame.
+        let mut fb = FrameBuffer565::new(50, 30);
+        let mut mirror = vec![Rgb565::BLACK; 50 * 30];
+        let frames: [&dyn Fn(&mut FrameBuffer565); 3] = [
+            &|fb| fb.clear(Rgb565::BLUE).unwrap(),
+            &|fb| {
+                Rectangle::new(Point::new(7, 9), Size::new(20, 3))
+                    .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+                    .draw(fb)
+                    .unwrap();
+            },
+            &|fb| fb.dim(),
+        ];
+        for draw in frames {
+            draw(&mut fb);
+            for rect in fb.take_damage() {
+                for point in rect.points() {
+                    mirror[point.y as usize * 50 + point.x as usize] = fb.pixel(point);
+                }
+            }
+            let colors: Vec<Rgb565> = fb.pixels().map(|Pixel(_, c)| c).collect();
+            assert_eq!(mirror, colors);
+        }
+    }
+
+    #[test]
+    fn dim_halves_every_channel_of_every_pixel() {
+        let mut fb = FrameBuffer565::new(2, 1);
+        fb.inner.set_color_at(Point::new(0, 0), Rgb565::new(31, 63, 31));
+        fb.inner.set_color_at(Point::new(1, 0), Rgb565::new(10, 20, 1));
+        fb.dim();
+        assert_eq!(fb.pixel(Point::new(0, 0)), Rgb565::new(15, 31, 15));
+        assert_eq!(fb.pixel(Point::new(1, 0)), Rgb565::new(5, 10, 0));
+    }
+
+    #[test]
+    fn mono_threshold_keeps_light_pixels_and_drops_dark_ones() {
+        let mut fb = FrameBuffer565::new(3, 1);
+        fb.inner.set_color_at(Point::new(0, 0), Rgb565::WHITE);
+        fb.inner.set_color_at(Point::new(1, 0), Rgb565::new(4, 10, 9));
+        fb.inner.set_color_at(Point::new(2, 0), Rgb565::new(20, 40, 20));
+        let mono = MonoFrameBuffer::from_rgb565(&fb, Dither::Threshold);
+        assert_eq!(mono.pixel(Point::new(0, 0)), BinaryColor::On);
+        assert_eq!(mono.pixel(Point::new(1, 0)), BinaryColor::Off);
+        assert_eq!(mono.pixel(Point::new(2, 0)), BinaryColor::On);
+    }
+
+    #[test]
+    fn mono_ordered_dither_density_tracks_luminance() {
+        let lit = |color: Rgb565| {
+            let mut fb = FrameBuffer565::new(8, 8);
+            fb.clear(color).unwrap();
+            let mono = MonoFrameBuffer::from_rgb565(&fb, Dither::Ordered);
+            mono.as_bytes().iter().map(|b| b.count_ones()).sum::<u32>()
+        };
+        assert_eq!(lit(Rgb565::BLACK), 0);
+        assert_eq!(lit(Rgb565::WHITE), 64);
+        let (dark, mid) = (lit(Rgb565::new(4, 10, 9)), lit(Rgb565::new(16, 32, 16)));
+        assert!(0 < dark && dark < mid && mid < 64, "dark {dark}, mid {mid}");
+        assert_eq!(mid, 32, "mid-grey is half on");
+    }
+
+    #[test]
+    fn mono_packs_rows_msb_first_padded_to_a_byte() {
+        let mut mono = MonoFrameBuffer::new(10, 2);
+        assert_eq!(mono.as_bytes().len(), 4);
+        mono.draw_iter([
+            Pixel(Point::new(0, 0), BinaryColor::On),
+            Pixel(Point::new(9, 1), BinaryColor::On),
+            Pixel(Point::new(10, 0), BinaryColor::On),
+            Pixel(Point::new(-1, 0), BinaryColor::On),
+        ])
+        .unwrap();
+        assert_eq!(mono.as_bytes(), &[0x80, 0x00, 0x00, 0x40]);
+        assert_eq!(mono.pixel(Point::new(9, 1)), BinaryColor::On);
+        assert_eq!(mono.pixel(Point::new(10, 1)), BinaryColor::Off, "outside reads as off");
+    }
+
+    #[test]
+    fn write_pages_packs_columns_lsb_top_one_page_at_a_time() {
+        let mut mono = MonoFrameBuffer::new(3, 10);
+        mono.draw_iter([
+            Pixel(Point::new(0, 0), BinaryColor::On),
+            Pixel(Point::new(0, 7), BinaryColor::On),
+            Pixel(Point::new(2, 3), BinaryColor::On),
+            Pixel(Point::new(1, 9), BinaryColor::On),
+        ])
+        .unwrap();
+        let mut out = [0xff; 6];
+        mono.write_pages(&mut out);
+        assert_eq!(out, [0x81, 0x00, 0x08, 0x00, 0x02, 0x00]);
+    }
 }
@@ -0,0 +1,209 @@
+//! Layout profiles: the handful of per-panel decisions the render core
+//! can't make from a screen size alone — how tall the chrome bars are,
+//! which part of the panel is actually visible, and whether the panel
+//! forces a font set — chosen from the panel's size by
+//! [`LayoutProfile::for_panel`].
+//!
+//! The chrome/content/hint stack itself is still `chrome.rs`'s fixed-region
+//! layout (the ADR rejects a general layout engine); a profile only
+//! supplies the numbers it stacks with. Everything below the chrome —
+//! list row heights, detail field heights, dialog line pitch — already
+//! derives from the theme's [`FontSet`], so a profile reaches it by
+//! overriding the font set ([`LayoutProfile::theme`]) rather than through
+//! a second parameter on every widget.
+//!
+//! Four profiles cover the panels this project targets or has on the
+//! bench:
+//!
+//! - [`LayoutProfile::LANDSCAPE`]: the T-Embed's 320x170 ST7789, and the
+//!   default for any wider-than-tall panel. Exactly the pre-profile
+//!   layout.
+//! - [`LayoutProfile::PORTRAIT`]: taller than wide (a 170x320 or 240x320
+//!   module). Narrow, so the hint bar gets room for the hint to wrap onto
+//!   a second line.
+//! - [`LayoutProfile::STRIP`]: 64 px tall or less — the original 128x32
+//!   SSD1306 HUZZAH32 prototype. A slim title bar, no hint bar, and
+//!   [`FontSet::Compact`]'s single-line rows.
+//! - [`LayoutProfile::round`]: square panels, which in practice are round
+//!   (GC9A01-style 240x240). Everything is drawn inside the square
+//!   inscribed in the circle, so no corner of a bar or row is cut off, and
+//!   the hint bar is portrait's two-line one.
+
+use embedded_graphics::prelude::{Point, Size};
+use embedded_graphics::primitives::Rectangle;
+
+use super::chrome::{HINT_BAR_HEIGHT, TITLE_BAR_HEIGHT};
+use super::theme::{FontSet, Theme};
+
+/// Panels this tall or shorter get [`LayoutProfile::STRIP`].
+pub const STRIP_MAX_HEIGHT: u32 = 64;
+/// A hint bar with room for two lines of [`FontSet::hint`], for profiles
+/// too narrow for the app's hints on one.
+const TWO_LINE_HINT_BAR_HEIGHT: u32 = 28;
+
+/// Which family of panel a [`LayoutProfile`] is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelClass {
+    Landscape,
+    Portrait,
+    Strip,
+    Round,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutProfile {
+    pub class: PanelClass,
+    /// Height (px) of the title bar.
+    pub title_bar_height: u32,
+    /// Height (px) of the hint bar; `0` for none.
+    pub hint_bar_height: u32,
+    /// Margin (px) on every side of the panel that nothing is drawn in
+    /// but the background — the corners a round panel doesn't have.
+    pub inset: u32,
+    /// A font set the panel forces regardless of the theme's, for panels
+    /// where the theme's fonts can't fit at all.
+    pub fonts: Option<FontSet>,
+}
+
+impl LayoutProfile {
+    pub const LANDSCAPE: Self = Self {
+        class: PanelClass::Landscape,
+        title_bar_height: TITLE_BAR_HEIGHT,
+        hint_bar_height: HINT_BAR_HEIGHT,
+        inset: 0,
+        fonts: None,
+    };
+
+    /// The landscape bars, with a hint bar two lines of hint text tall:
+    /// a portrait panel's width wraps every hint the app shows.
+    pub const PORTRAIT: Self = Self {
+        class: PanelClass::Portrait,
+        title_bar_height: TITLE_BAR_HEIGHT,
+        hint_bar_height: TWO_LINE_HINT_BAR_HEIGHT,
+        inset: 0,
+        fonts: None,
+    };
+
+    /// An 8 px title bar (one line of [`FontSet::Compact`]'s title font)
+    /// and no hint bar: on 32 px, two single-line rows are worth more
+    /// than a legend for three buttons. Toasts take over the bottom of the
+    /// content area instead (see [`super::chrome::ChromeLayout::toast`]).
+    pub const STRIP: Self = Self {
+        class: PanelClass::Strip,
+        title_bar_height: 8,
+        hint_bar_height: 0,
+        inset: 0,
+        fonts: Some(FontSet::Compact),
+    };
+
+    /// The portrait bars, inset to the square inscribed in a round panel
+    /// of `diameter` px: `(1 - 1/√2) / 2` of the diameter on each side,
+    /// rounded up. That square is narrow (168px of a 240px panel), so the
+    /// hint wraps here too.
+    #[must_use]
+    pub const fn round(diameter: u32) -> Self {
+        Self {
+            class: PanelClass::Round,
+            title_bar_height: TITLE_BAR_HEIGHT,
+            hint_bar_height: TWO_LINE_HINT_BAR_HEIGHT,
+            inset: (diameter * 1465).div_ceil(10_000),
+            fonts: None,
+        }
+    }
+
+    /// The profile for a panel of `size`: strip if it's short enough,
+    /// round if it's square, then portrait or landscape by aspect.
+    #[must_use]
+    pub const fn for_panel(size: Size) -> Self {
+        if size.height <= STRIP_MAX_HEIGHT {
+            Self::STRIP
+        } else if size.width == size.height {
+            Self::round(size.width)
+        } else if size.height > size.width {
+            Self::PORTRAIT
+        } else {
+            Self::LANDSCAPE
+        }
+    }
+
+    /// The part of a `screen`-sized panel the chrome lays out in: the
+    /// whole panel, less [`Self::inset`] on every side.
+    #[must_use]
+    pub fn safe_area(&self, screen: Size) -> Rectangle {
+        let inset = self.inset.min(screen.width / 2).min(screen.height / 2);
+        Rectangle::new(
+            Point::new(inset as i32, inset as i32),
+            Size::new(screen.width - 2 * inset, screen.height - 2 * inset),
+        )
+    }
+
+    /// `theme` with this profile's forced font set, if it has one.
+    #[must_use]
+    pub fn theme(&self, theme: &Theme) -> Theme {
+        Theme {
+            fonts: self.fonts.unwrap_or(theme.fonts),
+            ..*theme
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panels_get_the_profile_their_shape_calls_for() {
+        assert_eq!(
+            LayoutProfile::for_panel(Size::new(320, 170)),
+            LayoutProfile::LANDSCAPE
+        );
+        assert_eq!(
+            LayoutProfile::for_panel(Size::new(170, 320)),
+            LayoutProfile::PORTRAIT
+        );
+        assert_eq!(
+            LayoutProfile::for_panel(Size::new(128, 32)),
+            LayoutProfile::STRIP
+        );
+        assert_eq!(
+            LayoutProfile::for_panel(Size::new(128, 64)),
+            LayoutProfile::STRIP
+        );
+        assert_eq!(
+            LayoutProfile::for_panel(Size::new(240, 240)).class,
+            PanelClass::Round
+        );
+    }
+
+    #[test]
+    fn a_round_panels_safe_area_is_the_inscribed_square() {
+        let area = LayoutProfile::round(240).safe_area(Size::new(240, 240));
+        // Every corner of the safe area lies inside the circle.
+        let radius = 120.0_f64;
+        let corner = area.top_left;
+        let dx = f64::from(corner.x) - radius;
+        let dy = f64::from(corner.y) - radius;
+        assert!((dx * dx + dy * dy).sqrt() <= radius);
+        assert_eq!(area.size, Size::new(168, 168));
+    }
+
+    #[test]
+    fn a_rectangular_panels_safe_area_is_the_whole_panel() {
+        let size = Size::new(320, 170);
+        assert_eq!(
+            LayoutProfile::LANDSCAPE.safe_area(size),
+            Rectangle::new(Point::zero(), size)
+        );
+    }
+
+    #[test]
+    fn only_a_forced_font_set_overrides_the_themes() {
+        assert_eq!(
+            LayoutProfile::LANDSCAPE.theme(&Theme::LARGE_TEXT),
+            Theme::LARGE_TEXT
+        );
+        let strip = LayoutProfile::STRIP.theme(&Theme::LARGE_TEXT);
+        assert_eq!(strip.fonts, FontSet::Compact);
+        assert_eq!(strip.palette, Theme::LARGE_TEXT.palette);
+    }
+}
@@ -20,10 +20,13 @@ use embedded_graphics::{
 };
 use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
 
+use crate::input::type_ahead::TypeAhead;
 use crate::input::NavIntent;
 
 use super::framebuffer::FrameBuffer565;
-use super::theme::{self, font, icon, palette};
+use super::screen::text_width;
+use super::semantics::{Role, SemanticNode};
+use super::theme::{self, font, icon, FontSet, Theme, SELECTION_ACCENT_WIDTH};
 use super::widget::{Action, FocusEvent, Widget};
 
 /// A single displayable row. Display-only: no identifiers, no domain
@@ -64,30 +67,11 @@ impl ListItem {
 /// comment for the resulting row budget.
 const ROW_PADDING: i32 = 3;
 const LINE_GAP: i32 = 2;
-
-/// Worst-case pixel footprint (leading + ink height, including
-/// descenders) of a single line rendered with [`font::name`]/
-/// [`font::username`] from a `VerticalPosition::Top`-anchored position —
-/// i.e. how much vertical room a `render_aligned(.., VerticalPosition::Top,
-/// ..)` call at row-relative y=0 actually occupies in the worst case.
-///
-/// These are measured constants, not something `u8g2-fonts` can compute at
-/// compile time (`FontRenderer::get_rendered_dimensions*` take `&self` and
-/// aren't `const fn`) — derived once via a throwaway probe
-/// (`core/examples/dim_probe.rs`, run manually, not part of the build)
-/// against `"gjpqy"` (all five ASCII descenders) rendered in each font,
-/// then hardcoded here the same way the retired `FONT_ASCENT`/
-/// `FONT_DESCENT` constants were hardcoded from `FONT_6X10`'s metrics.
-///
-/// Using the worst case (not the specific credential name/username being
-/// rendered) is deliberate: this guards every possible name/username the
-/// same way `ROW_HEIGHT`'s previous, font-metric-derived formula did,
-/// rather than only the non-descender demo strings the design-review
-/// spike's mockup happened to use (its "+2/+15" row offsets were tuned
-/// against `"GitHub"`/`"Amazon Web Services"`-style names with no
-/// descenders, which this bead's real credential data can't guarantee).
-const NAME_LINE_FOOTPRINT: i32 = 17;
-const USERNAME_LINE_FOOTPRINT: i32 = 15;
+/// [`ROW_PADDING`] for [`FontSet::Compact`]'s single-line rows, where a
+/// 32 px panel can't spare three pixels a side.
+const COMPACT_ROW_PADDING: i32 = 1;
+/// Gap (px) between a chipless row's selection accent bar and its text.
+const COMPACT_TEXT_GAP: i32 = 3;
 
 /// Pixel height of a single row (padding + name line + gap + username
 /// line + padding). Fixed, like the chrome bar heights in `chrome.rs` — a
@@ -104,8 +88,33 @@ const USERNAME_LINE_FOOTPRINT: i32 = 15;
 /// always fits entirely inside `[0, ROW_HEIGHT)`, the same guarantee the
 /// original `ROW_HEIGHT` doc comment describes and `render_png_dump.rs`'s
 /// `text_never_bleeds_past_a_rows_bottom_padding` test still enforces.
-pub const ROW_HEIGHT: u32 =
-    (ROW_PADDING + NAME_LINE_FOOTPRINT + LINE_GAP + USERNAME_LINE_FOOTPRINT + ROW_PADDING) as u32;
+///
+/// This is the standard font set's height; a theme with
+/// [`FontSet::Large`] gets taller rows from [`row_height`] (and
+/// [`FontSet::Compact`] single-line ones), which is what every list
+/// actually lays out with.
+pub const ROW_HEIGHT: u32 = row_height(FontSet::Standard);
+
+/// Pixel height of a single row whose lines are drawn in `fonts` — see
+/// [`ROW_HEIGHT`] for the budget and [`FontSet::name_line_footprint`] for
+/// where the per-line figures come from.
+#[must_use]
+pub const fn row_height(fonts: FontSet) -> u32 {
+    let padding = row_padding(fonts);
+    if fonts.two_line_rows() {
+        (padding + fonts.name_line_footprint() + LINE_GAP + fonts.username_line_footprint() + padding) as u32
+    } else {
+        (padding + fonts.name_line_footprint() + padding) as u32
+    }
+}
+
+const fn row_padding(fonts: FontSet) -> i32 {
+    if fonts.two_line_rows() {
+        ROW_PADDING
+    } else {
+        COMPACT_ROW_PADDING
+    }
+}
 
 /// Left margin (px) from a row's left edge to its chip's left edge.
 const CHIP_LEFT_MARGIN: i32 = 6;
@@ -113,25 +122,37 @@ const CHIP_LEFT_MARGIN: i32 = 6;
 const CHIP_TEXT_GAP: i32 = 8;
 /// Right margin (px) reserved for the focused row's disclosure caret.
 const CARET_RIGHT_MARGIN: i32 = 6;
+/// Gap (px) between the end of a row's text column and the caret's left
+/// edge.
+const CARET_TEXT_GAP: i32 = 4;
 
 /// Side length (px) of a row's chip: sized to the two-line name+username
-/// text block's own height (`NAME_LINE_FOOTPRINT + LINE_GAP +
-/// USERNAME_LINE_FOOTPRINT`, i.e. `ROW_HEIGHT` minus its top/bottom
-/// `ROW_PADDING`) so the chip's vertical extent lines up with the text
+/// text block's own height (the two line footprints plus `LINE_GAP`, i.e.
+/// [`row_height`] minus its top/bottom `ROW_PADDING`) so the chip's vertical extent lines up with the text
 /// beside it instead of bleeding into the row's padding — the same
 /// "vertical padding is sacred" invariant `text_never_bleeds_past_a_rows_
-/// bottom_padding` enforces for text.
-pub(crate) fn chip_size() -> u32 {
-    (NAME_LINE_FOOTPRINT + LINE_GAP + USERNAME_LINE_FOOTPRINT) as u32
+/// bottom_padding` enforces for text. Zero for single-line rows, which
+/// have no chip.
+pub(crate) const fn chip_size(fonts: FontSet) -> u32 {
+    if fonts.two_line_rows() {
+        (fonts.name_line_footprint() + LINE_GAP + fonts.username_line_footprint()) as u32
+    } else {
+        0
+    }
 }
 
 /// Row-relative X offset where a row's text block (name/username) starts —
-/// past the chip's left margin, its own width, and the chip-to-text gap.
+/// past the chip's left margin, its own width, and the chip-to-text gap,
+/// or for a chipless single-line row just past the selection accent bar.
 /// `pub(crate)`: see [`name_top_offset`]'s doc comment for why
 /// `credential_list_view.rs` needs to reuse layout constants like this one
 /// rather than recomputing them independently.
-pub(crate) fn text_left_offset() -> i32 {
-    CHIP_LEFT_MARGIN + chip_size() as i32 + CHIP_TEXT_GAP
+pub(crate) const fn text_left_offset(fonts: FontSet) -> i32 {
+    if fonts.two_line_rows() {
+        CHIP_LEFT_MARGIN + chip_size(fonts) as i32 + CHIP_TEXT_GAP
+    } else {
+        SELECTION_ACCENT_WIDTH as i32 + COMPACT_TEXT_GAP
+    }
 }
 
 /// Row-relative Y offset for the name line's
@@ -145,15 +166,59 @@ pub(crate) fn text_left_offset() -> i32 {
 /// `VerticalPosition::Top` does the ascent/descent arithmetic internally,
 /// which is the whole point of retiring the old `FONT_ASCENT`/
 /// `FONT_DESCENT`/baseline-offset math this replaces.
-pub(crate) const fn name_top_offset() -> i32 {
-    ROW_PADDING
+pub(crate) const fn name_top_offset(fonts: FontSet) -> i32 {
+    row_padding(fonts)
 }
 
 /// Row-relative Y offset for the username line's `render_aligned` call —
 /// directly below the name line's worst-case footprint, plus `LINE_GAP`.
 /// `pub(crate)`: see [`name_top_offset`]'s doc comment.
-pub(crate) const fn username_top_offset() -> i32 {
-    name_top_offset() + NAME_LINE_FOOTPRINT + LINE_GAP
+pub(crate) const fn username_top_offset(fonts: FontSet) -> i32 {
+    name_top_offset(fonts) + fonts.name_line_footprint() + LINE_GAP
+}
+
+/// Width (px) of a row's text column: from [`text_left_offset`] to just
+/// short of the disclosure caret. Reserved on every row, selected or not,
+/// so a label's clip edge doesn't jump as the selection moves over it.
+/// Name and username are both clipped to this column in [`draw_row`].
+pub(crate) fn text_column_width(row_width: u32, fonts: FontSet) -> u32 {
+    let mut buf = [0_u8; 4];
+    let caret: &str = icon::CARET_RIGHT.encode_utf8(&mut buf);
+    let caret_width = text_width(&font::icon_1x(), caret) as i32;
+    let reserved = text_left_offset(fonts) + CARET_TEXT_GAP + caret_width + CARET_RIGHT_MARGIN;
+    row_width.saturating_sub(reserved.max(0) as u32)
+}
+
+/// How far (px) `name` and `username` overflow a `row_width`-wide row's
+/// text column — `(0, 0)` when both fit (a single-line row's username is
+/// never drawn, so never overflows). What a row's owner feeds a
+/// [`super::marquee::Marquee`] to decide whether (and how far) the
+/// focused row's labels scroll.
+pub(crate) fn label_overflow(row_width: u32, name: &str, username: Option<&str>, fonts: FontSet) -> LabelOffsets {
+    let column = text_column_width(row_width, fonts);
+    LabelOffsets {
+        name: text_width(&fonts.name(), name).saturating_sub(column),
+        username: username
+            .filter(|_| fonts.two_line_rows())
+            .map_or(0, |username| text_width(&fonts.username(), username).saturating_sub(column)),
+    }
+}
+
+/// Horizontal pixel offsets for a row's two text lines — how far each is
+/// scrolled left inside its text column (see [`draw_row`]). Also used for
+/// how far each *overflows* the column ([`label_overflow`]), the upper
+/// bound on that scroll.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LabelOffsets {
+    pub name: u32,
+    pub username: u32,
+}
+
+impl LabelOffsets {
+    /// The wider of the two — how far the row's marquee has to travel.
+    pub(crate) fn max(self) -> u32 {
+        self.name.max(self.username)
+    }
 }
 
 /// Reconciles a list's scroll-top **row index** against a newly resolved
@@ -232,6 +297,8 @@ pub(crate) fn reconcile_top_index(prev_top: usize, selected: usize, visible_rows
 /// bottom divider, the chip, the bold name line, the muted username line,
 /// and — for the focused/selected row — the full-width selection fill
 /// (via [`theme::draw_selection`]) plus a right-edge disclosure caret.
+/// Single-line rows ([`FontSet::two_line_rows`]) drop the chip and the
+/// username line.
 ///
 /// Extracted as a free function (rather than duplicated inside both
 /// `VerticalList::render` and `credential_list_view.rs`'s hand-rolled row
@@ -249,6 +316,12 @@ pub(crate) fn reconcile_top_index(prev_top: usize, selected: usize, visible_rows
 /// directly below a *selected* row would fight the selection block's own
 /// bottom edge instead of reading as a plain row separator.
 ///
+/// Both text lines are clipped to the row's text column
+/// ([`text_column_width`]), so an over-long label stops short of the caret
+/// instead of running under it. `offsets` shifts each line left inside
+/// that column — the focused row's marquee (see `super::marquee`); every
+/// other caller passes `LabelOffsets::default()`.
+///
 /// Generic over `D: DrawTarget<Color = Rgb565, Error = Infallible>` for
 /// the same reason [`theme::draw_selection`] is — callers pass a
 /// `DrawTargetExt::clipped()` sub-region directly.
@@ -258,53 +331,63 @@ pub(crate) fn reconcile_top_index(prev_top: usize, selected: usize, visible_rows
 /// Returns `Infallible`'s uninhabited variant in practice — see
 /// [`super::widget::Widget::render`]'s doc comment for why the `Result`
 /// return exists at all.
+#[allow(clippy::too_many_arguments)] // one row's worth of independent inputs; a struct would only rename them
 pub(crate) fn draw_row<D>(
     target: &mut D,
     row_rect: Rectangle,
     name: &str,
     username: Option<&str>,
+    offsets: LabelOffsets,
     selected: bool,
     draw_divider: bool,
+    theme: &Theme,
 ) -> Result<(), Infallible>
 where
     D: DrawTarget<Color = Rgb565, Error = Infallible>,
 {
     if selected {
-        theme::draw_selection(row_rect, target)?;
+        theme::draw_selection(row_rect, theme, target)?;
     } else if draw_divider {
         let divider = Rectangle::new(
             Point::new(row_rect.top_left.x, row_rect.top_left.y + row_rect.size.height as i32 - 1),
             Size::new(row_rect.size.width, 1),
         );
-        divider.into_styled(PrimitiveStyle::with_fill(palette::DIVIDER)).draw(target)?;
+        divider.into_styled(PrimitiveStyle::with_fill(theme.palette.divider)).draw(target)?;
     }
 
-    let initial = name.chars().next().map_or('#', |c| c.to_ascii_uppercase());
-    let chip_rect = Rectangle::new(
-        Point::new(row_rect.top_left.x + CHIP_LEFT_MARGIN, row_rect.top_left.y + ROW_PADDING),
-        Size::new_equal(chip_size()),
-    );
-    theme::draw_chip(target, chip_rect, initial)?;
+    if theme.fonts.two_line_rows() {
+        let initial = name.chars().next().map_or('#', |c| c.to_ascii_uppercase());
+        let chip_rect = Rectangle::new(
+            Point::new(row_rect.top_left.x + CHIP_LEFT_MARGIN, row_rect.top_left.y + ROW_PADDING),
+            Size::new_equal(chip_size(theme.fonts)),
+        );
+        theme::draw_chip(target, chip_rect, initial, theme)?;
+    }
 
-    let text_x = row_rect.top_left.x + text_left_offset();
+    let text_x = row_rect.top_left.x + text_left_offset(theme.fonts);
+    let column = Rectangle::new(
+        Point::new(text_x, row_rect.top_left.y),
+        Size::new(text_column_width(row_rect.size.width, theme.fonts), row_rect.size.height),
+    );
+    let mut text_target = target.clipped(&column);
 
-    let _ = font::name().render_aligned(
+    let _ = theme.fonts.name().render_aligned(
         name,
-        Point::new(text_x, row_rect.top_left.y + name_top_offset()),
+        Point::new(text_x - offsets.name as i32, row_rect.top_left.y + name_top_offset(theme.fonts)),
         VerticalPosition::Top,
         HorizontalAlignment::Left,
-        FontColor::Transparent(palette::TEXT_PRIMARY),
-        target,
+        FontColor::Transparent(theme.palette.text_primary),
+        &mut text_target,
     );
 
-    if let Some(username) = username {
-        let _ = font::username().render_aligned(
+    if let Some(username) = username.filter(|_| theme.fonts.two_line_rows()) {
+        let _ = theme.fonts.username().render_aligned(
             username,
-            Point::new(text_x, row_rect.top_left.y + username_top_offset()),
+            Point::new(text_x - offsets.username as i32, row_rect.top_left.y + username_top_offset(theme.fonts)),
             VerticalPosition::Top,
             HorizontalAlignment::Left,
-            FontColor::Transparent(palette::TEXT_SECONDARY),
-            target,
+            FontColor::Transparent(theme.palette.text_secondary),
+            &mut text_target,
         );
     }
 
@@ -318,7 +401,7 @@ where
             Point::new(caret_x, caret_y),
             VerticalPosition::Center,
             HorizontalAlignment::Right,
-            FontColor::Transparent(palette::TEXT_PRIMARY),
+            FontColor::Transparent(theme.palette.text_primary),
             target,
         );
     }
@@ -331,8 +414,10 @@ where
 type OnActivate = Box<dyn Fn(&ListItem) -> Action>;
 
 /// A focusable, scrollable vertical list of [`ListItem`]s. Moves its
-/// internal selection in response to `NavIntent::{Next,Prev,NextN}` via
-/// `Widget::on_intent`, auto-scrolling to keep the selection visible (only
+/// internal selection in response to `NavIntent::{Next,Prev,NextN,PrevN,
+/// Home,End}` via `Widget::on_intent`, and jumps to the first row whose
+/// label matches the typed prefix on `NavIntent::Char` (see
+/// [`TypeAhead`]), auto-scrolling to keep the selection visible (only
 /// at the viewport edges — see [`reconcile_top_index`]); fires its
 /// `on_activate` callback (if any) when activated while focused.
 pub struct VerticalList {
@@ -347,6 +432,7 @@ pub struct VerticalList {
     top_index: Cell<usize>,
     focused: bool,
     on_activate: Option<OnActivate>,
+    type_ahead: TypeAhead,
 }
 
 impl VerticalList {
@@ -358,6 +444,7 @@ impl VerticalList {
             top_index: Cell::new(0),
             focused: false,
             on_activate: None,
+            type_ahead: TypeAhead::new(),
         }
     }
 
@@ -409,10 +496,17 @@ impl VerticalList {
         let next = (self.selected as i32 + delta).clamp(0, len - 1);
         self.selected = next as usize;
     }
+
+    fn type_ahead(&mut self, c: char) {
+        let labels = self.items.iter().map(|item| item.label.as_str());
+        if let Some(index) = self.type_ahead.push(c, labels, self.selected) {
+            self.selected = index;
+        }
+    }
 }
 
 impl Widget for VerticalList {
-    fn measure(&self, constraints: Size) -> Size {
+    fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
         // A list fills whatever vertical space its screen gives it; it
         // manages overflow itself via scrolling, not by requesting more
         // height than is on offer.
@@ -446,16 +540,23 @@ impl Widget for VerticalList {
     }
 
     fn on_intent(&mut self, intent: NavIntent) -> Action {
+        if !matches!(intent, NavIntent::Char(_)) {
+            self.type_ahead.reset();
+        }
         match intent {
             NavIntent::Next => self.move_selection(1),
             NavIntent::Prev => self.move_selection(-1),
             NavIntent::NextN(n) => self.move_selection(i32::from(n)),
+            NavIntent::PrevN(n) => self.move_selection(-i32::from(n)),
+            NavIntent::Home => self.move_selection(-(self.items.len() as i32)),
+            NavIntent::End => self.move_selection(self.items.len() as i32),
+            NavIntent::Char(c) => self.type_ahead(c),
             NavIntent::Activate | NavIntent::Back => {}
         }
         Action::None
     }
 
-    fn render(&self, area: Rectangle, target: &mut FrameBuffer565) -> Result<(), Infallible> {
+    fn render(&self, area: Rectangle, theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible> {
         // Real clipping (DrawTargetExt::clipped), not the retired
         // character-skip marquee: anything a row draws outside `area` —
         // an over-long label, a row scrolled partway off the top/bottom —
@@ -467,19 +568,20 @@ impl Widget for VerticalList {
         // `reconcile_top_index`'s doc comment for the full rule and why
         // this reconciliation happens here, in `render`, rather than in
         // `on_intent`.
-        let visible_rows = (area.size.height / ROW_HEIGHT).max(1) as usize;
+        let row_height = row_height(theme.fonts);
+        let visible_rows = (area.size.height / row_height).max(1) as usize;
         let top = reconcile_top_index(self.top_index.get(), self.selected, visible_rows, self.items.len());
         self.top_index.set(top);
-        let scroll = top as u32 * ROW_HEIGHT;
+        let scroll = top as u32 * row_height;
 
         for (index, item) in self.items.iter().enumerate() {
             let row_top =
-                area.top_left.y + (index as u32 * ROW_HEIGHT) as i32 - scroll as i32;
+                area.top_left.y + (index as u32 * row_height) as i32 - scroll as i32;
 
             // Skip rows fully outside the viewport — a cheap early-out;
             // `clipped` would drop their pixels anyway, but there's no
             // point building draw commands for off-screen rows.
-            if row_top + ROW_HEIGHT as i32 <= area.top_left.y
+            if row_top + row_height as i32 <= area.top_left.y
                 || row_top >= area.top_left.y + area.size.height as i32
             {
                 continue;
@@ -487,7 +589,7 @@ impl Widget for VerticalList {
 
             let row_rect = Rectangle::new(
                 Point::new(area.top_left.x, row_top),
-                Size::new(area.size.width, ROW_HEIGHT),
+                Size::new(area.size.width, row_height),
             );
 
             let selected = self.focused && index == self.selected;
@@ -496,13 +598,29 @@ impl Widget for VerticalList {
                 row_rect,
                 item.label.as_str(),
                 item.sublabel.as_deref(),
+                LabelOffsets::default(),
                 selected,
                 !selected,
+                theme,
             )?;
         }
 
         Ok(())
     }
+
+    fn semantics(&self) -> Option<SemanticNode> {
+        let rows = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                SemanticNode::new(Role::ListItem, item.label.as_str())
+                    .with_optional_value(item.sublabel.as_deref())
+                    .with_selected(index == self.selected)
+            })
+            .collect();
+        Some(SemanticNode::new(Role::List, "").with_children(rows))
+    }
 }
 
 #[cfg(test)]
@@ -553,6 +671,48 @@ mod tests {
         assert_eq!(list.selected_index(), 9);
     }
 
+    #[test]
+    fn prev_n_jumps_back_and_clamps() {
+        let mut list = VerticalList::new(items(10));
+        list.on_intent(NavIntent::NextN(9));
+        list.on_intent(NavIntent::PrevN(4));
+        assert_eq!(list.selected_index(), 5);
+        list.on_intent(NavIntent::PrevN(20));
+        assert_eq!(list.selected_index(), 0);
+    }
+
+    #[test]
+    fn home_and_end_jump_to_the_first_and_last_rows() {
+        let mut list = VerticalList::new(items(10));
+        list.on_intent(NavIntent::Next);
+        list.on_intent(NavIntent::End);
+        assert_eq!(list.selected_index(), 9);
+        list.on_intent(NavIntent::Home);
+        assert_eq!(list.selected_index(), 0);
+    }
+
+    #[test]
+    fn typed_characters_jump_to_the_first_matching_label() {
+        let names = ["Amazon", "Bank", "GitHub", "Gitlab", "Google"];
+        let mut list = VerticalList::new(names.into_iter().map(ListItem::new).collect());
+
+        list.on_intent(NavIntent::Char('g'));
+        assert_eq!(list.selected_index(), 2);
+        list.on_intent(NavIntent::Char('o'));
+        assert_eq!(list.selected_index(), 4);
+        list.on_intent(NavIntent::Char('x')); // no match: stays put
+        assert_eq!(list.selected_index(), 4);
+
+        // A repeated letter cycles; any other intent starts a fresh prefix,
+        // so the same letter after a rotation goes back to the first match.
+        list.on_intent(NavIntent::Char('g'));
+        list.on_intent(NavIntent::Char('g'));
+        assert_eq!(list.selected_index(), 3);
+        list.on_intent(NavIntent::Next);
+        list.on_intent(NavIntent::Char('g'));
+        assert_eq!(list.selected_index(), 2);
+    }
+
     #[test]
     fn intent_on_empty_list_does_not_panic() {
         let mut list = VerticalList::new(vec![]);
@@ -602,7 +762,7 @@ mod tests {
         let mut fb = FrameBuffer565::new(320, viewport_height);
 
         // Selection within the first screenful: no scroll needed.
-        list.render(area, &mut fb).unwrap();
+        list.render(area, &Theme::DARK, &mut fb).unwrap();
         assert_eq!(list.top_index.get(), 0);
 
         // Selecting row 4 (0-indexed) means rows 0-3 no longer all fit;
@@ -612,7 +772,7 @@ mod tests {
         for _ in 0..4 {
             list.on_intent(NavIntent::Next);
         }
-        list.render(area, &mut fb).unwrap();
+        list.render(area, &Theme::DARK, &mut fb).unwrap();
         assert_eq!(list.top_index.get(), 2);
 
         // The selected row's own top and bottom must both land inside
@@ -639,12 +799,12 @@ mod tests {
         for _ in 0..4 {
             list.on_intent(NavIntent::Next);
         }
-        list.render(area, &mut fb).unwrap(); // selected=4, top settles at 2
+        list.render(area, &Theme::DARK, &mut fb).unwrap(); // selected=4, top settles at 2
         let top_after_scrolling_down = list.top_index.get();
         assert_eq!(top_after_scrolling_down, 2);
 
         list.on_intent(NavIntent::Prev); // selected=3, still within [2, 5)
-        list.render(area, &mut fb).unwrap();
+        list.render(area, &Theme::DARK, &mut fb).unwrap();
         assert_eq!(
             list.top_index.get(),
             top_after_scrolling_down,
@@ -740,11 +900,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn an_over_long_label_is_clipped_short_of_the_caret() {
+        let name = "A credential name far too long to fit on a single row";
+        let row = Rectangle::new(Point::zero(), Size::new(320, ROW_HEIGHT));
+        assert!(label_overflow(row.size.width, name, None, FontSet::Standard).name > 0);
+
+        let mut fb = FrameBuffer565::new(320, ROW_HEIGHT);
+        draw_row(&mut fb, row, name, None, LabelOffsets::default(), false, false, &Theme::DARK).unwrap();
+
+        let column_end = text_left_offset(FontSet::Standard) + text_column_width(row.size.width, FontSet::Standard) as i32;
+        for x in column_end..320 {
+            for y in 0..ROW_HEIGHT as i32 {
+                assert_eq!(fb.pixel(Point::new(x, y)), Rgb565::default(), "text bled past the column at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn a_label_that_fits_has_no_overflow() {
+        assert_eq!(label_overflow(320, "GitHub", Some("alice"), FontSet::Standard), LabelOffsets::default());
+    }
+
     #[test]
     fn render_does_not_panic_for_a_small_viewport_with_more_rows_than_fit() {
         let list = VerticalList::new(items(50));
         let mut fb = FrameBuffer565::new(64, 40);
         let area = Rectangle::new(Point::new(0, 0), Size::new(64, 40));
-        list.render(area, &mut fb).unwrap();
+        list.render(area, &Theme::DARK, &mut fb).unwrap();
     }
 }
@@ -0,0 +1,189 @@
+//! `Marquee`: the timing state behind horizontally auto-scrolling a
+//! focused label that's too wide for its column (a long credential name,
+//! an email-address username) — the replacement for the retired engines'
+//! character-skip marquee, done in pixels against a real clip rectangle.
+//!
+//! The cycle, once a label gains focus: hold still for [`MARQUEE_DWELL`]
+//! (so a quick scroll past a row doesn't set every row it touches in
+//! motion), slide left at [`MARQUEE_SPEED_PX_PER_SEC`] until the label's
+//! end is in view, hold for [`MARQUEE_END_PAUSE`], snap back, repeat.
+//!
+//! Time comes in through [`Marquee::tick`] from the `Clock` timestamps
+//! `App::tick` hands down (the core never reads the time itself). `tick`
+//! reports whether the offset actually moved, which is what lets the
+//! owning widget's `Widget::tick` feed `App`'s dirty gate: during the
+//! dwell, the end pause, and for any label that fits, nothing changes and
+//! idle frames skip render+flush as before.
+//!
+//! How far the label overflows is only known at render time (it depends on
+//! the width the widget is given), so `render` reports it back through
+//! [`Marquee::set_overflow`] — a `Cell`, since `render` takes `&self`.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// How long a focused label sits still before it starts scrolling.
+pub const MARQUEE_DWELL: Duration = Duration::from_millis(1500);
+/// How long the fully scrolled label holds before snapping back.
+pub const MARQUEE_END_PAUSE: Duration = Duration::from_millis(1500);
+/// Scroll speed. Slow enough to read while it moves.
+pub const MARQUEE_SPEED_PX_PER_SEC: u32 = 40;
+
+/// Scroll state for whatever one thing is currently focused, identified
+/// by a key `K` (a credential id, a field index) so the cycle restarts
+/// whenever focus moves to something else.
+#[derive(Debug)]
+pub struct Marquee<K> {
+    key: Option<K>,
+    /// When the current key's cycle started; set on its first tick.
+    started: Option<Instant>,
+    /// How far (px) the cycle has scrolled. Each label scrolls by this,
+    /// capped at its own overflow (see [`Self::offset`]).
+    scrolled: u32,
+    /// The widest overflow (px) among the focused labels, as of the last
+    /// render. Zero means nothing needs to scroll.
+    overflow: Cell<u32>,
+}
+
+impl<K> Default for Marquee<K> {
+    fn default() -> Self {
+        Self {
+            key: None,
+            started: None,
+            scrolled: 0,
+            overflow: Cell::new(0),
+        }
+    }
+}
+
+impl<K: PartialEq> Marquee<K> {
+    /// Advances the cycle to `now` for whatever `key` is focused (`None`
+    /// for nothing, or for something that must never scroll — a revealed
+    /// secret). A new key restarts the cycle from the dwell. Returns
+    /// whether the scroll offset changed, i.e. whether a re-render is due.
+    pub fn tick(&mut self, key: Option<K>, now: Instant) -> bool {
+        let before = self.scrolled;
+        if key != self.key {
+            self.key = key;
+            self.started = None;
+            self.scrolled = 0;
+            // `overflow` is deliberately kept: it's whatever the last
+            // render measured, which for a focus move is already the new
+            // key's (input re-renders before the dwell is up), and a fresh
+            // key never dirties the frame on its own to re-measure it.
+        }
+        if self.key.is_some() {
+            let started = *self.started.get_or_insert(now);
+            self.scrolled =
+                scrolled_at(now.saturating_duration_since(started), self.overflow.get());
+        }
+        self.scrolled != before
+    }
+
+    /// Records how far the focused label(s) overflow their column, as
+    /// measured at render. Takes `&self` so `Widget::render` can call it.
+    pub fn set_overflow(&self, overflow: u32) {
+        self.overflow.set(overflow);
+    }
+
+    /// The horizontal offset (px) to draw a label with `overflow` px of
+    /// overflow at: the cycle's scroll position, capped so a label never
+    /// scrolls further than its own end.
+    #[must_use]
+    pub fn offset(&self, overflow: u32) -> u32 {
+        self.scrolled.min(overflow)
+    }
+}
+
+/// The scroll position `elapsed` into a looping cycle for `overflow` px
+/// of overflow (see the module doc for the cycle's phases).
+fn scrolled_at(elapsed: Duration, overflow: u32) -> u32 {
+    if overflow == 0 {
+        return 0;
+    }
+    let travel_ms = u64::from(overflow) * 1000 / u64::from(MARQUEE_SPEED_PX_PER_SEC);
+    let dwell_ms = MARQUEE_DWELL.as_millis() as u64;
+    let cycle_ms = dwell_ms + travel_ms + MARQUEE_END_PAUSE.as_millis() as u64;
+    let t = elapsed.as_millis() as u64 % cycle_ms;
+    if t < dwell_ms {
+        return 0;
+    }
+    let px = (t - dwell_ms) * u64::from(MARQUEE_SPEED_PX_PER_SEC) / 1000;
+    px.min(u64::from(overflow)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn scrolled_at_walks_through_dwell_travel_pause_and_loops() {
+        // 80 px at 40 px/s: 2 s of travel.
+        assert_eq!(scrolled_at(ms(0), 80), 0);
+        assert_eq!(scrolled_at(ms(1499), 80), 0, "still dwelling");
+        assert_eq!(scrolled_at(ms(2500), 80), 40, "halfway");
+        assert_eq!(scrolled_at(ms(3500), 80), 80, "at the end");
+        assert_eq!(scrolled_at(ms(4999), 80), 80, "pausing at the end");
+        assert_eq!(
+            scrolled_at(ms(5000), 80),
+            0,
+            "snapped back for the next cycle"
+        );
+    }
+
+    #[test]
+    fn a_label_that_fits_never_scrolls() {
+        assert_eq!(scrolled_at(ms(10_000), 0), 0);
+    }
+
+    #[test]
+    fn tick_reports_change_only_when_the_offset_moves() {
+        let start = Instant::now();
+        let mut marquee = Marquee::default();
+        marquee.set_overflow(80);
+        assert!(!marquee.tick(Some(1), start));
+        marquee.set_overflow(80);
+        assert!(!marquee.tick(Some(1), start + ms(1000)), "dwelling is idle");
+        assert!(marquee.tick(Some(1), start + ms(2000)));
+        assert_eq!(marquee.offset(80), 20);
+        assert_eq!(
+            marquee.offset(10),
+            10,
+            "a shorter label stops at its own end"
+        );
+    }
+
+    #[test]
+    fn a_new_key_restarts_the_cycle() {
+        let start = Instant::now();
+        let mut marquee = Marquee::default();
+        marquee.tick(Some(1), start);
+        marquee.set_overflow(80);
+        marquee.tick(Some(1), start + ms(2500));
+        assert_eq!(marquee.offset(80), 40);
+
+        assert!(
+            marquee.tick(Some(2), start + ms(2600)),
+            "snapping back is a change"
+        );
+        assert_eq!(marquee.offset(80), 0);
+        marquee.set_overflow(80);
+        assert!(
+            !marquee.tick(Some(2), start + ms(3000)),
+            "the new key dwells first"
+        );
+    }
+
+    #[test]
+    fn no_key_never_scrolls() {
+        let start = Instant::now();
+        let mut marquee: Marquee<u8> = Marquee::default();
+        marquee.set_overflow(80);
+        assert!(!marquee.tick(None, start + ms(2500)));
+        assert_eq!(marquee.offset(80), 0);
+    }
+}
@@ -6,24 +6,47 @@
 //!
 //! Module map:
 //! - [`framebuffer`]: the canonical Rgb565 in-RAM framebuffer
-//!   ([`FrameBuffer565`]), the app core's single render output.
+//!   ([`FrameBuffer565`]), the app core's single render output, and the
+//!   1-bit [`MonoFrameBuffer`] a monochrome panel's surface dithers it
+//!   into. It also tracks damage: the tiles whose pixels changed since
+//!   the last flush, which is all a partial-flush surface sends.
 //! - [`widget`]: the retained-mode [`Widget`] trait, [`Action`], and
 //!   [`FocusEvent`].
 //! - [`chrome`]: fixed title/content/hint region layout
 //!   ([`compute_chrome`]).
+//! - [`layout`]: [`LayoutProfile`], the per-panel bar heights, safe area
+//!   and forced font set the chrome is laid out with, chosen from the
+//!   panel size.
 //! - [`list`]: [`VerticalList`], the one content widget this bead needs.
+//! - [`marquee`]: [`Marquee`], the `Clock`-driven dwell/scroll/pause
+//!   cycle that pans the focused row's over-long labels, ticked through
+//!   `Widget::tick`.
+//! - [`dialog`]: [`Dialog`], the modal confirmation widget opened via
+//!   `Action::ShowModal` (title, wrapped body, up to three choices).
+//! - [`form`]: [`Form`] and its rows ([`Toggle`], [`EnumPicker`],
+//!   [`Stepper`], [`ActionRow`]) — the settings screen's building blocks,
+//!   reading and writing values through closures.
+//! - [`toast`]: [`Toast`]/[`ToastQueue`], transient messages drawn over the
+//!   hint bar and expired from `Clock` timestamps.
 //! - [`screen`]: [`Screen`], one entry in the navigation stack.
 //! - [`navigator`]: [`Navigator`], owning the screen stack.
+//! - [`text_view`]: [`TextView`], a word-wrapped, line-scrolled, paged
+//!   read-only text screen (long notes), plus the [`wrap_text`] helper
+//!   `Dialog` shares.
+//! - [`semantics`]: [`SemanticNode`], the role/label/value/focus tree
+//!   widgets describe themselves as and the [`Navigator`] assembles — what
+//!   is on screen, as data, for tests, agents and the emulator's
+//!   `GET /api/ui-tree`.
 //! - [`secret_field`]: [`SecretField`] (bead `ai-bitwarden-hw-key-0v8.6`) —
 //!   the masked/revealed password-value rendering primitive used by
 //!   `crate::credential_detail_view::CredentialDetailView`.
 //!
-//! - [`theme`]: the M1 visual design language — the semantic color
-//!   palette, per-role `u8g2-fonts` accessors, `open_iconic` icon
+//! - [`theme`]: the M1 visual design language — [`Theme`] (a semantic
+//!   color [`Palette`] plus a per-role [`FontSet`]), `open_iconic` icon
 //!   codepoints, and the shared chip/selection drawing primitives (bead
-//!   `ai-bitwarden-hw-key-0v8.8`). `screen`/`list`/`credential_list_view`
-//!   render through this instead of `embedded-graphics`' built-in
-//!   `MonoFont`/`WebColors`.
+//!   `ai-bitwarden-hw-key-0v8.8`). The theme is a runtime value passed down
+//!   through every `Widget::render`, so the dark, light, high-contrast
+//!   and large-text variants are a settings choice, not a rebuild.
 //!
 //! What's deliberately NOT here yet (later beads, see the bead's explicit
 //! out-of-scope list):
@@ -44,18 +67,33 @@
 )]
 
 pub mod chrome;
+pub mod dialog;
+pub mod form;
 pub mod framebuffer;
+pub mod layout;
 pub mod list;
+pub mod marquee;
 pub mod navigator;
 pub mod screen;
 pub mod secret_field;
+pub mod semantics;
+pub mod text_view;
 pub mod theme;
+pub mod toast;
 pub mod widget;
 
 pub use chrome::{compute_chrome, ChromeLayout};
-pub use framebuffer::FrameBuffer565;
-pub use list::{ListItem, VerticalList, ROW_HEIGHT};
+pub use dialog::{Dialog, MAX_CHOICES};
+pub use form::{ActionRow, EnumPicker, Form, FormRow, Stepper, Toggle, FORM_ROW_HEIGHT};
+pub use framebuffer::{Dither, FrameBuffer565, MonoFrameBuffer, DAMAGE_TILE};
+pub use layout::{LayoutProfile, PanelClass};
+pub use list::{row_height, ListItem, VerticalList, ROW_HEIGHT};
+pub use marquee::{Marquee, MARQUEE_DWELL, MARQUEE_END_PAUSE, MARQUEE_SPEED_PX_PER_SEC};
 pub use navigator::Navigator;
 pub use screen::Screen;
 pub use secret_field::{SecretField, MASK_GLYPH_COUNT};
+pub use semantics::{Role, SemanticNode};
+pub use text_view::{wrap_text, TextView};
+pub use theme::{FontSet, Palette, Theme};
+pub use toast::{Toast, ToastQueue, DEFAULT_TOAST_DURATION};
 pub use widget::{Action, ChromeContribution, ChromeStatus, FocusEvent, Widget};
@@ -9,21 +9,38 @@
 //! `focused_index` lives on the struct itself), so pushing a new screen
 //! and later popping back restores exactly the focus state that was there
 //! before the push — no explicit save/restore step needed.
+//!
+//! Two layers sit above the stack. A modal [`Dialog`]
+//! (`Action::ShowModal`) takes over input entirely while it's open and is
+//! drawn over a dimmed copy of the current screen; it's kept apart from
+//! the stack so closing it never disturbs the screen beneath. Toasts
+//! (`Action::ShowToast`) are drawn last, over the hint bar, and expire via
+//! [`Navigator::tick`] — they never see input at all.
 
 use std::convert::Infallible;
+use std::time::Instant;
 
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::OriginDimensions;
 
 use super::chrome::compute_chrome;
+use super::dialog::Dialog;
 use super::framebuffer::FrameBuffer565;
+use super::layout::LayoutProfile;
 use super::screen::Screen;
-use super::theme::palette;
-use super::widget::Action;
+use super::semantics::{Role, SemanticNode};
+use super::theme::Theme;
+use super::toast::{Toast, ToastQueue};
+use super::widget::{Action, FocusEvent, Widget};
 use crate::input::NavIntent;
 
 pub struct Navigator {
     stack: Vec<Screen>,
+    /// Open dialogs, topmost last. Usually zero or one; more only when
+    /// something opens a dialog while another is already showing (a
+    /// chained confirmation closes the first before opening the second).
+    modals: Vec<Dialog>,
+    toasts: ToastQueue,
 }
 
 impl Navigator {
@@ -32,7 +49,11 @@ impl Navigator {
     #[must_use]
     pub fn new(mut root: Screen) -> Self {
         root.initialize_focus();
-        Self { stack: vec![root] }
+        Self {
+            stack: vec![root],
+            modals: Vec::new(),
+            toasts: ToastQueue::default(),
+        }
     }
 
     /// The currently visible screen.
@@ -75,21 +96,106 @@ impl Navigator {
         }
     }
 
+    /// Opens `dialog` over the current screen; from now until it closes,
+    /// [`Self::dispatch`] routes every intent to it alone.
+    pub fn show_modal(&mut self, dialog: Dialog) {
+        self.modals.push(dialog);
+    }
+
+    /// The dialog currently receiving input, if one is open.
+    #[must_use]
+    pub fn modal(&self) -> Option<&Dialog> {
+        self.modals.last()
+    }
+
+    /// Queues `toast` behind any already showing.
+    pub fn show_toast(&mut self, toast: Toast) {
+        self.toasts.push(toast);
+    }
+
+    /// The toast currently on screen, if any.
+    #[must_use]
+    pub fn toast(&self) -> Option<&Toast> {
+        self.toasts.current()
+    }
+
+    /// What's on screen as a [`SemanticNode`] tree (see
+    /// `super::semantics`): a [`Role::Root`] holding the current screen,
+    /// then the open dialog and the toast, if any — the same layers, in
+    /// the same order, [`Self::render`] draws. Focus follows input: the
+    /// dialog has it while one is open, the screen otherwise.
+    #[must_use]
+    pub fn semantics(&self) -> SemanticNode {
+        let mut layers = vec![self.current().semantics().with_selected(self.modals.is_empty())];
+        if let Some(dialog) = self.modal().and_then(Widget::semantics) {
+            layers.push(dialog.with_selected(true));
+        }
+        if let Some(toast) = self.toast() {
+            layers.push(toast.semantics());
+        }
+        SemanticNode::new(Role::Root, "").with_children(layers).with_focus(true)
+    }
+
+    /// Advances toast expiry, and the current screen's focused widget (see
+    /// `Widget::tick`), to `now` (read from the platform's `Clock`). The
+    /// screen isn't ticked while a dialog is open — it's dimmed and has no
+    /// input focus, so nothing on it should be moving. Returns whether
+    /// anything visible changed, so the caller knows to re-render.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let toast_changed = self.toasts.tick(now);
+        let screen_changed = self.modals.is_empty() && self.current_mut().tick(now);
+        toast_changed || screen_changed
+    }
+
     fn apply_action(&mut self, action: Action) {
         match action {
             Action::PushView(builder) => self.push(builder()),
             Action::PopView | Action::Back => {
                 self.pop();
             }
+            Action::ShowModal(dialog) => self.show_modal(dialog),
+            Action::ShowToast(toast) => self.show_toast(toast),
             Action::None => {}
         }
     }
 
+    /// Input routing while a dialog is open: `Next`/`Prev`/`NextN`/`PrevN`
+    /// and `Home`/`End` move between its choices (`Char` is forwarded too,
+    /// and ignored by `Dialog`), `Activate` closes it and then applies the
+    /// selected choice's action (so that action acts on the screen
+    /// beneath, not on the dialog), and `Back` closes it without running
+    /// any choice.
+    fn dispatch_to_modal(&mut self, intent: NavIntent) {
+        match intent {
+            NavIntent::Next
+            | NavIntent::NextN(_)
+            | NavIntent::Prev
+            | NavIntent::PrevN(_)
+            | NavIntent::Home
+            | NavIntent::End
+            | NavIntent::Char(_) => {
+                if let Some(modal) = self.modals.last_mut() {
+                    let action = modal.on_intent(intent);
+                    self.apply_action(action);
+                }
+            }
+            NavIntent::Activate => {
+                if let Some(mut modal) = self.modals.pop() {
+                    let action = modal.on_focus(FocusEvent::Activated);
+                    self.apply_action(action);
+                }
+            }
+            NavIntent::Back => {
+                self.modals.pop();
+            }
+        }
+    }
+
     /// Dispatches a semantic navigation intent to the current screen.
     ///
     /// # Known simplification
     ///
-    /// For `Next`/`Prev`/`NextN`, the intent is forwarded to the focused
+    /// For `Next`/`Prev`/`NextN`/`PrevN`, the intent is forwarded to the focused
     /// widget first (so e.g. a list can move its own internal selection),
     /// and then *also* drives the screen's own top-level focus cycling
     /// (salvaged from `Document::focus_next`/`focus_previous`). On a
@@ -101,24 +207,46 @@ impl Navigator {
     /// widget to say "I consumed that, don't also refocus." Fixing that
     /// needs a "consumed" signal `Widget::on_intent` doesn't have today.
     /// Deferred — flagged here rather than silently shipped as correct.
+    ///
+    /// `Home`, `End` and `Char` only ever go to the focused widget: they
+    /// are jumps within it (first/last row, type-ahead), so they never
+    /// cycle top-level focus and the simplification above doesn't apply.
+    ///
+    /// `Back` pops the current screen. On the root screen, which can't be
+    /// popped, it's forwarded to the focused widget instead — that's how
+    /// the root list opens the settings screen on a long press.
+    ///
+    /// While a dialog is open none of this applies: the screen stack sees
+    /// no input at all until the dialog closes (see `dispatch_to_modal`).
     pub fn dispatch(&mut self, intent: NavIntent) {
+        if !self.modals.is_empty() {
+            self.dispatch_to_modal(intent);
+            return;
+        }
         match intent {
             NavIntent::Next | NavIntent::NextN(_) => {
                 let action = self.current_mut().forward_to_focused(intent);
                 self.apply_action(action);
                 self.current_mut().focus_next();
             }
-            NavIntent::Prev => {
+            NavIntent::Prev | NavIntent::PrevN(_) => {
                 let action = self.current_mut().forward_to_focused(intent);
                 self.apply_action(action);
                 self.current_mut().focus_previous();
             }
+            NavIntent::Home | NavIntent::End | NavIntent::Char(_) => {
+                let action = self.current_mut().forward_to_focused(intent);
+                self.apply_action(action);
+            }
             NavIntent::Activate => {
                 let action = self.current_mut().activate_focused();
                 self.apply_action(action);
             }
             NavIntent::Back => {
-                self.pop();
+                if !self.pop() {
+                    let action = self.current_mut().forward_to_focused(intent);
+                    self.apply_action(action);
+                }
             }
         }
     }
@@ -126,7 +254,7 @@ impl Navigator {
     /// Renders the current screen into `target`, computing chrome regions
     /// from whatever size `target` happens to be.
     ///
-    /// Clears `target` to [`palette::BACKGROUND`] first. This matters because, per the
+    /// Clears `target` to the theme's [`Palette::background`] first. This matters because, per the
     /// presentation-surface ADR, the app core owns a single long-lived
     /// framebuffer that gets re-rendered into every frame rather than
     /// reallocated — without an explicit clear, a widget that doesn't
@@ -137,23 +265,45 @@ impl Navigator {
     /// selection moves away. Widgets and tests that always render into a
     /// fresh `FrameBuffer565` (already black) are unaffected by this.
     ///
+    /// With a dialog open, the screen is rendered as usual, dimmed with
+    /// [`FrameBuffer565::dim`], and the topmost dialog is drawn centered
+    /// on the whole framebuffer (only the topmost: anything beneath it is
+    /// unreachable until it closes anyway). The current toast, if any, is
+    /// drawn last, over the hint bar, so it stays readable above the dim
+    /// layer.
+    ///
+    /// The chrome is laid out with the [`LayoutProfile`] for `target`'s
+    /// size, and everything is drawn in `theme` as that profile adapts it
+    /// (a strip panel forces its compact font set), so one `Navigator`
+    /// renders correctly into any panel it's handed.
+    ///
     /// # Errors
     ///
     /// Never, in practice: `FrameBuffer565`'s `DrawTarget::Error` is
     /// `Infallible`. The `Result` return exists so this can use `?`
     /// against embedded-graphics `Drawable::draw` calls internally.
-    pub fn render(&self, target: &mut FrameBuffer565) -> Result<(), Infallible> {
-        target.clear(palette::BACKGROUND)?;
-        let chrome = compute_chrome(target.size());
-        self.current().render(&chrome, target)
+    pub fn render(&self, theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible> {
+        let profile = LayoutProfile::for_panel(target.size());
+        let theme = &profile.theme(theme);
+        target.clear(theme.palette.background)?;
+        let chrome = compute_chrome(target.size(), &profile);
+        self.current().render(&chrome, theme, target)?;
+        if let Some(modal) = self.modal() {
+            target.dim();
+            modal.render(profile.safe_area(target.size()), theme, target)?;
+        }
+        self.toasts.render(chrome.toast, theme, target)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::render::theme::Palette;
     use crate::render::list::{ListItem, VerticalList};
-    use embedded_graphics::prelude::{OriginDimensions, Point, Size};
+    use embedded_graphics::prelude::{OriginDimensions, Point, Primitive, Size};
+    use embedded_graphics::primitives::Rectangle;
+    use embedded_graphics::Drawable;
 
     fn list_screen(title: &str, n: usize) -> Screen {
         let items = (0..n).map(|i| ListItem::new(format!("{title}-item-{i}"))).collect();
@@ -190,6 +340,39 @@ mod tests {
         assert_eq!(nav.depth(), 1);
     }
 
+    #[test]
+    fn back_on_the_root_screen_is_forwarded_to_its_focused_widget() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct BackCounter(Rc<Cell<u32>>);
+        impl Widget for BackCounter {
+            fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
+                constraints
+            }
+            fn render(&self, _area: Rectangle, _theme: &Theme, _target: &mut FrameBuffer565) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn is_focusable(&self) -> bool {
+                true
+            }
+            fn on_intent(&mut self, intent: NavIntent) -> Action {
+                if intent == NavIntent::Back {
+                    self.0.set(self.0.get() + 1);
+                }
+                Action::None
+            }
+        }
+
+        let backs = Rc::new(Cell::new(0));
+        let mut nav = Navigator::new(Screen::new("root", vec![Box::new(BackCounter(Rc::clone(&backs)))]));
+        nav.push(list_screen("detail", 1));
+        nav.dispatch(NavIntent::Back);
+        assert_eq!(backs.get(), 0, "a pop doesn't also reach the widget beneath");
+        nav.dispatch(NavIntent::Back);
+        assert_eq!(backs.get(), 1);
+    }
+
     #[test]
     fn next_intent_moves_the_focused_lists_selection() {
         let mut nav = Navigator::new(list_screen("root", 5));
@@ -254,17 +437,17 @@ mod tests {
         let mut fb = FrameBuffer565::new(320, 170);
         let mut nav = Navigator::new(list_screen("Vault", 3));
 
-        nav.render(&mut fb).unwrap();
+        nav.render(&Theme::DARK, &mut fb).unwrap();
         // x=20: past the 4px selection accent bar, so this samples the
         // row's plain elevated fill rather than the accent stripe.
         let row0_highlighted = fb.pixel(Point::new(20, 18));
-        assert_eq!(row0_highlighted, palette::SURFACE_ELEVATED, "row 0 starts selected");
+        assert_eq!(row0_highlighted, Palette::DARK.surface_elevated, "row 0 starts selected");
 
         nav.dispatch(NavIntent::Next);
-        nav.render(&mut fb).unwrap();
+        nav.render(&Theme::DARK, &mut fb).unwrap();
         let row0_after_move = fb.pixel(Point::new(20, 18));
         assert_ne!(
-            row0_after_move, palette::SURFACE_ELEVATED,
+            row0_after_move, Palette::DARK.surface_elevated,
             "row 0's stale highlight from the first render must not survive into the second"
         );
     }
@@ -273,11 +456,149 @@ mod tests {
     fn render_works_end_to_end_on_a_fresh_navigator() {
         let mut fb = FrameBuffer565::new(320, 170);
         let nav = Navigator::new(list_screen("Vault", 3));
-        nav.render(&mut fb).unwrap();
+        nav.render(&Theme::DARK, &mut fb).unwrap();
         assert_eq!(fb.size(), Size::new(320, 170));
         // Sanity: the title bar's surface fill was drawn somewhere, i.e.
         // rendering actually did something (not just the background clear).
-        let any_title_bar_surface = fb.pixels().any(|p| p.1 == palette::SURFACE);
+        let any_title_bar_surface = fb.pixels().any(|p| p.1 == Palette::DARK.surface);
         assert!(any_title_bar_surface);
     }
+
+    fn wipe_dialog() -> Dialog {
+        Dialog::new("Wipe vault?", "This cannot be undone.")
+            .with_choice("Cancel", || Action::None)
+            .with_choice("Wipe", || Action::PopView)
+    }
+
+    #[test]
+    fn an_open_modal_receives_all_input_and_the_screen_none() {
+        let mut nav = Navigator::new(list_screen("root", 3));
+        nav.push(list_screen("detail", 3));
+        nav.show_modal(wipe_dialog());
+
+        nav.dispatch(NavIntent::Next);
+        assert_eq!(nav.modal().map(Dialog::selected_index), Some(1));
+        assert_eq!(nav.depth(), 2, "the screen stack is untouched while the modal is open");
+    }
+
+    #[test]
+    fn activating_a_choice_closes_the_modal_then_applies_its_action_to_the_stack() {
+        let mut nav = Navigator::new(list_screen("root", 3));
+        nav.push(list_screen("detail", 3));
+        nav.show_modal(wipe_dialog());
+
+        nav.dispatch(NavIntent::Next);
+        nav.dispatch(NavIntent::Activate);
+        assert!(nav.modal().is_none());
+        assert_eq!(nav.depth(), 1, "the Wipe choice's PopView popped the screen beneath");
+    }
+
+    #[test]
+    fn back_dismisses_the_modal_without_running_a_choice() {
+        let mut nav = Navigator::new(list_screen("root", 3));
+        nav.push(list_screen("detail", 3));
+        nav.show_modal(wipe_dialog().with_selected(1));
+
+        nav.dispatch(NavIntent::Back);
+        assert!(nav.modal().is_none());
+        assert_eq!(nav.depth(), 2, "Back only closed the dialog, it didn't also pop a screen");
+    }
+
+    #[test]
+    fn a_widget_can_open_a_modal_and_a_choice_can_chain_another() {
+        let list = VerticalList::new(vec![ListItem::new("wipe")]).on_activate(|_item| {
+            Action::ShowModal(Dialog::new("Wipe vault?", "").with_choice("Yes", || {
+                Action::ShowModal(Dialog::new("Really?", "").with_choice("Yes", || Action::None))
+            }))
+        });
+        let mut nav = Navigator::new(Screen::new("root", vec![Box::new(list)]));
+
+        nav.dispatch(NavIntent::Activate);
+        assert_eq!(nav.modal().map(Dialog::title), Some("Wipe vault?"));
+        nav.dispatch(NavIntent::Activate);
+        assert_eq!(nav.modal().map(Dialog::title), Some("Really?"));
+        nav.dispatch(NavIntent::Activate);
+        assert!(nav.modal().is_none());
+    }
+
+    #[test]
+    fn render_dims_the_screen_beneath_an_open_modal() {
+        let mut fb = FrameBuffer565::new(320, 170);
+        let mut nav = Navigator::new(list_screen("Vault", 3));
+        nav.render(&Theme::DARK, &mut fb).unwrap();
+        let title_bar = fb.pixel(Point::new(2, 2));
+        assert_eq!(title_bar, Palette::DARK.surface);
+
+        nav.show_modal(wipe_dialog());
+        nav.render(&Theme::DARK, &mut fb).unwrap();
+        let mut dimmed = FrameBuffer565::new(1, 1);
+        Rectangle::new(Point::zero(), Size::new(1, 1))
+            .into_styled(embedded_graphics::primitives::PrimitiveStyle::with_fill(Palette::DARK.surface))
+            .draw(&mut dimmed)
+            .unwrap();
+        dimmed.dim();
+        assert_eq!(fb.pixel(Point::new(2, 2)), dimmed.pixel(Point::zero()));
+        assert_eq!(fb.pixel(Point::new(160, 85)), Palette::DARK.surface, "the dialog card itself is not dimmed");
+    }
+
+    /// A focusable widget that reports a change on every tick.
+    struct Animated;
+
+    impl Widget for Animated {
+        fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
+            constraints
+        }
+
+        fn render(&self, _area: Rectangle, _theme: &Theme, _target: &mut FrameBuffer565) -> Result<(), Infallible> {
+            Ok(())
+        }
+
+        fn is_focusable(&self) -> bool {
+            true
+        }
+
+        fn tick(&mut self, _now: Instant) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn tick_reaches_the_focused_widget_except_under_a_dialog() {
+        let mut nav = Navigator::new(Screen::new("Vault", vec![Box::new(Animated)]));
+        let now = Instant::now();
+        assert!(nav.tick(now));
+
+        nav.show_modal(Dialog::new("Wipe vault?", ""));
+        assert!(!nav.tick(now), "the dimmed screen under a dialog holds still");
+    }
+
+    #[test]
+    fn toasts_draw_over_the_hint_bar_and_expire_on_tick() {
+        use crate::render::ChromeStatus;
+        use std::time::Duration;
+
+        let mut fb = FrameBuffer565::new(320, 170);
+        let mut nav = Navigator::new(list_screen("Vault", 3));
+        let start = Instant::now();
+        nav.show_toast(Toast::new("Sync failed").with_status(ChromeStatus::Error));
+        nav.tick(start);
+
+        nav.render(&Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixel(Point::new(0, 165)), Palette::DARK.status_error);
+
+        assert!(nav.tick(start + Duration::from_secs(5)));
+        assert!(nav.toast().is_none());
+        nav.render(&Theme::DARK, &mut fb).unwrap();
+        assert_ne!(fb.pixel(Point::new(0, 165)), Palette::DARK.status_error);
+    }
+
+    #[test]
+    fn a_toast_stays_readable_above_an_open_modal() {
+        let mut fb = FrameBuffer565::new(320, 170);
+        let mut nav = Navigator::new(list_screen("Vault", 3));
+        nav.show_modal(wipe_dialog());
+        nav.show_toast(Toast::new("Copied"));
+        nav.render(&Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixel(Point::new(300, 165)), Palette::DARK.surface_elevated, "the toast is drawn after the dim pass");
+    }
 }
@@ -5,9 +5,11 @@
 //! + `focused_index`), reimplemented on `embedded-graphics`.
 
 use std::convert::Infallible;
+use std::time::Instant;
 
 use embedded_graphics::{
     draw_target::DrawTargetExt,
+    pixelcolor::Rgb565,
     prelude::{Point, Primitive, Size},
     primitives::{Circle, PrimitiveStyle, Rectangle},
     Drawable,
@@ -19,7 +21,9 @@ use crate::input::NavIntent;
 
 use super::chrome::ChromeLayout;
 use super::framebuffer::FrameBuffer565;
-use super::theme::{font, icon, palette};
+use super::semantics::{Role, SemanticNode};
+use super::text_view::wrap_text;
+use super::theme::{font, icon, line_height, Theme};
 use super::widget::{Action, ChromeContribution, ChromeStatus, FocusEvent, Widget};
 
 /// Margin (px) from the title bar's left/right edges to its shield mark /
@@ -43,12 +47,23 @@ const HINT_SIDE_MARGIN: i32 = 8;
 /// hardcoding a per-character pixel width (the thing this bead's title-bar
 /// rework is specifically meant to survive the later `u8g2-fonts` font
 /// swap without, per the bead description).
-fn text_width(font: &FontRenderer, text: &str) -> u32 {
+pub(crate) fn text_width(font: &FontRenderer, text: &str) -> u32 {
     font.get_rendered_dimensions_aligned(text, Point::zero(), VerticalPosition::Top, HorizontalAlignment::Left)
         .unwrap_or(None)
         .map_or(0, |bbox| bbox.size.width)
 }
 
+/// The palette color a [`ChromeStatus`] is painted in — the title bar's
+/// status dot, and a toast's accent bar (see `super::toast`), so the two
+/// can never disagree about what "error" looks like.
+pub(super) fn status_color(status: ChromeStatus, theme: &Theme) -> Rgb565 {
+    match status {
+        ChromeStatus::Success => theme.palette.status_success,
+        ChromeStatus::Error => theme.palette.status_error,
+        ChromeStatus::Neutral => theme.palette.text_secondary,
+    }
+}
+
 pub struct Screen {
     pub title: String,
     /// Static hint text drawn in the hint bar, e.g. control legends. Not a
@@ -97,6 +112,41 @@ impl Screen {
         self.focused_index.and_then(|index| self.widgets[index].chrome_contribution())
     }
 
+    /// The chrome text this screen draws right now: the focused widget's
+    /// [`ChromeContribution`] with the screen's static `title`/`hint`
+    /// filled in where it has none, so `title` and `hint` are always
+    /// `Some`. What [`Self::render`] paints, as data — for tests and
+    /// tooling that need to know what's on screen without reading pixels.
+    #[must_use]
+    pub fn chrome(&self) -> ChromeContribution {
+        let contribution = self.chrome_contribution().unwrap_or_default();
+        ChromeContribution {
+            title: Some(contribution.title.unwrap_or_else(|| self.title.clone())),
+            hint: Some(contribution.hint.unwrap_or_else(|| self.hint.clone())),
+            ..contribution
+        }
+    }
+
+    /// This screen as a [`SemanticNode`]: titled and valued (the readout)
+    /// from [`Self::chrome`], with each widget that describes itself as a
+    /// child — the focused one selected — and the hint text last. Not
+    /// itself focused: whether the screen has input at all (it doesn't
+    /// under a dialog) is the `Navigator`'s call.
+    #[must_use]
+    pub fn semantics(&self) -> SemanticNode {
+        let chrome = self.chrome();
+        let mut children: Vec<SemanticNode> = self
+            .widgets
+            .iter()
+            .enumerate()
+            .filter_map(|(index, widget)| Some(widget.semantics()?.with_selected(self.focused_index == Some(index))))
+            .collect();
+        children.push(SemanticNode::new(Role::Hint, chrome.hint.unwrap_or_default()));
+        SemanticNode::new(Role::Screen, chrome.title.unwrap_or_default())
+            .with_optional_value(chrome.readout)
+            .with_children(children)
+    }
+
     /// Focuses the first focusable widget, if none is focused yet. Called
     /// when a screen is first pushed onto the stack. A no-op if focus was
     /// already established (which is how per-screen focus memory works:
@@ -171,6 +221,15 @@ impl Screen {
         }
     }
 
+    /// Forwards a clock tick to the currently focused widget (if any) — see
+    /// `Widget::tick`. Returns whether it needs a re-render.
+    pub(super) fn tick(&mut self, now: Instant) -> bool {
+        match self.focused_index {
+            Some(index) => self.widgets[index].tick(now),
+            None => false,
+        }
+    }
+
     /// Activates the currently focused widget (`NavIntent::Activate`).
     pub(super) fn activate_focused(&mut self) -> Action {
         match self.focused_index {
@@ -184,16 +243,18 @@ impl Screen {
     /// `Widget::measure`), and the hint bar — pulling live overrides from
     /// the focused widget's [`ChromeContribution`] (see
     /// [`Self::chrome_contribution`]) over this screen's static
-    /// `title`/`hint` wherever the widget supplies one.
+    /// `title`/`hint` wherever the widget supplies one — all in `theme`,
+    /// which is handed on to every widget's `render`.
     pub(super) fn render(
         &self,
         chrome: &ChromeLayout,
+        theme: &Theme,
         target: &mut FrameBuffer565,
     ) -> Result<(), Infallible> {
-        chrome.title.into_styled(PrimitiveStyle::with_fill(palette::SURFACE)).draw(target)?;
+        chrome.title.into_styled(PrimitiveStyle::with_fill(theme.palette.surface)).draw(target)?;
 
         // Hairline dividers along the title bar's bottom edge and the hint
-        // bar's top edge — the same `palette::DIVIDER` hairline the list
+        // bar's top edge — the same `Palette::divider` hairline the list
         // rows use between unfocused rows, so chrome and content read as
         // one consistent visual language rather than content borrowing a
         // rule chrome doesn't also follow.
@@ -202,18 +263,18 @@ impl Screen {
                 Point::new(chrome.title.top_left.x, chrome.title.top_left.y + chrome.title.size.height as i32 - 1),
                 Size::new(chrome.title.size.width, 1),
             );
-            divider.into_styled(PrimitiveStyle::with_fill(palette::DIVIDER)).draw(target)?;
+            divider.into_styled(PrimitiveStyle::with_fill(theme.palette.divider)).draw(target)?;
         }
         if chrome.hint.size.height > 0 {
             let divider = Rectangle::new(chrome.hint.top_left, Size::new(chrome.hint.size.width, 1));
-            divider.into_styled(PrimitiveStyle::with_fill(palette::DIVIDER)).draw(target)?;
+            divider.into_styled(PrimitiveStyle::with_fill(theme.palette.divider)).draw(target)?;
         }
 
-        let contribution = self.chrome_contribution();
-        let title_text = contribution.as_ref().and_then(|c| c.title.as_deref()).unwrap_or(self.title.as_str());
-        let readout_text = contribution.as_ref().and_then(|c| c.readout.as_deref());
-        let status = contribution.as_ref().and_then(|c| c.status);
-        let hint_text = contribution.as_ref().and_then(|c| c.hint.as_deref()).unwrap_or(self.hint.as_str());
+        let contribution = self.chrome();
+        let title_text = contribution.title.as_deref().unwrap_or_default();
+        let readout_text = contribution.readout.as_deref();
+        let status = contribution.status;
+        let hint_text = contribution.hint.as_deref().unwrap_or_default();
 
         // Vertically centered in the title bar via `VerticalPosition::Center`
         // rather than a hand-picked baseline offset (the "+11" this
@@ -243,7 +304,7 @@ impl Screen {
             Point::new(shield_x, shield_y),
             VerticalPosition::Top,
             HorizontalAlignment::Left,
-            FontColor::Transparent(palette::BRAND_BRIGHT),
+            FontColor::Transparent(theme.palette.brand_bright),
             target,
         );
         let title_text_x = shield_x + text_width(&shield_font, shield_str) as i32 + TITLE_ELEMENT_GAP;
@@ -254,11 +315,7 @@ impl Screen {
         let mut right_cursor = chrome.title.top_left.x + chrome.title.size.width as i32 - TITLE_SIDE_MARGIN;
 
         if let Some(status) = status {
-            let dot_color = match status {
-                ChromeStatus::Success => palette::STATUS_SUCCESS,
-                ChromeStatus::Error => palette::STATUS_ERROR,
-                ChromeStatus::Neutral => palette::TEXT_SECONDARY,
-            };
+            let dot_color = status_color(status, theme);
             let dot_center = Point::new(right_cursor - STATUS_DOT_DIAMETER as i32 / 2, title_mid_y);
             Circle::with_center(dot_center, STATUS_DOT_DIAMETER)
                 .into_styled(PrimitiveStyle::with_fill(dot_color))
@@ -266,14 +323,14 @@ impl Screen {
             right_cursor -= STATUS_DOT_DIAMETER as i32 + TITLE_ELEMENT_GAP;
         }
 
-        let readout_font = font::title();
+        let readout_font = theme.fonts.title();
         if let Some(readout) = readout_text {
             let _ = readout_font.render_aligned(
                 readout,
                 Point::new(right_cursor, title_mid_y),
                 VerticalPosition::Center,
                 HorizontalAlignment::Right,
-                FontColor::Transparent(palette::TEXT_SECONDARY),
+                FontColor::Transparent(theme.palette.text_secondary),
                 target,
             );
             right_cursor -= text_width(&readout_font, readout) as i32 + TITLE_ELEMENT_GAP;
@@ -289,12 +346,12 @@ impl Screen {
             Size::new(title_clip_width, chrome.title.size.height),
         );
         let mut title_target = target.clipped(&title_rect);
-        let _ = font::title().render_aligned(
+        let _ = theme.fonts.title().render_aligned(
             title_text,
             Point::new(title_text_x, title_mid_y),
             VerticalPosition::Center,
             HorizontalAlignment::Left,
-            FontColor::Transparent(palette::TEXT_PRIMARY),
+            FontColor::Transparent(theme.palette.text_primary),
             &mut title_target,
         );
 
@@ -305,24 +362,43 @@ impl Screen {
                 break;
             }
             let available = Size::new(chrome.content.size.width, (bottom - y) as u32);
-            let requested = widget.measure(available);
+            let requested = widget.measure(available, theme);
             let height = requested.height.min(available.height);
 
             let area = Rectangle::new(Point::new(chrome.content.top_left.x, y), Size::new(chrome.content.size.width, height));
-            widget.render(area, target)?;
+            widget.render(area, theme, target)?;
             y += height as i32;
         }
 
         if chrome.hint.size.height > 0 {
-            let hint_mid_y = chrome.hint.top_left.y + chrome.hint.size.height as i32 / 2;
-            let _ = font::hint().render_aligned(
-                hint_text,
-                Point::new(chrome.hint.top_left.x + HINT_SIDE_MARGIN, hint_mid_y),
-                VerticalPosition::Center,
-                HorizontalAlignment::Left,
-                FontColor::Transparent(palette::TEXT_SECONDARY),
-                target,
-            );
+            // Wrapped to the bar's width, as many lines as its height holds
+            // (a portrait profile's taller bar takes two); the block is
+            // centred vertically, so a hint that fits on one line sits
+            // exactly where the single-line bar has always put it.
+            let hint_font = theme.fonts.hint();
+            let pitch = line_height(&hint_font);
+            let max_lines = (chrome.hint.size.height as i32 / pitch).max(1) as usize;
+            let wrap_width = (chrome.hint.size.width as i32 - 2 * HINT_SIDE_MARGIN).max(0) as u32;
+            let mut lines = wrap_text(&hint_font, hint_text, wrap_width);
+            if lines.len() <= 1 || max_lines == 1 {
+                lines = vec![hint_text.to_string()];
+            }
+            lines.truncate(max_lines);
+            let block_mid_y = chrome.hint.top_left.y + chrome.hint.size.height as i32 / 2;
+            let first_mid_y = block_mid_y - (lines.len() as i32 - 1) * pitch / 2;
+            // Clipped to the bar: an unwrapped line too long for a narrow
+            // (or round-inset) bar is cut at its edge, never drawn past it.
+            let mut clipped = target.clipped(&chrome.hint);
+            for (i, line) in lines.iter().enumerate() {
+                let _ = hint_font.render_aligned(
+                    line.as_str(),
+                    Point::new(chrome.hint.top_left.x + HINT_SIDE_MARGIN, first_mid_y + i as i32 * pitch),
+                    VerticalPosition::Center,
+                    HorizontalAlignment::Left,
+                    FontColor::Transparent(theme.palette.text_secondary),
+                    &mut clipped,
+                );
+            }
         }
 
         Ok(())
@@ -332,6 +408,8 @@ impl Screen {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::render::theme::Palette;
+    use crate::render::LayoutProfile;
     use crate::render::list::{ListItem, VerticalList};
 
     fn list_screen(n: usize) -> Screen {
@@ -367,10 +445,10 @@ mod tests {
     fn render_does_not_panic_and_writes_into_the_provided_chrome_regions() {
         let mut screen = list_screen(5);
         screen.initialize_focus();
-        let chrome = super::super::chrome::compute_chrome(Size::new(320, 170));
+        let chrome = super::super::chrome::compute_chrome(Size::new(320, 170), &LayoutProfile::LANDSCAPE);
         let mut fb = FrameBuffer565::new(320, 170);
-        screen.render(&chrome, &mut fb).unwrap();
+        screen.render(&chrome, &Theme::DARK, &mut fb).unwrap();
         // Title bar was filled with its background color.
-        assert_eq!(fb.pixel(Point::new(0, 0)), palette::SURFACE);
+        assert_eq!(fb.pixel(Point::new(0, 0)), Palette::DARK.surface);
     }
 }
@@ -29,7 +29,7 @@ use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::{Point, Size};
 use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
 
-use super::theme::{font, icon, palette};
+use super::theme::{font, icon, Theme};
 use super::widget::FocusEvent;
 
 /// Number of mask glyphs drawn when hidden — fixed, *not* derived from the
@@ -39,12 +39,18 @@ use super::widget::FocusEvent;
 pub const MASK_GLYPH_COUNT: usize = 10;
 
 /// The glyph repeated [`MASK_GLYPH_COUNT`] times when hidden. `*`, not a
-/// bullet/dot codepoint — [`font::secret`]'s `profont17` has no bullet
+/// bullet/dot codepoint — [`FontSet::secret`]'s `profont17` has no bullet
 /// glyph (see that accessor's doc comment on why `profont` was chosen:
 /// monospacing, not glyph coverage), and `*` is available and reads
 /// unambiguously as "masked" in a monospaced font.
 const MASK_GLYPH: char = '*';
 
+/// [`MASK_GLYPH`] repeated [`MASK_GLYPH_COUNT`] times: what a hidden
+/// secret shows instead of its value.
+fn mask() -> String {
+    std::iter::repeat(MASK_GLYPH).take(MASK_GLYPH_COUNT).collect()
+}
+
 /// Horizontal space reserved at the field's right edge for the lock icon
 /// (`~16px` per [`font::icon_2x`]'s doc comment, plus a small margin) —
 /// subtracted from the value text's clip width so a long revealed password
@@ -106,6 +112,19 @@ impl SecretField {
         }
     }
 
+    /// The value this field shows, as text: the real `password` while
+    /// revealed, the fixed-length mask otherwise — for the field's
+    /// [`super::SemanticNode`], so the semantic tree never exposes more
+    /// than the screen does.
+    #[must_use]
+    pub fn semantic_value(&self, password: &str) -> String {
+        if self.is_revealed() {
+            password.to_string()
+        } else {
+            mask()
+        }
+    }
+
     /// Draws this field's value line — the masked-dots-or-real-password
     /// text plus the lock status icon — into `area`. Does not draw a
     /// label or any focus/selection background; that's the caller's job
@@ -123,7 +142,7 @@ impl SecretField {
     /// Returns `Infallible`'s uninhabited variant in practice — see
     /// [`super::widget::Widget::render`]'s doc comment for why the
     /// `Result` return exists at all.
-    pub fn render<D>(&self, area: embedded_graphics::primitives::Rectangle, password: &str, target: &mut D) -> Result<(), Infallible>
+    pub fn render<D>(&self, area: embedded_graphics::primitives::Rectangle, password: &str, theme: &Theme, target: &mut D) -> Result<(), Infallible>
     where
         D: DrawTarget<Color = Rgb565, Error = Infallible>,
     {
@@ -132,7 +151,7 @@ impl SecretField {
         // Muted while masked ("nothing to see"), amber `STATUS_WARNING`
         // once revealed ("caution: this is currently exposed") — per the
         // bead spec.
-        let icon_color = if self.is_revealed() { palette::STATUS_WARNING } else { palette::TEXT_SECONDARY };
+        let icon_color = if self.is_revealed() { theme.palette.status_warning } else { theme.palette.text_secondary };
         let mut icon_buf = [0_u8; 4];
         let icon_str: &str = icon_char.encode_utf8(&mut icon_buf);
         let _ = icon_font.render_aligned(
@@ -151,24 +170,23 @@ impl SecretField {
         // Both masked and revealed text render in `TEXT_PRIMARY` — per the
         // bead spec, only the icon's color signals reveal state; the value
         // text itself is styled the same as any other field's value.
-        let value_font = font::secret();
+        let value_font = theme.fonts.secret();
         if self.is_revealed() {
             let _ = value_font.render_aligned(
                 password,
                 text_area.top_left,
                 VerticalPosition::Top,
                 HorizontalAlignment::Left,
-                FontColor::Transparent(palette::TEXT_PRIMARY),
+                FontColor::Transparent(theme.palette.text_primary),
                 &mut text_target,
             );
         } else {
-            let mask: String = std::iter::repeat(MASK_GLYPH).take(MASK_GLYPH_COUNT).collect();
             let _ = value_font.render_aligned(
-                mask.as_str(),
+                mask().as_str(),
                 text_area.top_left,
                 VerticalPosition::Top,
                 HorizontalAlignment::Left,
-                FontColor::Transparent(palette::TEXT_PRIMARY),
+                FontColor::Transparent(theme.palette.text_primary),
                 &mut text_target,
             );
         }
@@ -181,6 +199,7 @@ impl SecretField {
 mod tests {
     use super::*;
     use crate::render::framebuffer::FrameBuffer565;
+    use crate::render::theme::Palette;
     use embedded_graphics::prelude::Point as EgPoint;
     use embedded_graphics::primitives::Rectangle;
 
@@ -230,11 +249,11 @@ mod tests {
         // independent of `password`'s content entirely.
         let short = SecretField::new();
         let mut fb_short = FrameBuffer565::new(320, 170);
-        short.render(AREA, "a", &mut fb_short).unwrap();
+        short.render(AREA, "a", &Theme::DARK, &mut fb_short).unwrap();
 
         let long = SecretField::new();
         let mut fb_long = FrameBuffer565::new(320, 170);
-        long.render(AREA, "a-very-long-password-indeed-1234567890", &mut fb_long).unwrap();
+        long.render(AREA, "a-very-long-password-indeed-1234567890", &Theme::DARK, &mut fb_long).unwrap();
 
         let short_pixels: Vec<_> = fb_short.pixels().map(|p| p.1).collect();
         let long_pixels: Vec<_> = fb_long.pixels().map(|p| p.1).collect();
@@ -245,11 +264,11 @@ mod tests {
     fn revealed_rendering_differs_from_masked_rendering() {
         let field = SecretField::new();
         let mut fb_masked = FrameBuffer565::new(320, 170);
-        field.render(AREA, "hunter2", &mut fb_masked).unwrap();
+        field.render(AREA, "hunter2", &Theme::DARK, &mut fb_masked).unwrap();
 
         field.on_focus(FocusEvent::Activated);
         let mut fb_revealed = FrameBuffer565::new(320, 170);
-        field.render(AREA, "hunter2", &mut fb_revealed).unwrap();
+        field.render(AREA, "hunter2", &Theme::DARK, &mut fb_revealed).unwrap();
 
         let masked_pixels: Vec<_> = fb_masked.pixels().map(|p| p.1).collect();
         let revealed_pixels: Vec<_> = fb_revealed.pixels().map(|p| p.1).collect();
@@ -260,16 +279,16 @@ mod tests {
     fn revealed_icon_uses_the_warning_color_and_masked_icon_uses_the_muted_color() {
         let field = SecretField::new();
         let mut fb_masked = FrameBuffer565::new(320, 170);
-        field.render(AREA, "hunter2", &mut fb_masked).unwrap();
-        let any_muted_icon_pixel = fb_masked.pixels().any(|p| p.1 == palette::TEXT_SECONDARY);
+        field.render(AREA, "hunter2", &Theme::DARK, &mut fb_masked).unwrap();
+        let any_muted_icon_pixel = fb_masked.pixels().any(|p| p.1 == Palette::DARK.text_secondary);
         assert!(any_muted_icon_pixel, "masked lock icon should draw in the muted color");
-        let any_warning_pixel_masked = fb_masked.pixels().any(|p| p.1 == palette::STATUS_WARNING);
+        let any_warning_pixel_masked = fb_masked.pixels().any(|p| p.1 == Palette::DARK.status_warning);
         assert!(!any_warning_pixel_masked, "masked state must not show the warning color anywhere");
 
         field.on_focus(FocusEvent::Activated);
         let mut fb_revealed = FrameBuffer565::new(320, 170);
-        field.render(AREA, "hunter2", &mut fb_revealed).unwrap();
-        let any_warning_pixel_revealed = fb_revealed.pixels().any(|p| p.1 == palette::STATUS_WARNING);
+        field.render(AREA, "hunter2", &Theme::DARK, &mut fb_revealed).unwrap();
+        let any_warning_pixel_revealed = fb_revealed.pixels().any(|p| p.1 == Palette::DARK.status_warning);
         assert!(any_warning_pixel_revealed, "revealed lock icon should draw in the warning (amber) color");
     }
 }
@@ -0,0 +1,223 @@
+//! `SemanticNode`: what's on screen as data rather than pixels — each
+//! widget's role, label, value and selection, assembled by the
+//! [`super::Navigator`] into one tree per frame (see
+//! [`super::Navigator::semantics`]).
+//!
+//! Headless tests and agents used to learn what the UI showed by reading
+//! pixels or guessing coordinates; [`super::Screen::chrome`] covered the
+//! title and hint bars but nothing in between. The tree covers the
+//! content too, so "the focused row is GitHub" is an assertion on a
+//! string. It's also the groundwork for a screen-reader-style feedback
+//! mode: everything such a mode would announce is already here.
+//!
+//! - **Widgets describe themselves** through
+//!   [`super::Widget::semantics`], read live like
+//!   [`super::Widget::chrome_contribution`], so the tree always matches
+//!   the frame rendered from the same state.
+//! - **Widgets report selection; the tree derives focus.** A widget marks
+//!   which of its children is selected (the list's cursor row, the
+//!   dialog's highlighted button) and nothing else; a screen marks its
+//!   focused widget the same way, and the root the layer taking input
+//!   (the dialog while one is open, the screen otherwise). Focus is then
+//!   applied once, from the root down, by [`SemanticNode::with_focus`]: a
+//!   focused node passes focus on to its selected children. Selected rows
+//!   of a list under a dialog stay selected but not focused, which is
+//!   exactly what the user sees.
+//! - **Secrets stay secret.** A masked password's value is the mask as
+//!   drawn, never the password; the real value only appears while it's
+//!   revealed on screen (see [`super::SecretField::semantic_value`]).
+//!
+//! Serializes with `serde` as-is: the emulator serves it as JSON from
+//! `GET /api/ui-tree`.
+
+use serde::Serialize;
+
+/// What kind of thing a [`SemanticNode`] is, by how the user interacts
+/// with it rather than how it's drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// The top of the tree: the current screen plus any dialog and toast
+    /// over it.
+    Root,
+    /// One entry in the navigation stack. Label: its title; value: the
+    /// title bar's readout (e.g. `"2 / 5"`), if any.
+    Screen,
+    /// The hint bar's text, as the last child of its screen.
+    Hint,
+    /// A modal dialog. Label: its title; value: its body.
+    Dialog,
+    /// A transient message over the hint bar.
+    Toast,
+    /// A scrollable list of [`Role::ListItem`]s.
+    List,
+    /// One list row. Label: its main line; value: its secondary line.
+    ListItem,
+    /// A list of setting rows.
+    Form,
+    /// An on/off setting row.
+    Toggle,
+    /// A setting row picking one of a fixed set of options.
+    Picker,
+    /// A numeric setting row.
+    Stepper,
+    /// Something that acts when pressed: a dialog choice, an action row.
+    Button,
+    /// A group of read-only fields, e.g. a credential's details. Label:
+    /// what the fields belong to.
+    Group,
+    /// A labelled read-only value.
+    Field,
+    /// A [`Role::Field`] whose value is masked until revealed.
+    SecretField,
+    /// A block of read-only text.
+    Text,
+    /// A content-state message standing in for content that isn't there
+    /// (waiting for sync, empty vault, a removed item). Label: the
+    /// headline; value: the subline, if any.
+    Message,
+}
+
+/// One node of the semantic tree. See the module doc for how `selected`
+/// and `focused` relate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SemanticNode {
+    pub role: Role,
+    pub label: String,
+    /// The node's current value, if it has one: a setting's shown value, a
+    /// field's contents (masked, for a hidden secret), a row's second line.
+    pub value: Option<String>,
+    /// Whether this node is on the path that receives input.
+    pub focused: bool,
+    /// Whether this node is its container's current selection.
+    pub selected: bool,
+    pub children: Vec<SemanticNode>,
+}
+
+impl SemanticNode {
+    /// An unselected, unfocused node with no value and no children.
+    #[must_use]
+    pub fn new(role: Role, label: impl Into<String>) -> Self {
+        Self {
+            role,
+            label: label.into(),
+            value: None,
+            focused: false,
+            selected: false,
+            children: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Sets the value to `value`, if any — for callers holding an
+    /// `Option` already.
+    #[must_use]
+    pub fn with_optional_value(mut self, value: Option<impl Into<String>>) -> Self {
+        self.value = value.map(Into::into);
+        self
+    }
+
+    #[must_use]
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    #[must_use]
+    pub fn with_children(mut self, children: Vec<SemanticNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Marks this node `focused` and, when it is, passes focus on to its
+    /// selected children, recursively. Called once on the finished tree's
+    /// root; see the module doc.
+    #[must_use]
+    pub fn with_focus(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self.children = self
+            .children
+            .into_iter()
+            .map(|child| {
+                let selected = child.selected;
+                child.with_focus(focused && selected)
+            })
+            .collect();
+        self
+    }
+
+    /// This node and every node beneath it, depth first, parents before
+    /// their children.
+    pub fn iter(&self) -> impl Iterator<Item = &SemanticNode> {
+        let mut pending = vec![self];
+        std::iter::from_fn(move || {
+            let node = pending.pop()?;
+            pending.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// The first node (depth first) matching `predicate`.
+    pub fn find(&self, predicate: impl Fn(&SemanticNode) -> bool) -> Option<&SemanticNode> {
+        self.iter().find(|node| predicate(node))
+    }
+
+    /// The deepest focused node: where input goes next, and what a screen
+    /// reader would announce. `None` if nothing is focused.
+    #[must_use]
+    pub fn focus(&self) -> Option<&SemanticNode> {
+        if !self.focused {
+            return None;
+        }
+        self.children
+            .iter()
+            .find_map(SemanticNode::focus)
+            .or(Some(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list() -> SemanticNode {
+        SemanticNode::new(Role::List, "").with_children(vec![
+            SemanticNode::new(Role::ListItem, "Amazon"),
+            SemanticNode::new(Role::ListItem, "GitHub").with_selected(true),
+        ])
+    }
+
+    #[test]
+    fn focus_flows_only_into_selected_children() {
+        let focused = list().with_focus(true);
+        assert!(!focused.children[0].focused);
+        assert!(focused.children[1].focused);
+        assert_eq!(
+            focused.focus().map(|node| node.label.as_str()),
+            Some("GitHub")
+        );
+
+        let unfocused = list().with_focus(false);
+        assert!(unfocused.children[1].selected);
+        assert!(!unfocused.children[1].focused);
+        assert!(unfocused.focus().is_none());
+    }
+
+    #[test]
+    fn a_focused_node_without_a_selected_child_is_its_own_focus() {
+        let node = SemanticNode::new(Role::Text, "Hello").with_focus(true);
+        assert_eq!(node.focus(), Some(&node));
+    }
+
+    #[test]
+    fn iter_visits_parents_before_children_in_order() {
+        let labels: Vec<_> = list().iter().map(|node| node.label.clone()).collect();
+        assert_eq!(labels, ["", "Amazon", "GitHub"]);
+        assert!(list().find(|node| node.label == "GitHub").is_some());
+    }
+}
@@ -0,0 +1,407 @@
+//! `TextView`: a read-only, word-wrapped, scrollable block of text — for
+//! content too long for any fixed-size field (a secure note running to
+//! dozens of lines), shown as a full screen of its own.
+//!
+//! Wrapping happens at render time, against the width the view is actually
+//! given, using the same [`Theme`] font metrics the text is drawn with
+//! (via [`wrap_text`]), and is cached per width and font set so an
+//! unchanged frame doesn't re-measure every word. Scrolling is by whole lines: `Next`/
+//! `Prev` move one line, `NextN` (fast rotation, Pg Dn) moves a page —
+//! one line short of a full screen, so the last line of the previous page
+//! stays visible as an anchor. A right-edge scrollbar, the same one
+//! `CredentialListView` draws ([`theme::draw_scrollbar`]), shows where in
+//! the text the viewport is.
+//!
+//! The text itself is a snapshot taken when the view is built, unlike the
+//! store-backed views: a note being read doesn't reflow under the reader
+//! when a sync lands.
+
+use std::cell::{Cell, RefCell};
+use std::convert::Infallible;
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use embedded_graphics::prelude::{Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
+use u8g2_fonts::FontRenderer;
+
+use crate::input::NavIntent;
+
+use super::framebuffer::FrameBuffer565;
+use super::screen::text_width;
+use super::semantics::{Role, SemanticNode};
+use super::theme::{self, FontSet, Theme, SCROLLBAR_WIDTH};
+use super::widget::{Action, ChromeContribution, FocusEvent, Widget};
+
+/// Horizontal margin (px) between the view's left edge (and the
+/// scrollbar) and the text.
+const SIDE_MARGIN: u32 = 8;
+/// Vertical padding (px) above the first line and below the last.
+const TOP_PADDING: u32 = 6;
+/// Extra leading (px) between lines, on top of the font's own footprint.
+const LINE_GAP: u32 = 2;
+
+/// Greedy word wrap of `text` into lines no wider than `max_width` px in
+/// `font`. Hard line breaks (`\n`) are kept, including blank lines; runs of
+/// other whitespace collapse to one space. A single word wider than
+/// `max_width` (a long URL, a recovery key) is broken between characters
+/// rather than overflowing.
+#[must_use]
+pub fn wrap_text(font: &FontRenderer, text: &str, max_width: u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if text_width(font, &candidate) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if text_width(font, word) <= max_width {
+                line = word.to_string();
+            } else {
+                line = break_word(font, word, max_width, &mut lines);
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Pushes as many full-width character runs of `word` onto `lines` as it
+/// takes, returning the remainder (which fits). Always takes at least one
+/// character per line, so a `max_width` narrower than any glyph still
+/// terminates.
+fn break_word(font: &FontRenderer, word: &str, max_width: u32, lines: &mut Vec<String>) -> String {
+    let mut chunk = String::new();
+    for ch in word.chars() {
+        chunk.push(ch);
+        if text_width(font, &chunk) > max_width && chunk.chars().count() > 1 {
+            chunk.pop();
+            lines.push(std::mem::replace(&mut chunk, ch.to_string()));
+        }
+    }
+    chunk
+}
+
+pub struct TextView {
+    text: String,
+    /// `(width, fonts, lines)`: `text` wrapped for the width and font set
+    /// it was last rendered with. `RefCell` because wrapping needs the
+    /// render width and theme, which only `render` (`&self`) knows.
+    wrapped: RefCell<(u32, FontSet, Vec<String>)>,
+    /// The first visible line. `Cell` so `render` can clamp it if the
+    /// viewport grew or the text rewrapped shorter.
+    top_line: Cell<usize>,
+    /// How many lines fit the viewport as of the last render — the page
+    /// size for `NextN`/`PrevN`, and what `on_intent` clamps scrolling
+    /// against.
+    visible_lines: Cell<usize>,
+}
+
+impl TextView {
+    #[must_use]
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            wrapped: RefCell::new((0, FontSet::default(), Vec::new())),
+            top_line: Cell::new(0),
+            visible_lines: Cell::new(0),
+        }
+    }
+
+    /// The first visible line (0-based).
+    #[must_use]
+    pub fn top_line(&self) -> usize {
+        self.top_line.get()
+    }
+
+    /// How many lines the text wrapped to at the last render (0 before
+    /// the first).
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.wrapped.borrow().2.len()
+    }
+
+    fn line_pitch(fonts: FontSet) -> u32 {
+        theme::line_height(&fonts.value()) as u32 + LINE_GAP
+    }
+
+    fn max_top_line(&self) -> usize {
+        self.line_count().saturating_sub(self.visible_lines.get())
+    }
+
+    /// How far `NextN`/`PrevN` scroll: a viewport less one line, so the
+    /// last line of one page is the first of the next.
+    fn page(&self) -> usize {
+        self.visible_lines.get().saturating_sub(1).max(1)
+    }
+
+    fn scroll_by(&self, delta: i64) {
+        let max = self.max_top_line() as i64;
+        let next = (self.top_line.get() as i64 + delta).clamp(0, max);
+        self.top_line.set(next as usize);
+    }
+
+    /// Rewraps `text` if `width` or `fonts` differ from the cached wrap.
+    fn wrap_for(&self, width: u32, fonts: FontSet) {
+        let mut wrapped = self.wrapped.borrow_mut();
+        if wrapped.0 != width
+            || wrapped.1 != fonts
+            || (wrapped.2.is_empty() && !self.text.is_empty())
+        {
+            *wrapped = (width, fonts, wrap_text(&fonts.value(), &self.text, width));
+        }
+    }
+}
+
+impl Widget for TextView {
+    fn measure(&self, constraints: Size, _theme: &Theme) -> Size {
+        constraints
+    }
+
+    fn render(
+        &self,
+        area: Rectangle,
+        theme: &Theme,
+        target: &mut FrameBuffer565,
+    ) -> Result<(), Infallible> {
+        let text_area_width = area
+            .size
+            .width
+            .saturating_sub(SCROLLBAR_WIDTH + 2 * SIDE_MARGIN);
+        self.wrap_for(text_area_width, theme.fonts);
+
+        let pitch = Self::line_pitch(theme.fonts);
+        let visible = (area.size.height.saturating_sub(2 * TOP_PADDING) / pitch).max(1) as usize;
+        self.visible_lines.set(visible);
+        self.scroll_by(0);
+        let top = self.top_line.get();
+
+        let wrapped = self.wrapped.borrow();
+        let lines = &wrapped.2;
+        {
+            let mut clipped = target.clipped(&area);
+            let value_font = theme.fonts.value();
+            for (row, line) in lines.iter().skip(top).take(visible).enumerate() {
+                let y = area.top_left.y + (TOP_PADDING + row as u32 * pitch) as i32;
+                let _ = value_font.render_aligned(
+                    line.as_str(),
+                    Point::new(area.top_left.x + SIDE_MARGIN as i32, y),
+                    VerticalPosition::Top,
+                    HorizontalAlignment::Left,
+                    FontColor::Transparent(theme.palette.text_primary),
+                    &mut clipped,
+                );
+            }
+        }
+
+        // Content height includes the same padding the viewport has, so
+        // a text that exactly fits draws no scrollbar. At the last page,
+        // scroll is pinned to the end so the thumb reaches the bottom even
+        // when the viewport isn't a whole number of lines tall.
+        let content_height = lines.len() as u32 * pitch + 2 * TOP_PADDING;
+        let scroll = if top >= self.max_top_line() {
+            u32::MAX
+        } else {
+            top as u32 * pitch
+        };
+        theme::draw_scrollbar(area, content_height, scroll, theme, target)
+    }
+
+    /// Focusable so it receives rotation; there's nothing to select, so
+    /// focus changes draw nothing.
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self, _event: FocusEvent) -> Action {
+        Action::None
+    }
+
+    fn on_intent(&mut self, intent: NavIntent) -> Action {
+        match intent {
+            NavIntent::Next => self.scroll_by(1),
+            NavIntent::Prev => self.scroll_by(-1),
+            NavIntent::NextN(_) => self.scroll_by(self.page() as i64),
+            NavIntent::PrevN(_) => self.scroll_by(-(self.page() as i64)),
+            NavIntent::Home => self.scroll_by(-(self.max_top_line() as i64)),
+            NavIntent::End => self.scroll_by(self.max_top_line() as i64),
+            NavIntent::Char(_) | NavIntent::Activate | NavIntent::Back => {}
+        }
+        Action::None
+    }
+
+    /// A "lines 1-6 / 40"-style readout once the text is longer than a
+    /// screen, and a scroll hint.
+    fn chrome_contribution(&self) -> Option<ChromeContribution> {
+        let count = self.line_count();
+        let visible = self.visible_lines.get();
+        let readout = (visible > 0 && count > visible).then(|| {
+            let first = self.top_line.get() + 1;
+            let last = (self.top_line.get() + visible).min(count);
+            format!("{first}-{last} / {count}")
+        });
+        Some(ChromeContribution {
+            title: None,
+            readout,
+            hint: Some("Rotate to scroll - Hold to go back".to_string()),
+            status: None,
+        })
+    }
+
+    /// The whole text, not just the lines scrolled into view.
+    fn semantics(&self) -> Option<SemanticNode> {
+        Some(SemanticNode::new(Role::Text, self.text.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::theme::Palette;
+
+    const AREA: Rectangle = Rectangle::new(Point::new(0, 0), Size::new(320, 120));
+
+    fn long_text(paragraphs: usize) -> String {
+        (0..paragraphs)
+            .map(|i| {
+                format!("Paragraph {i}: the quick brown fox jumps over the lazy dog, twice over.")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn rendered(text: &str) -> TextView {
+        let view = TextView::new(text);
+        let mut fb = FrameBuffer565::new(320, 120);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        view
+    }
+
+    #[test]
+    fn wrap_text_fits_every_line_and_keeps_blank_lines() {
+        let font = FontSet::Standard.value();
+        let lines = wrap_text(&font, "one two three four five six seven\n\nend", 80);
+        assert!(lines.len() > 3, "{lines:?}");
+        assert!(
+            lines.iter().all(|line| text_width(&font, line) <= 80),
+            "{lines:?}"
+        );
+        assert_eq!(lines[lines.len() - 2], "", "the blank line survives");
+        assert_eq!(lines.last().map(String::as_str), Some("end"));
+    }
+
+    #[test]
+    fn wrap_text_breaks_a_word_wider_than_the_line() {
+        let font = FontSet::Standard.value();
+        let key = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        let lines = wrap_text(&font, key, 60);
+        assert!(lines.len() > 1);
+        assert!(
+            lines.iter().all(|line| text_width(&font, line) <= 60),
+            "{lines:?}"
+        );
+        assert_eq!(lines.concat(), key, "nothing lost or reordered");
+    }
+
+    #[test]
+    fn wrap_text_handles_multibyte_utf8() {
+        let font = FontSet::Standard.value();
+        let text = "über straße café ñandú";
+        let lines = wrap_text(&font, text, 40);
+        assert!(lines.len() > 1);
+        assert_eq!(
+            lines.concat(),
+            text.replace(' ', ""),
+            "split on char boundaries, nothing lost"
+        );
+    }
+
+    #[test]
+    fn next_and_prev_scroll_by_a_line_and_clamp() {
+        let mut view = rendered(&long_text(20));
+        view.on_intent(NavIntent::Prev);
+        assert_eq!(view.top_line(), 0);
+        view.on_intent(NavIntent::Next);
+        view.on_intent(NavIntent::Next);
+        assert_eq!(view.top_line(), 2);
+
+        for _ in 0..500 {
+            view.on_intent(NavIntent::Next);
+        }
+        assert_eq!(
+            view.top_line(),
+            view.line_count() - view.visible_lines.get()
+        );
+    }
+
+    #[test]
+    fn next_n_and_prev_n_page_keeping_one_line_of_overlap() {
+        let mut view = rendered(&long_text(20));
+        let visible = view.visible_lines.get();
+        assert!(visible > 1);
+        view.on_intent(NavIntent::NextN(5));
+        assert_eq!(view.top_line(), visible - 1);
+        view.on_intent(NavIntent::PrevN(5));
+        assert_eq!(view.top_line(), 0, "PrevN pages back by the same amount");
+    }
+
+    #[test]
+    fn home_and_end_jump_to_the_top_and_the_last_page() {
+        let mut view = rendered(&long_text(20));
+        view.on_intent(NavIntent::End);
+        assert_eq!(
+            view.top_line(),
+            view.line_count() - view.visible_lines.get()
+        );
+        view.on_intent(NavIntent::Home);
+        assert_eq!(view.top_line(), 0);
+    }
+
+    #[test]
+    fn short_text_doesnt_scroll_or_show_a_scrollbar_or_readout() {
+        let mut view = TextView::new("A short note.");
+        let mut fb = FrameBuffer565::new(320, 120);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        view.on_intent(NavIntent::Next);
+        assert_eq!(view.top_line(), 0);
+        assert_eq!(fb.pixel(Point::new(319, 0)), Default::default());
+        assert_eq!(view.chrome_contribution().unwrap().readout, None);
+    }
+
+    #[test]
+    fn long_text_shows_a_scrollbar_and_a_line_readout() {
+        let view = TextView::new(long_text(20));
+        let mut fb = FrameBuffer565::new(320, 120);
+        view.render(AREA, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(
+            fb.pixel(Point::new(319, 0)),
+            Palette::DARK.text_secondary,
+            "thumb at the top"
+        );
+        let readout = view.chrome_contribution().unwrap().readout.unwrap();
+        assert!(readout.starts_with("1-"), "{readout}");
+    }
+
+    #[test]
+    fn scrolling_changes_the_rendered_text() {
+        let mut view = rendered(&long_text(20));
+        let mut before = FrameBuffer565::new(320, 120);
+        view.render(AREA, &Theme::DARK, &mut before).unwrap();
+        view.on_intent(NavIntent::Next);
+        let mut after = FrameBuffer565::new(320, 120);
+        view.render(AREA, &Theme::DARK, &mut after).unwrap();
+        assert_ne!(
+            before.pixels().collect::<Vec<_>>(),
+            after.pixels().collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,6 +1,7 @@
-//! The M1 visual design language: a semantic color palette, per-role
-//! `u8g2-fonts` accessors, `open_iconic` icon codepoints, and two shared
-//! drawing primitives (a chip and a full-width selection block).
+//! The M1 visual design language: a semantic color palette and per-role
+//! `u8g2-fonts` accessors (bundled as a runtime-selectable [`Theme`]),
+//! `open_iconic` icon codepoints, and the shared drawing primitives (a
+//! chip, a full-width selection block, a scrollbar).
 //!
 //! Approved by Uma (UX) + Andreas for the T-Embed's 320x170 ST7789 color
 //! panel; see bead `ai-bitwarden-hw-key-0v8.8`. This module is the
@@ -29,89 +30,226 @@ use embedded_graphics::primitives::{
 };
 use embedded_graphics::Drawable;
 use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
+use u8g2_fonts::fonts;
 use u8g2_fonts::FontRenderer;
 
-/// The semantic color palette. Every color used by the render core should
-/// come from here, by role, rather than an ad-hoc `embedded_graphics`
-/// `WebColors` constant — that's what "centralizing the theme" means: a
-/// future palette tweak (or a light-mode variant, if one is ever wanted)
-/// is a one-file change instead of a codebase-wide grep.
-pub mod palette {
-    use embedded_graphics::pixelcolor::Rgb565;
-
-    /// Screen base fill (`#0B1120`): whatever isn't covered by a bar,
-    /// card, or row — the "void" behind all content.
-    pub const BACKGROUND: Rgb565 = Rgb565::new(1, 4, 4);
+/// A theme: the [`Palette`] and [`FontSet`] every widget draws with,
+/// handed to `Screen::render` and from there to each `Widget::render` —
+/// nothing in the render core reaches for a color or a text font any
+/// other way, which is what makes the theme switchable at runtime (the
+/// settings screen's "Theme" row) rather than a rebuild.
+///
+/// Layout follows the font set where text has to fit (list rows grow
+/// with [`FontSet::Large`], see `list::row_height`); the chrome bars keep
+/// their fixed heights in every theme, which is why [`FontSet`]'s title
+/// and hint fonts don't change size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub palette: Palette,
+    pub fonts: FontSet,
+}
+
+impl Theme {
+    /// The original M1 look (bead `ai-bitwarden-hw-key-0v8.8`), and the
+    /// default.
+    pub const DARK: Self = Self {
+        palette: Palette::DARK,
+        fonts: FontSet::Standard,
+    };
+    /// Dark text on light surfaces, for bright rooms.
+    pub const LIGHT: Self = Self {
+        palette: Palette::LIGHT,
+        fonts: FontSet::Standard,
+    };
+    /// Pure black and white with saturated accents, for low vision.
+    pub const HIGH_CONTRAST: Self = Self {
+        palette: Palette::HIGH_CONTRAST,
+        fonts: FontSet::Standard,
+    };
+    /// The dark palette with every content font a size or two up, for
+    /// readers who need bigger text more than they need a fourth row.
+    pub const LARGE_TEXT: Self = Self {
+        palette: Palette::DARK,
+        fonts: FontSet::Large,
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+/// The semantic color palette. Every color the render core draws comes
+/// from a theme's palette, by role, rather than an ad-hoc
+/// `embedded_graphics` `WebColors` constant — a palette tweak (or a whole
+/// new variant) is a change to the constants below, not a codebase-wide
+/// grep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// Screen base fill: whatever isn't covered by a bar, card, or row —
+    /// the "void" behind all content.
+    pub background: Rgb565,
     /// Chrome bars (title/hint) and an unselected row's implicit
-    /// backdrop (`#16213A`) — one step brighter than `BACKGROUND`.
-    pub const SURFACE: Rgb565 = Rgb565::new(3, 8, 7);
-    /// A selected row or focused field's backdrop (`#1E2A47`) — one step
-    /// brighter than `SURFACE`, giving focus a visible lift without a
-    /// literal raised/rounded card.
-    pub const SURFACE_ELEVATED: Rgb565 = Rgb565::new(4, 10, 9);
-    /// The brand fill color (`#175DDC`) — used for solid brand-colored
-    /// shapes (e.g. the initial chip's background), not text or icons.
-    pub const BRAND: Rgb565 = Rgb565::new(3, 23, 27);
-    /// The brighter brand accent (`#3B82F6`) — selection accent bars,
-    /// active/positive icon glyphs; reserved for things that should read
-    /// as "brand, but louder" than a plain `BRAND` fill.
-    pub const BRAND_BRIGHT: Rgb565 = Rgb565::new(7, 32, 30);
-    /// Primary text and glyph color (`#F5F7FA`) — credential names,
-    /// field values, chip initials.
-    pub const TEXT_PRIMARY: Rgb565 = Rgb565::new(30, 61, 30);
-    /// Secondary/muted text (`#9AA6BF`) — usernames, hints, field
-    /// labels, the sync counter.
-    pub const TEXT_SECONDARY: Rgb565 = Rgb565::new(19, 41, 23);
-    /// Hairline separators between chrome and content, or between rows
-    /// (`#22304F`).
-    pub const DIVIDER: Rgb565 = Rgb565::new(4, 12, 10);
-    /// A successful/positive status indicator (`#3DDC84`) — e.g. a
-    /// sync-ok dot.
-    pub const STATUS_SUCCESS: Rgb565 = Rgb565::new(7, 54, 16);
-    /// An error/negative status indicator (`#FF5964`).
-    pub const STATUS_ERROR: Rgb565 = Rgb565::new(31, 22, 12);
-    /// A caution/attention status indicator (`#FFB020`) — e.g. "secret
-    /// currently revealed."
-    pub const STATUS_WARNING: Rgb565 = Rgb565::new(31, 44, 4);
+    /// backdrop — one step removed from `background`.
+    pub surface: Rgb565,
+    /// A selected row or focused field's backdrop — one step further
+    /// than `surface`, giving focus a visible lift without a literal
+    /// raised/rounded card.
+    pub surface_elevated: Rgb565,
+    /// The brand fill color — used for solid brand-colored shapes (e.g.
+    /// the initial chip's background), not text or icons.
+    pub brand: Rgb565,
+    /// The louder brand accent — selection accent bars, active/positive
+    /// icon glyphs; reserved for things that should read as "brand, but
+    /// louder" than a plain `brand` fill.
+    pub brand_bright: Rgb565,
+    /// Text drawn *on* a `brand` fill (a chip's initial) — not
+    /// `text_primary`, which a light palette makes dark.
+    pub on_brand: Rgb565,
+    /// Primary text and glyph color — credential names, field values.
+    pub text_primary: Rgb565,
+    /// Secondary/muted text — usernames, hints, field labels, the sync
+    /// counter.
+    pub text_secondary: Rgb565,
+    /// Hairline separators between chrome and content, or between rows.
+    pub divider: Rgb565,
+    /// A successful/positive status indicator — e.g. a sync-ok dot.
+    pub status_success: Rgb565,
+    /// An error/negative status indicator.
+    pub status_error: Rgb565,
+    /// A caution/attention status indicator — e.g. "secret currently
+    /// revealed."
+    pub status_warning: Rgb565,
 }
 
-/// Per-role `u8g2-fonts` accessors. Each returns a fresh, independently
-/// configured [`FontRenderer`] (cheap — it's a thin wrapper over a static
-/// font table, not an allocation) with
+impl Palette {
+    /// The approved M1 palette for the T-Embed's 320x170 ST7789 panel
+    /// (hex values are the design-review spike's).
+    pub const DARK: Self = Self {
+        background: Rgb565::new(1, 4, 4),       // #0B1120
+        surface: Rgb565::new(3, 8, 7),          // #16213A
+        surface_elevated: Rgb565::new(4, 10, 9), // #1E2A47
+        brand: Rgb565::new(3, 23, 27),          // #175DDC
+        brand_bright: Rgb565::new(7, 32, 30),   // #3B82F6
+        on_brand: Rgb565::new(30, 61, 30),      // #F5F7FA
+        text_primary: Rgb565::new(30, 61, 30),  // #F5F7FA
+        text_secondary: Rgb565::new(19, 41, 23), // #9AA6BF
+        divider: Rgb565::new(4, 12, 10),        // #22304F
+        status_success: Rgb565::new(7, 54, 16), // #3DDC84
+        status_error: Rgb565::new(31, 22, 12),  // #FF5964
+        status_warning: Rgb565::new(31, 44, 4), // #FFB020
+    };
+
+    /// The dark palette's roles inverted onto light surfaces. Accents and
+    /// status colors are darkened so they still read against white.
+    pub const LIGHT: Self = Self {
+        background: Rgb565::new(29, 60, 30),      // #EEF1F6
+        surface: Rgb565::new(31, 63, 31),         // #FFFFFF
+        surface_elevated: Rgb565::new(27, 57, 30), // #DCE5F5
+        brand: Rgb565::new(3, 23, 27),            // #175DDC
+        brand_bright: Rgb565::new(3, 19, 23),     // #1A4FBF
+        on_brand: Rgb565::new(31, 63, 31),        // #FFFFFF
+        text_primary: Rgb565::new(1, 4, 4),       // #0B1120
+        text_secondary: Rgb565::new(9, 21, 14),   // #4A5670
+        divider: Rgb565::new(25, 52, 28),         // #C9D1E0
+        status_success: Rgb565::new(2, 34, 9),    // #178A4C
+        status_error: Rgb565::new(25, 10, 7),     // #C8283A
+        status_warning: Rgb565::new(21, 23, 0),   // #A85F00
+    };
+
+    /// White on black, with a saturated blue selection and a yellow
+    /// accent: every pairing a widget actually draws (text on any
+    /// surface, accent on selection) clears WCAG AAA's 7:1.
+    pub const HIGH_CONTRAST: Self = Self {
+        background: Rgb565::new(0, 0, 0),         // #000000
+        surface: Rgb565::new(0, 0, 0),            // #000000
+        surface_elevated: Rgb565::new(0, 12, 25), // #0033CC
+        brand: Rgb565::new(0, 20, 31),            // #0050FF
+        brand_bright: Rgb565::new(31, 63, 0),     // #FFFF00
+        on_brand: Rgb565::new(31, 63, 31),        // #FFFFFF
+        text_primary: Rgb565::new(31, 63, 31),    // #FFFFFF
+        text_secondary: Rgb565::new(24, 48, 24),  // #C0C0C0
+        divider: Rgb565::new(31, 63, 31),         // #FFFFFF
+        status_success: Rgb565::new(0, 63, 12),   // #00FF66
+        status_error: Rgb565::new(31, 16, 8),     // #FF4040
+        status_warning: Rgb565::new(31, 63, 0),   // #FFFF00
+    };
+}
+
+/// The text fonts, per role, in one of two size ladders. Each accessor
+/// returns a fresh, independently configured [`FontRenderer`] (cheap —
+/// it's a thin wrapper over a static font table, not an allocation) with
 /// [`with_ignore_unknown_chars`](FontRenderer::with_ignore_unknown_chars)
 /// set: credential names/usernames are arbitrary user data (could contain
 /// glyphs `helv`/`profont` don't cover), and this render core must never
 /// panic on unusual input — better to silently skip an unrenderable
 /// character than crash the render loop over it.
-pub mod font {
-    use u8g2_fonts::fonts;
-    use u8g2_fonts::FontRenderer;
+///
+/// Between [`Self::Standard`] and [`Self::Large`] only content text
+/// scales: [`Self::title`] and [`Self::hint`] are the same in both because
+/// the chrome bars they sit in are fixed-height (see `super::chrome`).
+/// [`Self::Compact`] is different in kind — it's never a user's choice,
+/// but what `super::layout::LayoutProfile` forces on panels too short for
+/// anything else, bars included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FontSet {
+    /// The approved M1 fonts.
+    #[default]
+    Standard,
+    /// Every content role a size or two up.
+    Large,
+    /// Fixed-cell X11 fonts for strip panels like the 128x32 SSD1306:
+    /// legible at 1 bit per pixel, and small enough for single-line list
+    /// rows (see [`Self::two_line_rows`]).
+    Compact,
+}
 
+impl FontSet {
     /// Screen/chrome titles (the title bar).
     #[must_use]
-    pub const fn title() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_helvB10_tf>().with_ignore_unknown_chars(true)
+    pub const fn title(self) -> FontRenderer {
+        match self {
+            Self::Standard | Self::Large => FontRenderer::new::<fonts::u8g2_font_helvB10_tf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_5x7_tf>(),
+        }
+        .with_ignore_unknown_chars(true)
     }
 
     /// A credential's name — the bold, primary line of a list row (and
-    /// the detail screen's title-bar text, via [`title`] instead — `name`
-    /// is specifically the *list row* weight/size).
+    /// the detail screen's title-bar text, via [`Self::title`] instead —
+    /// `name` is specifically the *list row* weight/size).
     #[must_use]
-    pub const fn name() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_helvB12_tf>().with_ignore_unknown_chars(true)
+    pub const fn name(self) -> FontRenderer {
+        match self {
+            Self::Standard => FontRenderer::new::<fonts::u8g2_font_helvB12_tf>(),
+            Self::Large => FontRenderer::new::<fonts::u8g2_font_helvB14_tf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_6x10_tf>(),
+        }
+        .with_ignore_unknown_chars(true)
     }
 
     /// A credential's username — the secondary line of a list row.
     #[must_use]
-    pub const fn username() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_helvR10_tf>().with_ignore_unknown_chars(true)
+    pub const fn username(self) -> FontRenderer {
+        match self {
+            Self::Standard => FontRenderer::new::<fonts::u8g2_font_helvR10_tf>(),
+            Self::Large => FontRenderer::new::<fonts::u8g2_font_helvR12_tf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_5x7_tf>(),
+        }
+        .with_ignore_unknown_chars(true)
     }
 
     /// A plain detail-field value (e.g. website/URI) — not the secret
-    /// field, which uses [`secret`] instead.
+    /// field, which uses [`Self::secret`] instead.
     #[must_use]
-    pub const fn value() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_helvR12_tf>().with_ignore_unknown_chars(true)
+    pub const fn value(self) -> FontRenderer {
+        match self {
+            Self::Standard => FontRenderer::new::<fonts::u8g2_font_helvR12_tf>(),
+            Self::Large => FontRenderer::new::<fonts::u8g2_font_helvR14_tf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_6x10_tf>(),
+        }
+        .with_ignore_unknown_chars(true)
     }
 
     /// The password/secret field's value. Monospaced (`profont`) so
@@ -119,8 +257,13 @@ pub mod font {
     /// width — a proportional font would make a masked secret's length
     /// visually leak information a monospaced mask doesn't.
     #[must_use]
-    pub const fn secret() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_profont17_mf>().with_ignore_unknown_chars(true)
+    pub const fn secret(self) -> FontRenderer {
+        match self {
+            Self::Standard => FontRenderer::new::<fonts::u8g2_font_profont17_mf>(),
+            Self::Large => FontRenderer::new::<fonts::u8g2_font_profont22_mf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_6x10_mf>(),
+        }
+        .with_ignore_unknown_chars(true)
     }
 
     /// A detail field's label (e.g. "Username"). Callers render the
@@ -128,8 +271,13 @@ pub mod font {
     /// the font/weight, per Uma's spec (`helvB08`, small caps-style
     /// label above each field).
     #[must_use]
-    pub const fn label() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_helvB08_tf>().with_ignore_unknown_chars(true)
+    pub const fn label(self) -> FontRenderer {
+        match self {
+            Self::Standard => FontRenderer::new::<fonts::u8g2_font_helvB08_tf>(),
+            Self::Large => FontRenderer::new::<fonts::u8g2_font_helvB10_tf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_5x7_tf>(),
+        }
+        .with_ignore_unknown_chars(true)
     }
 
     /// The hint bar's control-legend text. One size down from
@@ -138,9 +286,62 @@ pub mod font {
     /// so it can afford to be the smallest text on screen) — `helvR08`,
     /// the next `helv` size down that's still legible on this panel.
     #[must_use]
-    pub const fn hint() -> FontRenderer {
-        FontRenderer::new::<fonts::u8g2_font_helvR08_tf>().with_ignore_unknown_chars(true)
+    pub const fn hint(self) -> FontRenderer {
+        match self {
+            Self::Standard | Self::Large => FontRenderer::new::<fonts::u8g2_font_helvR08_tf>(),
+            Self::Compact => FontRenderer::new::<fonts::u8g2_font_4x6_tf>(),
+        }
+        .with_ignore_unknown_chars(true)
+    }
+
+    /// Worst-case pixel footprint (leading + ink height, including
+    /// descenders) of a single line rendered with [`Self::name`] from a
+    /// `VerticalPosition::Top`-anchored position — i.e. how much vertical
+    /// room a `render_aligned(.., VerticalPosition::Top, ..)` call at
+    /// y=0 actually occupies in the worst case.
+    ///
+    /// Measured, not computed: `u8g2-fonts` can't do it at compile time
+    /// (`FontRenderer::get_rendered_dimensions*` take `&self` and aren't
+    /// `const fn`), so each value was derived once with a throwaway probe
+    /// (`core/examples/dim_probe.rs`, run manually, not part of the
+    /// build) rendering `"gjpqy"` (all five ASCII descenders) and
+    /// hardcoded here. Using the worst case rather than the specific
+    /// string being drawn is what lets `list::row_height` guarantee every
+    /// possible name fits its row.
+    #[must_use]
+    pub const fn name_line_footprint(self) -> i32 {
+        match self {
+            Self::Standard => 17,
+            Self::Large => 19,
+            Self::Compact => 10,
+        }
+    }
+
+    /// [`Self::name_line_footprint`]'s counterpart for [`Self::username`].
+    #[must_use]
+    pub const fn username_line_footprint(self) -> i32 {
+        match self {
+            Self::Standard => 15,
+            Self::Large => 17,
+            Self::Compact => 8,
+        }
+    }
+
+    /// Whether list rows have room for a second (username) line and the
+    /// initial chip beside it. Only [`Self::Compact`]'s rows don't: on a
+    /// 32 px panel, two-line rows would mean one credential per screen.
+    #[must_use]
+    pub const fn two_line_rows(self) -> bool {
+        !matches!(self, Self::Compact)
     }
+}
+
+/// The `open_iconic` icon fonts. Unlike text, icons don't change with the
+/// theme — they're sized to the fixed-height chrome bars and row chips
+/// they sit in, not to reading distance.
+pub mod font {
+    use u8g2_fonts::fonts;
+    use u8g2_fonts::FontRenderer;
 
     /// `open_iconic` glyphs at 1x scale (roughly 8x8px) — for compact
     /// inline icons (e.g. a row's focus caret), and deliberately also the
@@ -215,7 +416,7 @@ pub const CHIP_CORNER_RADIUS: u32 = 4;
 /// Draws a brand-colored rounded-square "chip" filling `rect`, with
 /// `initial` centered in it on *both* axes.
 ///
-/// Uses [`font::name`] for the glyph (matching the design-review mockup),
+/// Uses [`FontSet::name`] for the glyph (matching the design-review mockup),
 /// but centers it by measuring the glyph's actual rendered ink
 /// bounding box via
 /// [`get_rendered_dimensions_aligned`](FontRenderer::get_rendered_dimensions_aligned)
@@ -232,9 +433,9 @@ pub const CHIP_CORNER_RADIUS: u32 = 4;
 /// sidesteps the whole line-metrics-vs-ink distinction.
 ///
 /// Falls back to `rect`'s geometric center (no visible ink to align) if
-/// `initial` has no glyph in [`font::name`] — this can't happen for the
+/// `initial` has no glyph in [`FontSet::name`] — this can't happen for the
 /// ASCII/Latin-1 initials `char::to_ascii_uppercase` produces, but
-/// `font::name` is configured to ignore unknown glyphs rather than error,
+/// `FontSet::name` is configured to ignore unknown glyphs rather than error,
 /// so this stays a graceful no-glyph-drawn case instead of a panic for a
 /// non-Latin initial.
 ///
@@ -243,15 +444,15 @@ pub const CHIP_CORNER_RADIUS: u32 = 4;
 /// Returns `Infallible`'s uninhabited variant in practice — see
 /// [`super::widget::Widget::render`]'s doc comment for why the `Result`
 /// return exists at all.
-pub fn draw_chip<D>(target: &mut D, rect: Rectangle, initial: char) -> Result<(), Infallible>
+pub fn draw_chip<D>(target: &mut D, rect: Rectangle, initial: char, theme: &Theme) -> Result<(), Infallible>
 where
     D: DrawTarget<Color = Rgb565, Error = Infallible>,
 {
     let radii = CornerRadiiBuilder::new().all(Size::new_equal(CHIP_CORNER_RADIUS)).build();
     RoundedRectangle::new(rect, radii)
-        .draw_styled(&PrimitiveStyle::with_fill(palette::BRAND), target)?;
+        .draw_styled(&PrimitiveStyle::with_fill(theme.palette.brand), target)?;
 
-    let glyph_font: FontRenderer = font::name();
+    let glyph_font: FontRenderer = theme.fonts.name();
     let mut buf = [0_u8; 4];
     let text: &str = initial.encode_utf8(&mut buf);
 
@@ -278,7 +479,7 @@ where
         render_pos,
         VerticalPosition::Top,
         HorizontalAlignment::Left,
-        FontColor::Transparent(palette::TEXT_PRIMARY),
+        FontColor::Transparent(theme.palette.on_brand),
         target,
     );
 
@@ -289,9 +490,9 @@ where
 pub const SELECTION_ACCENT_WIDTH: u32 = 4;
 
 /// Draws the shared "this is the selected/focused thing" visual across
-/// `area`: a full-`area`, edge-to-edge fill in [`palette::SURFACE_ELEVATED`],
+/// `area`: a full-`area`, edge-to-edge fill in [`Palette::surface_elevated`],
 /// then a [`SELECTION_ACCENT_WIDTH`]-px accent bar in
-/// [`palette::BRAND_BRIGHT`] along `area`'s left edge.
+/// [`Palette::brand_bright`] along `area`'s left edge.
 ///
 /// Replaces `render::widget::draw_focus_block` (this bead retires it):
 /// same shape (full-area fill + left accent bar, no inset/rounding/fake
@@ -313,43 +514,129 @@ pub const SELECTION_ACCENT_WIDTH: u32 = 4;
 /// Returns `Infallible`'s uninhabited variant in practice — see
 /// [`super::widget::Widget::render`]'s doc comment for why the `Result`
 /// return exists at all.
-pub fn draw_selection<D>(area: Rectangle, target: &mut D) -> Result<(), Infallible>
+pub fn draw_selection<D>(area: Rectangle, theme: &Theme, target: &mut D) -> Result<(), Infallible>
 where
     D: DrawTarget<Color = Rgb565, Error = Infallible>,
 {
-    area.into_styled(PrimitiveStyle::with_fill(palette::SURFACE_ELEVATED)).draw(target)?;
+    area.into_styled(PrimitiveStyle::with_fill(theme.palette.surface_elevated)).draw(target)?;
 
     let accent_width = SELECTION_ACCENT_WIDTH.min(area.size.width);
     let accent = Rectangle::new(area.top_left, Size::new(accent_width, area.size.height));
-    accent.into_styled(PrimitiveStyle::with_fill(palette::BRAND_BRIGHT)).draw(target)?;
+    accent.into_styled(PrimitiveStyle::with_fill(theme.palette.brand_bright)).draw(target)?;
 
     Ok(())
 }
 
+/// Width, in pixels, of the right-edge scrollbar [`draw_scrollbar`]
+/// draws. Scrolling widgets reserve it unconditionally (not just when
+/// their content overflows) so their text's right boundary doesn't shift
+/// depending on content length.
+pub const SCROLLBAR_WIDTH: u32 = 3;
+
+/// Minimum scrollbar thumb height, so very long content doesn't shrink the
+/// thumb to an invisible sliver.
+pub const MIN_SCROLLBAR_THUMB_HEIGHT: u32 = 4;
+
+/// Draws the shared right-edge scrollbar (a [`Palette::divider`] track
+/// and a proportional [`Palette::text_secondary`] thumb) along `area`'s
+/// right [`SCROLLBAR_WIDTH`] columns, for `content_height` px of content
+/// scrolled by `scroll` px within `area`'s height. A no-op if the content
+/// already fits — there's nothing to scroll, so no thumb (or even an empty
+/// track) is drawn.
+///
+/// Extracted from `CredentialListView` so every scrolling view (the list,
+/// `TextView`) shows the same scrollbar by construction.
+///
+/// # Errors
+///
+/// Returns `Infallible`'s uninhabited variant in practice — see
+/// [`super::widget::Widget::render`]'s doc comment for why the `Result`
+/// return exists at all.
+pub fn draw_scrollbar<D>(
+    area: Rectangle,
+    content_height: u32,
+    scroll: u32,
+    theme: &Theme,
+    target: &mut D,
+) -> Result<(), Infallible>
+where
+    D: DrawTarget<Color = Rgb565, Error = Infallible>,
+{
+    let viewport_height = area.size.height;
+    if viewport_height == 0 || content_height <= viewport_height {
+        return Ok(());
+    }
+
+    let track_x = area.top_left.x + area.size.width as i32 - SCROLLBAR_WIDTH as i32;
+
+    let track = Rectangle::new(Point::new(track_x, area.top_left.y), Size::new(SCROLLBAR_WIDTH, viewport_height));
+    track.into_styled(PrimitiveStyle::with_fill(theme.palette.divider)).draw(target)?;
+
+    let thumb_height = (u64::from(viewport_height) * u64::from(viewport_height) / u64::from(content_height))
+        .max(u64::from(MIN_SCROLLBAR_THUMB_HEIGHT)) as u32;
+    let thumb_height = thumb_height.min(viewport_height);
+
+    let max_thumb_top = viewport_height - thumb_height;
+    let scrollable_range = content_height - viewport_height;
+    let thumb_top = if max_thumb_top == 0 || scrollable_range == 0 {
+        0
+    } else {
+        (u64::from(scroll.min(scrollable_range)) * u64::from(max_thumb_top) / u64::from(scrollable_range)) as u32
+    };
+
+    let thumb = Rectangle::new(
+        Point::new(track_x, area.top_left.y + thumb_top as i32),
+        Size::new(SCROLLBAR_WIDTH, thumb_height),
+    );
+    thumb.into_styled(PrimitiveStyle::with_fill(theme.palette.text_secondary)).draw(target)
+}
+
+/// Fallback line height (px) used only if a font's metrics are somehow
+/// unavailable (`get_rendered_dimensions_aligned` returning `None` — see
+/// [`font`]'s module doc: this happens for a glyph with no coverage, not
+/// for the fixed ASCII probe string used here, but the render core must
+/// never panic on a font-metrics hiccup).
+const FALLBACK_LINE_HEIGHT: i32 = 16;
+
+/// Measures a font's worst-case single-line pixel footprint from a
+/// `VerticalPosition::Top`-anchored position, using the same "all five
+/// ASCII descenders (+ a capital, for ascent)" probe string convention
+/// [`FontSet::name_line_footprint`]'s doc comment describes — computed at runtime via `get_rendered_dimensions_aligned`
+/// rather than hardcoded from an offline probe, so it's correct for any
+/// font without a probe-per-font. Moved here from `CredentialDetailView`
+/// once `TextView` needed the same measurement.
+#[must_use]
+pub fn line_height(font: &FontRenderer) -> i32 {
+    font.get_rendered_dimensions_aligned("Agjpqy", Point::zero(), VerticalPosition::Top, HorizontalAlignment::Left)
+        .unwrap_or(None)
+        .map_or(FALLBACK_LINE_HEIGHT, |bbox| bbox.size.height as i32)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::render::FrameBuffer565;
+    use embedded_graphics::prelude::RgbColor;
 
     #[test]
     fn draw_selection_fills_the_full_area_and_paints_a_left_accent_bar() {
         let mut fb = FrameBuffer565::new(40, 20);
         let area = Rectangle::new(Point::new(0, 0), Size::new(40, 20));
-        draw_selection(area, &mut fb).unwrap();
+        draw_selection(area, &Theme::DARK, &mut fb).unwrap();
 
         // Left edge: the accent bar.
-        assert_eq!(fb.pixel(Point::new(0, 10)), palette::BRAND_BRIGHT);
+        assert_eq!(fb.pixel(Point::new(0, 10)), Palette::DARK.brand_bright);
         assert_eq!(
             fb.pixel(Point::new(SELECTION_ACCENT_WIDTH as i32 - 1, 10)),
-            palette::BRAND_BRIGHT
+            Palette::DARK.brand_bright
         );
         // Just past the accent bar, and the far right edge: the fill —
         // full width, no inset.
-        assert_eq!(fb.pixel(Point::new(SELECTION_ACCENT_WIDTH as i32, 10)), palette::SURFACE_ELEVATED);
-        assert_eq!(fb.pixel(Point::new(39, 10)), palette::SURFACE_ELEVATED);
+        assert_eq!(fb.pixel(Point::new(SELECTION_ACCENT_WIDTH as i32, 10)), Palette::DARK.surface_elevated);
+        assert_eq!(fb.pixel(Point::new(39, 10)), Palette::DARK.surface_elevated);
         // Full height, no vertical inset either.
-        assert_eq!(fb.pixel(Point::new(20, 0)), palette::SURFACE_ELEVATED);
-        assert_eq!(fb.pixel(Point::new(20, 19)), palette::SURFACE_ELEVATED);
+        assert_eq!(fb.pixel(Point::new(20, 0)), Palette::DARK.surface_elevated);
+        assert_eq!(fb.pixel(Point::new(20, 19)), Palette::DARK.surface_elevated);
     }
 
     #[test]
@@ -358,27 +645,27 @@ mod tests {
         let area = Rectangle::new(Point::new(0, 0), Size::new(2, 10));
         // Must not panic even though `area` is narrower than
         // `SELECTION_ACCENT_WIDTH`.
-        draw_selection(area, &mut fb).unwrap();
-        assert_eq!(fb.pixel(Point::new(0, 5)), palette::BRAND_BRIGHT);
-        assert_eq!(fb.pixel(Point::new(1, 5)), palette::BRAND_BRIGHT);
+        draw_selection(area, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixel(Point::new(0, 5)), Palette::DARK.brand_bright);
+        assert_eq!(fb.pixel(Point::new(1, 5)), Palette::DARK.brand_bright);
     }
 
     #[test]
     fn draw_chip_paints_brand_background_and_a_centered_glyph() {
         let mut fb = FrameBuffer565::new(30, 30);
         let rect = Rectangle::new(Point::new(4, 4), Size::new(22, 22));
-        draw_chip(&mut fb, rect, 'G').unwrap();
+        draw_chip(&mut fb, rect, 'G', &Theme::DARK).unwrap();
 
         // A corner (outside the rounded radius, outside the glyph) is the
         // brand fill.
-        assert_eq!(fb.pixel(Point::new(5, 5)), palette::BRAND);
-        // Somewhere in the chip drew primary-text-colored ink (the glyph)
+        assert_eq!(fb.pixel(Point::new(5, 5)), Palette::DARK.brand);
+        // Somewhere in the chip drew on-brand-colored ink (the glyph)
         // — proves a glyph was actually rasterized, not just the
         // background.
         let any_glyph_ink =
             (rect.top_left.x..rect.top_left.x + rect.size.width as i32).any(|x| {
                 (rect.top_left.y..rect.top_left.y + rect.size.height as i32)
-                    .any(|y| fb.pixel(Point::new(x, y)) == palette::TEXT_PRIMARY)
+                    .any(|y| fb.pixel(Point::new(x, y)) == Palette::DARK.on_brand)
             });
         assert!(any_glyph_ink, "draw_chip should have rasterized the initial's glyph ink");
     }
@@ -386,20 +673,20 @@ mod tests {
     #[test]
     fn draw_chip_centers_the_glyphs_ink_bounding_box_on_the_rects_center() {
         // Render 'G' via draw_chip, then independently recompute where
-        // font::name()'s *ink bounding box* for "G" landed, and assert its
+        // the name font's *ink bounding box* for "G" landed, and assert its
         // center matches rect.center() exactly. This is the specific
         // Andreas-flagged bug (naive Center/Center alignment centers on
         // font line-metrics, not glyph ink) this primitive exists to fix.
         let mut fb = FrameBuffer565::new(30, 30);
         let rect = Rectangle::new(Point::new(4, 4), Size::new(22, 22));
-        draw_chip(&mut fb, rect, 'G').unwrap();
+        draw_chip(&mut fb, rect, 'G', &Theme::DARK).unwrap();
 
         let mut min = Point::new(i32::MAX, i32::MAX);
         let mut max = Point::new(i32::MIN, i32::MIN);
         let mut found_any = false;
         for x in rect.top_left.x..rect.top_left.x + rect.size.width as i32 {
             for y in rect.top_left.y..rect.top_left.y + rect.size.height as i32 {
-                if fb.pixel(Point::new(x, y)) == palette::TEXT_PRIMARY {
+                if fb.pixel(Point::new(x, y)) == Palette::DARK.on_brand {
                     found_any = true;
                     min.x = min.x.min(x);
                     min.y = min.y.min(y);
@@ -423,4 +710,57 @@ mod tests {
             "chip glyph should be vertically centered: ink_center={ink_center:?} rect_center={rect_center:?}"
         );
     }
+
+    /// WCAG relative luminance of an `Rgb565`, each channel widened to
+    /// its 0..=1 fraction first.
+    fn luminance(color: Rgb565) -> f64 {
+        let linear = |value: u8, max: u8| {
+            let c = f64::from(value) / f64::from(max);
+            if c <= 0.039_28 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(color.r(), 31) + 0.7152 * linear(color.g(), 63) + 0.0722 * linear(color.b(), 31)
+    }
+
+    fn contrast(a: Rgb565, b: Rgb565) -> f64 {
+        let (la, lb) = (luminance(a), luminance(b));
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
+
+    #[test]
+    fn every_palette_keeps_text_legible_on_every_surface_it_sits_on() {
+        for (name, palette, minimum) in [
+            ("dark", Palette::DARK, 4.5),
+            ("light", Palette::LIGHT, 4.5),
+            ("high contrast", Palette::HIGH_CONTRAST, 7.0),
+        ] {
+            for surface in [palette.background, palette.surface, palette.surface_elevated] {
+                let ratio = contrast(palette.text_primary, surface);
+                assert!(ratio >= minimum, "{name}: primary text at {ratio:.1}:1");
+            }
+            let ratio = contrast(palette.on_brand, palette.brand);
+            assert!(ratio >= 4.5, "{name}: chip initial at {ratio:.1}:1");
+        }
+    }
+
+    #[test]
+    fn draw_scrollbar_is_a_noop_when_the_content_fits() {
+        let mut fb = FrameBuffer565::new(20, 40);
+        let area = Rectangle::new(Point::new(0, 0), Size::new(20, 40));
+        draw_scrollbar(area, 40, 0, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixel(Point::new(19, 0)), Rgb565::default());
+    }
+
+    #[test]
+    fn draw_scrollbar_moves_the_thumb_to_the_bottom_at_full_scroll() {
+        let mut fb = FrameBuffer565::new(20, 40);
+        let area = Rectangle::new(Point::new(0, 0), Size::new(20, 40));
+        draw_scrollbar(area, 160, 120, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixel(Point::new(19, 39)), Palette::DARK.text_secondary);
+        assert_eq!(fb.pixel(Point::new(19, 0)), Palette::DARK.divider);
+        assert_eq!(fb.pixel(Point::new(16, 39)), Rgb565::default(), "only the rightmost columns");
+    }
 }
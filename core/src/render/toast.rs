@@ -0,0 +1,265 @@
+//! Toasts: short, transient messages ("Sync failed", "Copied") drawn over
+//! the hint bar, above everything else the `Navigator` renders — screen,
+//! dim layer and dialog alike. A toast never takes input; it just expires.
+//!
+//! Expiry runs off timestamps the caller reads from the injected `Clock`
+//! (`crate::platform::Clock`) and hands to [`ToastQueue::tick`] — the core
+//! never reads the time itself, per the presentation-surface ADR. Toasts
+//! are shown one at a time, oldest first, and a toast's display time only
+//! starts counting on the first tick it's at the front of the queue, so a
+//! burst of three errors reads as three messages in turn rather than three
+//! that expire together behind each other.
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use embedded_graphics::prelude::{Point, Primitive, Size};
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::Drawable;
+use u8g2_fonts::types::{FontColor, HorizontalAlignment, VerticalPosition};
+
+use super::framebuffer::FrameBuffer565;
+use super::screen::status_color;
+use super::semantics::{Role, SemanticNode};
+use super::theme::{Theme, SELECTION_ACCENT_WIDTH};
+use super::widget::ChromeStatus;
+
+/// How long a toast stays up unless [`Toast::with_duration`] says
+/// otherwise.
+pub const DEFAULT_TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// Left margin (px) for toast text, past the status accent bar — matches
+/// the hint text it covers, so the swap doesn't make the text jump.
+const TOAST_TEXT_MARGIN: i32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub message: String,
+    /// The accent bar's color, by meaning — the same vocabulary (and the
+    /// same palette mapping) as the title bar's status dot.
+    pub status: ChromeStatus,
+    pub duration: Duration,
+}
+
+impl Toast {
+    /// A neutral toast shown for [`DEFAULT_TOAST_DURATION`].
+    #[must_use]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status: ChromeStatus::Neutral,
+            duration: DEFAULT_TOAST_DURATION,
+        }
+    }
+
+    #[must_use]
+    pub fn with_status(mut self, status: ChromeStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    #[must_use]
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// This toast as a [`SemanticNode`]: the message, valued with what its
+    /// accent bar means (`"success"`, `"error"` or `"neutral"`). Never
+    /// focused — a toast takes no input.
+    #[must_use]
+    pub fn semantics(&self) -> SemanticNode {
+        let status = match self.status {
+            ChromeStatus::Success => "success",
+            ChromeStatus::Error => "error",
+            ChromeStatus::Neutral => "neutral",
+        };
+        SemanticNode::new(Role::Toast, self.message.as_str()).with_value(status)
+    }
+}
+
+/// The pending toasts, front first, plus when the front one expires.
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    queue: VecDeque<Toast>,
+    /// `None` until the front toast has seen its first [`Self::tick`].
+    shown_until: Option<Instant>,
+}
+
+impl ToastQueue {
+    pub fn push(&mut self, toast: Toast) {
+        self.queue.push_back(toast);
+    }
+
+    /// The toast currently on screen, if any.
+    #[must_use]
+    pub fn current(&self) -> Option<&Toast> {
+        self.queue.front()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Advances expiry to `now`: starts the front toast's timer if it
+    /// hasn't started, and drops every toast whose time is up (starting
+    /// the next one's timer from `now`). Returns whether the visible toast
+    /// changed, i.e. whether the screen needs re-rendering.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let mut changed = false;
+        while let Some(front) = self.queue.front() {
+            match self.shown_until {
+                None => {
+                    self.shown_until = Some(now + front.duration);
+                }
+                Some(until) if now >= until => {
+                    self.queue.pop_front();
+                    self.shown_until = None;
+                    changed = true;
+                }
+                Some(_) => break,
+            }
+        }
+        changed
+    }
+
+    /// Draws the current toast, if any, filling `area` (the hint bar): an
+    /// elevated fill, a status-colored accent bar on the left, and the
+    /// message clipped to `area`.
+    ///
+    /// # Errors
+    ///
+    /// Never, in practice — see [`super::widget::Widget::render`].
+    pub fn render(
+        &self,
+        area: Rectangle,
+        theme: &Theme,
+        target: &mut FrameBuffer565,
+    ) -> Result<(), Infallible> {
+        let Some(toast) = self.current() else {
+            return Ok(());
+        };
+        if area.size.height == 0 {
+            return Ok(());
+        }
+
+        area.into_styled(PrimitiveStyle::with_fill(theme.palette.surface_elevated))
+            .draw(target)?;
+        let accent = Rectangle::new(
+            area.top_left,
+            Size::new(
+                SELECTION_ACCENT_WIDTH.min(area.size.width),
+                area.size.height,
+            ),
+        );
+        accent
+            .into_styled(PrimitiveStyle::with_fill(status_color(toast.status, theme)))
+            .draw(target)?;
+
+        let mid_y = area.top_left.y + area.size.height as i32 / 2;
+        let _ = theme.fonts.hint().render_aligned(
+            toast.message.as_str(),
+            Point::new(area.top_left.x + TOAST_TEXT_MARGIN, mid_y),
+            VerticalPosition::Center,
+            HorizontalAlignment::Left,
+            FontColor::Transparent(theme.palette.text_primary),
+            &mut target.clipped(&area),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::theme::Palette;
+
+    #[test]
+    fn a_toast_expires_its_duration_after_its_first_tick() {
+        let start = Instant::now();
+        let mut toasts = ToastQueue::default();
+        toasts.push(Toast::new("Copied").with_duration(Duration::from_secs(1)));
+
+        assert!(
+            !toasts.tick(start),
+            "starting the timer doesn't change what's shown"
+        );
+        assert!(!toasts.tick(start + Duration::from_millis(999)));
+        assert_eq!(toasts.current().map(|t| t.message.as_str()), Some("Copied"));
+
+        assert!(toasts.tick(start + Duration::from_secs(1)));
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn queued_toasts_are_shown_one_after_another() {
+        let start = Instant::now();
+        let mut toasts = ToastQueue::default();
+        toasts.push(Toast::new("first").with_duration(Duration::from_secs(1)));
+        toasts.push(Toast::new("second").with_duration(Duration::from_secs(1)));
+        toasts.tick(start);
+
+        assert!(toasts.tick(start + Duration::from_secs(1)));
+        assert_eq!(toasts.current().map(|t| t.message.as_str()), Some("second"));
+        // The second toast's second starts when it came to the front.
+        assert!(!toasts.tick(start + Duration::from_millis(1999)));
+        assert!(toasts.tick(start + Duration::from_secs(2)));
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn a_late_tick_only_expires_the_front_toast_the_next_still_gets_its_full_time() {
+        let start = Instant::now();
+        let mut toasts = ToastQueue::default();
+        toasts.push(Toast::new("a").with_duration(Duration::from_secs(1)));
+        toasts.push(Toast::new("b").with_duration(Duration::from_secs(1)));
+        toasts.tick(start);
+
+        assert!(toasts.tick(start + Duration::from_secs(10)));
+        assert_eq!(toasts.current().map(|t| t.message.as_str()), Some("b"));
+        assert!(!toasts.tick(start + Duration::from_millis(10_500)));
+    }
+
+    #[test]
+    fn zero_duration_toasts_are_dropped_on_their_first_tick() {
+        let mut toasts = ToastQueue::default();
+        toasts.push(Toast::new("a").with_duration(Duration::ZERO));
+        toasts.push(Toast::new("b").with_duration(Duration::ZERO));
+        toasts.push(Toast::new("c"));
+
+        assert!(toasts.tick(Instant::now()));
+        assert_eq!(toasts.current().map(|t| t.message.as_str()), Some("c"));
+        assert_eq!(toasts.len(), 1);
+    }
+
+    #[test]
+    fn render_fills_the_area_with_a_status_colored_accent() {
+        let mut fb = FrameBuffer565::new(100, 40);
+        let area = Rectangle::new(Point::new(0, 22), Size::new(100, 18));
+        let mut toasts = ToastQueue::default();
+        toasts.render(area, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(
+            fb.pixel(Point::new(90, 30)),
+            Default::default(),
+            "nothing queued, nothing drawn"
+        );
+
+        toasts.push(Toast::new("Sync failed").with_status(ChromeStatus::Error));
+        toasts.render(area, &Theme::DARK, &mut fb).unwrap();
+        assert_eq!(fb.pixel(Point::new(0, 30)), Palette::DARK.status_error);
+        assert_eq!(fb.pixel(Point::new(90, 30)), Palette::DARK.surface_elevated);
+        assert_eq!(
+            fb.pixel(Point::new(90, 10)),
+            Default::default(),
+            "outside the area is untouched"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! The retained-mode `Widget` trait and the two return-value vocabularies
-//! it uses: `Action` (what the navigation stack should do next) and
-//! `FocusEvent` (what happened to a widget's focus state).
+//! it uses: `Action` (what the navigation stack, or the modal/toast layers
+//! above it, should do next) and `FocusEvent` (what happened to a widget's
+//! focus state).
 //!
 //! Salvaged concepts, reimplemented cleanly on `embedded-graphics` (see
 //! `.planning/decisions/2026-08-11-ui-framework-reuse-vs-rewrite.md`):
@@ -21,14 +22,19 @@
 //! `area` it was handed, per `DrawTargetExt::clipped()`.
 
 use std::convert::Infallible;
+use std::time::Instant;
 
 use embedded_graphics::prelude::Size;
 use embedded_graphics::primitives::Rectangle;
 
 use crate::input::NavIntent;
 
+use super::dialog::Dialog;
 use super::framebuffer::FrameBuffer565;
 use super::screen::Screen;
+use super::semantics::SemanticNode;
+use super::theme::Theme;
+use super::toast::Toast;
 
 /// High-level focus state transitions, decoupled from whatever transport
 /// triggered them (encoder, keyboard, headless HTTP injection — see
@@ -65,6 +71,14 @@ pub enum Action {
     /// the frozen `Action` shape so the two intents don't have to be
     /// conflated if a future widget needs to distinguish them.
     Back,
+    /// Open a modal [`Dialog`] over the current screen. The screen stays
+    /// where it is (dimmed) and receives no input until the dialog closes;
+    /// see `Navigator::dispatch`.
+    ShowModal(Dialog),
+    /// Queue a transient [`Toast`] message over the hint bar. Never takes
+    /// input and never blocks it; it expires on its own (see
+    /// `Navigator::tick`).
+    ShowToast(Toast),
     /// No navigation-stack action.
     #[default]
     None,
@@ -73,15 +87,16 @@ pub enum Action {
 /// A status-dot color a [`ChromeContribution`] can ask the chrome to paint
 /// in the title bar — semantic (what the status *means*), not a raw
 /// `Rgb565`, so the mapping to an actual palette color lives in one place
-/// ([`super::screen::Screen::render`]) instead of every widget picking its
-/// own shade of green/red.
+/// (`super::screen::status_color`, shared by the title bar's status dot
+/// and toasts) instead of every widget picking its own shade of
+/// green/red.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChromeStatus {
     /// Everything's fine (e.g. `VaultStore`'s last sync succeeded with
-    /// items) — rendered in [`super::theme::palette::STATUS_SUCCESS`].
+    /// items) — rendered in [`super::theme::Palette::status_success`].
     Success,
     /// Something's wrong (e.g. the last sync failed) — rendered in
-    /// [`super::theme::palette::STATUS_ERROR`].
+    /// [`super::theme::Palette::status_error`].
     Error,
     /// Neither good nor bad news yet (e.g. no sync has run, or it
     /// succeeded with an empty vault) — rendered in a muted neutral color,
@@ -129,8 +144,10 @@ pub trait Widget {
     /// Screens use this to stack widgets vertically in the content region
     /// (see `Screen::render`); a widget is free to request less than
     /// `constraints` (e.g. a single-line label) or all of it (e.g. a list
-    /// that should fill the remaining content area).
-    fn measure(&self, constraints: Size) -> Size;
+    /// that should fill the remaining content area). `theme` is the one
+    /// the widget is about to be rendered in, for widgets whose size
+    /// follows their text.
+    fn measure(&self, constraints: Size, theme: &Theme) -> Size;
 
     /// Draws into `target`, constrained to `area`. Implementations that
     /// need to guard against overdraw (text overflow, an oversized row)
@@ -139,6 +156,10 @@ pub trait Widget {
     /// clipping mechanism the old character-skip marquee code is retired
     /// in favor of.
     ///
+    /// Every color and text font comes from `theme` (see
+    /// [`Theme`]) — never a constant — so switching themes repaints the
+    /// widget correctly on the very next frame.
+    ///
     /// # Errors
     ///
     /// Returns `Infallible`'s uninhabited variant in practice: the core's
@@ -146,7 +167,7 @@ pub trait Widget {
     /// return exists only to match `Drawable`/`DrawTarget`'s signature so
     /// widget impls can use `?` freely when calling into embedded-graphics
     /// primitives.
-    fn render(&self, area: Rectangle, target: &mut FrameBuffer565) -> Result<(), Infallible>;
+    fn render(&self, area: Rectangle, theme: &Theme, target: &mut FrameBuffer565) -> Result<(), Infallible>;
 
     /// Whether this widget can receive focus. Defaults to `false` (e.g.
     /// static labels, dividers).
@@ -181,4 +202,27 @@ pub trait Widget {
     fn chrome_contribution(&self) -> Option<ChromeContribution> {
         None
     }
+
+    /// What this widget shows, as a [`SemanticNode`]: its role, label and
+    /// value, with whichever of its children (rows, fields, buttons) is
+    /// currently selected marked `selected`. Read live on every call, like
+    /// [`Self::chrome_contribution`]. Focus isn't the widget's to report —
+    /// the screen applies it (see `super::semantics`'s module doc). A
+    /// secret value must come through masked unless it's revealed on
+    /// screen. Defaults to `None`, which leaves the widget out of the tree;
+    /// every widget that shows content should describe it.
+    fn semantics(&self) -> Option<SemanticNode> {
+        None
+    }
+
+    /// Advances any time-driven state (a label marquee) to `now`, read
+    /// from the platform's `Clock` by `App::tick` — widgets never read the
+    /// time themselves. Only called on the focused widget of the current
+    /// screen (see `Screen::tick`). Returns whether anything visible
+    /// changed, which is what decides whether the frame is dirty; a widget
+    /// with nothing animating must return `false` so idle frames stay
+    /// free. Defaults to `false`.
+    fn tick(&mut self, _now: Instant) -> bool {
+        false
+    }
 }
@@ -10,11 +10,14 @@
 //!     let intents = input.poll();
 //!     app.handle_input(intents);
 //!     app.step(sync);
+//!     app.tick(clock.now());
+//!     app.persist_settings(storage, clock.now());
+//!     display.set_brightness(app.display_brightness()); // when it changes
 //!     if app.dirty() {
-//!         let fb = app.render();
-//!         display.flush(&fb);
+//!         let (fb, damage) = app.render_with_damage();
+//!         display.flush(&fb, &damage);
 //!     }
-//!     sleep(frame_budget - elapsed);
+//!     clock.sleep_until(frame_start + frame_budget);
 //! }
 //! ```
 //!
@@ -24,7 +27,18 @@
 //! cadence but skips the expensive part entirely. The off-by-default
 //! `frame-timing` feature (bead ai-bitwarden-hw-key-ego) logs a rolling
 //! average of render/flush durations for exactly the frames that *do*
-//! take this path — see [`FrameTiming`].
+//! take this path — see [`FrameTiming`]. Within a dirty frame, `flush`
+//! only gets the regions whose pixels actually changed (see
+//! `FrameBuffer565::take_damage`), so a surface that can address part of
+//! its panel sends a moved selection, not the whole screen.
+//!
+//! Settings are restored from `Storage` once, before the first iteration.
+//!
+//! One iteration minus the sleep is [`FrameStepper::step`], public so a
+//! caller that owns time itself — the emulator's scenario runner, which
+//! advances a virtual clock between frames and inspects the [`App`]
+//! between them — runs exactly the frames `run` would, without a
+//! `should_continue` closure that would have to borrow the app.
 //!
 //! # Why `should_continue` instead of an unconditional `loop`
 //!
@@ -39,19 +53,24 @@
 //! predicate and lets each binary decide what "keep going" means for it.
 //! The firmware binary just passes `|| true`.
 //!
-//! # Why sleeping is `std::thread::sleep`, not a `Clock` method
+//! # Why sleeping is a `Clock` method
 //!
-//! `Clock` (frozen in W1, see `crate::platform`) only exposes `now()`, not
-//! a sleep primitive — deliberately: the trait models *reading* wall-clock
-//! time, not scheduling. `std::thread::sleep` is available on both targets
-//! today (the same `std`-on-ESP-IDF support that makes `Instant` available
-//! per `crate::platform`'s doc comment covers `thread::sleep` too), so
-//! there is no portability reason to route it through an injected trait.
+//! `Clock` (frozen in W1, see `crate::platform`) started out exposing only
+//! `now()`, and the loop slept with `std::thread::sleep` — available on
+//! both targets, so there was no portability reason to inject it. What
+//! that couldn't do is let time itself be injected: with a virtual clock
+//! the app saw simulated time while the loop still paced itself by real
+//! time, so a test of a 30-second timeout still took 30 seconds. Waiting
+//! out the frame is therefore [`Clock::sleep_until`] the frame's deadline,
+//! whose default is the same `thread::sleep` as before (so the firmware
+//! and the real-time host clocks are unchanged), and which a virtual clock
+//! implements by parking the loop until whoever owns time moves it — see
+//! the emulator's `VirtualClock` and its `POST /api/time/advance`.
 
 use std::time::Duration;
 
 use crate::app::App;
-use crate::platform::{Clock, DisplaySurface, InputSource, Platform};
+use crate::platform::{Clock, DisplaySurface, InputSource, Platform, Storage};
 use crate::sync_source::SyncSource;
 
 /// Rolling-average frame-timing accumulator, active only behind the
@@ -151,49 +170,62 @@ impl FrameTiming {
     }
 }
 
-/// Runs the app loop against `platform` until `should_continue` returns
-/// `false`. `frame_budget` is the target time per iteration (input poll +
-/// app step + render + flush); if an iteration finishes early, the
-/// remainder of the budget is spent asleep so the loop doesn't spin.
-///
-/// Takes `platform`/`app`/`sync` by `&mut` (rather than by value) so
-/// callers retain ownership after `run` returns — e.g. a headless caller
-/// that wants to encode a PNG from its concrete `HeadlessSurface` once the
-/// loop stops.
-///
-/// `<P::Display as DisplaySurface>::Error: Debug` (bead
-/// ai-bitwarden-hw-key-mqk) is required so a persistently-failing
-/// `flush` can be logged (see [`FlushErrorTracker`]) — every concrete
-/// `DisplaySurface` in this codebase already satisfies this (firmware's
-/// `St7789SurfaceError` derives `Debug`; the emulator surfaces use
-/// `Infallible`, which is `Debug`), so this is not expected to be a
-/// breaking bound for any real caller.
-pub fn run<P: Platform, S: SyncSource>(
-    platform: &mut P,
-    app: &mut App,
-    sync: &mut S,
-    frame_budget: Duration,
-    mut should_continue: impl FnMut() -> bool,
-) where
-    S::Error: std::fmt::Display,
-    <P::Display as DisplaySurface>::Error: core::fmt::Debug,
-{
+/// The loop state that outlives a single iteration of [`run`] — the
+/// flush-error rate limiter, the backlight level last set and (behind
+/// `frame-timing`) the timing accumulator — with the iteration itself as
+/// [`FrameStepper::step`].
+pub struct FrameStepper {
     #[cfg(feature = "frame-timing")]
-    let mut frame_timing = FrameTiming::new();
-    let mut flush_errors = FlushErrorTracker::default();
+    frame_timing: FrameTiming,
+    flush_errors: FlushErrorTracker,
+    /// The backlight level last handed to the display, so it's only set on
+    /// a change. `None` forces the first step to set it.
+    brightness: Option<u8>,
+}
 
-    while should_continue() {
-        let frame_start = platform.clock().now();
+impl FrameStepper {
+    /// Restores `app`'s settings from `platform`'s storage — what [`run`]
+    /// does once before its first iteration — and returns a stepper ready
+    /// for the first frame.
+    #[must_use]
+    pub fn new<P: Platform>(platform: &mut P, app: &mut App) -> Self {
+        app.restore_settings(platform.storage());
+        Self {
+            #[cfg(feature = "frame-timing")]
+            frame_timing: FrameTiming::new(),
+            flush_errors: FlushErrorTracker::default(),
+            brightness: None,
+        }
+    }
 
+    /// Runs one frame: polls input, steps sync, ticks and persists at the
+    /// platform clock's `now`, updates the backlight, and renders + flushes
+    /// if anything changed. Never sleeps — pacing is the caller's job (see
+    /// [`run`]).
+    pub fn step<P: Platform, S: SyncSource>(&mut self, platform: &mut P, app: &mut App, sync: &mut S)
+    where
+        S::Error: std::fmt::Display,
+        <P::Display as DisplaySurface>::Error: core::fmt::Debug,
+        <P::Storage as Storage>::Error: core::fmt::Debug,
+    {
         let intents = platform.input().poll();
         app.handle_input(intents);
         app.step(sync);
+        app.tick(platform.clock().now());
+        let now = platform.clock().now();
+        app.persist_settings(platform.storage(), now);
+
+        let wanted = app.display_brightness();
+        if self.brightness != Some(wanted) {
+            platform.display().set_brightness(wanted);
+            self.brightness = Some(wanted);
+        }
 
         if app.dirty() {
             #[cfg(feature = "frame-timing")]
             let render_start = platform.clock().now();
 
-            let framebuffer = app.render();
+            let (framebuffer, damage) = app.render_with_damage();
 
             #[cfg(feature = "frame-timing")]
             let render_end = platform.clock().now();
@@ -207,25 +239,60 @@ pub fn run<P: Platform, S: SyncSource>(
             // either: `FlushErrorTracker` makes a persistent failure
             // visible over serial (rate-limited) instead of looking like
             // an inexplicable frozen screen (bead ai-bitwarden-hw-key-mqk).
-            match platform.display().flush(framebuffer) {
-                Ok(()) => flush_errors.on_ok(),
-                Err(error) => flush_errors.on_err(&error),
+            match platform.display().flush(framebuffer, &damage) {
+                Ok(()) => self.flush_errors.on_ok(),
+                Err(error) => self.flush_errors.on_err(&error),
             }
 
             #[cfg(feature = "frame-timing")]
             {
                 let flush_end = platform.clock().now();
-                frame_timing.record(
+                self.frame_timing.record(
                     render_end.saturating_duration_since(render_start),
                     flush_end.saturating_duration_since(render_end),
                 );
             }
         }
+    }
+}
 
-        let elapsed = platform.clock().now().saturating_duration_since(frame_start);
-        if let Some(remaining) = frame_budget.checked_sub(elapsed) {
-            std::thread::sleep(remaining);
-        }
+/// Runs the app loop against `platform` until `should_continue` returns
+/// `false`. `frame_budget` is the target time per iteration (input poll +
+/// app step + render + flush); if an iteration finishes early, the
+/// remainder of the budget is spent in the platform clock's
+/// [`Clock::sleep_until`] so the loop doesn't spin.
+///
+/// Takes `platform`/`app`/`sync` by `&mut` (rather than by value) so
+/// callers retain ownership after `run` returns — e.g. a headless caller
+/// that wants to encode a PNG from its concrete `HeadlessSurface` once the
+/// loop stops.
+///
+/// `<P::Display as DisplaySurface>::Error: Debug` (bead
+/// ai-bitwarden-hw-key-mqk) is required so a persistently-failing
+/// `flush` can be logged (see [`FlushErrorTracker`]) — every concrete
+/// `DisplaySurface` in this codebase already satisfies this (firmware's
+/// `St7789SurfaceError` derives `Debug`; the emulator surfaces use
+/// `Infallible`, which is `Debug`), so this is not expected to be a
+/// breaking bound for any real caller. The same goes for
+/// `<P::Storage as Storage>::Error: Debug`, needed to log a failed
+/// settings save (see [`App::persist_settings`]).
+pub fn run<P: Platform, S: SyncSource>(
+    platform: &mut P,
+    app: &mut App,
+    sync: &mut S,
+    frame_budget: Duration,
+    mut should_continue: impl FnMut() -> bool,
+) where
+    S::Error: std::fmt::Display,
+    <P::Display as DisplaySurface>::Error: core::fmt::Debug,
+    <P::Storage as Storage>::Error: core::fmt::Debug,
+{
+    let mut stepper = FrameStepper::new(platform, app);
+
+    while should_continue() {
+        let frame_start = platform.clock().now();
+        stepper.step(platform, app, sync);
+        platform.clock().sleep_until(frame_start + frame_budget);
     }
 }
 
@@ -235,7 +302,8 @@ mod tests {
     use crate::input::NavIntent;
     use crate::platform::FrameBuffer565;
     use crate::vault_item::VaultItem;
-    use std::cell::RefCell;
+    use embedded_graphics::primitives::Rectangle;
+    use std::cell::{Cell, RefCell};
     use std::convert::Infallible;
     use std::rc::Rc;
     use std::time::Instant;
@@ -246,7 +314,7 @@ mod tests {
     }
     impl DisplaySurface for StubDisplay {
         type Error = Infallible;
-        fn flush(&mut self, _framebuffer: &FrameBuffer565) -> Result<(), Self::Error> {
+        fn flush(&mut self, _framebuffer: &FrameBuffer565, _damage: &[Rectangle]) -> Result<(), Self::Error> {
             *self.flush_count.borrow_mut() += 1;
             Ok(())
         }
@@ -266,7 +334,7 @@ mod tests {
     struct FailingStubDisplay;
     impl DisplaySurface for FailingStubDisplay {
         type Error = StubFlushError;
-        fn flush(&mut self, _framebuffer: &FrameBuffer565) -> Result<(), Self::Error> {
+        fn flush(&mut self, _framebuffer: &FrameBuffer565, _damage: &[Rectangle]) -> Result<(), Self::Error> {
             Err(StubFlushError)
         }
     }
@@ -292,6 +360,9 @@ mod tests {
         fn set(&mut self, _key: &str, _value: Vec<u8>) -> Result<(), Self::Error> {
             Ok(())
         }
+        fn remove(&mut self, _key: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
     }
 
     #[derive(Default, Clone, Copy)]
@@ -302,16 +373,16 @@ mod tests {
         }
     }
 
-    struct StubPlatform {
+    struct StubPlatform<C = StubClock> {
         display: StubDisplay,
         input: QueuedInput,
-        clock: StubClock,
+        clock: C,
         storage: StubStorage,
     }
-    impl Platform for StubPlatform {
+    impl<C: Clock> Platform for StubPlatform<C> {
         type Display = StubDisplay;
         type Input = QueuedInput;
-        type Clock = StubClock;
+        type Clock = C;
         type Storage = StubStorage;
 
         fn display(&mut self) -> &mut Self::Display {
@@ -359,6 +430,23 @@ mod tests {
         }
     }
 
+    /// A clock that only moves when the loop sleeps on it, jumping
+    /// straight to the deadline and recording it — virtual time in its
+    /// simplest form.
+    struct SteppedClock {
+        now: Cell<Instant>,
+        deadlines: RefCell<Vec<Instant>>,
+    }
+    impl Clock for SteppedClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+        fn sleep_until(&self, deadline: Instant) {
+            self.deadlines.borrow_mut().push(deadline);
+            self.now.set(self.now.get().max(deadline));
+        }
+    }
+
     struct EmptySyncSource;
     impl SyncSource for EmptySyncSource {
         type Error = Infallible;
@@ -403,6 +491,40 @@ mod tests {
         assert_eq!(iterations, ITERATIONS + 1, "should_continue is checked once more after the last real iteration");
     }
 
+    #[test]
+    fn a_frame_stepper_runs_single_frames_with_the_app_reachable_in_between() {
+        let flush_count = Rc::new(RefCell::new(0));
+        let mut platform = StubPlatform {
+            display: StubDisplay { flush_count: Rc::clone(&flush_count) },
+            input: QueuedInput(vec![Vec::new(), vec![NavIntent::Next]]),
+            clock: StubClock,
+            storage: StubStorage,
+        };
+        let items = vec![
+            VaultItem { id: Uuid::new_v4(), name: "a".into(), username: "a".into(), password: String::new(), uri: None, notes: None },
+            VaultItem { id: Uuid::new_v4(), name: "b".into(), username: "b".into(), password: String::new(), uri: None, notes: None },
+        ];
+        // Keeps answering with the same two items, so the readout has
+        // something to count after `App::step` applies each sync.
+        struct FixedSyncSource(Vec<VaultItem>);
+        impl SyncSource for FixedSyncSource {
+            type Error = Infallible;
+            fn sync(&mut self) -> Result<Vec<VaultItem>, Self::Error> {
+                Ok(self.0.clone())
+            }
+        }
+        let mut app = App::new(320, 170, items.clone());
+        let mut sync = FixedSyncSource(items);
+
+        let mut stepper = FrameStepper::new(&mut platform, &mut app);
+        stepper.step(&mut platform, &mut app, &mut sync);
+        assert_eq!((*flush_count.borrow(), app.chrome().readout), (1, Some("1 / 2".to_string())));
+        stepper.step(&mut platform, &mut app, &mut sync);
+        assert_eq!((*flush_count.borrow(), app.chrome().readout), (2, Some("2 / 2".to_string())));
+        stepper.step(&mut platform, &mut app, &mut sync);
+        assert_eq!(*flush_count.borrow(), 2, "an idle frame doesn't flush");
+    }
+
     #[test]
     fn run_stops_when_should_continue_returns_false() {
         let flush_count = Rc::new(RefCell::new(0));
@@ -453,4 +575,33 @@ mod tests {
         // dirty -> flush. Frame 3: no new input -> not dirty -> no flush.
         assert_eq!(*flush_count.borrow(), 2);
     }
+
+    #[test]
+    fn run_paces_frames_through_the_platform_clock() {
+        const BUDGET: Duration = Duration::from_millis(33);
+        let start = Instant::now();
+        let mut platform = StubPlatform {
+            display: StubDisplay { flush_count: Rc::new(RefCell::new(0)) },
+            input: QueuedInput(Vec::new()),
+            clock: SteppedClock { now: Cell::new(start), deadlines: RefCell::new(Vec::new()) },
+            storage: StubStorage,
+        };
+        let mut app = App::new(10, 10, Vec::new());
+        let mut sync = EmptySyncSource;
+
+        let mut iterations = 0;
+        let real_start = Instant::now();
+        run(&mut platform, &mut app, &mut sync, BUDGET, || {
+            iterations += 1;
+            iterations <= 3
+        });
+
+        // Each frame's deadline is measured from that frame's start on the
+        // clock the platform hands out, and sleeping on it is the clock's
+        // business: this one just jumps, so three frames of simulated time
+        // cost no real time.
+        assert_eq!(*platform.clock.deadlines.borrow(), vec![start + BUDGET, start + BUDGET * 2, start + BUDGET * 3]);
+        assert_eq!(platform.clock.now(), start + BUDGET * 3);
+        assert!(real_start.elapsed() < BUDGET * 3);
+    }
 }
@@ -0,0 +1,310 @@
+//! `Settings`: the device's user preferences (brightness, idle screen-off,
+//! list sort order, list sublabel, theme), and their persisted form.
+//!
+//! Like [`crate::vault_store::VaultStore`], the live value is App-owned and
+//! shared as `Rc<RefCell<Settings>>`: the settings screen writes into it,
+//! and everything that depends on a preference (`CredentialListView`'s
+//! ordering and sublabel, `App`'s brightness and idle handling) reads it
+//! live, so a change applies on the very next frame without rebuilding
+//! anything.
+//!
+//! # Persisted schema
+//!
+//! Stored under [`SETTINGS_KEY`] via `crate::platform::Storage` as a CBOR
+//! map: `{"version": SETTINGS_VERSION, "brightness": .., ...}`.
+//!
+//! - A field missing from the stored map takes its default, so adding a
+//!   field is *not* a schema change and needs no version bump.
+//! - Renaming a field or changing what a value means *is*: bump
+//!   [`SETTINGS_VERSION`] and add a step to [`migrate`] that rewrites a
+//!   map of the previous version into the next one. [`Settings::decode`]
+//!   runs every step from the stored version up to the current one.
+//! - A map written by a *newer* firmware (a downgrade) can't be
+//!   interpreted safely and is ignored in favor of the defaults, as is
+//!   anything that doesn't decode at all. Losing preferences is an
+//!   inconvenience; misreading them (a brightness of 0) is a bricked-looking
+//!   screen.
+
+use std::time::Duration;
+
+use ciborium::value::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Theme;
+
+/// The `Storage` key settings live under. Short enough for NVS's 15-byte
+/// key limit.
+pub const SETTINGS_KEY: &str = "settings";
+
+/// The current schema version; see the module doc for when to bump it.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Backlight brightness bounds and step, in percent. The floor is above
+/// zero on purpose: "off" is what the idle timeout is for, and a
+/// brightness setting that can black out the screen can't be undone by
+/// someone looking at it.
+pub const BRIGHTNESS_MIN: u8 = 10;
+pub const BRIGHTNESS_MAX: u8 = 100;
+pub const BRIGHTNESS_STEP: u8 = 10;
+
+/// Idle screen-off timeout bounds and step, in seconds.
+pub const IDLE_TIMEOUT_MIN_SECS: u16 = 15;
+pub const IDLE_TIMEOUT_MAX_SECS: u16 = 600;
+pub const IDLE_TIMEOUT_STEP_SECS: u16 = 15;
+
+/// The order the root credential list shows items in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Whatever order the last sync delivered (the pre-settings behavior).
+    #[default]
+    AsSynced,
+    /// Case-insensitively by name, A to Z.
+    Name,
+}
+
+/// What a credential list row shows under the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sublabel {
+    #[default]
+    Username,
+    /// The credential's first URI, if it has one.
+    Website,
+    /// Name only.
+    Nothing,
+}
+
+/// Which [`Theme`] the UI renders with. Persisted by name rather than as
+/// the palette itself, so retuning a theme's colors reaches devices that
+/// already chose it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeKind {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// The dark palette with the larger font set.
+    LargeText,
+}
+
+impl ThemeKind {
+    #[must_use]
+    pub fn theme(self) -> Theme {
+        match self {
+            ThemeKind::Dark => Theme::DARK,
+            ThemeKind::Light => Theme::LIGHT,
+            ThemeKind::HighContrast => Theme::HIGH_CONTRAST,
+            ThemeKind::LargeText => Theme::LARGE_TEXT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Backlight brightness, percent, within
+    /// [`BRIGHTNESS_MIN`]`..=`[`BRIGHTNESS_MAX`].
+    pub brightness: u8,
+    /// Whether to turn the screen off after [`Self::idle_timeout_secs`]
+    /// without input.
+    pub screen_off_when_idle: bool,
+    pub idle_timeout_secs: u16,
+    pub sort_order: SortOrder,
+    pub sublabel: Sublabel,
+    pub theme: ThemeKind,
+}
+
+impl Default for Settings {
+    /// Matches how the device behaved before it had settings: full
+    /// brightness, never sleeps, sync order, usernames, dark theme.
+    fn default() -> Self {
+        Self {
+            brightness: BRIGHTNESS_MAX,
+            screen_off_when_idle: false,
+            idle_timeout_secs: 60,
+            sort_order: SortOrder::AsSynced,
+            sublabel: Sublabel::Username,
+            theme: ThemeKind::Dark,
+        }
+    }
+}
+
+impl Settings {
+    /// The idle timeout, or `None` if the screen never turns off.
+    #[must_use]
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.screen_off_when_idle
+            .then(|| Duration::from_secs(u64::from(self.idle_timeout_secs)))
+    }
+
+    /// Clamps every numeric field into its allowed range — applied to
+    /// whatever comes out of storage, which may have been written by a
+    /// build with different bounds (or not by us at all).
+    #[must_use]
+    pub fn clamped(mut self) -> Self {
+        self.brightness = self.brightness.clamp(BRIGHTNESS_MIN, BRIGHTNESS_MAX);
+        self.idle_timeout_secs = self
+            .idle_timeout_secs
+            .clamp(IDLE_TIMEOUT_MIN_SECS, IDLE_TIMEOUT_MAX_SECS);
+        self
+    }
+
+    /// The persisted form: a CBOR map tagged with [`SETTINGS_VERSION`].
+    ///
+    /// # Panics
+    ///
+    /// Never, in practice: every field is a plain integer, bool or unit
+    /// enum, which CBOR can always represent.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let Ok(Value::Map(mut fields)) = Value::serialized(self) else {
+            unreachable!("Settings always serializes to a map");
+        };
+        fields.insert(
+            0,
+            (
+                Value::Text("version".to_string()),
+                Value::Integer(SETTINGS_VERSION.into()),
+            ),
+        );
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Value::Map(fields), &mut bytes)
+            .expect("writing CBOR into a Vec cannot fail");
+        bytes
+    }
+
+    /// Reads settings back from [`Self::encode`]'s output, migrating older
+    /// schema versions forward. `None` (nothing stored yet), undecodable
+    /// bytes, and newer-than-known versions all yield the defaults — see
+    /// the module doc.
+    #[must_use]
+    pub fn decode(bytes: Option<&[u8]>) -> Self {
+        let Some(bytes) = bytes else {
+            return Self::default();
+        };
+        match Self::try_decode(bytes) {
+            Ok(settings) => settings.clamped(),
+            Err(reason) => {
+                log::warn!("ignoring stored settings ({reason}); using defaults");
+                Self::default()
+            }
+        }
+    }
+
+    fn try_decode(bytes: &[u8]) -> Result<Self, String> {
+        let value: Value = ciborium::from_reader(bytes).map_err(|e| e.to_string())?;
+        let Value::Map(mut fields) = value else {
+            return Err("not a map".to_string());
+        };
+        let version_index = fields
+            .iter()
+            .position(|(key, _)| key.as_text() == Some("version"))
+            .ok_or("no schema version")?;
+        let (_, version) = fields.remove(version_index);
+        let version = version
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or("schema version is not an integer")?;
+        if version > SETTINGS_VERSION {
+            return Err(format!(
+                "schema version {version} is newer than this firmware's {SETTINGS_VERSION}"
+            ));
+        }
+        for from in version..SETTINGS_VERSION {
+            fields = migrate(from, fields)?;
+        }
+        Value::Map(fields)
+            .deserialized()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Rewrites a settings map stored under schema version `from` into version
+/// `from + 1`. Version 1 is the first schema, so no step exists yet and
+/// anything older (there is nothing older) is rejected; each future
+/// [`SETTINGS_VERSION`] bump adds its `n => ...` arm here.
+fn migrate(from: u32, _fields: Vec<(Value, Value)>) -> Result<Vec<(Value, Value)>, String> {
+    Err(format!("no migration from schema version {from}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(fields: Vec<(&str, Value)>) -> Vec<u8> {
+        let map = fields
+            .into_iter()
+            .map(|(key, value)| (Value::Text(key.to_string()), value))
+            .collect();
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Value::Map(map), &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn nothing_stored_yields_the_defaults() {
+        assert_eq!(Settings::decode(None), Settings::default());
+    }
+
+    #[test]
+    fn encode_then_decode_round_trips() {
+        let settings = Settings {
+            brightness: 40,
+            screen_off_when_idle: true,
+            idle_timeout_secs: 120,
+            sort_order: SortOrder::Name,
+            sublabel: Sublabel::Website,
+            theme: ThemeKind::HighContrast,
+        };
+        assert_eq!(Settings::decode(Some(&settings.encode())), settings);
+    }
+
+    #[test]
+    fn missing_fields_take_their_defaults_and_unknown_fields_are_ignored() {
+        let bytes = stored(vec![
+            ("version", Value::Integer(1.into())),
+            ("brightness", Value::Integer(50.into())),
+            ("haptics", Value::Bool(true)),
+        ]);
+        let settings = Settings::decode(Some(&bytes));
+        assert_eq!(settings.brightness, 50);
+        assert_eq!(settings.sort_order, SortOrder::AsSynced);
+        assert_eq!(settings.theme, ThemeKind::Dark, "settings stored before themes existed stay dark");
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let bytes = stored(vec![
+            ("version", Value::Integer(1.into())),
+            ("brightness", Value::Integer(0.into())),
+            ("idle_timeout_secs", Value::Integer(5.into())),
+        ]);
+        let settings = Settings::decode(Some(&bytes));
+        assert_eq!(settings.brightness, BRIGHTNESS_MIN);
+        assert_eq!(settings.idle_timeout_secs, IDLE_TIMEOUT_MIN_SECS);
+    }
+
+    #[test]
+    fn a_newer_schema_version_or_garbage_falls_back_to_the_defaults() {
+        let newer = stored(vec![
+            ("version", Value::Integer((SETTINGS_VERSION + 1).into())),
+            ("brightness", Value::Integer(50.into())),
+        ]);
+        assert_eq!(Settings::decode(Some(&newer)), Settings::default());
+
+        let unversioned = stored(vec![("brightness", Value::Integer(50.into()))]);
+        assert_eq!(Settings::decode(Some(&unversioned)), Settings::default());
+
+        assert_eq!(Settings::decode(Some(b"not cbor")), Settings::default());
+    }
+
+    #[test]
+    fn idle_timeout_is_none_unless_enabled() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.idle_timeout(), None);
+        settings.screen_off_when_idle = true;
+        assert_eq!(settings.idle_timeout(), Some(Duration::from_secs(60)));
+    }
+}
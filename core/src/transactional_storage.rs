@@ -0,0 +1,414 @@
+//! `TransactionalStorage`: a [`Storage`] layered over another one (NVS on
+//! the device) that stores values of any size atomically.
+//!
+//! Raw NVS caps a blob at [`nvs::MAX_BLOB_LEN`] and only makes single
+//! entries atomic. An encrypted vault is bigger than one entry, and
+//! writing it as several raw entries means a power cut halfway leaves a
+//! vault that's half old, half new, and decrypts as neither. This layer
+//! stores each value as:
+//!
+//! - **Chunks** of at most [`CHUNK_LEN`] bytes, under `{key}~a00`,
+//!   `{key}~a01`, ... or `{key}~b00`, ...: two alternating slots, picked
+//!   by the generation's parity.
+//! - **A manifest** under `{key}~m`: a small CBOR record of the committed
+//!   generation, the value's length, its chunk count and its CRC32.
+//!
+//! # Committing
+//!
+//! `set` writes the new value's chunks into the slot the *current*
+//! generation isn't using, then writes the manifest naming the next
+//! generation. That one manifest write is the commit point, and NVS makes
+//! it atomic: a power cut before it leaves the old manifest, pointing at
+//! the old slot, which nothing touched; a cut after it leaves the new one,
+//! whose chunks are all written. `get` then checks the reassembled value
+//! against the manifest's length and CRC32, so damage from outside this
+//! protocol reads as absent rather than as a wrong vault.
+//!
+//! Once committed, the previous generation is garbage: its chunks are
+//! removed, as is anything past the new value's end left in the new slot
+//! by an older, longer value or an interrupted sweep. Sweeps remove from
+//! the last chunk down, so an interrupted one still leaves a run from
+//! chunk 0 for the next commit's sweep to find. Garbage is therefore at
+//! most one generation, however many commits a power cut interrupts. A
+//! failed sweep doesn't fail the `set` — the value is committed — and is
+//! logged instead.
+//!
+//! # Wear
+//!
+//! NVS levels wear across its pages itself, so what this layer controls is
+//! how much it writes: a `set` of the value already stored writes nothing,
+//! a real change writes each new chunk once plus the manifest, and the
+//! slots alternate rather than ever growing the set of keys.
+//!
+//! Keys written by an earlier firmware straight to raw storage (the
+//! settings blob, before this layer) still read: a key with no manifest
+//! falls back to the raw entry of the same name, and the first commit
+//! removes it.
+//!
+//! Checked on host against [`nvs::SimulatedNvs`], which enforces NVS's
+//! limits and cuts the power after any write.
+
+use std::fmt;
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+use serde::{Deserialize, Serialize};
+
+use crate::nvs;
+use crate::platform::Storage;
+
+/// The most bytes one chunk holds: one NVS blob.
+pub const CHUNK_LEN: usize = nvs::MAX_BLOB_LEN;
+
+/// The most chunks one value spans (`~a00` to `~aff`), so the largest value
+/// is `MAX_CHUNKS * CHUNK_LEN`, just under 500 KiB.
+pub const MAX_CHUNKS: usize = 256;
+
+/// The longest key this layer stores: NVS's limit, less the four bytes of
+/// `~a00` its chunk keys add.
+pub const MAX_KEY_LEN: usize = nvs::MAX_KEY_LEN - 4;
+
+const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// The committed state of one key (see the module doc).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Manifest {
+    generation: u32,
+    len: u32,
+    chunks: u16,
+    crc32: u32,
+}
+
+/// Why a [`TransactionalStorage`] write failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionalStorageError<E> {
+    /// The key is longer than [`MAX_KEY_LEN`].
+    KeyTooLong,
+    /// The value needs more than [`MAX_CHUNKS`] chunks.
+    TooLarge,
+    /// The underlying storage failed. Nothing was committed.
+    Storage(E),
+}
+
+impl<E: fmt::Display> fmt::Display for TransactionalStorageError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyTooLong => write!(f, "transactional keys are at most {MAX_KEY_LEN} bytes"),
+            Self::TooLarge => write!(f, "values are at most {} bytes", MAX_CHUNKS * CHUNK_LEN),
+            Self::Storage(e) => write!(f, "storage write failed: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for TransactionalStorageError<E> {}
+
+/// Atomic, chunked values over `S`. See the module doc.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionalStorage<S> {
+    inner: S,
+}
+
+impl<S: Storage> TransactionalStorage<S>
+where
+    S::Error: fmt::Debug,
+{
+    /// Layers over `inner`, picking up whatever it already holds.
+    #[must_use]
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn manifest(&self, key: &str) -> Option<Manifest> {
+        let bytes = self.inner.get(&manifest_key(key))?;
+        ciborium::from_reader(bytes.as_slice()).ok()
+    }
+
+    /// The value `manifest` commits, if its chunks are all there and
+    /// check out.
+    fn read(&self, key: &str, manifest: &Manifest) -> Option<Vec<u8>> {
+        let mut value = Vec::with_capacity(manifest.len as usize);
+        for index in 0..usize::from(manifest.chunks) {
+            value.extend(
+                self.inner
+                    .get(&chunk_key(key, manifest.generation, index))?,
+            );
+        }
+        (value.len() == manifest.len as usize && CRC.checksum(&value) == manifest.crc32)
+            .then_some(value)
+    }
+
+    /// Removes the chunks of `key` in `generation`'s slot from `from` on,
+    /// last first (see the module doc).
+    fn sweep(&mut self, key: &str, generation: u32, from: usize) -> Result<(), S::Error> {
+        let mut end = from;
+        while end < MAX_CHUNKS && self.inner.get(&chunk_key(key, generation, end)).is_some() {
+            end += 1;
+        }
+        for index in (from..end).rev() {
+            self.inner.remove(&chunk_key(key, generation, index))?;
+        }
+        Ok(())
+    }
+
+    /// Sweeps everything `key` no longer needs once `generation` holds
+    /// `chunks` chunks (or, with `None`, once it's removed).
+    fn collect_garbage(
+        &mut self,
+        key: &str,
+        committed: Option<(u32, usize)>,
+    ) -> Result<(), S::Error> {
+        match committed {
+            Some((generation, chunks)) => {
+                self.sweep(key, generation.wrapping_add(1), 0)?;
+                self.sweep(key, generation, chunks)?;
+            }
+            None => {
+                self.sweep(key, 0, 0)?;
+                self.sweep(key, 1, 0)?;
+            }
+        }
+        if self.inner.get(key).is_some() {
+            self.inner.remove(key)?;
+        }
+        Ok(())
+    }
+}
+
+fn manifest_key(key: &str) -> String {
+    format!("{key}~m")
+}
+
+fn chunk_key(key: &str, generation: u32, index: usize) -> String {
+    let slot = if generation % 2 == 0 { 'a' } else { 'b' };
+    format!("{key}~{slot}{index:02x}")
+}
+
+impl<S: Storage> Storage for TransactionalStorage<S>
+where
+    S::Error: fmt::Debug,
+{
+    type Error = TransactionalStorageError<S::Error>;
+
+    /// The committed value, or `None` if there isn't one or it doesn't
+    /// match its manifest. Falls back to a raw entry written before this
+    /// layer existed.
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        match self.manifest(key) {
+            Some(manifest) => self.read(key, &manifest),
+            None => self.inner.get(key),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Vec<u8>) -> Result<(), Self::Error> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(TransactionalStorageError::KeyTooLong);
+        }
+        let chunks: Vec<&[u8]> = value.chunks(CHUNK_LEN).collect();
+        let chunk_count = u16::try_from(chunks.len())
+            .ok()
+            .filter(|&count| usize::from(count) <= MAX_CHUNKS)
+            .ok_or(TransactionalStorageError::TooLarge)?;
+
+        let previous = self.manifest(key);
+        if previous.is_some_and(|manifest| self.read(key, &manifest).as_ref() == Some(&value)) {
+            return Ok(());
+        }
+
+        let generation = previous.map_or(0, |manifest| manifest.generation.wrapping_add(1));
+        for (index, chunk) in chunks.iter().enumerate() {
+            self.inner
+                .set(&chunk_key(key, generation, index), chunk.to_vec())
+                .map_err(TransactionalStorageError::Storage)?;
+        }
+        let manifest = Manifest {
+            generation,
+            len: value.len() as u32,
+            chunks: chunk_count,
+            crc32: CRC.checksum(&value),
+        };
+        let mut encoded = Vec::new();
+        ciborium::into_writer(&manifest, &mut encoded).expect("a manifest always encodes");
+        self.inner
+            .set(&manifest_key(key), encoded)
+            .map_err(TransactionalStorageError::Storage)?;
+
+        if let Err(error) = self.collect_garbage(key, Some((generation, chunks.len()))) {
+            log::warn!("{key:?} committed, but sweeping its old chunks failed: {error:?}");
+        }
+        Ok(())
+    }
+
+    /// Removes the manifest (the commit point, as for `set`), then every
+    /// chunk.
+    fn remove(&mut self, key: &str) -> Result<(), Self::Error> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(TransactionalStorageError::KeyTooLong);
+        }
+        if self.manifest(key).is_some() {
+            self.inner
+                .remove(&manifest_key(key))
+                .map_err(TransactionalStorageError::Storage)?;
+        }
+        self.collect_garbage(key, None)
+            .map_err(TransactionalStorageError::Storage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nvs::SimulatedNvs;
+
+    /// A value of `len` bytes that differs from every other `seed`.
+    fn value(len: usize, seed: u8) -> Vec<u8> {
+        (0..len)
+            .map(|i| (i as u8).wrapping_mul(31) ^ seed)
+            .collect()
+    }
+
+    fn keys(storage: &TransactionalStorage<SimulatedNvs>) -> Vec<String> {
+        storage.inner().keys().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn a_value_bigger_than_one_nvs_blob_round_trips_in_bounded_chunks() {
+        let mut storage = TransactionalStorage::new(SimulatedNvs::new());
+        let vault = value(5 * CHUNK_LEN + 7, 1);
+        storage.set("vault", vault.clone()).unwrap();
+
+        assert_eq!(storage.get("vault"), Some(vault));
+        assert_eq!(
+            keys(&storage),
+            [
+                "vault~a00",
+                "vault~a01",
+                "vault~a02",
+                "vault~a03",
+                "vault~a04",
+                "vault~a05",
+                "vault~m"
+            ]
+        );
+        assert_eq!(
+            storage.set("twelve-bytes", vec![1]),
+            Err(TransactionalStorageError::KeyTooLong)
+        );
+        assert_eq!(
+            storage.set("big", vec![0; MAX_CHUNKS * CHUNK_LEN + 1]),
+            Err(TransactionalStorageError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn a_power_cut_at_any_write_leaves_the_old_value_or_the_new_one() {
+        let old = value(3 * CHUNK_LEN - 5, 1);
+        let new = value(2 * CHUNK_LEN + 9, 2);
+        let mut committed = TransactionalStorage::new(SimulatedNvs::new());
+        committed.set("vault", old.clone()).unwrap();
+
+        // How many writes a whole commit takes: chunks, manifest, sweep.
+        let mut uncut = committed.clone();
+        let before = uncut.inner().total_writes();
+        uncut.set("vault", new.clone()).unwrap();
+        let commit_writes = (uncut.inner().total_writes() - before) as usize;
+        assert!(
+            commit_writes > 3,
+            "a multi-chunk commit takes several writes"
+        );
+
+        for cut in 0..=commit_writes {
+            let mut storage = committed.clone();
+            let mut nvs = storage.into_inner();
+            nvs.cut_power_after(cut);
+            storage = TransactionalStorage::new(nvs);
+            let result = storage.set("vault", new.clone());
+
+            // Reboot: the flash as the cut left it, read afresh.
+            let mut nvs = storage.into_inner();
+            nvs.restore_power();
+            let mut rebooted = TransactionalStorage::new(nvs);
+            let seen = rebooted.get("vault");
+            assert!(
+                seen == Some(old.clone()) || seen == Some(new.clone()),
+                "cut after {cut} write(s): neither the old value nor the new"
+            );
+            if result.is_ok() {
+                assert_eq!(
+                    seen,
+                    Some(new.clone()),
+                    "cut after {cut}: set reported success"
+                );
+            }
+
+            // A later commit succeeds and leaves exactly its own keys.
+            let next = value(CHUNK_LEN + 1, 3);
+            rebooted.set("vault", next.clone()).unwrap();
+            assert_eq!(rebooted.get("vault"), Some(next));
+            assert_eq!(
+                keys(&rebooted).len(),
+                3,
+                "cut after {cut}: {:?}",
+                keys(&rebooted)
+            );
+        }
+    }
+
+    #[test]
+    fn old_generations_are_collected_and_the_slots_alternate() {
+        let mut storage = TransactionalStorage::new(SimulatedNvs::new());
+        storage.set("vault", value(3 * CHUNK_LEN, 1)).unwrap();
+        storage.set("vault", value(CHUNK_LEN, 2)).unwrap();
+        assert_eq!(keys(&storage), ["vault~b00", "vault~m"]);
+
+        storage.set("vault", value(2 * CHUNK_LEN, 3)).unwrap();
+        assert_eq!(keys(&storage), ["vault~a00", "vault~a01", "vault~m"]);
+
+        storage.remove("vault").unwrap();
+        assert!(keys(&storage).is_empty());
+        assert_eq!(storage.get("vault"), None);
+    }
+
+    #[test]
+    fn rewriting_the_stored_value_writes_nothing() {
+        let mut storage = TransactionalStorage::new(SimulatedNvs::new());
+        storage.set("vault", value(2 * CHUNK_LEN, 1)).unwrap();
+        let writes = storage.inner().total_writes();
+
+        storage.set("vault", value(2 * CHUNK_LEN, 1)).unwrap();
+        assert_eq!(storage.inner().total_writes(), writes);
+
+        storage.set("vault", value(2 * CHUNK_LEN, 2)).unwrap();
+        assert_eq!(storage.inner().writes("vault~b00"), 1);
+        assert_eq!(storage.inner().writes("vault~m"), 2);
+    }
+
+    #[test]
+    fn a_raw_value_from_before_this_layer_reads_until_the_first_commit_replaces_it() {
+        let mut nvs = SimulatedNvs::new();
+        nvs.set("settings", vec![0xA1]).unwrap();
+        let mut storage = TransactionalStorage::new(nvs);
+        assert_eq!(storage.get("settings"), Some(vec![0xA1]));
+
+        storage.set("settings", vec![0xA2]).unwrap();
+        assert_eq!(storage.get("settings"), Some(vec![0xA2]));
+        assert_eq!(keys(&storage), ["settings~a00", "settings~m"]);
+    }
+
+    #[test]
+    fn a_chunk_damaged_outside_the_protocol_reads_as_absent() {
+        let mut storage = TransactionalStorage::new(SimulatedNvs::new());
+        storage.set("vault", value(2 * CHUNK_LEN, 1)).unwrap();
+        let mut nvs = storage.into_inner();
+        nvs.set("vault~a01", vec![0; 10]).unwrap();
+        assert_eq!(TransactionalStorage::new(nvs).get("vault"), None);
+    }
+}
@@ -0,0 +1,132 @@
+//! Tests for the golden-image harness itself (`support::golden`): the
+//! perceptual distance, exact and tolerant comparison, the diff image, and
+//! bless mode. Works against goldens in a scratch directory, never the
+//! checked-in ones, so running it with `UPDATE_GOLDEN` set is harmless.
+
+mod support;
+
+use std::path::PathBuf;
+
+use image::{Rgb, RgbImage};
+
+use support::golden::{compare, delta, verify, Tolerance, Verdict};
+
+const GRAY: Rgb<u8> = Rgb([120, 120, 120]);
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "bhk-golden-harness-{name}-{}",
+        uuid::Uuid::new_v4()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn frame() -> RgbImage {
+    RgbImage::from_pixel(8, 4, GRAY)
+}
+
+#[test]
+fn delta_grows_from_identical_toward_black_against_white() {
+    assert_eq!(delta(GRAY, GRAY), 0.0);
+    let extremes = delta(Rgb([0; 3]), Rgb([255; 3]));
+    assert!(extremes > 0.95 && extremes <= 1.0, "{extremes}");
+
+    // One step of Rgb565 green is barely visible; red against the same
+    // gray is not.
+    let nudged = delta(GRAY, Rgb([120, 124, 120]));
+    let red = delta(GRAY, Rgb([255, 0, 0]));
+    assert!(nudged > 0.0 && nudged < 0.05, "{nudged}");
+    assert!(red > 0.3, "{red}");
+}
+
+#[test]
+fn a_threshold_separates_changed_pixels_from_differing_ones() {
+    let mut actual = frame();
+    actual.put_pixel(1, 1, Rgb([120, 124, 120]));
+    actual.put_pixel(2, 2, Rgb([255, 0, 0]));
+
+    let exact = compare(&frame(), &actual, 0.0).unwrap();
+    assert_eq!((exact.changed, exact.differing), (2, 2));
+    assert!(!exact.passes(Tolerance::EXACT));
+
+    let tolerant = compare(&frame(), &actual, 0.1).unwrap();
+    assert_eq!((tolerant.changed, tolerant.differing), (2, 1));
+    assert!(tolerant.passes(Tolerance {
+        threshold: 0.1,
+        max_differing_pixels: 1,
+    }));
+
+    assert!(compare(&frame(), &RgbImage::new(4, 8), 0.0).is_none());
+}
+
+#[test]
+fn the_diff_image_marks_differences_red_and_sub_threshold_changes_yellow() {
+    let mut actual = frame();
+    actual.put_pixel(1, 1, Rgb([120, 124, 120]));
+    actual.put_pixel(2, 2, Rgb([255, 0, 0]));
+
+    let diff = compare(&frame(), &actual, 0.1).unwrap().diff;
+    assert_eq!(*diff.get_pixel(2, 2), Rgb([255, 0, 0]));
+    assert_eq!(*diff.get_pixel(1, 1), Rgb([255, 210, 0]));
+    let backdrop = diff.get_pixel(0, 0);
+    assert!(
+        backdrop[0] == backdrop[1] && backdrop[0] > 200,
+        "{backdrop:?}"
+    );
+}
+
+#[test]
+fn a_mismatch_fails_with_both_artifacts_written_and_named() {
+    let dir = scratch_dir("mismatch");
+    let golden = dir.join("golden/scene.png");
+    std::fs::create_dir_all(golden.parent().unwrap()).unwrap();
+    frame().save(&golden).unwrap();
+    let mut actual = frame();
+    actual.put_pixel(3, 0, Rgb([0, 0, 0]));
+
+    let artifacts = dir.join("artifacts");
+    let message = verify(&golden, &artifacts, &actual, Tolerance::EXACT, false).unwrap_err();
+    assert!(message.contains("1 pixel(s) differ"), "{message}");
+    assert!(message.contains("UPDATE_GOLDEN=1"), "{message}");
+    for artifact in ["scene.actual.png", "scene.diff.png"] {
+        let path = artifacts.join(artifact);
+        assert!(path.exists(), "{} wasn't written", path.display());
+        assert!(message.contains(&path.display().to_string()), "{message}");
+    }
+    assert_eq!(image::open(&golden).unwrap().to_rgb8(), frame());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn bless_mode_rewrites_only_goldens_that_are_missing_or_failing() {
+    let dir = scratch_dir("bless");
+    let golden = dir.join("scene.png");
+    let artifacts = dir.join("artifacts");
+
+    assert!(verify(&golden, &artifacts, &frame(), Tolerance::EXACT, false).is_err());
+    assert_eq!(
+        verify(&golden, &artifacts, &frame(), Tolerance::EXACT, true),
+        Ok(Verdict::Blessed)
+    );
+    assert_eq!(
+        verify(&golden, &artifacts, &frame(), Tolerance::EXACT, true),
+        Ok(Verdict::Matched)
+    );
+
+    // Within tolerance, bless leaves the approved image alone.
+    let mut close = frame();
+    close.put_pixel(0, 0, Rgb([120, 124, 120]));
+    let loose = Tolerance {
+        threshold: 0.1,
+        max_differing_pixels: 0,
+    };
+    assert_eq!(
+        verify(&golden, &artifacts, &close, loose, true),
+        Ok(Verdict::Matched)
+    );
+    assert_eq!(image::open(&golden).unwrap().to_rgb8(), frame());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,115 @@
+//! Golden-image tests across panel geometries: renders the same app states
+//! — the credential list, a credential's detail view and a confirmation
+//! dialog — on each panel shape [`LayoutProfile::for_panel`] distinguishes
+//! (the 128x32 SSD1306 strip, a 170x320 portrait module and a 240x240
+//! round panel), plus the strip once more through [`MonoFrameBuffer`]'s
+//! dithering as the SSD1306 would show it. The fourth shape, the 320x170
+//! landscape T-Embed, is the panel `theme_goldens` renders these same
+//! scenes on (its `theme_dark_*` goldens), so it isn't repeated here.
+//!
+//! Goldens are regenerated with `UPDATE_GOLDEN=1 cargo test --test
+//! layout_resolutions`, which rewrites only the ones that no longer match (see
+//! `support::golden`); review the PNG diff before committing it.
+
+mod support;
+
+use bhk_core::app::App;
+use bhk_core::input::NavIntent;
+use bhk_core::render::{Dither, LayoutProfile, MonoFrameBuffer, Palette};
+use embedded_graphics::prelude::{Point, RgbColor};
+
+use support::fixtures::{delete_dialog, sample_items};
+use support::golden::{assert_golden, to_image};
+
+const PANELS: [(u32, u32); 3] = [(128, 32), (170, 320), (240, 240)];
+
+fn app(width: u32, height: u32) -> App {
+    App::new(width, height, sample_items())
+}
+
+fn mono_to_image(framebuffer: &MonoFrameBuffer) -> image::RgbImage {
+    image::RgbImage::from_fn(framebuffer.width(), framebuffer.height(), |x, y| {
+        let on = framebuffer.pixel(Point::new(x as i32, y as i32)).is_on();
+        image::Rgb(if on { [255; 3] } else { [0; 3] })
+    })
+}
+
+#[test]
+fn credential_list_matches_its_golden_on_every_panel() {
+    for (width, height) in PANELS {
+        let mut app = app(width, height);
+        app.handle_input(vec![NavIntent::Next]);
+        assert_golden(
+            &format!("layout_{width}x{height}_list"),
+            &to_image(app.render()),
+        );
+    }
+}
+
+#[test]
+fn credential_detail_matches_its_golden_on_every_panel() {
+    for (width, height) in PANELS {
+        let mut app = app(width, height);
+        app.handle_input(vec![NavIntent::Activate, NavIntent::Next]);
+        assert_golden(
+            &format!("layout_{width}x{height}_detail"),
+            &to_image(app.render()),
+        );
+    }
+}
+
+#[test]
+fn dialog_matches_its_golden_on_every_panel() {
+    for (width, height) in PANELS {
+        let mut app = app(width, height);
+        app.show_modal(delete_dialog());
+        assert_golden(
+            &format!("layout_{width}x{height}_dialog"),
+            &to_image(app.render()),
+        );
+    }
+}
+
+#[test]
+fn dithered_strip_matches_its_golden() {
+    let mut app = app(128, 32);
+    app.handle_input(vec![NavIntent::Next]);
+    let mono = MonoFrameBuffer::from_rgb565(app.render(), Dither::Ordered);
+    assert_golden("layout_128x32_list_mono", &mono_to_image(&mono));
+}
+
+#[test]
+fn a_round_panel_draws_nothing_outside_its_safe_area() {
+    let size = embedded_graphics::prelude::Size::new(240, 240);
+    let safe = LayoutProfile::for_panel(size).safe_area(size);
+    let mut app = app(size.width, size.height);
+    let frames = [
+        to_image(app.render()),
+        {
+            app.handle_input(vec![NavIntent::Activate]);
+            to_image(app.render())
+        },
+        {
+            app.show_modal(delete_dialog());
+            to_image(app.render())
+        },
+    ];
+    let background = Palette::DARK.background;
+    let background = [
+        background.r() << 3,
+        background.g() << 2,
+        background.b() << 3,
+    ];
+    // The modal frame dims everything beneath the dialog, corners
+    // included, so there it's only uniformity outside the safe area.
+    assert_eq!(frames[0].get_pixel(0, 0).0, background);
+    assert_eq!(frames[1].get_pixel(0, 0).0, background);
+    for frame in &frames {
+        let corner = frame.get_pixel(0, 0).0;
+        for (x, y, pixel) in frame.enumerate_pixels() {
+            if !safe.contains(Point::new(x as i32, y as i32)) {
+                assert_eq!(pixel.0, corner, "({x}, {y}) is outside the safe area");
+            }
+        }
+    }
+}
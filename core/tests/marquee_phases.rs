@@ -0,0 +1,112 @@
+//! Golden-image tests for the focused row's marquee: renders the app's
+//! credential list with one over-long name at fixed points in the
+//! dwell/scroll/pause cycle (`bhk_core::render::marquee`) and compares each
+//! frame against a checked-in PNG under `tests/golden/`, so a change to
+//! the timing constants, the text column, or the clip shows up as a
+//! failing image rather than a subtle on-device difference.
+//!
+//! Goldens are regenerated with `UPDATE_GOLDEN=1 cargo test --test
+//! marquee_phases`, which rewrites only the ones that no longer match (see
+//! `support::golden`); review the PNG diff before committing it.
+//!
+//! Also covers the other half of the contract: that the marquee only
+//! dirties `App` while the label is actually moving, so the dwell and the
+//! end pause cost no render or flush.
+
+mod support;
+
+use std::time::{Duration, Instant};
+
+use bhk_core::app::App;
+use bhk_core::render::{MARQUEE_DWELL, MARQUEE_END_PAUSE, MARQUEE_SPEED_PX_PER_SEC};
+use bhk_core::vault_item::VaultItem;
+use uuid::Uuid;
+
+use support::golden::{assert_golden, to_image};
+
+const LONG_NAME: &str = "Consolidated Regional Credit Union Online Banking";
+
+fn app() -> App {
+    let item = VaultItem {
+        id: Uuid::from_u128(1),
+        name: LONG_NAME.to_string(),
+        username: "a.very.long.username@example-credit-union.com".to_string(),
+        password: "hunter2".to_string(),
+        uri: None,
+        notes: None,
+    };
+    App::new(320, 170, vec![item])
+}
+
+/// Frame interval the tests step the clock by — roughly the run loop's.
+const FRAME: Duration = Duration::from_millis(50);
+
+/// Ticks `app` one `FRAME` at a time from `from`, rendering whenever it's
+/// dirty, until `until(dirty)` says stop. Returns the time it stopped at.
+fn step_until(app: &mut App, from: Instant, mut until: impl FnMut(bool) -> bool) -> Instant {
+    let mut now = from;
+    for _ in 0..1000 {
+        now += FRAME;
+        app.tick(now);
+        let dirty = app.dirty();
+        if dirty {
+            app.render();
+        }
+        if until(dirty) {
+            return now;
+        }
+    }
+    panic!("marquee never reached the expected phase");
+}
+
+#[test]
+fn marquee_phases_match_their_goldens() {
+    let start = Instant::now();
+    let mut app = app();
+    app.tick(start);
+    assert_golden("marquee_at_rest", &to_image(app.render()));
+
+    let scrolling = step_until(&mut app, start, |dirty| dirty);
+    // A second into the scroll, at the speed the constants promise.
+    let mid_scroll = step_until(&mut app, scrolling, {
+        let mut frames = 0;
+        move |_| {
+            frames += 1;
+            frames == 20
+        }
+    });
+    assert_golden("marquee_mid_scroll", &to_image(app.render()));
+
+    // The first clean frame after the scroll is the end pause.
+    step_until(&mut app, mid_scroll, |dirty| !dirty);
+    assert_golden("marquee_scrolled_to_end", &to_image(app.render()));
+}
+
+#[test]
+fn idle_frames_stay_clean_during_the_dwell_and_the_end_pause() {
+    let start = Instant::now();
+    let mut app = app();
+    app.tick(start);
+    app.render();
+
+    let scrolling = step_until(&mut app, start, |dirty| dirty);
+    assert!(
+        scrolling - start >= MARQUEE_DWELL,
+        "the dwell must not cost a render (scrolled after {:?})",
+        scrolling - start
+    );
+
+    let paused = step_until(&mut app, scrolling, |dirty| !dirty);
+    let resumed = step_until(&mut app, paused, |dirty| dirty);
+    // `paused` is the first clean frame, so the pause began a frame before.
+    assert!(
+        resumed - paused + FRAME >= MARQUEE_END_PAUSE,
+        "the end pause must not cost a render (resumed after {:?})",
+        resumed - paused
+    );
+    let travel = paused - scrolling;
+    assert!(
+        travel >= Duration::from_millis(u64::from(1000 / MARQUEE_SPEED_PX_PER_SEC)),
+        "the label should take visible time to travel, not jump"
+    );
+}
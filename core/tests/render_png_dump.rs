@@ -5,7 +5,9 @@
 //! directory (proving the framebuffer's pixel data round-trips through a
 //! real image encoder, not just that `render()` returns `Ok`), and asserts
 //! on specific pixel colors to prove the scene actually drew what it
-//! should have — not merely that nothing panicked.
+//! should have — not merely that nothing panicked. Finally holds the
+//! whole frame to an approved PNG under `tests/golden/` (see
+//! `support::golden`).
 
 // See the identical allow (and rationale) on `bhk_core::render`: this test
 // mirrors that module's `embedded-graphics` `Point`(i32)/`Size`(u32)
@@ -13,12 +15,16 @@
 // justification applies.
 #![allow(clippy::cast_possible_wrap)]
 
+mod support;
+
 use bhk_core::input::NavIntent;
 use bhk_core::render::chrome::TITLE_BAR_HEIGHT;
-use bhk_core::render::theme::palette;
 use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Screen, VerticalList, ROW_HEIGHT};
+use bhk_core::render::{Palette, Theme};
 use embedded_graphics::prelude::{Point, RgbColor};
 
+use support::golden::{assert_golden, to_image};
+
 const ITEM_COUNT: i32 = 3;
 
 fn build_scene() -> Navigator {
@@ -42,11 +48,13 @@ fn row_top(index: i32) -> i32 {
 fn scene_renders_expected_chrome_colors() {
     let navigator = build_scene();
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator
+        .render(&Theme::DARK, &mut framebuffer)
+        .expect("core DrawTarget is Infallible");
 
     // Title bar background, per Screen::render.
-    assert_eq!(framebuffer.pixel(Point::new(0, 0)), palette::SURFACE);
-    assert_eq!(framebuffer.pixel(Point::new(319, 0)), palette::SURFACE);
+    assert_eq!(framebuffer.pixel(Point::new(0, 0)), Palette::DARK.surface);
+    assert_eq!(framebuffer.pixel(Point::new(319, 0)), Palette::DARK.surface);
 
     // First row is selected by default (initialize_focus + selected == 0):
     // its background should be the selected-row elevated fill, not the
@@ -56,13 +64,16 @@ fn scene_renders_expected_chrome_colors() {
     // plain fill rather than the accent stripe or a glyph pixel.
     let sample_x = 300;
     let first_row_background = framebuffer.pixel(Point::new(sample_x, row_top(0) + 2));
-    assert_eq!(first_row_background, palette::SURFACE_ELEVATED);
+    assert_eq!(first_row_background, Palette::DARK.surface_elevated);
 
     // Below the last row (blank content area) should still be plain
     // screen background — proves the list isn't painting outside its own
     // rows.
     let below_last_row = row_top(ITEM_COUNT) + 5;
-    assert_eq!(framebuffer.pixel(Point::new(sample_x, below_last_row)), palette::BACKGROUND);
+    assert_eq!(
+        framebuffer.pixel(Point::new(sample_x, below_last_row)),
+        Palette::DARK.background
+    );
 }
 
 #[test]
@@ -71,16 +82,24 @@ fn dispatching_next_moves_the_selection_highlight_down_one_row() {
     navigator.dispatch(NavIntent::Next);
 
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator
+        .render(&Theme::DARK, &mut framebuffer)
+        .expect("core DrawTarget is Infallible");
 
     // x=300: clear of the left selection accent bar and of any row text,
     // so this samples the plain elevated fill rather than the accent
     // stripe or a glyph pixel.
     let sample_x = 300;
     // Row 0's background is no longer highlighted...
-    assert_ne!(framebuffer.pixel(Point::new(sample_x, row_top(0) + 2)), palette::SURFACE_ELEVATED);
+    assert_ne!(
+        framebuffer.pixel(Point::new(sample_x, row_top(0) + 2)),
+        Palette::DARK.surface_elevated
+    );
     // ...row 1's is.
-    assert_eq!(framebuffer.pixel(Point::new(sample_x, row_top(1) + 2)), palette::SURFACE_ELEVATED);
+    assert_eq!(
+        framebuffer.pixel(Point::new(sample_x, row_top(1) + 2)),
+        Palette::DARK.surface_elevated
+    );
 }
 
 /// Regression test for the row-overflow bug: `FONT_6X10` positions text at
@@ -102,7 +121,9 @@ const BOTTOM_PADDING_PX: i32 = 1;
 fn text_never_bleeds_past_a_rows_bottom_padding() {
     let navigator = build_scene();
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator
+        .render(&Theme::DARK, &mut framebuffer)
+        .expect("core DrawTarget is Infallible");
 
     for index in 0..ITEM_COUNT {
         let top = row_top(index);
@@ -112,12 +133,12 @@ fn text_never_bleeds_past_a_rows_bottom_padding() {
                 let color = framebuffer.pixel(Point::new(x, y));
                 assert_ne!(
                     color,
-                    palette::TEXT_PRIMARY,
+                    Palette::DARK.text_primary,
                     "row {index}'s label text bled into its bottom padding at ({x},{y})"
                 );
                 assert_ne!(
                     color,
-                    palette::TEXT_SECONDARY,
+                    Palette::DARK.text_secondary,
                     "row {index}'s sublabel text bled into its bottom padding at ({x},{y})"
                 );
             }
@@ -142,13 +163,17 @@ fn an_unselected_rows_pixels_do_not_depend_on_which_other_row_is_selected() {
     let mut selecting_row1 = build_scene();
     selecting_row1.dispatch(NavIntent::Next);
     let mut frame_row1_selected = FrameBuffer565::new(320, 170);
-    selecting_row1.render(&mut frame_row1_selected).unwrap();
+    selecting_row1
+        .render(&Theme::DARK, &mut frame_row1_selected)
+        .unwrap();
 
     let mut selecting_row2 = build_scene();
     selecting_row2.dispatch(NavIntent::Next);
     selecting_row2.dispatch(NavIntent::Next);
     let mut frame_row2_selected = FrameBuffer565::new(320, 170);
-    selecting_row2.render(&mut frame_row2_selected).unwrap();
+    selecting_row2
+        .render(&Theme::DARK, &mut frame_row2_selected)
+        .unwrap();
 
     let row0_top = row_top(0);
     let row0_bottom = row0_top + ROW_HEIGHT as i32;
@@ -169,7 +194,9 @@ fn an_unselected_rows_pixels_do_not_depend_on_which_other_row_is_selected() {
 fn framebuffer_round_trips_through_a_real_png_encoder() {
     let navigator = build_scene();
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator
+        .render(&Theme::DARK, &mut framebuffer)
+        .expect("core DrawTarget is Infallible");
 
     let mut image = image::RgbImage::new(framebuffer.width(), framebuffer.height());
     for pixel in framebuffer.pixels() {
@@ -188,11 +215,13 @@ fn framebuffer_round_trips_through_a_real_png_encoder() {
     image.save(&path).expect("failed to encode/write PNG");
 
     // Round-trip: decode what we just wrote and check the title bar pixel
-    // survived encoding intact (the theme's `palette::SURFACE`, downsampled
+    // survived encoding intact (the theme's `Palette::surface`, downsampled
     // to 565 and back up to 8-bit-per-channel space).
-    let decoded = image::open(&path).expect("failed to decode PNG we just wrote").to_rgb8();
+    let decoded = image::open(&path)
+        .expect("failed to decode PNG we just wrote")
+        .to_rgb8();
     let title_pixel = decoded.get_pixel(0, 0);
-    let expected = palette::SURFACE;
+    let expected = Palette::DARK.surface;
     assert_eq!(
         *title_pixel,
         image::Rgb([expected.r() << 3, expected.g() << 2, expected.b() << 3])
@@ -200,3 +229,19 @@ fn framebuffer_round_trips_through_a_real_png_encoder() {
 
     std::fs::remove_file(&path).ok();
 }
+
+/// The same scene, one `Next` in, against its approved baseline — the
+/// check the pixel samples above can't make, that nothing *else* in the
+/// frame moved. `emulator/tests/surface_parity.rs` holds the headless
+/// surface's PNG of this scene to the same golden.
+#[test]
+fn scene_matches_its_golden() {
+    let mut navigator = build_scene();
+    navigator.dispatch(NavIntent::Next);
+    let mut framebuffer = FrameBuffer565::new(320, 170);
+    navigator
+        .render(&Theme::DARK, &mut framebuffer)
+        .expect("core DrawTarget is Infallible");
+
+    assert_golden("render_scene_dark", &to_image(&framebuffer));
+}
@@ -0,0 +1,40 @@
+//! The vault and modal the snapshot suites render: the same four
+//! credentials and the same delete confirmation in `theme_goldens` and
+//! `layout_resolutions`, so a scene that differs between the two suites
+//! differs only in the theme or the panel.
+
+use bhk_core::render::{Action, Dialog};
+use bhk_core::vault_item::VaultItem;
+use uuid::Uuid;
+
+/// A credential with a fixed password and no notes.
+pub fn item(id: u128, name: &str, username: &str, uri: Option<&str>) -> VaultItem {
+    VaultItem {
+        id: Uuid::from_u128(id),
+        name: name.to_string(),
+        username: username.to_string(),
+        password: "correct horse".to_string(),
+        uri: uri.map(str::to_string),
+        notes: None,
+    }
+}
+
+/// The snapshot vault: long and short names, one item with a website.
+pub fn sample_items() -> Vec<VaultItem> {
+    vec![
+        item(1, "GitHub", "alice-dev", Some("https://github.com")),
+        item(2, "AWS Console", "alice@corp.io", None),
+        item(3, "Postgres (prod)", "svc-account", None),
+        item(4, "Bitwarden.com", "alice@example.com", None),
+    ]
+}
+
+/// A two-choice confirmation whose body wraps on every panel.
+pub fn delete_dialog() -> Dialog {
+    Dialog::new(
+        "Delete item?",
+        "This removes it from the device until the next sync.",
+    )
+    .with_choice("Cancel", || Action::None)
+    .with_choice("Delete", || Action::None)
+}
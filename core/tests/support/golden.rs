@@ -0,0 +1,285 @@
+//! The golden-image harness behind the render snapshot tests: compares a
+//! rendered frame against an approved PNG under `tests/golden/`, and on a
+//! mismatch leaves behind what a reviewer needs to see why.
+//!
+//! - **Exact or tolerant.** [`assert_golden`] wants every pixel identical.
+//!   [`assert_golden_within`] takes a [`Tolerance`]: a per-pixel
+//!   perceptual threshold (the YIQ color distance `pixelmatch` uses,
+//!   normalized so 0.0 is identical; black against white is about 0.97)
+//!   and a count of pixels allowed past it. Our rasterizer is
+//!   deterministic, so the snapshot suites compare exactly; the tolerance
+//!   is for frames that go through something lossy on the way, like
+//!   dithering or scaling.
+//! - **Diff image on failure.** A failing comparison writes
+//!   `<name>.actual.png` and `<name>.diff.png` under the test's
+//!   `CARGO_TARGET_TMPDIR` and names both in the panic. The diff shows the
+//!   golden faded to a light gray, with pixels past the threshold in red
+//!   and pixels that changed within it in yellow.
+//! - **Bless mode.** With `UPDATE_GOLDEN` set, a golden that is missing or
+//!   fails its comparison is rewritten from the actual frame instead, and
+//!   the rewrite is logged; goldens that still pass are left alone, so a
+//!   tolerant comparison doesn't churn files. Review the PNG diff before
+//!   committing it.
+
+use std::path::{Path, PathBuf};
+
+use bhk_core::render::FrameBuffer565;
+use embedded_graphics::prelude::{OriginDimensions, Point, RgbColor};
+use image::{Rgb, RgbImage};
+
+/// The environment variable that turns on bless mode.
+pub const BLESS_VAR: &str = "UPDATE_GOLDEN";
+
+/// `pixelmatch`'s bound on the weighted YIQ distance, used to normalize it.
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+/// How far a frame may drift from its golden and still pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// A pixel only counts as differing when its perceptual distance from
+    /// the golden's pixel is above this (0.0 to 1.0; see [`delta`]).
+    pub threshold: f64,
+    /// How many differing pixels the frame may have.
+    pub max_differing_pixels: usize,
+}
+
+impl Tolerance {
+    /// Every pixel identical.
+    pub const EXACT: Self = Self {
+        threshold: 0.0,
+        max_differing_pixels: 0,
+    };
+}
+
+/// The outcome of comparing two same-sized images.
+#[derive(Debug)]
+pub struct Comparison {
+    /// Pixels whose distance is above the threshold.
+    pub differing: usize,
+    /// Pixels that changed at all, including those within the threshold.
+    pub changed: usize,
+    /// The largest distance seen, 0.0 to 1.0.
+    pub worst: f64,
+    /// The golden faded to light gray, with differing pixels in red and
+    /// pixels changed within the threshold in yellow.
+    pub diff: RgbImage,
+}
+
+impl Comparison {
+    #[must_use]
+    pub fn passes(&self, tolerance: Tolerance) -> bool {
+        self.differing <= tolerance.max_differing_pixels
+    }
+}
+
+/// Widens `framebuffer`'s `Rgb565` pixels into an 8-bit image, the way a
+/// screenshot does: each channel shifted left, so the low bits are 0.
+#[must_use]
+pub fn to_image(framebuffer: &FrameBuffer565) -> RgbImage {
+    let size = framebuffer.size();
+    RgbImage::from_fn(size.width, size.height, |x, y| {
+        let color = framebuffer.pixel(Point::new(x as i32, y as i32));
+        Rgb([color.r() << 3, color.g() << 2, color.b() << 3])
+    })
+}
+
+/// The perceptual distance between two colors, 0.0 (identical) to 1.0
+/// (black against white is about 0.97): `pixelmatch`'s weighted YIQ
+/// difference, square rooted and normalized so a threshold reads like its
+/// `threshold` option.
+#[must_use]
+pub fn delta(a: Rgb<u8>, b: Rgb<u8>) -> f64 {
+    let [ya, ia, qa] = yiq(a);
+    let [yb, ib, qb] = yiq(b);
+    let (y, i, q) = (ya - yb, ia - ib, qa - qb);
+    let squared = 0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q;
+    (squared / MAX_YIQ_DELTA).sqrt().min(1.0)
+}
+
+fn yiq(Rgb([r, g, b]): Rgb<u8>) -> [f64; 3] {
+    let (r, g, b) = (f64::from(r), f64::from(g), f64::from(b));
+    [
+        r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23,
+        r * 0.595_977_99 - g * 0.274_176_10 - b * 0.321_801_89,
+        r * 0.211_470_17 - g * 0.522_617_11 + b * 0.311_146_94,
+    ]
+}
+
+/// Compares `actual` against `expected` pixel by pixel, counting a pixel
+/// as differing when its [`delta`] is above `threshold`. `None` when the
+/// two aren't the same size.
+#[must_use]
+pub fn compare(expected: &RgbImage, actual: &RgbImage, threshold: f64) -> Option<Comparison> {
+    if expected.dimensions() != actual.dimensions() {
+        return None;
+    }
+    let mut comparison = Comparison {
+        differing: 0,
+        changed: 0,
+        worst: 0.0,
+        diff: RgbImage::new(expected.width(), expected.height()),
+    };
+    for ((x, y, &want), &got) in expected.enumerate_pixels().zip(actual.pixels()) {
+        let pixel = if want == got {
+            faded(want)
+        } else {
+            let distance = delta(want, got);
+            comparison.changed += 1;
+            comparison.worst = comparison.worst.max(distance);
+            if distance > threshold {
+                comparison.differing += 1;
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([255, 210, 0])
+            }
+        };
+        comparison.diff.put_pixel(x, y, pixel);
+    }
+    Some(comparison)
+}
+
+/// A pixel's luma blended 90% of the way to white, as the diff image's
+/// backdrop: enough to recognize the frame, faint enough that red stands
+/// out against any theme.
+fn faded(pixel: Rgb<u8>) -> Rgb<u8> {
+    let luma = yiq(pixel)[0];
+    let value = (255.0 - (255.0 - luma) * 0.1).round() as u8;
+    Rgb([value; 3])
+}
+
+/// The checked-in golden for `name`.
+#[must_use]
+pub fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.png"))
+}
+
+/// Where a failing comparison writes its artifacts.
+#[must_use]
+pub fn artifact_dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+fn bless_requested() -> bool {
+    std::env::var_os(BLESS_VAR).is_some()
+}
+
+/// Compares `actual` against `tests/golden/<name>.png` exactly.
+///
+/// # Panics
+///
+/// When the golden is missing or any pixel differs, outside bless mode;
+/// see the module docs.
+pub fn assert_golden(name: &str, actual: &RgbImage) {
+    assert_golden_within(name, actual, Tolerance::EXACT);
+}
+
+/// Compares `actual` against `tests/golden/<name>.png` within `tolerance`.
+///
+/// # Panics
+///
+/// When the golden is missing or differs by more than `tolerance`,
+/// outside bless mode; see the module docs.
+pub fn assert_golden_within(name: &str, actual: &RgbImage, tolerance: Tolerance) {
+    let verdict = verify(
+        &golden_path(name),
+        &artifact_dir(),
+        actual,
+        tolerance,
+        bless_requested(),
+    );
+    if let Err(message) = verdict {
+        panic!("{name}: {message}");
+    }
+}
+
+/// What [`verify`] did when it didn't fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The golden exists and `actual` is within tolerance of it.
+    Matched,
+    /// Bless mode rewrote the golden from `actual`.
+    Blessed,
+}
+
+/// The body of [`assert_golden_within`], with the paths and the bless
+/// switch passed in so the harness can test itself without touching the
+/// checked-in goldens.
+///
+/// # Errors
+///
+/// A message for the panic when the golden at `golden` is missing, is a
+/// different size, or differs by more than `tolerance` and `bless` is
+/// off. A failing comparison also writes `<stem>.actual.png` and
+/// `<stem>.diff.png` under `artifacts`, and the message names them.
+///
+/// # Panics
+///
+/// When a golden, or an artifact, can't be written.
+pub fn verify(
+    golden: &Path,
+    artifacts: &Path,
+    actual: &RgbImage,
+    tolerance: Tolerance,
+    bless: bool,
+) -> Result<Verdict, String> {
+    let failure = match image::open(golden) {
+        Err(e) => format!("missing golden {}: {e}", golden.display()),
+        Ok(expected) => {
+            let expected = expected.to_rgb8();
+            match compare(&expected, actual, tolerance.threshold) {
+                Some(comparison) if comparison.passes(tolerance) => return Ok(Verdict::Matched),
+                Some(comparison) => {
+                    let written = write_artifacts(golden, artifacts, actual, &comparison.diff);
+                    format!(
+                        "{} pixel(s) differ from {} by more than {:.3} (worst {:.3}, {} changed \
+                         in all, {} allowed)\n  actual: {}\n  diff:   {}",
+                        comparison.differing,
+                        golden.display(),
+                        tolerance.threshold,
+                        comparison.worst,
+                        comparison.changed,
+                        tolerance.max_differing_pixels,
+                        written.0.display(),
+                        written.1.display(),
+                    )
+                }
+                None => format!(
+                    "frame is {}x{} but {} is {}x{}",
+                    actual.width(),
+                    actual.height(),
+                    golden.display(),
+                    expected.width(),
+                    expected.height(),
+                ),
+            }
+        }
+    };
+
+    if bless {
+        std::fs::create_dir_all(golden.parent().unwrap()).unwrap();
+        actual.save(golden).unwrap();
+        eprintln!("blessed {} ({failure})", golden.display());
+        return Ok(Verdict::Blessed);
+    }
+    Err(format!(
+        "{failure}\nrerun with {BLESS_VAR}=1 to bless the new frame, then review the PNG diff"
+    ))
+}
+
+fn write_artifacts(
+    golden: &Path,
+    artifacts: &Path,
+    actual: &RgbImage,
+    diff: &RgbImage,
+) -> (PathBuf, PathBuf) {
+    let stem = golden.file_stem().unwrap().to_string_lossy();
+    let actual_path = artifacts.join(format!("{stem}.actual.png"));
+    let diff_path = artifacts.join(format!("{stem}.diff.png"));
+    std::fs::create_dir_all(artifacts).unwrap();
+    actual.save(&actual_path).unwrap();
+    diff.save(&diff_path).unwrap();
+    (actual_path, diff_path)
+}
@@ -0,0 +1,8 @@
+//! Helpers shared by the integration tests. Each test binary pulls this in
+//! with `mod support;` and uses what it needs, so unused items are
+//! expected per binary.
+
+#![allow(dead_code)]
+
+pub mod fixtures;
+pub mod golden;
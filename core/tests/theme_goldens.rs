@@ -0,0 +1,131 @@
+//! Golden-image tests for every selectable theme: renders the same app
+//! states — the credential list, an empty vault, a credential's detail
+//! view, the settings screen, a confirmation dialog and a toast — once per
+//! [`ThemeKind`] and compares
+//! each frame against a checked-in PNG under `tests/golden/`, so a palette
+//! or font-set change shows up as a reviewed image diff for every theme it
+//! touches, not just the default one.
+//!
+//! Goldens are regenerated with `UPDATE_GOLDEN=1 cargo test --test
+//! theme_goldens`, which rewrites only the ones that no longer match (see
+//! `support::golden`); review the PNG diff before committing it.
+
+mod support;
+
+use std::convert::Infallible;
+
+use bhk_core::app::App;
+use bhk_core::input::NavIntent;
+use bhk_core::platform::Storage;
+use bhk_core::render::{ChromeStatus, Toast};
+use bhk_core::settings::{Settings, ThemeKind, SETTINGS_KEY};
+use bhk_core::vault_item::VaultItem;
+
+use support::fixtures::{delete_dialog, sample_items};
+use support::golden::{assert_golden, to_image};
+
+const THEMES: [(ThemeKind, &str); 4] = [
+    (ThemeKind::Dark, "dark"),
+    (ThemeKind::Light, "light"),
+    (ThemeKind::HighContrast, "high_contrast"),
+    (ThemeKind::LargeText, "large_text"),
+];
+
+/// Just enough `Storage` to hand `App::restore_settings` one stored value.
+struct StoredSettings(Vec<u8>);
+
+impl Storage for StoredSettings {
+    type Error = Infallible;
+
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        (key == SETTINGS_KEY).then(|| self.0.clone())
+    }
+
+    fn set(&mut self, _key: &str, _value: Vec<u8>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn remove(&mut self, _key: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+fn app(theme: ThemeKind) -> App {
+    app_with(theme, sample_items())
+}
+
+fn app_with(theme: ThemeKind, items: Vec<VaultItem>) -> App {
+    let mut app = App::new(320, 170, items);
+    let settings = Settings {
+        theme,
+        ..Settings::default()
+    };
+    app.restore_settings(&StoredSettings(settings.encode()));
+    app
+}
+
+#[test]
+fn credential_list_matches_its_golden_in_every_theme() {
+    for (theme, slug) in THEMES {
+        let mut app = app(theme);
+        app.handle_input(vec![NavIntent::Next]);
+        assert_golden(&format!("theme_{slug}_list"), &to_image(app.render()));
+    }
+}
+
+#[test]
+fn empty_vault_matches_its_golden_in_every_theme() {
+    for (theme, slug) in THEMES {
+        let mut app = app_with(theme, Vec::new());
+        assert_golden(&format!("theme_{slug}_empty"), &to_image(app.render()));
+    }
+}
+
+#[test]
+fn credential_detail_matches_its_golden_in_every_theme() {
+    for (theme, slug) in THEMES {
+        let mut app = app(theme);
+        app.handle_input(vec![NavIntent::Activate, NavIntent::Next]);
+        assert_golden(&format!("theme_{slug}_detail"), &to_image(app.render()));
+    }
+}
+
+#[test]
+fn settings_screen_matches_its_golden_in_every_theme() {
+    for (theme, slug) in THEMES {
+        let mut app = app(theme);
+        app.handle_input(vec![NavIntent::Back]);
+        assert_golden(&format!("theme_{slug}_settings"), &to_image(app.render()));
+    }
+}
+
+#[test]
+fn dialog_matches_its_golden_in_every_theme() {
+    for (theme, slug) in THEMES {
+        let mut app = app(theme);
+        app.show_modal(delete_dialog());
+        assert_golden(&format!("theme_{slug}_dialog"), &to_image(app.render()));
+    }
+}
+
+#[test]
+fn toast_matches_its_golden_in_every_theme() {
+    for (theme, slug) in THEMES {
+        let mut app = app(theme);
+        app.show_toast(Toast::new("Sync failed").with_status(ChromeStatus::Error));
+        assert_golden(&format!("theme_{slug}_toast"), &to_image(app.render()));
+    }
+}
+
+#[test]
+fn themes_render_differently() {
+    let frames: Vec<Vec<u8>> = THEMES
+        .iter()
+        .map(|&(theme, _)| to_image(app(theme).render()).into_raw())
+        .collect();
+    for (i, a) in frames.iter().enumerate() {
+        for b in &frames[i + 1..] {
+            assert_ne!(a, b, "every theme should look different from every other");
+        }
+    }
+}
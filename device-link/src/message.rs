@@ -167,6 +167,14 @@ pub enum WireIntent {
     Prev,
     /// Jump forward N items (fast encoder rotation, held button, Pg Dn).
     NextN(u16),
+    /// Jump backward N items (fast reverse rotation, Pg Up).
+    PrevN(u16),
+    /// Jump to the first item (Home).
+    Home,
+    /// Jump to the last item (End).
+    End,
+    /// A typed character, for the focused list's type-ahead jump.
+    Char(char),
     /// Select the focused item (encoder short press, space, enter).
     Activate,
     /// Return to parent / dismiss modal (encoder long press, esc, back).
@@ -321,6 +329,11 @@ mod tests {
             WireIntent::Next,
             WireIntent::Prev,
             WireIntent::NextN(7),
+            WireIntent::PrevN(7),
+            WireIntent::Home,
+            WireIntent::End,
+            WireIntent::Char('g'),
+            WireIntent::Char('é'),
             WireIntent::Activate,
             WireIntent::Back,
         ] {
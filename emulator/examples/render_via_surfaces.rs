@@ -22,7 +22,8 @@ use std::rc::Rc;
 
 use bhk_core::input::NavIntent;
 use bhk_core::platform::DisplaySurface;
-use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Screen, VerticalList};
+use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Screen, Theme, VerticalList};
+use embedded_graphics::geometry::Dimensions;
 use emulator::platform::{HeadlessSurface, MinifbSurface};
 
 const WIDTH: u32 = 320;
@@ -48,10 +49,10 @@ fn main() {
 
     let navigator = build_scene();
     let mut framebuffer = FrameBuffer565::new(WIDTH, HEIGHT);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator.render(&Theme::DARK, &mut framebuffer).expect("core DrawTarget is Infallible");
 
     let mut headless = HeadlessSurface::new();
-    headless.flush(&framebuffer).expect("HeadlessSurface::flush is Infallible");
+    headless.flush(&framebuffer, &[framebuffer.bounding_box()]).expect("HeadlessSurface::flush is Infallible");
     let path = "headless_scene.png";
     headless.save_png(path).expect("failed to write PNG");
     println!("wrote {path} ({WIDTH}x{HEIGHT}) via HeadlessSurface");
@@ -71,7 +72,7 @@ fn main() {
     let window = Rc::new(RefCell::new(window));
 
     let mut minifb_surface = MinifbSurface::new(Rc::clone(&window), WIDTH, HEIGHT, WINDOW_SCALE);
-    minifb_surface.flush(&framebuffer).expect("failed to flush to minifb window");
+    minifb_surface.flush(&framebuffer, &[framebuffer.bounding_box()]).expect("failed to flush to minifb window");
     println!("MinifbSurface window open at {WINDOW_SCALE}x scale ({WIDTH}x{HEIGHT} source) — close it to exit.");
     println!("Compare against {path}: they must show the same layout, colors, and text.");
 
@@ -79,7 +80,7 @@ fn main() {
         // Re-present the same static framebuffer every frame; minifb needs
         // `update`/`update_with_buffer` calls to keep pumping the OS event
         // loop (window close, etc.) even though nothing changes.
-        minifb_surface.flush(&framebuffer).expect("failed to flush to minifb window");
+        minifb_surface.flush(&framebuffer, &[framebuffer.bounding_box()]).expect("failed to flush to minifb window");
         std::thread::sleep(std::time::Duration::from_millis(16));
     }
 }
@@ -0,0 +1,351 @@
+//! `DeviceConfig`: which emulated device a `desktop` process is — its
+//! name, HTTP port, data directory and panel profile — so several
+//! emulators can run side by side, each looking like a different key.
+//!
+//! Resolved in three layers, later ones winning: the defaults (the one
+//! T-Embed on `:8080` with its state under `./data`, i.e. exactly what a
+//! bare `desktop` has always been), then a `--config FILE`, then the
+//! individual flags. A config file is TOML with any subset of the fields:
+//!
+//! ```toml
+//! name = "Strip key"
+//! port = 8081
+//! data_dir = "data/strip"   # relative to the working directory
+//! profile = "ssd1306"       # a profile name, or WIDTHxHEIGHT
+//! ```
+//!
+//! and the flags are `--name`, `--port`, `--data-dir`, `--profile` and
+//! `--panel WIDTHxHEIGHT` (a custom profile). Two instances need distinct
+//! ports and data directories; nothing enforces that beyond the second
+//! one failing to bind the port.
+//!
+//! The resolved config is reported back as a [`DeviceDescriptor`] from
+//! `GET /api/status` (see `SyncServer::set_device`), which is how
+//! `web-companion`'s `EmulatorTransportProvider` tells its emulators
+//! apart.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The name a device gets without `--name`; `web-companion` lists the
+/// emulator under the same one.
+pub const DEFAULT_NAME: &str = "Desktop Emulator";
+/// The port `desktop` has always bound.
+pub const DEFAULT_PORT: u16 = 8080;
+/// Where `desktop` has always kept `credentials.json` and its settings
+/// (`kv_store.bin`).
+pub const DEFAULT_DATA_DIR: &str = "./data";
+
+/// A panel to emulate: a named preset, or a custom size from `--panel`.
+/// The render core picks its layout from the size alone
+/// (`bhk_core::render::LayoutProfile::for_panel`); the name is for people
+/// and for `/api/status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelProfile {
+    /// The preset's name, or `"custom"`.
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Every named profile: `(name, what it is, (width, height))`. The
+/// T-Embed's size comes from its pin table (`board_variants`), so the
+/// emulator can't drift from what the firmware drives.
+pub const PROFILES: [(&str, &str, (u32, u32)); 4] = [
+    ("t-embed", "LilyGo T-Embed, 1.9\" ST7789 in landscape", t_embed_size()),
+    ("t-embed-portrait", "the same panel held upright", (t_embed_size().1, t_embed_size().0)),
+    ("ssd1306", "SSD1306 128x32 monochrome OLED strip", (128, 32)),
+    ("round-240", "240x240 round GC9A01", (240, 240)),
+];
+
+const fn t_embed_size() -> (u32, u32) {
+    let (width, height) = board_variants::T_EMBED_CC1101.panel.logical_size();
+    (width as u32, height as u32)
+}
+
+impl PanelProfile {
+    /// The named preset `name`, if there is one.
+    #[must_use]
+    pub fn named(name: &str) -> Option<Self> {
+        PROFILES.iter().find(|(preset, _, _)| *preset == name).map(|&(name, _, (width, height))| Self {
+            name: name.to_string(),
+            width,
+            height,
+        })
+    }
+
+    /// A custom profile from `WIDTHxHEIGHT` (e.g. `128x32`).
+    #[must_use]
+    pub fn custom(value: &str) -> Option<Self> {
+        let (width, height) = value.split_once(['x', 'X'])?;
+        let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+        (width > 0 && height > 0).then(|| Self { name: "custom".to_string(), width, height })
+    }
+
+    /// A preset name or, failing that, a custom `WIDTHxHEIGHT`.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::named(value).or_else(|| Self::custom(value))
+    }
+
+    #[must_use]
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+impl Default for PanelProfile {
+    fn default() -> Self {
+        Self::named("t-embed").expect("t-embed is always a preset")
+    }
+}
+
+impl fmt::Display for PanelProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}x{})", self.name, self.width, self.height)
+    }
+}
+
+fn profile<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PanelProfile, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    PanelProfile::parse(&value).ok_or_else(|| serde::de::Error::custom(unknown_profile(&value)))
+}
+
+fn unknown_profile(value: &str) -> String {
+    let names: Vec<_> = PROFILES.iter().map(|(name, _, _)| *name).collect();
+    format!("unknown panel profile {value:?}: expected one of {} or WIDTHxHEIGHT", names.join(", "))
+}
+
+/// One emulated device. See the module doc for how it's resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeviceConfig {
+    pub name: String,
+    /// `0` binds an ephemeral port; the descriptor reports the real one.
+    pub port: u16,
+    /// Holds this device's `credentials.json` and `kv_store.bin`.
+    pub data_dir: PathBuf,
+    #[serde(deserialize_with = "profile")]
+    pub profile: PanelProfile,
+}
+
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_NAME.to_string(),
+            port: DEFAULT_PORT,
+            data_dir: PathBuf::from(DEFAULT_DATA_DIR),
+            profile: PanelProfile::default(),
+        }
+    }
+}
+
+/// Why a device couldn't be configured.
+#[derive(Debug)]
+pub enum DeviceConfigError {
+    /// The `--config` file couldn't be read.
+    Io(PathBuf, io::Error),
+    /// The `--config` file isn't a valid device config.
+    Parse(PathBuf, toml::de::Error),
+    /// A device flag is missing its value or has one that doesn't parse.
+    Flag(String),
+}
+
+impl fmt::Display for DeviceConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceConfigError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            DeviceConfigError::Parse(path, e) => write!(f, "invalid device config {}: {e}", path.display()),
+            DeviceConfigError::Flag(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DeviceConfigError {}
+
+impl DeviceConfig {
+    /// Parses a config file's contents over the defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeviceConfigError::Parse`] (naming `path`) if `text`
+    /// isn't a valid device config.
+    pub fn parse(text: &str, path: &Path) -> Result<Self, DeviceConfigError> {
+        toml::from_str(text).map_err(|e| DeviceConfigError::Parse(path.to_path_buf(), e))
+    }
+
+    /// Reads and parses the config file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeviceConfigError::Io`] if the file can't be read, or
+    /// [`DeviceConfigError::Parse`] if it isn't a valid device config.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DeviceConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| DeviceConfigError::Io(path.to_path_buf(), e))?;
+        Self::parse(&text, path)
+    }
+
+    /// Resolves the device from the command line (`args`, without the
+    /// program name): the `--config` file if given, else the defaults,
+    /// then each device flag over that. Flags this doesn't know are left
+    /// alone for the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DeviceConfigError`] if the config file can't be loaded,
+    /// or a device flag is missing its value or has a bad one.
+    pub fn from_args(args: &[String]) -> Result<Self, DeviceConfigError> {
+        let value = |flag: &str| -> Result<Option<&str>, DeviceConfigError> {
+            match args.iter().position(|a| a == flag) {
+                None => Ok(None),
+                Some(i) => args
+                    .get(i + 1)
+                    .map(|v| Some(v.as_str()))
+                    .ok_or_else(|| DeviceConfigError::Flag(format!("{flag} expects a value"))),
+            }
+        };
+
+        let mut config = match value("--config")? {
+            Some(path) => Self::load(path)?,
+            None => Self::default(),
+        };
+        if let Some(name) = value("--name")? {
+            config.name = name.to_string();
+        }
+        if let Some(port) = value("--port")? {
+            config.port = port.parse().map_err(|_| DeviceConfigError::Flag(format!("--port expects a port number, got {port:?}")))?;
+        }
+        if let Some(dir) = value("--data-dir")? {
+            config.data_dir = PathBuf::from(dir);
+        }
+        if let Some(profile) = value("--profile")? {
+            config.profile = PanelProfile::parse(profile).ok_or_else(|| DeviceConfigError::Flag(unknown_profile(profile)))?;
+        }
+        if let Some(panel) = value("--panel")? {
+            config.profile = PanelProfile::custom(panel)
+                .ok_or_else(|| DeviceConfigError::Flag(format!("--panel expects WIDTHxHEIGHT (e.g. 128x32), got {panel:?}")))?;
+        }
+        Ok(config)
+    }
+
+    /// The address the HTTP server binds: loopback only, as ever.
+    #[must_use]
+    pub fn bind_addr(&self) -> String {
+        format!("127.0.0.1:{}", self.port)
+    }
+
+    /// Where this device's pushed credentials live (`DesktopStorage`).
+    #[must_use]
+    pub fn credentials_path(&self) -> PathBuf {
+        self.data_dir.join("credentials.json")
+    }
+
+    /// Where this device's settings live (`platform::FileStorage`).
+    #[must_use]
+    pub fn kv_store_path(&self) -> PathBuf {
+        self.data_dir.join("kv_store.bin")
+    }
+
+    /// What `/api/status` reports, with the port the server actually
+    /// bound (which differs from `self.port` when that was `0`).
+    #[must_use]
+    pub fn descriptor(&self, bound_port: u16) -> DeviceDescriptor {
+        DeviceDescriptor {
+            name: self.name.clone(),
+            profile: self.profile.name.clone(),
+            panel: self.profile.size(),
+            port: bound_port,
+            data_dir: self.data_dir.display().to_string(),
+        }
+    }
+}
+
+/// The `device` object in `GET /api/status`: enough for a companion to
+/// list several emulators by name and panel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeviceDescriptor {
+    pub name: String,
+    /// The [`PanelProfile`]'s name (`"custom"` for `--panel`).
+    pub profile: String,
+    /// `[width, height]`.
+    pub panel: (u32, u32),
+    pub port: u16,
+    pub data_dir: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn config_file(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("bhk-device-config-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn no_flags_is_the_one_t_embed_desktop_has_always_been() {
+        let config = DeviceConfig::from_args(&args("--headless")).unwrap();
+        assert_eq!(config, DeviceConfig::default());
+        assert_eq!(config.bind_addr(), "127.0.0.1:8080");
+        assert_eq!(config.profile.size(), (320, 170));
+        assert_eq!(config.credentials_path(), Path::new("./data/credentials.json"));
+    }
+
+    #[test]
+    fn profiles_are_presets_or_custom_sizes() {
+        assert_eq!(PanelProfile::parse("ssd1306").unwrap().size(), (128, 32));
+        assert_eq!(PanelProfile::parse("t-embed-portrait").unwrap().size(), (170, 320));
+        let custom = PanelProfile::parse("200X100").unwrap();
+        assert_eq!((custom.name.as_str(), custom.size()), ("custom", (200, 100)));
+        assert!(PanelProfile::parse("0x32").is_none());
+        assert!(PanelProfile::parse("st7735").is_none());
+    }
+
+    #[test]
+    fn flags_override_the_config_file_which_overrides_the_defaults() {
+        let path = config_file("name = \"Strip key\"\nport = 8081\nprofile = \"ssd1306\"\n");
+        let line = format!("--config {} --port 8082 --data-dir data/strip", path.display());
+        let config = DeviceConfig::from_args(&args(&line)).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(config.name, "Strip key");
+        assert_eq!(config.port, 8082);
+        assert_eq!(config.kv_store_path(), Path::new("data/strip/kv_store.bin"));
+        assert_eq!(config.profile, PanelProfile::named("ssd1306").unwrap());
+
+        let descriptor = config.descriptor(8082);
+        assert_eq!(
+            serde_json::to_value(&descriptor).unwrap(),
+            serde_json::json!({
+                "name": "Strip key",
+                "profile": "ssd1306",
+                "panel": [128, 32],
+                "port": 8082,
+                "data_dir": "data/strip",
+            })
+        );
+    }
+
+    #[test]
+    fn bad_config_and_flags_are_errors_naming_the_problem() {
+        let path = config_file("profile = \"st7735\"\n");
+        let message = DeviceConfig::load(&path).unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(message.contains("unknown panel profile \"st7735\""), "{message}");
+        assert!(message.contains("ssd1306"), "{message}");
+
+        assert!(DeviceConfig::parse("colour = \"red\"", Path::new("x.toml")).is_err());
+        assert!(DeviceConfig::from_args(&args("--port http")).is_err());
+        assert!(DeviceConfig::from_args(&args("--name")).is_err());
+        assert!(DeviceConfig::from_args(&args("--panel 128by32")).is_err());
+    }
+}
@@ -1,9 +1,14 @@
-use crate::desktop::DesktopStorage;
-use crate::platform::HeadlessSurface;
+use crate::desktop::link;
+use crate::desktop::{DesktopStorage, DeviceDescriptor};
+use crate::faults::{Faults, SharedFaults};
+use crate::platform::{HeadlessSurface, SharedRecorder, VirtualClock};
 use bhk_core::input::NavIntent;
+use bhk_core::render::SemanticNode;
+use device_link::SyncNack;
 use push_protocol::{Credential, SyncRequest, SyncResponse};
 use std::collections::VecDeque;
 use std::error::Error;
+use std::io::Cursor;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -23,6 +28,39 @@ pub struct SyncServer {
     /// set via `set_screenshot_surface` before the server starts handling
     /// requests in headless mode. See `emulator::platform::headless_surface::SharedHeadlessSurface`.
     screenshot_surface: Option<Arc<Mutex<HeadlessSurface>>>,
+    /// The clock `POST /api/time/advance` moves, if the render loop runs
+    /// on virtual time (`desktop --headless --virtual-time`); `None`
+    /// otherwise, and the endpoint responds 404. Set via
+    /// `set_virtual_clock`, under the same before-starting rule as
+    /// `screenshot_surface`.
+    virtual_clock: Option<VirtualClock>,
+    /// The tree `GET /api/ui-tree` serves, republished by the headless
+    /// loop after every frame (see `emulator::desktop::ui_tree`). `None`
+    /// in windowed mode, where the endpoint responds 404; set via
+    /// `set_ui_tree` under the same before-starting rule as
+    /// `screenshot_surface`.
+    ui_tree: Option<Arc<Mutex<Option<SemanticNode>>>>,
+    /// The recorder `POST /api/record/start|stop` drives, fed by the
+    /// loop's `RecordingSurface` (see `emulator::platform::recording`).
+    /// `None` until `set_recorder`, and the endpoints respond 404.
+    recorder: Option<SharedRecorder>,
+    /// Which emulated device this is, reported as `device` by `GET
+    /// /api/status` so a companion can tell several emulators apart (see
+    /// `emulator::desktop::device`). `null` until `set_device`.
+    device: Option<DeviceDescriptor>,
+    /// What's broken on purpose, set through `/api/faults` (see
+    /// `emulator::faults`). Healthy until then.
+    faults: SharedFaults,
+    /// Why the last push failed, or `None` once one succeeds. Read by
+    /// `PushSyncSource::with_sync_errors`, which is how a failed push
+    /// becomes `SyncStatus::Error` on screen.
+    sync_error: Arc<Mutex<Option<String>>>,
+}
+
+/// `POST /api/time/advance`'s body.
+#[derive(serde::Deserialize)]
+struct TimeAdvance {
+    ms: u64,
 }
 
 impl SyncServer {
@@ -46,6 +84,12 @@ impl SyncServer {
             should_shutdown: Arc::new(AtomicBool::new(false)),
             input_queue: Arc::new(Mutex::new(VecDeque::new())),
             screenshot_surface: None,
+            virtual_clock: None,
+            ui_tree: None,
+            recorder: None,
+            device: None,
+            faults: SharedFaults::new(),
+            sync_error: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -77,6 +121,50 @@ impl SyncServer {
         self.screenshot_surface = Some(surface);
     }
 
+    /// Registers the `VirtualClock` the render loop paces itself by, which
+    /// turns on `POST /api/time/advance`. Only meaningful with
+    /// `--virtual-time`; like `set_screenshot_surface`, must be called
+    /// before the server starts handling requests.
+    pub fn set_virtual_clock(&mut self, clock: VirtualClock) {
+        self.virtual_clock = Some(clock);
+    }
+
+    /// Registers the tree slot `GET /api/ui-tree` reads from (a
+    /// `SharedUiTree::handle`). Headless only, and like
+    /// `set_screenshot_surface`, must be called before the server starts
+    /// handling requests.
+    pub fn set_ui_tree(&mut self, tree: Arc<Mutex<Option<SemanticNode>>>) {
+        self.ui_tree = Some(tree);
+    }
+
+    /// Registers the recorder `/api/record/*` starts and stops. Both run
+    /// modes have one; like `set_screenshot_surface`, must be called
+    /// before the server starts handling requests.
+    pub fn set_recorder(&mut self, recorder: SharedRecorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Hands out the faults `/api/faults` sets, for a `FileStorage` to
+    /// fail its writes by (`FileStorage::with_faults`).
+    #[must_use]
+    pub fn get_faults_ref(&self) -> SharedFaults {
+        self.faults.clone()
+    }
+
+    /// Hands out the slot holding why the last push failed, for
+    /// `PushSyncSource::with_sync_errors`.
+    #[must_use]
+    pub fn get_sync_error_ref(&self) -> Arc<Mutex<Option<String>>> {
+        self.sync_error.clone()
+    }
+
+    /// Registers the descriptor `GET /api/status` reports. Like
+    /// `set_screenshot_surface`, must be called before the server starts
+    /// handling requests.
+    pub fn set_device(&mut self, device: DeviceDescriptor) {
+        self.device = Some(device);
+    }
+
     /// The address the server actually bound to. Useful when binding to
     /// port 0 (an ephemeral port), e.g. in tests that don't want to
     /// hardcode/collide on 8080.
@@ -96,10 +184,18 @@ impl SyncServer {
 
         match (request.method(), request.url()) {
             (&Method::Post, "/api/sync") => self.handle_sync(request),
+            (&Method::Post, "/api/link") => self.handle_link(request),
+            (&Method::Get, "/api/faults") => self.handle_get_faults(request),
+            (&Method::Put, "/api/faults") => self.handle_set_faults(request),
+            (&Method::Delete, "/api/faults") => self.handle_clear_faults(request),
             (&Method::Get, "/api/status") => self.handle_status(request),
             (&Method::Post, "/api/clear") => self.handle_clear(request),
             (&Method::Post, "/api/input") => self.handle_input(request),
             (&Method::Get, "/api/screenshot") => self.handle_screenshot(request),
+            (&Method::Get, "/api/ui-tree") => self.handle_ui_tree(request),
+            (&Method::Post, "/api/record/start") => self.handle_record_start(request),
+            (&Method::Post, "/api/record/stop") => self.handle_record_stop(request),
+            (&Method::Post, "/api/time/advance") => self.handle_time_advance(request),
             (&Method::Post, "/api/shutdown") => self.handle_shutdown(request),
             _ => request
                 .respond(Response::from_string("Not Found").with_status_code(StatusCode(404)))
@@ -107,25 +203,40 @@ impl SyncServer {
         }
     }
 
+    /// `POST /api/sync`: replaces the vault with the CBOR `SyncRequest`
+    /// body. Any `/api/faults` apply first: the delay, then an injected
+    /// status (the push is refused as is) or a damaged body. A push that
+    /// fails for any reason is answered with an error status and recorded
+    /// for `PushSyncSource` (see `record_push_failure`).
     fn handle_sync(&self, mut request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
-        // Parse CBOR body
-        let sync_req: SyncRequest = ciborium::from_reader(request.as_reader())?;
-
-        let total_bytes = request.body_length().unwrap_or(0);
+        let faults = self.faults.current();
+        hold(&faults);
+        if let Some(status) = faults.sync_status {
+            let reason = format!("Push rejected ({status})");
+            self.record_push_failure(&reason);
+            return request.respond(json_response(status, &failure_body(&reason))).map_err(Into::into);
+        }
 
-        // Store credentials in memory
-        let mut creds = self.credentials.lock().unwrap();
-        *creds = sync_req.credentials;
-        let synced = creds.len();
+        let mut body = Vec::new();
+        request.as_reader().read_to_end(&mut body)?;
+        let total_bytes = body.len();
+        if let Some(fault) = faults.sync_body {
+            fault.apply(&mut body);
+        }
 
-        // Persist to storage
-        self.storage
-            .lock()
-            .unwrap()
-            .save(&creds)
-            .map_err(|e| format!("Failed to save credentials: {}", e))?;
+        let sync_req: SyncRequest = match ciborium::from_reader(body.as_slice()) {
+            Ok(sync_req) => sync_req,
+            Err(e) => {
+                eprintln!("Rejected push: invalid CBOR: {e}");
+                self.record_push_failure("Push unreadable");
+                return request.respond(json_response(400, &failure_body("Push unreadable"))).map_err(Into::into);
+            }
+        };
 
-        drop(creds);
+        let synced = match self.accept_push(sync_req.credentials) {
+            Ok(synced) => synced,
+            Err(reason) => return request.respond(json_response(500, &failure_body(&reason))).map_err(Into::into),
+        };
 
         // Respond with JSON
         let response = SyncResponse {
@@ -148,6 +259,104 @@ impl SyncServer {
             .map_err(|e| e.into())
     }
 
+    /// Saves and applies a received push, clearing any recorded failure.
+    /// Saving comes first, so a push that can't be persisted (including an
+    /// injected storage fault) leaves the vault as it was.
+    ///
+    /// # Errors
+    ///
+    /// The failure reason (also recorded) if the push couldn't be saved.
+    fn accept_push(&self, credentials: Vec<Credential>) -> Result<usize, String> {
+        let saved = if self.faults.storage_writes_fail() {
+            Err("injected fault".into())
+        } else {
+            self.storage.lock().unwrap().save(&credentials)
+        };
+        if let Err(e) = saved {
+            eprintln!("Failed to save credentials: {e}");
+            let reason = "Push not saved".to_string();
+            self.record_push_failure(&reason);
+            return Err(reason);
+        }
+
+        let synced = credentials.len();
+        *self.credentials.lock().unwrap() = credentials;
+        *self.sync_error.lock().unwrap() = None;
+        Ok(synced)
+    }
+
+    /// Records why a push failed, for `PushSyncSource` to report as a sync
+    /// error. Kept short: it's the subline of the device's "Sync error"
+    /// screen.
+    fn record_push_failure(&self, reason: &str) {
+        *self.sync_error.lock().unwrap() = Some(reason.to_string());
+    }
+
+    /// `POST /api/link`: a sync over device-link framing (see
+    /// `desktop::link`). The body is the companion's wire bytes; the
+    /// response is always 200 with the device's reply frames, because on
+    /// a serial link the refusal travels as a `SyncNack`, not a status.
+    /// The fault delay applies, and the link faults drop or corrupt frames
+    /// before they're decoded.
+    fn handle_link(&self, mut request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        let faults = self.faults.current();
+        hold(&faults);
+        let mut wire = Vec::new();
+        request.as_reader().read_to_end(&mut wire)?;
+        let wire = link::inject(&wire, &faults.link_drop_frames, &faults.link_corrupt_frames);
+
+        let outcome = match link::receive(&wire) {
+            Ok(sync_req) => self
+                .accept_push(sync_req.credentials)
+                .map(|synced| SyncResponse { status: "success".to_string(), synced, total_bytes: wire.len() })
+                .map_err(|reason| SyncNack { code: link::nack::NOT_SAVED, message: reason }),
+            Err(nack) => {
+                eprintln!("Refused link sync: {}", nack.message);
+                self.record_push_failure(if nack.code == link::nack::UNDECODABLE { "Push unreadable" } else { "Transfer damaged" });
+                Err(nack)
+            }
+        };
+
+        request
+            .respond(
+                Response::from_data(link::reply(&outcome))
+                    .with_header("Content-Type: application/octet-stream".parse::<Header>().unwrap()),
+            )
+            .map_err(Into::into)
+    }
+
+    /// `GET /api/faults`: the faults currently injected, as JSON (see
+    /// `emulator::faults::Faults`).
+    fn handle_get_faults(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        request.respond(json_response(200, &self.faults.current())).map_err(Into::into)
+    }
+
+    /// `PUT /api/faults`: replaces the injected faults with the body's
+    /// `Faults` JSON; fields left out are healthy, so `{}` heals
+    /// everything. 400, with the faults unchanged, if the body isn't
+    /// valid.
+    fn handle_set_faults(&self, mut request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        let parsed = serde_json::from_reader::<_, Faults>(request.as_reader())
+            .map_err(|e| e.to_string())
+            .and_then(|faults| faults.validate().map(|()| faults));
+        match parsed {
+            Ok(faults) => {
+                println!("Faults injected: {}", serde_json::to_string(&faults)?);
+                self.faults.set(faults.clone());
+                request.respond(json_response(200, &serde_json::json!({ "status": "success", "faults": faults })))
+            }
+            Err(message) => request.respond(json_response(400, &failure_body(&message))),
+        }
+        .map_err(Into::into)
+    }
+
+    /// `DELETE /api/faults`: heals everything.
+    fn handle_clear_faults(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        self.faults.clear();
+        println!("Faults cleared");
+        request.respond(json_response(200, &serde_json::json!({ "status": "success" }))).map_err(Into::into)
+    }
+
     fn handle_status(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
         let creds = self.credentials.lock().unwrap();
         let count = creds.len();
@@ -156,6 +365,9 @@ impl SyncServer {
         let status = serde_json::json!({
             "status": "running",
             "credential_count": count,
+            "device": self.device,
+            "sync_error": *self.sync_error.lock().unwrap(),
+            "faults": self.faults.current(),
         });
 
         request
@@ -207,9 +419,10 @@ impl SyncServer {
     /// half of the headless testability protocol (see
     /// `.planning/decisions/2026-08-11-three-mode-testability.md`). Body
     /// is the JSON form of `bhk_core::input::NavIntent`'s derived
-    /// `Deserialize`: a bare string for the unit variants (e.g. `"Next"`,
-    /// `"Prev"`, `"Activate"`, `"Back"`) or `{"NextN":5}` for the one
-    /// tuple variant.
+    /// `Deserialize`: a bare string for the unit variants (`"Next"`,
+    /// `"Prev"`, `"Home"`, `"End"`, `"Activate"`, `"Back"`), or a
+    /// one-key object for the rest: `{"NextN":5}` / `{"PrevN":5}` for a
+    /// jump, `{"Char":"g"}` for one type-ahead character.
     fn handle_input(&self, mut request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
         let intent: NavIntent = serde_json::from_reader(request.as_reader())?;
         self.input_queue.lock().unwrap().push_back(intent);
@@ -261,6 +474,130 @@ impl SyncServer {
         }
     }
 
+    /// `POST /api/record/start`: starts recording every flushed frame.
+    /// 409 if a recording (including `--record`'s) is already running;
+    /// 404 if no recorder is registered.
+    fn handle_record_start(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        let Some(recorder) = &self.recorder else {
+            return request.respond(json_response(404, &failure_body("Recording unavailable"))).map_err(Into::into);
+        };
+        if recorder.start() {
+            println!("Recording started");
+            request.respond(json_response(200, &serde_json::json!({ "status": "recording" }))).map_err(Into::into)
+        } else {
+            request.respond(json_response(409, &failure_body("Already recording"))).map_err(Into::into)
+        }
+    }
+
+    /// `POST /api/record/stop`: stops the recording and responds with it
+    /// as an animated PNG (`image/apng`), each frame lasting until the
+    /// next. `X-Recording-Truncated: true` if it reached its byte budget
+    /// and ended early (see `emulator::platform::recording`). 409 if
+    /// nothing is recording, or if nothing was ever rendered to record;
+    /// 404 if no recorder is registered.
+    fn handle_record_stop(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        let Some(recorder) = &self.recorder else {
+            return request.respond(json_response(404, &failure_body("Recording unavailable"))).map_err(Into::into);
+        };
+        let Some(recording) = recorder.stop() else {
+            return request.respond(json_response(409, &failure_body("Not recording"))).map_err(Into::into);
+        };
+        let Some(apng) = recording.encode_apng() else {
+            return request.respond(json_response(409, &failure_body("No frame rendered while recording"))).map_err(Into::into);
+        };
+        println!("Recording stopped: {} frame(s){}", recording.frames().len(), if recording.truncated() { ", truncated" } else { "" });
+        let truncated = format!("X-Recording-Truncated: {}", recording.truncated());
+        request
+            .respond(
+                Response::from_data(apng)
+                    .with_header("Content-Type: image/apng".parse::<Header>().unwrap())
+                    .with_header(truncated.parse::<Header>().unwrap()),
+            )
+            .map_err(Into::into)
+    }
+
+    /// `GET /api/ui-tree`: the semantic tree of what's on screen (see
+    /// `bhk_core::render::semantics`) as of the last frame, as JSON — each
+    /// node's `role`, `label`, `value`, `focused`, `selected` and
+    /// `children`. Lets a test or agent assert "the focused row is
+    /// GitHub" without reading pixels. A hidden password's value is its
+    /// mask. Responds 404 if no tree is registered (not running headless)
+    /// and 503 before the first frame.
+    fn handle_ui_tree(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        let Some(tree) = &self.ui_tree else {
+            return request
+                .respond(
+                    Response::from_string("UI tree unavailable: not running in headless mode")
+                        .with_status_code(StatusCode(404)),
+                )
+                .map_err(Into::into);
+        };
+
+        let json = match &*tree.lock().unwrap() {
+            Some(tree) => serde_json::to_string(tree)?,
+            None => {
+                return request
+                    .respond(Response::from_string("No frame rendered yet").with_status_code(StatusCode(503)))
+                    .map_err(Into::into);
+            }
+        };
+
+        request
+            .respond(
+                Response::from_string(json)
+                    .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+                    .with_header(
+                        "Access-Control-Allow-Origin: http://localhost:4200"
+                            .parse::<Header>()
+                            .unwrap(),
+                    ),
+            )
+            .map_err(Into::into)
+    }
+
+    /// `POST /api/time/advance`: moves the virtual clock forward by the
+    /// body's `{"ms": N}`, one frame deadline at a time, and responds only
+    /// once the render loop has run every frame in that span (see
+    /// `VirtualClock::advance_stepped`). A screenshot taken after the
+    /// response is therefore the frame at the new time, which is what
+    /// makes timeout and animation checks over HTTP reproducible. Responds
+    /// 404 without `--virtual-time`, where time is the wall clock's.
+    ///
+    /// Requests are served one at a time, so a long advance holds up
+    /// anything sent meanwhile until it's done.
+    fn handle_time_advance(&self, mut request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
+        let Some(clock) = &self.virtual_clock else {
+            return request
+                .respond(
+                    Response::from_string("Time control unavailable: not running with --virtual-time")
+                        .with_status_code(StatusCode(404)),
+                )
+                .map_err(Into::into);
+        };
+
+        let advance: TimeAdvance = serde_json::from_reader(request.as_reader())?;
+        let frames = clock.advance_stepped(std::time::Duration::from_millis(advance.ms));
+
+        let response = serde_json::json!({
+            "status": "success",
+            "advanced_ms": advance.ms,
+            "elapsed_ms": u64::try_from(clock.elapsed().as_millis()).unwrap_or(u64::MAX),
+            "frames": frames,
+        });
+
+        request
+            .respond(
+                Response::from_string(response.to_string())
+                    .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+                    .with_header(
+                        "Access-Control-Allow-Origin: http://localhost:4200"
+                            .parse::<Header>()
+                            .unwrap(),
+                    ),
+            )
+            .map_err(Into::into)
+    }
+
     fn handle_shutdown(&self, request: tiny_http::Request) -> Result<(), Box<dyn Error>> {
         // Signal shutdown
         self.should_shutdown.store(true, Ordering::Relaxed);
@@ -287,3 +624,26 @@ impl SyncServer {
         self.credentials.lock().unwrap().clone()
     }
 }
+
+/// Sleeps out `faults.sync_delay_ms` on the server thread (real time, even
+/// under `--virtual-time`: it stands in for a slow link, not for the
+/// device's clock).
+fn hold(faults: &Faults) {
+    if faults.sync_delay_ms > 0 {
+        std::thread::sleep(std::time::Duration::from_millis(faults.sync_delay_ms));
+    }
+}
+
+/// The JSON body of a refused request.
+fn failure_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "status": "error", "message": message })
+}
+
+/// A JSON response with `status` and the same CORS header every endpoint
+/// sends.
+fn json_response(status: u16, body: &impl serde::Serialize) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(serde_json::to_string(body).expect("response bodies always serialize"))
+        .with_status_code(StatusCode(status))
+        .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+        .with_header("Access-Control-Allow-Origin: http://localhost:4200".parse::<Header>().unwrap())
+}
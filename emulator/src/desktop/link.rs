@@ -0,0 +1,210 @@
+//! The device side of device-link's sync flow (`SyncBegin`, a `MORE`-linked
+//! run of `SyncChunk`s, `SyncEnd` with the whole blob's CRC32), served over
+//! HTTP as `POST /api/link`: the body is the bytes a companion would write
+//! to the T-Embed's USB serial port, and the response body is the bytes
+//! the device would write back — a `SyncAck` or a `SyncNack` frame.
+//!
+//! The emulator has no serial port, so this is where the link layer's
+//! failure handling gets exercised on host: `faults::Faults` can drop or
+//! corrupt individual frames on the way in ([`inject`]), and [`receive`]
+//! has to notice — a corrupt frame fails its CRC and is skipped by the
+//! decoder, a missing chunk leaves the blob short or its CRC wrong — and
+//! refuse the push rather than apply half a vault.
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+use device_link::frame::CRC_LEN;
+use device_link::{encode_frame, from_cbor, to_cbor, Decoder, MessageType, Reassembler, SyncBegin, SyncEnd, SyncNack, HEADER_LEN};
+use push_protocol::{SyncRequest, SyncResponse};
+
+/// `SyncNack::code`s this device sends.
+pub mod nack {
+    /// The sequence never finished: no `SyncBegin`, no `SyncEnd`, or the
+    /// chunk run was cut off.
+    pub const INCOMPLETE: u16 = 1;
+    /// The reassembled blob isn't the size `SyncBegin` announced, or its
+    /// CRC32 isn't the one `SyncEnd` carried.
+    pub const BLOB_MISMATCH: u16 = 2;
+    /// The blob arrived intact but isn't a CBOR `SyncRequest`.
+    pub const UNDECODABLE: u16 = 3;
+    /// The push was received but couldn't be saved.
+    pub const NOT_SAVED: u16 = 4;
+}
+
+fn refusal(code: u16, message: impl Into<String>) -> SyncNack {
+    SyncNack { code, message: message.into() }
+}
+
+/// Splits `wire` at frame boundaries, reading each header's length rather
+/// than validating anything, so a fault can target "frame N". Bytes that
+/// don't start a frame (or a frame cut short) end up in a last, partial
+/// piece.
+fn frames(wire: &[u8]) -> Vec<&[u8]> {
+    let mut pieces = Vec::new();
+    let mut rest = wire;
+    while rest.len() >= HEADER_LEN && rest[..2] == device_link::MAGIC {
+        let len = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let end = (HEADER_LEN + len + CRC_LEN).min(rest.len());
+        let (frame, tail) = rest.split_at(end);
+        pieces.push(frame);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+/// `wire` with the frames at `drop` removed and the ones at `corrupt`
+/// damaged: the first payload byte (or, for an empty payload, the CRC)
+/// inverted, which the frame's CRC32 always catches.
+#[must_use]
+pub fn inject(wire: &[u8], drop: &[usize], corrupt: &[usize]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(wire.len());
+    for (index, frame) in frames(wire).into_iter().enumerate() {
+        if drop.contains(&index) {
+            continue;
+        }
+        let start = out.len();
+        out.extend_from_slice(frame);
+        if corrupt.contains(&index) {
+            if let Some(byte) = out.get_mut(start + HEADER_LEN) {
+                *byte ^= 0xFF;
+            }
+        }
+    }
+    out
+}
+
+/// Decodes one sync sequence from `wire`, checking it the way the
+/// firmware's receiver must: frames that fail their CRC are skipped, and
+/// the reassembled blob has to match both the size `SyncBegin` announced
+/// and the CRC32 `SyncEnd` carried before it's decoded.
+///
+/// # Errors
+///
+/// The `SyncNack` to send back, with a [`nack`] code, if the sequence is
+/// incomplete, the blob doesn't match, or it doesn't decode.
+pub fn receive(wire: &[u8]) -> Result<SyncRequest, SyncNack> {
+    const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+    let mut decoder = Decoder::new();
+    decoder.feed(wire);
+    let mut begin: Option<SyncBegin> = None;
+    let mut blob = Reassembler::new();
+    let mut end: Option<SyncEnd> = None;
+    let mut rejected = 0usize;
+    while let Some(decoded) = decoder.poll() {
+        let Ok(frame) = decoded else {
+            rejected += 1;
+            continue;
+        };
+        match frame.msg_type {
+            MessageType::SyncBegin => {
+                begin = from_cbor(&frame.payload).ok();
+                blob = Reassembler::new();
+            }
+            MessageType::SyncChunk => blob.push(&frame.payload, frame.more()),
+            MessageType::SyncEnd => end = from_cbor(&frame.payload).ok(),
+            _ => {}
+        }
+    }
+
+    let skipped = if rejected > 0 { format!(" ({rejected} bad frame(s) skipped)") } else { String::new() };
+    let (Some(begin), Some(end)) = (begin, end) else {
+        return Err(refusal(nack::INCOMPLETE, format!("sync sequence incomplete{skipped}")));
+    };
+    let Some(blob) = blob.finish() else {
+        return Err(refusal(nack::INCOMPLETE, format!("chunk run cut off{skipped}")));
+    };
+    if blob.len() != begin.total_bytes as usize || CRC.checksum(&blob) != end.crc32_of_whole_blob {
+        return Err(refusal(nack::BLOB_MISMATCH, format!("blob doesn't match SyncBegin/SyncEnd{skipped}")));
+    }
+    from_cbor(&blob).map_err(|e| refusal(nack::UNDECODABLE, format!("blob isn't a SyncRequest: {e}")))
+}
+
+/// The frames the device writes back: `SyncAck` carrying `response`, or
+/// `SyncNack` carrying `refusal`.
+///
+/// # Panics
+///
+/// Never in practice: both payloads are a few dozen bytes of CBOR, well
+/// under `device_link::MAX_PAYLOAD_LEN`.
+#[must_use]
+pub fn reply(outcome: &Result<SyncResponse, SyncNack>) -> Vec<u8> {
+    let (msg_type, payload) = match outcome {
+        Ok(response) => (MessageType::SyncAck, to_cbor(response)),
+        Err(refusal) => (MessageType::SyncNack, to_cbor(refusal)),
+    };
+    encode_frame(msg_type, 0, &payload.expect("reply payloads always encode")).expect("reply payloads fit in one frame")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use device_link::encode_chunks;
+    use push_protocol::Credential;
+
+    /// The wire bytes of a full sync of `request`, in `chunk_size`-byte
+    /// chunks: what `companion`'s serial transport writes.
+    fn sync_wire(request: &SyncRequest, chunk_size: usize) -> Vec<u8> {
+        const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+        let blob = to_cbor(request).unwrap();
+        let begin = SyncBegin { total_bytes: blob.len() as u32, item_count: request.credentials.len() as u32 };
+        let mut wire = encode_frame(MessageType::SyncBegin, 0, &to_cbor(&begin).unwrap()).unwrap();
+        for frame in encode_chunks(MessageType::SyncChunk, &blob, chunk_size).unwrap() {
+            wire.extend(frame);
+        }
+        let end = SyncEnd { crc32_of_whole_blob: CRC.checksum(&blob) };
+        wire.extend(encode_frame(MessageType::SyncEnd, 0, &to_cbor(&end).unwrap()).unwrap());
+        wire
+    }
+
+    fn request() -> SyncRequest {
+        SyncRequest {
+            credentials: (0..4)
+                .map(|i| Credential {
+                    id: uuid::Uuid::from_u128(i + 1),
+                    name: format!("Service {i}"),
+                    username: format!("user{i}"),
+                    password: "hunter2".to_string(),
+                    uri: None,
+                    notes: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn a_clean_sequence_is_received_whole() {
+        let wire = sync_wire(&request(), 32);
+        assert!(frames(&wire).len() > 4, "the request should need several chunks");
+        assert_eq!(receive(&wire).unwrap().credentials.len(), 4);
+        assert_eq!(inject(&wire, &[], &[]), wire);
+    }
+
+    #[test]
+    fn a_dropped_or_corrupted_chunk_is_refused_not_half_applied() {
+        let wire = sync_wire(&request(), 32);
+
+        let dropped = receive(&inject(&wire, &[2], &[])).unwrap_err();
+        assert_eq!(dropped.code, nack::BLOB_MISMATCH, "{}", dropped.message);
+
+        let corrupted = receive(&inject(&wire, &[], &[2])).unwrap_err();
+        assert_eq!(corrupted.code, nack::BLOB_MISMATCH);
+        assert!(corrupted.message.contains("1 bad frame(s) skipped"), "{}", corrupted.message);
+
+        let last = frames(&wire).len() - 1;
+        let no_end = receive(&inject(&wire, &[last], &[])).unwrap_err();
+        assert_eq!(no_end.code, nack::INCOMPLETE);
+    }
+
+    #[test]
+    fn replies_are_one_ack_or_nack_frame() {
+        let mut decoder = Decoder::new();
+        decoder.feed(&reply(&Err(refusal(nack::NOT_SAVED, "disk full"))));
+        let frame = decoder.poll().unwrap().unwrap();
+        assert_eq!(frame.msg_type, MessageType::SyncNack);
+        assert_eq!(from_cbor::<SyncNack>(&frame.payload).unwrap().code, nack::NOT_SAVED);
+        assert!(decoder.poll().is_none());
+    }
+}
@@ -6,10 +6,15 @@
 // from `platform::FileStorage`'s opaque KV blob store, so nothing in this
 // bead's platform work actually replaces it.
 
+pub mod device;
 pub mod http_server;
+pub mod link;
 pub mod push_sync_source;
 pub mod storage;
+pub mod ui_tree;
 
+pub use device::{DeviceConfig, DeviceDescriptor, PanelProfile};
 pub use http_server::SyncServer;
 pub use push_sync_source::PushSyncSource;
 pub use storage::DesktopStorage;
+pub use ui_tree::{run_publishing, SharedUiTree};
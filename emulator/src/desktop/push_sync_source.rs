@@ -12,21 +12,42 @@
 //! (`ToVaultItem::to_vault_item`, defined in `crate::credentials`) at the
 //! boundary.
 //!
-//! `sync()` never actually fails today (reading a shared `Vec` behind a
-//! `Mutex` has no failure mode other than a poisoned lock, which would
-//! indicate a prior panic elsewhere and is not something this type can
-//! meaningfully recover from), so `Error = Infallible`.
+//! `sync()` fails when the companion's last push did: `SyncServer`
+//! records why in a second shared slot (`get_sync_error_ref()`) whenever a
+//! push is rejected — unreadable, refused by an injected fault, or not
+//! saved — and clears it when one succeeds. Reading that slot through
+//! [`PushSyncSource::with_sync_errors`] is what turns a failed push into
+//! `SyncStatus::Error` on screen (with the last-known-good list kept, per
+//! `App::step`), and the next good push into recovery. Reading the
+//! shared `Vec` itself can't fail short of a poisoned lock, which would
+//! mean a panic elsewhere.
 
 use crate::credentials::ToVaultItem;
 use bhk_core::{SyncSource, VaultItem};
 use push_protocol::Credential;
-use std::convert::Infallible;
+use std::fmt;
 use std::sync::{Arc, Mutex};
 
 pub struct PushSyncSource {
     credentials: Arc<Mutex<Vec<Credential>>>,
+    /// Why the last push failed, if it did. `None` without
+    /// `with_sync_errors`, in which case `sync()` never fails.
+    sync_error: Option<Arc<Mutex<Option<String>>>>,
 }
 
+/// A failed push, as `sync()` reports it: the reason `SyncServer`
+/// recorded, shown as the sync error's subline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushFailed(pub String);
+
+impl fmt::Display for PushFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PushFailed {}
+
 impl PushSyncSource {
     /// Wrap the shared credential handle a `SyncServer` hands out via
     /// `get_credentials_ref()`. Cloning the `Arc` here means the
@@ -35,14 +56,25 @@ impl PushSyncSource {
     /// into the same `Mutex` concurrently).
     #[must_use]
     pub fn new(credentials: Arc<Mutex<Vec<Credential>>>) -> Self {
-        Self { credentials }
+        Self { credentials, sync_error: None }
+    }
+
+    /// Also reports the failure `SyncServer` records for a rejected push
+    /// (its `get_sync_error_ref()`), until a push succeeds.
+    #[must_use]
+    pub fn with_sync_errors(mut self, sync_error: Arc<Mutex<Option<String>>>) -> Self {
+        self.sync_error = Some(sync_error);
+        self
     }
 }
 
 impl SyncSource for PushSyncSource {
-    type Error = Infallible;
+    type Error = PushFailed;
 
     fn sync(&mut self) -> Result<Vec<VaultItem>, Self::Error> {
+        if let Some(message) = self.sync_error.as_ref().and_then(|slot| slot.lock().unwrap().clone()) {
+            return Err(PushFailed(message));
+        }
         let credentials = self.credentials.lock().unwrap();
         Ok(credentials.iter().map(Credential::to_vault_item).collect())
     }
@@ -103,6 +135,20 @@ mod tests {
         assert_eq!(items[0].name, "Newly Pushed");
     }
 
+    #[test]
+    fn a_recorded_push_failure_is_a_sync_error_until_it_is_cleared() {
+        let shared = Arc::new(Mutex::new(vec![credential("GitHub")]));
+        let failure = Arc::new(Mutex::new(None));
+        let mut source = PushSyncSource::new(shared).with_sync_errors(failure.clone());
+        assert_eq!(source.sync().unwrap().len(), 1);
+
+        *failure.lock().unwrap() = Some("Push rejected (503)".to_string());
+        assert_eq!(source.sync().unwrap_err(), PushFailed("Push rejected (503)".to_string()));
+
+        *failure.lock().unwrap() = None;
+        assert_eq!(source.sync().unwrap().len(), 1);
+    }
+
     #[test]
     fn sync_maps_all_credential_fields_onto_the_vault_item_view_model() {
         let cred = Credential {
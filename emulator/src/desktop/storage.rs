@@ -16,7 +16,14 @@ pub struct DesktopStorage {
 
 impl DesktopStorage {
     pub fn new() -> Result<Self, Box<dyn Error>> {
-        let file_path = PathBuf::from("./data/credentials.json");
+        Self::at("./data/credentials.json")
+    }
+
+    /// Storage backed by the file at `file_path`, e.g. one emulated
+    /// device's `DeviceConfig::credentials_path` when several run side by
+    /// side.
+    pub fn at(file_path: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let file_path = file_path.into();
 
         // Create data directory if it doesn't exist
         if let Some(parent) = file_path.parent() {
@@ -0,0 +1,70 @@
+//! `SharedUiTree`: the headless loop's latest `bhk_core::App::ui_tree`,
+//! shared with the HTTP server thread so `GET /api/ui-tree` can serve it —
+//! the semantic counterpart of `platform::SharedHeadlessSurface` and
+//! `GET /api/screenshot`. Where a screenshot shows the pixels, the tree
+//! says what they mean: which screen, which rows, which one has focus (see
+//! `bhk_core::render::semantics`).
+//!
+//! `App` isn't `Send` (its widgets share the vault store through `Rc`), so
+//! the HTTP thread can't ask it for a tree on demand. Instead the loop
+//! publishes a fresh copy after every frame ([`run_publishing`]), and the
+//! server hands out whichever copy is current — never more than one frame
+//! old, and under `--virtual-time` exactly current once an advance has
+//! returned, since the loop publishes before it parks.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bhk_core::platform::{Clock, DisplaySurface, Platform, Storage};
+use bhk_core::render::SemanticNode;
+use bhk_core::{App, FrameStepper, SyncSource};
+
+/// The most recently published UI tree, shared between the render loop
+/// and the HTTP server. `None` until the first frame has run.
+#[derive(Clone, Default)]
+pub struct SharedUiTree(Arc<Mutex<Option<SemanticNode>>>);
+
+impl SharedUiTree {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hands out a second owner of the same tree slot, for
+    /// `SyncServer::set_ui_tree`.
+    #[must_use]
+    pub fn handle(&self) -> Arc<Mutex<Option<SemanticNode>>> {
+        Arc::clone(&self.0)
+    }
+
+    /// Replaces the shared tree with `app`'s current one.
+    pub fn publish(&self, app: &App) {
+        let tree = app.ui_tree();
+        *self.0.lock().unwrap() = Some(tree);
+    }
+}
+
+/// `bhk_core::run` for headless mode: the same frames at the same pace
+/// (`FrameStepper::step`, then `Clock::sleep_until` the end of the frame
+/// budget), publishing `app`'s UI tree to `ui_tree` after each one.
+pub fn run_publishing<P: Platform, S: SyncSource>(
+    platform: &mut P,
+    app: &mut App,
+    sync: &mut S,
+    frame_budget: Duration,
+    ui_tree: &SharedUiTree,
+    mut should_continue: impl FnMut() -> bool,
+) where
+    S::Error: std::fmt::Display,
+    <P::Display as DisplaySurface>::Error: core::fmt::Debug,
+    <P::Storage as Storage>::Error: core::fmt::Debug,
+{
+    let mut stepper = FrameStepper::new(platform, app);
+
+    while should_continue() {
+        let frame_start = platform.clock().now();
+        stepper.step(platform, app, sync);
+        ui_tree.publish(app);
+        platform.clock().sleep_until(frame_start + frame_budget);
+    }
+}
@@ -0,0 +1,179 @@
+//! Fault injection: make the emulated device's sync path slow, flaky or
+//! corrupt on purpose, so the UI's handling of a bad sync
+//! (`bhk_core::SyncStatus::Error`, the last-known-good list, recovery on
+//! the next good push) can be tested against the real code paths instead
+//! of a hand-built error.
+//!
+//! One [`Faults`] value describes everything that's currently broken. It's
+//! set over HTTP (`PUT /api/faults` with its JSON, `DELETE /api/faults` to
+//! heal; see `desktop::SyncServer`) and read at each point it applies:
+//!
+//! - **`/api/sync`**: held for `sync_delay_ms` first, then answered with
+//!   `sync_status` instead of being applied, or decoded from a body damaged
+//!   by `sync_body` (cut short, or one byte inverted). The delay also holds
+//!   `/api/link`.
+//! - **device-link** (`POST /api/link`, see `desktop::link`): the frames
+//!   at the `link_drop_frames` indices never arrive, and the ones at
+//!   `link_corrupt_frames` arrive with a byte flipped, so the decoder's
+//!   CRC check throws them away. Indices count the frames of one request,
+//!   from 0.
+//! - **Storage**: with `fail_storage_writes`, saving a push to the
+//!   credential store fails, and so does every `platform::FileStorage`
+//!   write that shares these faults (the app's settings).
+//!
+//! Every failed push, injected or not, is recorded on the server and
+//! reported by `desktop::PushSyncSource` as a sync error until the next
+//! push succeeds, which is what puts the app into `SyncStatus::Error` and
+//! back out again. Faults stay until they're replaced or cleared.
+
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+/// The longest [`Faults::sync_delay_ms`] accepted: a minute. The delay is
+/// slept on the HTTP server's only thread, so a larger one would leave the
+/// emulator deaf (even to the `DELETE /api/faults` that heals it) for as
+/// long as it lasts.
+pub const MAX_SYNC_DELAY_MS: u64 = 60_000;
+
+/// What's currently broken. The default is a healthy device.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Faults {
+    /// Hold each `/api/sync` and `/api/link` this long before handling it,
+    /// up to [`MAX_SYNC_DELAY_MS`]. The server answers one request at a
+    /// time, so everything else waits too, as it would behind a slow
+    /// device.
+    pub sync_delay_ms: u64,
+    /// Answer `/api/sync` with this HTTP status (400-599) and leave the
+    /// vault as it was.
+    pub sync_status: Option<u16>,
+    /// Damage `/api/sync`'s CBOR body before it's decoded.
+    pub sync_body: Option<BodyFault>,
+    /// Frames of a `/api/link` request that never arrive.
+    pub link_drop_frames: Vec<usize>,
+    /// Frames of a `/api/link` request that arrive with a byte flipped.
+    pub link_corrupt_frames: Vec<usize>,
+    /// Make every storage write fail.
+    pub fail_storage_writes: bool,
+}
+
+/// How [`Faults::sync_body`] damages a body. JSON: `{"truncate": 12}` or
+/// `{"corrupt": 0}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyFault {
+    /// Keep only the first this-many bytes.
+    Truncate(usize),
+    /// Invert every bit of the byte at this offset (if the body is that
+    /// long). Like real corruption, the result may still decode: offset 0
+    /// (the top-level CBOR header) never does, a byte inside a string
+    /// usually does.
+    Corrupt(usize),
+}
+
+impl BodyFault {
+    pub fn apply(self, body: &mut Vec<u8>) {
+        match self {
+            BodyFault::Truncate(len) => body.truncate(len),
+            BodyFault::Corrupt(offset) => {
+                if let Some(byte) = body.get_mut(offset) {
+                    *byte ^= 0xFF;
+                }
+            }
+        }
+    }
+}
+
+impl Faults {
+    /// Checks the faults are ones the server can inject.
+    ///
+    /// # Errors
+    ///
+    /// A message naming the problem, if `sync_status` isn't an error
+    /// status (400-599) or `sync_delay_ms` is over [`MAX_SYNC_DELAY_MS`].
+    pub fn validate(&self) -> Result<(), String> {
+        match self.sync_status {
+            Some(status) if !(400..=599).contains(&status) => {
+                return Err(format!("sync_status must be an error status (400-599), got {status}"));
+            }
+            _ => {}
+        }
+        if self.sync_delay_ms > MAX_SYNC_DELAY_MS {
+            return Err(format!("sync_delay_ms must be at most {MAX_SYNC_DELAY_MS}, got {}", self.sync_delay_ms));
+        }
+        Ok(())
+    }
+
+    /// Whether nothing is broken.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The current [`Faults`], shared between the HTTP server (which sets them
+/// and applies the sync and link ones) and the storage that fails writes.
+#[derive(Debug, Clone, Default)]
+pub struct SharedFaults(Arc<Mutex<Faults>>);
+
+impl SharedFaults {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A copy of the current faults.
+    #[must_use]
+    pub fn current(&self) -> Faults {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, faults: Faults) {
+        *self.0.lock().unwrap() = faults;
+    }
+
+    pub fn clear(&self) {
+        self.set(Faults::default());
+    }
+
+    #[must_use]
+    pub fn storage_writes_fail(&self) -> bool {
+        self.0.lock().unwrap().fail_storage_writes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faults_parse_from_partial_json_and_reject_unknown_keys() {
+        let faults: Faults = serde_json::from_str(r#"{"sync_status": 503, "sync_body": {"truncate": 4}}"#).unwrap();
+        assert_eq!(faults.sync_status, Some(503));
+        assert_eq!(faults.sync_body, Some(BodyFault::Truncate(4)));
+        assert!(!faults.is_healthy());
+
+        assert!(serde_json::from_str::<Faults>("{}").unwrap().is_healthy());
+        assert!(serde_json::from_str::<Faults>(r#"{"sync_staus": 503}"#).is_err());
+        assert!(Faults { sync_status: Some(200), ..Faults::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn a_sync_delay_over_the_cap_is_rejected() {
+        assert!(Faults { sync_delay_ms: MAX_SYNC_DELAY_MS, ..Faults::default() }.validate().is_ok());
+        let error = Faults { sync_delay_ms: MAX_SYNC_DELAY_MS + 1, ..Faults::default() }.validate().unwrap_err();
+        assert!(error.contains("sync_delay_ms"), "{error}");
+    }
+
+    #[test]
+    fn body_faults_cut_or_flip_without_panicking_on_short_bodies() {
+        let mut body = vec![0xA1, 0x01, 0x02];
+        BodyFault::Corrupt(0).apply(&mut body);
+        assert_eq!(body, [0x5E, 0x01, 0x02]);
+        BodyFault::Corrupt(10).apply(&mut body);
+        assert_eq!(body, [0x5E, 0x01, 0x02]);
+        BodyFault::Truncate(1).apply(&mut body);
+        assert_eq!(body, [0x5E]);
+    }
+}
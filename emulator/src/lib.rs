@@ -9,4 +9,6 @@
 
 pub mod credentials;
 pub mod desktop;
+pub mod faults;
 pub mod platform;
+pub mod scenario;
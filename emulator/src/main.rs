@@ -8,6 +8,15 @@
 //! Replaces the old 128x32 `simple_gui` pipeline this file used to run
 //! directly (retired in W7 — see `lib.rs`).
 //!
+//! Both modes emulate one device, described by `desktop::DeviceConfig`:
+//! `--name`, `--port` (default 8080), `--data-dir` (default `./data`) and
+//! `--profile` (a panel preset such as `t-embed`, the default, or
+//! `ssd1306`; `--panel WIDTHxHEIGHT` for any other size), or any of those
+//! from a TOML `--config FILE`, flags winning. The render core derives its
+//! layout from the panel size. Give each instance its own port and data
+//! directory and several run side by side; `/api/status` reports which
+//! device answered.
+//!
 //! # Usage
 //!
 //! Windowed (default): `cargo run --bin desktop --target <host-triple>`
@@ -22,34 +31,74 @@
 //! /api/screenshot` returns a PNG of whatever `platform::
 //! SharedHeadlessSurface` most recently had flushed to it — the same
 //! `HeadlessSurface` PNG path `--dump-png` uses, just shared with the HTTP
-//! server thread instead of read once at loop exit. This is how an agent
+//! server thread instead of read once at loop exit. `GET /api/ui-tree`
+//! returns the same frame as data: a JSON tree of roles, labels, values
+//! and focus (`desktop::SharedUiTree`, republished after every frame), so
+//! "the focused row is GitHub" needs no pixel reading. This is how an agent
 //! drives and observes the shell with no window and no hardware; see
 //! `.planning/decisions/2026-08-11-three-mode-testability.md`.
 //!
+//! Virtual time: add `--virtual-time` to a headless run and the loop
+//! paces itself by an `emulator::platform::VirtualClock` instead of the
+//! wall clock. Time stands still (the UI still takes input and renders)
+//! until `POST /api/time/advance` with `{"ms": N}` moves it. That request
+//! runs every frame in the span as fast as the host can and only then
+//! responds, so idle timeouts, toast expiry and the marquee can be checked
+//! with a screenshot immediately, and come out the same on every run.
+//! Ignored in windowed mode.
+//!
+//! Recording: add `--record PATH` to either mode and every frame flushed
+//! from launch to exit is written to `PATH` as an animated PNG, each frame
+//! lasting until the next (so idle stretches are one long frame, in
+//! virtual time under `--virtual-time`). `POST /api/record/start` and
+//! `POST /api/record/stop` do the same for a span of a running emulator,
+//! the stop responding with the APNG; see `emulator::platform::recording`.
+//! A recording holds at most `MAX_RECORDING_BYTES` of frames (about a
+//! minute of continuous animation) and ends early once it's full.
+//!
+//! Scenario: `cargo run --bin desktop --target <host-triple> -- --scenario
+//! FILE`. Runs a scripted scenario file (TOML: seed credentials, inject
+//! intents, advance frames or simulated time, assert on what's on screen,
+//! save/compare screenshots — see `emulator::scenario::format`) headlessly
+//! against a virtual clock, prints the outcome, and exits 0 if every step
+//! held, 1 otherwise. No window, no HTTP server, nothing read from or
+//! written to the data directory; the device flags are ignored and the
+//! panel is the file's own `panel`.
+//!
 //! The HTTP push server (`POST /api/sync`, `/api/status`, `/api/clear`,
-//! `/api/input`, `GET /api/screenshot`, `/api/shutdown`) keeps running in
+//! `/api/input`, `GET /api/screenshot`, `/api/ui-tree`, `/api/shutdown`,
+//! `/api/record/start|stop`, and `POST /api/time/advance` with
+//! `--virtual-time`) keeps running in
 //! both modes exactly as before — it's how a companion (or `curl`, or the
 //! Web Vault dev harness) gets credentials onto the device; `PushSyncSource`
-//! wraps it as the app's `SyncSource`. `/api/screenshot` only does
-//! anything in headless mode (404 otherwise); `/api/input` is always
+//! wraps it as the app's `SyncSource`. `/api/screenshot` and `/api/ui-tree`
+//! only do anything in headless mode (404 otherwise); `/api/input` is always
 //! accepted, but windowed mode's `WindowedInput` never drains the queue it
 //! feeds, so injecting there is a harmless no-op.
+//!
+//! The same server takes syncs over device-link framing (`POST /api/link`)
+//! and can be told to misbehave: `PUT /api/faults` delays or refuses
+//! pushes, damages their bodies, drops or corrupts link frames, or fails
+//! storage writes, and `DELETE /api/faults` heals it (see
+//! `emulator::faults`). A push that fails shows on the device as a sync
+//! error until the next one succeeds.
 
 use std::cell::RefCell;
-use std::collections::VecDeque;
 use std::rc::Rc;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use bhk_core::input::NavIntent;
+use bhk_core::platform::{Clock, Platform};
 use bhk_core::{run, App, SyncSource};
-use emulator::desktop::{DesktopStorage, PushSyncSource, SyncServer};
-use emulator::platform::{FileStorage, HostPlatform, HttpInput, MinifbSurface, SharedHeadlessSurface, WindowedInput};
+use emulator::desktop::{run_publishing, DesktopStorage, DeviceConfig, PushSyncSource, SharedUiTree, SyncServer};
+use emulator::platform::{
+    FileStorage, HeadlessSurface, HostClock, HostPlatform, HttpInput, MinifbSurface, RecordingSurface, SharedHeadlessSurface,
+    SharedRecorder, VirtualClock, WindowedInput,
+};
+use emulator::scenario::Scenario;
 use minifb::{Window, WindowOptions};
 
-const WIDTH: u32 = 320;
-const HEIGHT: u32 = 170;
 const WINDOW_SCALE: u32 = 3;
 /// ~30fps: generous for a credential list (no animation), light on CPU for
 /// a background/agent-driven headless run.
@@ -57,13 +106,33 @@ const FRAME_BUDGET: Duration = Duration::from_millis(33);
 
 struct Args {
     headless: bool,
+    /// `--scenario FILE`: run the scenario and exit, instead of either
+    /// interactive mode.
+    scenario: Option<String>,
     dump_png: Option<String>,
     frames: u32,
+    /// `--record PATH`: record the whole run and write it to `PATH` as an
+    /// APNG on exit.
+    record: Option<String>,
+    /// `--virtual-time`: pace a headless run by a `VirtualClock` that only
+    /// `POST /api/time/advance` moves.
+    virtual_time: bool,
+    /// Which device to be: name, port, data directory and panel profile.
+    /// The render core picks its layout profile from the panel size alone
+    /// (`bhk_core::render::LayoutProfile::for_panel`), so `--profile
+    /// ssd1306` previews the SSD1306 strip layout, `t-embed-portrait`
+    /// portrait, `round-240` round.
+    device: DeviceConfig,
 }
 
 fn parse_args() -> Args {
     let raw: Vec<String> = std::env::args().collect();
     let headless = raw.iter().any(|a| a == "--headless");
+    let virtual_time = raw.iter().any(|a| a == "--virtual-time");
+    let scenario = raw
+        .iter()
+        .position(|a| a == "--scenario")
+        .map(|i| raw.get(i + 1).cloned().unwrap_or_else(|| panic!("--scenario expects a scenario file path")));
     let dump_png = raw
         .iter()
         .position(|a| a == "--dump-png")
@@ -75,19 +144,34 @@ fn parse_args() -> Args {
         .and_then(|i| raw.get(i + 1))
         .and_then(|s| s.parse().ok())
         .unwrap_or(1);
-    Args { headless, dump_png, frames }
+    let record = raw
+        .iter()
+        .position(|a| a == "--record")
+        .map(|i| raw.get(i + 1).cloned().unwrap_or_else(|| panic!("--record expects an output path")));
+    let device = DeviceConfig::from_args(&raw[1..]).unwrap_or_else(|e| panic!("{e}"));
+    Args { headless, scenario, dump_png, frames, record, virtual_time, device }
 }
 
 fn main() {
     let args = parse_args();
 
-    println!("Starting desktop emulator ({} mode)...", if args.headless { "headless" } else { "windowed" });
+    if let Some(path) = &args.scenario {
+        std::process::exit(run_scenario(path));
+    }
+
+    let device = &args.device;
+    println!("Starting desktop emulator \"{}\" ({} mode)...", device.name, if args.headless { "headless" } else { "windowed" });
 
-    let storage_backend = Arc::new(Mutex::new(DesktopStorage::new().expect("Failed to create credential storage")));
-    let mut server = SyncServer::new("127.0.0.1:8080", storage_backend).expect("Failed to start HTTP server");
+    let storage_backend =
+        Arc::new(Mutex::new(DesktopStorage::at(device.credentials_path()).expect("Failed to create credential storage")));
+    let mut server = SyncServer::new(&device.bind_addr(), storage_backend).expect("Failed to start HTTP server");
+    let server_addr = server.local_addr();
+    server.set_device(device.descriptor(server_addr.port()));
     let credentials_ref = server.get_credentials_ref();
     let shutdown_signal = server.get_shutdown_signal();
     let input_queue = server.get_input_queue_ref();
+    let faults = server.get_faults_ref();
+    let sync_error = server.get_sync_error_ref();
 
     // The headless screenshot surface has to be created here (before the
     // server is moved into its request-loop thread below) so the same
@@ -103,15 +187,50 @@ fn main() {
     } else {
         None
     };
+    // And for `GET /api/ui-tree`: the loop publishes, the server reads.
+    let ui_tree = SharedUiTree::new();
+    if args.headless {
+        server.set_ui_tree(ui_tree.handle());
+    }
+
+    // Same reasoning for the virtual clock: `POST /api/time/advance` (HTTP
+    // thread) and the loop's `Clock` (this thread) must be one clock.
+    let virtual_clock = if args.headless && args.virtual_time {
+        let clock = VirtualClock::new();
+        server.set_virtual_clock(clock.clone());
+        Some(clock)
+    } else {
+        if args.virtual_time {
+            println!("--virtual-time only applies to headless mode; the window runs on the wall clock.");
+        }
+        None
+    };
+
+    // The recorder stamps frames by the loop's clock, so a virtual-time
+    // recording plays back in virtual time.
+    let recorder = match &virtual_clock {
+        Some(clock) => SharedRecorder::new(clock.clone()),
+        None => SharedRecorder::new(HostClock::new()),
+    };
+    server.set_recorder(recorder.clone());
+    if args.record.is_some() {
+        recorder.start();
+    }
 
     std::thread::spawn(move || {
-        println!("HTTP server running on http://127.0.0.1:8080");
+        println!("HTTP server running on http://{server_addr}");
         println!("Endpoints:");
         println!("  POST /api/sync - Sync credentials (CBOR)");
         println!("  GET  /api/status - Get server status");
         println!("  POST /api/clear - Clear credentials");
+        println!("  POST /api/link - Sync credentials over device-link frames");
+        println!("  GET/PUT/DELETE /api/faults - Inspect, inject or clear faults (JSON)");
         println!("  POST /api/input - Inject a NavIntent (JSON; headless mode only takes effect)");
         println!("  GET  /api/screenshot - PNG of the current framebuffer (headless mode only)");
+        println!("  GET  /api/ui-tree - JSON tree of what's on screen (headless mode only)");
+        println!("  POST /api/record/start - Start recording frames");
+        println!("  POST /api/record/stop - Stop recording; responds with an animated PNG");
+        println!("  POST /api/time/advance - Advance virtual time, {{\"ms\": N}} (--virtual-time only)");
         println!("  POST /api/shutdown - Shutdown emulator");
         loop {
             if let Err(e) = server.handle_request() {
@@ -120,36 +239,82 @@ fn main() {
         }
     });
 
-    let kv_storage = FileStorage::new_default().expect("Failed to open kv store");
-    let mut sync_source = PushSyncSource::new(credentials_ref);
-    let initial_items = sync_source.sync().expect("PushSyncSource::sync is Infallible");
+    let kv_storage = FileStorage::new(device.kv_store_path()).expect("Failed to open kv store").with_faults(faults);
+    let mut sync_source = PushSyncSource::new(credentials_ref).with_sync_errors(sync_error);
+    let initial_items = sync_source.sync().expect("no push has failed before the server has taken one");
 
-    let mut app = App::new(WIDTH, HEIGHT, initial_items);
+    let (width, height) = device.profile.size();
+    println!("Panel: {}, data in {}", device.profile, device.data_dir.display());
+    let mut app = App::new(width, height, initial_items);
 
     if args.headless {
-        let surface = screenshot_surface.expect("headless mode always constructs a screenshot surface above");
-        run_headless(&mut app, &mut sync_source, kv_storage, &shutdown_signal, input_queue, surface, &args);
+        let surface = RecordingSurface::new(
+            screenshot_surface.expect("headless mode always constructs a screenshot surface above"),
+            recorder.clone(),
+        );
+        let input = HttpInput::new(input_queue);
+        match virtual_clock {
+            Some(clock) => {
+                let platform = HostPlatform::with_clock(surface, input, kv_storage, clock);
+                run_headless(platform, &mut app, &mut sync_source, &ui_tree, &shutdown_signal, &args);
+            }
+            None => {
+                let platform = HostPlatform::new(surface, input, kv_storage);
+                run_headless(platform, &mut app, &mut sync_source, &ui_tree, &shutdown_signal, &args);
+            }
+        }
     } else {
-        run_windowed(&mut app, &mut sync_source, kv_storage, &shutdown_signal);
+        run_windowed(&mut app, &mut sync_source, kv_storage, &recorder, &shutdown_signal, device);
+    }
+
+    if let Some(path) = &args.record {
+        match recorder.stop() {
+            Some(recording) => {
+                recording.save_apng(path).expect("failed to save recording");
+                println!("Wrote recording to {path} ({} frame(s))", recording.frames().len());
+                if recording.truncated() {
+                    println!("The recording reached its size limit and ends early; record a shorter span over HTTP for the rest");
+                }
+            }
+            None => println!("Recording was stopped over HTTP; nothing written to {path}"),
+        }
     }
 
     println!("Emulator closed.");
 }
 
-fn run_headless(
+/// Runs the scenario file at `path`, returning the process exit code.
+fn run_scenario(path: &str) -> i32 {
+    let base_dir = std::path::Path::new(path).parent().unwrap_or(std::path::Path::new(""));
+    match Scenario::load(path).and_then(|scenario| scenario.run(base_dir)) {
+        Ok(report) => {
+            println!(
+                "Scenario {path} passed: {} step(s), {} frame(s), {:?} simulated",
+                report.steps, report.frames, report.elapsed
+            );
+            0
+        }
+        Err(error) => {
+            eprintln!("Scenario {path} failed: {error}");
+            1
+        }
+    }
+}
+
+/// Runs headless mode on `platform`, whose clock is the wall clock or,
+/// with `--virtual-time`, a `VirtualClock` the HTTP server moves.
+fn run_headless<C: Clock>(
+    mut platform: HostPlatform<RecordingSurface<SharedHeadlessSurface>, HttpInput, C>,
     app: &mut App,
     sync_source: &mut PushSyncSource,
-    storage: FileStorage,
+    ui_tree: &SharedUiTree,
     shutdown_signal: &Arc<std::sync::atomic::AtomicBool>,
-    input_queue: Arc<Mutex<VecDeque<NavIntent>>>,
-    surface: SharedHeadlessSurface,
     args: &Args,
 ) {
-    // Keep a second handle to the same `HeadlessSurface` around: `surface`
-    // itself is about to be moved into `platform`, but `--dump-png` still
-    // needs to read the final frame back out after the loop stops.
-    let surface_handle = surface.handle();
-    let mut platform = HostPlatform::new(surface, HttpInput::new(input_queue), storage);
+    // Keep a second handle to the same `HeadlessSurface` around: the
+    // surface itself now lives in `platform`, but `--dump-png` still needs
+    // to read the final frame back out after the loop stops.
+    let surface_handle: Arc<Mutex<HeadlessSurface>> = platform.display().inner().handle();
 
     if let Some(path) = &args.dump_png {
         // Bounded run for automated/agent verification: N frames, then dump
@@ -160,10 +325,11 @@ fn run_headless(
             frame <= args.frames
         });
         surface_handle.lock().unwrap().save_png(path).expect("failed to save headless PNG");
-        println!("Wrote headless screenshot to {path} ({WIDTH}x{HEIGHT}, {} frame(s))", args.frames);
+        let (width, height) = args.device.profile.size();
+        println!("Wrote headless screenshot to {path} ({width}x{height}, {} frame(s))", args.frames);
     } else {
-        println!("Headless mode running. Drive it over HTTP: POST /api/input (NavIntent JSON), GET /api/screenshot (PNG).");
-        run(&mut platform, app, sync_source, FRAME_BUDGET, || !shutdown_signal.load(Ordering::Relaxed));
+        println!("Headless mode running. Drive it over HTTP: POST /api/input (NavIntent JSON), GET /api/screenshot (PNG), GET /api/ui-tree (JSON).");
+        run_publishing(&mut platform, app, sync_source, FRAME_BUDGET, ui_tree, || !shutdown_signal.load(Ordering::Relaxed));
     }
 }
 
@@ -171,22 +337,25 @@ fn run_windowed(
     app: &mut App,
     sync_source: &mut PushSyncSource,
     storage: FileStorage,
+    recorder: &SharedRecorder,
     shutdown_signal: &Arc<std::sync::atomic::AtomicBool>,
+    device: &DeviceConfig,
 ) {
+    let (width, height) = device.profile.size();
     println!("Controls: Arrow Up/Down (Prev/Next), Enter (Activate), Backspace/Esc (Back)");
-    println!("Window size: {}x{} ({WINDOW_SCALE}x scale)", WIDTH * WINDOW_SCALE, HEIGHT * WINDOW_SCALE);
+    println!("Window size: {}x{} ({WINDOW_SCALE}x scale)", width * WINDOW_SCALE, height * WINDOW_SCALE);
 
     let mut window = Window::new(
-        "Bitwarden HW Key - Desktop Emulator",
-        (WIDTH * WINDOW_SCALE) as usize,
-        (HEIGHT * WINDOW_SCALE) as usize,
+        &format!("Bitwarden HW Key - {}", device.name),
+        (width * WINDOW_SCALE) as usize,
+        (height * WINDOW_SCALE) as usize,
         WindowOptions::default(),
     )
     .unwrap_or_else(|e| panic!("Unable to create window: {e}"));
     window.set_target_fps(60);
     let window = Rc::new(RefCell::new(window));
 
-    let display = MinifbSurface::new(Rc::clone(&window), WIDTH, HEIGHT, WINDOW_SCALE);
+    let display = RecordingSurface::new(MinifbSurface::new(Rc::clone(&window), width, height, WINDOW_SCALE), recorder.clone());
     let input = WindowedInput::new(Rc::clone(&window));
     let mut platform = HostPlatform::new(display, input, storage);
 
@@ -1,10 +1,16 @@
-//! Host `Clock`: a thin wrapper over `std::time::Instant`, which is
-//! already available on both targets (see the rationale in
+//! Host `Clock`s: [`HostClock`], a thin wrapper over `std::time::Instant`,
+//! which is already available on both targets (see the rationale in
 //! `bhk_core::platform`), so there is nothing host-specific to do beyond
-//! satisfying the trait.
+//! satisfying the trait; and [`VirtualClock`], which only moves when told
+//! to, for runs that must not depend on how fast the host is (the
+//! scenario runner, see `crate::scenario`, and `desktop --virtual-time`).
+//! Both pace the run loop too, through `Clock::sleep_until`: the host
+//! clock with the trait's default real sleep, the virtual one by parking
+//! the loop until its time is moved.
 
 use bhk_core::platform::Clock;
-use std::time::Instant;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct HostClock;
@@ -22,6 +28,171 @@ impl Clock for HostClock {
     }
 }
 
+/// A `Clock` frozen at construction that moves forward only when told to.
+/// Clones share the same time, so whoever drives the run (and owns one
+/// clone) moves the clock the platform reads (through another).
+///
+/// Two ways to drive it:
+/// - [`VirtualClock::advance`] jumps the time in one step and runs
+///   nothing itself. The scenario runner steps frames by hand and uses
+///   this between them.
+/// - [`VirtualClock::advance_stepped`] is for a clock paced by a live
+///   `bhk_core::run` loop on another thread (`desktop --headless
+///   --virtual-time`, driven by `POST /api/time/advance`). It walks the
+///   time forward one frame deadline at a time and waits for the loop to
+///   run each frame. The loop sees every frame it would have seen in real
+///   time, but a minute of it takes however long the frames take to
+///   compute.
+///
+/// While nobody advances it, a loop parked in
+/// [`Clock::sleep_until`] still wakes after the frame's budget of *real*
+/// time. Each wake runs a frame at the same frozen instant, which changes
+/// nothing time-based but keeps input and shutdown flowing.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled whenever `now` moves or a loop parks.
+    changed: Condvar,
+}
+
+#[derive(Debug)]
+struct State {
+    start: Instant,
+    now: Instant,
+    /// The deadline a run loop is parked in `sleep_until` for, if one is
+    /// parked right now.
+    parked_until: Option<Instant>,
+    /// How many times a loop has parked. A loop parks once per frame, so
+    /// a change means it has run another one.
+    parks: u64,
+    /// Asks the parked loop to run a frame now, deadline or not. Cleared
+    /// by the loop when it wakes.
+    wake: bool,
+}
+
+/// How long [`VirtualClock::advance_stepped`] waits for the run loop
+/// before deciding nothing is driving frames and just jumping.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl VirtualClock {
+    /// Starts at the real `Instant::now()` — an `Instant` can't be built
+    /// any other way — after which only `advance` and `advance_stepped`
+    /// move it.
+    #[must_use]
+    pub fn new() -> Self {
+        let now = Instant::now();
+        let state = State { start: now, now, parked_until: None, parks: 0, wake: false };
+        Self { shared: Arc::new(Shared { state: Mutex::new(state), changed: Condvar::new() }) }
+    }
+
+    /// How far the clock has been advanced since it was created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another clone panicked while holding the lock.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        let state = self.shared.state.lock().unwrap();
+        state.now - state.start
+    }
+
+    /// Moves the clock (and every clone of it) forward by `by` in one
+    /// jump, waking a parked loop if that passes its deadline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another clone panicked while holding the lock.
+    pub fn advance(&self, by: Duration) {
+        self.shared.state.lock().unwrap().now += by;
+        self.shared.changed.notify_all();
+    }
+
+    /// Moves the clock forward by `by` through every frame deadline a
+    /// parked run loop asks for on the way. At each deadline it sets the
+    /// time, lets the loop run that frame, and waits for the loop to park
+    /// again before moving on. It ends exactly `by` later with one more
+    /// frame at that instant, unless the last deadline fell on it, so the
+    /// screen afterwards always shows the new time however the span was
+    /// split into calls. Returns how many frames the loop ran.
+    ///
+    /// If no loop parks within a second (none is running, or it has
+    /// stopped), the rest of the span is a plain [`advance`](Self::advance).
+    ///
+    /// # Panics
+    ///
+    /// Panics if another clone panicked while holding the lock.
+    pub fn advance_stepped(&self, by: Duration) -> u32 {
+        let changed = &self.shared.changed;
+        let mut state = self.shared.state.lock().unwrap();
+        let target = state.now + by;
+        let mut frames = 0;
+        let mut ran_at_target = false;
+        loop {
+            state = changed.wait_timeout_while(state, SETTLE_TIMEOUT, |s| s.parked_until.is_none()).unwrap().0;
+            let Some(deadline) = state.parked_until.filter(|&deadline| deadline <= target) else {
+                break;
+            };
+            let parks = state.parks;
+            state.now = state.now.max(deadline);
+            changed.notify_all();
+            let (next, wait) = changed.wait_timeout_while(state, SETTLE_TIMEOUT, |s| s.parks == parks).unwrap();
+            state = next;
+            if wait.timed_out() {
+                break;
+            }
+            frames += 1;
+            ran_at_target = deadline == target;
+        }
+        state.now = state.now.max(target);
+        if state.parked_until.is_some() && !ran_at_target {
+            let parks = state.parks;
+            state.wake = true;
+            changed.notify_all();
+            let (next, wait) = changed.wait_timeout_while(state, SETTLE_TIMEOUT, |s| s.parks == parks).unwrap();
+            state = next;
+            if !wait.timed_out() {
+                frames += 1;
+            }
+        }
+        drop(state);
+        changed.notify_all();
+        frames
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.shared.state.lock().unwrap().now
+    }
+
+    /// Parks until the clock reaches `deadline`, or until the same span
+    /// of real time has passed. See the type docs for why it wakes early.
+    fn sleep_until(&self, deadline: Instant) {
+        let changed = &self.shared.changed;
+        let mut state = self.shared.state.lock().unwrap();
+        let Some(idle) = deadline.checked_duration_since(state.now).filter(|idle| !idle.is_zero()) else {
+            return;
+        };
+        state.parked_until = Some(deadline);
+        state.parks += 1;
+        changed.notify_all();
+        let mut state = changed.wait_timeout_while(state, idle, |s| s.now < deadline && !s.wake).unwrap().0;
+        state.parked_until = None;
+        state.wake = false;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,4 +204,72 @@ mod tests {
         let second = clock.now();
         assert!(second >= first);
     }
+
+    #[test]
+    fn a_virtual_clock_only_moves_when_advanced_and_clones_share_it() {
+        let clock = VirtualClock::new();
+        let driver = clock.clone();
+        let start = clock.now();
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(clock.now(), start);
+
+        driver.advance(Duration::from_secs(90));
+        assert_eq!(clock.now() - start, Duration::from_secs(90));
+    }
+
+    /// A stand-in for `bhk_core::run` on another thread: records the time
+    /// of every frame, then sleeps a 33ms budget on the clock.
+    fn spawn_loop(clock: &VirtualClock, stop: &Arc<std::sync::atomic::AtomicBool>) -> std::thread::JoinHandle<Vec<Duration>> {
+        let (clock, stop) = (clock.clone(), Arc::clone(stop));
+        std::thread::spawn(move || {
+            let mut frames = Vec::new();
+            while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                let frame_start = clock.now();
+                frames.push(clock.elapsed());
+                clock.sleep_until(frame_start + Duration::from_millis(33));
+            }
+            frames
+        })
+    }
+
+    #[test]
+    fn advance_stepped_runs_every_frame_deadline_in_the_span_without_real_waiting() {
+        let clock = VirtualClock::new();
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let handle = spawn_loop(&clock, &stop);
+
+        let started = Instant::now();
+        // 33, 66 and 99ms, then the closing frame at 100ms.
+        assert_eq!(clock.advance_stepped(Duration::from_millis(100)), 4);
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+        // Every 33ms from 133ms up to 60.1s, then 60.1s itself.
+        assert_eq!(clock.advance_stepped(Duration::from_secs(60)), 1818 + 1);
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        clock.advance(Duration::from_secs(1));
+        let frames = handle.join().unwrap();
+
+        // Frames only ever ran at the instants above, however many extra
+        // times an idle wake re-ran one at a frozen instant.
+        let mut distinct = frames;
+        distinct.dedup();
+        assert_eq!(&distinct[..6], &[0, 33, 66, 99, 100, 133].map(Duration::from_millis));
+        assert_eq!(distinct.last(), Some(&Duration::from_millis(60_100)));
+    }
+
+    #[test]
+    fn advance_stepped_without_a_running_loop_is_a_plain_jump() {
+        let clock = VirtualClock::new();
+        assert_eq!(clock.advance_stepped(Duration::from_secs(5)), 0);
+        assert_eq!(clock.elapsed(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn a_parked_loop_still_wakes_in_real_time_while_the_clock_stands_still() {
+        let clock = VirtualClock::new();
+        let start = clock.now();
+        clock.sleep_until(start + Duration::from_millis(5));
+        assert_eq!(clock.now(), start);
+    }
 }
@@ -5,7 +5,11 @@
 //! screenshot without a display server.
 //!
 //! `flush` itself can never fail (there is no real device I/O to fail
-//! against — it's a RAM-to-RAM copy), so `Error = Infallible`.
+//! against — it's a RAM-to-RAM copy), so `Error = Infallible`. It honours
+//! the damage contract the same way the ST7789 does: once a frame has
+//! been captured, only the damaged regions are copied into it, so a
+//! screenshot shows exactly what a partial-flush panel would — the
+//! `partial_and_full_flushes_converge` parity test depends on that.
 //!
 //! The pixel -> PNG conversion (`Rgb565::r() << 3`, etc.) is deliberately
 //! identical to the one already proven in
@@ -27,6 +31,7 @@ use std::sync::{Arc, Mutex};
 use bhk_core::platform::{DisplaySurface, FrameBuffer565};
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::RgbColor;
+use embedded_graphics::primitives::{PointsIter, Rectangle};
 use embedded_graphics::Pixel;
 
 struct CapturedFrame {
@@ -48,16 +53,11 @@ impl HeadlessSurface {
         Self::default()
     }
 
-    /// Encodes the most recently flushed framebuffer as a PNG, returning
-    /// `None` if `flush` has never been called.
-    ///
-    /// # Panics
-    ///
-    /// Panics if in-memory PNG encoding fails, which should not be
-    /// possible for a buffer built directly from a `FrameBuffer565` (no
-    /// filesystem or format-mismatch failure modes apply here).
+    /// The most recently flushed framebuffer as an RGB image (the PNG's
+    /// pixels, before encoding), or `None` if `flush` has never been
+    /// called.
     #[must_use]
-    pub fn encode_png(&self) -> Option<Vec<u8>> {
+    pub fn to_image(&self) -> Option<image::RgbImage> {
         let frame = self.last_frame.as_ref()?;
         let mut image = image::RgbImage::new(frame.width, frame.height);
 
@@ -67,6 +67,20 @@ impl HeadlessSurface {
             let y = index / frame.width;
             image.put_pixel(x, y, image::Rgb([color.r() << 3, color.g() << 2, color.b() << 3]));
         }
+        Some(image)
+    }
+
+    /// Encodes the most recently flushed framebuffer as a PNG, returning
+    /// `None` if `flush` has never been called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if in-memory PNG encoding fails, which should not be
+    /// possible for a buffer built directly from a `FrameBuffer565` (no
+    /// filesystem or format-mismatch failure modes apply here).
+    #[must_use]
+    pub fn encode_png(&self) -> Option<Vec<u8>> {
+        let image = self.to_image()?;
 
         let mut buffer = Vec::new();
         image
@@ -95,11 +109,22 @@ impl HeadlessSurface {
 impl DisplaySurface for HeadlessSurface {
     type Error = Infallible;
 
-    fn flush(&mut self, framebuffer: &FrameBuffer565) -> Result<(), Self::Error> {
+    /// Copies only `damage` into the captured frame, or the whole frame
+    /// when there is none yet (or it's a different size).
+    fn flush(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) -> Result<(), Self::Error> {
         let width = framebuffer.width();
         let height = framebuffer.height();
-        let pixels: Vec<Rgb565> = framebuffer.pixels().map(|Pixel(_, color)| color).collect();
-        self.last_frame = Some(CapturedFrame { width, height, pixels });
+        match &mut self.last_frame {
+            Some(frame) if frame.width == width && frame.height == height => {
+                for point in damage.iter().flat_map(PointsIter::points) {
+                    frame.pixels[(point.y as u32 * width + point.x as u32) as usize] = framebuffer.pixel(point);
+                }
+            }
+            _ => {
+                let pixels: Vec<Rgb565> = framebuffer.pixels().map(|Pixel(_, color)| color).collect();
+                self.last_frame = Some(CapturedFrame { width, height, pixels });
+            }
+        }
         Ok(())
     }
 }
@@ -135,17 +160,18 @@ impl SharedHeadlessSurface {
 impl DisplaySurface for SharedHeadlessSurface {
     type Error = Infallible;
 
-    fn flush(&mut self, framebuffer: &FrameBuffer565) -> Result<(), Self::Error> {
-        self.0.lock().unwrap().flush(framebuffer)
+    fn flush(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) -> Result<(), Self::Error> {
+        self.0.lock().unwrap().flush(framebuffer, damage)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use embedded_graphics::geometry::Dimensions;
     use embedded_graphics::pixelcolor::WebColors;
     use embedded_graphics::prelude::{Point, Size};
-    use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+    use embedded_graphics::primitives::PrimitiveStyle;
     use embedded_graphics::{draw_target::DrawTarget, prelude::Primitive, Drawable};
 
     #[test]
@@ -160,7 +186,7 @@ mod tests {
         framebuffer.clear(Rgb565::RED).unwrap();
 
         let mut surface = HeadlessSurface::new();
-        surface.flush(&framebuffer).unwrap();
+        surface.flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
 
         let png_bytes = surface.encode_png().expect("frame was flushed");
         let decoded = image::load_from_memory(&png_bytes).unwrap().to_rgb8();
@@ -180,8 +206,8 @@ mod tests {
         blue_framebuffer.clear(Rgb565::BLUE).unwrap();
 
         let mut surface = HeadlessSurface::new();
-        surface.flush(&red_framebuffer).unwrap();
-        surface.flush(&blue_framebuffer).unwrap();
+        surface.flush(&red_framebuffer, &[red_framebuffer.bounding_box()]).unwrap();
+        surface.flush(&blue_framebuffer, &[blue_framebuffer.bounding_box()]).unwrap();
 
         let png_bytes = surface.encode_png().unwrap();
         let decoded = image::load_from_memory(&png_bytes).unwrap().to_rgb8();
@@ -189,6 +215,26 @@ mod tests {
         assert_eq!(*decoded.get_pixel(0, 0), expected);
     }
 
+    #[test]
+    fn after_the_first_frame_only_damaged_regions_are_copied() {
+        let mut framebuffer = FrameBuffer565::new(4, 4);
+        framebuffer.clear(Rgb565::RED).unwrap();
+        let mut surface = HeadlessSurface::new();
+        surface.flush(&framebuffer, &[]).unwrap();
+
+        // Repaint blue, but report only the top-left 2x2 as damaged: the
+        // capture must keep the rest of the previous frame, as a panel
+        // would.
+        framebuffer.clear(Rgb565::BLUE).unwrap();
+        surface.flush(&framebuffer, &[Rectangle::new(Point::zero(), Size::new(2, 2))]).unwrap();
+
+        let decoded = image::load_from_memory(&surface.encode_png().unwrap()).unwrap().to_rgb8();
+        let rgb = |c: Rgb565| image::Rgb([c.r() << 3, c.g() << 2, c.b() << 3]);
+        assert_eq!(*decoded.get_pixel(1, 1), rgb(Rgb565::BLUE));
+        assert_eq!(*decoded.get_pixel(2, 1), rgb(Rgb565::RED));
+        assert_eq!(*decoded.get_pixel(0, 3), rgb(Rgb565::RED));
+    }
+
     #[test]
     fn preserves_pixel_positions_not_just_colors_present() {
         // Regression guard for the row-major reconstruction math in
@@ -201,7 +247,7 @@ mod tests {
             .unwrap();
 
         let mut surface = HeadlessSurface::new();
-        surface.flush(&framebuffer).unwrap();
+        surface.flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
         let decoded = image::load_from_memory(&surface.encode_png().unwrap()).unwrap().to_rgb8();
 
         let green = image::Rgb([Rgb565::GREEN.r() << 3, Rgb565::GREEN.g() << 2, Rgb565::GREEN.b() << 3]);
@@ -224,7 +270,7 @@ mod tests {
 
         assert!(handle.lock().unwrap().encode_png().is_none(), "nothing flushed yet");
 
-        surface.flush(&framebuffer).unwrap();
+        surface.flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
 
         let png_bytes = handle.lock().unwrap().encode_png().expect("the handle sees the flush");
         let decoded = image::load_from_memory(&png_bytes).unwrap().to_rgb8();
@@ -244,7 +290,7 @@ mod tests {
         let mut surface = SharedHeadlessSurface::new();
         let clone = surface.clone();
 
-        surface.flush(&framebuffer).unwrap();
+        surface.flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
 
         // `clone` is a second owner of the same `Arc<Mutex<HeadlessSurface>>`,
         // not an independent copy — it must observe the flush `surface`
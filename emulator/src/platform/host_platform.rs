@@ -1,9 +1,11 @@
-//! `HostPlatform<D, I>`: the minimal wiring needed to instantiate a
+//! `HostPlatform<D, I, C>`: the minimal wiring needed to instantiate a
 //! `bhk_core::platform::Platform` on the host, generic over which
 //! `DisplaySurface` (`D`) and `InputSource` (`I`) back it — the headless
 //! and windowed run modes plug in `HeadlessSurface`/`NoopInput` or
 //! `MinifbSurface`/`WindowedInput` respectively, sharing the same `Clock`
-//! and `Storage` implementations either way.
+//! and `Storage` implementations either way. The clock (`C`) defaults to
+//! the real [`HostClock`]; the scenario runner swaps in a
+//! [`super::clock::VirtualClock`] via [`HostPlatform::with_clock`].
 //!
 //! This exists to prove the capability-bundle trait actually assembles and
 //! to give `emulator/examples/render_via_surfaces.rs` and
@@ -13,29 +15,37 @@
 //! wiring — generic over `Platform` so it's shared by all three run modes
 //! — is W7's job.
 
-use bhk_core::platform::{DisplaySurface, InputSource, Platform};
+use bhk_core::platform::{Clock, DisplaySurface, InputSource, Platform};
 
 use super::clock::HostClock;
 use super::storage::FileStorage;
 
-pub struct HostPlatform<D: DisplaySurface, I: InputSource> {
+pub struct HostPlatform<D: DisplaySurface, I: InputSource, C: Clock = HostClock> {
     display: D,
     input: I,
-    clock: HostClock,
+    clock: C,
     storage: FileStorage,
 }
 
 impl<D: DisplaySurface, I: InputSource> HostPlatform<D, I> {
     #[must_use]
     pub fn new(display: D, input: I, storage: FileStorage) -> Self {
-        Self { display, input, clock: HostClock::new(), storage }
+        Self::with_clock(display, input, storage, HostClock::new())
     }
 }
 
-impl<D: DisplaySurface, I: InputSource> Platform for HostPlatform<D, I> {
+impl<D: DisplaySurface, I: InputSource, C: Clock> HostPlatform<D, I, C> {
+    /// [`HostPlatform::new`] with a clock other than the real one.
+    #[must_use]
+    pub fn with_clock(display: D, input: I, storage: FileStorage, clock: C) -> Self {
+        Self { display, input, clock, storage }
+    }
+}
+
+impl<D: DisplaySurface, I: InputSource, C: Clock> Platform for HostPlatform<D, I, C> {
     type Display = D;
     type Input = I;
-    type Clock = HostClock;
+    type Clock = C;
     type Storage = FileStorage;
 
     fn display(&mut self) -> &mut Self::Display {
@@ -60,12 +70,13 @@ mod tests {
     use super::*;
     use crate::platform::{HeadlessSurface, NoopInput};
     use bhk_core::platform::{Clock, Storage};
-    use bhk_core::render::{FrameBuffer565, Navigator, Screen};
+    use bhk_core::render::{FrameBuffer565, Navigator, Screen, Theme};
+    use embedded_graphics::geometry::Dimensions;
 
     #[test]
     fn a_headless_host_platform_can_be_assembled_and_used_through_the_platform_trait() {
         let storage = FileStorage::new(std::env::temp_dir().join(format!(
-            "bhk-emulator-host-platform-test-{}.json",
+            "bhk-emulator-host-platform-test-{}.bin",
             uuid::Uuid::new_v4()
         )))
         .unwrap();
@@ -80,7 +91,7 @@ mod tests {
 
         let navigator = Navigator::new(Screen::new("Test", vec![]));
         let mut framebuffer = FrameBuffer565::new(10, 10);
-        navigator.render(&mut framebuffer).unwrap();
-        platform.display().flush(&framebuffer).unwrap();
+        navigator.render(&Theme::DARK, &mut framebuffer).unwrap();
+        platform.display().flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
     }
 }
@@ -7,7 +7,14 @@
 //!
 //! Mapping (per the bead): arrow up / scroll up -> `Prev`, arrow down /
 //! scroll down -> `Next`, Enter -> `Activate`, Backspace or Escape ->
-//! `Back`.
+//! `Back`. Page Up / Page Down -> `PrevN`/`NextN` by [`PAGE_JUMP`], Home
+//! -> `Home`, End -> `End`, and any other printable character typed ->
+//! `Char`, for the focused list's type-ahead.
+//!
+//! Typed characters don't come from `is_key_down`: a `Key` is a physical
+//! key, not what it types under the user's layout and modifiers. They
+//! come from minifb's text input callback instead ([`TypedChars`]), which
+//! queues them until the next `poll()`.
 //!
 //! [`WindowedInput::poll`] also calls `minifb::Window::update()` before
 //! reading any input state. `bhk_core::run`'s loop calls `input.poll()`
@@ -33,18 +40,47 @@ use std::sync::{Arc, Mutex};
 
 use bhk_core::input::NavIntent;
 use bhk_core::platform::InputSource;
-use minifb::{Key, Window};
+use minifb::{InputCallback, Key, Window};
+
+/// How many rows Page Up / Page Down jump: roughly one landscape
+/// viewport of list rows. `TextView` ignores the count and pages by its
+/// own viewport height.
+const PAGE_JUMP: u16 = 5;
 
 /// The fixed set of keys this project maps to a `NavIntent`, and which
 /// intent each maps to on press.
 const KEY_INTENTS: &[(Key, NavIntent)] = &[
     (Key::Up, NavIntent::Prev),
     (Key::Down, NavIntent::Next),
+    (Key::PageUp, NavIntent::PrevN(PAGE_JUMP)),
+    (Key::PageDown, NavIntent::NextN(PAGE_JUMP)),
+    (Key::Home, NavIntent::Home),
+    (Key::End, NavIntent::End),
     (Key::Enter, NavIntent::Activate),
     (Key::Backspace, NavIntent::Back),
     (Key::Escape, NavIntent::Back),
 ];
 
+/// The character minifb's text callback reported, if it's one to feed to
+/// type-ahead: not a control character (Enter, Backspace and Escape are
+/// already mapped above) and not in the Private Use Area, where macOS
+/// reports the arrow and function keys.
+fn typed_char(uni_char: u32) -> Option<char> {
+    char::from_u32(uni_char).filter(|c| !c.is_control() && !('\u{e000}'..='\u{f8ff}').contains(c))
+}
+
+/// minifb text input callback: queues each typed character for
+/// [`WindowedInput::poll`] to turn into `NavIntent::Char`.
+struct TypedChars(Rc<RefCell<VecDeque<char>>>);
+
+impl InputCallback for TypedChars {
+    fn add_char(&mut self, uni_char: u32) {
+        if let Some(c) = typed_char(uni_char) {
+            self.0.borrow_mut().push_back(c);
+        }
+    }
+}
+
 /// Pure edge-detection: given the current down/up state of each mapped
 /// key, updates `previous` in place and returns the `NavIntent`s for keys
 /// that transitioned from up to down since the last call. Order follows
@@ -70,12 +106,17 @@ const SCROLL_DEADZONE: f32 = 0.1;
 pub struct WindowedInput {
     window: Rc<RefCell<Window>>,
     key_states: HashMap<Key, bool>,
+    typed: Rc<RefCell<VecDeque<char>>>,
 }
 
 impl WindowedInput {
+    /// Wraps the shared window and installs its text input callback (see
+    /// [`TypedChars`]), replacing any callback set before.
     #[must_use]
     pub fn new(window: Rc<RefCell<Window>>) -> Self {
-        Self { window, key_states: HashMap::new() }
+        let typed = Rc::new(RefCell::new(VecDeque::new()));
+        window.borrow_mut().set_input_callback(Box::new(TypedChars(Rc::clone(&typed))));
+        Self { window, key_states: HashMap::new(), typed }
     }
 }
 
@@ -106,6 +147,9 @@ impl InputSource for WindowedInput {
             intents.push(NavIntent::Next);
         }
 
+        // Filled by `TypedChars` during the `update()` above.
+        intents.extend(self.typed.borrow_mut().drain(..).map(NavIntent::Char));
+
         intents
     }
 }
@@ -196,7 +240,7 @@ mod tests {
     }
 
     #[test]
-    fn all_five_mapped_keys_produce_the_expected_intents() {
+    fn all_mapped_keys_produce_the_expected_intents() {
         let mut previous = HashMap::new();
         let mut down = HashMap::new();
         for (key, _) in KEY_INTENTS {
@@ -208,6 +252,10 @@ mod tests {
         let mut expected = vec![
             NavIntent::Prev,
             NavIntent::Next,
+            NavIntent::PrevN(PAGE_JUMP),
+            NavIntent::NextN(PAGE_JUMP),
+            NavIntent::Home,
+            NavIntent::End,
             NavIntent::Activate,
             NavIntent::Back, // Backspace
             NavIntent::Back, // Escape
@@ -217,6 +265,29 @@ mod tests {
         assert_eq!(intents, expected);
     }
 
+    #[test]
+    fn typed_characters_skip_control_and_private_use_codepoints() {
+        assert_eq!(typed_char('g' as u32), Some('g'));
+        assert_eq!(typed_char('G' as u32), Some('G'));
+        assert_eq!(typed_char('é' as u32), Some('é'));
+        assert_eq!(typed_char(' ' as u32), Some(' '));
+        assert_eq!(typed_char('\r' as u32), None);
+        assert_eq!(typed_char(0x08), None); // Backspace
+        assert_eq!(typed_char(0x1b), None); // Escape
+        assert_eq!(typed_char(0xf700), None); // macOS NSUpArrowFunctionKey
+        assert_eq!(typed_char(0xd800), None); // not a scalar value
+    }
+
+    #[test]
+    fn typed_chars_callback_queues_only_what_type_ahead_wants() {
+        let queue = Rc::new(RefCell::new(VecDeque::new()));
+        let mut callback = TypedChars(Rc::clone(&queue));
+        for c in ['a', '\r', 'b'] {
+            callback.add_char(c as u32);
+        }
+        assert_eq!(queue.borrow().iter().collect::<String>(), "ab");
+    }
+
     #[test]
     fn noop_input_never_produces_intents() {
         let mut input = NoopInput::new();
@@ -1,8 +1,9 @@
 //! `MinifbSurface`: the windowed `DisplaySurface`, presenting the core's
 //! Rgb565 `FrameBuffer565` in a real `minifb` window.
 //!
-//! Two things happen on every `flush`:
-//! 1. Rgb565 -> ARGB8888 conversion, via [`rasterize_scaled`] — the same
+//! Two things happen on every `flush`, for the damaged regions only (the
+//! ARGB buffer persists between frames, like a panel's GRAM):
+//! 1. Rgb565 -> ARGB8888 conversion, via [`rasterize_scaled_in`] — the same
 //!    "expand each channel to 8 bits by left-shifting" convention used by
 //!    `HeadlessSurface`'s PNG path (see that module's doc comment), so the
 //!    two surfaces are provably showing the same colors, not just
@@ -13,6 +14,14 @@
 //!    runtime width/height/scale, since `FrameBuffer565` is sized at
 //!    runtime per the ADR).
 //!
+//! A brightness below 100% (`DisplaySurface::set_brightness`, from the
+//! settings screen or the idle timeout) scales every channel after
+//! conversion, approximating a dimmed backlight; at 100% the output is
+//! untouched, so the parity test below still holds by default. A
+//! brightness change re-rasterizes the whole frame on the next flush,
+//! since the pixels outside that flush's damage were dimmed to the old
+//! level.
+//!
 //! `rasterize_scaled` is a free function, independent of any `minifb::
 //! Window`, specifically so `emulator/tests/surface_parity.rs` can call it
 //! directly and compare its output against `HeadlessSurface`'s PNG without
@@ -37,9 +46,10 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use bhk_core::platform::{DisplaySurface, FrameBuffer565};
+use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::RgbColor;
-use embedded_graphics::Pixel;
+use embedded_graphics::primitives::{PointsIter, Rectangle};
 use minifb::Window;
 
 /// Converts `framebuffer` to an ARGB8888 buffer upscaled by `scale`,
@@ -50,11 +60,21 @@ use minifb::Window;
 ///
 /// Panics (via out-of-bounds indexing) if `out` is smaller than that.
 pub fn rasterize_scaled(framebuffer: &FrameBuffer565, scale: u32, out: &mut [u32]) {
-    let width = framebuffer.width();
-    let scaled_width = width * scale;
+    rasterize_scaled_in(framebuffer, scale, &framebuffer.bounding_box(), out);
+}
 
-    for Pixel(point, color) in framebuffer.pixels() {
-        let argb = argb8888(color);
+/// [`rasterize_scaled`] restricted to the source pixels inside `area`:
+/// everything else in `out` is left as it was.
+///
+/// # Panics
+///
+/// Panics (via out-of-bounds indexing) if `out` is smaller than
+/// [`rasterize_scaled`] requires, or `area` reaches outside the frame.
+pub fn rasterize_scaled_in(framebuffer: &FrameBuffer565, scale: u32, area: &Rectangle, out: &mut [u32]) {
+    let scaled_width = framebuffer.width() * scale;
+
+    for point in area.points() {
+        let argb = argb8888(framebuffer.pixel(point));
         let base_x = point.x as u32 * scale;
         let base_y = point.y as u32 * scale;
 
@@ -80,6 +100,10 @@ pub struct MinifbSurface {
     height: u32,
     scale: u32,
     argb_buffer: Vec<u32>,
+    brightness: u8,
+    /// Set until the first flush and after a brightness change: the next
+    /// flush rasterizes the whole frame instead of just its damage.
+    needs_full_frame: bool,
 }
 
 impl MinifbSurface {
@@ -90,23 +114,57 @@ impl MinifbSurface {
     pub fn new(window: Rc<RefCell<Window>>, width: u32, height: u32, scale: u32) -> Self {
         assert!(scale >= 1, "scale must be at least 1");
         let buffer_len = (width * scale * height * scale) as usize;
-        Self { window, width, height, scale, argb_buffer: vec![0; buffer_len] }
+        Self { window, width, height, scale, argb_buffer: vec![0; buffer_len], brightness: 100, needs_full_frame: true }
+    }
+
+    /// Scales the ARGB pixels `area` (in source pixels) covers by the
+    /// current brightness.
+    fn dim_area(&mut self, area: &Rectangle) {
+        let scaled_width = self.width * self.scale;
+        for point in area.points() {
+            for sy in 0..self.scale {
+                let row = (point.y as u32 * self.scale + sy) * scaled_width;
+                for sx in 0..self.scale {
+                    let index = (row + point.x as u32 * self.scale + sx) as usize;
+                    self.argb_buffer[index] = dim_argb(self.argb_buffer[index], self.brightness);
+                }
+            }
+        }
     }
 }
 
 impl DisplaySurface for MinifbSurface {
     type Error = minifb::Error;
 
-    fn flush(&mut self, framebuffer: &FrameBuffer565) -> Result<(), Self::Error> {
+    fn flush(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) -> Result<(), Self::Error> {
         debug_assert_eq!(framebuffer.width(), self.width, "MinifbSurface sized for a different framebuffer width");
         debug_assert_eq!(framebuffer.height(), self.height, "MinifbSurface sized for a different framebuffer height");
 
-        rasterize_scaled(framebuffer, self.scale, &mut self.argb_buffer);
+        let full_frame = [framebuffer.bounding_box()];
+        let areas = if std::mem::take(&mut self.needs_full_frame) { &full_frame[..] } else { damage };
+        for area in areas {
+            rasterize_scaled_in(framebuffer, self.scale, area, &mut self.argb_buffer);
+            if self.brightness < 100 {
+                self.dim_area(area);
+            }
+        }
 
         let window_width = (self.width * self.scale) as usize;
         let window_height = (self.height * self.scale) as usize;
         self.window.borrow_mut().update_with_buffer(&self.argb_buffer, window_width, window_height)
     }
+
+    fn set_brightness(&mut self, percent: u8) {
+        let percent = percent.min(100);
+        self.needs_full_frame |= percent != self.brightness;
+        self.brightness = percent;
+    }
+}
+
+/// Scales each 8-bit channel of `argb` by `percent` / 100.
+fn dim_argb(argb: u32, percent: u8) -> u32 {
+    let scale = |shift: u32| (((argb >> shift) & 0xFF) * u32::from(percent) / 100) << shift;
+    scale(16) | scale(8) | scale(0)
 }
 
 #[cfg(test)]
@@ -114,17 +172,18 @@ mod tests {
     use super::*;
     use embedded_graphics::draw_target::DrawTarget;
     use embedded_graphics::pixelcolor::WebColors;
-    use embedded_graphics::Drawable;
+    use embedded_graphics::prelude::{Point, Size};
+    use embedded_graphics::{Drawable, Pixel};
 
     #[test]
     fn rasterize_scaled_expands_each_source_pixel_into_a_scale_by_scale_block() {
         let mut framebuffer = FrameBuffer565::new(2, 1);
         framebuffer.clear(Rgb565::BLACK).unwrap();
         // Left pixel red, right pixel blue.
-        Pixel(embedded_graphics::prelude::Point::new(0, 0), Rgb565::RED)
+        Pixel(Point::new(0, 0), Rgb565::RED)
             .draw(&mut framebuffer)
             .unwrap();
-        Pixel(embedded_graphics::prelude::Point::new(1, 0), Rgb565::BLUE)
+        Pixel(Point::new(1, 0), Rgb565::BLUE)
             .draw(&mut framebuffer)
             .unwrap();
 
@@ -145,6 +204,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn rasterize_scaled_in_leaves_everything_outside_the_area_alone() {
+        let mut framebuffer = FrameBuffer565::new(3, 2);
+        framebuffer.clear(Rgb565::RED).unwrap();
+
+        let mut out = vec![0u32; 3 * 2 * 4];
+        rasterize_scaled_in(&framebuffer, 2, &Rectangle::new(Point::new(1, 1), Size::new(1, 1)), &mut out);
+
+        let red = argb8888(Rgb565::RED);
+        let painted: Vec<usize> = (0..out.len()).filter(|&i| out[i] == red).collect();
+        // Source pixel (1, 1) is the 2x2 block at scaled (2..4, 2..4) in a
+        // 6-wide buffer.
+        assert_eq!(painted, vec![14, 15, 20, 21]);
+    }
+
+    #[test]
+    fn dim_argb_scales_every_channel() {
+        assert_eq!(dim_argb(0x00C8_6432, 50), 0x0064_3219);
+        assert_eq!(dim_argb(0x00FF_FFFF, 0), 0);
+    }
+
     #[test]
     fn argb8888_matches_the_headless_png_channel_expansion() {
         // Cross-check against the exact expression `HeadlessSurface` uses,
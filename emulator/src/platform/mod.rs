@@ -27,17 +27,23 @@
 //! can see the same captured frame; [`input::HttpInput`] is the
 //! `InputSource` counterpart, draining a `NavIntent` queue `POST
 //! /api/input` feeds.
+//!
+//! [`recording::RecordingSurface`] wraps either surface to record every
+//! flushed frame as an animated PNG (`desktop --record`, `POST
+//! /api/record/start|stop`).
 
 pub mod clock;
 pub mod headless_surface;
 pub mod host_platform;
 pub mod input;
 pub mod minifb_surface;
+pub mod recording;
 pub mod storage;
 
-pub use clock::HostClock;
+pub use clock::{HostClock, VirtualClock};
 pub use headless_surface::{HeadlessSurface, SharedHeadlessSurface};
 pub use host_platform::HostPlatform;
 pub use input::{HttpInput, NoopInput, WindowedInput};
 pub use minifb_surface::MinifbSurface;
+pub use recording::{Recording, RecordingSurface, SharedRecorder};
 pub use storage::{FileStorage, FileStorageError};
@@ -0,0 +1,385 @@
+//! Screen recording: every frame the app flushes, timestamped by the
+//! platform's `Clock`, exported as an animated PNG. Screenshots catch one
+//! moment; a bug about scrolling, the marquee or a toast needs the motion.
+//!
+//! [`RecordingSurface`] wraps whichever `DisplaySurface` the run mode uses
+//! (`SharedHeadlessSurface` or `MinifbSurface`) and, after each successful
+//! flush, hands the same framebuffer and damage to a [`SharedRecorder`].
+//! The recorder always keeps the current frame (so a recording started
+//! mid-run opens on what's on screen), and while a recording is running it
+//! appends a frame per flush. Start and stop come from `desktop --record
+//! PATH` (the whole run) or `POST /api/record/start` / `POST
+//! /api/record/stop` (see `desktop::SyncServer`).
+//!
+//! Idle time costs nothing. The run loop only flushes when something
+//! changed, so a still screen produces no frames; each frame simply lasts
+//! until the next one, and that span becomes its APNG frame delay. A flush
+//! that leaves every pixel as it was adds no frame either, and a second
+//! flush at the same instant (a frozen `VirtualClock`) replaces the frame
+//! it follows, since that one was never on screen for any time. Under
+//! `--virtual-time` the delays are virtual time, so a recording of a
+//! minute's idle timeout plays back at real speed however fast it was
+//! computed.
+//!
+//! Frames are kept uncompressed until the recording stops (about 160 KB
+//! each at 320x170), and a marquee or scroll changes the screen every
+//! frame, so a recording is capped at [`MAX_RECORDING_BYTES`] of frames.
+//! One that reaches the cap stops capturing and ends there, though it
+//! still has to be stopped to collect it; [`Recording::truncated`] says so,
+//! and both ways of stopping report it. Without the cap, a `--record` left
+//! running over an animation would grow by megabytes a second until the
+//! process ran out of memory.
+//!
+//! APNG rather than GIF: the colours come out exactly as `HeadlessSurface`
+//! renders them (GIF's 256-colour palette would dither the theme's
+//! gradients), it plays in every current browser, and the `png` encoder it
+//! needs is already in the tree under `image`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bhk_core::platform::{Clock, DisplaySurface, FrameBuffer565};
+use embedded_graphics::primitives::Rectangle;
+
+use super::headless_surface::HeadlessSurface;
+
+/// One recorded frame: what was on screen from `at` until the next frame
+/// (or the end of the recording).
+#[derive(Debug, Clone)]
+pub struct RecordedFrame {
+    pub at: Instant,
+    pub image: image::RgbImage,
+}
+
+/// How many bytes of frames one recording may hold: about 1,600 frames,
+/// or 55 seconds of continuous animation, at 320x170.
+pub const MAX_RECORDING_BYTES: usize = 256 * 1024 * 1024;
+
+/// A finished recording, from [`SharedRecorder::stop`].
+#[derive(Debug, Clone)]
+pub struct Recording {
+    frames: Vec<RecordedFrame>,
+    ended: Instant,
+    truncated: bool,
+}
+
+impl Recording {
+    #[must_use]
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Whether the recording reached its byte budget and ended there,
+    /// before it was stopped (see the module doc).
+    #[must_use]
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// How long each frame was on screen: until the next frame, and the
+    /// last one until the recording stopped.
+    #[must_use]
+    pub fn durations(&self) -> Vec<Duration> {
+        let ends = self.frames.iter().skip(1).map(|frame| frame.at).chain(std::iter::once(self.ended));
+        self.frames.iter().zip(ends).map(|(frame, end)| end.saturating_duration_since(frame.at)).collect()
+    }
+
+    /// Encodes the recording as an APNG that loops forever, each frame
+    /// shown for its [`Recording::durations`] entry. `None` if nothing was
+    /// ever flushed while it ran.
+    ///
+    /// # Panics
+    ///
+    /// Panics if in-memory PNG encoding fails, which should not be
+    /// possible: every frame is an RGB buffer of the recording's own size.
+    #[must_use]
+    pub fn encode_apng(&self) -> Option<Vec<u8>> {
+        let first = self.frames.first()?;
+        let mut buffer = Vec::new();
+        let mut encoder = png::Encoder::new(&mut buffer, first.image.width(), first.image.height());
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.frames.len() as u32, 0).expect("an APNG has at least one frame");
+        let mut writer = encoder.write_header().expect("in-memory APNG encode should never fail");
+        for (frame, duration) in self.frames.iter().zip(self.durations()) {
+            let (numerator, denominator) = apng_delay(duration);
+            writer.set_frame_delay(numerator, denominator).expect("frame delays are always valid");
+            writer.write_image_data(frame.image.as_raw()).expect("in-memory APNG encode should never fail");
+        }
+        writer.finish().expect("in-memory APNG encode should never fail");
+        Some(buffer)
+    }
+
+    /// Encodes and writes the recording to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file couldn't be written. Like
+    /// `HeadlessSurface::save_png`, a recording with no frames is a no-op
+    /// `Ok(())`.
+    pub fn save_apng(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        match self.encode_apng() {
+            Some(bytes) => std::fs::write(path, bytes),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An APNG frame delay (`numerator / denominator` seconds) for `duration`:
+/// milliseconds while they fit in a `u16`, whole seconds beyond that.
+fn apng_delay(duration: Duration) -> (u16, u16) {
+    match u16::try_from(duration.as_millis()) {
+        Ok(ms) => (ms, 1000),
+        Err(_) => (u16::try_from(duration.as_secs()).unwrap_or(u16::MAX), 1),
+    }
+}
+
+/// A recording in progress.
+#[derive(Default)]
+struct Capture {
+    frames: Vec<RecordedFrame>,
+    /// The bytes of image data in `frames`.
+    bytes: usize,
+    /// When the byte budget ran out, if it has; nothing is captured after.
+    full_at: Option<Instant>,
+}
+
+struct Recorder {
+    /// The current frame, kept whether or not anything is recording.
+    latest: HeadlessSurface,
+    /// The recording in progress, if any.
+    capture: Option<Capture>,
+    budget: usize,
+}
+
+impl Recorder {
+    fn capture(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle], now: Instant) {
+        self.latest.flush(framebuffer, damage).unwrap_or_else(|never| match never {});
+        if self.capture.as_ref().is_some_and(|capture| capture.full_at.is_none()) {
+            if let Some(image) = self.latest.to_image() {
+                self.append(image, now);
+            }
+        }
+    }
+
+    /// Appends `image` as shown from `now`, collapsing it into the
+    /// recording as the module doc describes, or ends the recording at
+    /// `now` if the frame would take it over budget.
+    fn append(&mut self, image: image::RgbImage, now: Instant) {
+        let Some(capture) = &mut self.capture else { return };
+        if capture.full_at.is_some() {
+            return;
+        }
+        let frames = &mut capture.frames;
+        match frames.last_mut() {
+            Some(last) if last.image == image => {}
+            Some(last) if last.at == now => {
+                last.image = image;
+                if frames.len() >= 2 && frames[frames.len() - 2].image == frames[frames.len() - 1].image {
+                    let dropped = frames.pop().expect("checked above");
+                    capture.bytes -= dropped.image.as_raw().len();
+                }
+            }
+            _ if capture.bytes + image.as_raw().len() > self.budget => {
+                eprintln!("Recording full after {} frame(s); no further frames are captured until it's stopped", frames.len());
+                capture.full_at = Some(now);
+            }
+            _ => {
+                capture.bytes += image.as_raw().len();
+                frames.push(RecordedFrame { at: now, image });
+            }
+        }
+    }
+}
+
+/// The recorder a [`RecordingSurface`] feeds, shared with whoever starts
+/// and stops recordings (`main.rs` for `--record`, the HTTP server for
+/// `/api/record/*`). Timestamps come from the clock it was built with,
+/// which must be the platform's, so a virtual-time run records virtual
+/// time.
+#[derive(Clone)]
+pub struct SharedRecorder {
+    recorder: Arc<Mutex<Recorder>>,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl SharedRecorder {
+    #[must_use]
+    pub fn new(clock: impl Clock + Send + Sync + 'static) -> Self {
+        Self::with_budget(clock, MAX_RECORDING_BYTES)
+    }
+
+    /// A recorder whose recordings hold at most `budget` bytes of frames,
+    /// instead of [`MAX_RECORDING_BYTES`].
+    #[must_use]
+    pub fn with_budget(clock: impl Clock + Send + Sync + 'static, budget: usize) -> Self {
+        let recorder = Recorder { latest: HeadlessSurface::default(), capture: None, budget };
+        Self { recorder: Arc::new(Mutex::new(recorder)), clock: Arc::new(clock) }
+    }
+
+    /// Starts recording, opening on the current frame if one has been
+    /// flushed. Returns `false`, changing nothing, if a recording is
+    /// already running.
+    pub fn start(&self) -> bool {
+        let now = self.clock.now();
+        let mut recorder = self.recorder.lock().unwrap();
+        if recorder.capture.is_some() {
+            return false;
+        }
+        recorder.capture = Some(Capture::default());
+        if let Some(image) = recorder.latest.to_image() {
+            recorder.append(image, now);
+        }
+        true
+    }
+
+    /// Stops recording and returns what was recorded, or `None` if
+    /// nothing was recording. A recording that ran out of budget ends
+    /// where it did, not now.
+    #[must_use]
+    pub fn stop(&self) -> Option<Recording> {
+        let now = self.clock.now();
+        let capture = self.recorder.lock().unwrap().capture.take()?;
+        Some(Recording { frames: capture.frames, ended: capture.full_at.unwrap_or(now), truncated: capture.full_at.is_some() })
+    }
+
+    /// Whether a recording is running, including one that's out of budget
+    /// and waiting to be stopped.
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().unwrap().capture.is_some()
+    }
+
+    fn capture(&self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) {
+        let now = self.clock.now();
+        self.recorder.lock().unwrap().capture(framebuffer, damage, now);
+    }
+}
+
+/// A `DisplaySurface` that presents through `D` and records what it
+/// presented (see the module doc).
+pub struct RecordingSurface<D> {
+    inner: D,
+    recorder: SharedRecorder,
+}
+
+impl<D> RecordingSurface<D> {
+    #[must_use]
+    pub fn new(inner: D, recorder: SharedRecorder) -> Self {
+        Self { inner, recorder }
+    }
+
+    /// The wrapped surface, e.g. for `SharedHeadlessSurface::handle`.
+    #[must_use]
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+}
+
+impl<D: DisplaySurface> DisplaySurface for RecordingSurface<D> {
+    type Error = D::Error;
+
+    /// Flushes through the wrapped surface, then records the frame, only
+    /// if the flush succeeded: a recording shows what reached the screen.
+    fn flush(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) -> Result<(), Self::Error> {
+        self.inner.flush(framebuffer, damage)?;
+        self.recorder.capture(framebuffer, damage);
+        Ok(())
+    }
+
+    /// Passed through; the recording keeps full brightness, so a dimmed or
+    /// sleeping screen still shows what it's displaying.
+    fn set_brightness(&mut self, percent: u8) {
+        self.inner.set_brightness(percent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::VirtualClock;
+    use embedded_graphics::draw_target::DrawTarget;
+    use embedded_graphics::geometry::Dimensions;
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_graphics::prelude::RgbColor;
+    use image::AnimationDecoder;
+
+    fn solid(color: Rgb565) -> FrameBuffer565 {
+        let mut framebuffer = FrameBuffer565::new(4, 2);
+        framebuffer.clear(color).unwrap();
+        framebuffer
+    }
+
+    fn flush(surface: &mut RecordingSurface<HeadlessSurface>, framebuffer: &FrameBuffer565) {
+        surface.flush(framebuffer, &[framebuffer.bounding_box()]).unwrap();
+    }
+
+    #[test]
+    fn idle_time_and_repeated_frames_become_longer_frames_not_more_of_them() {
+        let clock = VirtualClock::new();
+        let recorder = SharedRecorder::new(clock.clone());
+        let mut surface = RecordingSurface::new(HeadlessSurface::new(), recorder.clone());
+        flush(&mut surface, &solid(Rgb565::RED));
+
+        assert!(recorder.start());
+        assert!(!recorder.start(), "one recording at a time");
+        clock.advance(Duration::from_millis(100));
+        flush(&mut surface, &solid(Rgb565::RED));
+        clock.advance(Duration::from_millis(400));
+        flush(&mut surface, &solid(Rgb565::GREEN));
+        flush(&mut surface, &solid(Rgb565::BLUE));
+        clock.advance(Duration::from_secs(70));
+        let recording = recorder.stop().expect("a recording was running");
+
+        assert!(!recorder.is_recording());
+        assert_eq!(recording.frames().len(), 2, "the repeat is dropped and the same-instant green replaced");
+        assert_eq!(recording.durations(), [Duration::from_millis(500), Duration::from_secs(70)]);
+        assert_eq!(apng_delay(Duration::from_millis(500)), (500, 1000));
+        assert_eq!(apng_delay(Duration::from_secs(70)), (70, 1));
+    }
+
+    #[test]
+    fn the_apng_plays_back_each_frame_for_its_duration() {
+        let clock = VirtualClock::new();
+        let recorder = SharedRecorder::new(clock.clone());
+        let mut surface = RecordingSurface::new(HeadlessSurface::new(), recorder.clone());
+        assert!(recorder.start());
+        assert!(recorder.stop().unwrap().encode_apng().is_none(), "nothing flushed, nothing to encode");
+
+        assert!(recorder.start());
+        flush(&mut surface, &solid(Rgb565::RED));
+        clock.advance(Duration::from_millis(250));
+        flush(&mut surface, &solid(Rgb565::BLUE));
+        clock.advance(Duration::from_millis(1000));
+        let apng = recorder.stop().unwrap().encode_apng().unwrap();
+
+        let decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(apng)).unwrap();
+        let frames = decoder.apng().unwrap().into_frames().collect_frames().unwrap();
+        let delays: Vec<_> = frames.iter().map(|frame| Duration::from(frame.delay())).collect();
+        assert_eq!(delays, [Duration::from_millis(250), Duration::from_millis(1000)]);
+        let blue = image::Rgba([Rgb565::BLUE.r() << 3, Rgb565::BLUE.g() << 2, Rgb565::BLUE.b() << 3, 255]);
+        assert_eq!(*frames[1].buffer().get_pixel(3, 1), blue);
+    }
+
+    #[test]
+    fn a_recording_over_budget_ends_at_the_frame_that_would_exceed_it() {
+        // A 4x2 RGB frame is 24 bytes, so two fit and the third doesn't.
+        let clock = VirtualClock::new();
+        let recorder = SharedRecorder::with_budget(clock.clone(), 50);
+        let mut surface = RecordingSurface::new(HeadlessSurface::new(), recorder.clone());
+        assert!(recorder.start());
+        for color in [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE, Rgb565::RED] {
+            flush(&mut surface, &solid(color));
+            clock.advance(Duration::from_millis(100));
+        }
+        assert!(recorder.is_recording(), "a full recording still waits to be stopped");
+        clock.advance(Duration::from_secs(60));
+        let recording = recorder.stop().unwrap();
+
+        assert!(recording.truncated());
+        assert_eq!(recording.durations(), [Duration::from_millis(100), Duration::from_millis(100)]);
+
+        assert!(recorder.start(), "the next recording starts with a fresh budget");
+        assert!(!recorder.stop().unwrap().truncated());
+    }
+}
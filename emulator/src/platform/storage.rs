@@ -1,36 +1,85 @@
-//! Host `Storage`: an opaque-blob key/value store backed by a single JSON
-//! file on disk.
+//! Host `Storage`: an opaque-blob key/value store backed by a single
+//! append-only log file on disk.
 //!
-//! This wraps the same technique `desktop::storage::DesktopStorage` already
-//! uses (a JSON file under a data directory, `create_dir_all` on first
-//! write, `serde_json` for the on-disk format) but generalizes it: the core
-//! `Storage` trait is `get(key) -> Option<Vec<u8>>` / `set(key, value)`, an
-//! opaque byte-blob KV store, not the domain-specific `Vec<Credential>`
-//! shape `DesktopStorage` persists. The two are intentionally separate
-//! files/types — `DesktopStorage` still backs the existing HTTP credential
-//! sync path (`./data/credentials.json`); `FileStorage` is the new
-//! `bhk_core::platform::Storage` implementation the render-core migration
-//! will use going forward, defaulting to a different file
-//! (`./data/kv_store.json`) so the two never collide.
+//! This generalizes what `desktop::storage::DesktopStorage` does for the
+//! HTTP credential sync (a file under a data directory, `create_dir_all`
+//! on first write): the core `Storage` trait is `get(key) ->
+//! Option<Vec<u8>>` / `set(key, value)`, an opaque byte-blob KV store, not
+//! the domain-specific `Vec<Credential>` shape `DesktopStorage` persists.
+//! The two are intentionally separate files/types — `DesktopStorage` still
+//! backs the existing HTTP credential sync path (`./data/credentials.json`);
+//! `FileStorage` is the `bhk_core::platform::Storage` implementation the
+//! render core uses, defaulting to a different file
+//! (`./data/kv_store.bin`) so the two never collide.
 //!
-//! Known limitation (deferred, not a correctness issue): `Vec<u8>` values
-//! serialize through `serde_json` as JSON arrays of numbers, not a compact
-//! binary encoding. That is fine for the small blobs this project persists
-//! today; if that changes, swapping the on-disk codec (e.g. to `ciborium`,
-//! already a workspace dependency) is a change local to `persist`/`load`
-//! below, not to the `Storage` trait or its callers.
+//! # On-disk format
+//!
+//! An 8-byte header ([`MAGIC`], which ends in the format version), then
+//! records, each one `set` or `remove`:
+//!
+//! ```text
+//! body_len: u32 LE | crc32(body): u32 LE | body = key_len: u32 LE, key (UTF-8), value
+//! ```
+//!
+//! A `remove` is a tombstone: a record with [`TOMBSTONE`] set in
+//! `key_len` and no value.
+//!
+//! Values are stored as raw bytes (this used to be a JSON file, with every
+//! byte spelled out as a number). A `set` appends one record and
+//! `fsync`s it; the last record for a key wins. Once the log is more than
+//! twice the size of what it holds (and past [`COMPACT_MIN_LEN`]), the next
+//! `set` compacts it instead: the live entries are written to a temp file
+//! beside it, which is `fsync`ed and renamed over the log, so the file on
+//! disk is always either the old log or the new one.
+//!
+//! # Crash recovery
+//!
+//! Only an append can be cut short by a crash, and only at the end of the
+//! file. Loading reads records until one is incomplete or fails its CRC,
+//! keeps everything before it, and (if anything was left over) compacts at
+//! once, so later appends never land after garbage. A crash therefore
+//! loses at most the `set` in flight; `emulator`'s tests truncate a log at
+//! every byte offset to hold it to that. Damage in the middle of the file
+//! (not something a crash produces) loses the records from that point on
+//! rather than failing to open. A `set` or `remove` whose write fails
+//! without a crash is undone in memory as well, so `get` keeps answering
+//! what the file holds.
+//!
+//! A store from before this format (`kv_store.json`, a JSON object of byte
+//! arrays) is imported on first open: if the log doesn't exist but a
+//! `.json` file of the same name does, its entries become the new log and
+//! the JSON file is removed.
 
+use crate::faults::SharedFaults;
 use bhk_core::platform::Storage;
+use crc::{Crc, CRC_32_ISO_HDLC};
 use std::collections::HashMap;
 use std::fmt;
-use std::fs;
-use std::io;
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The first 8 bytes of every log: a tag and, last, the format version.
+pub const MAGIC: [u8; 8] = *b"BHKKV\0\0\x01";
+/// Logs smaller than this are never compacted: rewriting a few hundred
+/// bytes to save a few hundred bytes isn't worth a rename.
+pub const COMPACT_MIN_LEN: u64 = 4096;
+/// Set in a record's `key_len` to mark it a removal.
+pub const TOMBSTONE: u32 = 1 << 31;
+/// `body_len` and `crc32`, before each record's body.
+const RECORD_HEADER_LEN: usize = 8;
+const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
 
 #[derive(Debug)]
 pub enum FileStorageError {
     Io(io::Error),
+    /// A legacy JSON store couldn't be imported.
     Serde(serde_json::Error),
+    /// The file exists but doesn't start with [`MAGIC`]: it isn't a KV
+    /// log, or it's a version this build doesn't read.
+    BadHeader,
+    /// The write was failed on purpose (`faults::Faults::fail_storage_writes`).
+    Injected,
 }
 
 impl fmt::Display for FileStorageError {
@@ -38,6 +87,8 @@ impl fmt::Display for FileStorageError {
         match self {
             FileStorageError::Io(e) => write!(f, "storage I/O error: {e}"),
             FileStorageError::Serde(e) => write!(f, "storage (de)serialization error: {e}"),
+            FileStorageError::BadHeader => f.write_str("storage file isn't a kv store log (bad header)"),
+            FileStorageError::Injected => f.write_str("storage write failed (injected fault)"),
         }
     }
 }
@@ -59,26 +110,34 @@ impl From<serde_json::Error> for FileStorageError {
 /// File-backed opaque-blob key/value store: the host `bhk_core::platform::
 /// Storage` implementation. Loads eagerly at construction, keeps an
 /// in-memory `HashMap` as the read path (`get` never touches disk), and
-/// persists the whole map back to disk on every `set` (fine for this
-/// project's write frequency and data size; not designed for high-frequency
-/// writes or large blobs).
+/// appends one checksummed record per `set` (see the module doc for the
+/// format, compaction and crash recovery).
 pub struct FileStorage {
     file_path: PathBuf,
     entries: HashMap<String, Vec<u8>>,
+    /// Bytes of valid log on disk; `0` when there's no log yet, or the
+    /// last append may have left a torn tail. Either way the next `set`
+    /// compacts rather than appends.
+    log_len: u64,
+    /// Consulted on every write when set; see [`FileStorage::with_faults`].
+    faults: Option<SharedFaults>,
 }
 
 impl FileStorage {
     /// Opens (or creates) the KV store backed by `file_path`. Creates the
     /// parent directory if it doesn't exist, mirroring `DesktopStorage::
-    /// new`. If `file_path` doesn't exist yet, starts from an empty map
-    /// (also mirroring `DesktopStorage::load`'s "no file yet" case).
+    /// new`. If `file_path` doesn't exist yet, starts from an empty map (or
+    /// from a legacy JSON store beside it; see the module doc). Nothing is
+    /// written until the first `set`, unless a torn tail has to be cut off
+    /// or a legacy store imported.
     ///
     /// # Errors
     ///
     /// Returns `FileStorageError::Io` if the parent directory couldn't be
-    /// created or the existing file couldn't be read, or
-    /// `FileStorageError::Serde` if the existing file's contents aren't
-    /// valid JSON for the expected shape.
+    /// created or a file couldn't be read or rewritten,
+    /// `FileStorageError::BadHeader` if the existing file isn't a KV log,
+    /// or `FileStorageError::Serde` if a legacy JSON store isn't valid
+    /// JSON for the expected shape.
     pub fn new(file_path: impl Into<PathBuf>) -> Result<Self, FileStorageError> {
         let file_path = file_path.into();
 
@@ -88,33 +147,202 @@ impl FileStorage {
             }
         }
 
-        let entries = if file_path.exists() {
-            let contents = fs::read_to_string(&file_path)?;
-            serde_json::from_str(&contents)?
-        } else {
-            HashMap::new()
+        let legacy_path = file_path.with_extension("json");
+        if !file_path.exists() && legacy_path != file_path && legacy_path.exists() {
+            let entries = serde_json::from_str(&fs::read_to_string(&legacy_path)?)?;
+            let mut storage = Self { file_path, entries, log_len: 0, faults: None };
+            storage.compact()?;
+            fs::remove_file(&legacy_path)?;
+            return Ok(storage);
+        }
+
+        let bytes = match fs::read(&file_path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
         };
+        let (entries, valid_len) = decode_log(&bytes)?;
+        let mut storage = Self { file_path, entries, log_len: valid_len as u64, faults: None };
+        if valid_len < bytes.len() {
+            storage.compact()?;
+        }
+        Ok(storage)
+    }
 
-        Ok(Self { file_path, entries })
+    /// Makes writes fail with [`FileStorageError::Injected`] whenever
+    /// `faults` says storage writes should (`desktop`'s fault-injection
+    /// API). Reads are unaffected: what was stored stays readable.
+    #[must_use]
+    pub fn with_faults(mut self, faults: SharedFaults) -> Self {
+        self.faults = Some(faults);
+        self
     }
 
-    /// Default on-disk location for the host emulator: `./data/kv_store.json`,
+    /// Default on-disk location for the host emulator: `./data/kv_store.bin`,
     /// deliberately distinct from `DesktopStorage`'s `./data/credentials.json`.
     ///
     /// # Errors
     ///
     /// See [`FileStorage::new`].
     pub fn new_default() -> Result<Self, FileStorageError> {
-        Self::new("./data/kv_store.json")
+        Self::new("./data/kv_store.bin")
+    }
+
+    /// Puts `key` back the way it was before a change whose write failed
+    /// (`previous` is its old value, `None` if it had none), so `get` never
+    /// answers with something the log doesn't hold.
+    fn restore(&mut self, key: &str, previous: Option<Vec<u8>>) {
+        match previous {
+            Some(value) => self.entries.insert(key.to_string(), value),
+            None => self.entries.remove(key),
+        };
+    }
+
+    /// What a freshly compacted log of the current entries would take.
+    fn live_len(&self) -> u64 {
+        let records: usize = self.entries.iter().map(|(key, value)| RECORD_HEADER_LEN + 4 + key.len() + value.len()).sum();
+        (MAGIC.len() + records) as u64
+    }
+
+    /// Persists one record for a change already made to `entries`:
+    /// appended, or the whole log compacted once appending would leave it
+    /// mostly garbage (see the module doc). An injected fault fails here,
+    /// where a real I/O error would, and either way the caller undoes the
+    /// change (see [`FileStorage::restore`]).
+    fn write(&mut self, record: &[u8]) -> Result<(), FileStorageError> {
+        if self.faults.as_ref().is_some_and(SharedFaults::storage_writes_fail) {
+            return Err(FileStorageError::Injected);
+        }
+        let appended_len = self.log_len + record.len() as u64;
+        if self.log_len == 0 || (appended_len > COMPACT_MIN_LEN && appended_len > 2 * self.live_len()) {
+            self.compact()
+        } else {
+            self.append(record)
+        }
+    }
+
+    /// Appends `record` and `fsync`s it. On failure the tail may be torn,
+    /// so the next `set` compacts.
+    fn append(&mut self, record: &[u8]) -> Result<(), FileStorageError> {
+        let appended = OpenOptions::new().append(true).open(&self.file_path).and_then(|mut file| {
+            file.write_all(record)?;
+            file.sync_data()
+        });
+        match appended {
+            Ok(()) => {
+                self.log_len += record.len() as u64;
+                Ok(())
+            }
+            Err(e) => {
+                self.log_len = 0;
+                Err(e.into())
+            }
+        }
     }
 
-    fn persist(&self) -> Result<(), FileStorageError> {
-        let json = serde_json::to_string_pretty(&self.entries)?;
-        fs::write(&self.file_path, json)?;
+    /// Rewrites the log as just the live entries, atomically: temp file,
+    /// `fsync`, rename over the log, then `fsync` the directory so the
+    /// rename itself survives a crash.
+    fn compact(&mut self) -> Result<(), FileStorageError> {
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+        let mut log = MAGIC.to_vec();
+        for key in keys {
+            log.extend(encode_record(key, Some(&self.entries[key])));
+        }
+
+        let mut temp_name = self.file_path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = self.file_path.with_file_name(temp_name);
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(&log)?;
+        temp.sync_all()?;
+        drop(temp);
+        fs::rename(&temp_path, &self.file_path)?;
+        sync_parent_dir(&self.file_path);
+
+        self.log_len = log.len() as u64;
         Ok(())
     }
 }
 
+/// `fsync`s `path`'s directory. Best effort: not every platform can open a
+/// directory to sync it (Windows can't), and the rename has happened
+/// either way.
+fn sync_parent_dir(path: &Path) {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if let Ok(dir) = File::open(parent) {
+        dir.sync_all().ok();
+    }
+}
+
+/// One log record: `key` = `value`, or a tombstone for `key` when `value`
+/// is `None` (see the module doc).
+fn encode_record(key: &str, value: Option<&[u8]>) -> Vec<u8> {
+    let key_len = key.len() as u32 | if value.is_none() { TOMBSTONE } else { 0 };
+    let value = value.unwrap_or_default();
+    let mut body = Vec::with_capacity(4 + key.len() + value.len());
+    body.extend_from_slice(&key_len.to_le_bytes());
+    body.extend_from_slice(key.as_bytes());
+    body.extend_from_slice(value);
+
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN + body.len());
+    record.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    record.extend_from_slice(&CRC.checksum(&body).to_le_bytes());
+    record.extend(body);
+    record
+}
+
+/// Replays a log, returning its entries and how many leading bytes of it
+/// are valid: everything up to the first incomplete or corrupt record. An
+/// empty file, or one cut off inside the header, is an empty store with
+/// nothing valid.
+///
+/// # Errors
+///
+/// [`FileStorageError::BadHeader`] if `bytes` doesn't start with (a prefix
+/// of) [`MAGIC`].
+fn decode_log(bytes: &[u8]) -> Result<(HashMap<String, Vec<u8>>, usize), FileStorageError> {
+    let mut entries = HashMap::new();
+    if bytes.len() < MAGIC.len() {
+        return if MAGIC.starts_with(bytes) { Ok((entries, 0)) } else { Err(FileStorageError::BadHeader) };
+    }
+    if bytes[..MAGIC.len()] != MAGIC {
+        return Err(FileStorageError::BadHeader);
+    }
+
+    let mut valid = MAGIC.len();
+    while let Some((key, value, len)) = decode_record(&bytes[valid..]) {
+        match value {
+            Some(value) => entries.insert(key, value),
+            None => entries.remove(&key),
+        };
+        valid += len;
+    }
+    Ok((entries, valid))
+}
+
+/// The record at the start of `bytes` (its key, its value or `None` for a
+/// tombstone) and its length, or `None` if it's incomplete or corrupt.
+fn decode_record(bytes: &[u8]) -> Option<(String, Option<Vec<u8>>, usize)> {
+    let header = bytes.get(..RECORD_HEADER_LEN)?;
+    let body_len = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+    let crc = u32::from_le_bytes(header[4..].try_into().ok()?);
+    let body = bytes.get(RECORD_HEADER_LEN..RECORD_HEADER_LEN.checked_add(body_len)?)?;
+    if CRC.checksum(body) != crc {
+        return None;
+    }
+    let key_len = u32::from_le_bytes(body.get(..4)?.try_into().ok()?);
+    let removed = key_len & TOMBSTONE != 0;
+    let key_len = (key_len & !TOMBSTONE) as usize;
+    let key = std::str::from_utf8(body.get(4..4usize.checked_add(key_len)?)?).ok()?;
+    let value = (!removed).then(|| body[4 + key_len..].to_vec());
+    Some((key.to_string(), value, RECORD_HEADER_LEN + body_len))
+}
+
 impl Storage for FileStorage {
     type Error = FileStorageError;
 
@@ -123,8 +351,24 @@ impl Storage for FileStorage {
     }
 
     fn set(&mut self, key: &str, value: Vec<u8>) -> Result<(), Self::Error> {
-        self.entries.insert(key.to_string(), value);
-        self.persist()
+        let record = encode_record(key, Some(&value));
+        let previous = self.entries.insert(key.to_string(), value);
+        let written = self.write(&record);
+        if written.is_err() {
+            self.restore(key, previous);
+        }
+        written
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), Self::Error> {
+        let Some(previous) = self.entries.remove(key) else {
+            return Ok(());
+        };
+        let written = self.write(&encode_record(key, None));
+        if written.is_err() {
+            self.restore(key, Some(previous));
+        }
+        written
     }
 }
 
@@ -135,7 +379,7 @@ mod tests {
     fn temp_path(name: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
         path.push(format!(
-            "bhk-emulator-file-storage-test-{name}-{}.json",
+            "bhk-emulator-file-storage-test-{name}-{}.bin",
             uuid::Uuid::new_v4()
         ));
         path
@@ -179,4 +423,158 @@ mod tests {
         assert_eq!(storage.get("k"), Some(vec![2, 2]));
         fs::remove_file(&path).ok();
     }
+
+    #[test]
+    fn injected_write_faults_fail_sets_until_cleared_and_leave_reads_alone() {
+        let path = temp_path("faults");
+        let faults = SharedFaults::new();
+        let mut storage = FileStorage::new(&path).unwrap().with_faults(faults.clone());
+        storage.set("k", vec![1]).unwrap();
+
+        faults.set(crate::faults::Faults { fail_storage_writes: true, ..Default::default() });
+        assert!(matches!(storage.set("k", vec![2]), Err(FileStorageError::Injected)));
+        assert_eq!(storage.get("k"), Some(vec![1]));
+
+        faults.clear();
+        storage.set("k", vec![3]).unwrap();
+        assert_eq!(FileStorage::new(&path).unwrap().get("k"), Some(vec![3]));
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_old_entries_in_memory_and_on_disk() {
+        let path = temp_path("failed-write");
+        let faults = SharedFaults::new();
+        let mut storage = FileStorage::new(&path).unwrap().with_faults(faults.clone());
+        storage.set("kept", vec![1]).unwrap();
+        storage.set("doomed", vec![9]).unwrap();
+
+        faults.set(crate::faults::Faults { fail_storage_writes: true, ..Default::default() });
+        assert!(matches!(storage.set("kept", vec![2]), Err(FileStorageError::Injected)));
+        assert!(matches!(storage.set("new", vec![3]), Err(FileStorageError::Injected)));
+        assert!(matches!(storage.remove("doomed"), Err(FileStorageError::Injected)));
+
+        let reopened = FileStorage::new(&path).unwrap();
+        for store in [&storage, &reopened] {
+            assert_eq!(store.get("kept"), Some(vec![1]));
+            assert_eq!(store.get("new"), None);
+            assert_eq!(store.get("doomed"), Some(vec![9]));
+        }
+        fs::remove_file(&path).ok();
+    }
+
+    /// A log written by `sets` one at a time, and the store's contents
+    /// after each prefix of them (`snapshots[i]` = after the first `i`).
+    fn log_of(sets: &[(&str, &[u8])]) -> (Vec<u8>, Vec<HashMap<String, Vec<u8>>>) {
+        let path = temp_path("log");
+        let mut storage = FileStorage::new(&path).unwrap();
+        let mut snapshots = vec![HashMap::new()];
+        for (key, value) in sets {
+            storage.set(key, value.to_vec()).unwrap();
+            snapshots.push(storage.entries.clone());
+        }
+        let log = fs::read(&path).unwrap();
+        fs::remove_file(&path).ok();
+        (log, snapshots)
+    }
+
+    #[test]
+    fn values_are_stored_as_raw_bytes_not_json() {
+        let (log, _) = log_of(&[("blob", &[0xAB; 64])]);
+        assert_eq!(log[..MAGIC.len()], MAGIC);
+        assert_eq!(log.len(), MAGIC.len() + RECORD_HEADER_LEN + 4 + "blob".len() + 64);
+    }
+
+    #[test]
+    fn a_log_cut_off_at_any_byte_loses_at_most_the_write_in_flight() {
+        let sets: &[(&str, &[u8])] =
+            &[("brightness", &[80]), ("theme", b"dark"), ("brightness", &[40]), ("idle", &[0, 0, 1, 44]), ("theme", b"light")];
+        let (log, snapshots) = log_of(sets);
+        let path = temp_path("torn");
+
+        // Where each complete log ends: after the header and after each set.
+        let mut ends = vec![MAGIC.len()];
+        for (key, value) in sets {
+            ends.push(ends.last().unwrap() + RECORD_HEADER_LEN + 4 + key.len() + value.len());
+        }
+        assert_eq!(*ends.last().unwrap(), log.len());
+
+        for cut in 0..=log.len() {
+            fs::write(&path, &log[..cut]).unwrap();
+            let mut storage = FileStorage::new(&path).unwrap_or_else(|e| panic!("cut at {cut}: {e}"));
+            let survived = ends.iter().filter(|&&end| end <= cut).count().saturating_sub(1);
+            assert_eq!(storage.entries, snapshots[survived], "cut at {cut}");
+
+            // The torn tail is gone: a later write lands where it's read.
+            storage.set("after", vec![cut as u8]).unwrap();
+            let reopened = FileStorage::new(&path).unwrap();
+            assert_eq!(reopened.get("after"), Some(vec![cut as u8]), "cut at {cut}");
+            assert_eq!(reopened.entries.len(), snapshots[survived].len() + 1, "cut at {cut}");
+        }
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn a_corrupt_record_drops_it_and_everything_after_it() {
+        let (mut log, snapshots) = log_of(&[("a", b"1"), ("b", b"2"), ("c", b"3")]);
+        let second = MAGIC.len() + RECORD_HEADER_LEN + 4 + 1 + 1;
+        log[second + RECORD_HEADER_LEN + 4] ^= 0xFF;
+        let path = temp_path("corrupt");
+        fs::write(&path, &log).unwrap();
+
+        assert_eq!(FileStorage::new(&path).unwrap().entries, snapshots[1]);
+        assert!((fs::metadata(&path).unwrap().len() as usize) < log.len(), "the damage is cut off on open");
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn a_removed_key_stays_removed_after_reopening_and_compacting() {
+        let path = temp_path("remove");
+        let mut storage = FileStorage::new(&path).unwrap();
+        storage.set("a", vec![1]).unwrap();
+        storage.set("b", vec![2]).unwrap();
+        storage.remove("a").unwrap();
+        storage.remove("never-set").unwrap();
+        assert_eq!(storage.get("a"), None);
+
+        let mut reopened = FileStorage::new(&path).unwrap();
+        assert_eq!((reopened.get("a"), reopened.get("b")), (None, Some(vec![2])));
+        reopened.compact().unwrap();
+        assert_eq!(FileStorage::new(&path).unwrap().entries.len(), 1);
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn a_file_that_isnt_a_log_is_refused_rather_than_overwritten() {
+        let path = temp_path("foreign");
+        fs::write(&path, b"not a kv store").unwrap();
+        assert!(matches!(FileStorage::new(&path), Err(FileStorageError::BadHeader)));
+        assert_eq!(fs::read(&path).unwrap(), b"not a kv store");
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn rewriting_one_key_compacts_the_log_instead_of_growing_it_forever() {
+        let path = temp_path("compaction");
+        let mut storage = FileStorage::new(&path).unwrap();
+        for i in 0..2000u32 {
+            storage.set("counter", i.to_le_bytes().to_vec()).unwrap();
+        }
+        assert!(fs::metadata(&path).unwrap().len() <= 2 * COMPACT_MIN_LEN);
+        assert_eq!(FileStorage::new(&path).unwrap().get("counter"), Some(1999u32.to_le_bytes().to_vec()));
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn a_legacy_json_store_beside_the_log_is_imported_once() {
+        let path = temp_path("legacy");
+        let legacy = path.with_extension("json");
+        fs::write(&legacy, r#"{"theme": [100, 97, 114, 107]}"#).unwrap();
+
+        let storage = FileStorage::new(&path).unwrap();
+        assert_eq!(storage.get("theme"), Some(b"dark".to_vec()));
+        assert!(!legacy.exists());
+        assert_eq!(FileStorage::new(&path).unwrap().get("theme"), Some(b"dark".to_vec()));
+        fs::remove_file(&path).ok();
+    }
 }
@@ -0,0 +1,288 @@
+//! The scenario file format `desktop --scenario FILE` reads: TOML, one
+//! `[[step]]` table per step, each holding exactly one action key.
+//!
+//! ```toml
+//! panel = [320, 170]            # optional, the default
+//!
+//! [[step]]
+//! seed = [
+//!     { name = "GitHub", username = "octocat", password = "hunter2", uri = "https://github.com" },
+//!     { name = "Postgres", username = "postgres" },
+//! ]
+//!
+//! [[step]]
+//! input = ["Next", { NextN = 5 }, "Activate"]   # NavIntent, as POST /api/input takes it
+//!
+//! [[step]]
+//! type = "po"                   # one `Char` intent per character
+//!
+//! [[step]]
+//! frames = 3                    # run 3 frames of simulated time
+//!
+//! [[step]]
+//! wait = "2500ms"               # jump the clock ("ms", "s" or "m"), then run a frame
+//!
+//! [[step]]
+//! expect = { title = "Postgres", depth = 2, pixels = [{ x = 4, y = 4, color = "#101418" }] }
+//!
+//! [[step]]
+//! screenshot = "out/detail.png" # relative to the scenario file
+//!
+//! [[step]]
+//! compare = "golden/detail.png" # exact match against an approved PNG
+//! ```
+//!
+//! The step enum is serde's externally tagged form, so a step table with
+//! zero or two action keys, or an unknown one, is a parse error naming
+//! the step rather than something silently skipped.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bhk_core::input::NavIntent;
+use push_protocol::Credential;
+use serde::{Deserialize, Deserializer};
+
+/// The T-Embed's ST7789, as `desktop` itself defaults to.
+const DEFAULT_PANEL: (u32, u32) = (320, 170);
+
+/// A parsed scenario file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// `[width, height]` of the emulated panel.
+    #[serde(default = "default_panel")]
+    pub panel: (u32, u32),
+    #[serde(rename = "step", default)]
+    pub steps: Vec<Step>,
+}
+
+fn default_panel() -> (u32, u32) {
+    DEFAULT_PANEL
+}
+
+/// One scenario step. Every step except `expect`, `screenshot` and
+/// `compare` runs at least one frame, so its effect is on screen before
+/// the next step looks.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    /// Replaces the vault with these credentials, the way a companion
+    /// push does, then runs a frame so the app syncs them.
+    Seed(Vec<SeedCredential>),
+    /// Delivers these intents in one input poll, then runs a frame.
+    Input(Vec<NavIntent>),
+    /// Delivers one `NavIntent::Char` per character in one input poll,
+    /// then runs a frame.
+    Type(String),
+    /// Runs this many frames, each one frame period of simulated time.
+    Frames(u32),
+    /// Moves the clock forward by this span in one jump, then runs a
+    /// frame: timeouts and toast expiry see the whole span at once, so
+    /// an idle minute costs one frame, not 1800.
+    #[serde(deserialize_with = "duration")]
+    Wait(Duration),
+    /// Checks what's on screen; fails the scenario on the first mismatch.
+    Expect(Expectation),
+    /// Saves the current frame as a PNG.
+    Screenshot(PathBuf),
+    /// Compares the current frame with an approved PNG, pixel for pixel.
+    Compare(PathBuf),
+}
+
+/// A credential to seed. Only `name` is required; the id is assigned from
+/// the credential's position, so every run of a scenario seeds the same
+/// ids.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedCredential {
+    pub name: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    pub uri: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl SeedCredential {
+    /// The wire credential this seeds, as the `index`th of its step.
+    #[must_use]
+    pub fn to_credential(&self, index: usize) -> Credential {
+        Credential {
+            id: uuid::Uuid::from_u128(index as u128 + 1),
+            name: self.name.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+            uri: self.uri.clone(),
+            notes: self.notes.clone(),
+        }
+    }
+}
+
+/// What an `expect` step checks. Every field is optional and only the
+/// ones given are checked; text comparisons are exact.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    /// The title bar's text (see `App::chrome`).
+    pub title: Option<String>,
+    /// The title bar's position readout, e.g. `"2 / 5"`.
+    pub readout: Option<String>,
+    /// The hint bar's text.
+    pub hint: Option<String>,
+    /// The showing toast's message.
+    pub toast: Option<String>,
+    /// The open dialog's title.
+    pub dialog: Option<String>,
+    /// How many screens are on the navigator's stack.
+    pub depth: Option<usize>,
+    /// Whether the idle timeout has turned the screen off.
+    pub asleep: Option<bool>,
+    /// Pixels of the last flushed frame, in screenshot colors.
+    #[serde(default)]
+    pub pixels: Vec<PixelExpectation>,
+}
+
+/// One pixel an `expect` step checks.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PixelExpectation {
+    pub x: u32,
+    pub y: u32,
+    /// `"#rrggbb"`, as the pixel appears in a screenshot PNG (each
+    /// `Rgb565` channel widened by a left shift, so the low bits are 0).
+    #[serde(deserialize_with = "hex_color")]
+    pub color: [u8; 3],
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse_duration(&text).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "expected a span like \"250ms\", \"2s\" or \"5m\", got {text:?}"
+        ))
+    })
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 3], D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse_hex_color(&text).ok_or_else(|| {
+        serde::de::Error::custom(format!("expected a color like \"#1a2b3c\", got {text:?}"))
+    })
+}
+
+/// Parses `"<whole number><unit>"` with unit `ms`, `s` or `m`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    match unit {
+        "ms" => Some(Duration::from_millis(number)),
+        "s" => Some(Duration::from_secs(number)),
+        "m" => Some(Duration::from_secs(number.checked_mul(60)?)),
+        _ => None,
+    }
+}
+
+/// Parses `"#rrggbb"`.
+fn parse_hex_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_step_kind_parses_from_its_documented_form() {
+        let scenario: Scenario = toml::from_str(
+            r##"
+            panel = [128, 32]
+
+            [[step]]
+            seed = [{ name = "GitHub", username = "octocat" }]
+            [[step]]
+            input = ["Next", { NextN = 5 }, { Char = "g" }]
+            [[step]]
+            type = "po"
+            [[step]]
+            frames = 3
+            [[step]]
+            wait = "2s"
+            [[step]]
+            expect = { title = "Vault", depth = 1, pixels = [{ x = 1, y = 2, color = "#ff0080" }] }
+            [[step]]
+            screenshot = "out.png"
+            [[step]]
+            compare = "golden.png"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.panel, (128, 32));
+        assert_eq!(
+            scenario.steps,
+            vec![
+                Step::Seed(vec![SeedCredential {
+                    name: "GitHub".into(),
+                    username: "octocat".into(),
+                    password: String::new(),
+                    uri: None,
+                    notes: None,
+                }]),
+                Step::Input(vec![
+                    NavIntent::Next,
+                    NavIntent::NextN(5),
+                    NavIntent::Char('g')
+                ]),
+                Step::Type("po".into()),
+                Step::Frames(3),
+                Step::Wait(Duration::from_secs(2)),
+                Step::Expect(Expectation {
+                    title: Some("Vault".into()),
+                    depth: Some(1),
+                    pixels: vec![PixelExpectation {
+                        x: 1,
+                        y: 2,
+                        color: [0xff, 0x00, 0x80]
+                    }],
+                    ..Expectation::default()
+                }),
+                Step::Screenshot("out.png".into()),
+                Step::Compare("golden.png".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_panel_defaults_to_the_t_embed() {
+        let scenario: Scenario = toml::from_str("[[step]]\nframes = 1\n").unwrap();
+        assert_eq!(scenario.panel, (320, 170));
+    }
+
+    #[test]
+    fn a_step_with_two_actions_or_an_unknown_key_is_rejected() {
+        assert!(toml::from_str::<Scenario>("[[step]]\nframes = 1\ntype = \"a\"\n").is_err());
+        assert!(toml::from_str::<Scenario>("[[step]]\nsleep = \"1s\"\n").is_err());
+        assert!(toml::from_str::<Scenario>("[[step]]\nexpect = { titel = \"Vault\" }\n").is_err());
+    }
+
+    #[test]
+    fn durations_and_colors_reject_what_they_cannot_read() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1.5s"), None);
+        assert_eq!(parse_duration("ms"), None);
+        assert_eq!(parse_duration("10h"), None);
+
+        assert_eq!(parse_hex_color("#0A0b0C"), Some([10, 11, 12]));
+        assert_eq!(parse_hex_color("0a0b0c"), None);
+        assert_eq!(parse_hex_color("#0a0b0"), None);
+    }
+}
@@ -0,0 +1,367 @@
+//! Scripted scenarios for the headless emulator: `desktop --scenario FILE`
+//! runs a declarative [`format::Scenario`] (seed credentials, inject
+//! intents, advance frames or simulated time, assert on what's on screen,
+//! save or compare screenshots) against a real `bhk_core::App`, so QA can
+//! write regression flows without touching Rust.
+//!
+//! A run is deterministic: it goes through the same pieces headless mode
+//! wires up — `PushSyncSource` for seeded credentials, `HttpInput` for
+//! intents, `HeadlessSurface` for frames — but frames are stepped one at
+//! a time with `bhk_core::FrameStepper` instead of `bhk_core::run`'s
+//! wall-clock loop, and the platform reads a [`VirtualClock`] that moves
+//! exactly [`FRAME_PERIOD`] per frame (or a `wait` step's span), never
+//! with the host. Settings go to a throwaway store, so every run starts
+//! from defaults regardless of what `./data/kv_store.bin` holds.
+//!
+//! Text assertions read `App::chrome`/`modal`/`toast` — the strings the
+//! chrome draws — rather than OCR'ing pixels; pixel assertions and
+//! `compare` read the `HeadlessSurface`'s last frame, the same pixels
+//! `GET /api/screenshot` serves.
+
+pub mod format;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bhk_core::input::NavIntent;
+use bhk_core::platform::Platform;
+use bhk_core::{App, FrameStepper};
+use push_protocol::Credential;
+
+use crate::desktop::PushSyncSource;
+use crate::platform::{
+    FileStorage, FileStorageError, HeadlessSurface, HostPlatform, HttpInput, VirtualClock,
+};
+
+pub use format::{Expectation, PixelExpectation, Scenario, SeedCredential, Step};
+
+/// Simulated time per frame: the ~30fps budget `desktop` runs at.
+pub const FRAME_PERIOD: Duration = Duration::from_millis(33);
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    /// The scenario file, a screenshot or a baseline couldn't be read or
+    /// written.
+    Io(PathBuf, io::Error),
+    /// The scenario file isn't valid scenario TOML.
+    Parse(toml::de::Error),
+    /// The throwaway settings store couldn't be created.
+    Storage(FileStorageError),
+    /// Step `step` (1-based) didn't hold.
+    Failed { step: usize, message: String },
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            ScenarioError::Parse(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::Storage(e) => write!(f, "scenario settings store: {e}"),
+            ScenarioError::Failed { step, message } => write!(f, "step {step} failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// What a passing run did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScenarioReport {
+    pub steps: usize,
+    pub frames: u32,
+    /// Simulated time from the first frame to the end of the run.
+    pub elapsed: Duration,
+}
+
+impl Scenario {
+    /// Parses a scenario from TOML text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScenarioError::Parse`] if `text` isn't a valid scenario.
+    pub fn parse(text: &str) -> Result<Self, ScenarioError> {
+        toml::from_str(text).map_err(ScenarioError::Parse)
+    }
+
+    /// Reads and parses the scenario file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScenarioError::Io`] if the file can't be read, or
+    /// [`ScenarioError::Parse`] if it isn't a valid scenario.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let text =
+            std::fs::read_to_string(path).map_err(|e| ScenarioError::Io(path.to_path_buf(), e))?;
+        Self::parse(&text)
+    }
+
+    /// Runs every step in order, stopping at the first that fails.
+    /// Relative `screenshot`/`compare` paths resolve against `base_dir`
+    /// (the scenario file's directory, for `desktop --scenario`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScenarioError::Failed`] for the first step that doesn't
+    /// hold, [`ScenarioError::Io`] if a screenshot or baseline can't be
+    /// written or read, or [`ScenarioError::Storage`] if the throwaway
+    /// settings store can't be created.
+    pub fn run(&self, base_dir: &Path) -> Result<ScenarioReport, ScenarioError> {
+        let mut session = Session::new(self.panel)?;
+        for (index, step) in self.steps.iter().enumerate() {
+            session
+                .run_step(step, base_dir)
+                .map_err(|failure| failure.at_step(index + 1))?;
+        }
+        Ok(ScenarioReport {
+            steps: self.steps.len(),
+            frames: session.frames,
+            elapsed: session.elapsed,
+        })
+    }
+}
+
+/// A step's failure before it knows which step it was.
+enum StepFailure {
+    Io(PathBuf, io::Error),
+    Mismatch(String),
+}
+
+impl StepFailure {
+    fn at_step(self, step: usize) -> ScenarioError {
+        match self {
+            StepFailure::Io(path, e) => ScenarioError::Io(path, e),
+            StepFailure::Mismatch(message) => ScenarioError::Failed { step, message },
+        }
+    }
+}
+
+/// Reports `what` as a mismatch unless `expected` is unset or equal to
+/// `actual`.
+fn check<T: PartialEq + fmt::Debug + ?Sized>(
+    what: &str,
+    expected: Option<&T>,
+    actual: Option<&T>,
+) -> Result<(), StepFailure> {
+    match expected {
+        Some(expected) if actual != Some(expected) => Err(StepFailure::Mismatch(format!(
+            "expected {what} {expected:?}, found {actual:?}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Everything one run owns: the app, a platform over the emulator's own
+/// adapters with a virtual clock, and the handles the steps drive them
+/// through.
+struct Session {
+    app: App,
+    platform: HostPlatform<HeadlessSurface, HttpInput, VirtualClock>,
+    stepper: FrameStepper,
+    sync: PushSyncSource,
+    clock: VirtualClock,
+    input: Arc<Mutex<VecDeque<NavIntent>>>,
+    credentials: Arc<Mutex<Vec<Credential>>>,
+    storage_path: PathBuf,
+    frames: u32,
+    elapsed: Duration,
+}
+
+impl Session {
+    fn new((width, height): (u32, u32)) -> Result<Self, ScenarioError> {
+        let storage_path =
+            std::env::temp_dir().join(format!("bhk-scenario-{}.bin", uuid::Uuid::new_v4()));
+        let storage = FileStorage::new(&storage_path).map_err(ScenarioError::Storage)?;
+        let clock = VirtualClock::new();
+        let input = Arc::new(Mutex::new(VecDeque::new()));
+        let credentials = Arc::new(Mutex::new(Vec::new()));
+
+        let mut platform = HostPlatform::with_clock(
+            HeadlessSurface::new(),
+            HttpInput::new(Arc::clone(&input)),
+            storage,
+            clock.clone(),
+        );
+        let mut app = App::new(width, height, Vec::new());
+        let stepper = FrameStepper::new(&mut platform, &mut app);
+        Ok(Self {
+            app,
+            platform,
+            stepper,
+            sync: PushSyncSource::new(Arc::clone(&credentials)),
+            clock,
+            input,
+            credentials,
+            storage_path,
+            frames: 0,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    /// Runs one frame at the clock's current time, then moves the clock
+    /// on by a frame period.
+    fn frame(&mut self) {
+        self.stepper
+            .step(&mut self.platform, &mut self.app, &mut self.sync);
+        self.advance(FRAME_PERIOD);
+        self.frames += 1;
+    }
+
+    fn advance(&mut self, by: Duration) {
+        self.clock.advance(by);
+        self.elapsed += by;
+    }
+
+    fn run_step(&mut self, step: &Step, base_dir: &Path) -> Result<(), StepFailure> {
+        match step {
+            Step::Seed(seed) => {
+                *self.credentials.lock().unwrap() = seed
+                    .iter()
+                    .enumerate()
+                    .map(|(index, credential)| credential.to_credential(index))
+                    .collect();
+                self.frame();
+            }
+            Step::Input(intents) => {
+                self.input.lock().unwrap().extend(intents.iter().copied());
+                self.frame();
+            }
+            Step::Type(text) => {
+                self.input
+                    .lock()
+                    .unwrap()
+                    .extend(text.chars().map(NavIntent::Char));
+                self.frame();
+            }
+            Step::Frames(count) => {
+                for _ in 0..*count {
+                    self.frame();
+                }
+            }
+            Step::Wait(span) => {
+                // The last frame already moved the clock one period on.
+                self.advance(span.saturating_sub(FRAME_PERIOD));
+                self.frame();
+            }
+            Step::Expect(expectation) => self.expect(expectation)?,
+            Step::Screenshot(path) => {
+                let path = base_dir.join(path);
+                let image = self.screen()?;
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| StepFailure::Io(parent.to_path_buf(), e))?;
+                }
+                image
+                    .save(&path)
+                    .map_err(|e| StepFailure::Io(path.clone(), io::Error::other(e)))?;
+            }
+            Step::Compare(path) => self.compare(&base_dir.join(path))?,
+        }
+        Ok(())
+    }
+
+    /// The last flushed frame.
+    fn screen(&mut self) -> Result<image::RgbImage, StepFailure> {
+        self.platform.display().to_image().ok_or_else(|| {
+            StepFailure::Mismatch("nothing has been rendered yet; run a frame first".to_string())
+        })
+    }
+
+    fn expect(&mut self, expectation: &Expectation) -> Result<(), StepFailure> {
+        let chrome = self.app.chrome();
+        check("title", expectation.title.as_ref(), chrome.title.as_ref())?;
+        check(
+            "readout",
+            expectation.readout.as_ref(),
+            chrome.readout.as_ref(),
+        )?;
+        check("hint", expectation.hint.as_ref(), chrome.hint.as_ref())?;
+        check(
+            "toast",
+            expectation.toast.as_ref(),
+            self.app.toast().map(|toast| &toast.message),
+        )?;
+        check(
+            "dialog",
+            expectation.dialog.as_deref(),
+            self.app.modal().map(|dialog| dialog.title()),
+        )?;
+        check(
+            "depth",
+            expectation.depth.as_ref(),
+            Some(&self.app.navigator_depth()),
+        )?;
+        check(
+            "asleep",
+            expectation.asleep.as_ref(),
+            Some(&self.app.is_asleep()),
+        )?;
+
+        if expectation.pixels.is_empty() {
+            return Ok(());
+        }
+        let screen = self.screen()?;
+        for pixel in &expectation.pixels {
+            let actual = screen.get_pixel_checked(pixel.x, pixel.y).map(|rgb| rgb.0);
+            check(
+                &format!("pixel ({}, {})", pixel.x, pixel.y),
+                Some(&pixel.color),
+                actual.as_ref(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Compares the last frame with the PNG at `path`. On a mismatch the
+    /// actual frame is written next to it as `<name>.actual.png`, for
+    /// eyeballing or approving by copying it over the baseline.
+    fn compare(&mut self, path: &Path) -> Result<(), StepFailure> {
+        let actual = self.screen()?;
+        let expected = image::open(path)
+            .map_err(|e| StepFailure::Io(path.to_path_buf(), io::Error::other(e)))?
+            .to_rgb8();
+
+        let differing = if expected.dimensions() == actual.dimensions() {
+            expected
+                .pixels()
+                .zip(actual.pixels())
+                .filter(|(a, b)| a != b)
+                .count()
+        } else {
+            usize::MAX
+        };
+        if differing == 0 {
+            return Ok(());
+        }
+
+        let actual_path = path.with_extension("actual.png");
+        actual
+            .save(&actual_path)
+            .map_err(|e| StepFailure::Io(actual_path.clone(), io::Error::other(e)))?;
+        let summary = if differing == usize::MAX {
+            format!(
+                "is {:?}, the frame is {:?}",
+                expected.dimensions(),
+                actual.dimensions()
+            )
+        } else {
+            format!("differs in {differing} pixel(s)")
+        };
+        Err(StepFailure::Mismatch(format!(
+            "{} {summary}; actual frame written to {}",
+            path.display(),
+            actual_path.display()
+        )))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Only ever written if a scenario changed settings.
+        let _ = std::fs::remove_file(&self.storage_path);
+    }
+}
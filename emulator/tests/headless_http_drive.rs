@@ -11,6 +11,13 @@
 //! `emulator::platform::SharedHeadlessSurface` pair, exactly as
 //! `main.rs::run_headless` wires them.
 //!
+//! The virtual-time tests run the loop (recording, as `main.rs` does) the way `--virtual-time` does: on
+//! its own thread, paced by a `VirtualClock` that only `POST
+//! /api/time/advance` moves, publishing the UI tree `GET /api/ui-tree`
+//! serves after every frame. The fault tests run that loop over a
+//! `PushSyncSource`, as `main.rs` does, so an injected fault reaches the
+//! screen through the real sync path.
+//!
 //! No HTTP client crate exists in this workspace (there was never a reason
 //! to add one before this test), so [`http_request`] below speaks raw
 //! HTTP/1.1 over a `TcpStream` rather than pulling one in for a single
@@ -19,16 +26,19 @@
 use std::convert::Infallible;
 use std::io::{Read, Write};
 use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use bhk_core::render::chrome::TITLE_BAR_HEIGHT;
-use bhk_core::render::theme::palette;
-use bhk_core::render::ROW_HEIGHT;
+use bhk_core::render::{Palette, ROW_HEIGHT};
 use bhk_core::{run, App, SyncSource, VaultItem};
+use device_link::{encode_chunks, encode_frame, from_cbor, to_cbor, Decoder, MessageType, SyncBegin, SyncEnd, SyncNack};
 use embedded_graphics::prelude::RgbColor;
-use emulator::desktop::{DesktopStorage, SyncServer};
-use emulator::platform::{FileStorage, HostPlatform, HttpInput, SharedHeadlessSurface};
+use push_protocol::{Credential, SyncRequest};
+use emulator::desktop::{run_publishing, DesktopStorage, DeviceConfig, PushSyncSource, SharedUiTree, SyncServer};
+use emulator::platform::{FileStorage, HostPlatform, HttpInput, RecordingSurface, SharedHeadlessSurface, SharedRecorder, VirtualClock};
 
 const WIDTH: u32 = 320;
 const HEIGHT: u32 = 170;
@@ -64,6 +74,13 @@ fn vault_item(name: &str) -> VaultItem {
 /// a simple `read_to_end` instead of implementing chunked/keep-alive
 /// framing.
 fn http_request(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> (u16, Vec<u8>) {
+    let (status, _head, body) = http_exchange(addr, method, path, body);
+    (status, body)
+}
+
+/// `http_request`, also returning the response's head (status line and
+/// headers), lowercased so header names can be matched case-insensitively.
+fn http_exchange(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> (u16, String, Vec<u8>) {
     let mut stream = TcpStream::connect(addr).expect("connect to the test SyncServer");
     stream.set_read_timeout(Some(Duration::from_secs(5))).expect("set a read timeout");
 
@@ -88,7 +105,8 @@ fn http_request(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> (u16
         .expect("a well-formed HTTP response has a blank line after headers")
         + 4;
 
-    (status, raw[header_end..].to_vec())
+    let head = String::from_utf8_lossy(&raw[..header_end]).to_lowercase();
+    (status, head, raw[header_end..].to_vec())
 }
 
 fn get(addr: SocketAddr, path: &str) -> (u16, Vec<u8>) {
@@ -130,7 +148,7 @@ fn injecting_a_navintent_over_http_moves_the_selection_and_is_observable_in_the_
         }
     });
 
-    let kv_storage_path = std::env::temp_dir().join(format!("bhk-headless-http-drive-test-{}.json", uuid::Uuid::new_v4()));
+    let kv_storage_path = std::env::temp_dir().join(format!("bhk-headless-http-drive-test-{}.bin", uuid::Uuid::new_v4()));
     let kv_storage = FileStorage::new(kv_storage_path).expect("open a temp kv store");
     let mut platform = HostPlatform::new(surface, HttpInput::new(input_queue), kv_storage);
 
@@ -159,7 +177,7 @@ fn injecting_a_navintent_over_http_moves_the_selection_and_is_observable_in_the_
     let row0_y = TITLE_BAR_HEIGHT + 2;
     let row1_y = TITLE_BAR_HEIGHT + ROW_HEIGHT + 2;
     let sample_x = 250;
-    let highlight = palette::SURFACE_ELEVATED;
+    let highlight = Palette::DARK.surface_elevated;
     let highlight_rgb8 = image::Rgb([highlight.r() << 3, highlight.g() << 2, highlight.b() << 3]);
 
     // --- Frame 1: render the initial state (no input yet) and observe it
@@ -221,3 +239,378 @@ fn screenshot_is_a_404_when_no_headless_surface_is_registered() {
     let (status, _body) = get(addr, "/api/screenshot");
     assert_eq!(status, 404);
 }
+
+/// Starts what `main.rs::run_headless` starts with `--virtual-time`: a
+/// `SyncServer` with the clock and UI tree registered, and the loop on its
+/// own thread paced by that clock at the real 33ms budget, showing
+/// `items`. Returns the server's address and the flag that stops the
+/// loop.
+fn start_virtual_time_emulator(items: Vec<VaultItem>) -> (SocketAddr, Arc<AtomicBool>) {
+    let storage_backend = Arc::new(Mutex::new(DesktopStorage::new().expect("open credential storage")));
+    let server = SyncServer::new("127.0.0.1:0", storage_backend).expect("start SyncServer on an ephemeral port");
+    let kv_storage_path = std::env::temp_dir().join(format!("bhk-virtual-time-test-{}.bin", uuid::Uuid::new_v4()));
+    let kv_storage = FileStorage::new(kv_storage_path).expect("open a temp kv store");
+    let sync = FixedSyncSource(items.clone());
+    run_virtual_time(server, kv_storage, items, sync)
+}
+
+/// The rest of `start_virtual_time_emulator`, for any `sync`: registers
+/// the clock, UI tree and recorder on `server`, serves it on one thread
+/// and runs the loop on another.
+fn run_virtual_time<S>(mut server: SyncServer, kv_storage: FileStorage, items: Vec<VaultItem>, mut sync: S) -> (SocketAddr, Arc<AtomicBool>)
+where
+    S: SyncSource + Send + 'static,
+    S::Error: std::fmt::Display,
+{
+    let addr = server.local_addr();
+    let input_queue = server.get_input_queue_ref();
+    let surface = SharedHeadlessSurface::new();
+    server.set_screenshot_surface(surface.handle());
+    let clock = VirtualClock::new();
+    server.set_virtual_clock(clock.clone());
+    let recorder = SharedRecorder::new(clock.clone());
+    server.set_recorder(recorder.clone());
+    let ui_tree = SharedUiTree::new();
+    server.set_ui_tree(ui_tree.handle());
+    let stop = server.get_shutdown_signal();
+
+    std::thread::spawn(move || loop {
+        if server.handle_request().is_err() {
+            break;
+        }
+    });
+
+    let should_stop = Arc::clone(&stop);
+    std::thread::spawn(move || {
+        let display = RecordingSurface::new(surface, recorder);
+        let mut platform = HostPlatform::with_clock(display, HttpInput::new(input_queue), kv_storage, clock);
+        let mut app = App::new(WIDTH, HEIGHT, items);
+        run_publishing(&mut platform, &mut app, &mut sync, Duration::from_millis(33), &ui_tree, || !should_stop.load(Ordering::Relaxed));
+    });
+
+    (addr, stop)
+}
+
+fn advance(addr: SocketAddr, ms: u64) -> serde_json::Value {
+    let (status, body) = post(addr, "/api/time/advance", format!("{{\"ms\":{ms}}}").as_bytes());
+    assert_eq!(status, 200, "POST /api/time/advance did not succeed: {}", String::from_utf8_lossy(&body));
+    serde_json::from_slice(&body).expect("time advance responds with JSON")
+}
+
+#[test]
+fn advancing_virtual_time_over_http_runs_the_marquee_instantly_and_reproducibly() {
+    let items = || vec![vault_item("Consolidated Regional Credit Union Online Banking"), vault_item("GitHub")];
+    let (chunked, chunked_stop) = start_virtual_time_emulator(items());
+    let (single, single_stop) = start_virtual_time_emulator(items());
+
+    // A zero advance just waits for the loop to have run and parked, so
+    // the first frame is on screen.
+    advance(chunked, 0);
+    advance(single, 0);
+    let at_rest = decode_screenshot(get(single, "/api/screenshot"));
+
+    // 2.5s: past the marquee's 1.5s dwell, a second into the scroll. Every
+    // advance ends on a frame at its new time, so the same span in one
+    // request or in uneven pieces ends on the same pixels.
+    let started = std::time::Instant::now();
+    let response = advance(single, 2500);
+    assert_eq!(response["elapsed_ms"], 2500);
+    assert_eq!(response["frames"], 2500 / 33 + 1);
+    for ms in [700, 1, 1799] {
+        advance(chunked, ms);
+    }
+    assert!(started.elapsed() < Duration::from_millis(2500), "virtual time must not wait in real time");
+
+    let scrolled = decode_screenshot(get(single, "/api/screenshot"));
+    assert_ne!(scrolled, at_rest, "the focused row's long name should have scrolled");
+    assert_eq!(decode_screenshot(get(chunked, "/api/screenshot")), scrolled);
+
+    chunked_stop.store(true, Ordering::Relaxed);
+    single_stop.store(true, Ordering::Relaxed);
+}
+
+#[test]
+fn time_advance_is_a_404_without_a_virtual_clock() {
+    let storage_backend = Arc::new(Mutex::new(DesktopStorage::new().expect("open credential storage")));
+    let server = SyncServer::new("127.0.0.1:0", storage_backend).expect("start SyncServer on an ephemeral port");
+    let addr = server.local_addr();
+
+    std::thread::spawn(move || loop {
+        if server.handle_request().is_err() {
+            break;
+        }
+    });
+
+    let (status, _body) = post(addr, "/api/time/advance", b"{\"ms\":1000}");
+    assert_eq!(status, 404);
+}
+
+fn ui_tree(addr: SocketAddr) -> serde_json::Value {
+    let (status, body) = get(addr, "/api/ui-tree");
+    assert_eq!(status, 200, "GET /api/ui-tree did not succeed: {}", String::from_utf8_lossy(&body));
+    serde_json::from_slice(&body).expect("the UI tree is JSON")
+}
+
+/// The deepest node on the focused path, as `bhk_core::render::SemanticNode::focus` finds it.
+fn focus(node: &serde_json::Value) -> Option<&serde_json::Value> {
+    if node["focused"] != true {
+        return None;
+    }
+    node["children"].as_array().unwrap().iter().find_map(focus).or(Some(node))
+}
+
+#[test]
+fn the_ui_tree_over_http_names_the_focused_row_and_never_leaks_a_masked_password() {
+    let mut github = vault_item("GitHub");
+    github.username = "octocat".to_string();
+    github.password = "correct horse battery staple".to_string();
+    let (addr, stop) = start_virtual_time_emulator(vec![vault_item("Bitwarden.com"), github]);
+
+    post(addr, "/api/input", b"\"Next\"");
+    advance(addr, 0);
+    let tree = ui_tree(addr);
+    assert_eq!(tree["role"], "root");
+    let focused = focus(&tree).expect("something has focus");
+    assert_eq!((&focused["role"], &focused["label"], &focused["value"]), (&"list_item".into(), &"GitHub".into(), &"octocat".into()));
+
+    for intent in [&b"\"Activate\""[..], b"\"Next\""] {
+        post(addr, "/api/input", intent);
+    }
+    advance(addr, 0);
+    let (_, body) = get(addr, "/api/ui-tree");
+    let focused = focus(&serde_json::from_slice(&body).unwrap()).cloned().unwrap();
+    assert_eq!((&focused["role"], &focused["label"]), (&"secret_field".into(), &"PASSWORD".into()));
+    assert_eq!(focused["value"], "**********");
+    assert!(!String::from_utf8_lossy(&body).contains("correct horse"), "a masked password must not be anywhere in the tree");
+
+    post(addr, "/api/input", b"\"Activate\"");
+    advance(addr, 0);
+    assert_eq!(focus(&ui_tree(addr)).unwrap()["value"], "correct horse battery staple", "revealed on screen, revealed in the tree");
+
+    stop.store(true, Ordering::Relaxed);
+}
+
+#[test]
+fn ui_tree_is_a_404_when_no_tree_is_registered() {
+    let storage_backend = Arc::new(Mutex::new(DesktopStorage::new().expect("open credential storage")));
+    let server = SyncServer::new("127.0.0.1:0", storage_backend).expect("start SyncServer on an ephemeral port");
+    let addr = server.local_addr();
+
+    std::thread::spawn(move || loop {
+        if server.handle_request().is_err() {
+            break;
+        }
+    });
+
+    let (status, _body) = get(addr, "/api/ui-tree");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn side_by_side_emulators_each_report_their_own_device_from_api_status() {
+    // What two `desktop --config ...` processes amount to: two servers,
+    // each with its own port, data directory and profile, and each
+    // `/api/status` naming only itself.
+    let start = |line: &str| {
+        let raw: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        let device = DeviceConfig::from_args(&raw).expect("valid device flags");
+        let storage = DesktopStorage::at(device.credentials_path()).expect("open credential storage");
+        let mut server = SyncServer::new(&device.bind_addr(), Arc::new(Mutex::new(storage))).expect("start SyncServer");
+        let addr = server.local_addr();
+        server.set_device(device.descriptor(addr.port()));
+        std::thread::spawn(move || loop {
+            if server.handle_request().is_err() {
+                break;
+            }
+        });
+        addr
+    };
+    let data_dir = std::env::temp_dir().join(format!("bhk-multi-instance-{}", uuid::Uuid::new_v4()));
+    let left = start(&format!("--name Left --port 0 --data-dir {}", data_dir.join("left").display()));
+    let right = start(&format!("--name Right --port 0 --data-dir {} --profile ssd1306", data_dir.join("right").display()));
+    assert_ne!(left.port(), right.port());
+
+    let status = |addr| {
+        let (code, body) = get(addr, "/api/status");
+        assert_eq!(code, 200);
+        serde_json::from_slice::<serde_json::Value>(&body).expect("status responds with JSON")
+    };
+    let (left_status, right_status) = (status(left), status(right));
+    assert_eq!(left_status["device"]["name"], "Left");
+    assert_eq!(left_status["device"]["profile"], "t-embed");
+    assert_eq!(left_status["device"]["panel"], serde_json::json!([320, 170]));
+    assert_eq!(left_status["device"]["port"], left.port());
+    assert_eq!(right_status["device"]["name"], "Right");
+    assert_eq!(right_status["device"]["panel"], serde_json::json!([128, 32]));
+    assert_eq!(right_status["device"]["port"], right.port());
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+/// What `main.rs` runs with `--virtual-time`, synced the way it syncs: a
+/// `PushSyncSource` over whatever is pushed to the server, reporting
+/// failed pushes, and both stores sharing the server's faults, all in a
+/// fresh data directory (returned for cleanup).
+fn start_push_emulator() -> (SocketAddr, Arc<AtomicBool>, PathBuf) {
+    let data_dir = std::env::temp_dir().join(format!("bhk-faults-{}", uuid::Uuid::new_v4()));
+    let storage = DesktopStorage::at(data_dir.join("credentials.json")).expect("open credential storage");
+    let server = SyncServer::new("127.0.0.1:0", Arc::new(Mutex::new(storage))).expect("start SyncServer on an ephemeral port");
+    let kv_storage = FileStorage::new(data_dir.join("kv_store.bin")).expect("open a temp kv store").with_faults(server.get_faults_ref());
+    let sync = PushSyncSource::new(server.get_credentials_ref()).with_sync_errors(server.get_sync_error_ref());
+    let (addr, stop) = run_virtual_time(server, kv_storage, Vec::new(), sync);
+    (addr, stop, data_dir)
+}
+
+fn credentials(names: &[&str]) -> SyncRequest {
+    SyncRequest {
+        credentials: names
+            .iter()
+            .map(|name| Credential {
+                id: uuid::Uuid::new_v4(),
+                name: (*name).to_string(),
+                username: "user@example.com".to_string(),
+                password: "hunter2".to_string(),
+                uri: None,
+                notes: None,
+            })
+            .collect(),
+    }
+}
+
+fn push(addr: SocketAddr, request: &SyncRequest) -> u16 {
+    let mut body = Vec::new();
+    ciborium::into_writer(request, &mut body).expect("a SyncRequest always encodes");
+    post(addr, "/api/sync", &body).0
+}
+
+/// What `companion`'s `SerialLink::sync` writes for `request`, in 64-byte
+/// chunks.
+fn link_wire(request: &SyncRequest) -> Vec<u8> {
+    let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+    let blob = to_cbor(request).unwrap();
+    let begin = SyncBegin { total_bytes: blob.len() as u32, item_count: request.credentials.len() as u32 };
+    let mut wire = encode_frame(MessageType::SyncBegin, 0, &to_cbor(&begin).unwrap()).unwrap();
+    wire.extend(encode_chunks(MessageType::SyncChunk, &blob, 64).unwrap().concat());
+    let end = SyncEnd { crc32_of_whole_blob: crc.checksum(&blob) };
+    wire.extend(encode_frame(MessageType::SyncEnd, 0, &to_cbor(&end).unwrap()).unwrap());
+    wire
+}
+
+fn set_faults(addr: SocketAddr, faults: &str) {
+    let (status, body) = http_request(addr, "PUT", "/api/faults", faults.as_bytes());
+    assert_eq!(status, 200, "PUT /api/faults did not succeed: {}", String::from_utf8_lossy(&body));
+}
+
+/// The list's rows and the content-state message, after letting the loop
+/// run a frame.
+fn shown(addr: SocketAddr) -> (Vec<String>, Option<(String, String)>) {
+    advance(addr, 0);
+    let tree = ui_tree(addr);
+    let nodes: Vec<&serde_json::Value> = {
+        let mut pending = vec![&tree];
+        let mut all = Vec::new();
+        while let Some(node) = pending.pop() {
+            all.push(node);
+            pending.extend(node["children"].as_array().unwrap().iter().rev());
+        }
+        all
+    };
+    let rows = nodes.iter().filter(|node| node["role"] == "list_item").map(|node| node["label"].as_str().unwrap().to_string()).collect();
+    let message = nodes
+        .iter()
+        .find(|node| node["role"] == "message")
+        .map(|node| (node["label"].as_str().unwrap().to_string(), node["value"].as_str().unwrap_or_default().to_string()));
+    (rows, message)
+}
+
+#[test]
+fn injected_sync_faults_show_as_a_sync_error_and_the_next_good_push_recovers() {
+    let (addr, stop, data_dir) = start_push_emulator();
+
+    set_faults(addr, r#"{"sync_status": 503}"#);
+    assert_eq!(push(addr, &credentials(&["GitHub"])), 503);
+    assert_eq!(shown(addr), (vec![], Some(("Sync error".to_string(), "Push rejected (503)".to_string()))));
+
+    set_faults(addr, r#"{"sync_body": {"truncate": 5}}"#);
+    assert_eq!(push(addr, &credentials(&["GitHub"])), 400);
+    assert_eq!(shown(addr).1, Some(("Sync error".to_string(), "Push unreadable".to_string())));
+
+    let (status, _) = http_request(addr, "DELETE", "/api/faults", b"");
+    assert_eq!(status, 200);
+    assert_eq!(push(addr, &credentials(&["GitHub", "Gmail"])), 200);
+    assert_eq!(shown(addr), (vec!["GitHub".to_string(), "Gmail".to_string()], None));
+
+    // A failed push after a good one keeps the last-known-good list.
+    set_faults(addr, r#"{"fail_storage_writes": true}"#);
+    assert_eq!(push(addr, &credentials(&["Amazon"])), 500);
+    assert_eq!(shown(addr).0, ["GitHub", "Gmail"]);
+    let (_, status) = get(addr, "/api/status");
+    let status: serde_json::Value = serde_json::from_slice(&status).unwrap();
+    assert_eq!(status["sync_error"], "Push not saved");
+    assert_eq!(status["faults"]["fail_storage_writes"], true);
+
+    stop.store(true, Ordering::Relaxed);
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn link_syncs_nack_dropped_or_corrupted_frames_and_ack_clean_ones() {
+    let (addr, stop, data_dir) = start_push_emulator();
+    let reply = |wire: &[u8]| {
+        let (status, body) = post(addr, "/api/link", wire);
+        assert_eq!(status, 200, "a link refusal travels as a SyncNack, not a status");
+        let mut decoder = Decoder::new();
+        decoder.feed(&body);
+        decoder.poll().expect("one reply frame").expect("the reply frame is intact")
+    };
+    let wire = link_wire(&credentials(&["GitHub", "Gmail", "Amazon"]));
+
+    set_faults(addr, r#"{"link_corrupt_frames": [1]}"#);
+    let nack = reply(&wire);
+    assert_eq!(nack.msg_type, MessageType::SyncNack);
+    assert_eq!(from_cbor::<SyncNack>(&nack.payload).unwrap().code, emulator::desktop::link::nack::BLOB_MISMATCH);
+    assert_eq!(shown(addr), (vec![], Some(("Sync error".to_string(), "Transfer damaged".to_string()))));
+
+    set_faults(addr, r#"{"link_drop_frames": [0]}"#);
+    assert_eq!(reply(&wire).msg_type, MessageType::SyncNack);
+
+    set_faults(addr, "{}");
+    assert_eq!(reply(&wire).msg_type, MessageType::SyncAck);
+    assert_eq!(shown(addr), (vec!["GitHub".to_string(), "Gmail".to_string(), "Amazon".to_string()], None));
+
+    let (status, _) = http_request(addr, "PUT", "/api/faults", br#"{"sync_status": 200}"#);
+    assert_eq!(status, 400, "a success status isn't a fault");
+
+    stop.store(true, Ordering::Relaxed);
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn a_recording_over_http_is_one_frame_per_change_timed_in_virtual_time() {
+    let (addr, stop) = start_virtual_time_emulator(vec![vault_item("Amazon"), vault_item("GitHub"), vault_item("Gmail")]);
+    advance(addr, 0);
+    assert_eq!(post(addr, "/api/record/stop", b"").0, 409, "nothing is recording yet");
+
+    assert_eq!(post(addr, "/api/record/start", b"").0, 200);
+    assert_eq!(post(addr, "/api/record/start", b"").0, 409);
+    advance(addr, 500);
+    post(addr, "/api/input", b"\"Next\"");
+    advance(addr, 500);
+    post(addr, "/api/input", b"\"Next\"");
+    advance(addr, 1500);
+    let (status, head, apng) = http_exchange(addr, "POST", "/api/record/stop", b"");
+    assert_eq!(status, 200);
+    assert!(head.contains("x-recording-truncated: false"), "{head}");
+
+    // 2.5s is ~75 frames of the loop, but only three distinct screens: the
+    // opening one and one per move. Together they last exactly as long as
+    // the recording ran.
+    let decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(apng)).expect("the recording is a PNG");
+    let frames = image::AnimationDecoder::into_frames(decoder.apng().expect("and an animated one")).collect_frames().unwrap();
+    assert_eq!(frames.len(), 3);
+    let total: Duration = frames.iter().map(|frame| Duration::from(frame.delay())).sum();
+    assert_eq!(total, Duration::from_millis(2500));
+    assert_eq!(frames[2].buffer().dimensions(), (WIDTH, HEIGHT));
+
+    stop.store(true, Ordering::Relaxed);
+}
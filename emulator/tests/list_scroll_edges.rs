@@ -17,8 +17,7 @@ use std::convert::Infallible;
 use bhk_core::input::NavIntent;
 use bhk_core::platform::{InputSource, Platform};
 use bhk_core::render::chrome::TITLE_BAR_HEIGHT;
-use bhk_core::render::theme::palette;
-use bhk_core::render::ROW_HEIGHT;
+use bhk_core::render::{Palette, ROW_HEIGHT};
 use bhk_core::{run, App, SyncSource, VaultItem};
 use embedded_graphics::prelude::RgbColor;
 use emulator::platform::{FileStorage, HeadlessSurface, HostPlatform};
@@ -77,7 +76,7 @@ fn vault_item(name: &str) -> VaultItem {
 fn render_after(app: &mut App, sync: &mut FixedSyncSource, queued: Vec<Vec<NavIntent>>) -> image::RgbImage {
     let frames = queued.len();
     let surface = HeadlessSurface::new();
-    let storage_path = std::env::temp_dir().join(format!("bhk-list-scroll-edges-test-{}.json", uuid::Uuid::new_v4()));
+    let storage_path = std::env::temp_dir().join(format!("bhk-list-scroll-edges-test-{}.bin", uuid::Uuid::new_v4()));
     let storage = FileStorage::new(storage_path).expect("open a temp kv store");
     let mut platform = HostPlatform::new(surface, QueuedInput(queued), storage);
 
@@ -121,7 +120,7 @@ fn moving_selection_up_within_the_visible_window_does_not_scroll_the_list_headle
     let row_item3_y = TITLE_BAR_HEIGHT + ROW_HEIGHT + 2;
     let row_item4_y = TITLE_BAR_HEIGHT + 2 * ROW_HEIGHT + 2;
     let sample_x = 250; // past the accent bar and these short labels' text, per headless_http_drive.rs's identical convention
-    let highlight = palette::SURFACE_ELEVATED;
+    let highlight = Palette::DARK.surface_elevated;
     let highlight_rgb8 = image::Rgb([highlight.r() << 3, highlight.g() << 2, highlight.b() << 3]);
 
     assert_eq!(*scrolled_down.get_pixel(sample_x, row_item4_y), highlight_rgb8, "item 4 (the current selection) should be highlighted");
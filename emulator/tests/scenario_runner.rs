@@ -0,0 +1,128 @@
+//! The scenario runner end to end: every scenario shipped under
+//! `emulator/scenarios/` passes, failures name the step and what didn't
+//! match, and `screenshot`/`compare` round-trip through real PNG files.
+//! See `emulator::scenario`.
+
+use std::path::PathBuf;
+
+use bhk_core::render::Palette;
+use embedded_graphics::prelude::RgbColor;
+use emulator::scenario::{Scenario, ScenarioError};
+
+const SEED: &str = r#"
+[[step]]
+seed = [{ name = "GitHub" }, { name = "AWS" }, { name = "Postgres" }]
+"#;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("bhk-scenario-test-{name}-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn every_shipped_scenario_passes() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+    let mut ran = 0;
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+        let report = Scenario::load(&path)
+            .and_then(|scenario| scenario.run(&dir))
+            .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        assert!(report.frames > 0, "{} ran no frames", path.display());
+        ran += 1;
+    }
+    assert!(ran > 0, "no scenarios found in {}", dir.display());
+}
+
+#[test]
+fn a_failed_expectation_names_the_step_and_the_mismatch() {
+    let scenario = Scenario::parse(&format!(
+        "{SEED}
+        [[step]]
+        input = [\"Next\"]
+        [[step]]
+        expect = {{ readout = \"3 / 3\" }}
+        "
+    ))
+    .unwrap();
+
+    match scenario.run(&std::env::temp_dir()) {
+        Err(ScenarioError::Failed { step, message }) => {
+            assert_eq!(step, 3);
+            assert!(
+                message.contains("\"3 / 3\"") && message.contains("\"2 / 3\""),
+                "{message}"
+            );
+        }
+        other => panic!("expected step 3 to fail, got {other:?}"),
+    }
+}
+
+#[test]
+fn pixel_expectations_use_screenshot_colors() {
+    // The title bar is painted in `surface`; a screenshot widens each
+    // Rgb565 channel with a left shift.
+    let surface = Palette::DARK.surface;
+    let color = format!(
+        "#{:02x}{:02x}{:02x}",
+        surface.r() << 3,
+        surface.g() << 2,
+        surface.b() << 3
+    );
+    let scenario = Scenario::parse(&format!(
+        "{SEED}
+        [[step]]
+        expect = {{ pixels = [{{ x = 40, y = 2, color = \"{color}\" }}] }}
+        "
+    ))
+    .unwrap();
+
+    scenario.run(&std::env::temp_dir()).unwrap();
+}
+
+#[test]
+fn compare_passes_against_its_own_screenshot_and_writes_the_actual_frame_on_a_mismatch() {
+    let dir = temp_dir("compare");
+    let approve = Scenario::parse(&format!(
+        "{SEED}\n[[step]]\nscreenshot = \"golden/list.png\"\n"
+    ))
+    .unwrap();
+    approve.run(&dir).unwrap();
+    assert!(dir.join("golden/list.png").exists());
+
+    let same = Scenario::parse(&format!(
+        "{SEED}\n[[step]]\ncompare = \"golden/list.png\"\n"
+    ))
+    .unwrap();
+    same.run(&dir).unwrap();
+
+    let moved = Scenario::parse(&format!(
+        "{SEED}\n[[step]]\ninput = [\"End\"]\n[[step]]\ncompare = \"golden/list.png\"\n"
+    ))
+    .unwrap();
+    match moved.run(&dir) {
+        Err(ScenarioError::Failed { step: 3, message }) => {
+            assert!(message.contains("differs in"), "{message}")
+        }
+        other => panic!("expected the compare step to fail, got {other:?}"),
+    }
+    assert!(dir.join("golden/list.actual.png").exists());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn wait_moves_simulated_time_without_real_waiting() {
+    let scenario = Scenario::parse(&format!("{SEED}\n[[step]]\nwait = \"10m\"\n")).unwrap();
+    let started = std::time::Instant::now();
+    let report = scenario.run(&std::env::temp_dir()).unwrap();
+
+    assert_eq!(report.frames, 2);
+    assert!(report.elapsed >= std::time::Duration::from_secs(600));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
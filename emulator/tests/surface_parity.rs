@@ -1,5 +1,7 @@
 //! Proves the core deliverable of W4: `HeadlessSurface` and `MinifbSurface`
-//! flushing the *same* `FrameBuffer565` present the *same* pixels. This is
+//! flushing the *same* `FrameBuffer565` present the *same* pixels — and,
+//! since damage tracking, that flushing only the damaged regions ends up
+//! with the same pixels as flushing whole frames. This is
 //! the whole point of the presentation-surface ADR (`.planning/decisions/
 //! 2026-08-11-presentation-surface-run-mode-seam.md`) — a headless
 //! screenshot must be trustworthy evidence of what the windowed emulator
@@ -17,14 +19,16 @@
 //! screen + 3-item vertical list, one `NavIntent::Next` dispatched so the
 //! selection highlight isn't just sitting on row 0 by coincidence), for the
 //! same reason that test gives: proves real widget rendering, not a blank
-//! or synthetic frame.
+//! or synthetic frame — and holds the headless PNG of that scene to the
+//! golden that test approved.
 
 use bhk_core::input::NavIntent;
 use bhk_core::platform::DisplaySurface;
-use bhk_core::render::theme::palette;
-use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Screen, VerticalList};
+use bhk_core::render::{FrameBuffer565, ListItem, Navigator, Palette, Screen, Theme, VerticalList};
+use bhk_core::{App, VaultItem};
+use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::prelude::RgbColor;
-use emulator::platform::minifb_surface::rasterize_scaled;
+use emulator::platform::minifb_surface::{rasterize_scaled, rasterize_scaled_in};
 use emulator::platform::HeadlessSurface;
 
 /// Arbitrary scale > 1 for the full-sweep parity test, so it also proves
@@ -58,11 +62,11 @@ fn unpack_argb(pixel: u32) -> (u8, u8, u8) {
 fn headless_png_and_minifb_buffer_agree_on_every_pixel() {
     let navigator = build_scene();
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).expect("core DrawTarget is Infallible");
+    navigator.render(&Theme::DARK, &mut framebuffer).expect("core DrawTarget is Infallible");
 
     // Headless side: flush, encode, decode back to RGB8 pixels.
     let mut headless = HeadlessSurface::new();
-    headless.flush(&framebuffer).expect("HeadlessSurface::flush is Infallible");
+    headless.flush(&framebuffer, &[framebuffer.bounding_box()]).expect("HeadlessSurface::flush is Infallible");
     let png_bytes = headless.encode_png().expect("frame was flushed");
     let decoded = image::load_from_memory(&png_bytes).expect("PNG we just wrote must decode").to_rgb8();
     assert_eq!(decoded.width(), 320);
@@ -111,19 +115,103 @@ fn known_chrome_colors_match_between_surfaces_at_scale_one() {
 
     let navigator = build_scene();
     let mut framebuffer = FrameBuffer565::new(320, 170);
-    navigator.render(&mut framebuffer).unwrap();
+    navigator.render(&Theme::DARK, &mut framebuffer).unwrap();
 
     let mut headless = HeadlessSurface::new();
-    headless.flush(&framebuffer).unwrap();
+    headless.flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
     let decoded = image::load_from_memory(&headless.encode_png().unwrap()).unwrap().to_rgb8();
 
     let mut minifb_buffer = vec![0u32; (320 * 170) as usize];
     rasterize_scaled(&framebuffer, 1, &mut minifb_buffer);
 
-    let title_bar = palette::SURFACE;
+    let title_bar = Palette::DARK.surface;
     let expected_title = (title_bar.r() << 3, title_bar.g() << 2, title_bar.b() << 3);
 
     let png_title = decoded.get_pixel(0, 0);
     assert_eq!((png_title[0], png_title[1], png_title[2]), expected_title);
     assert_eq!(unpack_argb(minifb_buffer[0]), expected_title);
 }
+
+#[test]
+fn headless_png_matches_the_approved_core_golden() {
+    // Both surfaces agreeing only proves they're equally right or equally
+    // wrong; this pins the headless PNG to the baseline
+    // `core/tests/render_png_dump.rs` approved for this exact scene, so a
+    // rendering change fails here too until it's blessed over there.
+    let golden_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../core/tests/golden/render_scene_dark.png");
+    let golden = image::open(golden_path).expect("core's render_scene_dark golden is checked in").to_rgb8();
+
+    let navigator = build_scene();
+    let mut framebuffer = FrameBuffer565::new(320, 170);
+    navigator.render(&Theme::DARK, &mut framebuffer).unwrap();
+    let mut headless = HeadlessSurface::new();
+    headless.flush(&framebuffer, &[framebuffer.bounding_box()]).unwrap();
+    let decoded = image::load_from_memory(&headless.encode_png().unwrap()).unwrap().to_rgb8();
+
+    let differing = golden.pixels().zip(decoded.pixels()).filter(|(a, b)| a != b).count();
+    assert!(
+        golden.dimensions() == decoded.dimensions() && differing == 0,
+        "HeadlessSurface's PNG differs from {golden_path} in {differing} pixel(s); if the change is \
+         intended, bless it with UPDATE_GOLDEN=1 cargo test -p bhk-core --test render_png_dump"
+    );
+}
+
+fn vault_item(name: &str, notes: Option<&str>) -> VaultItem {
+    VaultItem {
+        id: uuid::Uuid::new_v4(),
+        name: name.to_string(),
+        username: format!("{}@example.com", name.to_lowercase()),
+        password: "hunter2".to_string(),
+        uri: None,
+        notes: notes.map(str::to_string),
+    }
+}
+
+fn decoded_pixels(surface: &HeadlessSurface) -> image::RgbImage {
+    image::load_from_memory(&surface.encode_png().expect("frame was flushed")).unwrap().to_rgb8()
+}
+
+#[test]
+fn partial_and_full_flushes_converge() {
+    // The damage contract end to end: drive a real `App` through list
+    // moves, type-ahead, a pushed detail screen, a pop and a long jump,
+    // flushing every frame twice — once with only the damage
+    // `render_with_damage` reports, once with the whole frame — and
+    // require both surfaces (and minifb's rasterizer) to hold identical
+    // pixels after every step, not just at the end.
+    let items: Vec<VaultItem> = ["Amazon", "Bitwarden", "GitHub", "Gitlab", "Google", "Postgres", "Zoom"]
+        .into_iter()
+        .map(|name| vault_item(name, Some("Recovery codes are in the safe.")))
+        .collect();
+    let mut app = App::new(320, 170, items);
+
+    let mut partial = HeadlessSurface::new();
+    let mut full = HeadlessSurface::new();
+    let mut partial_argb = vec![0u32; 320 * 170];
+    let mut full_argb = vec![0u32; 320 * 170];
+
+    let steps: [&[NavIntent]; 8] = [
+        &[],
+        &[NavIntent::Next],
+        &[NavIntent::Char('g'), NavIntent::Char('o')],
+        &[NavIntent::Activate],
+        &[NavIntent::Next, NavIntent::Next],
+        &[NavIntent::Back],
+        &[NavIntent::End],
+        &[NavIntent::PrevN(3)],
+    ];
+    for (step, intents) in steps.into_iter().enumerate() {
+        app.handle_input(intents.to_vec());
+        let (framebuffer, damage) = app.render_with_damage();
+
+        partial.flush(framebuffer, &damage).unwrap();
+        full.flush(framebuffer, &[framebuffer.bounding_box()]).unwrap();
+        for area in &damage {
+            rasterize_scaled_in(framebuffer, 1, area, &mut partial_argb);
+        }
+        rasterize_scaled(framebuffer, 1, &mut full_argb);
+
+        assert!(decoded_pixels(&partial) == decoded_pixels(&full), "headless surfaces diverged after step {step} ({intents:?})");
+        assert!(partial_argb == full_argb, "minifb buffers diverged after step {step} ({intents:?})");
+    }
+}
@@ -0,0 +1,71 @@
+//! [`bhk_core::platform::InputSource`] for the OLED FeatherWing's three
+//! buttons (A, B, C — top to bottom): Prev, Activate/Back, Next.
+//!
+//! This module only reads pins. Debounce, click-vs-hold and auto-repeat
+//! are `bhk_core::button_pad::ButtonPad`, which is platform-free and
+//! unit-tested on host; `poll` samples the three levels once per frame
+//! and hands them over with the current time. Frame-rate polling is fine
+//! here, unlike for the T-Embed's encoder (see `rotary_input`'s module
+//! doc): a button press lasts far longer than a ~33ms frame, so there is
+//! nothing to alias.
+//!
+//! # Untested assumption
+//!
+//! All three pins are configured as inputs with the internal pull-up,
+//! active low — the wing's buttons short to ground. B (GPIO32) also has
+//! a pull-up on the wing itself, which makes the internal one redundant
+//! but harmless.
+
+use std::time::Instant;
+
+use bhk_core::button_pad::ButtonPad;
+use bhk_core::{platform::InputSource, NavIntent};
+use esp_idf_hal::gpio::{Gpio14, Gpio15, Gpio32, Input, PinDriver, Pull};
+use esp_idf_hal::sys::EspError;
+
+pub struct ButtonInput {
+    button_a: PinDriver<'static, Gpio15, Input>,
+    button_b: PinDriver<'static, Gpio32, Input>,
+    button_c: PinDriver<'static, Gpio14, Input>,
+    pad: ButtonPad,
+}
+
+impl ButtonInput {
+    /// # Errors
+    ///
+    /// Returns `EspError` if any of the three GPIOs can't be configured
+    /// as a pulled-up digital input.
+    pub fn new(button_a: Gpio15, button_b: Gpio32, button_c: Gpio14) -> Result<Self, EspError> {
+        let mut a = PinDriver::input(button_a)?;
+        a.set_pull(Pull::Up)?;
+        let mut b = PinDriver::input(button_b)?;
+        b.set_pull(Pull::Up)?;
+        let mut c = PinDriver::input(button_c)?;
+        c.set_pull(Pull::Up)?;
+
+        Ok(Self {
+            button_a: a,
+            button_b: b,
+            button_c: c,
+            pad: ButtonPad::new(),
+        })
+    }
+}
+
+impl InputSource for ButtonInput {
+    fn poll(&mut self) -> Vec<NavIntent> {
+        let pressed = [
+            self.button_a.is_low(),
+            self.button_b.is_low(),
+            self.button_c.is_low(),
+        ];
+        let intents = self.pad.update(pressed, Instant::now());
+
+        // Same on-hardware debug aid as `RotaryEncoderInput::poll`.
+        for intent in &intents {
+            log::info!("ButtonInput: emitting {intent:?}");
+        }
+
+        intents
+    }
+}
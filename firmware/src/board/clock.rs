@@ -1,4 +1,5 @@
-//! [`bhk_core::platform::Clock`] for the T-Embed.
+//! [`bhk_core::platform::Clock`] for both boards (plain ESP-IDF, nothing
+//! board-specific).
 //!
 //! Per `core/src/platform.rs`'s own doc comment: "`std::time::Instant` is
 //! available on both targets today (esp-idf-svc's `std` feature provides
@@ -0,0 +1,97 @@
+//! Adafruit HUZZAH32 (ESP32) + 128x32 OLED FeatherWing pin map and
+//! peripheral assembly — the `board-huzzah32` counterpart to the T-Embed's
+//! `board_config.rs`, and mounted under the same `board::board_config`
+//! path (see `board/mod.rs`) so `main.rs` names the same
+//! [`BoardPeripherals`]/`DISPLAY_*` items whichever board is selected.
+//!
+//! Same rule as the T-Embed's: this is the single place in `firmware`
+//! that names a HUZZAH32 GPIO number. Every other `board/*` module takes
+//! already-typed peripherals.
+//!
+//! # Source of the pin map
+//!
+//! Adafruit's "FeatherWing OLED - 128x32" guide (pinouts page, ESP32
+//! Feather column) and the HUZZAH32 pinout: the wing sits on the
+//! Feather's fixed I2C pins and wires its three buttons to fixed GPIOs.
+//! This is also the wiring the original prototype ran on before the
+//! T-Embed move (the README's "Hardware requirements").
+//!
+//! # What is unverified
+//!
+//! This pin map and the adapters built on it compile for
+//! `xtensa-esp32-espidf` but have not run on the board since the
+//! workspace split — see `ssd1306_surface` and `button_input` for what
+//! each assumes.
+
+use esp_idf_hal::gpio::{Gpio14, Gpio15, Gpio22, Gpio23, Gpio32};
+use esp_idf_hal::i2c::I2C0;
+use esp_idf_hal::peripherals::Peripherals;
+
+/// The FeatherWing's I2C bus: the Feather's standard SDA/SCL pins.
+///
+/// Source: HUZZAH32 pinout, `SDA` = GPIO23, `SCL` = GPIO22.
+pub const OLED_SDA_PIN: u8 = 23;
+pub const OLED_SCL_PIN: u8 = 22;
+
+/// The SSD1306's 7-bit I2C address. The wing ties `SA0` low.
+///
+/// Source: the FeatherWing guide's "I2C address 0x3C".
+pub const OLED_I2C_ADDRESS: u8 = 0x3C;
+
+/// The wing's three buttons, top to bottom (A, B, C). Active low; B has
+/// a pull-up on the wing, A and C rely on the ESP32's internal pull-ups
+/// (see `button_input`).
+///
+/// Source: the FeatherWing guide's ESP32 column: A = GPIO15, B = GPIO32,
+/// C = GPIO14.
+pub const BUTTON_A_PIN: u8 = 15;
+pub const BUTTON_B_PIN: u8 = 32;
+pub const BUTTON_C_PIN: u8 = 14;
+
+/// Panel resolution: the 128x32 FeatherWing, which the render core lays
+/// out with its strip profile (`bhk_core::render::LayoutProfile::STRIP`).
+pub const DISPLAY_WIDTH: u16 = 128;
+pub const DISPLAY_HEIGHT: u16 = 32;
+
+/// The subset of ESP32 peripherals the HUZZAH32 board adapter needs,
+/// already split out of the single [`Peripherals::take`] singleton.
+pub struct BoardPeripherals {
+    pub oled_i2c: I2C0,
+    pub oled_sda: Gpio23,
+    pub oled_scl: Gpio22,
+    pub button_a: Gpio15,
+    pub button_b: Gpio32,
+    pub button_c: Gpio14,
+}
+
+impl BoardPeripherals {
+    /// Consumes the singleton [`Peripherals::take`] handle and splits out
+    /// exactly the pins this board adapter uses.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EspError` if the peripheral singleton has already been
+    /// taken elsewhere in the process.
+    pub fn take() -> Result<Self, esp_idf_hal::sys::EspError> {
+        let p = Peripherals::take()?;
+
+        Ok(Self {
+            oled_i2c: p.i2c0,
+            oled_sda: p.pins.gpio23,
+            oled_scl: p.pins.gpio22,
+            button_a: p.pins.gpio15,
+            button_b: p.pins.gpio32,
+            button_c: p.pins.gpio14,
+        })
+    }
+}
+
+// Compile-time cross-check that the typed fields above and the `u8`
+// constants agree, same as the T-Embed's `board_config.rs`.
+const _: () = {
+    assert!(OLED_SDA_PIN == 23);
+    assert!(OLED_SCL_PIN == 22);
+    assert!(BUTTON_A_PIN == 15);
+    assert!(BUTTON_B_PIN == 32);
+    assert!(BUTTON_C_PIN == 14);
+};
@@ -1,8 +1,24 @@
-//! Lilygo T-Embed (ESP32-S3) board adapter: concrete implementations of
-//! `bhk_core::platform`'s four traits (`DisplaySurface`, `InputSource`,
-//! `Clock`, `Storage`) for the real hardware target, assembled by
-//! [`platform::BoardPlatform`] and driven by the unified
-//! `bhk_core::run` loop from `main.rs` (W7).
+//! Board adapters: concrete implementations of `bhk_core::platform`'s
+//! four traits (`DisplaySurface`, `InputSource`, `Clock`, `Storage`) for
+//! the real hardware, assembled by [`platform::BoardPlatform`] and driven
+//! by the unified `bhk_core::run` loop from `main.rs` (W7).
+//!
+//! One board is compiled in, chosen by cargo feature (see
+//! `firmware/Cargo.toml`):
+//!
+//! - `board-t-embed-cc1101` (default) or `board-t-embed-plain`: Lilygo
+//!   T-Embed CC1101 or plain T-Embed (ESP32-S3) — [`St7789Surface`] and
+//!   [`RotaryEncoderInput`]. Both enable the `board-t-embed` family
+//!   feature the shared adapters are gated on; what differs between them
+//!   is data, the selected `board_variants::BoardVariant` pin table
+//!   (`board_config::VARIANT`).
+//! - `board-huzzah32`: Adafruit HUZZAH32 (ESP32) + 128x32 OLED
+//!   FeatherWing — `Ssd1306Surface` and `ButtonInput`.
+//!
+//! Each board's pin map is mounted as `board_config`, so `main.rs` and
+//! `platform` name [`BoardPeripherals`], `DISPLAY_WIDTH`/`DISPLAY_HEIGHT`
+//! and the [`Display`]/[`Input`] aliases the same way for both. `clock`
+//! and `nvs_storage` are plain ESP-IDF and shared.
 //!
 //! # What is and isn't verified
 //!
@@ -14,11 +30,30 @@
 //! its own piece (pin electrical behavior, panel init sequence, encoder
 //! timing, ...).
 
+#[cfg(all(feature = "board-t-embed", feature = "board-huzzah32"))]
+compile_error!("select exactly one board feature: a T-Embed variant or `board-huzzah32`, not both");
+#[cfg(not(any(feature = "board-t-embed", feature = "board-huzzah32")))]
+compile_error!("select a board feature: `board-t-embed-cc1101`, `board-t-embed-plain` or `board-huzzah32`");
+#[cfg(all(feature = "board-t-embed-cc1101", feature = "board-t-embed-plain"))]
+compile_error!("select exactly one T-Embed variant: `board-t-embed-cc1101` or `board-t-embed-plain`, not both");
+#[cfg(all(feature = "board-t-embed", not(any(feature = "board-t-embed-cc1101", feature = "board-t-embed-plain"))))]
+compile_error!("`board-t-embed` is the T-Embed family, not a board: select `board-t-embed-cc1101` or `board-t-embed-plain`");
+
+#[cfg(feature = "board-t-embed")]
 pub mod board_config;
+#[cfg(feature = "board-huzzah32")]
+#[path = "huzzah32_config.rs"]
+pub mod board_config;
+#[cfg(feature = "board-huzzah32")]
+pub mod button_input;
 pub mod clock;
 pub mod nvs_storage;
 pub mod platform;
+#[cfg(feature = "board-t-embed")]
 pub mod rotary_input;
+#[cfg(feature = "board-huzzah32")]
+pub mod ssd1306_surface;
+#[cfg(feature = "board-t-embed")]
 pub mod st7789_surface;
 
 // Only re-exported at this level if `main.rs` names it directly. This is a
@@ -30,7 +65,25 @@ pub mod st7789_surface;
 // to match on a specific error variant); `main.rs` today only propagates
 // them opaquely via `?`/`.expect()`.
 pub use board_config::{BoardPeripherals, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+#[cfg(feature = "board-huzzah32")]
+pub use button_input::ButtonInput;
 pub use nvs_storage::NvsStorage;
 pub use platform::BoardPlatform;
+#[cfg(feature = "board-t-embed")]
 pub use rotary_input::RotaryEncoderInput;
+#[cfg(feature = "board-huzzah32")]
+pub use ssd1306_surface::Ssd1306Surface;
+#[cfg(feature = "board-t-embed")]
 pub use st7789_surface::St7789Surface;
+
+/// The selected board's `DisplaySurface`.
+#[cfg(feature = "board-t-embed")]
+pub type Display = St7789Surface;
+#[cfg(feature = "board-huzzah32")]
+pub type Display = Ssd1306Surface;
+
+/// The selected board's `InputSource`.
+#[cfg(feature = "board-t-embed")]
+pub type Input = RotaryEncoderInput;
+#[cfg(feature = "board-huzzah32")]
+pub type Input = ButtonInput;
@@ -1,4 +1,4 @@
-//! [`bhk_core::platform::Storage`] for the T-Embed, backed by ESP-IDF's
+//! [`bhk_core::platform::Storage`] for both boards, backed by ESP-IDF's
 //! NVS (non-volatile storage) partition.
 //!
 //! The core's `Storage` trait is an opaque key/value blob store (`get`
@@ -12,20 +12,19 @@
 //! parts of ESP-IDF, so functional risk here is lower than the display
 //! or encoder adapters, but this has not been flashed or run against a
 //! real NVS partition — only compiled.
+//!
+//! Nothing stores through this directly: `BoardPlatform` wraps it in
+//! [`bhk_core::transactional_storage::TransactionalStorage`], which chunks
+//! values past NVS's blob limit and commits them atomically. That layer's
+//! power-cut behaviour is tested on host against
+//! `bhk_core::nvs::SimulatedNvs`, which models this adapter's limits.
 
+use bhk_core::nvs::MAX_KEY_LEN as NVS_MAX_KEY_LEN;
 use bhk_core::platform::Storage;
 use embedded_svc::storage::RawStorage;
 use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
 use esp_idf_svc::sys::EspError;
 
-/// ESP-IDF's NVS keys are capped at 15 bytes (16 with the implicit NUL
-/// terminator) — this is a documented ESP-IDF constraint, not something
-/// discovered on hardware. Silently truncating a longer key would risk
-/// two distinct keys colliding into the same NVS entry, which is worse
-/// than failing loudly, so `NvsStorage::set` rejects them instead (see
-/// [`NvsStorageError::KeyTooLong`]).
-const NVS_MAX_KEY_LEN: usize = 15;
-
 /// Errors from [`NvsStorage::set`]. `Storage::get` can't report errors
 /// (the core trait returns a bare `Option`, not a `Result` — frozen in
 /// W1), so a failed read is logged and treated as "key absent" instead;
@@ -39,7 +38,10 @@ const NVS_MAX_KEY_LEN: usize = 15;
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum NvsStorageError {
-    /// The key is longer than NVS's 15-byte limit.
+    /// The key is longer than NVS's 15-byte limit
+    /// ([`bhk_core::nvs::MAX_KEY_LEN`]). Silently truncating it would risk
+    /// two distinct keys colliding into the same NVS entry, which is worse
+    /// than failing loudly.
     KeyTooLong,
     /// The underlying NVS write failed.
     Esp(EspError),
@@ -111,4 +113,13 @@ impl Storage for NvsStorage {
         self.nvs.set_raw(key, &value)?;
         Ok(())
     }
+
+    fn remove(&mut self, key: &str) -> Result<(), Self::Error> {
+        if key.len() > NVS_MAX_KEY_LEN {
+            return Err(NvsStorageError::KeyTooLong);
+        }
+
+        self.nvs.remove(key)?;
+        Ok(())
+    }
 }
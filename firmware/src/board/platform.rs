@@ -1,40 +1,44 @@
-//! `BoardPlatform`: assembles the T-Embed board adapters (`St7789Surface`,
-//! `RotaryEncoderInput`, `EspClock`, `NvsStorage`) into a concrete
-//! `bhk_core::platform::Platform`, so `main.rs` can hand a single value to
-//! the unified `bhk_core::run` loop — the real-target counterpart to
-//! `emulator::platform::HostPlatform`.
+//! `BoardPlatform`: assembles the selected board's adapters (its
+//! `Display`/`Input`, plus the shared `EspClock` and `NvsStorage`) into a
+//! concrete `bhk_core::platform::Platform`, so `main.rs` can hand a single
+//! value to the unified `bhk_core::run` loop — the real-target counterpart
+//! to `emulator::platform::HostPlatform`.
 //!
 //! Unlike `HostPlatform` (generic over which `DisplaySurface`/`InputSource`
-//! back it, because the emulator has two of each), this is concrete: the
-//! real-target binary only ever has exactly one display and one input
-//! driver.
+//! back it, because the emulator has two of each), this is concrete: a
+//! firmware build only ever has exactly one board, so the display and
+//! input types are `board`'s feature-selected aliases, not parameters.
+//!
+//! Storage is `NvsStorage` behind `TransactionalStorage`, so every value the
+//! core stores is committed atomically and may outgrow a single NVS blob —
+//! the encrypted vault will.
 
 use bhk_core::platform::Platform;
+use bhk_core::transactional_storage::TransactionalStorage;
 
 use super::clock::EspClock;
 use super::nvs_storage::NvsStorage;
-use super::rotary_input::RotaryEncoderInput;
-use super::st7789_surface::St7789Surface;
+use super::{Display, Input};
 
 pub struct BoardPlatform {
-    display: St7789Surface,
-    input: RotaryEncoderInput,
+    display: Display,
+    input: Input,
     clock: EspClock,
-    storage: NvsStorage,
+    storage: TransactionalStorage<NvsStorage>,
 }
 
 impl BoardPlatform {
     #[must_use]
-    pub fn new(display: St7789Surface, input: RotaryEncoderInput, storage: NvsStorage) -> Self {
-        Self { display, input, clock: EspClock, storage }
+    pub fn new(display: Display, input: Input, storage: NvsStorage) -> Self {
+        Self { display, input, clock: EspClock, storage: TransactionalStorage::new(storage) }
     }
 }
 
 impl Platform for BoardPlatform {
-    type Display = St7789Surface;
-    type Input = RotaryEncoderInput;
+    type Display = Display;
+    type Input = Input;
     type Clock = EspClock;
-    type Storage = NvsStorage;
+    type Storage = TransactionalStorage<NvsStorage>;
 
     fn display(&mut self) -> &mut Self::Display {
         &mut self.display
@@ -38,51 +38,46 @@
 //! which is the whole point of copying it rather than inventing another
 //! polling-based scheme.
 //!
-//! The push-button (click = `Activate`, long-press = `Back`) is
-//! unchanged from the original design: it still reuses `button-driver`
-//! (already a dependency for the old 3-button `esp_input.rs` input
-//! driver), polled once per frame via `poll()` as before — buttons
-//! don't have the quadrature-aliasing problem a rotating encoder does
-//! (a single press/release pair can't "alias" the way rapid
-//! back-and-forth quadrature edges can), and the human's bug report was
-//! specifically about scrolling, not button response.
+//! # Acceleration and button gestures: `bhk_core::input::encoder`
 //!
-//! # No fast-rotation acceleration (intentionally dropped)
+//! What to *do* with the drained detents and the push-button's level is
+//! `bhk_core::input::encoder::EncoderGestures`, platform-free and tested
+//! on host with synthetic sample streams: slow rotation steps one item per
+//! detent, a fast spin (the ADR's "≥4 ticks in 100ms") jumps with
+//! `NavIntent::NextN`/`PrevN` in either direction, and the button reports
+//! click (`Activate`), double-click and long press (both `Back`). This
+//! module only reads hardware and passes the samples on with
+//! [`EspClock`]'s time, once per `poll`.
 //!
-//! An earlier version of this module had an `AccelerationWindow` that
-//! turned fast rotation (the ADR's "≥4 ticks in 100ms") into
-//! `NavIntent::NextN` multi-item jumps. **Deliberately removed** per the
-//! human's explicit request after testing the GPIO-interrupt decoder on
-//! real hardware: base (non-accelerated) scrolling was much better, but
-//! fast continuous scrolling was still erratic, and the acceleration
-//! path was the likely remaining cause (it also happened to be the
-//! entire reason bead ai-bitwarden-hw-key-2ed existed:
-//! `AccelerationWindow::classify` only ever emitted `NextN`, jump
-//! *forward*, even for a fast counter-clockwise spin, since
-//! `bhk_core::NavIntent` has no `PrevN`). Every detent now emits exactly
-//! one `NavIntent::Next`/`Prev`, however many detents land in a single
-//! poll — this module makes no attempt to detect or reward "fast"
-//! rotation. Both this behavior and bead 2ed's quirk are moot for now.
-//! Acceleration could be reintroduced properly later (with a real,
-//! signed `PrevN`-equivalent) per beads ai-bitwarden-hw-key-47d and
-//! ai-bitwarden-hw-key-2ed, but is out of scope until the base one-tick-
-//! per-detent path is confirmed smooth.
+//! An earlier `AccelerationWindow` here was **deliberately removed** after
+//! on-hardware testing (bead ai-bitwarden-hw-key-47d): fast scrolling was
+//! erratic, and it only ever jumped forward, even for a counter-clockwise
+//! spin (bead ai-bitwarden-hw-key-2ed), since `NavIntent` had no `PrevN`
+//! then. The core's version is symmetric and tested in both directions,
+//! and only kicks in once the decoder above is counting every edge — the
+//! erratic part was the frame-rate polling, which the ISR replaced.
 //!
-//! **Not yet exercised against real hardware** as of this revision (the
-//! erratic-scrolling report predates the GPIO-interrupt decoder, and the
-//! acceleration removal + direction fix below predate this exact commit)
-//! — pending the human's next on-hardware pass to confirm smooth,
-//! predictable one-step-per-detent scrolling in both directions.
+//! The push-button used to go through `button-driver`; it's now debounced
+//! by the core as well (`bhk_core::button_pad::Debouncer`), polled once
+//! per frame — buttons don't have the quadrature-aliasing problem a
+//! rotating encoder does (a press lasts far longer than a frame).
+//!
+//! **Not yet exercised against real hardware** as of this revision —
+//! pending the human's next on-hardware pass to confirm smooth,
+//! predictable scrolling in both directions, and that the acceleration
+//! thresholds feel right on this encoder.
 
 use std::sync::atomic::{AtomicI32, AtomicU8, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
 
-use bhk_core::{platform::InputSource, NavIntent};
-use button_driver::{Button, ButtonConfig};
-use esp_idf_hal::gpio::{Gpio0, Gpio4, Gpio5, Input, InterruptType, PinDriver, Pull};
+use bhk_core::input::encoder::EncoderGestures;
+use bhk_core::platform::{Clock, InputSource};
+use bhk_core::NavIntent;
+use esp_idf_hal::gpio::{AnyIOPin, Input, InterruptType, PinDriver, Pull};
 use esp_idf_hal::sys::{gpio_get_level, gpio_intr_enable, EspError};
 
+use super::clock::EspClock;
+
 /// Signed per-transition delta table, indexed `[prev_state][cur_state]`,
 /// both 2-bit `(lead << 1 | lag)` values `0..=3`. A `0` entry means "not
 /// a valid single-step quadrature transition" (a repeated state, or an
@@ -90,7 +85,7 @@ use esp_idf_hal::sys::{gpio_get_level, gpio_intr_enable, EspError};
 /// anti-glitch mechanism: bounce/noise on the lines is silently ignored
 /// (contributes zero) rather than miscounted, with no separate debounce
 /// timer needed for the quadrature signal itself (unlike the push-button,
-/// which still needs `button-driver`'s own timed debounce).
+/// which is still debounced by time in the core).
 ///
 /// Copied byte-for-byte from LilyGo's own T-Embed-CC1101 factory
 /// firmware, `examples/factory/factory.cpp`'s `kEncTable` — see the
@@ -129,7 +124,7 @@ struct EncoderIsrState {
     /// The physical GPIO fed as the table's "leading" bit.
     ///
     /// **Direction, round 2** (bead ai-bitwarden-hw-key-bgl): the first
-    /// GPIO-interrupt decoder revision fed `ENCODER_PIN_B` (GPIO5) here,
+    /// GPIO-interrupt decoder revision fed the encoder's B pin (GPIO5) here,
     /// carrying over the *assumption* that the old `rotary-encoder-hal`
     /// `Rotary::new(b, a)` swap's correction would transfer unchanged to
     /// this completely different decode algorithm. Hardware testing
@@ -210,8 +205,8 @@ impl EncoderIsrState {
 }
 
 /// `InputSource` for the T-Embed's EC11 rotary encoder + integrated
-/// push-button (`board::board_config::{ENCODER_PIN_A, ENCODER_PIN_B,
-/// ENCODER_BUTTON_PIN}`).
+/// push-button (`board::board_config::VARIANT.encoder`: GPIO4/5/0 on the
+/// CC1101, GPIO2/1/0 on the plain T-Embed).
 ///
 /// Owns and configures its three GPIOs itself (rather than taking
 /// pre-configured `PinDriver`s) because the pull-up requirement below is
@@ -235,11 +230,13 @@ pub struct RotaryEncoderInput {
     /// handles — so these fields exist purely to keep the underlying
     /// GPIO/interrupt configuration alive, hence `#[allow(dead_code)]`.
     #[allow(dead_code)]
-    pin_a: PinDriver<'static, Gpio4, Input>,
+    pin_a: PinDriver<'static, AnyIOPin, Input>,
     #[allow(dead_code)]
-    pin_b: PinDriver<'static, Gpio5, Input>,
+    pin_b: PinDriver<'static, AnyIOPin, Input>,
     encoder: Arc<EncoderIsrState>,
-    button: Button<PinDriver<'static, Gpio0, Input>, Instant>,
+    button: PinDriver<'static, AnyIOPin, Input>,
+    gestures: EncoderGestures,
+    clock: EspClock,
 }
 
 impl RotaryEncoderInput {
@@ -258,7 +255,7 @@ impl RotaryEncoderInput {
     /// inspected on hardware to confirm it doesn't already provide its
     /// own pull-ups (which would make this redundant but harmless) or,
     /// worse, pull-downs (which would make this wrong).
-    pub fn new(pin_a: Gpio4, pin_b: Gpio5, button_pin: Gpio0) -> Result<Self, EspError> {
+    pub fn new(pin_a: AnyIOPin, pin_b: AnyIOPin, button_pin: AnyIOPin) -> Result<Self, EspError> {
         let mut a = PinDriver::input(pin_a)?;
         a.set_pull(Pull::Up)?;
         let mut b = PinDriver::input(pin_b)?;
@@ -267,10 +264,11 @@ impl RotaryEncoderInput {
         btn.set_pull(Pull::Up)?;
 
         // Direction: see `EncoderIsrState::lead_pin`'s doc comment for
-        // the full history. `lead = a` (GPIO4/ENCODER_PIN_A), `lag = b`
-        // (GPIO5/ENCODER_PIN_B) — the vendor's own natural order, the
-        // opposite of the first GPIO-interrupt decoder revision's (wrong)
-        // swap.
+        // the full history. `lead = a` (`VARIANT.encoder.a`, GPIO4 on the
+        // CC1101), `lag = b` (`VARIANT.encoder.b`, GPIO5) — the vendor's
+        // own natural order, the opposite of the first GPIO-interrupt
+        // decoder revision's (wrong) swap. The plain T-Embed's encoder is
+        // the same module, so the same order is assumed there.
         let initial_lead = i32::from(a.is_high());
         let initial_lag = i32::from(b.is_high());
         let initial_state = ((initial_lead << 1) | initial_lag) as u8;
@@ -303,51 +301,24 @@ impl RotaryEncoderInput {
             pin_a: a,
             pin_b: b,
             encoder,
-            button: Button::new(
-                btn,
-                ButtonConfig {
-                    mode: button_driver::Mode::PullUp,
-                    ..Default::default()
-                },
-            ),
+            button: btn,
+            gestures: EncoderGestures::new(),
+            clock: EspClock,
         })
     }
 }
 
 impl InputSource for RotaryEncoderInput {
     fn poll(&mut self) -> Vec<NavIntent> {
-        let mut intents = Vec::new();
-
         // Drain whatever the ISR accumulated since the last poll — could
         // be 0 (no rotation), a small number (normal-speed rotation), or
         // several ticks at once if the encoder spun fast enough to
         // outrun this frame's ~33ms budget (which the frame-rate-polled
         // predecessor design would have aliased/miscounted instead of
-        // ever actually observing all of; see the module doc). Every
-        // tick emits exactly one `Next`/`Prev` — no acceleration/`NextN`
-        // upgrade (deliberately removed, see the module doc's "No
-        // fast-rotation acceleration" section).
+        // ever actually observing all of; see the module doc). Turning
+        // that and the button level into intents is the core's job.
         let delta = self.encoder.take_delta();
-        let intent = if delta > 0 {
-            Some(NavIntent::Next)
-        } else if delta < 0 {
-            Some(NavIntent::Prev)
-        } else {
-            None
-        };
-        if let Some(intent) = intent {
-            for _ in 0..delta.unsigned_abs() {
-                intents.push(intent);
-            }
-        }
-
-        self.button.tick();
-        if self.button.is_clicked() {
-            intents.push(NavIntent::Activate);
-        } else if self.button.held_time().is_some() {
-            intents.push(NavIntent::Back);
-        }
-        self.button.reset();
+        let intents = self.gestures.update(delta, self.button.is_low(), self.clock.now());
 
         // Debug aid for on-hardware verification (bead ekd): confirms the
         // encoder->NavIntent path is alive over serial, independent of
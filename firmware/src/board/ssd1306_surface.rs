@@ -0,0 +1,239 @@
+//! [`bhk_core::platform::DisplaySurface`] for the HUZZAH32 prototype's
+//! 128x32 SSD1306 OLED FeatherWing, over I2C.
+//!
+//! The core still renders color: `flush` gets the same `&FrameBuffer565`
+//! every other surface does, converts it to one bit per pixel with
+//! `bhk_core::render::MonoFrameBuffer` (ordered dithering, so the theme's
+//! surfaces stay distinguishable from its background), packs that into
+//! the controller's page layout with `MonoFrameBuffer::write_pages`, and
+//! sends it as one data write. Both conversions live in the core and are
+//! tested on host; this module only owns the bus.
+//!
+//! Raw command bytes rather than the `ssd1306` crate, for the same reason
+//! `St7789Surface` blits directly instead of going through `mipidsi` per
+//! pixel: the frame is already packed, and a driver crate would only
+//! re-pack it through a `DrawTarget`.
+//!
+//! # Hardware status
+//!
+//! Builds for `xtensa-esp32-espidf`; not run since the workspace split.
+//! The init sequence is the controller datasheet's 128x32 "application
+//! example" (the same one Adafruit's own library sends for this wing):
+//! multiplex 31, COM pins sequential, charge pump on.
+
+use std::time::Duration;
+
+use bhk_core::platform::{DisplaySurface, FrameBuffer565};
+use bhk_core::render::{Dither, MonoFrameBuffer};
+use embedded_graphics::primitives::Rectangle;
+use esp_idf_hal::delay::TickType;
+use esp_idf_hal::gpio::{Gpio22, Gpio23};
+use esp_idf_hal::i2c::{I2cConfig, I2cDriver, I2C0};
+use esp_idf_hal::sys::EspError;
+use esp_idf_hal::units::FromValueType;
+
+use super::board_config::{DISPLAY_HEIGHT, DISPLAY_WIDTH, OLED_I2C_ADDRESS};
+
+/// Fast-mode I2C: a full 512-byte frame is ~13ms on the wire, inside the
+/// run loop's ~33ms frame budget.
+const I2C_BAUDRATE_KHZ: u32 = 400;
+
+/// How long a write waits for the bus before failing, rather than
+/// blocking the run loop forever on a wedged bus or a missing wing.
+const I2C_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Control byte prefixing a run of command bytes (`Co = 0`, `D/C# = 0`).
+const CONTROL_COMMANDS: u8 = 0x00;
+/// Control byte prefixing a run of GDDRAM data bytes (`Co = 0`, `D/C# = 1`).
+const CONTROL_DATA: u8 = 0x40;
+
+/// One page per 8 rows, one byte per column per page.
+const FRAME_BYTES: usize = DISPLAY_WIDTH as usize * (DISPLAY_HEIGHT as usize).div_ceil(8);
+
+mod command {
+    pub const DISPLAY_OFF: u8 = 0xAE;
+    pub const DISPLAY_ON: u8 = 0xAF;
+    pub const SET_CLOCK_DIV: u8 = 0xD5;
+    pub const SET_MULTIPLEX: u8 = 0xA8;
+    pub const SET_DISPLAY_OFFSET: u8 = 0xD3;
+    pub const SET_START_LINE_0: u8 = 0x40;
+    pub const CHARGE_PUMP: u8 = 0x8D;
+    pub const SET_MEMORY_MODE: u8 = 0x20;
+    pub const SEGMENT_REMAP: u8 = 0xA1;
+    pub const COM_SCAN_DEC: u8 = 0xC8;
+    pub const SET_COM_PINS: u8 = 0xDA;
+    pub const SET_CONTRAST: u8 = 0x81;
+    pub const SET_PRECHARGE: u8 = 0xD9;
+    pub const SET_VCOM_DETECT: u8 = 0xDB;
+    pub const DISPLAY_ALL_ON_RESUME: u8 = 0xA4;
+    pub const NORMAL_DISPLAY: u8 = 0xA6;
+    pub const SET_COLUMN_ADDRESS: u8 = 0x21;
+    pub const SET_PAGE_ADDRESS: u8 = 0x22;
+}
+
+/// The datasheet's 128x32 power-on sequence (see the module doc).
+const INIT_SEQUENCE: &[u8] = &[
+    command::DISPLAY_OFF,
+    command::SET_CLOCK_DIV,
+    0x80,
+    command::SET_MULTIPLEX,
+    DISPLAY_HEIGHT as u8 - 1,
+    command::SET_DISPLAY_OFFSET,
+    0x00,
+    command::SET_START_LINE_0,
+    command::CHARGE_PUMP,
+    0x14,
+    // Horizontal addressing: the column pointer wraps onto the next page,
+    // so one data write fills the whole `write_pages` frame.
+    command::SET_MEMORY_MODE,
+    0x00,
+    command::SEGMENT_REMAP,
+    command::COM_SCAN_DEC,
+    command::SET_COM_PINS,
+    0x02,
+    command::SET_CONTRAST,
+    0x8F,
+    command::SET_PRECHARGE,
+    0xF1,
+    command::SET_VCOM_DETECT,
+    0x40,
+    command::DISPLAY_ALL_ON_RESUME,
+    command::NORMAL_DISPLAY,
+    command::DISPLAY_ON,
+];
+
+/// Resets the GDDRAM write pointer to the top-left of the full panel;
+/// sent before every frame.
+const ADDRESS_WINDOW: &[u8] = &[
+    command::SET_COLUMN_ADDRESS,
+    0,
+    DISPLAY_WIDTH as u8 - 1,
+    command::SET_PAGE_ADDRESS,
+    0,
+    (DISPLAY_HEIGHT / 8) as u8 - 1,
+];
+
+/// Errors from [`Ssd1306Surface::flush`].
+///
+/// `#[allow(dead_code)]`: read only through `Debug`, same as
+/// `St7789SurfaceError`.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub enum Ssd1306SurfaceError {
+    /// The I2C write to the controller failed (no ACK, bus timeout).
+    I2c(EspError),
+    /// The framebuffer handed to `flush` isn't sized for this panel.
+    FramebufferSizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+}
+
+/// `DisplaySurface` for the FeatherWing's SSD1306, driven over I2C0.
+pub struct Ssd1306Surface {
+    i2c: I2cDriver<'static>,
+    /// Control byte + packed frame, reused every flush.
+    frame_bytes: Vec<u8>,
+    /// The last frame sent, so an unchanged frame (most of them: the list
+    /// only changes on input) costs no bus time at all.
+    last_frame: Option<Vec<u8>>,
+}
+
+impl Ssd1306Surface {
+    /// Brings up I2C0 and sends the controller's init sequence.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EspError` if the I2C driver can't be installed or the
+    /// controller doesn't acknowledge the init sequence (wing missing,
+    /// wrong address).
+    pub fn new(i2c: I2C0, sda: Gpio23, scl: Gpio22) -> Result<Self, EspError> {
+        let config = I2cConfig::new().baudrate(I2C_BAUDRATE_KHZ.kHz().into());
+        let mut i2c = I2cDriver::new(i2c, sda, scl, &config)?;
+        send_commands(&mut i2c, INIT_SEQUENCE)?;
+
+        let mut frame_bytes = vec![0u8; FRAME_BYTES + 1];
+        frame_bytes[0] = CONTROL_DATA;
+        Ok(Self {
+            i2c,
+            frame_bytes,
+            last_frame: None,
+        })
+    }
+}
+
+/// Writes `bytes` (a control byte and its payload) to the controller.
+fn write(i2c: &mut I2cDriver<'static>, bytes: &[u8]) -> Result<(), EspError> {
+    i2c.write(OLED_I2C_ADDRESS, bytes, TickType::from(I2C_TIMEOUT).ticks())
+}
+
+/// Sends `commands` as one command-stream write.
+fn send_commands(i2c: &mut I2cDriver<'static>, commands: &[u8]) -> Result<(), EspError> {
+    let mut bytes = Vec::with_capacity(commands.len() + 1);
+    bytes.push(CONTROL_COMMANDS);
+    bytes.extend_from_slice(commands);
+    write(i2c, &bytes)
+}
+
+impl DisplaySurface for Ssd1306Surface {
+    type Error = Ssd1306SurfaceError;
+
+    /// The whole 1 KiB frame goes out whenever anything changed: the
+    /// SSD1306's page layout makes a partial window cost nearly as much
+    /// as a full one over I2C, so `damage` only short-circuits the
+    /// nothing-changed case before any dithering happens.
+    fn flush(
+        &mut self,
+        framebuffer: &FrameBuffer565,
+        damage: &[Rectangle],
+    ) -> Result<(), Self::Error> {
+        let (expected_w, expected_h) = (u32::from(DISPLAY_WIDTH), u32::from(DISPLAY_HEIGHT));
+        if framebuffer.width() != expected_w || framebuffer.height() != expected_h {
+            return Err(Ssd1306SurfaceError::FramebufferSizeMismatch {
+                expected: (expected_w, expected_h),
+                actual: (framebuffer.width(), framebuffer.height()),
+            });
+        }
+
+        if damage.is_empty() && self.last_frame.is_some() {
+            return Ok(());
+        }
+
+        MonoFrameBuffer::from_rgb565(framebuffer, Dither::Ordered)
+            .write_pages(&mut self.frame_bytes[1..]);
+        if self.last_frame.as_deref() == Some(&self.frame_bytes[1..]) {
+            return Ok(());
+        }
+
+        send_commands(&mut self.i2c, ADDRESS_WINDOW).map_err(Ssd1306SurfaceError::I2c)?;
+        write(&mut self.i2c, &self.frame_bytes).map_err(Ssd1306SurfaceError::I2c)?;
+        self.last_frame = Some(self.frame_bytes[1..].to_vec());
+        Ok(())
+    }
+
+    /// An OLED has no backlight: `0` switches the panel off (the idle
+    /// timeout), anything else switches it on with the contrast register
+    /// scaled to `percent`.
+    fn set_brightness(&mut self, percent: u8) {
+        let result = if percent == 0 {
+            send_commands(&mut self.i2c, &[command::DISPLAY_OFF])
+        } else {
+            let contrast = (u16::from(percent.min(100)) * 255 / 100) as u8;
+            send_commands(
+                &mut self.i2c,
+                &[command::SET_CONTRAST, contrast, command::DISPLAY_ON],
+            )
+        };
+        if let Err(error) = result {
+            log::warn!("failed to set the OLED's brightness: {error:?}");
+        }
+    }
+}
+
+impl Drop for Ssd1306Surface {
+    fn drop(&mut self) {
+        // Best-effort, like `St7789Surface`'s backlight-off: don't leave a
+        // frozen frame burning into the OLED.
+        let _ = send_commands(&mut self.i2c, &[command::DISPLAY_OFF]);
+    }
+}
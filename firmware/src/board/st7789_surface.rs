@@ -82,9 +82,9 @@
 //! 3. Writes that whole buffer in **one** `SpiDevice::write` call.
 //!
 //! The `CASET`/`RASET` address-window command bytes ([`CASET_ARGS`],
-//! [`RASET_ARGS`]) are sent **once**, in `new`, not per frame — this
-//! adapter only ever draws the same full-panel rectangle, so the window
-//! never needs to change. They are **hand-computed compile-time
+//! [`RASET_ARGS`]) for the full panel are sent in `new`, and again
+//! whenever `flush` goes back to a full frame after a partial one (see
+//! "Partial flushes" below). They are **hand-computed compile-time
 //! constants**, not derived at runtime by `mipidsi` — see their doc
 //! comments for the exact derivation (worked out by reading
 //! `mipidsi-0.10.0`'s private `Display::set_address_window` and the
@@ -94,7 +94,27 @@
 //! up on real hardware but not in `cargo build`** — hardware-verify
 //! with a human watching the actual panel, not just that this compiles
 //! and boots.
+//!
+//! # Partial flushes
+//!
+//! `flush` receives the damage rectangles `FrameBuffer565::take_damage`
+//! reported (see the `DisplaySurface::flush` contract). Moving the list
+//! selection only changes a couple of rows, so instead of the full
+//! 108,800-byte frame each damaged rectangle gets its own address window
+//! ([`address_window`], the same derivation as [`CASET_ARGS`] applied to
+//! the rectangle rather than the whole panel), `RAMWR`, and just that
+//! rectangle's pixels via
+//! [`FrameBuffer565::write_be_bytes_in`]. Every rectangle costs three
+//! extra command round trips, so once the damage covers at least
+//! [`FULL_FRAME_DAMAGE_PERCENT`] of the panel (a screen push, a dialog)
+//! a single full-frame blit is cheaper and used instead. A full frame is
+//! also forced for the first flush and after any failed one, since the
+//! panel's GRAM can't be trusted to hold the previous frame then. The
+//! window-to-panel mapping is the unverified part here — same caveat as
+//! above: hardware-verify that a partial update lands exactly where the
+//! full frame put it.
 use bhk_core::platform::{DisplaySurface, FrameBuffer565};
+use embedded_graphics::primitives::Rectangle;
 use embedded_hal::digital::OutputPin;
 use embedded_hal::spi::SpiDevice;
 use esp_idf_hal::delay::Delay;
@@ -107,7 +127,7 @@ use mipidsi::models::ST7789;
 use mipidsi::options::{ColorInversion, ColorOrder, Orientation, Rotation};
 use mipidsi::Builder;
 
-use crate::board::board_config::{DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use crate::board::board_config::{DISPLAY_HEIGHT, DISPLAY_WIDTH, VARIANT};
 
 type Spi = SpiDeviceDriver<'static, SpiDriver<'static>>;
 type CtrlPin = PinDriver<'static, AnyOutputPin, Output>;
@@ -166,9 +186,9 @@ const SPI_BAUDRATE_MHZ: u32 = 40;
 /// real-hardware boot-loop panic this constant fixes is on first T-Embed
 /// flash, per bead ai-bitwarden-hw-key-c6e. Hardware-verified: with this
 /// fix, boot reaches a stable "Entering main loop" with no panic/reboot
-/// loop.
-const NATIVE_WIDTH: u16 = 170;
-const NATIVE_HEIGHT: u16 = 320;
+/// loop. Taken from the selected variant's table (170x320 on both).
+const NATIVE_WIDTH: u16 = VARIANT.panel.native_width;
+const NATIVE_HEIGHT: u16 = VARIANT.panel.native_height;
 
 /// Total bytes in one full-panel Rgb565 frame (320 * 170 * 2 =
 /// 108,800). Used as the size of [`St7789Surface`]'s persistent
@@ -245,6 +265,10 @@ const DMA_CHUNK_BYTES: usize = FRAME_BYTES / 4;
 ///   rotation is called that.)
 ///
 /// So: CASET args for `(sx=0, ex=319)` = `0x0000` / `0x013F`.
+///
+/// Both T-Embed variants share this panel setup; the assertion below the
+/// constants turns a future variant with a different offset, rotation or
+/// size into a build error here rather than a shifted image on hardware.
 const CASET_ARGS: [u8; 4] = [0x00, 0x00, 0x01, 0x3F];
 
 /// `RASET` (Set Page Address, DCS `0x2B`) argument bytes for this
@@ -252,6 +276,47 @@ const CASET_ARGS: [u8; 4] = [0x00, 0x00, 0x01, 0x3F];
 /// for the full derivation. `(sy=35, ey=204)` = `0x0023` / `0x00CC`.
 const RASET_ARGS: [u8; 4] = [0x00, 0x23, 0x00, 0xCC];
 
+/// The final `(column, row)` offset [`CASET_ARGS`]'s derivation arrives
+/// at, after the rotation swapped `display_offset(35, 0)`: added to any
+/// framebuffer-space window to get the GRAM window to send.
+const WINDOW_OFFSET: (u16, u16) = (0, 35);
+
+/// `CASET`/`RASET` argument bytes for the framebuffer-space span
+/// `start..=end` on one axis, `offset` being that axis's
+/// [`WINDOW_OFFSET`] component: `[start_hi, start_lo, end_hi, end_lo]`,
+/// big-endian, the same encoding as [`CASET_ARGS`].
+const fn address_window(start: u16, end: u16, offset: u16) -> [u8; 4] {
+    let (start, end) = ((start + offset).to_be_bytes(), (end + offset).to_be_bytes());
+    [start[0], start[1], end[0], end[1]]
+}
+
+/// Damage covering at least this share of the panel is sent as one full
+/// frame rather than rectangle by rectangle — see the module doc's
+/// "Partial flushes" section.
+const FULL_FRAME_DAMAGE_PERCENT: u32 = 50;
+
+// The inputs `CASET_ARGS`/`RASET_ARGS` were derived from, and
+// `address_window` reproducing them for the full panel.
+const _: () = {
+    assert!(VARIANT.panel.offset.0 == 35 && VARIANT.panel.offset.1 == 0);
+    assert!(matches!(VARIANT.panel.rotation, board_variants::Rotation::Deg270));
+    assert!(NATIVE_WIDTH == 170 && NATIVE_HEIGHT == 320);
+    let caset = address_window(0, DISPLAY_WIDTH - 1, WINDOW_OFFSET.0);
+    let raset = address_window(0, DISPLAY_HEIGHT - 1, WINDOW_OFFSET.1);
+    let mut i = 0;
+    while i < 4 {
+        assert!(caset[i] == CASET_ARGS[i] && raset[i] == RASET_ARGS[i]);
+        i += 1;
+    }
+};
+
+/// Low time of the hardware reset pulse on boards that wire `RESX`, and
+/// the wait after releasing it before the first command. The ST7789V
+/// datasheet asks for at least 10us low and 5ms before commands (120ms
+/// before sleep-out, which `mipidsi`'s own post-reset delays cover).
+const RESET_PULSE_MS: u32 = 10;
+const RESET_RECOVERY_MS: u32 = 10;
+
 /// DCS instruction bytes this module sends directly (bypassing
 /// `mipidsi::dcs`'s typed command structs, which are private/internal to
 /// that crate's own dispatch): `CASET`, `RASET`, and `RAMWR`
@@ -288,6 +353,18 @@ fn send_command(spi: &mut Spi, dc: &mut CtrlPin, command: u8, args: &[u8]) -> Re
     SpiDevice::write(spi, args).map_err(InterfaceError::Spi)
 }
 
+/// Maps the variant table's rotation onto `mipidsi`'s. `board-variants`
+/// stays dependency-free (so its pin-table tests run on host), hence its
+/// own enum rather than `mipidsi`'s.
+const fn rotation(rotation: board_variants::Rotation) -> Rotation {
+    match rotation {
+        board_variants::Rotation::Deg0 => Rotation::Deg0,
+        board_variants::Rotation::Deg90 => Rotation::Deg90,
+        board_variants::Rotation::Deg180 => Rotation::Deg180,
+        board_variants::Rotation::Deg270 => Rotation::Deg270,
+    }
+}
+
 /// Errors from [`St7789Surface::new`].
 ///
 /// `#[allow(dead_code)]`: both variants' inner values are read only
@@ -303,9 +380,10 @@ pub enum St7789SurfaceInitError {
     /// `mipidsi`'s panel init sequence failed.
     ///
     /// The reset-pin error type is `core::convert::Infallible`, not
-    /// `GpioError`: this board has no LCD hardware reset pin, so `mipidsi`
-    /// uses its `NoResetPin` marker type, whose `OutputPin` impl can
-    /// never fail.
+    /// `GpioError`: `mipidsi` is never given a reset pin (the CC1101 has
+    /// none, and the plain T-Embed's is pulsed by `new` itself before
+    /// init), so it uses its `NoResetPin` marker type, whose `OutputPin`
+    /// impl can never fail.
     Display(mipidsi::InitError<InterfaceError, core::convert::Infallible>),
     /// Sending the one-time `CASET`/`RASET` address-window commands
     /// (after `mipidsi` init, before entering the raw per-frame blit
@@ -350,8 +428,13 @@ pub struct St7789Surface {
     dc: CtrlPin,
     /// Persistent, pre-allocated scratch buffer for
     /// [`FrameBuffer565::write_be_bytes`]'s output — reused every frame
-    /// rather than allocated fresh, since its size never changes.
+    /// rather than allocated fresh, since its size never changes. Partial
+    /// flushes use a prefix of it for one damage rectangle at a time.
     frame_bytes: Vec<u8>,
+    /// Whether the next `flush` must send the whole frame regardless of
+    /// the damage it's given: set for the first frame and after any
+    /// failed flush, when the panel's contents are unknown.
+    full_frame_pending: bool,
     backlight: CtrlPin,
     /// Kept alive for as long as the display is: dropping this would
     /// (per T-Embed-CC1101's own `factory.cpp`, which drives
@@ -360,11 +443,17 @@ pub struct St7789Surface {
     /// existing, not being called.
     #[allow(dead_code)]
     _peripheral_power: CtrlPin,
+    /// The plain T-Embed's LCD reset line, held high (released) for as
+    /// long as the display lives — dropping the driver would float it.
+    /// `None` on the CC1101, which doesn't wire one.
+    #[allow(dead_code)]
+    _lcd_reset: Option<CtrlPin>,
 }
 
 impl St7789Surface {
-    /// Powers on the T-Embed's shared peripheral rail, brings up the SPI
-    /// bus (with DMA enabled — see the module doc), initializes the
+    /// Powers on the T-Embed's shared peripheral rail, pulses the LCD
+    /// reset line if the variant has one, brings up the SPI bus (with
+    /// DMA enabled — see the module doc), initializes the
     /// ST7789 via `mipidsi`, sends the one-time `CASET`/`RASET` address
     /// window, and turns on the backlight.
     ///
@@ -387,19 +476,39 @@ impl St7789Surface {
         mosi: AnyOutputPin,
         cs: AnyOutputPin,
         dc: AnyOutputPin,
+        reset: Option<AnyOutputPin>,
         backlight: AnyOutputPin,
         peripheral_power_on: AnyOutputPin,
     ) -> Result<Self, St7789SurfaceInitError> {
+        log::info!("St7789Surface: initializing the {} panel", VARIANT.name);
+
         let mut peripheral_power = PinDriver::output(peripheral_power_on)?;
         // Per T-Embed-CC1101's own `factory.cpp` `setup()`: `BOARD_PWR_EN`
-        // must be driven high before `board_spi_init_shared_bus()` or the
-        // panel's shared power rail is off entirely. The settle delay
-        // before touching SPI is a guess (untested) — long enough to be
-        // safe on a busy-wait, not derived from a rail spec sheet we
-        // don't have.
-        peripheral_power.set_high()?;
+        // must be driven on (high, on both variants) before
+        // `board_spi_init_shared_bus()` or the panel's shared power rail
+        // is off entirely. The settle delay before touching SPI is a
+        // guess (untested) — long enough to be safe on a busy-wait, not
+        // derived from a rail spec sheet we don't have.
+        peripheral_power.set_level(VARIANT.power_enable.polarity.on_level().into())?;
         Delay::new_default().delay_ms(10);
 
+        // Hardware reset, where the board wires one (the plain T-Embed).
+        // Done here rather than through `Builder::reset_pin` so `mipidsi`'s
+        // reset-pin type (and `St7789SurfaceInitError::Display`'s) stays
+        // the same `NoResetPin` on every variant; `mipidsi` still sends its
+        // software reset afterwards, which is harmless after a hard one.
+        let lcd_reset = match reset {
+            Some(pin) => {
+                let mut pin = PinDriver::output(pin)?;
+                pin.set_low()?;
+                Delay::new_default().delay_ms(RESET_PULSE_MS);
+                pin.set_high()?;
+                Delay::new_default().delay_ms(RESET_RECOVERY_MS);
+                Some(pin)
+            }
+            None => None,
+        };
+
         // DMA enabled (bead ai-bitwarden-hw-key-ego): see the module
         // doc's "Hot-path blit" section for why this alone was a large
         // part of the fix (without it, ESP-IDF's SPI master driver caps
@@ -442,13 +551,13 @@ impl St7789Surface {
 
         let mut delay = Delay::new_default();
         let display = Builder::new(ST7789, interface)
-            // No `.reset_pin(...)` call: this board has no LCD hardware
+            // No `.reset_pin(...)` call: the CC1101 has no LCD hardware
             // reset line (`TFT_RST = -1` in LilyGo's own
-            // `Setup214_LilyGo_T_Embed_PN532.h`). With no reset pin
-            // configured, `mipidsi`'s `Builder::init` sends a
-            // `SoftReset` DCS command instead of toggling a GPIO — this
-            // is the intended, supported "no hardware reset" path, not a
-            // workaround.
+            // `Setup214_LilyGo_T_Embed_PN532.h`), and the plain T-Embed's
+            // was already pulsed above. With no reset pin configured,
+            // `mipidsi`'s `Builder::init` sends a `SoftReset` DCS command
+            // instead of toggling a GPIO — this is the intended,
+            // supported "no hardware reset" path, not a workaround.
             .display_size(NATIVE_WIDTH, NATIVE_HEIGHT)
             // Column/row start offset for this exact 170-wide ST7789
             // panel: the T-Embed-CC1101 repo's vendored
@@ -465,20 +574,21 @@ impl St7789Surface {
             // real T-Embed CC1101: fills the panel exactly, no
             // cropping/blank margin. (See `CASET_ARGS`'s doc comment for
             // how this feeds into the hand-derived address-window bytes
-            // this module now sends directly.)
-            .display_offset(35, 0)
+            // this module now sends directly.) Read from the variant
+            // table, which documents the same derivation.
+            .display_offset(VARIANT.panel.offset.0, VARIANT.panel.offset.1)
             .color_order(ColorOrder::Rgb)
             // Required for this panel: LilyGo's own T-Embed-CC1101
             // `Setup214_LilyGo_T_Embed_PN532.h` sets `TFT_INVERSION_ON`.
             // HARDWARE-CONFIRMED CORRECT on real T-Embed CC1101: colors
             // match expectations with this set.
-            .invert_colors(ColorInversion::Inverted)
+            .invert_colors(if VARIANT.panel.inverted { ColorInversion::Inverted } else { ColorInversion::Normal })
             // `Rotation::Deg90` rendered the image upside down on real
             // hardware; `Deg270` is the other of the two 320x170
             // landscape choices. HARDWARE-CONFIRMED CORRECT on real
             // T-Embed CC1101: right-side up, matching the factory
             // firmware's orientation.
-            .orientation(Orientation::new().rotate(Rotation::Deg270))
+            .orientation(Orientation::new().rotate(rotation(VARIANT.panel.rotation)))
             .init(&mut delay)
             .map_err(St7789SurfaceInitError::Display)?;
 
@@ -490,11 +600,10 @@ impl St7789Surface {
         let (interface, _model, _reset) = display.release();
         let (mut spi, mut dc) = interface.release();
 
-        // One-time CASET/RASET address-window setup (see `CASET_ARGS`/
-        // `RASET_ARGS`'s doc comments for the exact derivation). Sent
-        // once here, not per frame, since this adapter only ever draws
-        // the same full-panel rectangle -- `flush` only needs to resend
-        // RAMWR + pixel data.
+        // Initial CASET/RASET address-window setup (see `CASET_ARGS`/
+        // `RASET_ARGS`'s doc comments for the exact derivation). `flush`
+        // only resends it when switching back to full frames after a
+        // partial one (see the module doc's "Partial flushes" section).
         send_command(&mut spi, &mut dc, dcs_command::CASET, &CASET_ARGS).map_err(St7789SurfaceInitError::AddressWindow)?;
         send_command(&mut spi, &mut dc, dcs_command::RASET, &RASET_ARGS).map_err(St7789SurfaceInitError::AddressWindow)?;
 
@@ -504,33 +613,71 @@ impl St7789Surface {
             spi,
             dc,
             frame_bytes: vec![0u8; FRAME_BYTES],
+            full_frame_pending: true,
             backlight: backlight_pin,
             _peripheral_power: peripheral_power,
+            _lcd_reset: lcd_reset,
         })
     }
 }
 
-impl DisplaySurface for St7789Surface {
-    type Error = St7789SurfaceError;
-
-    fn flush(&mut self, framebuffer: &FrameBuffer565) -> Result<(), Self::Error> {
-        let (expected_w, expected_h) = (u32::from(DISPLAY_WIDTH), u32::from(DISPLAY_HEIGHT));
-        if framebuffer.width() != expected_w || framebuffer.height() != expected_h {
-            return Err(St7789SurfaceError::FramebufferSizeMismatch {
-                expected: (expected_w, expected_h),
-                actual: (framebuffer.width(), framebuffer.height()),
-            });
+impl St7789Surface {
+    /// The body of [`DisplaySurface::flush`] once the size is checked:
+    /// nothing for empty damage (unless a full frame is pending), the
+    /// damaged rectangles one address window at a time, or the whole
+    /// frame — see the module doc's "Partial flushes" section.
+    fn send_damage(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) -> Result<(), St7789SurfaceError> {
+        if !self.full_frame_pending {
+            let damaged: u32 = damage.iter().map(|area| area.size.width * area.size.height).sum();
+            if damaged == 0 {
+                return Ok(());
+            }
+            if damaged * 100 < u32::from(DISPLAY_WIDTH) * u32::from(DISPLAY_HEIGHT) * FULL_FRAME_DAMAGE_PERCENT {
+                for area in damage {
+                    self.send_area(framebuffer, area)?;
+                }
+                return Ok(());
+            }
         }
+        self.send_full_frame(framebuffer)
+    }
+
+    /// Sends one damage rectangle: its own CASET/RASET window, RAMWR,
+    /// then just its pixels (a prefix of `frame_bytes`).
+    fn send_area(&mut self, framebuffer: &FrameBuffer565, area: &Rectangle) -> Result<(), St7789SurfaceError> {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        // Damage rectangles are always inside the (already size-checked)
+        // framebuffer, so these fit the panel's u16 coordinates.
+        let caset = address_window(area.top_left.x as u16, bottom_right.x as u16, WINDOW_OFFSET.0);
+        let raset = address_window(area.top_left.y as u16, bottom_right.y as u16, WINDOW_OFFSET.1);
+        send_command(&mut self.spi, &mut self.dc, dcs_command::CASET, &caset).map_err(St7789SurfaceError::Spi)?;
+        send_command(&mut self.spi, &mut self.dc, dcs_command::RASET, &raset).map_err(St7789SurfaceError::Spi)?;
+        send_command(&mut self.spi, &mut self.dc, dcs_command::RAMWR, &[]).map_err(St7789SurfaceError::Spi)?;
+
+        let bytes = &mut self.frame_bytes[..area.size.width as usize * area.size.height as usize * 2];
+        framebuffer.write_be_bytes_in(area, bytes);
+        SpiDevice::write(&mut self.spi, bytes).map_err(InterfaceError::Spi).map_err(St7789SurfaceError::Spi)
+    }
+
+    /// Sends the whole frame through the full-panel window — the
+    /// original hot path (see the module doc's "Hot-path blit" section).
+    fn send_full_frame(&mut self, framebuffer: &FrameBuffer565) -> Result<(), St7789SurfaceError> {
+        // Restores the full-panel window in case a partial flush left its
+        // last rectangle's window set. Always resent rather than tracked:
+        // two tiny command writes, next to the 108,800-byte frame.
+        send_command(&mut self.spi, &mut self.dc, dcs_command::CASET, &CASET_ARGS).map_err(St7789SurfaceError::Spi)?;
+        send_command(&mut self.spi, &mut self.dc, dcs_command::RASET, &RASET_ARGS).map_err(St7789SurfaceError::Spi)?;
 
         // RAMWR: resets the controller's internal GRAM write pointer
         // back to the (CASET, RASET) window's start, then every
         // subsequent data byte (until the window's pixel count is
         // reached) is interpreted as pixel data. Resent every frame
-        // (unlike CASET/RASET, sent once in `new`) since it's what
-        // actually triggers "start writing pixels now". No args (per
-        // `dcs_basic_command!(WriteMemoryStart, 0x2C)` in mipidsi's own
-        // `src/dcs.rs`), so this leaves DC high afterward, ready for the
-        // raw pixel-data write below.
+        // since it's what actually triggers "start writing pixels now".
+        // No args (per `dcs_basic_command!(WriteMemoryStart, 0x2C)` in
+        // mipidsi's own `src/dcs.rs`), so this leaves DC high afterward,
+        // ready for the raw pixel-data write below.
         send_command(&mut self.spi, &mut self.dc, dcs_command::RAMWR, &[]).map_err(St7789SurfaceError::Spi)?;
 
         // Convert the whole frame to panel byte order (big-endian) in one
@@ -554,6 +701,37 @@ impl DisplaySurface for St7789Surface {
     }
 }
 
+impl DisplaySurface for St7789Surface {
+    type Error = St7789SurfaceError;
+
+    fn flush(&mut self, framebuffer: &FrameBuffer565, damage: &[Rectangle]) -> Result<(), Self::Error> {
+        let (expected_w, expected_h) = (u32::from(DISPLAY_WIDTH), u32::from(DISPLAY_HEIGHT));
+        if framebuffer.width() != expected_w || framebuffer.height() != expected_h {
+            return Err(St7789SurfaceError::FramebufferSizeMismatch {
+                expected: (expected_w, expected_h),
+                actual: (framebuffer.width(), framebuffer.height()),
+            });
+        }
+
+        // Any failure below leaves the panel's GRAM half-written, so the
+        // next flush can't patch it rectangle by rectangle.
+        let result = self.send_damage(framebuffer, damage);
+        self.full_frame_pending = result.is_err();
+        result
+    }
+
+    /// The backlight is driven as a plain GPIO (`PinDriver`), not an LEDC
+    /// PWM channel, so it only has two levels: `0` turns it off (the idle
+    /// timeout) and anything else turns it fully on. Intermediate
+    /// brightness settings need the pin moved onto LEDC first.
+    fn set_brightness(&mut self, percent: u8) {
+        let result = if percent == 0 { self.backlight.set_low() } else { self.backlight.set_high() };
+        if let Err(error) = result {
+            log::warn!("failed to switch the backlight: {error:?}");
+        }
+    }
+}
+
 impl Drop for St7789Surface {
     fn drop(&mut self) {
         // Best-effort: turn the backlight off on drop rather than leaving
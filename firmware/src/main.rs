@@ -1,5 +1,7 @@
-//! T-Embed (ESP32-S3) firmware entry point: assembles the real
-//! board adapters (`board::BoardPlatform`) and hands them to the unified,
+//! Firmware entry point: assembles the selected board's adapters
+//! (`board::BoardPlatform` — the T-Embed CC1101 by default, the plain
+//! T-Embed with `board-t-embed-plain`, the HUZZAH32 + SSD1306 prototype
+//! with `board-huzzah32`) and hands them to the unified,
 //! `Platform`-generic `bhk_core::run` loop — the same loop
 //! `emulator/src/main.rs` drives for the windowed/headless run modes, per
 //! `.planning/decisions/2026-08-11-three-mode-testability.md`.
@@ -7,7 +9,9 @@
 //! Replaces the old HUZZAH32 prototype's direct SSD1306 + 3-button wiring
 //! (`gui`/`simple_gui`/`view`/`simple_view`/`esp_input`/`time`, all deleted
 //! in this bead) — see `board`'s module doc for what is and isn't verified
-//! about the new wiring.
+//! about the new wiring. That prototype board is supported again, as a
+//! second `board` behind the same loop rather than its own GUI stack: the
+//! render core lays the app out for its 128x32 panel by size alone.
 //!
 //! # No sync transport on real hardware yet
 //!
@@ -35,7 +39,11 @@ use bhk_core::{run, App, SyncSource, VaultItem};
 use esp_idf_svc::nvs::EspDefaultNvsPartition;
 use esp_idf_svc::sys::EspError;
 
-use crate::board::{BoardPeripherals, BoardPlatform, NvsStorage, RotaryEncoderInput, St7789Surface, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use crate::board::{BoardPeripherals, BoardPlatform, NvsStorage, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+#[cfg(feature = "board-huzzah32")]
+use crate::board::{ButtonInput, Ssd1306Surface};
+#[cfg(feature = "board-t-embed")]
+use crate::board::{RotaryEncoderInput, St7789Surface};
 
 /// ~30fps, matching the emulator's `FRAME_BUDGET` — no product reason yet
 /// for the two to differ (no animation, nothing latency-sensitive in this
@@ -141,23 +149,30 @@ fn main() -> Result<(), EspError> {
     // Bind the log crate to the ESP Logging facilities
     esp_idf_svc::log::EspLogger::initialize_default();
 
-    log::info!("Bitwarden HW Key - T-Embed firmware starting");
+    log::info!("Bitwarden HW Key - {DISPLAY_WIDTH}x{DISPLAY_HEIGHT} board firmware starting");
 
     let peripherals = BoardPeripherals::take()?;
 
+    #[cfg(feature = "board-t-embed")]
     let display = St7789Surface::new(
         peripherals.lcd_spi,
         peripherals.lcd_sclk,
         peripherals.lcd_mosi,
         peripherals.lcd_cs,
         peripherals.lcd_dc,
+        peripherals.lcd_reset,
         peripherals.lcd_backlight,
         peripherals.peripheral_power_on,
     )
     .expect("failed to initialize the ST7789 display");
-
+    #[cfg(feature = "board-t-embed")]
     let input = RotaryEncoderInput::new(peripherals.encoder_pin_a, peripherals.encoder_pin_b, peripherals.encoder_button)?;
 
+    #[cfg(feature = "board-huzzah32")]
+    let display = Ssd1306Surface::new(peripherals.oled_i2c, peripherals.oled_sda, peripherals.oled_scl)?;
+    #[cfg(feature = "board-huzzah32")]
+    let input = ButtonInput::new(peripherals.button_a, peripherals.button_b, peripherals.button_c)?;
+
     let nvs_partition = EspDefaultNvsPartition::take()?;
     let storage = NvsStorage::new(nvs_partition)?;
 
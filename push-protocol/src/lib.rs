@@ -20,7 +20,7 @@ use uuid::Uuid;
 /// crosses the network/disk boundary, `VaultItem` is what the render layer
 /// consumes. The `From<Credential> for VaultItem` conversion lives in
 /// `emulator::credentials`, the boundary between the two.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Credential {
     pub id: Uuid,
     pub name: String,           // "GitHub"
@@ -25,13 +25,26 @@
 //! Unlocked
 //!   --lock------------------------------------> LoggedOut
 //!   --logout----------------------------------> LoggedOut
+//!   --vault timeout expires------------------> Locked
+//!
+//! Locked
+//!   --login / login-apikey-------------------> (same as from LoggedOut)
+//!   --logout----------------------------------> LoggedOut
 //!
 //! (any state) --logout-----------------------> LoggedOut (idempotent, always succeeds)
 //! ```
 //!
-//! `Session::Locked` (see `crate::state`) is never produced by this state
-//! machine -- see that module's doc comment for why `lock` goes straight to
-//! `LoggedOut` instead.
+//! `Session::Locked` is only ever produced by the vault timeout (see
+//! `crate::vault_timeout`), never by a handler in this module -- see
+//! `crate::state::Session` for why an explicit `lock` goes straight to
+//! `LoggedOut` instead. `GET`/`PUT /api/auth/timeout` read and change the
+//! timeout policy; `GET /api/auth/status` reports the time remaining while
+//! unlocked.
+//!
+//! Every transition into `Unlocked` goes through `unlocked_session`, which
+//! also starts the session's notifications-hub listener (see
+//! `crate::notifications`). A `LogOut` notification from the server moves
+//! `Unlocked` to `LoggedOut` from that listener, outside any handler here.
 //!
 //! ## Design decisions worth flagging for future beads (eml.4/eml.7)
 //!
@@ -78,7 +91,14 @@ use bitwarden_core::auth::login::{
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroizing;
 
-use crate::state::{build_client, AppState, PendingTwoFactorLogin, Session};
+use bitwarden_core::Client;
+
+use crate::environment::{ServerEnvironment, ServerSelection};
+use crate::notifications::spawn_hub_listener;
+use crate::state::{
+    build_client, AppState, PendingTwoFactorLogin, Session, SessionTokenHandler, UnlockedSession,
+};
+use crate::vault_timeout::{VaultTimeoutPolicy, VaultTimeoutStatus};
 
 // ---------------------------------------------------------------------
 // Wire DTOs
@@ -95,6 +115,10 @@ use crate::state::{build_client, AppState, PendingTwoFactorLogin, Session};
 pub struct LoginRequest {
     pub email: String,
     pub master_password: String,
+    /// Which Bitwarden server to log in to; omitted = US cloud. See
+    /// `crate::environment`.
+    #[serde(default)]
+    pub server: ServerSelection,
 }
 
 #[derive(Deserialize)]
@@ -114,17 +138,26 @@ pub struct ApiKeyLoginBody {
     /// `LoginRequest` -- this is our own wire contract with the browser,
     /// independent of the SDK's internal field naming.
     pub master_password: String,
+    /// Same as `LoginRequest::server`.
+    #[serde(default)]
+    pub server: ServerSelection,
 }
 
 /// `GET /api/auth/status` response shape. Deliberately does not re-serve
 /// the two-factor provider list on repeated polls -- see module docs.
+/// `Unlocked` carries the vault timeout policy and time remaining (see
+/// `crate::vault_timeout::VaultTimeoutStatus`) and the server the session
+/// is logged in to (non-secret, see `crate::environment`).
 #[derive(Serialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum AuthStatus {
     LoggedOut,
     TwoFactorRequired,
     Locked,
-    Unlocked,
+    Unlocked {
+        vault_timeout: VaultTimeoutStatus,
+        server: ServerEnvironment,
+    },
 }
 
 /// `POST /api/auth/login` / `POST /api/auth/2fa` success-path response
@@ -185,11 +218,31 @@ pub async fn status(State(state): State<AppState>) -> Json<AuthStatus> {
     Json(match &*session {
         Session::LoggedOut => AuthStatus::LoggedOut,
         Session::PendingTwoFactor(_) => AuthStatus::TwoFactorRequired,
-        Session::Locked(_) => AuthStatus::Locked,
-        Session::Unlocked(_) => AuthStatus::Unlocked,
+        Session::Locked => AuthStatus::Locked,
+        Session::Unlocked(unlocked) => AuthStatus::Unlocked {
+            vault_timeout: state.vault_timeout.status(),
+            server: unlocked.environment.clone(),
+        },
     })
 }
 
+/// Builds the `Unlocked` session for a successful login and starts its
+/// notifications-hub listener (see `crate::notifications`).
+fn unlocked_session(
+    state: &AppState,
+    client: Client,
+    tokens: SessionTokenHandler,
+    environment: ServerEnvironment,
+) -> UnlockedSession {
+    let hub = spawn_hub_listener(state, environment.notifications_url.as_deref(), &tokens);
+    UnlockedSession {
+        client,
+        environment,
+        tokens,
+        hub,
+    }
+}
+
 /// `POST /api/auth/login` -- `{ email, master_password }`.
 pub async fn login(State(state): State<AppState>, Json(body): Json<LoginRequest>) -> Response {
     if body.email.is_empty() || body.master_password.is_empty() {
@@ -198,6 +251,10 @@ pub async fn login(State(state): State<AppState>, Json(body): Json<LoginRequest>
             "email and master_password are required",
         );
     }
+    let environment = match body.server.resolve() {
+        Ok(environment) => environment,
+        Err(err) => return error_response(StatusCode::BAD_REQUEST, err.message()),
+    };
 
     // Locked for the whole handler -- see module docs "Design decisions".
     let mut session = state.session.lock().await;
@@ -209,10 +266,10 @@ pub async fn login(State(state): State<AppState>, Json(body): Json<LoginRequest>
                 "two-factor login already in progress",
             )
         }
-        Session::LoggedOut | Session::Locked(_) => {}
+        Session::LoggedOut | Session::Locked => {}
     }
 
-    let client = build_client();
+    let (client, tokens) = build_client(&environment);
     let request = PasswordLoginRequest {
         email: body.email,
         password: body.master_password,
@@ -223,7 +280,7 @@ pub async fn login(State(state): State<AppState>, Json(body): Json<LoginRequest>
     match result {
         Ok(response) => match classify_password_login(response) {
             LoginOutcome::Unlocked => {
-                *session = Session::Unlocked(client);
+                *session = Session::Unlocked(unlocked_session(&state, client, tokens, environment));
                 Json(LoginResult::Unlocked).into_response()
             }
             LoginOutcome::TwoFactorRequired(providers) => {
@@ -234,6 +291,7 @@ pub async fn login(State(state): State<AppState>, Json(body): Json<LoginRequest>
                 *session = Session::PendingTwoFactor(PendingTwoFactorLogin {
                     email,
                     master_password: Zeroizing::new(password),
+                    environment,
                 });
                 Json(LoginResult::TwoFactorRequired { providers }).into_response()
             }
@@ -258,15 +316,16 @@ pub async fn two_factor(
     Json(body): Json<TwoFactorLoginRequest>,
 ) -> Response {
     let mut session = state.session.lock().await;
-    let (email, password) = match &*session {
+    let (email, password, environment) = match &*session {
         Session::PendingTwoFactor(pending) => (
             pending.email.clone(),
             pending.master_password.as_str().to_owned(),
+            pending.environment.clone(),
         ),
         _ => return error_response(StatusCode::BAD_REQUEST, "no pending two-factor login"),
     };
 
-    let client = build_client();
+    let (client, tokens) = build_client(&environment);
     let request = PasswordLoginRequest {
         email,
         password,
@@ -284,7 +343,7 @@ pub async fn two_factor(
                 // Overwriting `*session` drops the old `PendingTwoFactor`,
                 // zeroizing the stashed password (see `Zeroizing` in
                 // `crate::state`).
-                *session = Session::Unlocked(client);
+                *session = Session::Unlocked(unlocked_session(&state, client, tokens, environment));
                 Json(LoginResult::Unlocked).into_response()
             }
             LoginOutcome::TwoFactorRequired(_) | LoginOutcome::AuthenticationFailed => {
@@ -309,13 +368,15 @@ pub async fn lock(State(state): State<AppState>) -> Response {
     match &*session {
         Session::Unlocked(_) => {
             // Dropping the old value here drops the `Client`, releasing
-            // our only reference to its internal key material. Also clear
+            // our only reference to its internal key material, and aborts
+            // the session's hub listener (`HubListener`). Also clear
             // the server-side decrypted vault (eml.4) -- it must not
             // outlive the session that decrypted it. Cleared while still
             // holding the session lock so a concurrent `/api/vault/*`
             // request can't observe a locked session with a stale
             // credential set.
             state.vault_credentials.clear().await;
+            state.device_selections.clear().await;
             *session = Session::LoggedOut;
             Json(AuthStatus::LoggedOut).into_response()
         }
@@ -323,9 +384,7 @@ pub async fn lock(State(state): State<AppState>) -> Response {
         Session::PendingTwoFactor(_) => {
             error_response(StatusCode::CONFLICT, "not logged in (two-factor pending)")
         }
-        Session::Locked(_) => {
-            unreachable!("Session::Locked is never constructed by this server; see crate::state")
-        }
+        Session::Locked => error_response(StatusCode::CONFLICT, "already locked"),
     }
 }
 
@@ -336,6 +395,7 @@ pub async fn lock(State(state): State<AppState>) -> Response {
 pub async fn logout(State(state): State<AppState>) -> Response {
     let mut session = state.session.lock().await;
     state.vault_credentials.clear().await;
+    state.device_selections.clear().await;
     *session = Session::LoggedOut;
     Json(AuthStatus::LoggedOut).into_response()
 }
@@ -355,6 +415,10 @@ pub async fn login_apikey(
             "client_id, client_secret, and master_password are required",
         );
     }
+    let environment = match body.server.resolve() {
+        Ok(environment) => environment,
+        Err(err) => return error_response(StatusCode::BAD_REQUEST, err.message()),
+    };
 
     let mut session = state.session.lock().await;
     match &*session {
@@ -365,10 +429,10 @@ pub async fn login_apikey(
                 "two-factor login already in progress",
             )
         }
-        Session::LoggedOut | Session::Locked(_) => {}
+        Session::LoggedOut | Session::Locked => {}
     }
 
-    let client = build_client();
+    let (client, tokens) = build_client(&environment);
     let request = ApiKeyLoginRequest {
         client_id: body.client_id,
         client_secret: body.client_secret,
@@ -378,8 +442,8 @@ pub async fn login_apikey(
 
     match result {
         Ok(response) if response.authenticated => {
-            *session = Session::Unlocked(client);
-            Json(AuthStatus::Unlocked).into_response()
+            *session = Session::Unlocked(unlocked_session(&state, client, tokens, environment));
+            Json(LoginResult::Unlocked).into_response()
         }
         Ok(_) => error_response(StatusCode::UNAUTHORIZED, "authentication failed"),
         Err(err) => {
@@ -389,6 +453,28 @@ pub async fn login_apikey(
     }
 }
 
+/// `GET /api/auth/timeout` -- the current vault timeout policy, in any
+/// session state (it is a setting, not session data).
+pub async fn get_timeout(State(state): State<AppState>) -> Json<VaultTimeoutPolicy> {
+    Json(state.vault_timeout.policy())
+}
+
+/// `PUT /api/auth/timeout` -- `{"policy":"minutes","minutes":N}`,
+/// `{"policy":"on_sleep"}` or `{"policy":"never"}`. Held in memory only,
+/// like everything else in `AppState`; a restart falls back to
+/// `VaultTimeoutPolicy::default()`. `minutes: 0` is rejected rather than
+/// meaning "lock immediately" -- that would make the vault unusable.
+pub async fn set_timeout(
+    State(state): State<AppState>,
+    Json(policy): Json<VaultTimeoutPolicy>,
+) -> Response {
+    if policy == (VaultTimeoutPolicy::Minutes { minutes: 0 }) {
+        return error_response(StatusCode::BAD_REQUEST, "minutes must be at least 1");
+    }
+    state.vault_timeout.set_policy(policy);
+    Json(policy).into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -455,6 +541,19 @@ mod tests {
         let parsed: LoginRequest = serde_json::from_str(json).unwrap();
         assert_eq!(parsed.email, "a@example.com");
         assert_eq!(parsed.master_password, "hunter2");
+        assert_eq!(parsed.server, ServerSelection::Us);
+    }
+
+    #[test]
+    fn login_request_accepts_server_selection() {
+        let json = r#"{"email":"a@example.com","master_password":"x","server":{"type":"self_hosted","base_url":"https://vault.example.com"}}"#;
+        let parsed: LoginRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            parsed.server,
+            ServerSelection::SelfHosted {
+                base_url: "https://vault.example.com".to_string()
+            }
+        );
     }
 
     #[test]
@@ -486,6 +585,22 @@ mod tests {
         assert_eq!(json, r#"{"status":"logged_out"}"#);
     }
 
+    #[test]
+    fn unlocked_auth_status_carries_vault_timeout() {
+        let status = AuthStatus::Unlocked {
+            vault_timeout: VaultTimeoutStatus {
+                policy: VaultTimeoutPolicy::Minutes { minutes: 15 },
+                remaining_seconds: Some(600),
+            },
+            server: ServerEnvironment::eu(),
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["status"], "unlocked");
+        assert_eq!(json["vault_timeout"]["policy"], "minutes");
+        assert_eq!(json["vault_timeout"]["remaining_seconds"], 600);
+        assert_eq!(json["server"]["api_url"], "https://api.bitwarden.eu");
+    }
+
     #[test]
     fn login_result_serializes_two_factor_required_with_providers() {
         let result = LoginResult::TwoFactorRequired {
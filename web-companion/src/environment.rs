@@ -0,0 +1,310 @@
+//! Which Bitwarden server a login talks to: US cloud, EU cloud, a
+//! self-hosted install (Bitwarden or Vaultwarden) by base URL, or fully
+//! explicit identity/api/notifications URLs.
+//!
+//! Before this module `crate::state::build_client` hardcoded
+//! `identity.bitwarden.com`/`api.bitwarden.com`, so anyone on the EU cloud
+//! or a self-hosted server could not log in at all.
+//!
+//! ## Wire shape
+//!
+//! `ServerSelection` is an optional `server` field on `POST
+//! /api/auth/login` and `POST /api/auth/login-apikey` (omitted = US cloud,
+//! the pre-existing behavior):
+//!
+//! ```text
+//! {"type":"us"}
+//! {"type":"eu"}
+//! {"type":"self_hosted","base_url":"https://vault.example.com"}
+//! {"type":"custom","identity_url":"...","api_url":"...","notifications_url":"..."}
+//! ```
+//!
+//! `custom`'s `notifications_url` is optional; a `self_hosted` base URL
+//! derives all three the same way the official clients do (`<base>/identity`,
+//! `<base>/api`, `<base>/notifications`).
+//!
+//! ## Validation
+//!
+//! Every URL must parse, use `https` (or `http` to a loopback host only --
+//! a Vaultwarden on `localhost`, or a test's mock server), and carry no
+//! userinfo, query or fragment. A plain-`http` remote host is refused
+//! rather than sending a master-password-derived hash over cleartext.
+//! Validation happens before any `Client` is built, so a bad URL is a clean
+//! `400` with no network traffic.
+//!
+//! ## Per-session storage
+//!
+//! The resolved `ServerEnvironment` is stored in
+//! `crate::state::PendingTwoFactorLogin` (so the 2FA retry talks to the
+//! same server as the first attempt) and `crate::state::UnlockedSession`
+//! (reported by `GET /api/auth/status`). It is never persisted.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Browser-supplied server choice. See module docs for the wire shape.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ServerSelection {
+    #[default]
+    Us,
+    Eu,
+    SelfHosted {
+        base_url: String,
+    },
+    Custom {
+        identity_url: String,
+        api_url: String,
+        notifications_url: Option<String>,
+    },
+}
+
+/// A validated, fully-resolved set of endpoints. Non-secret: safe to
+/// return to the browser.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ServerEnvironment {
+    pub identity_url: String,
+    pub api_url: String,
+    /// `None` only for a `custom` selection that didn't supply one.
+    pub notifications_url: Option<String>,
+}
+
+impl ServerEnvironment {
+    #[must_use]
+    pub fn us() -> Self {
+        Self {
+            identity_url: "https://identity.bitwarden.com".to_string(),
+            api_url: "https://api.bitwarden.com".to_string(),
+            notifications_url: Some("https://notifications.bitwarden.com".to_string()),
+        }
+    }
+
+    #[must_use]
+    pub fn eu() -> Self {
+        Self {
+            identity_url: "https://identity.bitwarden.eu".to_string(),
+            api_url: "https://api.bitwarden.eu".to_string(),
+            notifications_url: Some("https://notifications.bitwarden.eu".to_string()),
+        }
+    }
+}
+
+impl Default for ServerEnvironment {
+    fn default() -> Self {
+        Self::us()
+    }
+}
+
+/// Why a `ServerSelection` was rejected. Messages are `'static` and safe to
+/// forward to the browser verbatim (they describe the caller's own input,
+/// nothing server-side).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvironmentError {
+    InvalidUrl,
+    InsecureScheme,
+    UnexpectedUrlParts,
+}
+
+impl EnvironmentError {
+    #[must_use]
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::InvalidUrl => "server URL is not a valid absolute URL",
+            Self::InsecureScheme => "server URL must use https (http is only allowed for localhost)",
+            Self::UnexpectedUrlParts => {
+                "server URL must not contain credentials, a query string, or a fragment"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for EnvironmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for EnvironmentError {}
+
+fn is_loopback_host(url: &Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(addr)) => addr.is_loopback(),
+        Some(url::Host::Ipv6(addr)) => addr.is_loopback(),
+        None => false,
+    }
+}
+
+/// Validates one URL (see module docs "Validation") and returns it
+/// normalized without a trailing slash, so `<base>/identity` never turns
+/// into `<base>//identity`.
+fn validate_url(raw: &str) -> Result<String, EnvironmentError> {
+    let url = Url::parse(raw.trim()).map_err(|_| EnvironmentError::InvalidUrl)?;
+    if url.host().is_none() {
+        return Err(EnvironmentError::InvalidUrl);
+    }
+    match url.scheme() {
+        "https" => {}
+        "http" if is_loopback_host(&url) => {}
+        _ => return Err(EnvironmentError::InsecureScheme),
+    }
+    if !url.username().is_empty()
+        || url.password().is_some()
+        || url.query().is_some()
+        || url.fragment().is_some()
+    {
+        return Err(EnvironmentError::UnexpectedUrlParts);
+    }
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+impl ServerSelection {
+    /// Validates and resolves this selection into concrete endpoints.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EnvironmentError` if any supplied URL fails validation
+    /// (see module docs).
+    pub fn resolve(&self) -> Result<ServerEnvironment, EnvironmentError> {
+        match self {
+            Self::Us => Ok(ServerEnvironment::us()),
+            Self::Eu => Ok(ServerEnvironment::eu()),
+            Self::SelfHosted { base_url } => {
+                let base = validate_url(base_url)?;
+                Ok(ServerEnvironment {
+                    identity_url: format!("{base}/identity"),
+                    api_url: format!("{base}/api"),
+                    notifications_url: Some(format!("{base}/notifications")),
+                })
+            }
+            Self::Custom {
+                identity_url,
+                api_url,
+                notifications_url,
+            } => Ok(ServerEnvironment {
+                identity_url: validate_url(identity_url)?,
+                api_url: validate_url(api_url)?,
+                notifications_url: notifications_url.as_deref().map(validate_url).transpose()?,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn self_hosted(base_url: &str) -> ServerSelection {
+        ServerSelection::SelfHosted {
+            base_url: base_url.to_string(),
+        }
+    }
+
+    #[test]
+    fn default_selection_is_us_cloud() {
+        assert_eq!(
+            ServerSelection::default().resolve().unwrap(),
+            ServerEnvironment::us()
+        );
+    }
+
+    #[test]
+    fn eu_selection_uses_bitwarden_eu_hosts() {
+        let env = ServerSelection::Eu.resolve().unwrap();
+        assert_eq!(env.identity_url, "https://identity.bitwarden.eu");
+        assert_eq!(env.api_url, "https://api.bitwarden.eu");
+    }
+
+    #[test]
+    fn self_hosted_base_derives_all_three_endpoints() {
+        let env = self_hosted("https://vault.example.com/").resolve().unwrap();
+        assert_eq!(env.identity_url, "https://vault.example.com/identity");
+        assert_eq!(env.api_url, "https://vault.example.com/api");
+        assert_eq!(
+            env.notifications_url.as_deref(),
+            Some("https://vault.example.com/notifications")
+        );
+    }
+
+    #[test]
+    fn self_hosted_base_keeps_a_subpath() {
+        let env = self_hosted("https://example.com/vault").resolve().unwrap();
+        assert_eq!(env.api_url, "https://example.com/vault/api");
+    }
+
+    #[test]
+    fn plain_http_is_only_allowed_for_loopback() {
+        assert!(self_hosted("http://localhost:8000").resolve().is_ok());
+        assert!(self_hosted("http://127.0.0.1:8000").resolve().is_ok());
+        assert!(self_hosted("http://[::1]:8000").resolve().is_ok());
+        assert_eq!(
+            self_hosted("http://vault.example.com").resolve(),
+            Err(EnvironmentError::InsecureScheme)
+        );
+    }
+
+    #[test]
+    fn non_http_schemes_are_rejected() {
+        assert_eq!(
+            self_hosted("ftp://vault.example.com").resolve(),
+            Err(EnvironmentError::InsecureScheme)
+        );
+    }
+
+    #[test]
+    fn relative_or_garbage_urls_are_rejected() {
+        assert_eq!(
+            self_hosted("vault.example.com").resolve(),
+            Err(EnvironmentError::InvalidUrl)
+        );
+        assert_eq!(self_hosted("").resolve(), Err(EnvironmentError::InvalidUrl));
+    }
+
+    #[test]
+    fn credentials_query_and_fragment_are_rejected() {
+        for url in [
+            "https://user:pw@vault.example.com",
+            "https://vault.example.com/?x=1",
+            "https://vault.example.com/#frag",
+        ] {
+            assert_eq!(
+                self_hosted(url).resolve(),
+                Err(EnvironmentError::UnexpectedUrlParts),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn custom_selection_validates_every_url() {
+        let selection = ServerSelection::Custom {
+            identity_url: "https://id.example.com".to_string(),
+            api_url: "http://api.example.com".to_string(),
+            notifications_url: None,
+        };
+        assert_eq!(selection.resolve(), Err(EnvironmentError::InsecureScheme));
+    }
+
+    #[test]
+    fn custom_selection_without_notifications_url_resolves() {
+        let selection = ServerSelection::Custom {
+            identity_url: "https://id.example.com".to_string(),
+            api_url: "https://api.example.com".to_string(),
+            notifications_url: None,
+        };
+        let env = selection.resolve().unwrap();
+        assert_eq!(env.identity_url, "https://id.example.com");
+        assert_eq!(env.notifications_url, None);
+    }
+
+    #[test]
+    fn selection_deserializes_tagged_json() {
+        let parsed: ServerSelection =
+            serde_json::from_str(r#"{"type":"self_hosted","base_url":"https://v.example"}"#)
+                .unwrap();
+        assert_eq!(parsed, self_hosted("https://v.example"));
+
+        let parsed: ServerSelection = serde_json::from_str(r#"{"type":"eu"}"#).unwrap();
+        assert_eq!(parsed, ServerSelection::Eu);
+    }
+}
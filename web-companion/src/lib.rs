@@ -12,19 +12,22 @@
 //! HttpEmulatorTransport` directly.
 //!
 //! `src/main.rs` is now a thin binary: it builds the initial [`state::AppState`]
-//! and calls [`build_app`]/[`emulator_url`] from here. Nothing about the
+//! and calls [`build_app`]/[`emulator_urls`] from here. Nothing about the
 //! server's behavior changes -- this is a pure code-motion refactor, not a
 //! behavior change (see the moved `tests` module below, unchanged from its
 //! original form in `main.rs`, all still passing).
 
 pub mod auth;
 pub mod auth_routes;
+pub mod environment;
+pub mod notifications;
 pub mod routes;
 pub mod state;
 pub mod transport;
 pub mod transport_routes;
 pub mod vault;
 pub mod vault_routes;
+pub mod vault_timeout;
 
 use std::env;
 
@@ -40,14 +43,27 @@ use auth::require_bearer_token;
 use routes::{healthz, serve_index, static_dir};
 use state::AppState;
 use transport::DEFAULT_EMULATOR_URL;
+use vault_timeout::enforce_vault_timeout;
 
-/// Resolves the desktop emulator's base URL for the sole Phase-1
-/// `DeviceTransport`: the `EMULATOR_URL` env var if set, else
+/// Resolves the desktop emulators' base URLs for the Phase-1
+/// `DeviceTransport`: the `EMULATOR_URL` env var if set -- one URL, or
+/// several comma-separated for emulators running side by side -- else
 /// `transport::DEFAULT_EMULATOR_URL`. Resolved once, here, rather than
 /// inside `transport.rs` itself -- see that module's doc comment.
 #[must_use]
-pub fn emulator_url() -> String {
-    env::var("EMULATOR_URL").unwrap_or_else(|_| DEFAULT_EMULATOR_URL.to_string())
+pub fn emulator_urls() -> Vec<String> {
+    let urls: Vec<String> = env::var("EMULATOR_URL")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+        .collect();
+    if urls.is_empty() {
+        vec![DEFAULT_EMULATOR_URL.to_string()]
+    } else {
+        urls
+    }
 }
 
 /// Builds the axum `Router`. Split out from `main` so tests (see the
@@ -62,15 +78,28 @@ pub fn build_app(state: AppState) -> Router {
         .route("/auth/2fa", post(auth_routes::two_factor))
         .route("/auth/lock", post(auth_routes::lock))
         .route("/auth/logout", post(auth_routes::logout))
+        .route(
+            "/auth/timeout",
+            get(auth_routes::get_timeout).put(auth_routes::set_timeout),
+        )
         .route("/vault/sync", post(vault_routes::sync))
         .route("/vault/list", get(vault_routes::list))
         .route("/vault/status", get(vault_routes::status))
         .route("/devices", get(transport_routes::list_devices))
         .route("/sync", post(transport_routes::sync))
-        .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
-            state.clone(),
-            require_bearer_token,
-        )));
+        // Bearer token first (outermost), so unauthenticated requests never
+        // count as vault-timeout activity -- see `crate::vault_timeout`.
+        .layer(
+            ServiceBuilder::new()
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    require_bearer_token,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    enforce_vault_timeout,
+                )),
+        );
 
     Router::new()
         .route("/healthz", get(healthz))
@@ -84,6 +113,7 @@ pub fn build_app(state: AppState) -> Router {
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
     use axum::{
         body::Body,
@@ -94,16 +124,24 @@ mod tests {
     use tower::ServiceExt;
 
     use super::*;
+    use environment::ServerEnvironment;
     use state::{Session, TransportRegistry};
+    use vault_timeout::{ManualClock, VaultTimeout, VaultTimeoutPolicy};
 
     const TEST_TOKEN: &str = "test-token-do-not-use-in-prod";
 
     fn test_state() -> AppState {
+        test_state_with_clock(Arc::new(ManualClock::new()))
+    }
+
+    fn test_state_with_clock(clock: Arc<ManualClock>) -> AppState {
         AppState {
             session: Arc::new(Mutex::new(Session::LoggedOut)),
             transports: TransportRegistry::with_emulator(DEFAULT_EMULATOR_URL.to_string()),
             api_token: TEST_TOKEN.to_string(),
             vault_credentials: state::VaultCredentialStore::default(),
+            vault_timeout: VaultTimeout::new(clock, VaultTimeoutPolicy::default()),
+            device_selections: state::DeviceSelections::default(),
         }
     }
 
@@ -234,6 +272,19 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn login_with_insecure_server_url_is_bad_request_without_network() {
+        let response = authed_request(
+            "POST",
+            "/api/auth/login",
+            Some(r#"{"email":"a@b.com","master_password":"x","server":{"type":"self_hosted","base_url":"http://vault.example.com"}}"#),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_json(response).await;
+        assert!(body["error"].as_str().unwrap().contains("https"));
+    }
+
     #[tokio::test]
     async fn two_factor_without_pending_login_is_bad_request() {
         let response = authed_request(
@@ -359,6 +410,173 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    /// An `Unlocked` state with one retained credential, driven by a
+    /// `ManualClock`. `build_client` makes no network calls (see its doc
+    /// comment), so this needs no real login.
+    async fn unlocked_state_with_clock() -> (Arc<ManualClock>, AppState) {
+        let clock = Arc::new(ManualClock::new());
+        let state = test_state_with_clock(clock.clone());
+        let (client, tokens) = state::build_client(&ServerEnvironment::us());
+        *state.session.lock().await = Session::Unlocked(state::UnlockedSession {
+            client,
+            environment: ServerEnvironment::us(),
+            tokens,
+            hub: None,
+        });
+        state
+            .vault_credentials
+            .replace(vec![push_protocol::Credential {
+                id: uuid::Uuid::new_v4(),
+                name: "GitHub".to_string(),
+                username: "octocat".to_string(),
+                password: "hunter2".to_string(),
+                uri: None,
+                notes: None,
+            }])
+            .await;
+        (clock, state)
+    }
+
+    async fn authed_request_to(
+        state: &AppState,
+        method: &str,
+        path: &str,
+        json_body: Option<&str>,
+    ) -> axum::http::Response<Body> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("Authorization", format!("Bearer {TEST_TOKEN}"));
+        let body = if let Some(json) = json_body {
+            builder = builder.header("Content-Type", "application/json");
+            Body::from(json.to_string())
+        } else {
+            Body::empty()
+        };
+        build_app(state.clone())
+            .oneshot(builder.body(body).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn auth_status_reports_vault_timeout_remaining_while_unlocked() {
+        let (clock, state) = unlocked_state_with_clock().await;
+        clock.advance(Duration::from_secs(5 * 60));
+
+        let response = authed_request_to(&state, "GET", "/api/auth/status", None).await;
+        let body = body_json(response).await;
+
+        assert_eq!(body["status"], "unlocked");
+        assert_eq!(body["vault_timeout"]["policy"], "minutes");
+        assert_eq!(body["vault_timeout"]["remaining_seconds"], 10 * 60);
+    }
+
+    #[tokio::test]
+    async fn vault_timeout_locks_session_and_clears_store_on_next_request() {
+        let (clock, state) = unlocked_state_with_clock().await;
+        clock.advance(Duration::from_secs(15 * 60));
+
+        let response = authed_request_to(&state, "GET", "/api/vault/list", None).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        assert!(state.vault_credentials.get_all().await.is_empty());
+        let response = authed_request_to(&state, "GET", "/api/auth/status", None).await;
+        assert_eq!(body_json(response).await["status"], "locked");
+    }
+
+    #[tokio::test]
+    async fn status_polling_does_not_keep_vault_unlocked() {
+        let (clock, state) = unlocked_state_with_clock().await;
+
+        for _ in 0..15 {
+            clock.advance(Duration::from_secs(60));
+            authed_request_to(&state, "GET", "/api/auth/status", None).await;
+        }
+
+        assert!(matches!(*state.session.lock().await, Session::Locked));
+        assert!(state.vault_credentials.get_all().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn api_activity_keeps_vault_unlocked() {
+        let (clock, state) = unlocked_state_with_clock().await;
+
+        for _ in 0..30 {
+            clock.advance(Duration::from_secs(60));
+            let response = authed_request_to(&state, "GET", "/api/vault/status", None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        assert_eq!(state.vault_credentials.get_all().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_bearer_token_is_not_activity() {
+        let (clock, state) = unlocked_state_with_clock().await;
+        clock.advance(Duration::from_secs(14 * 60));
+
+        build_app(state.clone())
+            .oneshot(Request::get("/api/vault/status").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        clock.advance(Duration::from_secs(60));
+
+        assert!(vault_timeout::lock_if_expired(&state).await);
+    }
+
+    #[tokio::test]
+    async fn lock_from_locked_session_is_conflict() {
+        let (clock, state) = unlocked_state_with_clock().await;
+        clock.advance(Duration::from_secs(15 * 60));
+        assert!(vault_timeout::lock_if_expired(&state).await);
+
+        let response = authed_request_to(&state, "POST", "/api/auth/lock", None).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn timeout_policy_can_be_read_and_changed() {
+        let (clock, state) = unlocked_state_with_clock().await;
+
+        let response = authed_request_to(
+            &state,
+            "PUT",
+            "/api/auth/timeout",
+            Some(r#"{"policy":"never"}"#),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = authed_request_to(&state, "GET", "/api/auth/timeout", None).await;
+        assert_eq!(body_json(response).await["policy"], "never");
+
+        clock.advance(Duration::from_secs(24 * 60 * 60));
+        assert!(!vault_timeout::lock_if_expired(&state).await);
+    }
+
+    #[tokio::test]
+    async fn timeout_policy_of_zero_minutes_is_bad_request() {
+        let response = authed_request(
+            "PUT",
+            "/api/auth/timeout",
+            Some(r#"{"policy":"minutes","minutes":0}"#),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn timeout_policy_unknown_variant_is_client_error() {
+        let response = authed_request(
+            "PUT",
+            "/api/auth/timeout",
+            Some(r#"{"policy":"on_restart"}"#),
+        )
+        .await;
+        assert!(response.status().is_client_error());
+    }
+
     /// Exercises the REAL SDK login error path against the real Bitwarden
     /// identity server with bogus credentials. Proves `LoginError` maps to
     /// a clean 401 (not a 500/panic) end-to-end. Makes an outbound network
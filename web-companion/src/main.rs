@@ -11,7 +11,7 @@
 //! and `crate::` docs on each module for the pieces built on top of it.
 //!
 //! This binary is now a thin entry point: `web_companion` (this crate's own
-//! library target, `src/lib.rs`) owns `build_app`/`emulator_url` and every
+//! library target, `src/lib.rs`) owns `build_app`/`emulator_urls` and every
 //! route module, so a Phase-1 integration test
 //! (`tests/emulator_integration.rs`, ai-bitwarden-hw-key-eml.7) can reach
 //! `web_companion::transport::HttpEmulatorTransport` directly -- the exact
@@ -23,18 +23,27 @@ use std::sync::Arc;
 use tokio::{net::TcpListener, sync::Mutex};
 
 use web_companion::auth::generate_api_token;
-use web_companion::state::{AppState, Session, TransportRegistry, VaultCredentialStore};
-use web_companion::{build_app, emulator_url};
+use web_companion::state::{
+    AppState, DeviceSelections, Session, TransportRegistry, VaultCredentialStore,
+};
+use web_companion::vault_timeout::{run_idle_watchdog, SystemClock, VaultTimeout, VaultTimeoutPolicy};
+use web_companion::{build_app, emulator_urls};
 
 #[tokio::main]
 async fn main() {
     let state = AppState {
         session: Arc::new(Mutex::new(Session::LoggedOut)),
-        transports: TransportRegistry::with_emulator(emulator_url()),
+        transports: TransportRegistry::with_emulators(emulator_urls()),
         api_token: generate_api_token(),
         vault_credentials: VaultCredentialStore::default(),
+        vault_timeout: VaultTimeout::new(Arc::new(SystemClock), VaultTimeoutPolicy::default()),
+        device_selections: DeviceSelections::default(),
     };
 
+    // Locks the vault on inactivity even when no request arrives to
+    // trigger the middleware check -- see `web_companion::vault_timeout`.
+    tokio::spawn(run_idle_watchdog(state.clone()));
+
     let app = build_app(state);
 
     // Loopback ONLY -- never 0.0.0.0. See module docs.
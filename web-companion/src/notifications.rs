@@ -0,0 +1,955 @@
+//! Bitwarden notifications hub listener: pushes web-vault edits to
+//! connected devices within seconds instead of whenever the browser next
+//! runs `POST /api/vault/sync`.
+//!
+//! While a session is `Unlocked` (and its `ServerEnvironment` has a
+//! `notifications_url`), `run_hub_listener` keeps a WebSocket open to
+//! `<notifications_url>/hub` -- the same ASP.NET Core SignalR hub the
+//! official clients use -- and reacts to the server's `ReceiveMessage`
+//! invocations. The task is owned by `crate::state::UnlockedSession::hub`
+//! (`HubListener` aborts it on drop), so lock, logout and the vault
+//! timeout all stop it simply by replacing the session.
+//!
+//! ## Wire protocol
+//!
+//! SignalR's JSON hub protocol over a text WebSocket:
+//!
+//! ```text
+//! connect   wss://notifications.bitwarden.com/hub?access_token=<bearer>
+//! client -> {"protocol":"json","version":1}<RS>
+//! server -> {}<RS>                       (or {"error":"..."}<RS>)
+//! server -> {"type":1,"target":"ReceiveMessage","arguments":[{"Type":0,"Payload":{"Id":"..."},...}]}<RS>
+//! either -> {"type":6}<RS>               (ping, every KEEPALIVE_INTERVAL)
+//! server -> {"type":7,"error":"..."}<RS> (close)
+//! ```
+//!
+//! `<RS>` is the ASCII record separator (`0x1e`); one WebSocket frame may
+//! carry several records. The access token goes in the query string
+//! because browsers can't set headers on a WebSocket upgrade and the hub
+//! accepts it there for every client -- it is never logged (see
+//! `HubError`'s `Display`, which never includes the URL).
+//!
+//! Known gap: Vaultwarden's hub only speaks SignalR's MessagePack protocol
+//! and ignores the `json` handshake. Its binary frames surface as
+//! `HubError::UnsupportedProtocol` and the listener backs off, so a
+//! self-hosted Vaultwarden gets no live updates at all (the README says so
+//! up front): changes from it still need a manual `POST /api/vault/sync`.
+//!
+//! ## What a notification triggers
+//!
+//! `Payload.Type` is Bitwarden's `PushType`. Only the ones that can change
+//! a `push_protocol::Credential` matter (see `HubEvent::from_push`); folder,
+//! send and settings notifications are ignored.
+//!
+//! Events are coalesced for `COALESCE_WINDOW` into one `VaultDelta` (a bulk
+//! edit in the web vault sends one notification per item), then
+//! `apply_delta`:
+//!
+//! 1. Deletes are applied to `crate::state::VaultCredentialStore` directly
+//!    -- no network.
+//! 2. Upserts (and the whole-vault `SyncCiphers`/`SyncVault`/`SyncOrgKeys`
+//!    notifications) run `crate::vault::sync_and_decrypt`. The SDK at the
+//!    pinned rev has no single-cipher fetch that also handles org keys, so
+//!    the fetch is a full `/sync`; only the notified ids are taken from it
+//!    (`merge_delta`), so an unrelated local edit in between is not
+//!    clobbered by a notification for some other item.
+//! 3. The change actually made to the store (`diff_credentials`, old vs.
+//!    new -- a notification for an edit that doesn't touch any
+//!    `Credential` field changes nothing) is pushed to every device in
+//!    `crate::state::DeviceSelections` whose last-pushed selection it
+//!    touches. The device wire format (`push_protocol::SyncRequest`) is a
+//!    full replace, so "push the delta" means re-sending that device's own
+//!    selection, rebuilt from the updated store -- never more than it was
+//!    given before, and devices the change doesn't concern aren't woken.
+//!
+//! A `LogOut` notification (password change, "deauthorize sessions")
+//! clears the vault and moves the session to `LoggedOut`, the same as
+//! `POST /api/auth/logout`.
+//!
+//! ## Reconnects and token lifetime
+//!
+//! The hub only checks the access token at connect time. On a dropped
+//! connection `run_hub_listener` reconnects with exponential backoff
+//! (`INITIAL_BACKOFF`..`MAX_BACKOFF`), and before each reconnect it runs a
+//! whole-vault `apply_delta`: that both catches up on anything missed while
+//! disconnected and makes an authenticated SDK call, which renews an
+//! expired access token (inside `PasswordManagerTokenHandler`'s
+//! middleware) before `SessionTokenHandler::access_token` is read for the
+//! new connection.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use bitwarden_core::Client;
+use futures_util::{SinkExt, StreamExt};
+use push_protocol::{Credential, SyncRequest};
+use serde::Deserialize;
+use tokio::net::TcpStream;
+use tokio::task::AbortHandle;
+use tokio::time::{interval_at, Instant, Interval, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+use uuid::Uuid;
+
+use crate::state::{AppState, Session, SessionTokenHandler};
+use crate::transport::ensure_crypto_provider_installed;
+use crate::transport_routes::filter_credentials;
+use crate::vault::sync_and_decrypt;
+
+/// SignalR record separator -- terminates every JSON hub message.
+const RECORD_SEPARATOR: char = '\u{1e}';
+const HANDSHAKE: &str = "{\"protocol\":\"json\",\"version\":1}\u{1e}";
+const PING: &str = "{\"type\":6}\u{1e}";
+
+/// SignalR's default client keepalive. The server drops a connection it
+/// hasn't heard from in 30 s.
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// How long to keep collecting notifications into one `VaultDelta` after
+/// the first -- see module docs.
+pub const COALESCE_WINDOW: Duration = Duration::from_secs(2);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// A hub notification this crate acts on. See module docs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HubEvent {
+    /// `SyncCipherCreate` / `SyncCipherUpdate`.
+    CipherUpserted(Uuid),
+    /// `SyncLoginDelete` / `SyncCipherDelete`.
+    CipherDeleted(Uuid),
+    /// `SyncCiphers` / `SyncVault` / `SyncOrgKeys`: anything may have
+    /// changed (an import, an org key rotation...).
+    VaultChanged,
+    /// `LogOut`: the server revoked this session.
+    LoggedOut,
+}
+
+impl HubEvent {
+    /// Maps a Bitwarden `PushType` (+ the payload's cipher id, where the
+    /// type carries one) to an event, or `None` for push types that can't
+    /// affect a `Credential`.
+    #[must_use]
+    pub fn from_push(push_type: u8, id: Option<Uuid>) -> Option<Self> {
+        match push_type {
+            0 | 1 => id.map(Self::CipherUpserted),
+            2 | 9 => id.map(Self::CipherDeleted),
+            4..=6 => Some(Self::VaultChanged),
+            11 => Some(Self::LoggedOut),
+            _ => None,
+        }
+    }
+}
+
+/// One decoded SignalR record.
+#[derive(Debug, PartialEq, Eq)]
+enum HubRecord {
+    Event(HubEvent),
+    /// Server-initiated close; `Some` carries the server's reason.
+    Close(Option<String>),
+    /// Pings, completions, and notifications we don't act on.
+    Ignored,
+}
+
+#[derive(Deserialize)]
+struct RawRecord {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default)]
+    target: Option<String>,
+    #[serde(default)]
+    arguments: Vec<serde_json::Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// `ReceiveMessage`'s single argument. The cloud sends PascalCase;
+/// aliases accept camelCase too, which some self-hosted builds emit.
+#[derive(Deserialize)]
+struct PushNotification {
+    #[serde(rename = "Type", alias = "type")]
+    push_type: u8,
+    #[serde(rename = "Payload", alias = "payload", default)]
+    payload: Option<PushPayload>,
+}
+
+#[derive(Deserialize)]
+struct PushPayload {
+    #[serde(rename = "Id", alias = "id", default)]
+    id: Option<Uuid>,
+}
+
+fn parse_record(record: &str) -> Result<HubRecord, HubError> {
+    let raw: RawRecord =
+        serde_json::from_str(record).map_err(|_| HubError::Protocol("malformed hub message"))?;
+    match raw.kind {
+        1 if raw.target.as_deref() == Some("ReceiveMessage") => {
+            let Some(argument) = raw.arguments.into_iter().next() else {
+                return Ok(HubRecord::Ignored);
+            };
+            let Ok(notification) = serde_json::from_value::<PushNotification>(argument) else {
+                return Ok(HubRecord::Ignored);
+            };
+            let id = notification.payload.and_then(|payload| payload.id);
+            Ok(HubEvent::from_push(notification.push_type, id)
+                .map_or(HubRecord::Ignored, HubRecord::Event))
+        }
+        7 => Ok(HubRecord::Close(raw.error)),
+        _ => Ok(HubRecord::Ignored),
+    }
+}
+
+/// Splits one text frame into its records (see module docs "Wire
+/// protocol") and decodes each.
+fn parse_frame(frame: &str) -> Result<Vec<HubRecord>, HubError> {
+    frame
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(parse_record)
+        .collect()
+}
+
+/// Builds the hub's WebSocket URL from a `ServerEnvironment`'s
+/// `notifications_url`: `https` -> `wss` (`http` -> `ws` for a loopback
+/// server, see `crate::environment`), `/hub` appended, token in the query.
+///
+/// # Errors
+///
+/// `HubError::Protocol` if `notifications_url` isn't an http(s) URL (can't
+/// happen for a validated `ServerEnvironment`).
+pub fn hub_url(notifications_url: &str, access_token: &str) -> Result<String, HubError> {
+    let invalid = HubError::Protocol("notifications URL is not http(s)");
+    let mut url = Url::parse(notifications_url).map_err(|_| invalid.clone())?;
+    let scheme = match url.scheme() {
+        "https" => "wss",
+        "http" => "ws",
+        _ => return Err(invalid),
+    };
+    url.set_scheme(scheme).map_err(|()| invalid.clone())?;
+    let path = format!("{}/hub", url.path().trim_end_matches('/'));
+    url.set_path(&path);
+    url.query_pairs_mut()
+        .clear()
+        .append_pair("access_token", access_token);
+    Ok(url.into())
+}
+
+/// Why a hub connection failed or ended. `Display` never includes the hub
+/// URL (it carries the access token).
+#[derive(Debug, Clone)]
+pub enum HubError {
+    /// Transport-level failure (DNS, TLS, HTTP upgrade refused -- e.g. a
+    /// `401` for an expired token -- or the socket dropping).
+    WebSocket(String),
+    /// The server answered the handshake with `{"error":...}`.
+    Handshake(String),
+    /// The server sent a close record carrying an error.
+    Closed(String),
+    /// Binary frames: the server is speaking MessagePack (Vaultwarden),
+    /// see module docs "Known gap".
+    UnsupportedProtocol,
+    Protocol(&'static str),
+}
+
+impl fmt::Display for HubError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WebSocket(err) => write!(f, "websocket error: {err}"),
+            Self::Handshake(err) => write!(f, "hub rejected handshake: {err}"),
+            Self::Closed(err) => write!(f, "hub closed the connection: {err}"),
+            Self::UnsupportedProtocol => f.write_str("hub does not speak the JSON protocol"),
+            Self::Protocol(err) => f.write_str(err),
+        }
+    }
+}
+
+impl std::error::Error for HubError {}
+
+impl From<tungstenite::Error> for HubError {
+    fn from(err: tungstenite::Error) -> Self {
+        // `tungstenite::Error`'s own `Display` never echoes the request
+        // URL, only the failure (e.g. "HTTP error: 401 Unauthorized").
+        Self::WebSocket(err.to_string())
+    }
+}
+
+/// An open, handshaken hub connection.
+pub struct HubConnection {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    keepalive: Interval,
+    /// Events decoded from a frame but not yet returned by `next_event`.
+    pending: VecDeque<HubEvent>,
+}
+
+impl HubConnection {
+    /// Connects and completes the SignalR handshake.
+    ///
+    /// # Errors
+    ///
+    /// See `HubError`.
+    pub async fn connect(url: &str) -> Result<Self, HubError> {
+        Self::connect_with_keepalive(url, KEEPALIVE_INTERVAL).await
+    }
+
+    /// `connect` with a caller-chosen ping interval (tests use a short
+    /// one to observe it).
+    ///
+    /// # Errors
+    ///
+    /// See `HubError`.
+    pub async fn connect_with_keepalive(url: &str, keepalive: Duration) -> Result<Self, HubError> {
+        ensure_crypto_provider_installed();
+        let (mut socket, _response) = tokio_tungstenite::connect_async(url).await?;
+        socket.send(Message::text(HANDSHAKE)).await?;
+
+        // The handshake reply is the first record; the server may already
+        // have appended notifications after it in the same frame.
+        let reply = loop {
+            match socket.next().await {
+                Some(Ok(Message::Text(text))) => break text,
+                Some(Ok(Message::Binary(_))) => return Err(HubError::UnsupportedProtocol),
+                Some(Ok(Message::Close(_))) | None => {
+                    return Err(HubError::Protocol("hub closed during handshake"))
+                }
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err.into()),
+            }
+        };
+        let (handshake, rest) = reply
+            .as_str()
+            .split_once(RECORD_SEPARATOR)
+            .ok_or(HubError::Protocol("malformed handshake reply"))?;
+        #[derive(Deserialize)]
+        struct HandshakeReply {
+            #[serde(default)]
+            error: Option<String>,
+        }
+        let handshake: HandshakeReply = serde_json::from_str(handshake)
+            .map_err(|_| HubError::Protocol("malformed handshake reply"))?;
+        if let Some(error) = handshake.error {
+            return Err(HubError::Handshake(error));
+        }
+
+        let mut keepalive = interval_at(Instant::now() + keepalive, keepalive);
+        keepalive.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut connection = Self {
+            socket,
+            keepalive,
+            pending: VecDeque::new(),
+        };
+        connection.queue_frame(rest)?;
+        Ok(connection)
+    }
+
+    /// Decodes a frame into `pending`. `Ok(false)` = the server closed the
+    /// hub cleanly.
+    fn queue_frame(&mut self, frame: &str) -> Result<bool, HubError> {
+        for record in parse_frame(frame)? {
+            match record {
+                HubRecord::Event(event) => self.pending.push_back(event),
+                HubRecord::Close(Some(error)) => return Err(HubError::Closed(error)),
+                HubRecord::Close(None) => return Ok(false),
+                HubRecord::Ignored => {}
+            }
+        }
+        Ok(true)
+    }
+
+    /// Waits for the next `HubEvent`, sending keepalive pings meanwhile.
+    /// `Ok(None)` = the server closed the connection cleanly. Cancel-safe:
+    /// an event already decoded stays queued for the next call.
+    ///
+    /// # Errors
+    ///
+    /// See `HubError`.
+    pub async fn next_event(&mut self) -> Result<Option<HubEvent>, HubError> {
+        let mut open = true;
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            if !open {
+                return Ok(None);
+            }
+            tokio::select! {
+                _ = self.keepalive.tick() => {
+                    self.socket.send(Message::text(PING)).await?;
+                }
+                message = self.socket.next() => match message {
+                    Some(Ok(Message::Text(text))) => open = self.queue_frame(text.as_str())?,
+                    Some(Ok(Message::Binary(_))) => return Err(HubError::UnsupportedProtocol),
+                    Some(Ok(Message::Close(_))) | None => open = false,
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => return Err(err.into()),
+                },
+            }
+        }
+    }
+}
+
+/// Handle to a session's `run_hub_listener` task; aborts it on drop. See
+/// `crate::state::UnlockedSession::hub`.
+pub struct HubListener {
+    task: AbortHandle,
+}
+
+impl Drop for HubListener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Starts `run_hub_listener` for a session that just unlocked, or `None`
+/// if its server has no notifications hub (a `custom` server without a
+/// `notifications_url`, see `crate::environment`).
+#[must_use]
+pub fn spawn_hub_listener(
+    state: &AppState,
+    notifications_url: Option<&str>,
+    tokens: &SessionTokenHandler,
+) -> Option<HubListener> {
+    let notifications_url = notifications_url?.to_string();
+    let task = tokio::spawn(run_hub_listener(
+        state.clone(),
+        notifications_url,
+        tokens.clone(),
+    ));
+    Some(HubListener {
+        task: task.abort_handle(),
+    })
+}
+
+/// The set of changes accumulated from one burst of notifications (or
+/// computed by `diff_credentials`). An id is in at most one of `upserted`
+/// and `deleted` -- the later event wins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultDelta {
+    pub upserted: BTreeSet<Uuid>,
+    pub deleted: BTreeSet<Uuid>,
+    /// A whole-vault refresh was requested; `merge_delta` takes the fresh
+    /// sync as-is.
+    pub everything: bool,
+}
+
+impl VaultDelta {
+    /// Folds one event in. `LoggedOut` isn't a vault change and is ignored
+    /// here -- `follow_hub` handles it before it reaches a delta.
+    pub fn add(&mut self, event: HubEvent) {
+        match event {
+            HubEvent::CipherUpserted(id) => {
+                self.deleted.remove(&id);
+                self.upserted.insert(id);
+            }
+            HubEvent::CipherDeleted(id) => {
+                self.upserted.remove(&id);
+                self.deleted.insert(id);
+            }
+            HubEvent::VaultChanged => self.everything = true,
+            HubEvent::LoggedOut => {}
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        !self.everything && self.upserted.is_empty() && self.deleted.is_empty()
+    }
+
+    /// Whether applying this needs a fresh sync (deletes alone don't).
+    #[must_use]
+    pub fn needs_fetch(&self) -> bool {
+        self.everything || !self.upserted.is_empty()
+    }
+
+    /// Whether a device whose last push was `selection` (see
+    /// `crate::state::DeviceSelections`) holds anything this changes.
+    #[must_use]
+    pub fn touches(&self, selection: Option<&[Uuid]>) -> bool {
+        match selection {
+            _ if self.everything => true,
+            None => !self.is_empty(),
+            Some(ids) => ids
+                .iter()
+                .any(|id| self.upserted.contains(id) || self.deleted.contains(id)),
+        }
+    }
+}
+
+/// Applies `delta` to `current`, taking upserted items from `fresh` (the
+/// result of a full `sync_and_decrypt`; ignored unless
+/// `delta.needs_fetch()`). An upserted id missing from `fresh` -- moved to
+/// trash, or no longer a login -- is dropped, same as a delete. Existing
+/// items keep their position; new ones are appended in `fresh`'s order.
+#[must_use]
+pub fn merge_delta(
+    current: &[Credential],
+    fresh: &[Credential],
+    delta: &VaultDelta,
+) -> Vec<Credential> {
+    if delta.everything {
+        return fresh.to_vec();
+    }
+    let mut merged: Vec<Credential> = current
+        .iter()
+        .filter(|credential| !delta.deleted.contains(&credential.id))
+        .filter_map(|credential| {
+            if !delta.upserted.contains(&credential.id) {
+                return Some(credential.clone());
+            }
+            fresh.iter().find(|f| f.id == credential.id).cloned()
+        })
+        .collect();
+    for credential in fresh {
+        if delta.upserted.contains(&credential.id) && !current.iter().any(|c| c.id == credential.id)
+        {
+            merged.push(credential.clone());
+        }
+    }
+    merged
+}
+
+/// What actually differs between two credential sets, as a delta over ids
+/// (`everything` is never set).
+#[must_use]
+pub fn diff_credentials(old: &[Credential], new: &[Credential]) -> VaultDelta {
+    let mut delta = VaultDelta::default();
+    for credential in new {
+        if old.iter().find(|o| o.id == credential.id) != Some(credential) {
+            delta.upserted.insert(credential.id);
+        }
+    }
+    for credential in old {
+        if !new.iter().any(|n| n.id == credential.id) {
+            delta.deleted.insert(credential.id);
+        }
+    }
+    delta
+}
+
+/// Server-side diagnostic only, same posture as
+/// `vault_routes::log_vault_sync_error`.
+fn log_hub_error(context: &str, err: &dyn fmt::Display) {
+    eprintln!("web-companion: notifications hub: {context}: {err}");
+}
+
+/// Applies `delta` to the server-side vault and re-pushes affected
+/// devices -- module docs "What a notification triggers", steps 1-3.
+/// Returns `false` (and changes nothing) if the session is no longer
+/// unlocked or the sync failed.
+pub async fn apply_delta(state: &AppState, delta: &VaultDelta) -> bool {
+    apply_delta_with(state, delta, |client| async move {
+        sync_and_decrypt(&client).await
+    })
+    .await
+}
+
+/// `apply_delta` with the vault fetch (step 2) supplied by the caller, so
+/// tests can hold it pending. Public for `tests/notifications_hub.rs`.
+///
+/// The session lock is NOT held across `fetch` -- a full `/sync` can take
+/// seconds, and `/api/auth/*` must stay responsive meanwhile. So a lock,
+/// logout or vault timeout can land while it's in flight; the session is
+/// re-checked once it returns and held while the store is updated (the
+/// same "session, then vault store" lock order as `auth_routes::lock` and
+/// `vault_timeout::lock_if_expired`). Without that, this would refill the
+/// store they just cleared. Aborting the `HubListener` doesn't cover it:
+/// the abort only lands at this task's next `.await`.
+///
+/// Nor is the lock held across the device pushes, for the same reason: an
+/// unreachable device can take its full connect timeout. The pushes work
+/// from copies taken under the lock, and the session is re-checked before
+/// each one, so a lock that lands between pushes stops the rest.
+pub async fn apply_delta_with<F, Fut, E>(state: &AppState, delta: &VaultDelta, fetch: F) -> bool
+where
+    F: FnOnce(Client) -> Fut,
+    Fut: Future<Output = Result<Vec<Credential>, E>>,
+    E: fmt::Display,
+{
+    let fresh = if delta.needs_fetch() {
+        let client = {
+            let session = state.session.lock().await;
+            match &*session {
+                Session::Unlocked(unlocked) => unlocked.client.clone(),
+                _ => return false,
+            }
+        };
+        match fetch(client).await {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                log_hub_error("vault sync failed", &err);
+                return false;
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let pushes: Vec<(String, SyncRequest)> = {
+        let session = state.session.lock().await;
+        if !matches!(&*session, Session::Unlocked(_)) {
+            return false;
+        }
+        let current = state.vault_credentials.get_all().await;
+        let merged = merge_delta(&current, &fresh, delta);
+        let changed = diff_credentials(&current, &merged);
+        if changed.is_empty() {
+            return true;
+        }
+        state.vault_credentials.replace(merged.clone()).await;
+        state
+            .device_selections
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|(_, selection)| changed.touches(selection.as_deref()))
+            .map(|(target_id, selection)| {
+                let request = SyncRequest {
+                    credentials: filter_credentials(&merged, selection.as_deref()),
+                };
+                (target_id, request)
+            })
+            .collect()
+    };
+
+    for (target_id, request) in pushes {
+        if !session_is_unlocked(state).await {
+            return false;
+        }
+        let result = match state.transports.connect(&target_id).await {
+            Ok(transport) => transport.push(&request).await.map(|_| ()),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            log_hub_error("device push failed", &err);
+        }
+    }
+    true
+}
+
+/// How `follow_hub` ended without an error.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HubEnd {
+    /// The server closed the connection; reconnect.
+    Closed,
+    /// A `LogOut` notification ended the session; stop.
+    LoggedOut,
+}
+
+/// Reads events from an open connection until it ends, coalescing and
+/// applying them (see module docs). Public so tests can drive it against
+/// a stand-in hub with an already-unlocked `AppState`.
+///
+/// # Errors
+///
+/// Any `HubError` from the connection. A partially collected delta is
+/// still applied first.
+pub async fn follow_hub(
+    state: &AppState,
+    connection: &mut HubConnection,
+) -> Result<HubEnd, HubError> {
+    loop {
+        let Some(first) = connection.next_event().await? else {
+            return Ok(HubEnd::Closed);
+        };
+        let mut delta = VaultDelta::default();
+        let mut next = Ok(Some(first));
+        let deadline = Instant::now() + COALESCE_WINDOW;
+        let end = loop {
+            match next {
+                Ok(Some(HubEvent::LoggedOut)) => {
+                    log_out(state).await;
+                    return Ok(HubEnd::LoggedOut);
+                }
+                Ok(Some(event)) => delta.add(event),
+                Ok(None) => break Some(Ok(HubEnd::Closed)),
+                Err(err) => break Some(Err(err)),
+            }
+            match tokio::time::timeout_at(deadline, connection.next_event()).await {
+                Ok(result) => next = result,
+                Err(_elapsed) => break None,
+            }
+        };
+        if !delta.is_empty() {
+            apply_delta(state, &delta).await;
+        }
+        if let Some(end) = end {
+            return end;
+        }
+    }
+}
+
+/// `LogOut` notification: same effect as `POST /api/auth/logout`.
+/// Replacing the session drops its `HubListener`, aborting the calling task
+/// at its next `.await` -- so nothing may follow this but returning.
+async fn log_out(state: &AppState) {
+    let mut session = state.session.lock().await;
+    if !matches!(&*session, Session::Unlocked(_)) {
+        return;
+    }
+    state.vault_credentials.clear().await;
+    state.device_selections.clear().await;
+    *session = Session::LoggedOut;
+    eprintln!("web-companion: server ended the session, logged out");
+}
+
+/// The per-session listener task: connect, `follow_hub`, reconnect with
+/// backoff until the session ends (the task is aborted) or the server logs
+/// it out. See module docs "Reconnects and token lifetime".
+pub async fn run_hub_listener(
+    state: AppState,
+    notifications_url: String,
+    tokens: SessionTokenHandler,
+) {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut first_attempt = true;
+    loop {
+        if !first_attempt {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+            let mut refresh = VaultDelta::default();
+            refresh.add(HubEvent::VaultChanged);
+            if !apply_delta(&state, &refresh).await && !session_is_unlocked(&state).await {
+                return;
+            }
+        }
+        first_attempt = false;
+
+        let Some(access_token) = tokens.access_token().await else {
+            log_hub_error("not connecting", &"no access token");
+            continue;
+        };
+        let url = match hub_url(&notifications_url, &access_token) {
+            Ok(url) => url,
+            Err(err) => {
+                log_hub_error("not connecting", &err);
+                return;
+            }
+        };
+        let mut connection = match HubConnection::connect(&url).await {
+            Ok(connection) => connection,
+            Err(err) => {
+                log_hub_error("connect failed", &err);
+                continue;
+            }
+        };
+        backoff = INITIAL_BACKOFF;
+        match follow_hub(&state, &mut connection).await {
+            Ok(HubEnd::LoggedOut) => return,
+            Ok(HubEnd::Closed) => {}
+            Err(err) => log_hub_error("connection lost", &err),
+        }
+    }
+}
+
+async fn session_is_unlocked(state: &AppState) -> bool {
+    matches!(&*state.session.lock().await, Session::Unlocked(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential(name: &str) -> Credential {
+        Credential {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            username: "user".to_string(),
+            password: "hunter2".to_string(),
+            uri: None,
+            notes: None,
+        }
+    }
+
+    fn invocation(push_type: u8, id: Uuid) -> String {
+        format!(
+            "{{\"type\":1,\"target\":\"ReceiveMessage\",\"arguments\":[{{\"ContextId\":null,\"Type\":{push_type},\"Payload\":{{\"Id\":\"{id}\",\"UserId\":null}}}}]}}"
+        )
+    }
+
+    #[test]
+    fn push_types_map_to_events() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            HubEvent::from_push(0, Some(id)),
+            Some(HubEvent::CipherUpserted(id))
+        );
+        assert_eq!(
+            HubEvent::from_push(1, Some(id)),
+            Some(HubEvent::CipherUpserted(id))
+        );
+        assert_eq!(
+            HubEvent::from_push(2, Some(id)),
+            Some(HubEvent::CipherDeleted(id))
+        );
+        assert_eq!(
+            HubEvent::from_push(9, Some(id)),
+            Some(HubEvent::CipherDeleted(id))
+        );
+        assert_eq!(HubEvent::from_push(5, None), Some(HubEvent::VaultChanged));
+        assert_eq!(HubEvent::from_push(11, None), Some(HubEvent::LoggedOut));
+        // Folder create, and a cipher update with no id to act on.
+        assert_eq!(HubEvent::from_push(7, Some(id)), None);
+        assert_eq!(HubEvent::from_push(0, None), None);
+    }
+
+    #[test]
+    fn frame_with_several_records_decodes_each() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let frame = format!(
+            "{{\"type\":6}}\u{1e}{}\u{1e}{}\u{1e}",
+            invocation(1, a),
+            invocation(9, b)
+        );
+
+        assert_eq!(
+            parse_frame(&frame).unwrap(),
+            vec![
+                HubRecord::Ignored,
+                HubRecord::Event(HubEvent::CipherUpserted(a)),
+                HubRecord::Event(HubEvent::CipherDeleted(b)),
+            ]
+        );
+    }
+
+    #[test]
+    fn camel_case_payload_is_accepted() {
+        let id = Uuid::new_v4();
+        let record = format!(
+            "{{\"type\":1,\"target\":\"ReceiveMessage\",\"arguments\":[{{\"type\":0,\"payload\":{{\"id\":\"{id}\"}}}}]}}"
+        );
+        assert_eq!(
+            parse_record(&record).unwrap(),
+            HubRecord::Event(HubEvent::CipherUpserted(id))
+        );
+    }
+
+    #[test]
+    fn close_record_carries_the_servers_error() {
+        assert_eq!(
+            parse_record("{\"type\":7,\"error\":\"Unauthorized\"}").unwrap(),
+            HubRecord::Close(Some("Unauthorized".to_string()))
+        );
+        assert_eq!(
+            parse_record("{\"type\":7}").unwrap(),
+            HubRecord::Close(None)
+        );
+    }
+
+    #[test]
+    fn malformed_record_is_a_protocol_error() {
+        assert!(matches!(
+            parse_record("not json"),
+            Err(HubError::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn hub_url_switches_scheme_and_appends_hub() {
+        assert_eq!(
+            hub_url("https://notifications.bitwarden.com", "tok").unwrap(),
+            "wss://notifications.bitwarden.com/hub?access_token=tok"
+        );
+        assert_eq!(
+            hub_url("http://127.0.0.1:8000/notifications", "a b").unwrap(),
+            "ws://127.0.0.1:8000/notifications/hub?access_token=a+b"
+        );
+    }
+
+    #[test]
+    fn hub_error_display_never_contains_the_url() {
+        let err = HubError::from(tungstenite::Error::ConnectionClosed);
+        assert!(!err.to_string().contains("access_token"));
+    }
+
+    #[test]
+    fn later_event_for_the_same_id_wins() {
+        let id = Uuid::new_v4();
+        let mut delta = VaultDelta::default();
+        delta.add(HubEvent::CipherUpserted(id));
+        delta.add(HubEvent::CipherDeleted(id));
+
+        assert!(delta.upserted.is_empty());
+        assert_eq!(delta.deleted, BTreeSet::from([id]));
+        assert!(!delta.needs_fetch());
+    }
+
+    #[test]
+    fn delta_only_touches_devices_holding_a_changed_item() {
+        let changed = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let mut delta = VaultDelta::default();
+        delta.add(HubEvent::CipherDeleted(changed));
+
+        assert!(delta.touches(None));
+        assert!(delta.touches(Some(&[other, changed])));
+        assert!(!delta.touches(Some(&[other])));
+        assert!(!VaultDelta::default().touches(None));
+
+        delta.add(HubEvent::VaultChanged);
+        assert!(delta.touches(Some(&[])));
+    }
+
+    #[test]
+    fn merge_applies_only_notified_ids() {
+        let kept = credential("kept");
+        let edited = credential("edited");
+        let removed = credential("removed");
+        let current = vec![kept.clone(), edited.clone(), removed.clone()];
+
+        let mut fresh_edited = edited.clone();
+        fresh_edited.password = "new-password".to_string();
+        let mut unrelated_remote_edit = kept.clone();
+        unrelated_remote_edit.name = "renamed remotely".to_string();
+        let created = credential("created");
+        let fresh = vec![unrelated_remote_edit, fresh_edited.clone(), created.clone()];
+
+        let mut delta = VaultDelta::default();
+        delta.add(HubEvent::CipherUpserted(edited.id));
+        delta.add(HubEvent::CipherDeleted(removed.id));
+        delta.add(HubEvent::CipherUpserted(created.id));
+
+        assert_eq!(
+            merge_delta(&current, &fresh, &delta),
+            vec![kept, fresh_edited, created]
+        );
+    }
+
+    #[test]
+    fn upserted_id_missing_from_fresh_sync_is_dropped() {
+        let trashed = credential("trashed");
+        let mut delta = VaultDelta::default();
+        delta.add(HubEvent::CipherUpserted(trashed.id));
+
+        assert!(merge_delta(&[trashed], &[], &delta).is_empty());
+    }
+
+    #[test]
+    fn everything_takes_the_fresh_sync() {
+        let mut delta = VaultDelta::default();
+        delta.add(HubEvent::VaultChanged);
+        let fresh = vec![credential("a")];
+
+        assert_eq!(merge_delta(&[credential("old")], &fresh, &delta), fresh);
+    }
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_ids() {
+        let same = credential("same");
+        let edited = credential("edited");
+        let removed = credential("removed");
+        let added = credential("added");
+        let mut edited_new = edited.clone();
+        edited_new.username = "someone-else".to_string();
+
+        let delta = diff_credentials(
+            &[same.clone(), edited, removed.clone()],
+            &[same, edited_new.clone(), added.clone()],
+        );
+
+        assert_eq!(delta.upserted, BTreeSet::from([edited_new.id, added.id]));
+        assert_eq!(delta.deleted, BTreeSet::from([removed.id]));
+        assert!(!delta.everything);
+    }
+}
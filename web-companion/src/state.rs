@@ -1,14 +1,25 @@
 //! Shared application state for the web-companion axum server.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 use bitwarden_auth::token_management::PasswordManagerTokenHandler;
+use bitwarden_core::auth::TokenHandler;
+use bitwarden_core::client::persisted_state::{AuthenticationTokens, AUTHENTICATION_TOKENS};
+use bitwarden_core::key_management::KeySlotIds;
 use bitwarden_core::{Client, ClientSettings, DeviceType};
+use bitwarden_crypto::KeyStore;
+use bitwarden_state::registry::StateRegistry;
+use bitwarden_state::settings::Setting;
 use push_protocol::Credential;
 use tokio::sync::Mutex;
+use uuid::Uuid;
 use zeroize::{Zeroize, Zeroizing};
 
+use crate::environment::ServerEnvironment;
+use crate::notifications::HubListener;
 use crate::transport::{DeviceDescriptor, EmulatorTransportProvider, TransportError, TransportProvider};
+use crate::vault_timeout::VaultTimeout;
 
 /// Email + master password stashed server-side across a `POST
 /// /api/auth/login` -> `POST /api/auth/2fa` round trip (see
@@ -25,6 +36,23 @@ use crate::transport::{DeviceDescriptor, EmulatorTransportProvider, TransportErr
 pub struct PendingTwoFactorLogin {
     pub email: String,
     pub master_password: Zeroizing<String>,
+    /// The server the first attempt went to -- the 2FA retry must go to
+    /// the same one (see `crate::environment`).
+    pub environment: ServerEnvironment,
+}
+
+/// An unlocked SDK `Client` plus the server it is logged in to (see
+/// `crate::environment`; reported by `GET /api/auth/status`).
+///
+/// `hub` is the notifications-hub listener task for this session (see
+/// `crate::notifications`); it is aborted when this value is dropped, so
+/// any transition out of `Session::Unlocked` (lock, logout, vault timeout)
+/// stops it without each handler having to remember to.
+pub struct UnlockedSession {
+    pub client: Client,
+    pub environment: ServerEnvironment,
+    pub tokens: SessionTokenHandler,
+    pub hub: Option<HubListener>,
 }
 
 /// Authentication/session state for the (eventually) single Bitwarden
@@ -32,36 +60,29 @@ pub struct PendingTwoFactorLogin {
 /// full state-machine documentation and the handlers that drive these
 /// transitions.
 ///
-/// `Locked(Client)` is intentionally never constructed by this crate: at
-/// the pinned SDK revision (99ffb6ef) there is no re-unlock-without-password
-/// path for a fresh in-memory session (the SDK's persisted-state resume
-/// path is for rehydrating state this server does not persist -- see
-/// eml.1/eml.3 findings). A session that cannot be unlocked again without
-/// the password is functionally logged out, so `POST /api/auth/lock`
-/// transitions straight to `LoggedOut` rather than inventing an unusable
-/// `Locked` state. The variant is kept (rather than deleted) so a future
-/// bead that adds real persisted-state resume has a documented slot to fill
-/// in; `#[allow(dead_code)]` on it documents that gap rather than hiding it.
+/// `Locked` is produced only by the vault timeout (`crate::vault_timeout`)
+/// and deliberately holds no `Client`: at the pinned SDK revision (99ffb6ef)
+/// there is no re-unlock-without-password path for a fresh in-memory
+/// session (the SDK's persisted-state resume path is for rehydrating state
+/// this server does not persist -- see eml.1/eml.3 findings), so keeping
+/// the `Client` alive would only keep its user key in memory for nothing.
+/// Unlocking from `Locked` is therefore a full `/api/auth/login`, same as
+/// from `LoggedOut`; the separate variant exists so `GET /api/auth/status`
+/// can tell the browser *why* it is looking at the login form. An explicit
+/// `POST /api/auth/lock` still goes straight to `LoggedOut`.
 pub enum Session {
     LoggedOut,
     PendingTwoFactor(PendingTwoFactorLogin),
-    #[allow(dead_code)]
-    Locked(Client),
-    // The inner `Client` is constructed and stored here by `auth_routes`
-    // once unlocked, but nothing reads it back out yet -- vault access
-    // (the reason to ever read an unlocked `Client`) is eml.4's scope, not
-    // this bead's. `#[allow(dead_code)]` documents that gap rather than
-    // hiding it.
-    #[allow(dead_code)]
-    Unlocked(Client),
+    Locked,
+    Unlocked(UnlockedSession),
 }
 
-/// Constructs a `Client` the way every login attempt does: no credentials
-/// touched, no network calls made here. Called fresh for each login/2FA
-/// attempt in `crate::auth_routes` (a `Client` used in an attempt that
-/// didn't reach `IdentityTokenResponse::Authenticated` never picks up any
-/// internal state worth keeping -- see eml.3 report for why re-using the
-/// client across a 2FA retry isn't necessary).
+/// Constructs a `Client` for `environment` the way every login attempt
+/// does: no credentials touched, no network calls made here. Called fresh
+/// for each login/2FA attempt in `crate::auth_routes` (a `Client` used in
+/// an attempt that didn't reach `IdentityTokenResponse::Authenticated`
+/// never picks up any internal state worth keeping -- see eml.3 report for
+/// why re-using the client across a 2FA retry isn't necessary).
 ///
 /// **Required client-identification headers (ai-bitwarden-hw-key-eml.11):**
 /// against the LIVE Bitwarden API, `ClientSettings::default()`'s bare
@@ -101,19 +122,79 @@ pub enum Session {
 /// wired up here the same way via `Client::new_with_token_handler`.
 /// Confirmed fixed end-to-end against a real account; see the eml.11
 /// completion report.
+///
+/// `environment` supplies the identity/API endpoints (previously hardcoded
+/// to the US cloud). It must already be validated -- see
+/// `crate::environment::ServerSelection::resolve`.
+///
+/// Also returns the `SessionTokenHandler` wrapping that
+/// `PasswordManagerTokenHandler`, which is how `crate::notifications` reads
+/// the session's access token for the notifications hub.
 #[must_use]
-pub fn build_client() -> Client {
+pub fn build_client(environment: &ServerEnvironment) -> (Client, SessionTokenHandler) {
     let settings = ClientSettings {
-        identity_url: "https://identity.bitwarden.com".to_string(),
-        api_url: "https://api.bitwarden.com".to_string(),
+        identity_url: environment.identity_url.clone(),
+        api_url: environment.api_url.clone(),
         device_type: DeviceType::SDK,
         bitwarden_client_version: Some(env!("CARGO_PKG_VERSION").to_string()),
         ..ClientSettings::default()
     };
-    Client::new_with_token_handler(
-        Some(settings),
-        Arc::new(PasswordManagerTokenHandler::default()),
-    )
+    let tokens = SessionTokenHandler::default();
+    let client = Client::new_with_token_handler(Some(settings), Arc::new(tokens.clone()));
+    (client, tokens)
+}
+
+/// `PasswordManagerTokenHandler` plus read access to the token it stores.
+///
+/// The notifications hub (`crate::notifications`) authenticates with the
+/// session's bearer access token as a query parameter, but the SDK never
+/// hands that token out: `PasswordManagerTokenHandler` attaches it to the
+/// SDK's own requests inside its middleware and keeps it in the client's
+/// (crate-private) state registry. This wrapper delegates both trait
+/// methods unchanged and, in `initialize_middleware`, keeps its own handle
+/// on the same `AUTHENTICATION_TOKENS` setting the inner handler reads and
+/// renews -- so `access_token` always sees the current token, including
+/// after a renewal triggered by an ordinary SDK call.
+#[derive(Clone, Default)]
+pub struct SessionTokenHandler {
+    inner: PasswordManagerTokenHandler,
+    tokens: Arc<RwLock<Option<Setting<AuthenticationTokens>>>>,
+}
+
+impl SessionTokenHandler {
+    /// The session's current access token, or `None` before a login has
+    /// stored one. May be expired if no SDK request has run recently
+    /// enough to renew it -- see `crate::notifications` for how the hub
+    /// listener copes with that.
+    pub async fn access_token(&self) -> Option<String> {
+        let setting = self.tokens.read().ok()?.clone()?;
+        setting
+            .get()
+            .await
+            .ok()
+            .flatten()
+            .map(|tokens| tokens.access_token)
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenHandler for SessionTokenHandler {
+    fn initialize_middleware(
+        &self,
+        state_registry: &StateRegistry,
+        identity_config: bitwarden_api_api::Configuration,
+        key_store: KeyStore<KeySlotIds>,
+    ) -> Arc<dyn reqwest_middleware::Middleware> {
+        if let Ok(mut tokens) = self.tokens.write() {
+            *tokens = state_registry.setting(AUTHENTICATION_TOKENS).ok();
+        }
+        self.inner
+            .initialize_middleware(state_registry, identity_config, key_store)
+    }
+
+    async fn set_tokens(&self, token: String, refresh_token: Option<String>, expires_in: u64) {
+        self.inner.set_tokens(token, refresh_token, expires_in).await;
+    }
 }
 
 /// Unions every registered `TransportProvider`'s view of the world into one
@@ -138,14 +219,30 @@ impl TransportRegistry {
     }
 
     /// Convenience constructor wiring up the one Phase-1 provider (the
-    /// desktop emulator over HTTP) at `base_url`. See `main.rs` for where
-    /// `base_url` is resolved (the `EMULATOR_URL` env var, falling back to
-    /// `crate::transport::DEFAULT_EMULATOR_URL`).
+    /// desktop emulator over HTTP) at `base_url`.
     #[must_use]
     pub fn with_emulator(base_url: String) -> Self {
         Self::new(vec![Arc::new(EmulatorTransportProvider::new(base_url))])
     }
 
+    /// Like `with_emulator`, for one or more emulators side by side (see
+    /// `EmulatorTransportProvider::with_urls`). See `main.rs` for where
+    /// `base_urls` are resolved (the `EMULATOR_URL` env var, falling back
+    /// to `crate::transport::DEFAULT_EMULATOR_URL`).
+    #[must_use]
+    pub fn with_emulators(base_urls: Vec<String>) -> Self {
+        Self::new(vec![Arc::new(EmulatorTransportProvider::with_urls(base_urls))])
+    }
+
+    /// Has every registered provider `refresh` its targets, so the next
+    /// `list_all_targets` reflects what's reachable now. The providers
+    /// refresh concurrently: a slow one costs its own probe timeout, not
+    /// the sum of everyone's.
+    pub async fn refresh(&self) {
+        let refreshes = self.providers.iter().map(|provider| provider.refresh());
+        futures_util::future::join_all(refreshes).await;
+    }
+
     /// Union of every registered provider's `list_targets()`.
     #[must_use]
     pub fn list_all_targets(&self) -> Vec<DeviceDescriptor> {
@@ -221,6 +318,40 @@ impl VaultCredentialStore {
     }
 }
 
+/// Which devices have been pushed to this process lifetime, and with which
+/// selection: `None` = "everything" (`POST /api/sync` without
+/// `item_ids`), `Some(ids)` = that explicit subset. Recorded by
+/// `crate::transport_routes::sync` on a successful push and read by
+/// `crate::notifications` to re-push a device only when a hub
+/// notification touched something that device holds. Never holds
+/// credential data, only ids.
+#[derive(Clone, Default)]
+pub struct DeviceSelections {
+    selections: Arc<Mutex<HashMap<String, Option<Vec<Uuid>>>>>,
+}
+
+impl DeviceSelections {
+    pub async fn record(&self, target_id: &str, item_ids: Option<Vec<Uuid>>) {
+        self.selections
+            .lock()
+            .await
+            .insert(target_id.to_string(), item_ids);
+    }
+
+    pub async fn clear(&self) {
+        self.selections.lock().await.clear();
+    }
+
+    pub async fn get_all(&self) -> Vec<(String, Option<Vec<Uuid>>)> {
+        self.selections
+            .lock()
+            .await
+            .iter()
+            .map(|(id, selection)| (id.clone(), selection.clone()))
+            .collect()
+    }
+}
+
 /// Shared state handed to every axum handler via `axum::extract::State`.
 ///
 /// `Client` (inside `Session`) is `Send + Sync + Clone` (confirmed in
@@ -237,4 +368,9 @@ pub struct AppState {
     /// See `VaultCredentialStore` docs -- the server-side-only decrypted
     /// vault, populated by `crate::vault_routes::sync`.
     pub vault_credentials: VaultCredentialStore,
+    /// Inactivity tracker and policy -- see `crate::vault_timeout`, which
+    /// clears `vault_credentials` and locks `session` when it expires.
+    pub vault_timeout: VaultTimeout,
+    /// See `DeviceSelections` docs.
+    pub device_selections: DeviceSelections,
 }
@@ -7,8 +7,9 @@
 //! - `DeviceTransport` -- a single connected/addressable device that can
 //!   accept a credential push (`push`) and describe itself (`descriptor`).
 //! - `TransportProvider` -- enumerates devices reachable over one medium
-//!   (`list_targets`, no network needed) and opens a `DeviceTransport` for
-//!   a chosen one (`connect`, fallible -- the id may be stale/unknown).
+//!   (`list_targets`, no network needed), optionally re-discovers them
+//!   first (`refresh`), and opens a `DeviceTransport` for a chosen one
+//!   (`connect`, fallible -- the id may be stale/unknown).
 //!
 //! Phase 1 (this bead, ai-bitwarden-hw-key-eml.5) implements exactly one
 //! medium: `HttpEmulatorTransport` / `EmulatorTransportProvider`, talking to
@@ -17,6 +18,13 @@
 //! retired `companion::push_to_device` (`ureq` -> `reqwest`). BLE/USB
 //! providers are Phase 2 (the T-Embed hardware migration); see `DeviceKind`.
 //!
+//! One provider can front several emulators: `desktop` instances run side
+//! by side on their own ports (`--port`/`--config`, see
+//! `emulator/src/desktop/device.rs`), and `EMULATOR_URL` may list them
+//! comma-separated. `EmulatorTransportProvider::refresh` asks each one's
+//! `GET /api/status` for its device descriptor (name, panel profile) so
+//! the device list can tell them apart.
+//!
 //! See `crate::state::TransportRegistry` for how multiple providers (future
 //! BLE/USB) would union under one `list_all_targets`/`connect` surface, and
 //! `crate::transport_routes` for the HTTP surface built on top of this
@@ -32,10 +40,11 @@
 //! inside `HttpEmulatorTransport::push`.
 
 use std::fmt;
-use std::sync::Once;
+use std::sync::{Once, OnceLock, RwLock};
+use std::time::Duration;
 
 use push_protocol::{SyncRequest, SyncResponse};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Installs the process-wide rustls `CryptoProvider` exactly once. See the
 /// `rustls` dependency comment in `Cargo.toml` for the full "why": reqwest
@@ -44,7 +53,7 @@ use serde::Serialize;
 /// so `reqwest::Client::new()` panics at construction time until this runs.
 /// Idempotent -- `install_default` returning `Err` (already installed,
 /// e.g. by some other crate in the process) is not a bug and is ignored.
-fn ensure_crypto_provider_installed() {
+pub(crate) fn ensure_crypto_provider_installed() {
     static INIT: Once = Once::new();
     INIT.call_once(|| {
         let _ = rustls::crypto::ring::default_provider().install_default();
@@ -53,17 +62,27 @@ fn ensure_crypto_provider_installed() {
 
 /// Default base URL for the desktop emulator's HTTP sync server (see
 /// `emulator/src/desktop/http_server.rs`). Overridable via the `EMULATOR_URL`
-/// env var, resolved once in `main.rs` (see `state::TransportRegistry::with_emulator`
-/// callers) -- this constant is only the documented fallback, not something
+/// env var (one URL, or several comma-separated), resolved once in
+/// `main.rs` (see `state::TransportRegistry::with_emulators` callers) --
+/// this constant is only the documented fallback, not something
 /// `transport.rs` itself reads from the environment.
 pub const DEFAULT_EMULATOR_URL: &str = "http://127.0.0.1:8080";
 
-/// Fixed id for the (currently sole) emulator target. Both
+/// Fixed id for the emulator target when only one is configured (the
+/// common case, and the only one before multi-instance emulators). Both
 /// `EmulatorTransportProvider::connect` and the descriptor builder key off
-/// this constant so the id used to list a device and the id used to
-/// address it can't drift apart.
+/// `EmulatorTarget::descriptor`'s id, which is this constant for a sole
+/// target, so the id used to list a device and the id used to address it
+/// can't drift apart. With several targets each is `emulator@<host:port>`
+/// instead (see `emulator_target_id`).
 const EMULATOR_DEVICE_ID: &str = "emulator";
 
+/// How long `EmulatorTransportProvider::refresh` waits on one emulator's
+/// `/api/status` before keeping what it last knew. Loopback answers in
+/// microseconds; this only bounds a port nothing is listening on, or one
+/// something else is.
+const STATUS_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// Distinguishes the physical/logical medium a `DeviceTransport` talks
 /// over. Only `Emulator` exists today -- BLE/USB are Phase 2 additions (the
 /// T-Embed hardware migration). `#[non_exhaustive]` so adding those variants
@@ -93,6 +112,44 @@ fn emulator_descriptor() -> DeviceDescriptor {
     }
 }
 
+/// The id a target at `base_url` is listed under: `EMULATOR_DEVICE_ID` if
+/// it's the only one, else `emulator@<host:port>` -- stable across
+/// restarts of the same configuration, unlike the name an instance
+/// reports about itself.
+fn emulator_target_id(base_url: &str, sole: bool) -> String {
+    if sole {
+        return EMULATOR_DEVICE_ID.to_string();
+    }
+    let authority = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest);
+    format!("{EMULATOR_DEVICE_ID}@{}", authority.trim_end_matches('/'))
+}
+
+/// The part of the emulator's `GET /api/status` body `refresh` reads.
+/// `device` is absent (or `null`) from emulators predating multi-instance
+/// support, which just keep their default name.
+#[derive(Deserialize)]
+struct EmulatorStatus {
+    device: Option<EmulatorDevice>,
+}
+
+#[derive(Deserialize)]
+struct EmulatorDevice {
+    name: String,
+    profile: String,
+    panel: (u32, u32),
+}
+
+impl EmulatorDevice {
+    /// E.g. `"Strip key (ssd1306, 128x32)"`: the panel is how a user
+    /// tells otherwise identical instances apart in the device picker.
+    fn display_name(&self) -> String {
+        let (width, height) = self.panel;
+        format!("{} ({}, {width}x{height})", self.name, self.profile)
+    }
+}
+
 /// Errors from a `DeviceTransport`/`TransportProvider`. Deliberately opaque
 /// to HTTP callers -- see module docs.
 #[derive(Debug)]
@@ -140,11 +197,17 @@ pub trait DeviceTransport: Send + Sync {
 #[async_trait::async_trait]
 pub trait TransportProvider: Send + Sync {
     /// Lists every device this provider currently sees. No network access
-    /// required -- for `EmulatorTransportProvider` this is a fixed,
-    /// statically-known single entry; a future BLE provider would return
-    /// the results of its last scan.
+    /// required -- for `EmulatorTransportProvider` this is one entry per
+    /// configured emulator URL, named from its last `refresh`; a future BLE
+    /// provider would return the results of its last scan.
     fn list_targets(&self) -> Vec<DeviceDescriptor>;
 
+    /// Re-discovers what `list_targets` returns (probe the configured
+    /// emulators, rescan BLE, ...). Never fails: a device that doesn't
+    /// answer keeps its last-known descriptor. The default does nothing,
+    /// for providers whose targets don't change.
+    async fn refresh(&self) {}
+
     /// Opens a `DeviceTransport` for `id`. Fails with
     /// `TransportError::UnknownDevice` if this provider doesn't recognize
     /// `id` (e.g. it belongs to a different provider, or the device is
@@ -165,15 +228,27 @@ pub trait TransportProvider: Send + Sync {
 pub struct HttpEmulatorTransport {
     base_url: String,
     client: reqwest::Client,
+    descriptor: DeviceDescriptor,
 }
 
 impl HttpEmulatorTransport {
+    /// A transport to the emulator at `base_url`, described as the sole
+    /// `"emulator"` target.
     #[must_use]
     pub fn new(base_url: String) -> Self {
+        Self::with_descriptor(base_url, emulator_descriptor())
+    }
+
+    /// A transport to the emulator at `base_url`, described by
+    /// `descriptor` (one of several targets, see
+    /// `EmulatorTransportProvider::with_urls`).
+    #[must_use]
+    pub fn with_descriptor(base_url: String, descriptor: DeviceDescriptor) -> Self {
         ensure_crypto_provider_installed();
         Self {
             base_url,
             client: reqwest::Client::new(),
+            descriptor,
         }
     }
 }
@@ -209,20 +284,78 @@ impl DeviceTransport for HttpEmulatorTransport {
     }
 
     fn descriptor(&self) -> DeviceDescriptor {
-        emulator_descriptor()
+        self.descriptor.clone()
     }
 }
 
-/// `TransportProvider` for the single desktop-emulator target. Phase 1's
+/// One configured emulator: where it listens, and how it's listed (the
+/// id fixed at construction, the name updated by `refresh`).
+struct EmulatorTarget {
+    base_url: String,
+    descriptor: RwLock<DeviceDescriptor>,
+}
+
+/// `TransportProvider` for the desktop emulator(s) over HTTP. Phase 1's
 /// only provider.
 pub struct EmulatorTransportProvider {
-    base_url: String,
+    targets: Vec<EmulatorTarget>,
+    /// Only `refresh` needs one, so it's built on the first probe rather
+    /// than at construction (see `list_targets`).
+    probe_client: OnceLock<reqwest::Client>,
 }
 
 impl EmulatorTransportProvider {
+    /// A provider for the one emulator at `base_url`, listed as
+    /// `"emulator"`.
     #[must_use]
     pub fn new(base_url: String) -> Self {
-        Self { base_url }
+        Self::with_urls(vec![base_url])
+    }
+
+    /// A provider for an emulator at each of `base_urls`, e.g. several
+    /// `desktop` instances on their own ports. Ids are per
+    /// `emulator_target_id`; names are "Desktop Emulator" until `refresh`
+    /// learns better.
+    #[must_use]
+    pub fn with_urls(base_urls: Vec<String>) -> Self {
+        let sole = base_urls.len() == 1;
+        let targets = base_urls
+            .into_iter()
+            .map(|base_url| {
+                let descriptor = DeviceDescriptor {
+                    id: emulator_target_id(&base_url, sole),
+                    ..emulator_descriptor()
+                };
+                EmulatorTarget {
+                    base_url,
+                    descriptor: RwLock::new(descriptor),
+                }
+            })
+            .collect();
+        Self {
+            targets,
+            probe_client: OnceLock::new(),
+        }
+    }
+
+    /// Asks `target`'s `/api/status` what it is. `None` if it doesn't
+    /// answer in time, answers with something else, or predates the
+    /// `device` descriptor.
+    async fn probe(&self, target: &EmulatorTarget) -> Option<EmulatorDevice> {
+        let client = self.probe_client.get_or_init(|| {
+            ensure_crypto_provider_installed();
+            reqwest::Client::new()
+        });
+        let response = client
+            .get(format!("{}/api/status", target.base_url))
+            .timeout(STATUS_PROBE_TIMEOUT)
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.json::<EmulatorStatus>().await.ok()?.device
     }
 }
 
@@ -232,16 +365,36 @@ impl TransportProvider for EmulatorTransportProvider {
         // Built without constructing a `HttpEmulatorTransport` (and thus
         // without allocating a `reqwest::Client`) -- listing targets should
         // not require standing up a connection pool. See `emulator_descriptor`.
-        vec![emulator_descriptor()]
+        self.targets
+            .iter()
+            .map(|target| target.descriptor.read().unwrap().clone())
+            .collect()
     }
 
-    async fn connect(&self, id: &str) -> Result<Box<dyn DeviceTransport>, TransportError> {
-        if id == EMULATOR_DEVICE_ID {
-            Ok(Box::new(HttpEmulatorTransport::new(self.base_url.clone())))
-        } else {
-            Err(TransportError::UnknownDevice(id.to_string()))
+    /// Probes every target at once, so a refresh takes at most one
+    /// `STATUS_PROBE_TIMEOUT` however many emulators don't answer.
+    async fn refresh(&self) {
+        let probes = self.targets.iter().map(|target| self.probe(target));
+        let devices = futures_util::future::join_all(probes).await;
+        for (target, device) in self.targets.iter().zip(devices) {
+            if let Some(device) = device {
+                target.descriptor.write().unwrap().name = device.display_name();
+            }
         }
     }
+
+    async fn connect(&self, id: &str) -> Result<Box<dyn DeviceTransport>, TransportError> {
+        let target = self
+            .targets
+            .iter()
+            .find(|target| target.descriptor.read().unwrap().id == id)
+            .ok_or_else(|| TransportError::UnknownDevice(id.to_string()))?;
+        let descriptor = target.descriptor.read().unwrap().clone();
+        Ok(Box::new(HttpEmulatorTransport::with_descriptor(
+            target.base_url.clone(),
+            descriptor,
+        )))
+    }
 }
 
 #[cfg(test)]
@@ -312,6 +465,57 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn several_emulators_are_listed_and_addressed_by_host_and_port() {
+        let provider = EmulatorTransportProvider::with_urls(vec![
+            "http://127.0.0.1:8080".to_string(),
+            "http://127.0.0.1:8081/".to_string(),
+        ]);
+        let ids: Vec<String> = provider.list_targets().into_iter().map(|t| t.id).collect();
+
+        assert_eq!(ids, ["emulator@127.0.0.1:8080", "emulator@127.0.0.1:8081"]);
+        let transport = provider.connect("emulator@127.0.0.1:8081").await.unwrap();
+        assert_eq!(transport.descriptor().id, "emulator@127.0.0.1:8081");
+        assert!(matches!(
+            provider.connect("emulator").await,
+            Err(TransportError::UnknownDevice(_))
+        ));
+    }
+
+    /// `refresh` names each target from its `/api/status` descriptor, and
+    /// one that doesn't answer keeps the name it had. A stand-in axum
+    /// router plays the emulator's status endpoint.
+    #[tokio::test]
+    async fn refresh_names_targets_from_their_status_and_skips_silent_ones() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let live = format!("http://{}", listener.local_addr().unwrap());
+        let status = axum::Router::new().route(
+            "/api/status",
+            axum::routing::get(|| async {
+                axum::Json(serde_json::json!({
+                    "status": "running",
+                    "credential_count": 0,
+                    "device": { "name": "Strip key", "profile": "ssd1306", "panel": [128, 32], "port": 0, "data_dir": "data/strip" },
+                }))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, status).await });
+
+        let silent = TcpListener::bind("127.0.0.1:0").expect("bind an ephemeral port");
+        let silent_url = format!("http://{}", silent.local_addr().unwrap());
+        drop(silent);
+
+        let provider = EmulatorTransportProvider::with_urls(vec![live, silent_url]);
+        provider.refresh().await;
+        let names: Vec<String> = provider
+            .list_targets()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+
+        assert_eq!(names, ["Strip key (ssd1306, 128x32)", "Desktop Emulator"]);
+    }
+
     /// Proves the "device unreachable" path maps cleanly to
     /// `TransportError::Unreachable` rather than panicking, without needing
     /// the real emulator binary running: binds a `TcpListener` to get a
@@ -65,13 +65,15 @@ fn log_transport_error(err: &TransportError) {
 }
 
 /// `GET /api/devices` -- lists every target every registered
-/// `TransportProvider` currently sees (Phase 1: just the emulator).
-/// Requires `Session::Unlocked`.
+/// `TransportProvider` currently sees (Phase 1: the configured emulators),
+/// refreshed first so each emulator is listed under the name and panel it
+/// reports. Requires `Session::Unlocked`.
 pub async fn list_devices(State(state): State<AppState>) -> Response {
     if let Err(response) = require_unlocked(&state).await {
         return response;
     }
 
+    state.transports.refresh().await;
     let devices: Vec<DeviceDescriptor> = state.transports.list_all_targets();
     Json(devices).into_response()
 }
@@ -108,7 +110,7 @@ struct SyncPushResult {
 /// credential in `all` are simply absent from the output -- treated as a
 /// no-op, not an error (an "optional filter" reasonably tolerates
 /// stale/unknown ids without failing the whole push).
-fn filter_credentials(all: &[Credential], item_ids: Option<&[Uuid]>) -> Vec<Credential> {
+pub(crate) fn filter_credentials(all: &[Credential], item_ids: Option<&[Uuid]>) -> Vec<Credential> {
     match item_ids {
         None => all.to_vec(),
         Some(ids) => all.iter().filter(|c| ids.contains(&c.id)).cloned().collect(),
@@ -119,6 +121,10 @@ fn filter_credentials(all: &[Credential], item_ids: Option<&[Uuid]>) -> Vec<Cred
 /// credential set to `target_id` over whatever `DeviceTransport`
 /// `state.transports.connect` resolves it to. Requires `Session::Unlocked`.
 /// See module docs for the security posture.
+///
+/// A successful push records `item_ids` in `state.device_selections`, so
+/// later hub notifications re-push this device with the same selection
+/// (see `crate::notifications`).
 pub async fn sync(State(state): State<AppState>, Json(body): Json<SyncPushRequest>) -> Response {
     if let Err(response) = require_unlocked(&state).await {
         return response;
@@ -141,7 +147,13 @@ pub async fn sync(State(state): State<AppState>, Json(body): Json<SyncPushReques
 
     let device = transport.descriptor();
     match transport.push(&request).await {
-        Ok(_response) => Json(SyncPushResult { pushed, device }).into_response(),
+        Ok(_response) => {
+            state
+                .device_selections
+                .record(&body.target_id, body.item_ids)
+                .await;
+            Json(SyncPushResult { pushed, device }).into_response()
+        }
         Err(err) => {
             log_transport_error(&err);
             error_response(StatusCode::BAD_GATEWAY, "device push failed")
@@ -117,7 +117,7 @@ struct VaultSyncResult {
 async fn unlocked_client(state: &AppState) -> Option<bitwarden_core::Client> {
     let session = state.session.lock().await;
     match &*session {
-        Session::Unlocked(client) => Some(client.clone()),
+        Session::Unlocked(unlocked) => Some(unlocked.client.clone()),
         _ => None,
     }
 }
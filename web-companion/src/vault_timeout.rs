@@ -0,0 +1,439 @@
+//! Vault timeout: lock the session after a period of `/api/*` inactivity,
+//! mirroring the Bitwarden clients' "Vault timeout" setting.
+//!
+//! Before this module, decrypted `Credential`s (passwords included) sat in
+//! `crate::state::VaultCredentialStore` until an explicit
+//! `/api/auth/lock`/`/api/auth/logout` or process exit -- a companion left
+//! running overnight kept the whole vault in memory indefinitely.
+//!
+//! ## Policies
+//!
+//! - `Minutes { minutes }` -- lock once no `/api/*` activity has been seen
+//!   for that long. Default: 15 minutes, same as the Bitwarden clients.
+//! - `OnSleep` -- lock as soon as the host is detected to have slept (see
+//!   "Sleep detection" below), regardless of how recently it was used.
+//! - `Never` -- no automatic lock; only explicit lock/logout clears the
+//!   vault.
+//!
+//! ## What counts as activity
+//!
+//! Every authenticated `/api/*` request EXCEPT `GET /api/auth/status`
+//! (see `is_activity`). The browser UI polls that route to decide which
+//! view to show; counting it would let an open-but-abandoned tab keep the
+//! vault unlocked forever, which is exactly what this module exists to
+//! prevent.
+//!
+//! ## Enforcement
+//!
+//! Two paths, both ending in `lock_if_expired`:
+//!
+//! - `enforce_vault_timeout` (axum middleware on `/api/*`, see
+//!   `crate::build_app`) checks BEFORE recording the current request as
+//!   activity, so the first request after the deadline observes a locked
+//!   session rather than silently extending it.
+//! - `run_idle_watchdog` (spawned from `main.rs`) re-checks every
+//!   `WATCHDOG_INTERVAL`, so the vault is cleared on time even if the
+//!   browser tab was closed and no request ever arrives.
+//!
+//! On expiry the server-side vault is cleared (zeroizing passwords, same
+//! as `/api/auth/lock`) and the session becomes `Session::Locked`, which
+//! `GET /api/auth/status` reports as `"locked"` -- the browser UI already
+//! routes that to its login view.
+//!
+//! ## Sleep detection
+//!
+//! `Instant` is `CLOCK_MONOTONIC` on Linux, which does NOT advance while
+//! the host is suspended; `SystemTime` (wall clock) does. So "wall clock
+//! moved noticeably further than the monotonic clock since the last
+//! activity" means the host slept in between. `SLEEP_DETECTION_THRESHOLD`
+//! absorbs ordinary NTP slew. This is best-effort: on platforms whose
+//! monotonic clock keeps counting through suspend (macOS'
+//! `mach_continuous_time`-backed builds, Windows' QPC) the gap never
+//! opens and `OnSleep` degrades to `Never`. Inactivity under `Minutes`
+//! uses the larger of the two elapsed values, so time spent asleep still
+//! counts towards the timeout on every platform.
+//!
+//! ## Clock
+//!
+//! All time comes from a `Clock` trait object rather than calling
+//! `Instant::now()`/`SystemTime::now()` directly, so tests drive expiry
+//! with `ManualClock` instead of sleeping for real.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::state::{AppState, Session};
+
+/// Wall-vs-monotonic divergence above which the host is assumed to have
+/// slept. See module docs "Sleep detection".
+pub const SLEEP_DETECTION_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// How often `run_idle_watchdog` re-checks for expiry. Bounds how late a
+/// lock can fire when no requests arrive at all.
+pub const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Source of both monotonic and wall-clock time. See module docs "Clock".
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn wall_now(&self) -> SystemTime;
+}
+
+/// The real clock: `Instant::now()` / `SystemTime::now()`.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wall_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Test clock that only moves when told to. `advance` moves both clocks
+/// together (ordinary idle time); `sleep` moves only the wall clock, which
+/// is what a host suspend looks like (see module docs "Sleep detection").
+pub struct ManualClock {
+    times: Mutex<(Instant, SystemTime)>,
+}
+
+impl ManualClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            times: Mutex::new((Instant::now(), SystemTime::now())),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut times = self.times.lock().expect("ManualClock mutex poisoned");
+        times.0 += by;
+        times.1 += by;
+    }
+
+    pub fn sleep(&self, by: Duration) {
+        let mut times = self.times.lock().expect("ManualClock mutex poisoned");
+        times.1 += by;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.times.lock().expect("ManualClock mutex poisoned").0
+    }
+
+    fn wall_now(&self) -> SystemTime {
+        self.times.lock().expect("ManualClock mutex poisoned").1
+    }
+}
+
+/// The user-selectable timeout policy. Wire shape (`GET`/`PUT
+/// /api/auth/timeout`): `{"policy":"minutes","minutes":15}`,
+/// `{"policy":"on_sleep"}`, `{"policy":"never"}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "snake_case", deny_unknown_fields)]
+pub enum VaultTimeoutPolicy {
+    Minutes { minutes: u32 },
+    OnSleep,
+    Never,
+}
+
+impl Default for VaultTimeoutPolicy {
+    fn default() -> Self {
+        Self::Minutes { minutes: 15 }
+    }
+}
+
+/// What `GET /api/auth/status` reports about the timeout while unlocked.
+/// `remaining_seconds` is `None` for policies with no inactivity deadline
+/// (`OnSleep`, `Never`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VaultTimeoutStatus {
+    #[serde(flatten)]
+    pub policy: VaultTimeoutPolicy,
+    pub remaining_seconds: Option<u64>,
+}
+
+struct TimeoutState {
+    policy: VaultTimeoutPolicy,
+    last_activity: (Instant, SystemTime),
+}
+
+/// Shared inactivity tracker held in `AppState::vault_timeout`. Cheap to
+/// clone (one `Arc`); the inner `std::sync::Mutex` is never held across an
+/// `.await`.
+#[derive(Clone)]
+pub struct VaultTimeout {
+    clock: Arc<dyn Clock>,
+    state: Arc<Mutex<TimeoutState>>,
+}
+
+impl VaultTimeout {
+    #[must_use]
+    pub fn new(clock: Arc<dyn Clock>, policy: VaultTimeoutPolicy) -> Self {
+        let last_activity = (clock.now(), clock.wall_now());
+        Self {
+            clock,
+            state: Arc::new(Mutex::new(TimeoutState {
+                policy,
+                last_activity,
+            })),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, TimeoutState> {
+        self.state.lock().expect("vault timeout mutex poisoned")
+    }
+
+    #[must_use]
+    pub fn policy(&self) -> VaultTimeoutPolicy {
+        self.lock_state().policy
+    }
+
+    /// Replaces the policy. Also counts as activity: changing the setting
+    /// is an explicit user action, and it would be surprising for a
+    /// shorter timeout to lock the vault in the same breath.
+    pub fn set_policy(&self, policy: VaultTimeoutPolicy) {
+        let now = (self.clock.now(), self.clock.wall_now());
+        let mut state = self.lock_state();
+        state.policy = policy;
+        state.last_activity = now;
+    }
+
+    pub fn record_activity(&self) {
+        let now = (self.clock.now(), self.clock.wall_now());
+        self.lock_state().last_activity = now;
+    }
+
+    /// `(monotonic, wall)` time elapsed since the last activity. A wall
+    /// clock stepped backwards yields zero rather than an error.
+    fn elapsed(&self, state: &TimeoutState) -> (Duration, Duration) {
+        let (last_mono, last_wall) = state.last_activity;
+        let mono = self.clock.now().saturating_duration_since(last_mono);
+        let wall = self
+            .clock
+            .wall_now()
+            .duration_since(last_wall)
+            .unwrap_or_default();
+        (mono, wall)
+    }
+
+    /// Whether the current policy says the vault should be locked now.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        let state = self.lock_state();
+        let (mono, wall) = self.elapsed(&state);
+        match state.policy {
+            VaultTimeoutPolicy::Minutes { minutes } => {
+                mono.max(wall) >= minutes_to_duration(minutes)
+            }
+            VaultTimeoutPolicy::OnSleep => wall.saturating_sub(mono) > SLEEP_DETECTION_THRESHOLD,
+            VaultTimeoutPolicy::Never => false,
+        }
+    }
+
+    #[must_use]
+    pub fn status(&self) -> VaultTimeoutStatus {
+        let state = self.lock_state();
+        let remaining_seconds = match state.policy {
+            VaultTimeoutPolicy::Minutes { minutes } => {
+                let (mono, wall) = self.elapsed(&state);
+                Some(
+                    minutes_to_duration(minutes)
+                        .saturating_sub(mono.max(wall))
+                        .as_secs(),
+                )
+            }
+            VaultTimeoutPolicy::OnSleep | VaultTimeoutPolicy::Never => None,
+        };
+        VaultTimeoutStatus {
+            policy: state.policy,
+            remaining_seconds,
+        }
+    }
+}
+
+fn minutes_to_duration(minutes: u32) -> Duration {
+    Duration::from_secs(u64::from(minutes) * 60)
+}
+
+/// Locks an `Unlocked` session whose timeout has expired: clears the
+/// server-side vault and moves to `Session::Locked`. Returns whether it
+/// locked. Any other session state is left alone -- there is nothing
+/// decrypted to protect outside `Unlocked`.
+///
+/// Lock order (session, then vault store) matches `crate::auth_routes::
+/// lock`, for the same reason: a concurrent `/api/vault/*` request must
+/// never observe a locked session with a stale credential set.
+pub async fn lock_if_expired(state: &AppState) -> bool {
+    let mut session = state.session.lock().await;
+    if !matches!(&*session, Session::Unlocked(_)) || !state.vault_timeout.is_expired() {
+        return false;
+    }
+    state.vault_credentials.clear().await;
+    state.device_selections.clear().await;
+    *session = Session::Locked;
+    eprintln!("web-companion: vault timeout expired, session locked");
+    true
+}
+
+/// `GET /api/auth/status` is a poll, not user activity -- see module docs
+/// "What counts as activity".
+fn is_activity(req: &Request) -> bool {
+    !(req.method() == Method::GET && req.uri().path().ends_with("/auth/status"))
+}
+
+/// axum middleware for `/api/*`: enforce the timeout, then record this
+/// request as activity. Applied inside the bearer-token layer (see
+/// `crate::build_app`), so unauthenticated requests never reset the timer.
+pub async fn enforce_vault_timeout(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    lock_if_expired(&state).await;
+    if is_activity(&req) {
+        state.vault_timeout.record_activity();
+    }
+    next.run(req).await
+}
+
+/// Background loop for `main.rs`: see module docs "Enforcement". Never
+/// returns.
+pub async fn run_idle_watchdog(state: AppState) {
+    let mut interval = tokio::time::interval(WATCHDOG_INTERVAL);
+    loop {
+        interval.tick().await;
+        lock_if_expired(&state).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(policy: VaultTimeoutPolicy) -> (Arc<ManualClock>, VaultTimeout) {
+        let clock = Arc::new(ManualClock::new());
+        let timeout = VaultTimeout::new(clock.clone(), policy);
+        (clock, timeout)
+    }
+
+    #[test]
+    fn default_policy_is_fifteen_minutes() {
+        assert_eq!(
+            VaultTimeoutPolicy::default(),
+            VaultTimeoutPolicy::Minutes { minutes: 15 }
+        );
+    }
+
+    #[test]
+    fn minutes_policy_expires_exactly_at_deadline() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::Minutes { minutes: 5 });
+
+        clock.advance(Duration::from_secs(5 * 60 - 1));
+        assert!(!timeout.is_expired());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(timeout.is_expired());
+    }
+
+    #[test]
+    fn activity_restarts_the_countdown() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::Minutes { minutes: 5 });
+
+        clock.advance(Duration::from_secs(4 * 60));
+        timeout.record_activity();
+        clock.advance(Duration::from_secs(4 * 60));
+
+        assert!(!timeout.is_expired());
+        assert_eq!(timeout.status().remaining_seconds, Some(60));
+    }
+
+    #[test]
+    fn time_asleep_counts_towards_minutes_policy() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::Minutes { minutes: 5 });
+
+        clock.sleep(Duration::from_secs(10 * 60));
+
+        assert!(timeout.is_expired());
+        assert_eq!(timeout.status().remaining_seconds, Some(0));
+    }
+
+    #[test]
+    fn on_sleep_policy_ignores_idle_time_but_fires_on_sleep() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::OnSleep);
+
+        clock.advance(Duration::from_secs(24 * 60 * 60));
+        assert!(!timeout.is_expired());
+
+        clock.sleep(SLEEP_DETECTION_THRESHOLD + Duration::from_secs(1));
+        assert!(timeout.is_expired());
+    }
+
+    #[test]
+    fn on_sleep_policy_tolerates_small_clock_slew() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::OnSleep);
+
+        clock.sleep(SLEEP_DETECTION_THRESHOLD);
+
+        assert!(!timeout.is_expired());
+    }
+
+    #[test]
+    fn never_policy_never_expires() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::Never);
+
+        clock.advance(Duration::from_secs(365 * 24 * 60 * 60));
+        clock.sleep(Duration::from_secs(60 * 60));
+
+        assert!(!timeout.is_expired());
+        assert_eq!(timeout.status().remaining_seconds, None);
+    }
+
+    #[test]
+    fn set_policy_counts_as_activity() {
+        let (clock, timeout) = tracker(VaultTimeoutPolicy::Never);
+
+        clock.advance(Duration::from_secs(60 * 60));
+        timeout.set_policy(VaultTimeoutPolicy::Minutes { minutes: 1 });
+
+        assert!(!timeout.is_expired());
+    }
+
+    #[test]
+    fn policy_round_trips_tagged_json() {
+        let json = r#"{"policy":"minutes","minutes":30}"#;
+        let parsed: VaultTimeoutPolicy = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed, VaultTimeoutPolicy::Minutes { minutes: 30 });
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+        let parsed: VaultTimeoutPolicy = serde_json::from_str(r#"{"policy":"on_sleep"}"#).unwrap();
+        assert_eq!(parsed, VaultTimeoutPolicy::OnSleep);
+    }
+
+    #[test]
+    fn status_flattens_policy_next_to_remaining_seconds() {
+        let (_clock, timeout) = tracker(VaultTimeoutPolicy::Minutes { minutes: 2 });
+
+        let json = serde_json::to_value(timeout.status()).unwrap();
+
+        assert_eq!(json["policy"], "minutes");
+        assert_eq!(json["minutes"], 2);
+        assert_eq!(json["remaining_seconds"], 120);
+    }
+}
@@ -50,6 +50,9 @@
     loginForm: document.getElementById("login-form"),
     loginEmail: document.getElementById("login-email"),
     loginPassword: document.getElementById("login-password"),
+    loginServer: document.getElementById("login-server"),
+    loginServerUrlField: document.getElementById("login-server-url-field"),
+    loginServerUrl: document.getElementById("login-server-url"),
     loginError: document.getElementById("login-error"),
 
     viewTwoFactor: document.getElementById("view-twofactor"),
@@ -178,6 +181,26 @@
     }
   }
 
+  // ---------------------------------------------------------------------
+  // Server (environment) picker. Maps onto the `server` field of
+  // POST /api/auth/login -- see src/environment.rs for the wire shape and
+  // the URL validation the server applies.
+  // ---------------------------------------------------------------------
+  function selectedServer() {
+    const type = el.loginServer.value;
+    if (type === "self_hosted") {
+      return { type, base_url: el.loginServerUrl.value.trim() };
+    }
+    return { type };
+  }
+
+  el.loginServer.addEventListener("change", () => {
+    el.loginServerUrlField.classList.toggle(
+      "hidden",
+      el.loginServer.value !== "self_hosted"
+    );
+  });
+
   // ---------------------------------------------------------------------
   // Login
   // ---------------------------------------------------------------------
@@ -192,12 +215,17 @@
       showError(el.loginError, "Enter your email and master password.");
       return;
     }
+    const server = selectedServer();
+    if (server.type === "self_hosted" && !server.base_url) {
+      showError(el.loginError, "Enter your self-hosted server URL.");
+      return;
+    }
 
     let res;
     try {
       res = await api("/api/auth/login", {
         method: "POST",
-        body: JSON.stringify({ email, master_password: masterPassword }),
+        body: JSON.stringify({ email, master_password: masterPassword, server }),
       });
     } catch (_err) {
       showError(
@@ -230,7 +258,12 @@
     if (res.status === 401) {
       showError(el.loginError, "Wrong email or master password.");
     } else if (res.status === 400) {
-      showError(el.loginError, "Enter your email and master password.");
+      // Either a missing field or a rejected server URL -- the server's
+      // message says which (see src/environment.rs).
+      showError(
+        el.loginError,
+        await readError(res, "Enter your email and master password.")
+      );
     } else if (res.status === 409) {
       // Another tab/request already has a session going -- resync to
       // whatever the server actually thinks is true rather than guessing.
@@ -22,9 +22,8 @@
 //! - The `aarch64-apple-darwin` (or your host triple) target buildable for
 //!   `-p emulator --bin desktop` from the repo root (this test invokes that
 //!   build itself, see `build_emulator_binary`).
-//! - Port 8080 free (the emulator's fixed HTTP port,
-//!   `emulator/src/main.rs`'s `SyncServer::new("127.0.0.1:8080", ...)` --
-//!   not configurable). If something is already listening there (e.g. a
+//! - Port 8080 free (the emulator's default HTTP port, which this test
+//!   starts it on -- see `--port` in `emulator/src/main.rs`). If something is already listening there (e.g. a
 //!   manually-started emulator), this test refuses to touch it and fails
 //!   with a clear message -- per repo `CLAUDE.md`, this test suite must
 //!   never blanket-`pkill` a process it didn't spawn itself.
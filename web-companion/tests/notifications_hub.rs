@@ -0,0 +1,441 @@
+//! Notifications hub listener against a local WebSocket stand-in.
+//!
+//! The stand-in speaks just enough of SignalR's JSON hub protocol (the
+//! `{"protocol":"json","version":1}` handshake, `0x1e`-separated records,
+//! pings, `ReceiveMessage` invocations, close) to exercise
+//! `web_companion::notifications::HubConnection` and `follow_hub` with
+//! canned messages in the shape the Bitwarden cloud sends. Each stand-in
+//! serves exactly one connection on `127.0.0.1:0`; no outbound network.
+//!
+//! Only notifications that need no vault fetch (deletes, `LogOut`) are
+//! driven through `follow_hub`: an upsert runs a real SDK sync, which
+//! needs a logged-in session against a real server -- the merge logic it
+//! feeds is covered by `notifications`' unit tests instead. What happens
+//! around that fetch is driven through `apply_delta_with` with a stand-in
+//! fetch the test holds pending.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::State;
+use futures_util::{SinkExt, StreamExt};
+use push_protocol::{Credential, SyncRequest, SyncResponse};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+use web_companion::auth_routes;
+use web_companion::environment::ServerEnvironment;
+use web_companion::notifications::{
+    apply_delta_with, follow_hub, hub_url, HubConnection, HubEnd, HubError, HubEvent, VaultDelta,
+};
+use web_companion::state::{
+    build_client, AppState, DeviceSelections, Session, TransportRegistry, UnlockedSession,
+    VaultCredentialStore,
+};
+use web_companion::transport::{
+    DeviceDescriptor, DeviceKind, DeviceTransport, TransportError, TransportProvider,
+};
+use web_companion::vault_timeout::{SystemClock, VaultTimeout, VaultTimeoutPolicy};
+
+const ACCESS_TOKEN: &str = "hub-test-access-token";
+const RS: &str = "\u{1e}";
+
+/// What the stand-in does after a successful handshake.
+enum Script {
+    /// Reply `{}`, send each frame in order, then close.
+    Send(Vec<String>),
+    /// Reply with a handshake error.
+    RejectHandshake,
+    /// Reply `{}`, then report the first record the client sends.
+    AwaitClientRecord(oneshot::Sender<String>),
+}
+
+fn invocation(push_type: u8, id: Option<Uuid>) -> String {
+    let payload = match id {
+        Some(id) => format!("{{\"Id\":\"{id}\",\"UserId\":null,\"OrganizationId\":null}}"),
+        None => "{\"UserId\":null,\"Date\":\"2026-01-01T00:00:00Z\"}".to_string(),
+    };
+    format!(
+        "{{\"type\":1,\"target\":\"ReceiveMessage\",\"arguments\":[{{\"ContextId\":null,\"Type\":{push_type},\"Payload\":{payload}}}]}}{RS}"
+    )
+}
+
+/// `accept_hdr_async` callback: the client must ask for `/hub` with the
+/// session's token in the query (see `notifications` module docs).
+#[allow(clippy::result_large_err)] // signature fixed by tungstenite's `Callback`
+fn check_request(request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+    assert_eq!(request.uri().path(), "/hub");
+    assert_eq!(
+        request.uri().query(),
+        Some(format!("access_token={ACCESS_TOKEN}").as_str())
+    );
+    Ok(response)
+}
+
+/// Starts a one-connection stand-in hub and returns its `http://` base
+/// URL (a `ServerEnvironment::notifications_url`).
+async fn start_hub(script: Script) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_hdr_async(stream, check_request)
+            .await
+            .unwrap();
+
+        let handshake = socket.next().await.unwrap().unwrap();
+        assert_eq!(
+            handshake.to_text().unwrap(),
+            format!("{{\"protocol\":\"json\",\"version\":1}}{RS}")
+        );
+
+        match script {
+            Script::RejectHandshake => {
+                let reply =
+                    format!("{{\"error\":\"Requested protocol 'json' is not available.\"}}{RS}");
+                socket.send(Message::text(reply)).await.unwrap();
+            }
+            Script::Send(frames) => {
+                socket
+                    .send(Message::text(format!("{{}}{RS}")))
+                    .await
+                    .unwrap();
+                for frame in frames {
+                    socket.send(Message::text(frame)).await.unwrap();
+                }
+                socket.close(None).await.unwrap();
+            }
+            Script::AwaitClientRecord(report) => {
+                socket
+                    .send(Message::text(format!("{{}}{RS}")))
+                    .await
+                    .unwrap();
+                let record = socket.next().await.unwrap().unwrap();
+                let _ = report.send(record.to_text().unwrap().to_string());
+            }
+        }
+    });
+    format!("http://{addr}")
+}
+
+async fn connect(base_url: &str) -> Result<HubConnection, HubError> {
+    HubConnection::connect(&hub_url(base_url, ACCESS_TOKEN).unwrap()).await
+}
+
+#[tokio::test]
+async fn handshake_then_canned_notifications_are_decoded_in_order() {
+    let upserted = Uuid::new_v4();
+    let deleted = Uuid::new_v4();
+    let base_url = start_hub(Script::Send(vec![
+        format!("{{\"type\":6}}{RS}"),
+        // Two records in one frame, with a folder notification between.
+        format!(
+            "{}{}{}",
+            invocation(1, Some(upserted)),
+            invocation(7, Some(Uuid::new_v4())),
+            invocation(2, Some(deleted))
+        ),
+        invocation(5, None),
+    ]))
+    .await;
+
+    let mut connection = connect(&base_url).await.unwrap();
+
+    assert_eq!(
+        connection.next_event().await.unwrap(),
+        Some(HubEvent::CipherUpserted(upserted))
+    );
+    assert_eq!(
+        connection.next_event().await.unwrap(),
+        Some(HubEvent::CipherDeleted(deleted))
+    );
+    assert_eq!(
+        connection.next_event().await.unwrap(),
+        Some(HubEvent::VaultChanged)
+    );
+    assert_eq!(connection.next_event().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn handshake_error_is_reported() {
+    let base_url = start_hub(Script::RejectHandshake).await;
+
+    let err = connect(&base_url).await.err().unwrap();
+
+    assert!(matches!(err, HubError::Handshake(message) if message.contains("not available")));
+}
+
+#[tokio::test]
+async fn close_record_with_error_ends_the_connection_with_it() {
+    let base_url = start_hub(Script::Send(vec![format!(
+        "{{\"type\":7,\"error\":\"Unauthorized\"}}{RS}"
+    )]))
+    .await;
+
+    let mut connection = connect(&base_url).await.unwrap();
+
+    assert!(matches!(
+        connection.next_event().await,
+        Err(HubError::Closed(message)) if message == "Unauthorized"
+    ));
+}
+
+#[tokio::test]
+async fn idle_connection_sends_keepalive_pings() {
+    let (report, received) = oneshot::channel();
+    let base_url = start_hub(Script::AwaitClientRecord(report)).await;
+    let url = hub_url(&base_url, ACCESS_TOKEN).unwrap();
+    let mut connection = HubConnection::connect_with_keepalive(&url, Duration::from_millis(50))
+        .await
+        .unwrap();
+
+    // Nothing arrives from the stand-in, so `next_event` only ever pings;
+    // it is dropped once the stand-in has reported what it saw.
+    let record = tokio::select! {
+        record = received => record.unwrap(),
+        _ = connection.next_event() => panic!("stand-in sent no events"),
+    };
+
+    assert_eq!(record, format!("{{\"type\":6}}{RS}"));
+}
+
+// ---------------------------------------------------------------------
+// `follow_hub` against an unlocked `AppState`
+
+/// Records every push instead of sending it anywhere. Knows two devices:
+/// see `unlocked_state`.
+/// One push the provider saw: `(target_id, pushed credential ids)`.
+type Pushes = Arc<Mutex<Vec<(String, Vec<Uuid>)>>>;
+
+#[derive(Clone, Default)]
+struct RecordingProvider {
+    pushes: Pushes,
+}
+
+struct RecordingTransport {
+    id: String,
+    pushes: Pushes,
+}
+
+fn descriptor(id: &str) -> DeviceDescriptor {
+    DeviceDescriptor {
+        id: id.to_string(),
+        name: id.to_string(),
+        kind: DeviceKind::Emulator,
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceTransport for RecordingTransport {
+    async fn push(&self, request: &SyncRequest) -> Result<SyncResponse, TransportError> {
+        let ids = request.credentials.iter().map(|c| c.id).collect();
+        self.pushes.lock().unwrap().push((self.id.clone(), ids));
+        Ok(SyncResponse {
+            status: "ok".to_string(),
+            synced: request.credentials.len(),
+            total_bytes: 0,
+        })
+    }
+
+    fn descriptor(&self) -> DeviceDescriptor {
+        descriptor(&self.id)
+    }
+}
+
+#[async_trait::async_trait]
+impl TransportProvider for RecordingProvider {
+    fn list_targets(&self) -> Vec<DeviceDescriptor> {
+        vec![descriptor("everything"), descriptor("subset")]
+    }
+
+    async fn connect(&self, id: &str) -> Result<Box<dyn DeviceTransport>, TransportError> {
+        if id != "everything" && id != "subset" {
+            return Err(TransportError::UnknownDevice(id.to_string()));
+        }
+        Ok(Box::new(RecordingTransport {
+            id: id.to_string(),
+            pushes: self.pushes.clone(),
+        }))
+    }
+}
+
+fn credential(name: &str) -> Credential {
+    Credential {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        username: "user".to_string(),
+        password: "hunter2".to_string(),
+        uri: None,
+        notes: None,
+    }
+}
+
+/// Unlocked (no real login -- nothing here makes an SDK call) with
+/// `credentials` in the vault, "everything" last pushed the whole vault
+/// and "subset" last pushed only `credentials[0]`.
+async fn unlocked_state(credentials: &[Credential]) -> (AppState, RecordingProvider) {
+    let provider = RecordingProvider::default();
+    let state = unlocked_state_with(Arc::new(provider.clone()), credentials).await;
+    (state, provider)
+}
+
+/// `unlocked_state`, pushing through `provider` instead.
+async fn unlocked_state_with(
+    provider: Arc<dyn TransportProvider>,
+    credentials: &[Credential],
+) -> AppState {
+    let state = AppState {
+        session: Arc::new(tokio::sync::Mutex::new(Session::LoggedOut)),
+        transports: TransportRegistry::new(vec![provider]),
+        api_token: "unused".to_string(),
+        vault_credentials: VaultCredentialStore::default(),
+        vault_timeout: VaultTimeout::new(Arc::new(SystemClock), VaultTimeoutPolicy::default()),
+        device_selections: DeviceSelections::default(),
+    };
+    let (client, tokens) = build_client(&ServerEnvironment::us());
+    *state.session.lock().await = Session::Unlocked(UnlockedSession {
+        client,
+        environment: ServerEnvironment::us(),
+        tokens,
+        hub: None,
+    });
+    state.vault_credentials.replace(credentials.to_vec()).await;
+    state.device_selections.record("everything", None).await;
+    state
+        .device_selections
+        .record("subset", Some(vec![credentials[0].id]))
+        .await;
+    state
+}
+
+#[tokio::test]
+async fn delete_notification_updates_the_vault_and_repushes_affected_devices_only() {
+    let kept = credential("kept");
+    let deleted = credential("deleted");
+    let (state, provider) = unlocked_state(&[kept.clone(), deleted.clone()]).await;
+    let base_url = start_hub(Script::Send(vec![invocation(9, Some(deleted.id))])).await;
+    let mut connection = connect(&base_url).await.unwrap();
+
+    let end = follow_hub(&state, &mut connection).await.unwrap();
+
+    assert_eq!(end, HubEnd::Closed);
+    assert_eq!(state.vault_credentials.get_all().await, vec![kept.clone()]);
+    // "subset" only holds `kept`, so it isn't woken.
+    assert_eq!(
+        *provider.pushes.lock().unwrap(),
+        vec![("everything".to_string(), vec![kept.id])]
+    );
+}
+
+#[tokio::test]
+async fn notification_for_an_unknown_item_pushes_nothing() {
+    let (state, provider) = unlocked_state(&[credential("a")]).await;
+    let base_url = start_hub(Script::Send(vec![invocation(2, Some(Uuid::new_v4()))])).await;
+    let mut connection = connect(&base_url).await.unwrap();
+
+    follow_hub(&state, &mut connection).await.unwrap();
+
+    assert!(provider.pushes.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn logout_notification_ends_the_session() {
+    let (state, provider) = unlocked_state(&[credential("a")]).await;
+    let base_url = start_hub(Script::Send(vec![invocation(11, None)])).await;
+    let mut connection = connect(&base_url).await.unwrap();
+
+    let end = follow_hub(&state, &mut connection).await.unwrap();
+
+    assert_eq!(end, HubEnd::LoggedOut);
+    assert!(matches!(*state.session.lock().await, Session::LoggedOut));
+    assert!(state.vault_credentials.get_all().await.is_empty());
+    assert!(state.device_selections.get_all().await.is_empty());
+    assert!(provider.pushes.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn a_lock_while_the_vault_fetch_is_pending_is_not_undone() {
+    let existing = credential("existing");
+    let (state, provider) = unlocked_state(&[existing.clone()]).await;
+    let fresh = vec![existing, credential("added")];
+    let (fetch_started, started) = oneshot::channel();
+    let (release_fetch, released) = oneshot::channel::<()>();
+    let mut delta = VaultDelta::default();
+    delta.add(HubEvent::VaultChanged);
+
+    let applying = tokio::spawn({
+        let state = state.clone();
+        async move {
+            apply_delta_with(&state, &delta, |_client| async move {
+                fetch_started.send(()).unwrap();
+                released.await.unwrap();
+                Ok::<_, std::convert::Infallible>(fresh)
+            })
+            .await
+        }
+    });
+    started.await.unwrap();
+    auth_routes::lock(State(state.clone())).await;
+    release_fetch.send(()).unwrap();
+
+    assert!(!applying.await.unwrap());
+    assert!(matches!(*state.session.lock().await, Session::LoggedOut));
+    assert!(state.vault_credentials.get_all().await.is_empty());
+    assert!(provider.pushes.lock().unwrap().is_empty());
+}
+
+/// Knows the same two devices as `RecordingProvider`, but connecting to
+/// either never completes: an unreachable device sat in its connect
+/// timeout.
+struct UnreachableProvider;
+
+#[async_trait::async_trait]
+impl TransportProvider for UnreachableProvider {
+    fn list_targets(&self) -> Vec<DeviceDescriptor> {
+        vec![descriptor("everything"), descriptor("subset")]
+    }
+
+    async fn connect(&self, _id: &str) -> Result<Box<dyn DeviceTransport>, TransportError> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn a_stalled_device_push_does_not_hold_the_session_lock() {
+    let kept = credential("kept");
+    let deleted = credential("deleted");
+    let state = unlocked_state_with(
+        Arc::new(UnreachableProvider),
+        &[kept.clone(), deleted.clone()],
+    )
+    .await;
+    let mut delta = VaultDelta::default();
+    delta.add(HubEvent::CipherDeleted(deleted.id));
+
+    let applying = tokio::spawn({
+        let state = state.clone();
+        async move {
+            apply_delta_with(&state, &delta, |_client| async {
+                Ok::<Vec<Credential>, std::convert::Infallible>(Vec::new())
+            })
+            .await
+        }
+    });
+
+    // The store is updated before the pushes start...
+    while state.vault_credentials.get_all().await != vec![kept.clone()] {
+        tokio::task::yield_now().await;
+    }
+    // ...and while the push is stuck, the session is free to be locked.
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        auth_routes::lock(State(state.clone())),
+    )
+    .await
+    .expect("the session lock is not held across the device push");
+    assert!(matches!(*state.session.lock().await, Session::LoggedOut));
+    applying.abort();
+}
@@ -0,0 +1,229 @@
+//! Self-hosted login against a local mock identity server.
+//!
+//! Proves that a `server` selection on `POST /api/auth/login` actually
+//! reaches the chosen endpoints (not the hardcoded US cloud this crate
+//! used before `web_companion::environment` existed), and that the 2FA
+//! retry goes back to the SAME server the first attempt used.
+//!
+//! The mock replays canned responses in the shapes the real identity
+//! server (and Vaultwarden) send: a `prelogin` KDF config, and `400`
+//! `invalid_grant` token responses -- with and without a
+//! `TwoFactorProviders2` challenge. A *successful* token response is
+//! deliberately not replayed: the SDK then decrypts the returned user key
+//! with the master key, which would need real key material minted for a
+//! fixed password rather than canned JSON, and the unlocked path is already
+//! covered against the real API by the eml.11 runbook.
+//!
+//! Binds `127.0.0.1:0` (an OS-assigned port) so it never collides with
+//! the emulator's `:8080` or the server's own `:3000`. No outbound network.
+
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{Request, StatusCode, Uri},
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+
+use web_companion::build_app;
+use web_companion::state::{
+    AppState, DeviceSelections, Session, TransportRegistry, VaultCredentialStore,
+};
+use web_companion::transport::DEFAULT_EMULATOR_URL;
+use web_companion::vault_timeout::{SystemClock, VaultTimeout, VaultTimeoutPolicy};
+
+const TEST_TOKEN: &str = "self-hosted-login-test-token";
+
+/// One request the mock saw: `(path, body)`.
+type Seen = Arc<Mutex<Vec<(String, String)>>>;
+
+#[derive(Clone)]
+struct MockIdentity {
+    seen: Seen,
+    /// Body of every `/connect/token` reply (always sent as `400`, see
+    /// module docs).
+    token_reply: Value,
+}
+
+fn prelogin_reply() -> Value {
+    json!({
+        "kdf": 0,
+        "kdfIterations": 600_000,
+        "kdfMemory": null,
+        "kdfParallelism": null
+    })
+}
+
+fn invalid_credentials_reply() -> Value {
+    json!({
+        "error": "invalid_grant",
+        "error_description": "invalid_username_or_password",
+        "ErrorModel": {
+            "Message": "Username or password is incorrect. Try again.",
+            "Object": "error"
+        }
+    })
+}
+
+fn two_factor_required_reply() -> Value {
+    json!({
+        "error": "invalid_grant",
+        "error_description": "Two factor required.",
+        "TwoFactorProviders": [1],
+        "TwoFactorProviders2": { "1": { "Email": "t***@example.com" } },
+        "MasterPasswordPolicy": null
+    })
+}
+
+fn record(mock: &MockIdentity, uri: &Uri, body: Bytes) {
+    mock.seen.lock().unwrap().push((
+        uri.path().to_string(),
+        String::from_utf8_lossy(&body).into_owned(),
+    ));
+}
+
+async fn prelogin(State(mock): State<MockIdentity>, uri: Uri, body: Bytes) -> Json<Value> {
+    record(&mock, &uri, body);
+    Json(prelogin_reply())
+}
+
+async fn token(State(mock): State<MockIdentity>, uri: Uri, body: Bytes) -> impl IntoResponse {
+    record(&mock, &uri, body);
+    (StatusCode::BAD_REQUEST, Json(mock.token_reply.clone()))
+}
+
+/// Starts the mock on an ephemeral loopback port and returns its base URL
+/// (suitable as a `self_hosted` `base_url`). Prelogin is served under both
+/// `/identity` and `/api` since SDK revisions differ on which host owns it.
+async fn start_mock(token_reply: Value) -> (String, Seen) {
+    let seen: Seen = Arc::default();
+    let app = Router::new()
+        .route("/identity/accounts/prelogin", post(prelogin))
+        .route("/api/accounts/prelogin", post(prelogin))
+        .route("/identity/connect/token", post(token))
+        .with_state(MockIdentity {
+            seen: seen.clone(),
+            token_reply,
+        });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}"), seen)
+}
+
+fn test_state() -> AppState {
+    AppState {
+        session: Arc::new(tokio::sync::Mutex::new(Session::LoggedOut)),
+        transports: TransportRegistry::with_emulator(DEFAULT_EMULATOR_URL.to_string()),
+        api_token: TEST_TOKEN.to_string(),
+        vault_credentials: VaultCredentialStore::default(),
+        vault_timeout: VaultTimeout::new(Arc::new(SystemClock), VaultTimeoutPolicy::default()),
+        device_selections: DeviceSelections::default(),
+    }
+}
+
+async fn post_json(state: &AppState, path: &str, body: Value) -> (StatusCode, Value) {
+    let response = build_app(state.clone())
+        .oneshot(
+            Request::post(path)
+                .header("Authorization", format!("Bearer {TEST_TOKEN}"))
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
+
+fn login_body(base_url: &str) -> Value {
+    json!({
+        "email": "test@example.com",
+        "master_password": "correct horse battery staple",
+        "server": { "type": "self_hosted", "base_url": base_url }
+    })
+}
+
+fn token_requests(seen: &Seen) -> Vec<String> {
+    seen.lock()
+        .unwrap()
+        .iter()
+        .filter(|(path, _)| path == "/identity/connect/token")
+        .map(|(_, body)| body.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn login_reaches_self_hosted_identity_server() {
+    let (base_url, seen) = start_mock(invalid_credentials_reply()).await;
+    let state = test_state();
+
+    let (status, _) = post_json(&state, "/api/auth/login", login_body(&base_url)).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let paths: Vec<String> = seen.lock().unwrap().iter().map(|(p, _)| p.clone()).collect();
+    assert!(
+        paths.iter().any(|p| p.ends_with("/accounts/prelogin")),
+        "expected a prelogin call on the mock, saw {paths:?}"
+    );
+    let tokens = token_requests(&seen);
+    assert_eq!(tokens.len(), 1);
+    assert!(tokens[0].contains("grant_type=password"));
+    assert!(matches!(*state.session.lock().await, Session::LoggedOut));
+}
+
+#[tokio::test]
+async fn two_factor_retry_goes_to_the_same_server() {
+    let (base_url, seen) = start_mock(two_factor_required_reply()).await;
+    let state = test_state();
+
+    let (status, body) = post_json(&state, "/api/auth/login", login_body(&base_url)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "two_factor_required");
+
+    // No `server` field here: the retry must reuse the pending login's.
+    let (status, _) = post_json(
+        &state,
+        "/api/auth/2fa",
+        json!({ "provider": 1, "token": "123456" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let tokens = token_requests(&seen);
+    assert_eq!(tokens.len(), 2, "2FA retry should hit the mock, not the cloud");
+    assert!(tokens[1].contains("twoFactorToken=123456"));
+    assert!(matches!(
+        *state.session.lock().await,
+        Session::PendingTwoFactor(_)
+    ));
+}
+
+#[tokio::test]
+async fn insecure_remote_server_is_rejected_before_any_request() {
+    let (_base_url, seen) = start_mock(invalid_credentials_reply()).await;
+    let state = test_state();
+
+    let (status, body) = post_json(
+        &state,
+        "/api/auth/login",
+        login_body("http://vault.example.com"),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("https"));
+    assert!(seen.lock().unwrap().is_empty());
+}